@@ -26,6 +26,13 @@ if [[ -z $2 ]]; then
   usage "No runtime provided"
 fi
 
+# Targets with a generated seed corpus write it to their input directory first
+case $fuzz_target in
+  token-2022-rebase-instructions)
+    cargo +"$rust_stable" run --bin token-2022-rebase-instruction-seeds
+    ;;
+esac
+
 HFUZZ_RUN_ARGS="--run_time $run_time --exit_upon_crash" cargo +"$rust_stable" hfuzz run $fuzz_target
 
 # Until https://github.com/rust-fuzz/honggfuzz-rs/issues/16 is resolved,
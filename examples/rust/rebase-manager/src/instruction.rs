@@ -0,0 +1,117 @@
+//! Program instructions
+
+use {
+    crate::get_authority_address,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+};
+
+/// Instructions supported by the program
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RebaseManagerInstruction {
+    /// Grow the supply of the mint by the yield that the attester reports,
+    /// through `RebaseSupply` signed by the program-derived supply authority.
+    ///
+    /// Mints with a reserve account, or requiring rebase memos, are not
+    /// supported.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The rebasing mint
+    ///   1. `[]` The supply authority, as given by `get_authority_address`
+    ///   2. `[signer]` The attester
+    ///   3. `[]` The token program
+    Rebase {
+        /// Yield since the last rebase, in basis points of the supply
+        yield_bps: u16,
+    },
+    /// Hand the supply authority over to another key, which may then rebase
+    /// the mint directly. If the mint requires a two-step authority transfer,
+    /// the key is only proposed, and takes over once it accepts.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The rebasing mint
+    ///   1. `[]` The supply authority, as given by `get_authority_address`
+    ///   2. `[signer]` The attester
+    ///   3. `[]` The new supply authority
+    ///   4. `[]` The token program
+    RotateAuthority,
+}
+
+impl RebaseManagerInstruction {
+    /// Unpacks a byte buffer into a
+    /// [RebaseManagerInstruction](enum.RebaseManagerInstruction.html).
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        let (&tag, rest) = input
+            .split_first()
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        Ok(match tag {
+            0 => {
+                let yield_bps = rest
+                    .get(..2)
+                    .and_then(|slice| slice.try_into().ok())
+                    .map(u16::from_le_bytes)
+                    .ok_or(ProgramError::InvalidInstructionData)?;
+                Self::Rebase { yield_bps }
+            }
+            1 => Self::RotateAuthority,
+            _ => return Err(ProgramError::InvalidInstructionData),
+        })
+    }
+
+    /// Packs a [RebaseManagerInstruction](enum.RebaseManagerInstruction.html)
+    /// into a byte buffer.
+    pub fn pack(&self) -> Vec<u8> {
+        match self {
+            Self::Rebase { yield_bps } => {
+                let mut buf = vec![0];
+                buf.extend_from_slice(&yield_bps.to_le_bytes());
+                buf
+            }
+            Self::RotateAuthority => vec![1],
+        }
+    }
+}
+
+/// Creates a `Rebase` instruction
+pub fn rebase(
+    program_id: &Pubkey,
+    mint: &Pubkey,
+    attester: &Pubkey,
+    yield_bps: u16,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*mint, false),
+            AccountMeta::new_readonly(get_authority_address(mint, attester, program_id), false),
+            AccountMeta::new_readonly(*attester, true),
+            AccountMeta::new_readonly(spl_token_2022::id(), false),
+        ],
+        data: RebaseManagerInstruction::Rebase { yield_bps }.pack(),
+    }
+}
+
+/// Creates a `RotateAuthority` instruction
+pub fn rotate_authority(
+    program_id: &Pubkey,
+    mint: &Pubkey,
+    attester: &Pubkey,
+    new_authority: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*mint, false),
+            AccountMeta::new_readonly(get_authority_address(mint, attester, program_id), false),
+            AccountMeta::new_readonly(*attester, true),
+            AccountMeta::new_readonly(*new_authority, false),
+            AccountMeta::new_readonly(spl_token_2022::id(), false),
+        ],
+        data: RebaseManagerInstruction::RotateAuthority.pack(),
+    }
+}
@@ -0,0 +1,32 @@
+//! A program demonstrating how a program-derived address can act as the
+//! supply authority of a rebasing mint
+#![deny(missing_docs)]
+#![forbid(unsafe_code)]
+
+mod entrypoint;
+pub mod instruction;
+pub mod processor;
+
+use solana_program::pubkey::Pubkey;
+
+/// Seed of the program-derived address acting as the supply authority,
+/// followed by the mint and the attester
+pub const AUTHORITY_SEED: &[u8] = b"authority";
+
+/// Get the address of the supply authority that `attester` drives for `mint`
+pub fn get_authority_address(mint: &Pubkey, attester: &Pubkey, program_id: &Pubkey) -> Pubkey {
+    get_authority_address_and_bump_seed(mint, attester, program_id).0
+}
+
+/// Get the address of the supply authority that `attester` drives for `mint`,
+/// and its bump seed
+pub fn get_authority_address_and_bump_seed(
+    mint: &Pubkey,
+    attester: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[AUTHORITY_SEED, mint.as_ref(), attester.as_ref()],
+        program_id,
+    )
+}
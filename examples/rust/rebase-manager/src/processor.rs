@@ -0,0 +1,174 @@
+//! Program instruction processor
+
+use {
+    crate::{
+        get_authority_address_and_bump_seed, instruction::RebaseManagerInstruction, AUTHORITY_SEED,
+    },
+    solana_program::{
+        account_info::{next_account_info, AccountInfo},
+        entrypoint::ProgramResult,
+        msg,
+        program::invoke_signed,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    spl_token_2022::{
+        extension::{
+            rebase_mint::{instruction, RebaseMintConfig},
+            BaseStateWithExtensions, StateWithExtensions,
+        },
+        state::Mint,
+    },
+};
+
+/// Most that a single rebase may grow the supply by, in basis points, so that
+/// a faulty attestation can't inflate balances at once
+pub const MAX_YIELD_BPS: u16 = 100;
+
+const BPS_DENOMINATOR: u128 = 10_000;
+
+/// Check that the attester signed, and that the supply authority is the one
+/// it drives for the mint, returning the authority's bump seed
+fn check_authority(
+    program_id: &Pubkey,
+    mint_info: &AccountInfo,
+    authority_info: &AccountInfo,
+    attester_info: &AccountInfo,
+) -> Result<u8, ProgramError> {
+    if !attester_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let (expected_authority, bump_seed) =
+        get_authority_address_and_bump_seed(mint_info.key, attester_info.key, program_id);
+    if expected_authority != *authority_info.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    Ok(bump_seed)
+}
+
+fn get_rebase_mint_config(mint_info: &AccountInfo) -> Result<RebaseMintConfig, ProgramError> {
+    if mint_info.owner != &spl_token_2022::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<Mint>::unpack(&mint_data)?;
+    Ok(*mint.get_extension::<RebaseMintConfig>()?)
+}
+
+fn process_rebase(program_id: &Pubkey, accounts: &[AccountInfo], yield_bps: u16) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let mint_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+    let attester_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+
+    let bump_seed = check_authority(program_id, mint_info, authority_info, attester_info)?;
+    if yield_bps > MAX_YIELD_BPS {
+        msg!(
+            "Yield of {} bps exceeds the max of {}",
+            yield_bps,
+            MAX_YIELD_BPS
+        );
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    // The config is copied out, so that the mint isn't borrowed anymore
+    // during the CPI
+    let config = get_rebase_mint_config(mint_info)?;
+    let total_supply = u64::from(config.total_supply);
+    let new_supply = u64::try_from(
+        u128::from(total_supply) * (BPS_DENOMINATOR + u128::from(yield_bps)) / BPS_DENOMINATOR,
+    )
+    .map_err(|_| ProgramError::ArithmeticOverflow)?;
+
+    // The supply authority is a program-derived address, so it isn't a signer
+    // of the transaction: the program signs for it with its seeds
+    msg!("Rebasing supply from {} to {}", total_supply, new_supply);
+    invoke_signed(
+        &instruction::update_supply(
+            token_program_info.key,
+            mint_info.key,
+            None,
+            authority_info.key,
+            &[], // no multisig allowed
+            new_supply,
+        )?,
+        &[
+            mint_info.clone(),
+            authority_info.clone(),
+            token_program_info.clone(), // not required, but better for clarity
+        ],
+        &[&[
+            AUTHORITY_SEED,
+            mint_info.key.as_ref(),
+            attester_info.key.as_ref(),
+            &[bump_seed],
+        ]],
+    )
+}
+
+fn process_rotate_authority(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let mint_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+    let attester_info = next_account_info(account_info_iter)?;
+    let new_authority_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+
+    let bump_seed = check_authority(program_id, mint_info, authority_info, attester_info)?;
+    let config = get_rebase_mint_config(mint_info)?;
+
+    // A mint with two-step transfers only lets the new authority take over
+    // once it accepts, which proves that it can sign
+    let rotate_instruction = if bool::from(config.two_step_authority_transfer) {
+        msg!("Proposing {} as supply authority", new_authority_info.key);
+        instruction::propose_supply_authority(
+            token_program_info.key,
+            mint_info.key,
+            authority_info.key,
+            &[],
+            new_authority_info.key,
+        )?
+    } else {
+        msg!("Setting {} as supply authority", new_authority_info.key);
+        instruction::set_supply_authority(
+            token_program_info.key,
+            mint_info.key,
+            authority_info.key,
+            &[],
+            Some(*new_authority_info.key),
+        )?
+    };
+    invoke_signed(
+        &rotate_instruction,
+        &[
+            mint_info.clone(),
+            authority_info.clone(),
+            token_program_info.clone(),
+        ],
+        &[&[
+            AUTHORITY_SEED,
+            mint_info.key.as_ref(),
+            attester_info.key.as_ref(),
+            &[bump_seed],
+        ]],
+    )
+}
+
+/// Instruction processor
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    match RebaseManagerInstruction::unpack(instruction_data)? {
+        RebaseManagerInstruction::Rebase { yield_bps } => {
+            msg!("Instruction: Rebase");
+            process_rebase(program_id, accounts, yield_bps)
+        }
+        RebaseManagerInstruction::RotateAuthority => {
+            msg!("Instruction: RotateAuthority");
+            process_rotate_authority(program_id, accounts)
+        }
+    }
+}
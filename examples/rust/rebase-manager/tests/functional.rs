@@ -0,0 +1,346 @@
+use {
+    solana_program::{instruction::InstructionError, pubkey::Pubkey, system_instruction},
+    solana_program_test::{processor, tokio, BanksClient, ProgramTest},
+    solana_sdk::{
+        hash::Hash,
+        signature::Signer,
+        signer::keypair::Keypair,
+        transaction::{Transaction, TransactionError},
+    },
+    spl_example_rebase_manager::{
+        get_authority_address,
+        instruction::{rebase, rotate_authority},
+        processor::{process_instruction, MAX_YIELD_BPS},
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{self, RebaseMintConfig},
+            BaseStateWithExtensions, ExtensionType, StateWithExtensionsOwned,
+        },
+        state::Mint,
+    },
+    std::str::FromStr,
+};
+
+const INITIAL_SUPPLY: u64 = 1_000_000;
+
+fn program_test(program_id: &Pubkey) -> ProgramTest {
+    let mut program_test = ProgramTest::new(
+        "spl_example_rebase_manager",
+        *program_id,
+        processor!(process_instruction),
+    );
+    program_test.prefer_bpf(false);
+    program_test.add_program(
+        "spl_token_2022",
+        spl_token_2022::id(),
+        processor!(spl_token_2022::processor::Processor::process),
+    );
+    program_test
+}
+
+/// Create a rebasing mint whose supply authority is the program-derived
+/// address that `attester` drives
+async fn create_mint(
+    banks_client: &mut BanksClient,
+    payer: &Keypair,
+    recent_blockhash: Hash,
+    program_id: &Pubkey,
+    attester: &Pubkey,
+    two_step_authority_transfer: bool,
+) -> Pubkey {
+    let mint = Keypair::new();
+    let space =
+        ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::RebaseMintConfig])
+            .unwrap();
+    let rent = banks_client.get_rent().await.unwrap();
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            system_instruction::create_account(
+                &payer.pubkey(),
+                &mint.pubkey(),
+                rent.minimum_balance(space),
+                space as u64,
+                &spl_token_2022::id(),
+            ),
+            {
+                let builder = rebase_mint::instruction::InitializeRebaseMintBuilder::new(
+                    &spl_token_2022::id(),
+                )
+                .mint(&mint.pubkey())
+                .supply_authority(&get_authority_address(&mint.pubkey(), attester, program_id))
+                .initial_supply(INITIAL_SUPPLY, 0);
+                if two_step_authority_transfer {
+                    builder.two_step_authority_transfer()
+                } else {
+                    builder
+                }
+                .build()
+                .unwrap()
+            },
+            spl_token_2022::instruction::initialize_mint(
+                &spl_token_2022::id(),
+                &mint.pubkey(),
+                &payer.pubkey(),
+                None,
+                6,
+            )
+            .unwrap(),
+        ],
+        Some(&payer.pubkey()),
+        &[payer, &mint],
+        recent_blockhash,
+    );
+    banks_client.process_transaction(transaction).await.unwrap();
+    mint.pubkey()
+}
+
+async fn get_config(banks_client: &mut BanksClient, mint: &Pubkey) -> RebaseMintConfig {
+    let account = banks_client.get_account(*mint).await.unwrap().unwrap();
+    let mint = StateWithExtensionsOwned::<Mint>::unpack(account.data).unwrap();
+    *mint.get_extension::<RebaseMintConfig>().unwrap()
+}
+
+#[tokio::test]
+async fn success_rebase() {
+    let program_id = Pubkey::from_str("RebaseManager111111111111111111111111111111").unwrap();
+    let (mut banks_client, payer, recent_blockhash) = program_test(&program_id).start().await;
+    let attester = Keypair::new();
+    let mint = create_mint(
+        &mut banks_client,
+        &payer,
+        recent_blockhash,
+        &program_id,
+        &attester.pubkey(),
+        false,
+    )
+    .await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[rebase(&program_id, &mint, &attester.pubkey(), 50)],
+        Some(&payer.pubkey()),
+        &[&payer, &attester],
+        recent_blockhash,
+    );
+    banks_client.process_transaction(transaction).await.unwrap();
+    let config = get_config(&mut banks_client, &mint).await;
+    assert_eq!(u64::from(config.total_supply), 1_005_000);
+    assert_eq!(u64::from(config.total_shares), INITIAL_SUPPLY);
+}
+
+#[tokio::test]
+async fn fail_rebase() {
+    let program_id = Pubkey::from_str("RebaseManager111111111111111111111111111111").unwrap();
+    let (mut banks_client, payer, recent_blockhash) = program_test(&program_id).start().await;
+    let attester = Keypair::new();
+    let mint = create_mint(
+        &mut banks_client,
+        &payer,
+        recent_blockhash,
+        &program_id,
+        &attester.pubkey(),
+        false,
+    )
+    .await;
+
+    // yield over the max
+    let transaction = Transaction::new_signed_with_payer(
+        &[rebase(
+            &program_id,
+            &mint,
+            &attester.pubkey(),
+            MAX_YIELD_BPS + 1,
+        )],
+        Some(&payer.pubkey()),
+        &[&payer, &attester],
+        recent_blockhash,
+    );
+    let err = banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        err,
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument)
+    );
+
+    // another attester drives another authority
+    let impostor = Keypair::new();
+    let mut instruction = rebase(&program_id, &mint, &impostor.pubkey(), 50);
+    instruction.accounts[1].pubkey = get_authority_address(&mint, &attester.pubkey(), &program_id);
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[&payer, &impostor],
+        recent_blockhash,
+    );
+    let err = banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        err,
+        TransactionError::InstructionError(0, InstructionError::InvalidSeeds)
+    );
+
+    // whose authority isn't the mint's
+    let transaction = Transaction::new_signed_with_payer(
+        &[rebase(&program_id, &mint, &impostor.pubkey(), 50)],
+        Some(&payer.pubkey()),
+        &[&payer, &impostor],
+        recent_blockhash,
+    );
+    let err = banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(TokenError::OwnerMismatch as u32)
+        )
+    );
+    let config = get_config(&mut banks_client, &mint).await;
+    assert_eq!(u64::from(config.total_supply), INITIAL_SUPPLY);
+}
+
+#[tokio::test]
+async fn success_rotate_authority() {
+    let program_id = Pubkey::from_str("RebaseManager111111111111111111111111111111").unwrap();
+    let (mut banks_client, payer, recent_blockhash) = program_test(&program_id).start().await;
+    let attester = Keypair::new();
+    let mint = create_mint(
+        &mut banks_client,
+        &payer,
+        recent_blockhash,
+        &program_id,
+        &attester.pubkey(),
+        false,
+    )
+    .await;
+    let human = Keypair::new();
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[rotate_authority(
+            &program_id,
+            &mint,
+            &attester.pubkey(),
+            &human.pubkey(),
+        )],
+        Some(&payer.pubkey()),
+        &[&payer, &attester],
+        recent_blockhash,
+    );
+    banks_client.process_transaction(transaction).await.unwrap();
+    let config = get_config(&mut banks_client, &mint).await;
+    assert_eq!(
+        Option::<Pubkey>::from(config.supply_authority),
+        Some(human.pubkey())
+    );
+
+    // the program can't rebase anymore, but the new authority can
+    let transaction = Transaction::new_signed_with_payer(
+        &[rebase(&program_id, &mint, &attester.pubkey(), 50)],
+        Some(&payer.pubkey()),
+        &[&payer, &attester],
+        recent_blockhash,
+    );
+    let err = banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(TokenError::OwnerMismatch as u32)
+        )
+    );
+    let transaction = Transaction::new_signed_with_payer(
+        &[rebase_mint::instruction::update_supply(
+            &spl_token_2022::id(),
+            &mint,
+            None,
+            &human.pubkey(),
+            &[],
+            2_000_000,
+        )
+        .unwrap()],
+        Some(&payer.pubkey()),
+        &[&payer, &human],
+        recent_blockhash,
+    );
+    banks_client.process_transaction(transaction).await.unwrap();
+    let config = get_config(&mut banks_client, &mint).await;
+    assert_eq!(u64::from(config.total_supply), 2_000_000);
+}
+
+#[tokio::test]
+async fn success_rotate_authority_two_step() {
+    let program_id = Pubkey::from_str("RebaseManager111111111111111111111111111111").unwrap();
+    let (mut banks_client, payer, recent_blockhash) = program_test(&program_id).start().await;
+    let attester = Keypair::new();
+    let mint = create_mint(
+        &mut banks_client,
+        &payer,
+        recent_blockhash,
+        &program_id,
+        &attester.pubkey(),
+        true,
+    )
+    .await;
+    let human = Keypair::new();
+
+    // the new authority is only proposed
+    let transaction = Transaction::new_signed_with_payer(
+        &[rotate_authority(
+            &program_id,
+            &mint,
+            &attester.pubkey(),
+            &human.pubkey(),
+        )],
+        Some(&payer.pubkey()),
+        &[&payer, &attester],
+        recent_blockhash,
+    );
+    banks_client.process_transaction(transaction).await.unwrap();
+    let config = get_config(&mut banks_client, &mint).await;
+    assert_eq!(
+        Option::<Pubkey>::from(config.supply_authority),
+        Some(get_authority_address(
+            &mint,
+            &attester.pubkey(),
+            &program_id
+        ))
+    );
+    assert_eq!(
+        Option::<Pubkey>::from(config.pending_supply_authority),
+        Some(human.pubkey())
+    );
+
+    // and takes over once it accepts
+    let transaction = Transaction::new_signed_with_payer(
+        &[rebase_mint::instruction::accept_supply_authority(
+            &spl_token_2022::id(),
+            &mint,
+            &human.pubkey(),
+            &[],
+        )
+        .unwrap()],
+        Some(&payer.pubkey()),
+        &[&payer, &human],
+        recent_blockhash,
+    );
+    banks_client.process_transaction(transaction).await.unwrap();
+    let config = get_config(&mut banks_client, &mint).await;
+    assert_eq!(
+        Option::<Pubkey>::from(config.supply_authority),
+        Some(human.pubkey())
+    );
+}
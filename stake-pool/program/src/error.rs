@@ -164,6 +164,9 @@ pub enum StakePoolError {
     /// Missing required sysvar account
     #[error("Missing required sysvar account")]
     MissingRequiredSysvar,
+    /// The pool mint rebases its supply, which pool tokens can't follow
+    #[error("RebasingPoolMint")]
+    RebasingPoolMint,
 }
 impl From<StakePoolError> for ProgramError {
     fn from(e: StakePoolError) -> Self {
@@ -38,7 +38,7 @@ use {
     },
     spl_token_2022::{
         check_spl_token_program_account,
-        extension::{BaseStateWithExtensions, StateWithExtensions},
+        extension::{rebase_mint::RebaseMintConfig, BaseStateWithExtensions, StateWithExtensions},
         native_mint,
         state::Mint,
     },
@@ -852,6 +852,13 @@ impl Processor {
                 return Err(StakePoolError::InvalidMintFreezeAuthority.into());
             }
 
+            // Pool tokens gain value against SOL through `pool_token_supply`,
+            // which a rebase wouldn't update, and are burned as amounts from
+            // the mint's base supply, which doesn't follow rebases either
+            if pool_mint.get_extension::<RebaseMintConfig>().is_ok() {
+                return Err(StakePoolError::RebasingPoolMint.into());
+            }
+
             let extensions = pool_mint.get_extension_types()?;
             if extensions
                 .iter()
@@ -4125,6 +4132,7 @@ impl PrintProgramError for StakePoolError {
             StakePoolError::IncorrectMintDecimals => msg!("Error: Provided mint does not have 9 decimals to match SOL"),
             StakePoolError::ReserveDepleted => msg!("Error: Pool reserve does not have enough lamports to fund rent-exempt reserve in split destination. Deposit more SOL in reserve, or pre-fund split destination with the rent-exempt reserve for a stake account."),
             StakePoolError::MissingRequiredSysvar => msg!("Missing required sysvar account"),
+            StakePoolError::RebasingPoolMint => msg!("Error: The pool mint cannot rebase its supply, pool tokens appreciate against SOL instead"),
         }
     }
 }
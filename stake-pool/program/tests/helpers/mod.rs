@@ -136,6 +136,11 @@ pub async fn create_mint(
             ),
             ExtensionType::NonTransferable =>
                 spl_token_2022::instruction::initialize_non_transferable_mint(program_id, &mint_pubkey),
+            ExtensionType::RebaseMintConfig => spl_token_2022::extension::rebase_mint::instruction::InitializeRebaseMintBuilder::new(program_id)
+                .mint(&mint_pubkey)
+                .supply_authority(manager)
+                .allow_supply_decrease()
+                .build(),
             _ => unimplemented!(),
         };
         instructions.push(instruction.unwrap());
@@ -249,7 +254,9 @@ pub async fn create_token_account(
             ExtensionType::TransferFeeAmount
             | ExtensionType::MemoTransfer
             | ExtensionType::CpiGuard
-            | ExtensionType::NonTransferableAccount => (),
+            | ExtensionType::NonTransferableAccount
+            | ExtensionType::RebaseAccount
+            | ExtensionType::LazyRebaseAccount => (),
             _ => unimplemented!(),
         };
     }
@@ -633,6 +633,57 @@ async fn fail_with_unsupported_mint_extension() {
     );
 }
 
+#[tokio::test]
+async fn fail_with_rebasing_mint() {
+    let (mut banks_client, payer, recent_blockhash) = program_test().start().await;
+    let stake_pool_accounts = StakePoolAccounts::new_with_token_program(spl_token_2022::id());
+
+    let mint_extensions = vec![ExtensionType::RebaseMintConfig];
+    create_required_accounts(
+        &mut banks_client,
+        &payer,
+        &recent_blockhash,
+        &stake_pool_accounts,
+        &mint_extensions,
+    )
+    .await;
+
+    let error = create_stake_pool(
+        &mut banks_client,
+        &payer,
+        &recent_blockhash,
+        &stake_pool_accounts.stake_pool,
+        &stake_pool_accounts.validator_list,
+        &stake_pool_accounts.reserve_stake.pubkey(),
+        &stake_pool_accounts.token_program_id,
+        &stake_pool_accounts.pool_mint.pubkey(),
+        &stake_pool_accounts.pool_fee_account.pubkey(),
+        &stake_pool_accounts.manager,
+        &stake_pool_accounts.staker.pubkey(),
+        &stake_pool_accounts.withdraw_authority,
+        &None,
+        &stake_pool_accounts.epoch_fee,
+        &stake_pool_accounts.withdrawal_fee,
+        &stake_pool_accounts.deposit_fee,
+        stake_pool_accounts.referral_fee,
+        &stake_pool_accounts.sol_deposit_fee,
+        stake_pool_accounts.sol_referral_fee,
+        stake_pool_accounts.max_validators,
+    )
+    .await
+    .err()
+    .unwrap()
+    .unwrap();
+
+    assert_eq!(
+        error,
+        TransactionError::InstructionError(
+            2,
+            InstructionError::Custom(error::StakePoolError::RebasingPoolMint as u32),
+        )
+    );
+}
+
 #[tokio::test]
 async fn fail_with_unsupported_account_extension() {
     let (mut banks_client, payer, recent_blockhash) = program_test().start().await;
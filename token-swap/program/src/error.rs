@@ -117,6 +117,9 @@ pub enum SwapError {
     /// The pool fee account is invalid.
     #[error("The pool fee account is invalid")]
     InvalidFeeAccount,
+    /// A swap token account of a rebasing mint is not exempt from rebasing.
+    #[error("Swap token account is not exempt from rebasing")]
+    RebasingSwapAccount,
 }
 impl From<SwapError> for ProgramError {
     fn from(e: SwapError) -> Self {
@@ -200,6 +203,9 @@ impl PrintProgramError for SwapError {
             SwapError::InvalidFeeAccount => {
                 msg!("Error: The pool fee account is invalid")
             }
+            SwapError::RebasingSwapAccount => {
+                msg!("Error: Swap token account is not exempt from rebasing")
+            }
         }
     }
 }
@@ -103,8 +103,10 @@ pub enum SwapInstruction {
     ///   0. `[writable, signer]` New Token-swap to create.
     ///   1. `[]` swap authority derived from
     ///      `create_program_address(&[Token-swap account])`
-    ///   2. `[]` token_a Account. Must be non zero, owned by swap authority.
-    ///   3. `[]` token_b Account. Must be non zero, owned by swap authority.
+    ///   2. `[]` token_a Account. Must be non zero, owned by swap authority,
+    ///      and exempt from rebasing if its mint is rebasing.
+    ///   3. `[]` token_b Account. Must be non zero, owned by swap authority,
+    ///      and exempt from rebasing if its mint is rebasing.
     ///   4. `[writable]` Pool Token Mint. Must be empty, owned by swap
     ///      authority.
     ///   5. `[]` Pool Token Account to deposit trading and withdraw fees.
@@ -129,8 +131,8 @@ pub enum SwapInstruction {
     ///      the owner.
     ///   7. `[writable]` Pool token mint, to generate trading fees
     ///   8. `[writable]` Fee account, to receive trading fees
-    ///   9. `[]` Token (A|B) SOURCE mint
-    ///   10. `[]` Token (A|B) DESTINATION mint
+    ///   9. `[]` Token (A|B) SOURCE mint, `[writable]` if rebasing
+    ///   10. `[]` Token (A|B) DESTINATION mint, `[writable]` if rebasing
     ///   11. `[]` Token (A|B) SOURCE program id
     ///   12. `[]` Token (A|B) DESTINATION program id
     ///   13. `[]` Pool Token program id
@@ -152,8 +154,8 @@ pub enum SwapInstruction {
     ///   7. `[writable]` Pool MINT account, swap authority is the owner.
     ///   8. `[writable]` Pool Account to deposit the generated tokens, user is
     ///      the owner.
-    ///   9. `[]` Token A mint
-    ///   10. `[]` Token B mint
+    ///   9. `[]` Token A mint, `[writable]` if rebasing
+    ///   10. `[]` Token B mint, `[writable]` if rebasing
     ///   11. `[]` Token A program id
     ///   12. `[]` Token B program id
     ///   13. `[]` Pool Token program id
@@ -174,8 +176,8 @@ pub enum SwapInstruction {
     ///   7. `[writable]` token_a user Account to credit.
     ///   8. `[writable]` token_b user Account to credit.
     ///   9. `[writable]` Fee account, to receive withdrawal fees
-    ///   10. `[]` Token A mint
-    ///   11. `[]` Token B mint
+    ///   10. `[]` Token A mint, `[writable]` if rebasing
+    ///   11. `[]` Token B mint, `[writable]` if rebasing
     ///   12. `[]` Pool Token program id
     ///   13. `[]` Token A program id
     ///   14. `[]` Token B program id
@@ -195,7 +197,7 @@ pub enum SwapInstruction {
     ///   6. `[writable]` Pool MINT account, swap authority is the owner.
     ///   7. `[writable]` Pool Account to deposit the generated tokens, user is
     ///      the owner.
-    ///   8. `[]` Token (A|B) SOURCE mint
+    ///   8. `[]` Token (A|B) SOURCE mint, `[writable]` if rebasing
     ///   9. `[]` Token (A|B) SOURCE program id
     ///   10. `[]` Pool Token program id
     DepositSingleTokenTypeExactAmountIn(DepositSingleTokenTypeExactAmountIn),
@@ -213,7 +215,7 @@ pub enum SwapInstruction {
     ///   6. `[writable]` token_b Swap Account to potentially withdraw from.
     ///   7. `[writable]` token_(A|B) User Account to credit
     ///   8. `[writable]` Fee account, to receive withdrawal fees
-    ///   9. `[]` Token (A|B) DESTINATION mint
+    ///   9. `[]` Token (A|B) DESTINATION mint, `[writable]` if rebasing
     ///   10. `[]` Pool Token program id
     ///   11. `[]` Token (A|B) DESTINATION program id
     WithdrawSingleTokenTypeExactAmountOut(WithdrawSingleTokenTypeExactAmountOut),
@@ -33,8 +33,8 @@ use {
         check_spl_token_program_account,
         error::TokenError,
         extension::{
-            mint_close_authority::MintCloseAuthority, transfer_fee::TransferFeeConfig,
-            BaseStateWithExtensions, StateWithExtensions,
+            mint_close_authority::MintCloseAuthority, rebase_mint::RebaseAccount,
+            transfer_fee::TransferFeeConfig, BaseStateWithExtensions, StateWithExtensions,
         },
         state::{Account, Mint},
     },
@@ -89,6 +89,27 @@ impl Processor {
         }
     }
 
+    /// Checks that a swap token account holds a fixed amount of tokens.
+    ///
+    /// The curves price trades from the swap's balances, so a rebase would
+    /// move the price without any trade, for arbitrageurs to take from the
+    /// liquidity providers. Accounts of rebasing mints must be exempt from
+    /// rebasing to hold the swap's tokens.
+    ///
+    /// Anything that isn't a token account is left for `unpack_token_account`
+    /// to reject.
+    fn check_not_rebasing(account_info: &AccountInfo) -> Result<(), SwapError> {
+        let account_data = account_info.data.borrow();
+        if let Ok(account) = StateWithExtensions::<Account>::unpack(&account_data) {
+            if let Ok(rebase_account) = account.get_extension::<RebaseAccount>() {
+                if !rebase_account.is_exempt() {
+                    return Err(SwapError::RebasingSwapAccount);
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Calculates the authority id by generating a program address.
     pub fn authority_id(
         program_id: &Pubkey,
@@ -174,7 +195,7 @@ impl Processor {
         let swap_bytes = swap.to_bytes();
         let authority_signature_seeds = [&swap_bytes[..32], &[bump_seed]];
         let signers = &[&authority_signature_seeds[..]];
-        let ix = spl_token_2022::instruction::transfer_checked(
+        let mut ix = spl_token_2022::instruction::transfer_checked(
             token_program.key,
             source.key,
             mint.key,
@@ -184,6 +205,9 @@ impl Processor {
             amount,
             decimals,
         )?;
+        // a rebasing mint's totals change when tokens move between the swap's
+        // exempt accounts and rebasing ones
+        ix.accounts[1].is_writable = mint.is_writable;
         invoke_signed_wrapper::<TokenError>(
             &ix,
             &[source, mint, destination, authority, token_program],
@@ -225,6 +249,8 @@ impl Processor {
         if *pool_token_program_info.key != *token_swap.token_program_id() {
             return Err(SwapError::IncorrectTokenProgramId.into());
         }
+        Self::check_not_rebasing(token_a_info)?;
+        Self::check_not_rebasing(token_b_info)?;
         if let Some(user_token_a_info) = user_token_a_info {
             if token_a_info.key == user_token_a_info.key {
                 return Err(SwapError::InvalidInput.into());
@@ -273,6 +299,8 @@ impl Processor {
         }
         let token_a = Self::unpack_token_account(token_a_info, &token_program_id)?;
         let token_b = Self::unpack_token_account(token_b_info, &token_program_id)?;
+        Self::check_not_rebasing(token_a_info)?;
+        Self::check_not_rebasing(token_b_info)?;
         let fee_account = Self::unpack_token_account(fee_account_info, &token_program_id)?;
         let destination = Self::unpack_token_account(destination_info, &token_program_id)?;
         let pool_mint = {
@@ -444,6 +472,9 @@ impl Processor {
             Self::unpack_token_account(swap_source_info, token_swap.token_program_id())?;
         let dest_account =
             Self::unpack_token_account(swap_destination_info, token_swap.token_program_id())?;
+        // the supply authority can stop exempting the accounts after `Initialize`
+        Self::check_not_rebasing(swap_source_info)?;
+        Self::check_not_rebasing(swap_destination_info)?;
         let pool_mint = Self::unpack_mint(pool_mint_info, token_swap.token_program_id())?;
 
         // Take transfer fees into account for actual amount transferred in
@@ -1299,6 +1330,7 @@ mod tests {
         spl_token_2022::{
             error::TokenError,
             extension::{
+                rebase_mint,
                 transfer_fee::{instruction::initialize_transfer_fee_config, TransferFee},
                 ExtensionType,
             },
@@ -1339,6 +1371,7 @@ mod tests {
                 for account_info in account_infos.iter() {
                     if meta.pubkey == *account_info.key {
                         let mut new_account_info = account_info.clone();
+                        new_account_info.is_writable = meta.is_writable;
                         for seeds in signers_seeds.iter() {
                             let signer =
                                 Pubkey::create_program_address(seeds, &SWAP_PROGRAM_ID).unwrap();
@@ -2100,6 +2133,9 @@ mod tests {
             .map(|(account_meta, account)| (&account_meta.pubkey, account_meta.is_signer, account))
             .collect::<Vec<_>>();
         let mut account_infos = create_is_signer_account_infos(&mut meta);
+        for (account_info, account_meta) in account_infos.iter_mut().zip(&instruction.accounts) {
+            account_info.is_writable = account_meta.is_writable;
+        }
         let res = if instruction.program_id == SWAP_PROGRAM_ID {
             Processor::process_with_constraints(
                 &instruction.program_id,
@@ -2275,6 +2311,111 @@ mod tests {
         (mint_key, mint_account)
     }
 
+    fn create_rebasing_mint(authority_key: &Pubkey) -> (Pubkey, SolanaAccount) {
+        let program_id = spl_token_2022::id();
+        let mint_key = Pubkey::new_unique();
+        let space =
+            ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::RebaseMintConfig])
+                .unwrap();
+        let minimum_balance = Rent::default().minimum_balance(space);
+        let mut mint_account = SolanaAccount::new(minimum_balance, space, &program_id);
+        let mut rent_sysvar_account = create_account_for_test(&Rent::free());
+
+        do_process_instruction(
+            rebase_mint::instruction::InitializeRebaseMintBuilder::new(&program_id)
+                .mint(&mint_key)
+                .supply_authority(authority_key)
+                .allow_supply_decrease()
+                .build()
+                .unwrap(),
+            vec![&mut mint_account],
+        )
+        .unwrap();
+        do_process_instruction(
+            initialize_mint(&program_id, &mint_key, authority_key, None, 2).unwrap(),
+            vec![&mut mint_account, &mut rent_sysvar_account],
+        )
+        .unwrap();
+
+        (mint_key, mint_account)
+    }
+
+    fn mint_rebasing_token(
+        mint_key: &Pubkey,
+        mint_account: &mut SolanaAccount,
+        authority_key: &Pubkey,
+        account_owner_key: &Pubkey,
+        amount: u64,
+    ) -> (Pubkey, SolanaAccount) {
+        let program_id = spl_token_2022::id();
+        let account_key = Pubkey::new_unique();
+        let space =
+            ExtensionType::try_calculate_account_len::<Account>(&[ExtensionType::RebaseAccount])
+                .unwrap();
+        let minimum_balance = Rent::default().minimum_balance(space);
+        let mut account_account = SolanaAccount::new(minimum_balance, space, &program_id);
+        let mut authority_account = SolanaAccount::default();
+        let mut rent_sysvar_account = create_account_for_test(&Rent::free());
+
+        do_process_instruction(
+            initialize_account(&program_id, &account_key, mint_key, account_owner_key).unwrap(),
+            vec![
+                &mut account_account,
+                mint_account,
+                &mut authority_account,
+                &mut rent_sysvar_account,
+            ],
+        )
+        .unwrap();
+        do_process_instruction(
+            mint_to(
+                &program_id,
+                mint_key,
+                &account_key,
+                authority_key,
+                &[],
+                amount,
+            )
+            .unwrap(),
+            vec![mint_account, &mut account_account, &mut authority_account],
+        )
+        .unwrap();
+
+        (account_key, account_account)
+    }
+
+    fn set_rebase_exempt(
+        account_key: &Pubkey,
+        account: &mut SolanaAccount,
+        mint_key: &Pubkey,
+        mint_account: &mut SolanaAccount,
+        authority_key: &Pubkey,
+        exempt: bool,
+    ) -> ProgramResult {
+        do_process_instruction(
+            rebase_mint::instruction::set_account_exempt(
+                &spl_token_2022::id(),
+                account_key,
+                mint_key,
+                authority_key,
+                &[],
+                exempt,
+            )
+            .unwrap(),
+            vec![account, mint_account, &mut SolanaAccount::default()],
+        )
+    }
+
+    fn rebased_balance(account: &SolanaAccount, mint_account: &SolanaAccount) -> u64 {
+        let account = StateWithExtensions::<Account>::unpack(&account.data).unwrap();
+        let mint = StateWithExtensions::<Mint>::unpack(&mint_account.data).unwrap();
+        let shares = account.get_extension::<RebaseAccount>().unwrap().shares;
+        mint.get_extension::<rebase_mint::RebaseMintConfig>()
+            .unwrap()
+            .shares_to_amount(shares.into())
+            .unwrap()
+    }
+
     #[test_case(spl_token::id(); "token")]
     #[test_case(spl_token_2022::id(); "token-2022")]
     fn test_token_program_id_error(token_program_id: Pubkey) {
@@ -8377,4 +8518,215 @@ mod tests {
             &token_b_program_id,
         );
     }
+
+    #[test]
+    fn test_rebasing_swap_accounts() {
+        let user_key = Pubkey::new_unique();
+        let swapper_key = Pubkey::new_unique();
+        let fees = Fees {
+            trade_fee_numerator: 1,
+            trade_fee_denominator: 10,
+            owner_trade_fee_numerator: 1,
+            owner_trade_fee_denominator: 30,
+            owner_withdraw_fee_numerator: 1,
+            owner_withdraw_fee_denominator: 30,
+            host_fee_numerator: 20,
+            host_fee_denominator: 100,
+        };
+        let swap_curve = SwapCurve {
+            curve_type: CurveType::ConstantProduct,
+            calculator: Arc::new(ConstantProductCurve {}),
+        };
+        let token_a_amount = 1_000_000;
+        let token_b_amount = 5_000_000;
+        let program_id = spl_token_2022::id();
+        let mut accounts = SwapAccountInfo::new(
+            &user_key,
+            fees.clone(),
+            SwapTransferFees::default(),
+            swap_curve.clone(),
+            token_a_amount,
+            token_b_amount,
+            &program_id,
+            &program_id,
+            &program_id,
+        );
+
+        // token A is rebasing
+        let (token_a_mint_key, mut token_a_mint_account) = create_rebasing_mint(&user_key);
+        let (token_a_key, token_a_account) = mint_rebasing_token(
+            &token_a_mint_key,
+            &mut token_a_mint_account,
+            &user_key,
+            &accounts.authority_key,
+            token_a_amount,
+        );
+        accounts.token_a_mint_key = token_a_mint_key;
+        accounts.token_a_mint_account = token_a_mint_account;
+        accounts.token_a_key = token_a_key;
+        accounts.token_a_account = token_a_account;
+
+        // the swap's account must be exempt
+        assert_eq!(
+            Err(SwapError::RebasingSwapAccount.into()),
+            accounts.initialize_swap()
+        );
+        set_rebase_exempt(
+            &accounts.token_a_key,
+            &mut accounts.token_a_account,
+            &accounts.token_a_mint_key,
+            &mut accounts.token_a_mint_account,
+            &user_key,
+            true,
+        )
+        .unwrap();
+        accounts.initialize_swap().unwrap();
+
+        let swapper_a_amount = 2_000_000;
+        let (swapper_a_key, mut swapper_a_account) = mint_rebasing_token(
+            &accounts.token_a_mint_key,
+            &mut accounts.token_a_mint_account,
+            &user_key,
+            &swapper_key,
+            swapper_a_amount,
+        );
+        let swapper_b_amount = 1_000_000;
+        let (swapper_b_key, mut swapper_b_account) = mint_token(
+            &program_id,
+            &accounts.token_b_mint_key,
+            &mut accounts.token_b_mint_account,
+            &user_key,
+            &swapper_key,
+            swapper_b_amount,
+        );
+
+        // halving the supply halves the swapper's balance, but not the swap's,
+        // so the price holds
+        do_process_instruction(
+            rebase_mint::instruction::update_supply(
+                &program_id,
+                &accounts.token_a_mint_key,
+                None,
+                &user_key,
+                &[],
+                swapper_a_amount / 2,
+            )
+            .unwrap(),
+            vec![
+                &mut accounts.token_a_mint_account,
+                &mut SolanaAccount::default(),
+            ],
+        )
+        .unwrap();
+        let swapper_a_amount = swapper_a_amount / 2;
+        assert_eq!(
+            rebased_balance(&swapper_a_account, &accounts.token_a_mint_account),
+            swapper_a_amount
+        );
+        let swap_token_a =
+            StateWithExtensions::<Account>::unpack(&accounts.token_a_account.data).unwrap();
+        assert_eq!(swap_token_a.base.amount, token_a_amount);
+
+        let b_to_a_amount = 100_000;
+        let swap_instruction = || {
+            let mut instruction = swap(
+                &SWAP_PROGRAM_ID,
+                &program_id,
+                &program_id,
+                &program_id,
+                &accounts.swap_key,
+                &accounts.authority_key,
+                &swapper_key,
+                &swapper_b_key,
+                &accounts.token_b_key,
+                &accounts.token_a_key,
+                &swapper_a_key,
+                &accounts.pool_mint_key,
+                &accounts.pool_fee_key,
+                &accounts.token_b_mint_key,
+                &accounts.token_a_mint_key,
+                None,
+                Swap {
+                    amount_in: b_to_a_amount,
+                    minimum_amount_out: 0,
+                },
+            )
+            .unwrap();
+            // tokens leaving the exempt account are issued as shares
+            instruction.accounts[10].is_writable = true;
+            instruction
+        };
+        do_process_instruction(
+            swap_instruction(),
+            vec![
+                &mut accounts.swap_account,
+                &mut SolanaAccount::default(),
+                &mut SolanaAccount::default(),
+                &mut swapper_b_account,
+                &mut accounts.token_b_account,
+                &mut accounts.token_a_account,
+                &mut swapper_a_account,
+                &mut accounts.pool_mint_account,
+                &mut accounts.pool_fee_account,
+                &mut accounts.token_b_mint_account,
+                &mut accounts.token_a_mint_account,
+                &mut SolanaAccount::default(),
+                &mut SolanaAccount::default(),
+                &mut SolanaAccount::default(),
+            ],
+        )
+        .unwrap();
+
+        let results = swap_curve
+            .swap(
+                b_to_a_amount.into(),
+                token_b_amount.into(),
+                token_a_amount.into(),
+                TradeDirection::BtoA,
+                &fees,
+            )
+            .unwrap();
+        let amount_out = to_u64(results.destination_amount_swapped).unwrap();
+        let swap_token_a =
+            StateWithExtensions::<Account>::unpack(&accounts.token_a_account.data).unwrap();
+        assert_eq!(swap_token_a.base.amount, token_a_amount - amount_out);
+        assert_eq!(
+            rebased_balance(&swapper_a_account, &accounts.token_a_mint_account),
+            swapper_a_amount + amount_out
+        );
+
+        // the supply authority can stop exempting the swap's account at any
+        // time, which stops trading
+        set_rebase_exempt(
+            &accounts.token_a_key,
+            &mut accounts.token_a_account,
+            &accounts.token_a_mint_key,
+            &mut accounts.token_a_mint_account,
+            &user_key,
+            false,
+        )
+        .unwrap();
+        assert_eq!(
+            Err(SwapError::RebasingSwapAccount.into()),
+            do_process_instruction(
+                swap_instruction(),
+                vec![
+                    &mut accounts.swap_account,
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                    &mut swapper_b_account,
+                    &mut accounts.token_b_account,
+                    &mut accounts.token_a_account,
+                    &mut swapper_a_account,
+                    &mut accounts.pool_mint_account,
+                    &mut accounts.pool_fee_account,
+                    &mut accounts.token_b_mint_account,
+                    &mut accounts.token_a_mint_account,
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                ],
+            )
+        );
+    }
 }
@@ -15,7 +15,8 @@ use {
 pub enum TokenUpgradeInstruction {
     /// Burns all of the original tokens in the user's account, and transfers
     /// the same amount of tokens from an account owned by a PDA into
-    /// another account.
+    /// another account. If the new mint is rebasing, each original token
+    /// becomes one share instead, worth the mint's current ratio.
     ///
     /// Accounts expected by this instruction:
     ///
@@ -25,7 +26,7 @@ pub enum TokenUpgradeInstruction {
     ///      address: `get_token_upgrade_authority_address(original_mint,
     ///      new_mint, program_id)`
     ///   3. `[writeable]` New token account to transfer into
-    ///   4. `[]` New token mint
+    ///   4. `[writeable]` New token mint
     ///   5. `[]` Transfer authority (owner or delegate) of new token escrow
     ///      held by PDA, must be:
     ///      `get_token_upgrade_authority_address(original_mint, new_mint,
@@ -14,7 +14,10 @@ use {
         pubkey::Pubkey,
     },
     spl_token_2022::{
-        extension::StateWithExtensions,
+        extension::{
+            rebase_mint::{self, RebaseAccount, RebaseMintConfig},
+            BaseStateWithExtensions, StateWithExtensions,
+        },
         instruction::decode_instruction_type,
         state::{Account, Mint},
     },
@@ -80,6 +83,52 @@ fn transfer_new_tokens<'a>(
     )
 }
 
+#[allow(clippy::too_many_arguments)]
+fn transfer_new_shares<'a>(
+    new_token_program: AccountInfo<'a>,
+    source: AccountInfo<'a>,
+    mint: AccountInfo<'a>,
+    destination: AccountInfo<'a>,
+    authority: AccountInfo<'a>,
+    authority_seeds: &[&[u8]],
+    shares: u64,
+    decimals: u8,
+) -> Result<(), ProgramError> {
+    let mut ix = rebase_mint::instruction::transfer_shares_checked(
+        new_token_program.key,
+        source.key,
+        mint.key,
+        destination.key,
+        authority.key,
+        &[],
+        shares,
+        decimals,
+    )?;
+    // an exempt escrow changes the totals on the mint
+    ix.accounts[1].is_writable = mint.is_writable;
+    invoke_signed(
+        &ix,
+        &[source, mint, destination, authority],
+        &[authority_seeds],
+    )
+}
+
+/// Shares held by a token account of a rebasing mint, counting an exempt
+/// account's tokens at the current ratio
+fn rebase_shares(
+    config: &RebaseMintConfig,
+    account: &StateWithExtensions<Account>,
+) -> Result<u64, ProgramError> {
+    let rebase_account = account.get_extension::<RebaseAccount>()?;
+    if rebase_account.is_exempt() {
+        config
+            .amount_to_shares(account.base.amount)
+            .ok_or(ProgramError::ArithmeticOverflow)
+    } else {
+        Ok(rebase_account.shares.into())
+    }
+}
+
 fn process_exchange(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
 
@@ -122,7 +171,7 @@ fn process_exchange(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramRes
     }
 
     // pull out these values in a block to drop all data before performing CPIs
-    let (token_amount, decimals) = {
+    let (token_amount, decimals, rebasing) = {
         // check mints are actually mints
         let original_mint_data = original_mint_info.try_borrow_data()?;
         let original_mint = StateWithExtensions::<Mint>::unpack(&original_mint_data)?;
@@ -138,10 +187,19 @@ fn process_exchange(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramRes
         let _ = StateWithExtensions::<Account>::unpack(&new_account_data)?;
 
         let token_amount = original_account.base.amount;
-        if new_escrow.base.amount < token_amount {
+        // Each original token becomes a share of a rebasing mint, so that
+        // holders get the same part of the supply whether they upgrade before
+        // or after a rebase
+        let rebase_mint_config = new_mint.get_extension::<RebaseMintConfig>().ok();
+        let (escrow_balance, unit) = match rebase_mint_config {
+            Some(config) => (rebase_shares(config, &new_escrow)?, "shares"),
+            None => (new_escrow.base.amount, "tokens"),
+        };
+        if escrow_balance < token_amount {
             msg!(
-                "Escrow only has {} tokens, needs at least {}",
-                new_escrow.base.amount,
+                "Escrow only has {} {}, needs at least {}",
+                escrow_balance,
+                unit,
                 token_amount
             );
             return Err(ProgramError::InsufficientFunds);
@@ -155,7 +213,11 @@ fn process_exchange(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramRes
             return Err(TokenUpgradeError::DecimalsMismatch.into());
         }
 
-        (original_account.base.amount, original_mint.base.decimals)
+        (
+            original_account.base.amount,
+            original_mint.base.decimals,
+            rebase_mint_config.is_some(),
+        )
     };
 
     burn_original_tokens(
@@ -168,16 +230,29 @@ fn process_exchange(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramRes
         decimals,
     )?;
 
-    transfer_new_tokens(
-        new_token_program.clone(),
-        new_escrow_info.clone(),
-        new_mint_info.clone(),
-        new_account_info.clone(),
-        new_transfer_authority_info.clone(),
-        &authority_seeds,
-        token_amount,
-        decimals,
-    )?;
+    if rebasing {
+        transfer_new_shares(
+            new_token_program.clone(),
+            new_escrow_info.clone(),
+            new_mint_info.clone(),
+            new_account_info.clone(),
+            new_transfer_authority_info.clone(),
+            &authority_seeds,
+            token_amount,
+            decimals,
+        )?;
+    } else {
+        transfer_new_tokens(
+            new_token_program.clone(),
+            new_escrow_info.clone(),
+            new_mint_info.clone(),
+            new_account_info.clone(),
+            new_transfer_authority_info.clone(),
+            &authority_seeds,
+            token_amount,
+            decimals,
+        )?;
+    }
 
     Ok(())
 }
@@ -15,12 +15,13 @@ use {
         signer::keypair::Keypair,
         transaction::{Transaction, TransactionError},
     },
+    spl_token_2022::extension::{rebase_mint::RebaseAccount, BaseStateWithExtensions},
     spl_token_client::{
         client::{
             ProgramBanksClient, ProgramBanksClientProcessTransaction, ProgramClient,
             SendTransaction, SimulateTransaction,
         },
-        token::Token,
+        token::{ExtensionInitializationParams, Token},
     },
     spl_token_upgrade::{
         error::TokenUpgradeError, get_token_upgrade_authority_address, instruction::exchange,
@@ -202,6 +203,170 @@ async fn success(original_program_id: Pubkey, new_program_id: Pubkey) {
     assert_eq!(escrow_info.base.amount, 0);
 }
 
+async fn exchange_all<T: SendTransaction + SimulateTransaction>(
+    context: &Mutex<ProgramTestContext>,
+    original_token: &Token<T>,
+    original_program_id: &Pubkey,
+    new_token: &Token<T>,
+    escrow_account: &Pubkey,
+    wallet: &Keypair,
+) {
+    let mut context = context.lock().await;
+    let transaction = Transaction::new_signed_with_payer(
+        &[exchange(
+            &spl_token_upgrade::id(),
+            &original_token.get_associated_token_address(&wallet.pubkey()),
+            original_token.get_address(),
+            escrow_account,
+            &new_token.get_associated_token_address(&wallet.pubkey()),
+            new_token.get_address(),
+            original_program_id,
+            &spl_token_2022::id(),
+            &wallet.pubkey(),
+            &[],
+        )],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, wallet],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+}
+
+#[test_case(spl_token::id() ; "upgrade from token")]
+#[test_case(spl_token_2022::id() ; "upgrade from token-2022")]
+#[tokio::test]
+async fn success_rebasing(original_program_id: Pubkey) {
+    let (context, client, payer) = setup().await;
+
+    let mint_authority = Keypair::new();
+    let mint_authority_pubkey = mint_authority.pubkey();
+    let supply_authority = Keypair::new();
+
+    let decimals = 2;
+    let original_token = setup_mint(
+        &original_program_id,
+        &mint_authority_pubkey,
+        decimals,
+        payer.clone(),
+        client.clone(),
+    )
+    .await;
+    let new_mint_account = Keypair::new();
+    let new_token = Token::new(
+        client,
+        &spl_token_2022::id(),
+        &new_mint_account.pubkey(),
+        Some(decimals),
+        payer,
+    );
+    new_token
+        .create_mint(
+            &mint_authority_pubkey,
+            None,
+            vec![ExtensionInitializationParams::RebaseMintConfig {
+                supply_authority: Some(supply_authority.pubkey()),
+                initial_supply: 0,
+            }],
+            &[&new_mint_account],
+        )
+        .await
+        .unwrap();
+
+    let program_escrow = get_token_upgrade_authority_address(
+        original_token.get_address(),
+        new_token.get_address(),
+        &spl_token_upgrade::id(),
+    );
+    new_token
+        .create_associated_token_account(&program_escrow)
+        .await
+        .unwrap();
+    let escrow_account = new_token.get_associated_token_address(&program_escrow);
+    let token_amount = 1_000_000;
+    new_token
+        .mint_to(
+            &escrow_account,
+            &mint_authority_pubkey,
+            2 * token_amount,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    let early_wallet = Keypair::new();
+    let late_wallet = Keypair::new();
+    for wallet in [&early_wallet, &late_wallet] {
+        original_token
+            .create_associated_token_account(&wallet.pubkey())
+            .await
+            .unwrap();
+        original_token
+            .mint_to(
+                &original_token.get_associated_token_address(&wallet.pubkey()),
+                &mint_authority_pubkey,
+                token_amount,
+                &[&mint_authority],
+            )
+            .await
+            .unwrap();
+        new_token
+            .create_associated_token_account(&wallet.pubkey())
+            .await
+            .unwrap();
+    }
+
+    // one holder upgrades before the supply doubles, the other after
+    exchange_all(
+        &context,
+        &original_token,
+        &original_program_id,
+        &new_token,
+        &escrow_account,
+        &early_wallet,
+    )
+    .await;
+    new_token
+        .rebase_supply(
+            &supply_authority.pubkey(),
+            4 * token_amount,
+            &[&supply_authority],
+        )
+        .await
+        .unwrap();
+    exchange_all(
+        &context,
+        &original_token,
+        &original_program_id,
+        &new_token,
+        &escrow_account,
+        &late_wallet,
+    )
+    .await;
+
+    let original_mint = original_token.get_mint_info().await.unwrap();
+    assert_eq!(original_mint.base.supply, 0);
+    for wallet in [&early_wallet, &late_wallet] {
+        let new_account = new_token.get_associated_token_address(&wallet.pubkey());
+        let new_account_info = new_token.get_account_info(&new_account).await.unwrap();
+        let shares = new_account_info
+            .get_extension::<RebaseAccount>()
+            .unwrap()
+            .shares;
+        assert_eq!(u64::from(shares), token_amount);
+        assert_eq!(
+            new_token.get_rebased_balance(&new_account).await.unwrap(),
+            2 * token_amount
+        );
+    }
+    let escrow_info = new_token.get_account_info(&escrow_account).await.unwrap();
+    let shares = escrow_info.get_extension::<RebaseAccount>().unwrap().shares;
+    assert_eq!(u64::from(shares), 0);
+}
+
 #[test_case(spl_token::id(), spl_token_2022::id() ; "fail upgrade to token-2022")]
 #[tokio::test]
 async fn fail_incorrect_escrow_derivation(original_program_id: Pubkey, new_program_id: Pubkey) {
@@ -77,6 +77,9 @@ pub enum CommandName {
     CreateMultisig,
     Authorize,
     SetInterestRate,
+    Rebase,
+    RebaseHistory,
+    Convert,
     Transfer,
     Burn,
     Mint,
@@ -192,6 +195,7 @@ pub enum CliAuthorityType {
     GroupPointer,
     GroupMemberPointer,
     Group,
+    RebaseSupply,
 }
 impl TryFrom<CliAuthorityType> for AuthorityType {
     type Error = Error;
@@ -222,6 +226,7 @@ impl TryFrom<CliAuthorityType> for AuthorityType {
             CliAuthorityType::Group => {
                 Err("Group update authority does not map to a token authority type".into())
             }
+            CliAuthorityType::RebaseSupply => Ok(AuthorityType::RebaseSupply),
         }
     }
 }
@@ -322,6 +327,53 @@ fn is_multisig_minimum_signers(string: String) -> Result<(), String> {
     }
 }
 
+/// Parse a decimal ratio such as `1.0003` exactly, as a numerator over a
+/// power of ten
+pub fn parse_decimal_ratio(string: &str) -> Result<(u128, u128), String> {
+    const MAX_FRACTION_DIGITS: usize = 18;
+    let (whole, fraction) = string.split_once('.').unwrap_or((string, ""));
+    if (whole.is_empty() && fraction.is_empty())
+        || !whole
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return Err(format!("`{}` is not a decimal number", string));
+    }
+    if fraction.len() > MAX_FRACTION_DIGITS {
+        return Err(format!(
+            "must have at most {} decimal places",
+            MAX_FRACTION_DIGITS
+        ));
+    }
+    let numerator = format!("{}{}", whole, fraction)
+        .parse::<u128>()
+        .map_err(|e| e.to_string())?;
+    Ok((numerator, 10u128.pow(fraction.len() as u32)))
+}
+
+fn is_decimal_ratio(string: String) -> Result<(), String> {
+    parse_decimal_ratio(&string).map(|_| ())
+}
+
+/// Parse the 32-byte reference of a rebase, given as 64 hex digits
+pub fn parse_rebase_reference(string: &str) -> Result<[u8; 32], String> {
+    let mut reference = [0; 32];
+    if string.len() != 2 * reference.len() || !string.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("`{}` is not 64 hex digits", string));
+    }
+    for (byte, digits) in reference.iter_mut().zip(string.as_bytes().chunks_exact(2)) {
+        // only ASCII hex digits, checked above
+        let digits = std::str::from_utf8(digits).unwrap();
+        *byte = u8::from_str_radix(digits, 16).map_err(|e| e.to_string())?;
+    }
+    Ok(reference)
+}
+
+fn is_rebase_reference(string: String) -> Result<(), String> {
+    parse_rebase_reference(&string).map(|_| ())
+}
+
 fn is_valid_token_program_id<T>(string: T) -> Result<(), String>
 where
     T: AsRef<str> + fmt::Display,
@@ -788,6 +840,39 @@ pub fn app<'a, 'b>(
                         .takes_value(false)
                         .help("Enables group member configurations in the mint. The mint authority must initialize the member."),
                 )
+                .arg(
+                    Arg::with_name("enable_rebase")
+                        .long("enable-rebase")
+                        .takes_value(false)
+                        .help(
+                            "Enables rebasing the supply of the mint. Token accounts hold shares \
+                            of the supply, so a rebase scales every balance at once."
+                        ),
+                )
+                .arg(
+                    Arg::with_name("rebase_authority")
+                        .long("rebase-authority")
+                        .value_name("ADDRESS")
+                        .validator(is_valid_pubkey)
+                        .takes_value(true)
+                        .requires("enable_rebase")
+                        .help(
+                            "Specify the address that can rebase the supply. \
+                            Defaults to the mint authority."
+                        ),
+                )
+                .arg(
+                    Arg::with_name("rebase_initial_supply")
+                        .long("rebase-initial-supply")
+                        .value_name("AMOUNT")
+                        .validator(is_amount)
+                        .takes_value(true)
+                        .requires("enable_rebase")
+                        .help(
+                            "Specify the initial supply of the rebasing mint, in tokens. \
+                            Defaults to 0."
+                        ),
+                )
                 .nonce_args(true)
                 .arg(memo_arg())
         )
@@ -821,6 +906,226 @@ pub fn app<'a, 'b>(
                     )
                 )
         )
+        .subcommand(
+            SubCommand::with_name(CommandName::Rebase.into())
+                .about("Rebase the supply of a rebasing token")
+                .arg(
+                    Arg::with_name("token")
+                        .validator(is_valid_pubkey)
+                        .value_name("TOKEN_MINT_ADDRESS")
+                        .takes_value(true)
+                        .index(1)
+                        .required(true)
+                        .help("The rebasing token address"),
+                )
+                .arg(
+                    Arg::with_name("new_supply")
+                        .validator(is_amount)
+                        .value_name("NEW_SUPPLY")
+                        .takes_value(true)
+                        .index(2)
+                        .required_unless_one(&["ratio", "bps"])
+                        .help("The new total supply, in tokens"),
+                )
+                .arg(
+                    Arg::with_name("raw")
+                        .long("raw")
+                        .takes_value(false)
+                        .requires("new_supply")
+                        .help("Take the new supply in base units rather than tokens"),
+                )
+                .arg(
+                    Arg::with_name("ui")
+                        .long("ui")
+                        .takes_value(false)
+                        .requires("new_supply")
+                        .conflicts_with("raw")
+                        .help(
+                            "Send the new supply in tokens as typed, for the program \
+                            to convert with the mint's decimals"
+                        ),
+                )
+                .arg(
+                    Arg::with_name("ratio")
+                        .long("ratio")
+                        .validator(is_decimal_ratio)
+                        .value_name("RATIO")
+                        .takes_value(true)
+                        .conflicts_with_all(&["new_supply", "bps", SIGN_ONLY_ARG.name])
+                        .help("Multiply the current supply by this ratio, for example 1.0003"),
+                )
+                .arg(
+                    Arg::with_name("bps")
+                        .long("bps")
+                        .validator(is_parsable::<i64>)
+                        .value_name("BASIS_POINTS")
+                        .takes_value(true)
+                        .allow_hyphen_values(true)
+                        .conflicts_with_all(&["new_supply", SIGN_ONLY_ARG.name])
+                        .help("Change the current supply by this many basis points"),
+                )
+                .arg(
+                    Arg::with_name("yes")
+                        .long("yes")
+                        .takes_value(false)
+                        .help(
+                            "Skip the confirmation of the new supply computed from \
+                            --ratio or --bps"
+                        ),
+                )
+                .arg(
+                    Arg::with_name("dry_run")
+                        .long("dry-run")
+                        .takes_value(false)
+                        .conflicts_with(SIGN_ONLY_ARG.name)
+                        .help(
+                            "Simulate the rebase and print its outcome without \
+                            sending anything. Fails if the rebase would fail."
+                        ),
+                )
+                .arg(
+                    Arg::with_name("reason")
+                        .long("reason")
+                        .validator(is_parsable::<u8>)
+                        .value_name("REASON_CODE")
+                        .takes_value(true)
+                        .help(
+                            "Tag the rebase with this reason code, from 1 to 255. \
+                            Logged and kept in the rebase history, but not interpreted \
+                            by the program."
+                        ),
+                )
+                .arg(
+                    Arg::with_name("reference")
+                        .long("reference")
+                        .validator(is_rebase_reference)
+                        .value_name("HEX")
+                        .takes_value(true)
+                        .help(
+                            "Tag the rebase with a 32-byte reference, such as the hash \
+                            of a report, given as 64 hex digits"
+                        ),
+                )
+                .arg(
+                    Arg::with_name("valid_until")
+                        .long("valid-until")
+                        .validator(is_parsable::<i64>)
+                        .value_name("UNIX_TIMESTAMP")
+                        .takes_value(true)
+                        .help(
+                            "Make the rebase fail if it lands after this unix timestamp, \
+                            so that a rebase signed ahead of time can't be applied \
+                            long after"
+                        ),
+                )
+                .arg(
+                    Arg::with_name("rebase_authority")
+                        .long("rebase-authority")
+                        .alias("owner")
+                        .validator(is_valid_signer)
+                        .value_name("SIGNER")
+                        .takes_value(true)
+                        .help(
+                            "Specify the rebase authority keypair, or the address of \
+                            a multisig rebase authority along with its signers. \
+                            Defaults to the client keypair address."
+                        )
+                )
+                .arg(
+                    Arg::with_name("reserve_account")
+                        .long("reserve-account")
+                        .validator(is_valid_pubkey)
+                        .value_name("RESERVE_ACCOUNT_ADDRESS")
+                        .takes_value(true)
+                        .requires(SIGN_ONLY_ARG.name)
+                        .help(
+                            "Specify the reserve account of the mint, if it has one. \
+                            Only needed with --sign-only; otherwise it is read from the mint."
+                        )
+                )
+                .arg(
+                    Arg::with_name("update_metadata")
+                        .long("update-metadata")
+                        .takes_value(false)
+                        .requires(SIGN_ONLY_ARG.name)
+                        .help(
+                            "Have the fee payer pay for growing the mint's token-metadata, \
+                            which mints writing their exchange rate there require. \
+                            Only needed with --sign-only; otherwise it is read from the mint."
+                        )
+                )
+                .arg(
+                    Arg::with_name("co_signer")
+                        .long("co-signer")
+                        .validator(is_valid_signer)
+                        .value_name("SIGNER")
+                        .takes_value(true)
+                        .help(
+                            "Specify the co-signer keypair, which must sign alongside \
+                            the rebase authority if the mint has one"
+                        )
+                )
+                .arg(mint_decimals_arg())
+                .arg(multisig_signer_arg())
+                .nonce_args(true)
+                .offline_args_config(&SignOnlyNeedsMintDecimals{}),
+        )
+        .subcommand(
+            SubCommand::with_name(CommandName::RebaseHistory.into())
+                .about("List the past rebases of a rebasing token, newest first")
+                .arg(
+                    Arg::with_name("token")
+                        .validator(is_valid_pubkey)
+                        .value_name("TOKEN_MINT_ADDRESS")
+                        .takes_value(true)
+                        .index(1)
+                        .required(true)
+                        .help("The rebasing token address"),
+                )
+                .arg(
+                    Arg::with_name("limit")
+                        .long("limit")
+                        .validator(is_parsable::<usize>)
+                        .value_name("N")
+                        .takes_value(true)
+                        .default_value("10")
+                        .help("Maximum number of rebases to list"),
+                )
+        )
+        .subcommand(
+            SubCommand::with_name(CommandName::Convert.into())
+                .about("Convert between tokens and shares of a rebasing token at the current ratio")
+                .arg(
+                    Arg::with_name("token")
+                        .validator(is_valid_pubkey)
+                        .value_name("TOKEN_MINT_ADDRESS")
+                        .takes_value(true)
+                        .index(1)
+                        .required(true)
+                        .help("The rebasing token address"),
+                )
+                .arg(
+                    Arg::with_name("amount")
+                        .long("amount")
+                        .validator(is_amount)
+                        .value_name("TOKEN_AMOUNT")
+                        .takes_value(true)
+                        .help("Amount of tokens to convert to shares"),
+                )
+                .arg(
+                    Arg::with_name("shares")
+                        .long("shares")
+                        .validator(is_parsable::<u64>)
+                        .value_name("SHARES")
+                        .takes_value(true)
+                        .help("Number of shares to convert to tokens"),
+                )
+                .group(
+                    ArgGroup::with_name("quantity")
+                        .args(&["amount", "shares"])
+                        .required(true)
+                )
+        )
         .subcommand(
             SubCommand::with_name(CommandName::SetTransferHook.into())
                 .about("Set the transfer hook program id for a token")
@@ -1351,6 +1656,14 @@ pub fn app<'a, 'b>(
                         .help("Send tokens confidentially. Both sender and recipient accounts must \
                             be pre-configured for confidential transfers.")
                 )
+                .arg(
+                    Arg::with_name("shares")
+                        .long("shares")
+                        .takes_value(false)
+                        .conflicts_with_all(&["fund_recipient", "expected_fee", "confidential"])
+                        .help("Take the amount in shares of a rebasing token rather than in \
+                            tokens; accepts keyword ALL. The recipient token account must exist.")
+                )
                 .arg(multisig_signer_arg())
                 .arg(mint_decimals_arg())
                 .nonce_args(true)
@@ -1378,6 +1691,12 @@ pub fn app<'a, 'b>(
                         .required(true)
                         .help("Amount to burn, in tokens"),
                 )
+                .arg(
+                    Arg::with_name("shares")
+                        .long("shares")
+                        .takes_value(false)
+                        .help("Take the amount in shares of a rebasing token rather than in tokens")
+                )
                 .arg(owner_keypair_arg_with_value_name("TOKEN_OWNER_KEYPAIR")
                         .help(
                             "Specify the burnt token owner account. \
@@ -1412,6 +1731,12 @@ pub fn app<'a, 'b>(
                         .required(true)
                         .help("Amount to mint, in tokens"),
                 )
+                .arg(
+                    Arg::with_name("shares")
+                        .long("shares")
+                        .takes_value(false)
+                        .help("Take the amount in shares of a rebasing token rather than in tokens")
+                )
                 .arg(
                     Arg::with_name("recipient")
                         .validator(is_valid_pubkey)
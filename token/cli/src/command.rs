@@ -8,11 +8,16 @@ use {
         output::*,
         sort::{sort_and_parse_token_accounts, AccountFilter},
     },
+    base64::{prelude::BASE64_STANDARD, Engine},
     clap::{value_t, value_t_or_exit, ArgMatches},
     futures::try_join,
+    num_traits::FromPrimitive,
     serde::Serialize,
     solana_account_decoder::{
-        parse_token::{get_token_account_mint, parse_token, TokenAccountType, UiAccountState},
+        parse_token::{
+            get_token_account_mint, parse_token, token_amount_to_ui_amount, TokenAccountType,
+            UiAccountState,
+        },
         UiAccountData,
     },
     solana_clap_utils::{
@@ -23,18 +28,22 @@ use {
         return_signers_data, CliSignOnlyData, CliSignature, OutputFormat, QuietDisplay,
         ReturnSignersConfig, VerboseDisplay,
     },
-    solana_client::rpc_request::TokenAccountsFilter,
+    solana_client::{client_error::ClientError, rpc_request::TokenAccountsFilter},
     solana_remote_wallet::remote_wallet::RemoteWalletManager,
     solana_sdk::{
-        instruction::AccountMeta,
+        instruction::{AccountMeta, InstructionError},
         native_token::*,
+        program_error::ProgramError,
         program_option::COption,
+        program_pack::Pack,
         pubkey::Pubkey,
         signature::{Keypair, Signer},
         system_program,
+        transaction::TransactionError,
     },
     spl_associated_token_account::get_associated_token_address_with_program_id,
     spl_token_2022::{
+        error::TokenError,
         extension::{
             confidential_transfer::{
                 account_info::{
@@ -53,9 +62,13 @@ use {
             metadata_pointer::MetadataPointer,
             mint_close_authority::MintCloseAuthority,
             permanent_delegate::PermanentDelegate,
+            rebase_mint::{
+                self, try_ui_supply_into_supply, ui::UiRebaseMintConfig, LazyRebaseAccount,
+                RebaseAccount, RebaseMintConfig, RebasePreview,
+            },
             transfer_fee::{TransferFeeAmount, TransferFeeConfig},
             transfer_hook::TransferHook,
-            BaseStateWithExtensions, ExtensionType, StateWithExtensionsOwned,
+            BaseStateWithExtensions, ExtensionType, StateWithExtensions, StateWithExtensionsOwned,
         },
         solana_zk_token_sdk::{
             encryption::{
@@ -64,15 +77,26 @@ use {
             },
             zk_token_elgamal::pod::ElGamalPubkey,
         },
-        state::{Account, AccountState, Mint},
+        state::{Account, AccountState, Mint, Multisig},
     },
     spl_token_client::{
         client::{ProgramRpcClientSendTransaction, RpcClientResponse},
-        token::{ExtensionInitializationParams, Token},
+        token::{
+            ExtensionInitializationParams, RebaseSupplyAccounts, Token,
+            TokenError as TokenClientError,
+        },
     },
     spl_token_group_interface::state::TokenGroup,
     spl_token_metadata_interface::state::{Field, TokenMetadata},
-    std::{collections::HashMap, fmt::Display, process::exit, rc::Rc, str::FromStr, sync::Arc},
+    std::{
+        collections::HashMap,
+        fmt::Display,
+        io::{self, Write},
+        process::exit,
+        rc::Rc,
+        str::FromStr,
+        sync::Arc,
+    },
 };
 
 fn print_error_and_exit<T, E: Display>(e: E) -> T {
@@ -208,6 +232,7 @@ async fn command_create_token(
     enable_metadata: bool,
     enable_group: bool,
     enable_member: bool,
+    rebase: Option<(Pubkey, f64)>,
     bulk_signers: Vec<Arc<dyn Signer>>,
 ) -> CommandResult {
     println_display(
@@ -335,6 +360,19 @@ async fn command_create_token(
         });
     }
 
+    let rebase = rebase.map(|(supply_authority, ui_initial_supply)| {
+        (
+            supply_authority,
+            spl_token::ui_amount_to_amount(ui_initial_supply, decimals),
+        )
+    });
+    if let Some((supply_authority, initial_supply)) = rebase {
+        extensions.push(ExtensionInitializationParams::RebaseMintConfig {
+            supply_authority: Some(supply_authority),
+            initial_supply,
+        });
+    }
+
     let res = token
         .create_mint(
             &authority,
@@ -380,6 +418,10 @@ async fn command_create_token(
             CliCreateToken {
                 address: token_pubkey.to_string(),
                 decimals,
+                rebase_authority: rebase.map(|(supply_authority, _)| supply_authority.to_string()),
+                rebase_initial_supply: rebase.map(|(_, initial_supply)| {
+                    spl_token_2022::amount_to_ui_amount_string_trimmed(initial_supply, decimals)
+                }),
                 transaction_data: cli_signature,
             },
             &CommandName::CreateToken,
@@ -449,6 +491,707 @@ async fn command_set_interest_rate(
     })
 }
 
+async fn get_rebase_mint_config(
+    token: &Token<ProgramRpcClientSendTransaction>,
+) -> Result<RebaseMintConfig, Error> {
+    token
+        .get_rebase_mint_config()
+        .await
+        .map_err(|err| match err {
+            TokenClientError::MissingRebaseMintConfig => {
+                format!("Mint {} is not a rebasing mint", token.get_address()).into()
+            }
+            err => Error::from(err),
+        })
+}
+
+/// Name the token program error behind a failed rebase, when there is one,
+/// since the RPC error only carries its code
+fn rebase_error(err: TokenClientError) -> Error {
+    if let TokenClientError::Rebase(token_error) = &err {
+        return format!("Rebase failed with {:?}: {}", token_error, token_error).into();
+    }
+    if let TokenClientError::Client(client_error) = &err {
+        if let Some(TransactionError::InstructionError(_, InstructionError::Custom(code))) =
+            client_error
+                .downcast_ref::<ClientError>()
+                .and_then(|client_error| client_error.get_transaction_error())
+        {
+            if let Some(token_error) = TokenError::from_u32(code) {
+                return format!("Rebase failed with {:?}: {}", token_error, token_error).into();
+            }
+        }
+    }
+    err.into()
+}
+
+/// Change in the ratio of tokens to shares, in basis points, or none if
+/// there was no ratio to begin with
+fn ratio_change_bps(old_config: &RebaseMintConfig, new_config: &RebaseMintConfig) -> Option<i64> {
+    let old_ratio = UiRebaseMintConfig::from(old_config).current_ratio?;
+    let new_ratio = UiRebaseMintConfig::from(new_config).current_ratio?;
+    (old_ratio != 0.0).then(|| ((new_ratio / old_ratio - 1.0) * 10_000.0).round() as i64)
+}
+
+fn rebase_preview_error(err: ProgramError) -> Error {
+    let token_error = match err {
+        ProgramError::Custom(code) => TokenError::from_u32(code),
+        _ => None,
+    };
+    match token_error {
+        Some(token_error) => {
+            format!("Rebase would fail with {:?}: {}", token_error, token_error).into()
+        }
+        None => format!("Rebase would fail: {}", err).into(),
+    }
+}
+
+/// Run `PreviewRebaseSupply` through `simulateTransaction`, or return none if
+/// the client can't simulate
+async fn simulate_rebase_preview(
+    token: &Token<ProgramRpcClientSendTransaction>,
+    mint_info: &MintInfo,
+    old_config: &RebaseMintConfig,
+    new_supply: u64,
+) -> Result<Option<RebasePreview>, Error> {
+    let reserve_account = Option::<Pubkey>::from(old_config.reserve_account);
+    let instruction = rebase_mint::instruction::preview_rebase_supply(
+        &mint_info.program_id,
+        &mint_info.address,
+        reserve_account.as_ref(),
+        new_supply,
+    )?;
+    let Ok(RpcClientResponse::Simulation(simulation)) = token
+        .simulate_ixs(&[instruction], &BulkSigners::default())
+        .await
+    else {
+        return Ok(None);
+    };
+    match simulation.err {
+        Some(TransactionError::InstructionError(_, InstructionError::Custom(code))) => {
+            return Err(rebase_preview_error(ProgramError::Custom(code)));
+        }
+        Some(err) => return Err(format!("Rebase would fail: {}", err).into()),
+        None => {}
+    }
+    let data = simulation
+        .return_data
+        .and_then(|return_data| BASE64_STANDARD.decode(return_data.data.0).ok())
+        .ok_or("Simulation did not return a preview")?;
+    let preview = bytemuck::try_from_bytes::<RebasePreview>(&data)
+        .map_err(|_| "Could not deserialize the rebase preview")?;
+    Ok(Some(*preview))
+}
+
+async fn command_rebase_preview(
+    config: &Config<'_>,
+    token: &Token<ProgramRpcClientSendTransaction>,
+    mint_info: MintInfo,
+    old_config: RebaseMintConfig,
+    new_supply: u64,
+) -> CommandResult {
+    let simulated_preview =
+        simulate_rebase_preview(token, &mint_info, &old_config, new_supply).await?;
+    let simulated = simulated_preview.is_some();
+    let preview = match simulated_preview {
+        Some(preview) => preview,
+        // can't check the reserve or the time of the first rebase
+        None => {
+            let (preview, _) = token
+                .preview_rebase_supply(new_supply)
+                .await
+                .map_err(|err| match err {
+                    TokenClientError::Program(err) => rebase_preview_error(err),
+                    err => Error::from(err),
+                })?;
+            preview
+        }
+    };
+    let mut new_config = old_config;
+    new_config.apply_rebase_with_fee(&preview)?;
+
+    Ok(format_output(
+        CliRebasePreview {
+            address: mint_info.address.to_string(),
+            old_supply: token_amount_to_ui_amount(
+                old_config.total_supply.into(),
+                mint_info.decimals,
+            ),
+            new_supply: token_amount_to_ui_amount(preview.new_supply.into(), mint_info.decimals),
+            old_shares: u64::from(old_config.total_shares).to_string(),
+            new_shares: u64::from(preview.total_shares).to_string(),
+            ratio_change_bps: ratio_change_bps(&old_config, &new_config),
+            rounding_error: u64::from(preview.rounding_error).to_string(),
+            simulated,
+        },
+        &CommandName::Rebase,
+        config,
+    ))
+}
+
+/// Check that enough members of `authority` sign, if it is a multisig, so
+/// that a transaction bound to fail isn't sent
+async fn check_multisig_signers(config: &Config<'_>, authority: &Pubkey) -> Result<(), Error> {
+    if config.multisigner_pubkeys.is_empty() {
+        return Ok(());
+    }
+    let account = config.get_account_checked(authority).await?;
+    let multisig = Multisig::unpack(&account.data)
+        .map_err(|_| format!("Authority {} is not a multisig", authority))?;
+    let members = &multisig.signers[..multisig.n as usize];
+    let signatures = members
+        .iter()
+        .filter(|member| config.multisigner_pubkeys.contains(member))
+        .count();
+    if signatures < multisig.m as usize {
+        return Err(format!(
+            "Multisig {} requires {} signatures, but {} of its members sign",
+            authority, multisig.m, signatures
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Supply to rebase to
+enum RebaseTarget {
+    /// Absolute supply, in base units
+    Supply(u64),
+    /// Absolute supply in tokens, sent as typed for the program to convert,
+    /// along with the same conversion done locally
+    UiSupply { ui_supply: String, new_supply: u64 },
+    /// Multiple of the current supply, as an exact fraction
+    Ratio { numerator: u128, denominator: u128 },
+}
+
+impl RebaseTarget {
+    fn from_bps(bps: i64) -> Result<Self, Error> {
+        let numerator = u128::try_from(10_000 + i128::from(bps))
+            .map_err(|_| format!("Cannot change the supply by {}bps", bps))?;
+        Ok(Self::Ratio {
+            numerator,
+            denominator: 10_000,
+        })
+    }
+
+    /// New supply in base units, rounding down
+    fn new_supply(&self, current_supply: u64) -> Result<u64, Error> {
+        match self {
+            Self::Supply(new_supply) | Self::UiSupply { new_supply, .. } => Ok(*new_supply),
+            Self::Ratio {
+                numerator,
+                denominator,
+            } => (current_supply as u128)
+                .checked_mul(*numerator)
+                .map(|product| product / denominator)
+                .and_then(|new_supply| u64::try_from(new_supply).ok())
+                .ok_or_else(|| "New supply is too large".into()),
+        }
+    }
+}
+
+fn confirm(prompt: &str) -> Result<bool, Error> {
+    eprint!("{} [y/N] ", prompt);
+    io::stderr().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn command_rebase(
+    config: &Config<'_>,
+    mint_info: MintInfo,
+    rebase_authority: Pubkey,
+    reserve_account: Option<Pubkey>,
+    metadata_payer: Option<Pubkey>,
+    co_signer: Option<Pubkey>,
+    target: RebaseTarget,
+    reason_code: u8,
+    reference: [u8; 32],
+    not_after_unix_timestamp: i64,
+    dry_run: bool,
+    skip_confirmation: bool,
+    bulk_signers: BulkSigners,
+) -> CommandResult {
+    let token = token_client_from_config(config, &mint_info.address, Some(mint_info.decimals))?;
+    if config.sign_only {
+        // the mint can't be read, so nothing can be previewed or reported
+        let accounts = RebaseSupplyAccounts {
+            reserve_account,
+            metadata_payer,
+            co_signer,
+            ..RebaseSupplyAccounts::default()
+        };
+        let res = match target {
+            RebaseTarget::Supply(new_supply) => {
+                token
+                    .rebase_supply_with_deadline(
+                        &rebase_authority,
+                        &accounts,
+                        new_supply,
+                        reason_code,
+                        reference,
+                        not_after_unix_timestamp,
+                        &bulk_signers,
+                    )
+                    .await?
+            }
+            RebaseTarget::UiSupply { ui_supply, .. } => {
+                token
+                    .rebase_supply_ui(
+                        &rebase_authority,
+                        &accounts,
+                        &ui_supply,
+                        reason_code,
+                        reference,
+                        not_after_unix_timestamp,
+                        &bulk_signers,
+                    )
+                    .await?
+            }
+            RebaseTarget::Ratio { .. } => {
+                return Err("Signing offline needs the new supply itself".into());
+            }
+        };
+        let tx_return = finish_tx(config, &res, false).await?;
+        return Ok(match tx_return {
+            TransactionReturnData::CliSignature(signature) => {
+                config.output_format.formatted_string(&signature)
+            }
+            TransactionReturnData::CliSignOnlyData(sign_only_data) => {
+                config.output_format.formatted_string(&sign_only_data)
+            }
+        });
+    }
+
+    let old_config = get_rebase_mint_config(&token).await?;
+    let new_supply = target.new_supply(old_config.total_supply.into())?;
+
+    if dry_run {
+        return command_rebase_preview(config, &token, mint_info, old_config, new_supply).await;
+    }
+    check_multisig_signers(config, &rebase_authority).await?;
+    let accounts = RebaseSupplyAccounts::from_config(&old_config, &config.fee_payer()?.pubkey());
+    if let Some(mint_co_signer) = accounts.co_signer {
+        if co_signer != Some(mint_co_signer) {
+            return Err(format!(
+                "Mint {} requires its co-signer {} to sign, with --co-signer",
+                mint_info.address, mint_co_signer
+            )
+            .into());
+        }
+    }
+
+    let summary = format!(
+        "Rebasing supply of {} from {} to {}",
+        mint_info.address,
+        spl_token::amount_to_ui_amount_string_trimmed(
+            u64::from(old_config.total_supply),
+            mint_info.decimals
+        ),
+        spl_token::amount_to_ui_amount_string_trimmed(new_supply, mint_info.decimals),
+    );
+    // a relative target is only worked out here, so it gets confirmed
+    if matches!(target, RebaseTarget::Ratio { .. }) && !skip_confirmation {
+        if !confirm(&format!("{}. Continue?", summary))? {
+            return Err("Rebase cancelled".into());
+        }
+    } else {
+        println_display(config, summary);
+    }
+
+    let res = match &target {
+        RebaseTarget::UiSupply { ui_supply, .. } => {
+            token
+                .rebase_supply_ui(
+                    &rebase_authority,
+                    &accounts,
+                    ui_supply,
+                    reason_code,
+                    reference,
+                    not_after_unix_timestamp,
+                    &bulk_signers,
+                )
+                .await
+        }
+        _ => {
+            token
+                .rebase_supply_with_deadline(
+                    &rebase_authority,
+                    &accounts,
+                    new_supply,
+                    reason_code,
+                    reference,
+                    not_after_unix_timestamp,
+                    &bulk_signers,
+                )
+                .await
+        }
+    }
+    .map_err(rebase_error)?;
+
+    let tx_return = finish_tx(config, &res, false).await?;
+    Ok(match tx_return {
+        TransactionReturnData::CliSignature(signature) => {
+            let new_config = token.get_rebase_mint_config().await?;
+            format_output(
+                CliRebase {
+                    address: mint_info.address.to_string(),
+                    old_supply: token_amount_to_ui_amount(
+                        old_config.total_supply.into(),
+                        mint_info.decimals,
+                    ),
+                    new_supply: token_amount_to_ui_amount(
+                        new_config.total_supply.into(),
+                        mint_info.decimals,
+                    ),
+                    old_shares: u64::from(old_config.total_shares).to_string(),
+                    new_shares: u64::from(new_config.total_shares).to_string(),
+                    transaction_data: signature,
+                },
+                &CommandName::Rebase,
+                config,
+            )
+        }
+        TransactionReturnData::CliSignOnlyData(sign_only_data) => {
+            config.output_format.formatted_string(&sign_only_data)
+        }
+    })
+}
+
+async fn command_rebase_history(
+    config: &Config<'_>,
+    token_pubkey: Pubkey,
+    limit: usize,
+) -> CommandResult {
+    let mint_info = config.get_mint_info(&token_pubkey, None).await?;
+    let token = token_client_from_config(config, &token_pubkey, Some(mint_info.decimals))?;
+    get_rebase_mint_config(&token).await?;
+
+    let rebases = token
+        .get_rebase_records(limit)
+        .await?
+        .into_iter()
+        .map(|record| CliRebaseHistoryEntry {
+            signature: record.signature.map(|signature| signature.to_string()),
+            unix_timestamp: record.unix_timestamp,
+            old_supply: token_amount_to_ui_amount(record.old_supply, mint_info.decimals),
+            new_supply: token_amount_to_ui_amount(record.new_supply, mint_info.decimals),
+            total_shares: record.total_shares.to_string(),
+        })
+        .collect();
+
+    let cli_rebase_history = CliRebaseHistory {
+        address: token_pubkey.to_string(),
+        rebases,
+    };
+    Ok(config.output_format.formatted_string(&cli_rebase_history))
+}
+
+async fn command_convert(
+    config: &Config<'_>,
+    token_pubkey: Pubkey,
+    ui_amount: Option<f64>,
+    shares: Option<u64>,
+) -> CommandResult {
+    let mint_info = config.get_mint_info(&token_pubkey, None).await?;
+    let token = token_client_from_config(config, &token_pubkey, Some(mint_info.decimals))?;
+    let rebase_config = get_rebase_mint_config(&token).await?;
+
+    // rounding down, like `ConvertAmountToShares` and `ConvertSharesToAmount`
+    let (amount, shares) = match (ui_amount, shares) {
+        (Some(ui_amount), _) => {
+            let amount = spl_token::ui_amount_to_amount(ui_amount, mint_info.decimals);
+            let shares = rebase_config
+                .amount_to_shares(amount)
+                .ok_or("Amount is too large to convert")?;
+            (amount, shares)
+        }
+        (None, Some(shares)) => {
+            let amount = rebase_config
+                .shares_to_amount(shares)
+                .ok_or("Shares are too many to convert")?;
+            (amount, shares)
+        }
+        (None, None) => unreachable!(),
+    };
+
+    let cli_conversion = CliShareConversion {
+        address: token_pubkey.to_string(),
+        amount: token_amount_to_ui_amount(amount, mint_info.decimals),
+        shares: shares.to_string(),
+    };
+    Ok(config.output_format.formatted_string(&cli_conversion))
+}
+
+fn parse_shares(shares: &str) -> Result<u64, Error> {
+    shares
+        .parse::<u64>()
+        .map_err(|_| format!("Shares must be a whole number, not {}", shares).into())
+}
+
+/// Describe the tokens that shares are worth, if the ratio is known
+fn shares_worth(rebase_config: Option<&RebaseMintConfig>, shares: u64, decimals: u8) -> String {
+    rebase_config
+        .and_then(|rebase_config| rebase_config.shares_to_amount(shares))
+        .map(|amount| {
+            format!(
+                " ({} tokens)",
+                spl_token_2022::amount_to_ui_amount_string_trimmed(amount, decimals)
+            )
+        })
+        .unwrap_or_default()
+}
+
+/// Report a transaction that moved shares along with the tokens they were
+/// worth, which is only known when the transaction wasn't signed offline
+fn format_share_output(
+    config: &Config,
+    tx_return: TransactionReturnData,
+    command_name: &CommandName,
+    mint_info: &MintInfo,
+    shares: u64,
+    rebase_config: Option<&RebaseMintConfig>,
+) -> String {
+    match (tx_return, rebase_config) {
+        (TransactionReturnData::CliSignature(signature), Some(rebase_config)) => format_output(
+            CliShareTransaction {
+                address: mint_info.address.to_string(),
+                shares: shares.to_string(),
+                amount: token_amount_to_ui_amount(
+                    rebase_config.shares_to_amount(shares).unwrap_or_default(),
+                    mint_info.decimals,
+                ),
+                transaction_data: signature,
+            },
+            command_name,
+            config,
+        ),
+        (TransactionReturnData::CliSignature(signature), None) => {
+            config.output_format.formatted_string(&signature)
+        }
+        (TransactionReturnData::CliSignOnlyData(sign_only_data), _) => {
+            config.output_format.formatted_string(&sign_only_data)
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn command_transfer_shares(
+    config: &Config<'_>,
+    token_pubkey: Pubkey,
+    shares: Option<u64>,
+    recipient: Pubkey,
+    sender: Option<Pubkey>,
+    sender_owner: Pubkey,
+    mint_decimals: Option<u8>,
+    no_recipient_is_ata_owner: bool,
+    use_unchecked_instruction: bool,
+    memo: Option<String>,
+    bulk_signers: BulkSigners,
+    no_wait: bool,
+    transfer_hook_accounts: Option<Vec<AccountMeta>>,
+) -> CommandResult {
+    let mint_info = config.get_mint_info(&token_pubkey, mint_decimals).await?;
+    let decimals = if use_unchecked_instruction {
+        None
+    } else {
+        Some(mint_info.decimals)
+    };
+    let token = if let Some(transfer_hook_accounts) = transfer_hook_accounts {
+        token_client_from_config(config, &token_pubkey, decimals)?
+            .with_transfer_hook_accounts(transfer_hook_accounts)
+    } else {
+        token_client_from_config(config, &token_pubkey, decimals)?
+    };
+
+    let sender = if let Some(sender) = sender {
+        sender
+    } else {
+        token.get_associated_token_address(&sender_owner)
+    };
+
+    let (shares, recipient_token_account, rebase_config) = if !config.sign_only {
+        let rebase_config = get_rebase_mint_config(&token).await?;
+        let sender_account = token.get_account_info(&sender).await?;
+        let sender_shares = u64::from(
+            sender_account
+                .get_extension::<RebaseAccount>()
+                .map_err(|_| format!("Account {} holds no shares", sender))?
+                .shares,
+        );
+        let shares = shares.unwrap_or(sender_shares);
+        if shares > sender_shares {
+            return Err(format!(
+                "Error: Sender has insufficient shares, current shares are {}",
+                sender_shares
+            )
+            .into());
+        }
+
+        // shares are only sent to accounts that exist
+        let recipient_is_token_account = config
+            .program_client
+            .get_account(recipient)
+            .await?
+            .filter(|account| account.owner == config.program_id)
+            .map(|account| StateWithExtensionsOwned::<Account>::unpack(account.data).is_ok())
+            .unwrap_or(false);
+        let recipient_token_account = if recipient_is_token_account {
+            recipient
+        } else {
+            token.get_associated_token_address(&recipient)
+        };
+        config
+            .check_account(&recipient_token_account, Some(token_pubkey))
+            .await?;
+
+        println_display(
+            config,
+            format!(
+                "Transfer {} shares ({} tokens)\n  Sender: {}\n  Recipient: {}",
+                shares,
+                spl_token_2022::amount_to_ui_amount_string_trimmed(
+                    rebase_config.shares_to_amount(shares).unwrap_or_default(),
+                    mint_info.decimals
+                ),
+                sender,
+                recipient_token_account
+            ),
+        );
+        (shares, recipient_token_account, Some(rebase_config))
+    } else {
+        let recipient_token_account = if no_recipient_is_ata_owner {
+            recipient
+        } else {
+            token.get_associated_token_address(&recipient)
+        };
+        let shares = shares.ok_or("Signing offline needs the number of shares to transfer")?;
+        (shares, recipient_token_account, None)
+    };
+
+    if let Some(text) = memo {
+        token.with_memo(text, vec![config.default_signer()?.pubkey()]);
+    }
+
+    let res = token
+        .transfer_shares(
+            &sender,
+            &recipient_token_account,
+            &sender_owner,
+            shares,
+            &bulk_signers,
+        )
+        .await?;
+
+    let tx_return = finish_tx(config, &res, no_wait).await?;
+    Ok(format_share_output(
+        config,
+        tx_return,
+        &CommandName::Transfer,
+        &mint_info,
+        shares,
+        rebase_config.as_ref(),
+    ))
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn command_mint_shares(
+    config: &Config<'_>,
+    token: Pubkey,
+    shares: u64,
+    recipient: Pubkey,
+    mint_info: MintInfo,
+    mint_authority: Pubkey,
+    memo: Option<String>,
+    bulk_signers: BulkSigners,
+) -> CommandResult {
+    let token_client =
+        token_client_from_config(config, &mint_info.address, Some(mint_info.decimals))?;
+    let rebase_config = if !config.sign_only {
+        Some(get_rebase_mint_config(&token_client).await?)
+    } else {
+        None
+    };
+    println_display(
+        config,
+        format!(
+            "Minting {} shares{}\n  Token: {}\n  Recipient: {}",
+            shares,
+            shares_worth(rebase_config.as_ref(), shares, mint_info.decimals),
+            token,
+            recipient
+        ),
+    );
+
+    if let Some(text) = memo {
+        token_client.with_memo(text, vec![config.default_signer()?.pubkey()]);
+    }
+
+    let res = token_client
+        .mint_to_shares(&recipient, &mint_authority, shares, &bulk_signers)
+        .await?;
+
+    let tx_return = finish_tx(config, &res, false).await?;
+    Ok(format_share_output(
+        config,
+        tx_return,
+        &CommandName::Mint,
+        &mint_info,
+        shares,
+        rebase_config.as_ref(),
+    ))
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn command_burn_shares(
+    config: &Config<'_>,
+    account: Pubkey,
+    owner: Pubkey,
+    shares: u64,
+    mint_address: Option<Pubkey>,
+    mint_decimals: Option<u8>,
+    memo: Option<String>,
+    bulk_signers: BulkSigners,
+) -> CommandResult {
+    let mint_address = config.check_account(&account, mint_address).await?;
+    let mint_info = config.get_mint_info(&mint_address, mint_decimals).await?;
+    let token = token_client_from_config(config, &mint_info.address, Some(mint_info.decimals))?;
+    let rebase_config = if !config.sign_only {
+        Some(get_rebase_mint_config(&token).await?)
+    } else {
+        None
+    };
+    println_display(
+        config,
+        format!(
+            "Burn {} shares{}\n  Source: {}",
+            shares,
+            shares_worth(rebase_config.as_ref(), shares, mint_info.decimals),
+            account
+        ),
+    );
+
+    if let Some(text) = memo {
+        token.with_memo(text, vec![config.default_signer()?.pubkey()]);
+    }
+
+    let res = token
+        .burn_shares(&account, &owner, shares, &bulk_signers)
+        .await?;
+
+    let tx_return = finish_tx(config, &res, false).await?;
+    Ok(format_share_output(
+        config,
+        tx_return,
+        &CommandName::Burn,
+        &mint_info,
+        shares,
+        rebase_config.as_ref(),
+    ))
+}
+
 async fn command_set_transfer_hook_program(
     config: &Config<'_>,
     token_pubkey: Pubkey,
@@ -1024,6 +1767,13 @@ async fn command_authorize(
                         Err(format!("Mint `{}` does not support token groups", account))
                     }
                 }
+                CliAuthorityType::RebaseSupply => {
+                    if let Ok(extension) = mint.get_extension::<RebaseMintConfig>() {
+                        Ok(Option::<Pubkey>::from(extension.supply_authority))
+                    } else {
+                        Err(format!("Mint `{}` is not a rebasing mint", account))
+                    }
+                }
             }?;
 
             Ok((account, previous_authority))
@@ -1065,7 +1815,8 @@ async fn command_authorize(
                 | CliAuthorityType::Metadata
                 | CliAuthorityType::GroupPointer
                 | CliAuthorityType::Group
-                | CliAuthorityType::GroupMemberPointer => Err(format!(
+                | CliAuthorityType::GroupMemberPointer
+                | CliAuthorityType::RebaseSupply => Err(format!(
                     "Authority type `{auth_str}` not supported for SPL Token accounts",
                 )),
                 CliAuthorityType::Owner => {
@@ -2140,6 +2891,54 @@ async fn command_close_mint(
 }
 
 async fn command_balance(config: &Config<'_>, address: Pubkey) -> CommandResult {
+    // the stored amount of a rebasing account is stale, so it's worked out
+    // from the shares, or the rebase index of a lazy account, instead
+    let rebasing_account = config
+        .rpc_client
+        .get_account(&address)
+        .await
+        .ok()
+        .filter(|account| account.owner == spl_token_2022::id())
+        .and_then(|account| StateWithExtensionsOwned::<Account>::unpack(account.data).ok())
+        .filter(|account| {
+            account.get_extension::<RebaseAccount>().is_ok()
+                || account.get_extension::<LazyRebaseAccount>().is_ok()
+        });
+    if let Some(account) = rebasing_account {
+        let mint_account = config.get_account_checked(&account.base.mint).await?;
+        let mint = StateWithExtensionsOwned::<Mint>::unpack(mint_account.data)
+            .map_err(|_| format!("Could not find mint account {}", account.base.mint))?;
+        let rebase_config = mint.get_extension::<RebaseMintConfig>()?;
+        let (amount, shares) =
+            if let Ok(lazy_account) = account.get_extension::<LazyRebaseAccount>() {
+                let amount = rebase_config
+                    .lazy_amount(
+                        account.base.amount,
+                        u128::from(lazy_account.last_seen_rebase_index),
+                    )
+                    .ok_or_else(|| format!("Balance of {} overflows", address))?;
+                (amount, None)
+            } else {
+                let rebase_account = account.get_extension::<RebaseAccount>()?;
+                if rebase_account.is_exempt() {
+                    (account.base.amount, None)
+                } else {
+                    let shares = u64::from(rebase_account.shares);
+                    let amount = rebase_config
+                        .shares_to_amount(shares)
+                        .ok_or_else(|| format!("Balance of {} overflows", address))?;
+                    (amount, Some(shares.to_string()))
+                }
+            };
+        let cli_rebased_token_amount = CliRebasedTokenAmount {
+            amount: token_amount_to_ui_amount(amount, mint.base.decimals),
+            shares,
+        };
+        return Ok(config
+            .output_format
+            .formatted_string(&cli_rebased_token_amount));
+    }
+
     let balance = config
         .rpc_client
         .get_token_account_balance(&address)
@@ -2150,6 +2949,29 @@ async fn command_balance(config: &Config<'_>, address: Pubkey) -> CommandResult
 }
 
 async fn command_supply(config: &Config<'_>, token: Pubkey) -> CommandResult {
+    let rebasing_mint = config
+        .rpc_client
+        .get_account(&token)
+        .await
+        .ok()
+        .filter(|account| account.owner == spl_token_2022::id())
+        .and_then(|account| StateWithExtensionsOwned::<Mint>::unpack(account.data).ok())
+        .filter(|mint| mint.get_extension::<RebaseMintConfig>().is_ok());
+    if let Some(mint) = rebasing_mint {
+        let rebase_config = mint.get_extension::<RebaseMintConfig>()?;
+        let total_supply = u64::from(rebase_config.total_supply);
+        let in_sync = mint.base.supply == total_supply;
+        let cli_rebased_supply = CliRebasedSupply {
+            amount: token_amount_to_ui_amount(total_supply, mint.base.decimals),
+            total_shares: u64::from(rebase_config.total_shares).to_string(),
+            current_ratio: UiRebaseMintConfig::from(rebase_config).current_ratio,
+            mint_supply_in_sync: in_sync,
+            mint_supply: (!in_sync)
+                .then(|| token_amount_to_ui_amount(mint.base.supply, mint.base.decimals)),
+        };
+        return Ok(config.output_format.formatted_string(&cli_rebased_supply));
+    }
+
     let supply = config.rpc_client.get_token_supply(&token).await?;
     let cli_token_amount = CliTokenAmount { amount: supply };
     Ok(config.output_format.formatted_string(&cli_token_amount))
@@ -2264,11 +3086,21 @@ async fn command_display(config: &Config<'_>, address: Pubkey) -> CommandResult
         }
         Ok(TokenAccountType::Mint(mint)) => {
             let epoch_info = config.rpc_client.get_epoch_info().await?;
+            // not known to the account decoder, so parsed here
+            let rebase = StateWithExtensions::<Mint>::unpack(&account_data.data)
+                .ok()
+                .and_then(|state| {
+                    state
+                        .get_extension::<RebaseMintConfig>()
+                        .ok()
+                        .map(UiRebaseMintConfig::from)
+                });
             let cli_output = CliMint {
                 address: address.to_string(),
                 epoch: epoch_info.epoch,
                 program_id: config.program_id.to_string(),
                 mint,
+                rebase,
             };
 
             Ok(config.output_format.formatted_string(&cli_output))
@@ -3412,6 +4244,17 @@ pub async fn process_command<'a>(
             let metadata_address = value_t!(arg_matches, "metadata_address", Pubkey).ok();
             let group_address = value_t!(arg_matches, "group_address", Pubkey).ok();
             let member_address = value_t!(arg_matches, "member_address", Pubkey).ok();
+            let rebase = if arg_matches.is_present("enable_rebase") {
+                let rebase_authority =
+                    pubkey_of_signer(arg_matches, "rebase_authority", &mut wallet_manager)
+                        .unwrap()
+                        .unwrap_or(mint_authority);
+                let ui_initial_supply =
+                    value_t!(arg_matches, "rebase_initial_supply", f64).unwrap_or(0.0);
+                Some((rebase_authority, ui_initial_supply))
+            } else {
+                None
+            };
 
             let transfer_fee = arg_matches.values_of("transfer_fee").map(|mut v| {
                 (
@@ -3466,6 +4309,7 @@ pub async fn process_command<'a>(
                 arg_matches.is_present("enable_metadata"),
                 arg_matches.is_present("enable_group"),
                 arg_matches.is_present("enable_member"),
+                rebase,
                 bulk_signers,
             )
             .await
@@ -3488,6 +4332,102 @@ pub async fn process_command<'a>(
             )
             .await
         }
+        (CommandName::Rebase, arg_matches) => {
+            let (rebase_authority_signer, rebase_authority) =
+                config.signer_or_default(arg_matches, "rebase_authority", &mut wallet_manager);
+            if config.multisigner_pubkeys.is_empty() {
+                push_signer_with_dedup(rebase_authority_signer, &mut bulk_signers);
+            }
+
+            let token_pubkey = pubkey_of_signer(arg_matches, "token", &mut wallet_manager)
+                .unwrap()
+                .unwrap();
+            let mint_decimals = value_of::<u8>(arg_matches, MINT_DECIMALS_ARG.name);
+            let mint_info = config.get_mint_info(&token_pubkey, mint_decimals).await?;
+            let reserve_account =
+                pubkey_of_signer(arg_matches, "reserve_account", &mut wallet_manager).unwrap();
+            let metadata_payer = if arg_matches.is_present("update_metadata") {
+                Some(config.fee_payer()?.pubkey())
+            } else {
+                None
+            };
+            let co_signer = get_signer(arg_matches, "co_signer", &mut wallet_manager).map(
+                |(co_signer_signer, co_signer)| {
+                    push_signer_with_dedup(co_signer_signer, &mut bulk_signers);
+                    co_signer
+                },
+            );
+            let target = if let Some(ratio) = arg_matches.value_of("ratio") {
+                let (numerator, denominator) = parse_decimal_ratio(ratio)?;
+                RebaseTarget::Ratio {
+                    numerator,
+                    denominator,
+                }
+            } else if arg_matches.is_present("bps") {
+                RebaseTarget::from_bps(value_t_or_exit!(arg_matches, "bps", i64))?
+            } else if arg_matches.is_present("raw") {
+                RebaseTarget::Supply(value_t_or_exit!(arg_matches, "new_supply", u64))
+            } else if arg_matches.is_present("ui") {
+                let ui_supply = arg_matches.value_of("new_supply").unwrap().to_string();
+                // the program would reject it the same way
+                let new_supply = try_ui_supply_into_supply(&ui_supply, mint_info.decimals)
+                    .map_err(|_| {
+                        format!(
+                            "{} is not a supply in tokens with at most {} decimals",
+                            ui_supply, mint_info.decimals
+                        )
+                    })?;
+                RebaseTarget::UiSupply {
+                    ui_supply,
+                    new_supply,
+                }
+            } else {
+                RebaseTarget::Supply(spl_token::ui_amount_to_amount(
+                    value_t_or_exit!(arg_matches, "new_supply", f64),
+                    mint_info.decimals,
+                ))
+            };
+            let reason_code = value_of::<u8>(arg_matches, "reason").unwrap_or_default();
+            let reference = arg_matches
+                .value_of("reference")
+                .map(parse_rebase_reference)
+                .transpose()?
+                .unwrap_or_default();
+            let not_after_unix_timestamp =
+                value_of::<i64>(arg_matches, "valid_until").unwrap_or_default();
+
+            command_rebase(
+                config,
+                mint_info,
+                rebase_authority,
+                reserve_account,
+                metadata_payer,
+                co_signer,
+                target,
+                reason_code,
+                reference,
+                not_after_unix_timestamp,
+                arg_matches.is_present("dry_run"),
+                arg_matches.is_present("yes"),
+                bulk_signers,
+            )
+            .await
+        }
+        (CommandName::RebaseHistory, arg_matches) => {
+            let token_pubkey = pubkey_of_signer(arg_matches, "token", &mut wallet_manager)
+                .unwrap()
+                .unwrap();
+            let limit = value_t_or_exit!(arg_matches, "limit", usize);
+            command_rebase_history(config, token_pubkey, limit).await
+        }
+        (CommandName::Convert, arg_matches) => {
+            let token_pubkey = pubkey_of_signer(arg_matches, "token", &mut wallet_manager)
+                .unwrap()
+                .unwrap();
+            let amount = value_of::<f64>(arg_matches, "amount");
+            let shares = value_of::<u64>(arg_matches, "shares");
+            command_convert(config, token_pubkey, amount, shares).await
+        }
         (CommandName::SetTransferHook, arg_matches) => {
             let token_pubkey = pubkey_of_signer(arg_matches, "token", &mut wallet_manager)
                 .unwrap()
@@ -3759,6 +4699,29 @@ pub async fn process_command<'a>(
                     .collect::<Vec<_>>()
             });
 
+            if arg_matches.is_present("shares") {
+                let shares = match arg_matches.value_of("amount").unwrap() {
+                    "ALL" => None,
+                    shares => Some(parse_shares(shares)?),
+                };
+                return command_transfer_shares(
+                    config,
+                    token,
+                    shares,
+                    recipient,
+                    sender,
+                    owner,
+                    mint_decimals,
+                    no_recipient_is_ata_owner,
+                    use_unchecked_instruction,
+                    memo,
+                    bulk_signers,
+                    arg_matches.is_present("no_wait"),
+                    transfer_hook_accounts,
+                )
+                .await;
+            }
+
             command_transfer(
                 config,
                 token,
@@ -3792,12 +4755,26 @@ pub async fn process_command<'a>(
                 push_signer_with_dedup(owner_signer, &mut bulk_signers);
             }
 
-            let amount = value_t_or_exit!(arg_matches, "amount", f64);
             let mint_address =
                 pubkey_of_signer(arg_matches, MINT_ADDRESS_ARG.name, &mut wallet_manager).unwrap();
             let mint_decimals = value_of::<u8>(arg_matches, MINT_DECIMALS_ARG.name);
             let use_unchecked_instruction = arg_matches.is_present("use_unchecked_instruction");
             let memo = value_t!(arg_matches, "memo", String).ok();
+            if arg_matches.is_present("shares") {
+                let shares = parse_shares(arg_matches.value_of("amount").unwrap())?;
+                return command_burn_shares(
+                    config,
+                    account,
+                    owner,
+                    shares,
+                    mint_address,
+                    mint_decimals,
+                    memo,
+                    bulk_signers,
+                )
+                .await;
+            }
+            let amount = value_t_or_exit!(arg_matches, "amount", f64);
             command_burn(
                 config,
                 account,
@@ -3821,7 +4798,6 @@ pub async fn process_command<'a>(
             let token = pubkey_of_signer(arg_matches, "token", &mut wallet_manager)
                 .unwrap()
                 .unwrap();
-            let amount = value_t_or_exit!(arg_matches, "amount", f64);
             let mint_decimals = value_of::<u8>(arg_matches, MINT_DECIMALS_ARG.name);
             let mint_info = config.get_mint_info(&token, mint_decimals).await?;
             let recipient = if let Some(address) =
@@ -3843,6 +4819,21 @@ pub async fn process_command<'a>(
             config.check_account(&recipient, Some(token)).await?;
             let use_unchecked_instruction = arg_matches.is_present("use_unchecked_instruction");
             let memo = value_t!(arg_matches, "memo", String).ok();
+            if arg_matches.is_present("shares") {
+                let shares = parse_shares(arg_matches.value_of("amount").unwrap())?;
+                return command_mint_shares(
+                    config,
+                    token,
+                    shares,
+                    recipient,
+                    mint_info,
+                    mint_authority,
+                    memo,
+                    bulk_signers,
+                )
+                .await;
+            }
+            let amount = value_t_or_exit!(arg_matches, "amount", f64);
             command_mint(
                 config,
                 token,
@@ -13,7 +13,14 @@ use {
             UiTransferFeeAmount, UiTransferFeeConfig, UiTransferHook, UiTransferHookAccount,
         },
     },
-    solana_cli_output::{display::writeln_name_value, OutputFormat, QuietDisplay, VerboseDisplay},
+    solana_cli_output::{
+        display::{unix_timestamp_to_string, writeln_name_value},
+        CliSignature, OutputFormat, QuietDisplay, VerboseDisplay,
+    },
+    spl_token_2022::{
+        amount_to_ui_amount_string_trimmed,
+        extension::rebase_mint::{ui::UiRebaseMintConfig, RebaseMode, REBASE_INDEX_SCALE},
+    },
     std::fmt::{self, Display},
 };
 
@@ -76,17 +83,37 @@ where
 {
     pub(crate) address: String,
     pub(crate) decimals: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) rebase_authority: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) rebase_initial_supply: Option<String>,
     pub(crate) transaction_data: T,
 }
 
+impl<T> CliCreateToken<T>
+where
+    T: Serialize + Display + QuietDisplay + VerboseDisplay,
+{
+    fn write_header(&self, w: &mut dyn std::fmt::Write) -> std::fmt::Result {
+        writeln!(w)?;
+        writeln_name_value(w, "Address: ", &self.address)?;
+        writeln_name_value(w, "Decimals: ", &format!("{}", self.decimals))?;
+        if let Some(rebase_authority) = &self.rebase_authority {
+            writeln_name_value(w, "Rebase authority: ", rebase_authority)?;
+        }
+        if let Some(rebase_initial_supply) = &self.rebase_initial_supply {
+            writeln_name_value(w, "Rebase initial supply: ", rebase_initial_supply)?;
+        }
+        Ok(())
+    }
+}
+
 impl<T> Display for CliCreateToken<T>
 where
     T: Serialize + Display + QuietDisplay + VerboseDisplay,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f)?;
-        writeln_name_value(f, "Address: ", &self.address)?;
-        writeln_name_value(f, "Decimals: ", &format!("{}", self.decimals))?;
+        self.write_header(f)?;
         Display::fmt(&self.transaction_data, f)
     }
 }
@@ -95,9 +122,7 @@ where
     T: Serialize + Display + QuietDisplay + VerboseDisplay,
 {
     fn write_str(&self, w: &mut dyn std::fmt::Write) -> std::fmt::Result {
-        writeln!(w)?;
-        writeln_name_value(w, "Address: ", &self.address)?;
-        writeln_name_value(w, "Decimals: ", &format!("{}", self.decimals))?;
+        self.write_header(w)?;
         QuietDisplay::write_str(&self.transaction_data, w)
     }
 }
@@ -106,13 +131,196 @@ where
     T: Serialize + Display + QuietDisplay + VerboseDisplay,
 {
     fn write_str(&self, w: &mut dyn std::fmt::Write) -> std::fmt::Result {
-        writeln!(w)?;
-        writeln_name_value(w, "Address: ", &self.address)?;
-        writeln_name_value(w, "Decimals: ", &format!("{}", self.decimals))?;
+        self.write_header(w)?;
         VerboseDisplay::write_str(&self.transaction_data, w)
     }
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CliRebase {
+    pub(crate) address: String,
+    pub(crate) old_supply: UiTokenAmount,
+    pub(crate) new_supply: UiTokenAmount,
+    pub(crate) old_shares: String,
+    pub(crate) new_shares: String,
+    pub(crate) transaction_data: CliSignature,
+}
+
+impl QuietDisplay for CliRebase {}
+impl VerboseDisplay for CliRebase {}
+
+impl fmt::Display for CliRebase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f)?;
+        writeln_name_value(f, "Address: ", &self.address)?;
+        writeln_name_value(
+            f,
+            "Old supply: ",
+            &self.old_supply.real_number_string_trimmed(),
+        )?;
+        writeln_name_value(
+            f,
+            "New supply: ",
+            &self.new_supply.real_number_string_trimmed(),
+        )?;
+        writeln_name_value(f, "Old shares: ", &self.old_shares)?;
+        writeln_name_value(f, "New shares: ", &self.new_shares)?;
+        Display::fmt(&self.transaction_data, f)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CliRebasePreview {
+    pub(crate) address: String,
+    pub(crate) old_supply: UiTokenAmount,
+    pub(crate) new_supply: UiTokenAmount,
+    pub(crate) old_shares: String,
+    pub(crate) new_shares: String,
+    pub(crate) ratio_change_bps: Option<i64>,
+    pub(crate) rounding_error: String,
+    /// False if the preview was computed locally, without checking the
+    /// reserve or the time of the first rebase
+    pub(crate) simulated: bool,
+}
+
+impl QuietDisplay for CliRebasePreview {}
+impl VerboseDisplay for CliRebasePreview {}
+
+impl fmt::Display for CliRebasePreview {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f)?;
+        writeln!(f, "{}", style("Rebase preview, nothing sent").bold())?;
+        writeln_name_value(f, "Address: ", &self.address)?;
+        writeln_name_value(
+            f,
+            "Old supply: ",
+            &self.old_supply.real_number_string_trimmed(),
+        )?;
+        writeln_name_value(
+            f,
+            "New supply: ",
+            &self.new_supply.real_number_string_trimmed(),
+        )?;
+        writeln_name_value(f, "Old shares: ", &self.old_shares)?;
+        writeln_name_value(f, "New shares: ", &self.new_shares)?;
+        writeln_name_value(
+            f,
+            "Ratio change: ",
+            &self
+                .ratio_change_bps
+                .map(|bps| format!("{:+}bps", bps))
+                .unwrap_or_default(),
+        )?;
+        writeln_name_value(f, "Rounding error: ", &self.rounding_error)?;
+        if !self.simulated {
+            writeln!(
+                f,
+                "{} Computed locally, without checking the reserve or the first rebase time",
+                WARNING
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CliShareConversion {
+    pub(crate) address: String,
+    pub(crate) amount: UiTokenAmount,
+    pub(crate) shares: String,
+}
+
+impl QuietDisplay for CliShareConversion {}
+impl VerboseDisplay for CliShareConversion {}
+
+impl fmt::Display for CliShareConversion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f)?;
+        writeln_name_value(f, "Address:", &self.address)?;
+        writeln_name_value(f, "  Amount:", &self.amount.real_number_string_trimmed())?;
+        writeln_name_value(f, "  Shares:", &self.shares)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CliShareTransaction {
+    pub(crate) address: String,
+    pub(crate) shares: String,
+    /// Tokens the shares were worth at the ratio before the transaction
+    pub(crate) amount: UiTokenAmount,
+    pub(crate) transaction_data: CliSignature,
+}
+
+impl QuietDisplay for CliShareTransaction {}
+impl VerboseDisplay for CliShareTransaction {}
+
+impl fmt::Display for CliShareTransaction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f)?;
+        writeln_name_value(f, "Address:", &self.address)?;
+        writeln_name_value(f, "  Shares:", &self.shares)?;
+        writeln_name_value(f, "  Amount:", &self.amount.real_number_string_trimmed())?;
+        Display::fmt(&self.transaction_data, f)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CliRebaseHistory {
+    pub(crate) address: String,
+    pub(crate) rebases: Vec<CliRebaseHistoryEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CliRebaseHistoryEntry {
+    /// Only known for rebases read from the logs rather than the mint's
+    /// history
+    pub(crate) signature: Option<String>,
+    pub(crate) unix_timestamp: i64,
+    pub(crate) old_supply: UiTokenAmount,
+    pub(crate) new_supply: UiTokenAmount,
+    pub(crate) total_shares: String,
+}
+
+impl QuietDisplay for CliRebaseHistory {}
+impl VerboseDisplay for CliRebaseHistory {}
+
+impl fmt::Display for CliRebaseHistory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{}",
+            style(format!("Rebases of {}", self.address)).bold()
+        )?;
+        if self.rebases.is_empty() {
+            return writeln!(f, "  None found");
+        }
+        for rebase in &self.rebases {
+            writeln!(f, "  {}", unix_timestamp_to_string(rebase.unix_timestamp))?;
+            if let Some(signature) = &rebase.signature {
+                writeln_name_value(f, "    Signature:", signature)?;
+            }
+            writeln_name_value(
+                f,
+                "    Supply:",
+                &format!(
+                    "{} -> {}",
+                    rebase.old_supply.real_number_string_trimmed(),
+                    rebase.new_supply.real_number_string_trimmed()
+                ),
+            )?;
+            writeln_name_value(f, "    Total shares:", &rebase.total_shares)?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct CliTokenAmount {
@@ -135,6 +343,112 @@ impl fmt::Display for CliTokenAmount {
     }
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CliRebasedTokenAmount {
+    #[serde(flatten)]
+    pub(crate) amount: UiTokenAmount,
+    /// Shares behind the amount, or none if the account is exempt from
+    /// rebasing
+    pub(crate) shares: Option<String>,
+}
+
+impl QuietDisplay for CliRebasedTokenAmount {
+    fn write_str(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        writeln!(w, "{}", self.amount.real_number_string_trimmed())
+    }
+}
+impl VerboseDisplay for CliRebasedTokenAmount {
+    fn write_str(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        writeln!(w, "ui amount: {}", self.amount.real_number_string_trimmed())?;
+        writeln!(w, "decimals: {}", self.amount.decimals)?;
+        writeln!(w, "amount: {}", self.amount.amount)?;
+        writeln!(
+            w,
+            "shares: {}",
+            self.shares.as_deref().unwrap_or("exempt from rebasing")
+        )
+    }
+}
+
+impl fmt::Display for CliRebasedTokenAmount {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", self.amount.real_number_string_trimmed())?;
+        if let Some(shares) = &self.shares {
+            writeln_name_value(f, "  Shares:", shares)
+        } else {
+            writeln!(f, "  {}", style("Exempt from rebasing").bold())
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CliRebasedSupply {
+    /// Total supply of the rebasing mint
+    #[serde(flatten)]
+    pub(crate) amount: UiTokenAmount,
+    pub(crate) total_shares: String,
+    /// Tokens that one share is currently worth, or none before any share
+    /// exists
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) current_ratio: Option<f64>,
+    /// Whether the supply recorded on the base mint matches the total supply
+    pub(crate) mint_supply_in_sync: bool,
+    /// Supply recorded on the base mint, if it differs from the total supply
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) mint_supply: Option<UiTokenAmount>,
+}
+
+impl QuietDisplay for CliRebasedSupply {
+    fn write_str(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        writeln!(w, "{}", self.amount.real_number_string_trimmed())
+    }
+}
+impl VerboseDisplay for CliRebasedSupply {
+    fn write_str(&self, w: &mut dyn fmt::Write) -> fmt::Result {
+        writeln!(w, "ui amount: {}", self.amount.real_number_string_trimmed())?;
+        writeln!(w, "decimals: {}", self.amount.decimals)?;
+        writeln!(w, "amount: {}", self.amount.amount)?;
+        writeln!(w, "total shares: {}", self.total_shares)?;
+        if let Some(current_ratio) = self.current_ratio {
+            writeln!(w, "current ratio: {}", current_ratio)?;
+        }
+        writeln!(w, "mint supply in sync: {}", self.mint_supply_in_sync)?;
+        if let Some(mint_supply) = &self.mint_supply {
+            writeln!(w, "mint supply: {}", mint_supply.amount)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for CliRebasedSupply {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", self.amount.real_number_string_trimmed())?;
+        writeln_name_value(f, "  Total shares:", &self.total_shares)?;
+        if let Some(current_ratio) = self.current_ratio {
+            writeln_name_value(f, "  Current ratio:", &current_ratio.to_string())?;
+        }
+        writeln_name_value(
+            f,
+            "  Mint supply in sync:",
+            if self.mint_supply_in_sync {
+                "Yes"
+            } else {
+                "No"
+            },
+        )?;
+        if let Some(mint_supply) = &self.mint_supply {
+            writeln_name_value(
+                f,
+                "  Mint supply:",
+                &mint_supply.real_number_string_trimmed(),
+            )?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct CliWalletAddress {
@@ -294,6 +608,8 @@ pub(crate) struct CliMint {
     pub(crate) epoch: u64,
     #[serde(flatten)]
     pub(crate) mint: UiMint,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) rebase: Option<UiRebaseMintConfig>,
 }
 
 impl QuietDisplay for CliMint {}
@@ -322,11 +638,14 @@ impl fmt::Display for CliMint {
                 .unwrap_or(&String::new()),
         )?;
 
-        if !self.mint.extensions.is_empty() {
+        if !self.mint.extensions.is_empty() || self.rebase.is_some() {
             writeln!(f, "{}", style("Extensions").bold())?;
             for extension in &self.mint.extensions {
                 display_ui_extension(f, self.epoch, extension)?;
             }
+            if let Some(rebase) = &self.rebase {
+                display_rebase_config(f, self.mint.decimals, rebase)?;
+            }
         }
 
         Ok(())
@@ -917,6 +1236,135 @@ fn display_ui_extension(
     }
 }
 
+/// Like `amount_to_ui_amount_string_trimmed`, for the cumulative rebase
+/// statistics, which may exceed a `u64`
+fn u128_amount_to_ui_amount_string_trimmed(amount: u128, decimals: u8) -> String {
+    let decimals = decimals as usize;
+    let mut s = format!("{:01$}", amount, decimals + 1);
+    if decimals > 0 {
+        s.insert(s.len() - decimals, '.');
+        s = s.trim_end_matches('0').trim_end_matches('.').to_string();
+    }
+    s
+}
+
+fn display_rebase_config(
+    f: &mut fmt::Formatter,
+    decimals: u8,
+    rebase: &UiRebaseMintConfig,
+) -> fmt::Result {
+    writeln!(f, "  {}", style("Rebasing:").bold())?;
+    writeln_name_value(
+        f,
+        "    Total supply:",
+        &amount_to_ui_amount_string_trimmed(rebase.total_supply, decimals),
+    )?;
+    if rebase.rebase_mode == RebaseMode::Lazy {
+        writeln_name_value(f, "    Rebase mode:", "Lazy")?;
+        writeln_name_value(
+            f,
+            "    Rebase index:",
+            &(rebase.rebase_index as f64 / REBASE_INDEX_SCALE as f64).to_string(),
+        )?;
+    } else {
+        writeln_name_value(f, "    Total shares:", &rebase.total_shares.to_string())?;
+        writeln_name_value(
+            f,
+            "    Current ratio:",
+            &rebase
+                .current_ratio
+                .map(|ratio| ratio.to_string())
+                .unwrap_or_default(),
+        )?;
+    }
+    writeln_name_value(
+        f,
+        "    Exempt supply:",
+        &amount_to_ui_amount_string_trimmed(rebase.exempt_supply, decimals),
+    )?;
+    writeln_name_value(
+        f,
+        "    Supply authority:",
+        &rebase
+            .supply_authority
+            .map(|pubkey| pubkey.to_string())
+            .unwrap_or_default(),
+    )?;
+    if let Some(co_signer) = rebase.co_signer {
+        writeln_name_value(f, "    Co-signer:", &co_signer.to_string())?;
+    }
+    if let Some(rebase_delegate) = rebase.rebase_delegate {
+        writeln_name_value(f, "    Rebase delegate:", &rebase_delegate.to_string())?;
+    }
+    if let Some(pending_supply_authority) = rebase.pending_supply_authority {
+        writeln_name_value(
+            f,
+            "    Pending supply authority:",
+            &pending_supply_authority.to_string(),
+        )?;
+    }
+    if let Some(scheduled_supply_authority) = rebase.scheduled_supply_authority {
+        writeln_name_value(
+            f,
+            "    Scheduled supply authority:",
+            &format!(
+                "{} (requested at {})",
+                scheduled_supply_authority, rebase.authority_change_requested_timestamp
+            ),
+        )?;
+    }
+    if let Some(reserve_account) = rebase.reserve_account {
+        writeln_name_value(f, "    Reserve account:", &reserve_account.to_string())?;
+    }
+    if rebase.first_rebase_after_timestamp != 0 {
+        writeln_name_value(
+            f,
+            "    First rebase after:",
+            &rebase.first_rebase_after_timestamp.to_string(),
+        )?;
+    }
+    if rebase.sync_base_supply {
+        writeln_name_value(f, "    Mint supply:", "Synced on every rebase")?;
+    }
+    writeln_name_value(
+        f,
+        "    Has rebased:",
+        if rebase.has_rebased { "Yes" } else { "No" },
+    )?;
+    if rebase.has_rebased {
+        writeln_name_value(
+            f,
+            "    Cumulative increase:",
+            &u128_amount_to_ui_amount_string_trimmed(rebase.cumulative_supply_increase, decimals),
+        )?;
+        writeln_name_value(
+            f,
+            "    Cumulative decrease:",
+            &u128_amount_to_ui_amount_string_trimmed(rebase.cumulative_supply_decrease, decimals),
+        )?;
+    }
+    if rebase.is_retired {
+        writeln_name_value(
+            f,
+            "    Retired at:",
+            &format!(
+                "{} for {} shares",
+                amount_to_ui_amount_string_trimmed(rebase.retired_supply, decimals),
+                rebase.retired_shares
+            ),
+        )?;
+    }
+    writeln_name_value(
+        f,
+        "    Supply decrease:",
+        if rebase.allow_supply_decrease {
+            "Allowed"
+        } else {
+            "Forbidden"
+        },
+    )
+}
+
 fn flattened<S: Serializer>(
     vec: &[Vec<CliTokenAccount>],
     serializer: S,
@@ -1,19 +1,25 @@
 #![allow(clippy::arithmetic_side_effects)]
 use {
+    base64::{prelude::BASE64_STANDARD, Engine},
     libtest_mimic::{Arguments, Trial},
     solana_cli_output::OutputFormat,
-    solana_client::{nonblocking::rpc_client::RpcClient, rpc_request::TokenAccountsFilter},
+    solana_client::{
+        nonblocking::rpc_client::RpcClient, rpc_config::RpcTransactionConfig,
+        rpc_request::TokenAccountsFilter,
+    },
     solana_sdk::{
         bpf_loader_upgradeable,
+        commitment_config::CommitmentConfig,
         hash::Hash,
         program_option::COption,
         program_pack::Pack,
         pubkey::Pubkey,
-        signature::{write_keypair_file, Keypair, Signer},
+        signature::{write_keypair_file, Keypair, Signature, Signer},
         system_instruction, system_program,
         transaction::Transaction,
     },
     solana_test_validator::{TestValidator, TestValidatorGenesis, UpgradeableProgramInfo},
+    solana_transaction_status::{option_serializer::OptionSerializer, UiTransactionEncoding},
     spl_associated_token_account::get_associated_token_address_with_program_id,
     spl_token_2022::{
         extension::{
@@ -27,6 +33,10 @@ use {
             memo_transfer::MemoTransfer,
             metadata_pointer::MetadataPointer,
             non_transferable::NonTransferable,
+            rebase_mint::{
+                event::{parse_event, RebaseEvent, RebaseTagged},
+                RebaseMintConfig,
+            },
             transfer_fee::{TransferFeeAmount, TransferFeeConfig},
             transfer_hook::TransferHook,
             BaseStateWithExtensions, StateWithExtensionsOwned,
@@ -88,6 +98,21 @@ async fn main() {
     let tests = vec![
         async_trial!(create_token_default, test_validator, payer),
         async_trial!(create_token_interest_bearing, test_validator, payer),
+        async_trial!(create_token_rebase, test_validator, payer),
+        async_trial!(rebase, test_validator, payer),
+        async_trial!(display_rebase, test_validator, payer),
+        async_trial!(authorize_rebase_supply, test_validator, payer),
+        async_trial!(rebased_balance_and_supply, test_validator, payer),
+        async_trial!(rebase_dry_run, test_validator, payer),
+        async_trial!(multisig_rebase, test_validator, payer),
+        async_trial!(rebase_by_ratio, test_validator, payer),
+        async_trial!(rebase_with_reason, test_validator, payer),
+        async_trial!(rebase_valid_until, test_validator, payer),
+        async_trial!(rebase_ui, test_validator, payer),
+        async_trial!(rebase_history, test_validator, payer),
+        async_trial!(offline_rebase_with_nonce, test_validator, payer),
+        async_trial!(convert_shares, test_validator, payer),
+        async_trial!(transfer_mint_and_burn_shares, test_validator, payer),
         async_trial!(set_interest_rate, test_validator, payer),
         async_trial!(supply, test_validator, payer),
         async_trial!(create_account_default, test_validator, payer),
@@ -481,6 +506,1423 @@ async fn create_token_interest_bearing(test_validator: &TestValidator, payer: &K
     );
 }
 
+async fn create_token_rebase(test_validator: &TestValidator, payer: &Keypair) {
+    let config = test_config_with_default_signer(test_validator, payer, &spl_token_2022::id());
+    let rebase_authority = Pubkey::new_unique();
+    let result = process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::CreateToken.into(),
+            "--enable-rebase",
+            "--rebase-authority",
+            &rebase_authority.to_string(),
+            "--rebase-initial-supply",
+            "10.5",
+        ],
+    )
+    .await;
+    let value: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+    assert_eq!(
+        value["commandOutput"]["rebaseAuthority"],
+        rebase_authority.to_string()
+    );
+    assert_eq!(value["commandOutput"]["rebaseInitialSupply"], "10.5");
+    let mint = Pubkey::from_str(value["commandOutput"]["address"].as_str().unwrap()).unwrap();
+    let account = config.rpc_client.get_account(&mint).await.unwrap();
+    let mint_account = StateWithExtensionsOwned::<Mint>::unpack(account.data).unwrap();
+    let extension = mint_account.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(
+        Option::<Pubkey>::from(extension.supply_authority),
+        Some(rebase_authority)
+    );
+    assert_eq!(u64::from(extension.total_supply), 10_500_000_000);
+
+    // and display reads it back
+    let result = process_test_command(
+        &config,
+        payer,
+        &["spl-token", CommandName::Display.into(), &mint.to_string()],
+    )
+    .await;
+    let value: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+    assert_eq!(value["address"], mint.to_string());
+    assert_eq!(value["decimals"], TEST_DECIMALS);
+
+    // the rebase authority defaults to the mint authority
+    let result = process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::CreateToken.into(),
+            "--enable-rebase",
+        ],
+    )
+    .await;
+    let value: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+    assert_eq!(
+        value["commandOutput"]["rebaseAuthority"],
+        payer.pubkey().to_string()
+    );
+    assert_eq!(value["commandOutput"]["rebaseInitialSupply"], "0");
+    let mint = Pubkey::from_str(value["commandOutput"]["address"].as_str().unwrap()).unwrap();
+    let account = config.rpc_client.get_account(&mint).await.unwrap();
+    let mint_account = StateWithExtensionsOwned::<Mint>::unpack(account.data).unwrap();
+    let extension = mint_account.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(
+        Option::<Pubkey>::from(extension.supply_authority),
+        Some(payer.pubkey())
+    );
+    assert_eq!(u64::from(extension.total_supply), 0);
+}
+
+async fn rebase(test_validator: &TestValidator, payer: &Keypair) {
+    let config = test_config_with_default_signer(test_validator, payer, &spl_token_2022::id());
+    let result = process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::CreateToken.into(),
+            "--enable-rebase",
+            "--rebase-initial-supply",
+            "100",
+        ],
+    )
+    .await;
+    let value: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+    let mint = Pubkey::from_str(value["commandOutput"]["address"].as_str().unwrap()).unwrap();
+
+    // in tokens, then in base units
+    for (args, old_supply, new_supply) in [
+        (vec!["150"], 100_000_000_000u64, 150_000_000_000u64),
+        (
+            vec!["--raw", "175000000000"],
+            150_000_000_000,
+            175_000_000_000,
+        ),
+    ] {
+        let mint_string = mint.to_string();
+        let mut command = vec!["spl-token", CommandName::Rebase.into(), &mint_string];
+        command.extend(args);
+        let result = process_test_command(&config, payer, &command).await;
+        let value: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+        let output = &value["commandOutput"];
+
+        let account = config.rpc_client.get_account(&mint).await.unwrap();
+        let mint_account = StateWithExtensionsOwned::<Mint>::unpack(account.data).unwrap();
+        let extension = mint_account.get_extension::<RebaseMintConfig>().unwrap();
+        assert_eq!(u64::from(extension.total_supply), new_supply);
+        assert_eq!(output["oldSupply"]["amount"], old_supply.to_string());
+        assert_eq!(
+            output["newSupply"]["amount"],
+            u64::from(extension.total_supply).to_string()
+        );
+        assert_eq!(output["newSupply"]["decimals"], TEST_DECIMALS);
+        assert_eq!(output["oldShares"], "100000000000");
+        assert_eq!(
+            output["newShares"],
+            u64::from(extension.total_shares).to_string()
+        );
+    }
+
+    // program errors come out by name
+    let err = process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::Rebase.into(),
+            &mint.to_string(),
+            "120",
+        ],
+    )
+    .await
+    .unwrap_err();
+    assert!(
+        err.to_string().contains("SupplyDecreaseForbidden"),
+        "{}",
+        err
+    );
+
+    let plain_token = create_token(&config, payer).await;
+    let err = process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::Rebase.into(),
+            &plain_token.to_string(),
+            "120",
+        ],
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!("Mint {} is not a rebasing mint", plain_token)
+    );
+}
+
+async fn display_rebase(test_validator: &TestValidator, payer: &Keypair) {
+    let mut config = test_config_with_default_signer(test_validator, payer, &spl_token_2022::id());
+    let result = process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::CreateToken.into(),
+            "--enable-rebase",
+            "--rebase-initial-supply",
+            "100",
+        ],
+    )
+    .await;
+    let value: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+    let mint = Pubkey::from_str(value["commandOutput"]["address"].as_str().unwrap()).unwrap();
+    process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::Rebase.into(),
+            &mint.to_string(),
+            "150",
+        ],
+    )
+    .await
+    .unwrap();
+
+    let result = process_test_command(
+        &config,
+        payer,
+        &["spl-token", CommandName::Display.into(), &mint.to_string()],
+    )
+    .await;
+    let value: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+    assert_eq!(
+        value["rebase"],
+        serde_json::json!({
+            "supplyAuthority": payer.pubkey().to_string(),
+            "totalSupply": "150000000000",
+            "totalShares": "100000000000",
+            "reserveAccount": null,
+            "firstRebaseAfterTimestamp": 0,
+            "pendingSupplyAuthority": null,
+            "twoStepAuthorityTransfer": false,
+            "authorityChangeDelaySeconds": 0,
+            "scheduledSupplyAuthority": null,
+            "authorityChangeRequestedTimestamp": 0,
+            "rebaseDelegate": null,
+            "useMintAuthorityFallback": false,
+            "requireRebaseMemo": false,
+            "hasRebased": true,
+            "exemptSupply": "0",
+            "outstandingShares": "0",
+            "virtualSharesOffset": "0",
+            "allowSupplyDecrease": false,
+            "rebaseFeeBps": 0,
+            "feeDestination": null,
+            "maxTotalShares": "9223372036854775808",
+            "allowSunset": false,
+            "isSunset": false,
+            "updateMetadataRate": false,
+            "isRetired": false,
+            "retiredSupply": "0",
+            "retiredShares": "0",
+            "coSigner": null,
+            "rebaseMode": "shares",
+            "rebaseIndex": "0",
+            "syncBaseSupply": false,
+            "cumulativeSupplyIncrease": "50000000000",
+            "cumulativeSupplyDecrease": "0",
+            "currentRatio": 1.5,
+        })
+    );
+
+    config.output_format = OutputFormat::Display;
+    let result = process_test_command(
+        &config,
+        payer,
+        &["spl-token", CommandName::Display.into(), &mint.to_string()],
+    )
+    .await
+    .unwrap();
+    let expected = format!(
+        "  Rebasing:
+    Total supply: 150
+    Total shares: 100000000000
+    Current ratio: 1.5
+    Exempt supply: 0
+    Supply authority: {}
+    Has rebased: Yes
+    Cumulative increase: 50
+    Cumulative decrease: 0
+    Supply decrease: Forbidden
+",
+        payer.pubkey()
+    );
+    assert!(result.ends_with(&expected), "{}", result);
+
+    // a mint without the extension has no rebase section in either format
+    let plain_token = create_token(&config, payer).await;
+    let result = process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::Display.into(),
+            &plain_token.to_string(),
+        ],
+    )
+    .await
+    .unwrap();
+    assert!(!result.contains("Rebasing"));
+    config.output_format = OutputFormat::JsonCompact;
+    let result = process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::Display.into(),
+            &plain_token.to_string(),
+        ],
+    )
+    .await;
+    let value: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+    assert!(value.get("rebase").is_none());
+}
+
+async fn authorize_rebase_supply(test_validator: &TestValidator, payer: &Keypair) {
+    let config = test_config_with_default_signer(test_validator, payer, &spl_token_2022::id());
+    let result = process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::CreateToken.into(),
+            "--enable-rebase",
+            "--rebase-initial-supply",
+            "100",
+        ],
+    )
+    .await;
+    let value: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+    let mint = Pubkey::from_str(value["commandOutput"]["address"].as_str().unwrap()).unwrap();
+
+    // rotate
+    let new_authority = Keypair::new();
+    let new_authority_keypair_file = NamedTempFile::new().unwrap();
+    write_keypair_file(&new_authority, &new_authority_keypair_file).unwrap();
+    process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::Authorize.into(),
+            &mint.to_string(),
+            "rebase-supply",
+            &new_authority.pubkey().to_string(),
+        ],
+    )
+    .await
+    .unwrap();
+    let result = process_test_command(
+        &config,
+        payer,
+        &["spl-token", CommandName::Display.into(), &mint.to_string()],
+    )
+    .await;
+    let value: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+    assert_eq!(
+        value["rebase"]["supplyAuthority"],
+        new_authority.pubkey().to_string()
+    );
+
+    // the old authority is out
+    let err = process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::Authorize.into(),
+            &mint.to_string(),
+            "rebase-supply",
+            &payer.pubkey().to_string(),
+        ],
+    )
+    .await
+    .unwrap_err();
+    assert!(err.to_string().contains("custom program error"), "{}", err);
+
+    // revoke
+    process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::Authorize.into(),
+            &mint.to_string(),
+            "rebase-supply",
+            "--disable",
+            "--authority",
+            new_authority_keypair_file.path().to_str().unwrap(),
+        ],
+    )
+    .await
+    .unwrap();
+    let result = process_test_command(
+        &config,
+        payer,
+        &["spl-token", CommandName::Display.into(), &mint.to_string()],
+    )
+    .await;
+    let value: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+    assert!(value["rebase"]["supplyAuthority"].is_null());
+
+    let err = process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::Rebase.into(),
+            &mint.to_string(),
+            "150",
+            "--rebase-authority",
+            new_authority_keypair_file.path().to_str().unwrap(),
+        ],
+    )
+    .await
+    .unwrap_err();
+    assert!(err.to_string().contains("RebaseSupplyImmutable"), "{}", err);
+
+    // a mint without the extension has no such authority
+    let plain_token = create_token(&config, payer).await;
+    let err = process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::Authorize.into(),
+            &plain_token.to_string(),
+            "rebase-supply",
+            &new_authority.pubkey().to_string(),
+        ],
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!("Mint `{}` is not a rebasing mint", plain_token)
+    );
+}
+
+async fn rebased_balance_and_supply(test_validator: &TestValidator, payer: &Keypair) {
+    let config = test_config_with_default_signer(test_validator, payer, &spl_token_2022::id());
+    let result = process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::CreateToken.into(),
+            "--enable-rebase",
+        ],
+    )
+    .await;
+    let value: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+    let mint = Pubkey::from_str(value["commandOutput"]["address"].as_str().unwrap()).unwrap();
+    let account = create_associated_account(&config, payer, &mint, &payer.pubkey()).await;
+    mint_tokens(&config, payer, mint, 100.0, account)
+        .await
+        .unwrap();
+
+    let config = &config;
+    let balance = || async move {
+        let result = process_test_command(
+            config,
+            payer,
+            &["spl-token", CommandName::Balance.into(), &mint.to_string()],
+        )
+        .await;
+        serde_json::from_str::<serde_json::Value>(&result.unwrap()).unwrap()
+    };
+    let supply = || async move {
+        let result = process_test_command(
+            config,
+            payer,
+            &["spl-token", CommandName::Supply.into(), &mint.to_string()],
+        )
+        .await;
+        serde_json::from_str::<serde_json::Value>(&result.unwrap()).unwrap()
+    };
+
+    let value = balance().await;
+    assert_eq!(value["amount"], "100000000000");
+    assert_eq!(value["uiAmountString"], "100");
+    assert_eq!(value["shares"], "100000000000");
+    let value = supply().await;
+    assert_eq!(value["amount"], "100000000000");
+    assert_eq!(value["totalShares"], "100000000000");
+    assert_eq!(value["currentRatio"], 1.0);
+    assert_eq!(value["mintSupplyInSync"], true);
+    assert!(value.get("mintSupply").is_none());
+
+    process_test_command(
+        config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::Rebase.into(),
+            &mint.to_string(),
+            "150",
+        ],
+    )
+    .await
+    .unwrap();
+
+    let value = balance().await;
+    assert_eq!(value["amount"], "150000000000");
+    assert_eq!(value["uiAmountString"], "150");
+    assert_eq!(value["shares"], "100000000000");
+    let value = supply().await;
+    assert_eq!(value["amount"], "150000000000");
+    assert_eq!(value["uiAmountString"], "150");
+    assert_eq!(value["totalShares"], "100000000000");
+    assert_eq!(value["currentRatio"], 1.5);
+    assert_eq!(value["mintSupplyInSync"], false);
+    assert_eq!(value["mintSupply"]["amount"], "100000000000");
+
+    let mut display_config =
+        test_config_with_default_signer(test_validator, payer, &spl_token_2022::id());
+    display_config.output_format = OutputFormat::Display;
+    let result = process_test_command(
+        &display_config,
+        payer,
+        &["spl-token", CommandName::Supply.into(), &mint.to_string()],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        result,
+        "150
+  Total shares: 100000000000
+  Current ratio: 1.5
+  Mint supply in sync: No
+  Mint supply: 100
+"
+    );
+
+    // a mint without the extension keeps the plain supply
+    let plain_token = create_token(config, payer).await;
+    let result = process_test_command(
+        config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::Supply.into(),
+            &plain_token.to_string(),
+        ],
+    )
+    .await;
+    let value: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+    assert_eq!(value["amount"], "0");
+    assert!(value.get("totalShares").is_none());
+    assert!(value.get("mintSupplyInSync").is_none());
+}
+
+async fn rebase_dry_run(test_validator: &TestValidator, payer: &Keypair) {
+    let config = test_config_with_default_signer(test_validator, payer, &spl_token_2022::id());
+    let result = process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::CreateToken.into(),
+            "--enable-rebase",
+            "--rebase-initial-supply",
+            "100",
+        ],
+    )
+    .await;
+    let value: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+    let mint = Pubkey::from_str(value["commandOutput"]["address"].as_str().unwrap()).unwrap();
+    let rpc_client = &config.rpc_client;
+    let total_supply = || async move {
+        let account = rpc_client.get_account(&mint).await.unwrap();
+        let mint_account = StateWithExtensionsOwned::<Mint>::unpack(account.data).unwrap();
+        let extension = mint_account.get_extension::<RebaseMintConfig>().unwrap();
+        u64::from(extension.total_supply)
+    };
+
+    let result = process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::Rebase.into(),
+            &mint.to_string(),
+            "150",
+            "--dry-run",
+        ],
+    )
+    .await;
+    let value: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+    let output = &value["commandOutput"];
+    assert_eq!(output["oldSupply"]["amount"], "100000000000");
+    assert_eq!(output["newSupply"]["amount"], "150000000000");
+    assert_eq!(output["newShares"], "100000000000");
+    assert_eq!(output["ratioChangeBps"], 5_000);
+    assert_eq!(output["simulated"], true);
+    assert_eq!(total_supply().await, 100_000_000_000);
+
+    // the decrease is rejected by the program, and the command fails
+    let err = process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::Rebase.into(),
+            &mint.to_string(),
+            "50",
+            "--dry-run",
+        ],
+    )
+    .await
+    .unwrap_err();
+    assert!(
+        err.to_string()
+            .starts_with("Rebase would fail with SupplyDecreaseForbidden"),
+        "{}",
+        err
+    );
+    assert_eq!(total_supply().await, 100_000_000_000);
+}
+
+async fn multisig_rebase(test_validator: &TestValidator, payer: &Keypair) {
+    let m = 2;
+    let n = 3u8;
+    let (multisig_members, multisig_paths): (Vec<_>, Vec<_>) = std::iter::repeat_with(Keypair::new)
+        .take(n as usize)
+        .map(|s| {
+            let keypair_file = NamedTempFile::new().unwrap();
+            write_keypair_file(&s, &keypair_file).unwrap();
+            (s.pubkey(), keypair_file)
+        })
+        .unzip();
+    let payer_path = NamedTempFile::new().unwrap();
+    write_keypair_file(payer, &payer_path).unwrap();
+    let config = test_config_with_default_signer(test_validator, payer, &spl_token_2022::id());
+
+    let multisig = Keypair::new();
+    let multisig_pubkey = multisig.pubkey();
+    let multisig_path = NamedTempFile::new().unwrap();
+    write_keypair_file(&multisig, &multisig_path).unwrap();
+    let multisig_strings = multisig_members
+        .iter()
+        .map(|p| p.to_string())
+        .collect::<Vec<_>>();
+    process_test_command(
+        &config,
+        payer,
+        [
+            "spl-token",
+            CommandName::CreateMultisig.into(),
+            "--address-keypair",
+            multisig_path.path().to_str().unwrap(),
+            "--program-id",
+            &spl_token_2022::id().to_string(),
+            &m.to_string(),
+        ]
+        .into_iter()
+        .chain(multisig_strings.iter().map(|p| p.as_str())),
+    )
+    .await
+    .unwrap();
+
+    let result = process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::CreateToken.into(),
+            "--enable-rebase",
+            "--rebase-authority",
+            &multisig_pubkey.to_string(),
+            "--rebase-initial-supply",
+            "100",
+        ],
+    )
+    .await;
+    let value: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+    let mint = Pubkey::from_str(value["commandOutput"]["address"].as_str().unwrap()).unwrap();
+
+    // one signature short of the threshold is caught before sending
+    let err = exec_test_cmd(
+        &config,
+        &[
+            "spl-token",
+            CommandName::Rebase.into(),
+            &mint.to_string(),
+            "150",
+            "--owner",
+            &multisig_pubkey.to_string(),
+            "--multisig-signer",
+            multisig_paths[0].path().to_str().unwrap(),
+            "--fee-payer",
+            payer_path.path().to_str().unwrap(),
+        ],
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "Multisig {} requires 2 signatures, but 1 of its members sign",
+            multisig_pubkey
+        )
+    );
+
+    exec_test_cmd(
+        &config,
+        &[
+            "spl-token",
+            CommandName::Rebase.into(),
+            &mint.to_string(),
+            "150",
+            "--owner",
+            &multisig_pubkey.to_string(),
+            "--multisig-signer",
+            multisig_paths[0].path().to_str().unwrap(),
+            "--multisig-signer",
+            multisig_paths[2].path().to_str().unwrap(),
+            "--fee-payer",
+            payer_path.path().to_str().unwrap(),
+        ],
+    )
+    .await
+    .unwrap();
+
+    let account = config.rpc_client.get_account(&mint).await.unwrap();
+    let mint_account = StateWithExtensionsOwned::<Mint>::unpack(account.data).unwrap();
+    let extension = mint_account.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(u64::from(extension.total_supply), 150_000_000_000);
+    assert_eq!(u64::from(extension.total_shares), 100_000_000_000);
+}
+
+async fn rebase_by_ratio(test_validator: &TestValidator, payer: &Keypair) {
+    let config = test_config_with_default_signer(test_validator, payer, &spl_token_2022::id());
+    let result = process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::CreateToken.into(),
+            "--enable-rebase",
+            "--rebase-initial-supply",
+            "100",
+        ],
+    )
+    .await;
+    let value: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+    let mint = Pubkey::from_str(value["commandOutput"]["address"].as_str().unwrap()).unwrap();
+
+    // each step applies to the supply left by the previous one, rounding down
+    for (args, expected_supply) in [
+        (["--bps", "3"], 100_030_000_000u64),
+        (["--ratio", "1.0003"], 100_060_009_000),
+        (["--bps", "7"], 100_130_051_006),
+        (["--ratio", "2"], 200_260_102_012),
+    ] {
+        let mint_string = mint.to_string();
+        let mut command = vec!["spl-token", CommandName::Rebase.into(), &mint_string];
+        command.extend(args);
+        command.push("--yes");
+        let result = process_test_command(&config, payer, &command).await;
+        let value: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+        assert_eq!(
+            value["commandOutput"]["newSupply"]["amount"],
+            expected_supply.to_string()
+        );
+
+        let account = config.rpc_client.get_account(&mint).await.unwrap();
+        let mint_account = StateWithExtensionsOwned::<Mint>::unpack(account.data).unwrap();
+        let extension = mint_account.get_extension::<RebaseMintConfig>().unwrap();
+        assert_eq!(u64::from(extension.total_supply), expected_supply);
+    }
+}
+
+async fn rebase_with_reason(test_validator: &TestValidator, payer: &Keypair) {
+    let config = test_config_with_default_signer(test_validator, payer, &spl_token_2022::id());
+    let result = process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::CreateToken.into(),
+            "--enable-rebase",
+            "--rebase-initial-supply",
+            "100",
+        ],
+    )
+    .await;
+    let value: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+    let mint = Pubkey::from_str(value["commandOutput"]["address"].as_str().unwrap()).unwrap();
+
+    let reference = "00112233445566778899aabbccddeeff00112233445566778899aabbccddeeff";
+    let result = process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::Rebase.into(),
+            &mint.to_string(),
+            "150",
+            "--reason",
+            "3",
+            "--reference",
+            reference,
+        ],
+    )
+    .await;
+    let value: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+    let signature = Signature::from_str(
+        value["commandOutput"]["transactionData"]["signature"]
+            .as_str()
+            .unwrap(),
+    )
+    .unwrap();
+
+    let transaction = config
+        .rpc_client
+        .get_transaction_with_config(
+            &signature,
+            RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Base64),
+                commitment: Some(CommitmentConfig::confirmed()),
+                max_supported_transaction_version: Some(0),
+            },
+        )
+        .await
+        .unwrap();
+    let OptionSerializer::Some(logs) = transaction.transaction.meta.unwrap().log_messages else {
+        panic!("no logs");
+    };
+    let events = logs
+        .iter()
+        .filter_map(|log| log.strip_prefix("Program data: "))
+        .filter_map(|data| {
+            let fields = data
+                .split(' ')
+                .map(|field| BASE64_STANDARD.decode(field).unwrap())
+                .collect::<Vec<_>>();
+            let fields = fields.iter().map(Vec::as_slice).collect::<Vec<_>>();
+            parse_event(&fields).ok()
+        })
+        .map(|(_, event)| event)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        events,
+        vec![RebaseEvent::RebaseTagged(RebaseTagged {
+            previous_supply: 100_000_000_000.into(),
+            new_supply: 150_000_000_000.into(),
+            reason_code: 3,
+            reference: parse_rebase_reference(reference).unwrap(),
+            cumulative_supply_increase: 50_000_000_000.into(),
+            cumulative_supply_decrease: 0.into(),
+        })]
+    );
+}
+
+async fn rebase_valid_until(test_validator: &TestValidator, payer: &Keypair) {
+    let config = test_config_with_default_signer(test_validator, payer, &spl_token_2022::id());
+    let result = process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::CreateToken.into(),
+            "--enable-rebase",
+            "--rebase-initial-supply",
+            "100",
+        ],
+    )
+    .await;
+    let value: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+    let mint = Pubkey::from_str(value["commandOutput"]["address"].as_str().unwrap()).unwrap();
+    let rpc_client = &config.rpc_client;
+    let total_supply = || async move {
+        let account = rpc_client.get_account(&mint).await.unwrap();
+        let mint_account = StateWithExtensionsOwned::<Mint>::unpack(account.data).unwrap();
+        let extension = mint_account.get_extension::<RebaseMintConfig>().unwrap();
+        u64::from(extension.total_supply)
+    };
+
+    // long past
+    let err = process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::Rebase.into(),
+            &mint.to_string(),
+            "150",
+            "--valid-until",
+            "1",
+        ],
+    )
+    .await
+    .unwrap_err();
+    assert!(
+        err.to_string()
+            .starts_with("Rebase failed with RebaseExpired"),
+        "{}",
+        err
+    );
+    assert_eq!(total_supply().await, 100_000_000_000);
+
+    let valid_until = i64::MAX.to_string();
+    process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::Rebase.into(),
+            &mint.to_string(),
+            "150",
+            "--valid-until",
+            &valid_until,
+        ],
+    )
+    .await
+    .unwrap();
+    assert_eq!(total_supply().await, 150_000_000_000);
+}
+
+async fn rebase_ui(test_validator: &TestValidator, payer: &Keypair) {
+    let config = test_config_with_default_signer(test_validator, payer, &spl_token_2022::id());
+    let result = process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::CreateToken.into(),
+            "--enable-rebase",
+            "--rebase-initial-supply",
+            "100",
+        ],
+    )
+    .await;
+    let value: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+    let mint = Pubkey::from_str(value["commandOutput"]["address"].as_str().unwrap()).unwrap();
+    let rpc_client = &config.rpc_client;
+    let total_supply = || async move {
+        let account = rpc_client.get_account(&mint).await.unwrap();
+        let mint_account = StateWithExtensionsOwned::<Mint>::unpack(account.data).unwrap();
+        let extension = mint_account.get_extension::<RebaseMintConfig>().unwrap();
+        u64::from(extension.total_supply)
+    };
+
+    // converted by the program, down to the last base unit
+    process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::Rebase.into(),
+            &mint.to_string(),
+            "150.000000001",
+            "--ui",
+        ],
+    )
+    .await
+    .unwrap();
+    assert_eq!(total_supply().await, 150_000_000_001);
+
+    // with a deadline like any other rebase
+    let err = process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::Rebase.into(),
+            &mint.to_string(),
+            "200",
+            "--ui",
+            "--reason",
+            "3",
+            "--valid-until",
+            "1",
+        ],
+    )
+    .await
+    .unwrap_err();
+    assert!(
+        err.to_string()
+            .starts_with("Rebase failed with RebaseExpired"),
+        "{}",
+        err
+    );
+    assert_eq!(total_supply().await, 150_000_000_001);
+
+    // rejected before sending anything
+    for new_supply in ["1e3", "150.0000000001"] {
+        let err = process_test_command(
+            &config,
+            payer,
+            &[
+                "spl-token",
+                CommandName::Rebase.into(),
+                &mint.to_string(),
+                new_supply,
+                "--ui",
+            ],
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "{} is not a supply in tokens with at most 9 decimals",
+                new_supply
+            )
+        );
+    }
+    assert_eq!(total_supply().await, 150_000_000_001);
+}
+
+async fn rebase_history(test_validator: &TestValidator, payer: &Keypair) {
+    let config = test_config_with_default_signer(test_validator, payer, &spl_token_2022::id());
+    let result = process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::CreateToken.into(),
+            "--enable-rebase",
+            "--rebase-initial-supply",
+            "100",
+        ],
+    )
+    .await;
+    let value: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+    let mint = value["commandOutput"]["address"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let mut signatures = vec![];
+    for new_supply in ["150", "200", "250"] {
+        let result = process_test_command(
+            &config,
+            payer,
+            &["spl-token", CommandName::Rebase.into(), &mint, new_supply],
+        )
+        .await;
+        let value: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+        signatures.push(value["commandOutput"]["transactionData"]["signature"].clone());
+    }
+
+    let result = process_test_command(
+        &config,
+        payer,
+        &["spl-token", CommandName::RebaseHistory.into(), &mint],
+    )
+    .await;
+    let value: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+    let rebases = value["rebases"].as_array().unwrap();
+    assert_eq!(rebases.len(), 3);
+    for ((rebase, (old_supply, new_supply)), signature) in rebases
+        .iter()
+        .zip([("200", "250"), ("150", "200"), ("100", "150")])
+        .zip(signatures.iter().rev())
+    {
+        assert_eq!(rebase["oldSupply"]["uiAmountString"], old_supply);
+        assert_eq!(rebase["newSupply"]["uiAmountString"], new_supply);
+        assert_eq!(rebase["totalShares"], "100000000000");
+        // without a history on the mint, every rebase comes from the logs
+        assert_eq!(&rebase["signature"], signature);
+        assert!(rebase["unixTimestamp"].is_i64());
+    }
+
+    let result = process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::RebaseHistory.into(),
+            &mint,
+            "--limit",
+            "2",
+        ],
+    )
+    .await;
+    let value: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+    let rebases = value["rebases"].as_array().unwrap();
+    assert_eq!(rebases.len(), 2);
+    assert_eq!(rebases[0]["newSupply"]["uiAmountString"], "250");
+}
+
+async fn offline_rebase_with_nonce(test_validator: &TestValidator, payer: &Keypair) {
+    let mut config = test_config_with_default_signer(test_validator, payer, &spl_token_2022::id());
+    let payer_path = NamedTempFile::new().unwrap();
+    write_keypair_file(payer, &payer_path).unwrap();
+    let rebase_authority = Keypair::new();
+    let rebase_authority_path = NamedTempFile::new().unwrap();
+    write_keypair_file(&rebase_authority, &rebase_authority_path).unwrap();
+
+    let result = process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::CreateToken.into(),
+            "--enable-rebase",
+            "--rebase-authority",
+            &rebase_authority.pubkey().to_string(),
+            "--rebase-initial-supply",
+            "100",
+        ],
+    )
+    .await;
+    let value: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+    let mint = Pubkey::from_str(value["commandOutput"]["address"].as_str().unwrap()).unwrap();
+
+    let nonce = create_nonce(&config, payer).await;
+    let nonce_account = config.rpc_client.get_account(&nonce).await.unwrap();
+    let start_hash_index = 4 + 4 + 32;
+    let blockhash = Hash::new(&nonce_account.data[start_hash_index..start_hash_index + 32]);
+
+    // the cold wallet only signs
+    let real_program_client = config.program_client;
+    let program_client: Arc<dyn ProgramClient<ProgramRpcClientSendTransaction>> = Arc::new(
+        ProgramOfflineClient::new(blockhash, ProgramRpcClientSendTransaction),
+    );
+    config.program_client = program_client;
+    let result = exec_test_cmd(
+        &config,
+        &[
+            "spl-token",
+            CommandName::Rebase.into(),
+            &mint.to_string(),
+            "150",
+            "--blockhash",
+            &blockhash.to_string(),
+            "--nonce",
+            &nonce.to_string(),
+            "--nonce-authority",
+            &payer.pubkey().to_string(),
+            "--sign-only",
+            "--mint-decimals",
+            &format!("{}", TEST_DECIMALS),
+            "--rebase-authority",
+            rebase_authority_path.path().to_str().unwrap(),
+            "--fee-payer",
+            &payer.pubkey().to_string(),
+        ],
+    )
+    .await
+    .unwrap();
+    let presigner = format!("{}=", rebase_authority.pubkey());
+    let signer = result
+        .lines()
+        .map(str::trim)
+        .find(|line| line.starts_with(&presigner))
+        .unwrap()
+        .to_string();
+    let absent_signers_position = result.find("Absent Signers").unwrap();
+    let absent_signers = result.get(absent_signers_position..).unwrap();
+    assert!(absent_signers.contains(&payer.pubkey().to_string()));
+    assert!(!absent_signers.contains(&rebase_authority.pubkey().to_string()));
+
+    // the online side adds the fee payer and nonce authority, and broadcasts
+    config.program_client = real_program_client;
+    exec_test_cmd(
+        &config,
+        &[
+            "spl-token",
+            CommandName::Rebase.into(),
+            &mint.to_string(),
+            "150",
+            "--blockhash",
+            &blockhash.to_string(),
+            "--nonce",
+            &nonce.to_string(),
+            "--nonce-authority",
+            payer_path.path().to_str().unwrap(),
+            "--mint-decimals",
+            &format!("{}", TEST_DECIMALS),
+            "--rebase-authority",
+            &rebase_authority.pubkey().to_string(),
+            "--signer",
+            &signer,
+            "--fee-payer",
+            payer_path.path().to_str().unwrap(),
+        ],
+    )
+    .await
+    .unwrap();
+
+    let account = config.rpc_client.get_account(&mint).await.unwrap();
+    let mint_account = StateWithExtensionsOwned::<Mint>::unpack(account.data).unwrap();
+    let extension = mint_account.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(u64::from(extension.total_supply), 150_000_000_000);
+    assert_eq!(u64::from(extension.total_shares), 100_000_000_000);
+
+    // and the nonce was consumed
+    let nonce_account = config.rpc_client.get_account(&nonce).await.unwrap();
+    assert_ne!(
+        Hash::new(&nonce_account.data[start_hash_index..start_hash_index + 32]),
+        blockhash
+    );
+}
+
+async fn create_rebased_token(config: &Config<'_>, payer: &Keypair) -> (Pubkey, Pubkey) {
+    let result = process_test_command(
+        config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::CreateToken.into(),
+            "--enable-rebase",
+        ],
+    )
+    .await;
+    let value: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+    let mint = Pubkey::from_str(value["commandOutput"]["address"].as_str().unwrap()).unwrap();
+    let account = create_associated_account(config, payer, &mint, &payer.pubkey()).await;
+    mint_tokens(config, payer, mint, 100.0, account)
+        .await
+        .unwrap();
+    // 100 shares are now worth 150 tokens
+    process_test_command(
+        config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::Rebase.into(),
+            &mint.to_string(),
+            "150",
+        ],
+    )
+    .await
+    .unwrap();
+    (mint, account)
+}
+
+async fn convert_shares(test_validator: &TestValidator, payer: &Keypair) {
+    let config = test_config_with_default_signer(test_validator, payer, &spl_token_2022::id());
+    let (mint, _) = create_rebased_token(&config, payer).await;
+
+    let result = process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::Convert.into(),
+            &mint.to_string(),
+            "--amount",
+            "1234.5",
+        ],
+    )
+    .await;
+    let value: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+    assert_eq!(value["amount"]["uiAmountString"], "1234.5");
+    assert_eq!(value["shares"], "823000000000");
+
+    let result = process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::Convert.into(),
+            &mint.to_string(),
+            "--shares",
+            "987",
+        ],
+    )
+    .await;
+    let value: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+    assert_eq!(value["shares"], "987");
+    // rounding down
+    assert_eq!(value["amount"]["amount"], "1480");
+
+    let token = create_token(&config, payer).await;
+    let err = process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::Convert.into(),
+            &token.to_string(),
+            "--shares",
+            "987",
+        ],
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!("Mint {} is not a rebasing mint", token)
+    );
+}
+
+async fn transfer_mint_and_burn_shares(test_validator: &TestValidator, payer: &Keypair) {
+    let config = test_config_with_default_signer(test_validator, payer, &spl_token_2022::id());
+    let (mint, source) = create_rebased_token(&config, payer).await;
+    let destination = create_auxiliary_account(&config, payer, mint).await;
+    let balance = |address: Pubkey| {
+        let config = &config;
+        async move {
+            let result = process_test_command(
+                config,
+                payer,
+                &[
+                    "spl-token",
+                    CommandName::Balance.into(),
+                    "--address",
+                    &address.to_string(),
+                ],
+            )
+            .await;
+            serde_json::from_str::<serde_json::Value>(&result.unwrap()).unwrap()
+        }
+    };
+
+    let result = process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::Transfer.into(),
+            &mint.to_string(),
+            "10000000000",
+            &destination.to_string(),
+            "--shares",
+        ],
+    )
+    .await;
+    let value: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+    assert_eq!(value["commandOutput"]["shares"], "10000000000");
+    assert_eq!(value["commandOutput"]["amount"]["uiAmountString"], "15");
+    let value = balance(destination).await;
+    assert_eq!(value["shares"], "10000000000");
+    assert_eq!(value["uiAmountString"], "15");
+    let value = balance(source).await;
+    assert_eq!(value["shares"], "90000000000");
+
+    process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::Mint.into(),
+            &mint.to_string(),
+            "2000000000",
+            &destination.to_string(),
+            "--shares",
+        ],
+    )
+    .await
+    .unwrap();
+    let value = balance(destination).await;
+    assert_eq!(value["shares"], "12000000000");
+    assert_eq!(value["uiAmountString"], "18");
+
+    let result = process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::Burn.into(),
+            &destination.to_string(),
+            "4000000000",
+            "--shares",
+        ],
+    )
+    .await;
+    let value: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+    assert_eq!(value["commandName"], "Burn");
+    assert_eq!(value["commandOutput"]["shares"], "4000000000");
+    assert_eq!(value["commandOutput"]["amount"]["uiAmountString"], "6");
+    let value = balance(destination).await;
+    assert_eq!(value["shares"], "8000000000");
+    assert_eq!(value["uiAmountString"], "12");
+
+    // sweeping every share leaves nothing behind, however the tokens round
+    process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::Rebase.into(),
+            &mint.to_string(),
+            "200.000000001",
+        ],
+    )
+    .await
+    .unwrap();
+    let result = process_test_command(
+        &config,
+        payer,
+        &[
+            "spl-token",
+            CommandName::Transfer.into(),
+            &mint.to_string(),
+            "ALL",
+            &destination.to_string(),
+            "--shares",
+        ],
+    )
+    .await;
+    let value: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+    assert_eq!(value["commandOutput"]["shares"], "90000000000");
+    let value = balance(source).await;
+    assert_eq!(value["shares"], "0");
+    assert_eq!(value["amount"], "0");
+    let value = balance(destination).await;
+    assert_eq!(value["shares"], "98000000000");
+
+    // shares only exist on rebasing mints
+    let token = create_token(&config, payer).await;
+    let source = create_associated_account(&config, payer, &token, &payer.pubkey()).await;
+    let destination = create_auxiliary_account(&config, payer, token).await;
+    mint_tokens(&config, payer, token, 100.0, source)
+        .await
+        .unwrap();
+    for args in [
+        vec![
+            CommandName::Transfer.into(),
+            token.to_string(),
+            "10".to_string(),
+            destination.to_string(),
+        ],
+        vec![
+            CommandName::Mint.into(),
+            token.to_string(),
+            "10".to_string(),
+            destination.to_string(),
+        ],
+        vec![
+            CommandName::Burn.into(),
+            source.to_string(),
+            "10".to_string(),
+        ],
+    ] {
+        let args = std::iter::once("spl-token".to_string())
+            .chain(args)
+            .chain(std::iter::once("--shares".to_string()));
+        let err = process_test_command(&config, payer, args)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("Mint {} is not a rebasing mint", token)
+        );
+    }
+}
+
 async fn set_interest_rate(test_validator: &TestValidator, payer: &Keypair) {
     let config = test_config_with_default_signer(test_validator, payer, &spl_token_2022::id());
     let initial_rate: i16 = 100;
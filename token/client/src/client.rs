@@ -2,13 +2,20 @@ use {
     async_trait::async_trait,
     solana_banks_interface::BanksTransactionResultWithSimulation,
     solana_program_test::{tokio::sync::Mutex, BanksClient, ProgramTestContext},
-    solana_rpc_client::nonblocking::rpc_client::RpcClient,
-    solana_rpc_client_api::response::RpcSimulateTransactionResult,
+    solana_rpc_client::{
+        nonblocking::rpc_client::RpcClient, rpc_client::GetConfirmedSignaturesForAddress2Config,
+    },
+    solana_rpc_client_api::{config::RpcTransactionConfig, response::RpcSimulateTransactionResult},
     solana_sdk::{
-        account::Account, hash::Hash, pubkey::Pubkey, signature::Signature,
-        transaction::Transaction,
+        account::Account,
+        clock::Slot,
+        hash::Hash,
+        pubkey::Pubkey,
+        signature::Signature,
+        transaction::{Transaction, TransactionError},
     },
-    std::{fmt, future::Future, pin::Pin, sync::Arc},
+    solana_transaction_status::UiTransactionEncoding,
+    std::{fmt, future::Future, pin::Pin, str::FromStr, sync::Arc},
 };
 
 type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
@@ -162,6 +169,16 @@ impl SimulateTransactionRpc for ProgramRpcClientSendTransaction {
 pub type ProgramClientError = Box<dyn std::error::Error + Send + Sync>;
 pub type ProgramClientResult<T> = Result<T, ProgramClientError>;
 
+/// Logs of a transaction, as returned by `ProgramClient::get_transaction_logs`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransactionLogs {
+    pub signature: Signature,
+    pub slot: Slot,
+    /// Error of a failed transaction, whose logs don't describe any change
+    pub err: Option<TransactionError>,
+    pub logs: Vec<String>,
+}
+
 /// Generic client interface for programs.
 #[async_trait]
 pub trait ProgramClient<ST>
@@ -183,6 +200,18 @@ where
         &self,
         transaction: &Transaction,
     ) -> ProgramClientResult<ST::SimulationOutput>;
+
+    /// Get the logs of up to `limit` transactions that referenced `address`,
+    /// newest first, starting before the transaction `before`. Fewer than
+    /// `limit` means there are no older ones.
+    ///
+    /// Only clients that can look transactions up by address support it.
+    async fn get_transaction_logs(
+        &self,
+        address: Pubkey,
+        before: Option<Signature>,
+        limit: usize,
+    ) -> ProgramClientResult<Vec<TransactionLogs>>;
 }
 
 enum ProgramBanksClientContext {
@@ -282,6 +311,15 @@ where
         })
         .await
     }
+
+    async fn get_transaction_logs(
+        &self,
+        _address: Pubkey,
+        _before: Option<Signature>,
+        _limit: usize,
+    ) -> ProgramClientResult<Vec<TransactionLogs>> {
+        Err("Unable to fetch transaction logs from a BanksClient".into())
+    }
 }
 
 /// Program client for `RpcClient` from crate `solana-client`.
@@ -339,6 +377,57 @@ where
             .await?
             .value)
     }
+
+    async fn get_transaction_logs(
+        &self,
+        address: Pubkey,
+        before: Option<Signature>,
+        limit: usize,
+    ) -> ProgramClientResult<Vec<TransactionLogs>> {
+        let statuses = self
+            .client
+            .get_signatures_for_address_with_config(
+                &address,
+                GetConfirmedSignaturesForAddress2Config {
+                    before,
+                    until: None,
+                    limit: Some(limit),
+                    commitment: Some(self.client.commitment()),
+                },
+            )
+            .await?;
+        let mut transactions = Vec::with_capacity(statuses.len());
+        for status in statuses {
+            let signature = Signature::from_str(&status.signature)?;
+            let logs = if status.err.is_some() {
+                vec![]
+            } else {
+                let transaction = self
+                    .client
+                    .get_transaction_with_config(
+                        &signature,
+                        RpcTransactionConfig {
+                            encoding: Some(UiTransactionEncoding::Base64),
+                            commitment: Some(self.client.commitment()),
+                            max_supported_transaction_version: Some(0),
+                        },
+                    )
+                    .await?;
+                transaction
+                    .transaction
+                    .meta
+                    .and_then(|meta| Option::from(meta.log_messages))
+                    .unwrap_or_default()
+            };
+            transactions.push(TransactionLogs {
+                signature,
+                slot: status.slot,
+                err: status.err,
+                logs,
+            });
+        }
+        Ok(transactions)
+    }
 }
 
 /// Program client for offline signing.
@@ -395,4 +484,13 @@ where
     async fn get_account(&self, _address: Pubkey) -> ProgramClientResult<Option<Account>> {
         Err("Unable to fetch account in offline mode".into())
     }
+
+    async fn get_transaction_logs(
+        &self,
+        _address: Pubkey,
+        _before: Option<Signature>,
+        _limit: usize,
+    ) -> ProgramClientResult<Vec<TransactionLogs>> {
+        Err("Unable to fetch transaction logs in offline mode".into())
+    }
 }
@@ -1,22 +1,36 @@
 use {
     crate::{
-        client::{ProgramClient, ProgramClientError, SendTransaction, SimulateTransaction},
+        client::{
+            ProgramClient, ProgramClientError, SendTransaction, SimulateTransaction,
+            TransactionLogs,
+        },
         proof_generation::transfer_with_fee_split_proof_data,
     },
-    futures::{future::join_all, try_join},
+    base64::{prelude::BASE64_STANDARD, Engine},
+    futures::{channel::mpsc, future::join_all, try_join, Stream, StreamExt},
     futures_util::TryFutureExt,
-    solana_program_test::tokio::time,
+    solana_account_decoder::UiAccountEncoding,
+    solana_program_test::{
+        tokio::{self, time},
+        BanksClientError,
+    },
+    solana_pubsub_client::nonblocking::pubsub_client::PubsubClient,
+    solana_rpc_client_api::{client_error::Error as RpcClientError, config::RpcAccountInfoConfig},
     solana_sdk::{
-        account::Account as BaseAccount,
+        account::{from_account, Account as BaseAccount},
+        clock::{Clock, Slot},
+        commitment_config::CommitmentConfig,
+        decode_error::DecodeError,
         hash::Hash,
-        instruction::{AccountMeta, Instruction},
+        instruction::{AccountMeta, Instruction, InstructionError},
         message::Message,
         program_error::ProgramError,
         program_pack::Pack,
         pubkey::Pubkey,
+        signature::Signature,
         signer::{signers::Signers, Signer, SignerError},
-        system_instruction,
-        transaction::Transaction,
+        system_instruction, sysvar,
+        transaction::{Transaction, TransactionError},
     },
     spl_associated_token_account::{
         get_associated_token_address_with_program_id,
@@ -43,8 +57,16 @@ use {
                 ConfidentialTransferFeeConfig,
             },
             cpi_guard, default_account_state, group_member_pointer, group_pointer,
-            interest_bearing_mint, memo_transfer, metadata_pointer, transfer_fee, transfer_hook,
-            BaseStateWithExtensions, Extension, ExtensionType, StateWithExtensionsOwned,
+            interest_bearing_mint, memo_transfer, metadata_pointer,
+            rebase_mint::{
+                self,
+                event::{parse_event, RebaseEvent as RebaseLogEvent},
+                instruction::RebaseSupplyBuilder,
+                RebaseHistory, RebaseHistoryEntry, RebaseMintConfig, RebasePreview, SupplyRebased,
+                REBASE_HISTORY_LEN,
+            },
+            transfer_fee, transfer_hook, BaseStateWithExtensions, Extension, ExtensionType,
+            StateWithExtensionsOwned,
         },
         instruction, offchain,
         proof::ProofLocation,
@@ -104,6 +126,18 @@ pub enum TokenError {
     MissingDecimals,
     #[error("decimals specified, but incorrect")]
     InvalidDecimals,
+    #[error("member mints don't match the group")]
+    InvalidGroupMembers,
+    #[error("mint is not a rebasing mint")]
+    MissingRebaseMintConfig,
+    #[error("rebasing mint config not cached")]
+    UncachedRebaseMintConfig,
+    #[error("invalid UI amount")]
+    InvalidUiAmount,
+    #[error("share slippage exceeded")]
+    ShareSlippageExceeded,
+    #[error("rebasing mint error: {0}")]
+    Rebase(spl_token_2022::error::TokenError),
 }
 impl PartialEq for TokenError {
     fn eq(&self, other: &Self) -> bool {
@@ -127,11 +161,74 @@ impl PartialEq for TokenError {
             (Self::MissingMemoSigner, Self::MissingMemoSigner) => true,
             (Self::MissingDecimals, Self::MissingDecimals) => true,
             (Self::InvalidDecimals, Self::InvalidDecimals) => true,
+            (Self::InvalidGroupMembers, Self::InvalidGroupMembers) => true,
+            (Self::MissingRebaseMintConfig, Self::MissingRebaseMintConfig) => true,
+            (Self::UncachedRebaseMintConfig, Self::UncachedRebaseMintConfig) => true,
+            (Self::InvalidUiAmount, Self::InvalidUiAmount) => true,
+            (Self::ShareSlippageExceeded, Self::ShareSlippageExceeded) => true,
+            (Self::Rebase(ref a), Self::Rebase(ref b)) => a == b,
             _ => false,
         }
     }
 }
 
+impl TokenError {
+    /// Turn a transaction that failed on a check of a rebasing mint into
+    /// `TokenError::Rebase`, leaving any other error as is
+    pub fn into_rebase_error(self) -> Self {
+        match &self {
+            Self::Client(client_error) => {
+                match transaction_error(client_error)
+                    .as_ref()
+                    .and_then(rebase_error_from_transaction_error)
+                {
+                    Some(rebase_error) => Self::Rebase(rebase_error),
+                    None => self,
+                }
+            }
+            _ => self,
+        }
+    }
+}
+
+/// Get the rebasing mint error behind a failed transaction, if it failed on
+/// one, so that callers don't need their own table of custom error codes
+pub fn rebase_error_from_transaction_error(
+    error: &TransactionError,
+) -> Option<spl_token_2022::error::TokenError> {
+    match error {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => {
+            spl_token_2022::error::TokenError::decode_custom_error_to_enum(*code)
+                .filter(spl_token_2022::error::TokenError::is_rebase_error)
+        }
+        _ => None,
+    }
+}
+
+/// Get the error that failed a transaction, from either kind of client
+fn transaction_error(error: &ProgramClientError) -> Option<TransactionError> {
+    if let Some(error) = error.downcast_ref::<BanksClientError>() {
+        match error {
+            BanksClientError::TransactionError(err)
+            | BanksClientError::SimulationError { err, .. } => Some(err.clone()),
+            _ => None,
+        }
+    } else if let Some(error) = error.downcast_ref::<RpcClientError>() {
+        error.get_transaction_error()
+    } else {
+        None
+    }
+}
+
+/// Get the custom program error that failed a transaction, from either kind of
+/// client
+fn custom_error_code(error: &ProgramClientError) -> Option<u32> {
+    match transaction_error(error)? {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => Some(code),
+        _ => None,
+    }
+}
+
 /// Encapsulates initializing an extension
 #[derive(Clone, Debug, PartialEq)]
 pub enum ExtensionInitializationParams {
@@ -180,6 +277,11 @@ pub enum ExtensionInitializationParams {
         authority: Option<Pubkey>,
         member_address: Option<Pubkey>,
     },
+    RebaseMintConfig {
+        supply_authority: Option<Pubkey>,
+        initial_supply: u64,
+    },
+    RebaseHistory,
 }
 impl ExtensionInitializationParams {
     /// Get the extension type associated with the init params
@@ -199,6 +301,8 @@ impl ExtensionInitializationParams {
             }
             Self::GroupPointer { .. } => ExtensionType::GroupPointer,
             Self::GroupMemberPointer { .. } => ExtensionType::GroupMemberPointer,
+            Self::RebaseMintConfig { .. } => ExtensionType::RebaseMintConfig,
+            Self::RebaseHistory => ExtensionType::RebaseHistory,
         }
     }
     /// Generate an appropriate initialization instruction for the given mint
@@ -308,7 +412,315 @@ impl ExtensionInitializationParams {
                 authority,
                 member_address,
             ),
+            Self::RebaseMintConfig {
+                supply_authority,
+                initial_supply,
+            } => {
+                let builder =
+                    rebase_mint::instruction::InitializeRebaseMintBuilder::new(token_program_id)
+                        .mint(mint)
+                        .initial_supply(initial_supply, 0);
+                match supply_authority {
+                    Some(supply_authority) => builder.supply_authority(&supply_authority),
+                    None => builder,
+                }
+                .build()
+            }
+            Self::RebaseHistory => {
+                rebase_mint::instruction::initialize_history(token_program_id, mint)
+            }
+        }
+    }
+}
+
+/// Token-metadata to initialize along with a rebasing mint
+#[derive(Clone, Debug, PartialEq)]
+pub struct RebaseMintMetadata {
+    pub update_authority: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+}
+
+/// Optional accounts of a rebase, which a rebasing mint requires depending
+/// on its configuration
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RebaseSupplyAccounts {
+    /// Reserve account, required if the mint has one
+    pub reserve_account: Option<Pubkey>,
+    /// Fee destination, required if the mint has one
+    pub fee_destination: Option<Pubkey>,
+    /// Payer for growing the mint's token-metadata, required if the mint has
+    /// `update_metadata_rate` set, which must be among the signers
+    pub metadata_payer: Option<Pubkey>,
+    /// Co-signer, required if the mint has one, which must be among the
+    /// signers
+    pub co_signer: Option<Pubkey>,
+}
+
+impl RebaseSupplyAccounts {
+    /// The accounts required by a mint with `config`, with `payer` paying
+    /// for any metadata growth
+    pub fn from_config(config: &RebaseMintConfig, payer: &Pubkey) -> Self {
+        Self {
+            reserve_account: config.reserve_account.into(),
+            fee_destination: config.fee_destination.into(),
+            metadata_payer: bool::from(config.update_metadata_rate).then_some(*payer),
+            co_signer: config.co_signer.into(),
+        }
+    }
+
+    /// Pass the accounts to a `RebaseSupply` or `RebaseSupplyUi` instruction
+    pub fn apply(&self, mut builder: RebaseSupplyBuilder) -> RebaseSupplyBuilder {
+        if let Some(reserve_account) = &self.reserve_account {
+            builder = builder.reserve_account(reserve_account);
+        }
+        if let Some(fee_destination) = &self.fee_destination {
+            builder = builder.fee_destination(fee_destination);
+        }
+        if let Some(metadata_payer) = &self.metadata_payer {
+            builder = builder.metadata_payer(metadata_payer);
+        }
+        if let Some(co_signer) = &self.co_signer {
+            builder = builder.co_signer(co_signer);
+        }
+        builder
+    }
+}
+
+/// Rebase of a rebasing mint, as reported by `Token::subscribe_rebase_events`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RebaseEvent {
+    /// Slot of the mint update that revealed the rebase
+    pub slot: Slot,
+    pub old_supply: u64,
+    pub new_supply: u64,
+    /// Total shares, which a rebase leaves untouched
+    pub total_shares: u64,
+}
+
+impl RebaseEvent {
+    /// A move from `previous` to `config` is a rebase if the supply changed
+    /// but the shares did not: mints and burns move both, and redelivered
+    /// updates move neither
+    fn from_change(
+        slot: Slot,
+        previous: &RebaseMintConfig,
+        config: &RebaseMintConfig,
+    ) -> Option<Self> {
+        let old_supply = u64::from(previous.total_supply);
+        let new_supply = u64::from(config.total_supply);
+        let total_shares = u64::from(config.total_shares);
+        if old_supply == new_supply || u64::from(previous.total_shares) != total_shares {
+            return None;
+        }
+        Some(Self {
+            slot,
+            old_supply,
+            new_supply,
+            total_shares,
+        })
+    }
+}
+
+/// Rebase of a rebasing mint, as returned by `Token::get_rebase_records`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RebaseRecord {
+    /// Unix timestamp of the rebase
+    pub unix_timestamp: i64,
+    pub old_supply: u64,
+    pub new_supply: u64,
+    /// Total shares after the rebase. Those read from the logs leave out the
+    /// shares issued for the rebase fee, if the mint charges one.
+    pub total_shares: u64,
+    /// Reason code the rebase was tagged with, or zero if unset
+    pub reason_code: u8,
+    /// Reference the rebase was tagged with, or zeroes if unset
+    pub reference: [u8; 32],
+    /// Transaction of the rebase, only known for rebases read from the logs
+    pub signature: Option<Signature>,
+}
+
+impl RebaseRecord {
+    /// Whether both records describe the same rebase, whichever source they
+    /// were read from
+    fn is_same_rebase(&self, other: &Self) -> bool {
+        self.unix_timestamp == other.unix_timestamp
+            && self.old_supply == other.old_supply
+            && self.new_supply == other.new_supply
+            && self.reason_code == other.reason_code
+            && self.reference == other.reference
+    }
+}
+
+impl From<&RebaseHistoryEntry> for RebaseRecord {
+    fn from(entry: &RebaseHistoryEntry) -> Self {
+        Self {
+            unix_timestamp: entry.unix_timestamp.into(),
+            old_supply: entry.old_supply.into(),
+            new_supply: entry.new_supply.into(),
+            total_shares: entry.new_shares.into(),
+            reason_code: entry.reason_code,
+            reference: entry.reference,
+            signature: None,
+        }
+    }
+}
+
+/// Decode the data logged by `program_id` itself, not by programs it invokes
+/// or that invoke it, in the order it was logged, as the fields of each
+/// `Program data:` line. Lines that aren't base64 are skipped.
+fn program_data_from_logs(logs: &[String], program_id: &Pubkey) -> Vec<Vec<Vec<u8>>> {
+    let program_id = program_id.to_string();
+    let mut invocations = vec![];
+    let mut program_data = vec![];
+    for log in logs {
+        let Some(log) = log.strip_prefix("Program ") else {
+            continue;
+        };
+        if let Some(data) = log.strip_prefix("data: ") {
+            if invocations.last() != Some(&program_id) {
+                continue;
+            }
+            if let Ok(fields) = data
+                .split(' ')
+                .map(|field| BASE64_STANDARD.decode(field))
+                .collect::<Result<Vec<_>, _>>()
+            {
+                program_data.push(fields);
+            }
+        } else if let Some((invoked_program_id, status)) = log.split_once(' ') {
+            if status.starts_with("invoke [") {
+                invocations.push(invoked_program_id.to_string());
+            } else if status == "success" || status.starts_with("failed") {
+                invocations.pop();
+            }
+        }
+    }
+    program_data
+}
+
+/// Pull the events of `mint` out of the logs of a transaction, in the order
+/// they were logged.
+///
+/// Only events logged by `program_id` itself count, as with
+/// `rebase_records_from_logs`.
+pub fn rebase_events_from_logs(
+    logs: &[String],
+    program_id: &Pubkey,
+    mint: &Pubkey,
+) -> Vec<RebaseLogEvent> {
+    program_data_from_logs(logs, program_id)
+        .iter()
+        .filter_map(|fields| {
+            let fields = fields.iter().map(Vec::as_slice).collect::<Vec<_>>();
+            parse_event(&fields).ok()
+        })
+        .filter(|(header, _)| header.mint == *mint)
+        .map(|(_, event)| event)
+        .collect()
+}
+
+/// Pull the rebases of `mint` out of the logs of a transaction, in the order
+/// they were logged, without their signature.
+///
+/// Only data logged by `program_id` itself counts, not by programs it invokes
+/// or that invoke it. Each rebase is found by its `RebaseTagged` event, which
+/// names the mint, and takes its shares from the bare `SupplyRebased` event
+/// logged just before.
+pub fn rebase_records_from_logs(
+    logs: &[String],
+    program_id: &Pubkey,
+    mint: &Pubkey,
+) -> Vec<RebaseRecord> {
+    let mut supply_rebased = None;
+    let mut records = vec![];
+    for fields in program_data_from_logs(logs, program_id) {
+        let fields = fields.iter().map(Vec::as_slice).collect::<Vec<_>>();
+        if let [field] = fields.as_slice() {
+            supply_rebased = bytemuck::try_pod_read_unaligned::<SupplyRebased>(field).ok();
+            continue;
+        }
+        let Ok((header, RebaseLogEvent::RebaseTagged(event))) = parse_event(&fields) else {
+            continue;
+        };
+        let supply_rebased = supply_rebased.take();
+        if header.mint != *mint {
+            continue;
+        }
+        records.push(RebaseRecord {
+            unix_timestamp: header.unix_timestamp.into(),
+            old_supply: event.previous_supply.into(),
+            new_supply: event.new_supply.into(),
+            total_shares: supply_rebased
+                .map(|supply_rebased| supply_rebased.total_shares.into())
+                .unwrap_or_default(),
+            reason_code: event.reason_code,
+            reference: event.reference,
+            signature: None,
+        });
+    }
+    records
+}
+
+/// Most transactions an RPC node returns per `getSignaturesForAddress` call
+const MAX_TRANSACTION_LOGS_PAGE_LEN: usize = 1_000;
+
+/// Delay before reconnecting a dropped rebase subscription
+const REBASE_SUBSCRIPTION_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Forward the rebases of `mint` to `sender` until the receiving end goes
+/// away, reconnecting to `websocket_url` whenever the subscription drops
+async fn forward_rebase_events(
+    websocket_url: String,
+    mint: Pubkey,
+    commitment: CommitmentConfig,
+    mut pubsub_client: PubsubClient,
+    mut previous: RebaseMintConfig,
+    sender: mpsc::UnboundedSender<RebaseEvent>,
+) {
+    let account_config = RpcAccountInfoConfig {
+        encoding: Some(UiAccountEncoding::Base64),
+        commitment: Some(commitment),
+        ..RpcAccountInfoConfig::default()
+    };
+    loop {
+        if let Ok((mut updates, unsubscribe)) = pubsub_client
+            .account_subscribe(&mint, Some(account_config.clone()))
+            .await
+        {
+            while let Some(update) = updates.next().await {
+                let Some(account) = update.value.decode::<BaseAccount>() else {
+                    continue;
+                };
+                let Ok(mint_info) = StateWithExtensionsOwned::<Mint>::unpack(account.data) else {
+                    continue;
+                };
+                let Ok(config) = mint_info.get_extension::<RebaseMintConfig>().copied() else {
+                    continue;
+                };
+                if let Some(event) =
+                    RebaseEvent::from_change(update.context.slot, &previous, &config)
+                {
+                    if sender.unbounded_send(event).is_err() {
+                        break;
+                    }
+                }
+                previous = config;
+            }
+            drop(updates);
+            unsubscribe().await;
         }
+        let _ = pubsub_client.shutdown().await;
+        pubsub_client = loop {
+            if sender.is_closed() {
+                return;
+            }
+            time::sleep(REBASE_SUBSCRIPTION_RETRY_DELAY).await;
+            if let Ok(pubsub_client) = PubsubClient::new(&websocket_url).await {
+                break pubsub_client;
+            }
+        };
     }
 }
 
@@ -339,6 +751,7 @@ pub struct Token<T> {
     nonce_blockhash: Option<Hash>,
     memo: Arc<RwLock<Option<TokenMemo>>>,
     transfer_hook_accounts: Option<Vec<AccountMeta>>,
+    rebase_mint_config: Arc<RwLock<Option<RebaseMintConfig>>>,
 }
 
 impl<T> fmt::Debug for Token<T> {
@@ -356,6 +769,10 @@ impl<T> fmt::Debug for Token<T> {
             .field("nonce_blockhash", &self.nonce_blockhash)
             .field("memo", &self.memo.read().unwrap())
             .field("transfer_hook_accounts", &self.transfer_hook_accounts)
+            .field(
+                "rebase_mint_config",
+                &self.rebase_mint_config.read().unwrap(),
+            )
             .finish()
     }
 }
@@ -402,6 +819,7 @@ where
             nonce_blockhash: None,
             memo: Arc::new(RwLock::new(None)),
             transfer_hook_accounts: None,
+            rebase_mint_config: Arc::new(RwLock::new(None)),
         }
     }
 
@@ -451,6 +869,13 @@ where
         self
     }
 
+    /// Cache a snapshot of the rebasing mint configuration for the `_cached`
+    /// conversions, instead of fetching it with `refresh_rebase_mint_config`
+    pub fn with_rebase_mint_config(self, config: RebaseMintConfig) -> Self {
+        *self.rebase_mint_config.write().unwrap() = Some(config);
+        self
+    }
+
     pub fn with_memo<M: AsRef<str>>(&self, memo: M, signers: Vec<Pubkey>) -> &Self {
         let mut w_memo = self.memo.write().unwrap();
         *w_memo = Some(TokenMemo {
@@ -505,6 +930,19 @@ where
         }
     }
 
+    /// Signing pubkeys other than a rebasing mint's co-signer, which signs
+    /// in its own place rather than as a multisig signer
+    fn without_co_signer<S: Signers>(
+        signing_keypairs: &S,
+        co_signer: Option<&Pubkey>,
+    ) -> Vec<Pubkey> {
+        signing_keypairs
+            .pubkeys()
+            .into_iter()
+            .filter(|pubkey| Some(pubkey) != co_signer)
+            .collect()
+    }
+
     async fn construct_tx<S: Signers>(
         &self,
         token_instructions: &[Instruction],
@@ -676,6 +1114,144 @@ where
         self.process_ixs(&instructions, signing_keypairs).await
     }
 
+    /// Create a rebasing mint with a metadata pointer to itself and its
+    /// initial token-metadata, funding the mint for the metadata up front
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_rebase_mint_with_metadata<S: Signers>(
+        &self,
+        mint_authority: &Pubkey,
+        freeze_authority: Option<&Pubkey>,
+        config: &rebase_mint::instruction::InitializeInstructionData,
+        update_authority: &Pubkey,
+        name: String,
+        symbol: String,
+        uri: String,
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        let decimals = self.decimals.ok_or(TokenError::MissingDecimals)?;
+
+        let token_metadata = TokenMetadata {
+            update_authority: Some(*update_authority).try_into()?,
+            mint: self.pubkey,
+            name,
+            symbol,
+            uri,
+            ..Default::default()
+        };
+        let (_, full_len) = rebase_mint::instruction::get_mint_len_with_metadata(&token_metadata)?;
+        let lamports = self
+            .client
+            .get_minimum_balance_for_rent_exemption(full_len)
+            .await
+            .map_err(TokenError::Client)?;
+
+        let instructions = rebase_mint::instruction::create_mint_with_metadata(
+            &self.program_id,
+            &self.payer.pubkey(),
+            &self.pubkey,
+            lamports,
+            mint_authority,
+            freeze_authority,
+            decimals,
+            config,
+            update_authority,
+            token_metadata.name,
+            token_metadata.symbol,
+            token_metadata.uri,
+        )?;
+
+        self.process_ixs(&instructions, signing_keypairs).await
+    }
+
+    /// Create a rebasing mint in a single transaction and return a handle to
+    /// it. If `metadata` is provided, the mint also gets a metadata pointer to
+    /// itself and its token-metadata.
+    ///
+    /// The mint and, with metadata, the mint authority must sign.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_rebase_mint<S: Signers>(
+        client: Arc<dyn ProgramClient<T>>,
+        program_id: &Pubkey,
+        mint: &Pubkey,
+        decimals: u8,
+        mint_authority: &Pubkey,
+        freeze_authority: Option<&Pubkey>,
+        supply_authority: Option<Pubkey>,
+        initial_supply: u64,
+        metadata: Option<RebaseMintMetadata>,
+        payer: Arc<dyn Signer>,
+        signing_keypairs: &S,
+    ) -> TokenResult<Self> {
+        let token = Self::new(client, program_id, mint, Some(decimals), payer);
+        let mut extension_initialization_params =
+            vec![ExtensionInitializationParams::RebaseMintConfig {
+                supply_authority,
+                initial_supply,
+            }];
+        let Some(metadata) = metadata else {
+            token
+                .create_mint(
+                    mint_authority,
+                    freeze_authority,
+                    extension_initialization_params,
+                    signing_keypairs,
+                )
+                .await?;
+            return Ok(token);
+        };
+
+        extension_initialization_params.push(ExtensionInitializationParams::MetadataPointer {
+            authority: Some(metadata.update_authority),
+            metadata_address: Some(*mint),
+        });
+        let token_metadata = TokenMetadata {
+            update_authority: Some(metadata.update_authority).try_into()?,
+            mint: *mint,
+            name: metadata.name,
+            symbol: metadata.symbol,
+            uri: metadata.uri,
+            ..Default::default()
+        };
+        // created at the size of the extensions, but funded for the metadata
+        // that gets reallocated into it
+        let (space, full_len) =
+            rebase_mint::instruction::get_mint_len_with_metadata(&token_metadata)?;
+        let mut instructions = vec![system_instruction::create_account(
+            &token.payer.pubkey(),
+            mint,
+            token
+                .client
+                .get_minimum_balance_for_rent_exemption(full_len)
+                .await
+                .map_err(TokenError::Client)?,
+            space as u64,
+            program_id,
+        )];
+        for params in extension_initialization_params {
+            instructions.push(params.instruction(program_id, mint)?);
+        }
+        instructions.push(instruction::initialize_mint(
+            program_id,
+            mint,
+            mint_authority,
+            freeze_authority,
+            decimals,
+        )?);
+        instructions.push(spl_token_metadata_interface::instruction::initialize(
+            program_id,
+            mint,
+            &metadata.update_authority,
+            mint,
+            mint_authority,
+            token_metadata.name,
+            token_metadata.symbol,
+            token_metadata.uri,
+        ));
+        token.process_ixs(&instructions, signing_keypairs).await?;
+
+        Ok(token)
+    }
+
     /// Create native mint
     pub async fn create_native_mint(
         client: Arc<dyn ProgramClient<T>>,
@@ -762,9 +1338,7 @@ where
         extensions: Vec<ExtensionType>,
     ) -> TokenResult<T::Output> {
         let state = self.get_mint_info().await?;
-        let mint_extensions: Vec<ExtensionType> = state.get_extension_types()?;
-        let mut required_extensions =
-            ExtensionType::get_required_init_account_extensions(&mint_extensions);
+        let mut required_extensions = rebase_mint::get_required_init_account_extensions(&state)?;
         for extension_type in extensions.into_iter() {
             if !required_extensions.contains(&extension_type) {
                 required_extensions.push(extension_type);
@@ -834,6 +1408,263 @@ where
         self.unpack_mint_info(account)
     }
 
+    fn unpack_rebase_mint_config(
+        mint_info: &StateWithExtensionsOwned<Mint>,
+    ) -> TokenResult<RebaseMintConfig> {
+        if !mint_info
+            .get_extension_types()?
+            .contains(&ExtensionType::RebaseMintConfig)
+        {
+            return Err(TokenError::MissingRebaseMintConfig);
+        }
+        Ok(*mint_info.get_extension::<RebaseMintConfig>()?)
+    }
+
+    /// Retrieve the rebasing mint configuration of the mint
+    pub async fn get_rebase_mint_config(&self) -> TokenResult<RebaseMintConfig> {
+        let mint_info = self.get_mint_info().await?;
+        Self::unpack_rebase_mint_config(&mint_info)
+    }
+
+    /// Fetch the rebasing mint configuration and cache it for the `_cached`
+    /// conversions
+    pub async fn refresh_rebase_mint_config(&self) -> TokenResult<RebaseMintConfig> {
+        let config = self.get_rebase_mint_config().await?;
+        *self.rebase_mint_config.write().unwrap() = Some(config);
+        Ok(config)
+    }
+
+    fn convert_cached(
+        &self,
+        convert: impl FnOnce(&RebaseMintConfig) -> Option<u64>,
+    ) -> TokenResult<u64> {
+        let r_config = self.rebase_mint_config.read().unwrap();
+        let config = r_config
+            .as_ref()
+            .ok_or(TokenError::UncachedRebaseMintConfig)?;
+        convert(config)
+            .ok_or_else(|| ProgramError::from(spl_token_2022::error::TokenError::Overflow).into())
+    }
+
+    /// Convert an amount of tokens to shares at the cached ratio, as
+    /// `ConvertAmountToShares` does on-chain.
+    ///
+    /// The cache goes stale with every rebase, mint and burn, so the result
+    /// only matches the program until then. Call `refresh_rebase_mint_config`
+    /// to catch up.
+    pub fn amount_to_shares_cached(&self, amount: u64) -> TokenResult<u64> {
+        self.convert_cached(|config| config.amount_to_shares(amount))
+    }
+
+    /// Convert an amount of shares to tokens at the cached ratio, as
+    /// `ConvertSharesToAmount` does on-chain, with the same staleness caveats
+    /// as `amount_to_shares_cached`
+    pub fn shares_to_amount_cached(&self, shares: u64) -> TokenResult<u64> {
+        self.convert_cached(|config| config.shares_to_amount(shares))
+    }
+
+    /// Retrieve the rebasing mint configuration of the mint in its display
+    /// form
+    #[cfg(feature = "serde-traits")]
+    pub async fn get_ui_rebase_mint_config(
+        &self,
+    ) -> TokenResult<rebase_mint::ui::UiRebaseMintConfig> {
+        let config = self.get_rebase_mint_config().await?;
+        Ok((&config).into())
+    }
+
+    /// Retrieve the rebases kept by the mint's `RebaseHistory` extension,
+    /// newest first, as returned on-chain by `GetRebaseHistory`
+    pub async fn get_rebase_history(
+        &self,
+    ) -> TokenResult<rebase_mint::instruction::RebaseHistoryData> {
+        let mint_info = self.get_mint_info().await?;
+        let history = mint_info.get_extension::<rebase_mint::RebaseHistory>()?;
+        Ok(history.into())
+    }
+
+    /// Retrieve up to `limit` rebases of the mint, newest first.
+    ///
+    /// They're read from the mint's `RebaseHistory` when it holds enough of
+    /// them, and otherwise from the logs of the mint's transactions, page by
+    /// page, which only clients that can look transactions up by address
+    /// support. The logs also fill in the signatures of the rebases read from
+    /// the history.
+    pub async fn get_rebase_records(&self, limit: usize) -> TokenResult<Vec<RebaseRecord>> {
+        let mint_info = self.get_mint_info().await?;
+        Self::unpack_rebase_mint_config(&mint_info)?;
+        let (mut records, complete) = match mint_info.get_extension::<RebaseHistory>() {
+            Ok(history) => (
+                history
+                    .entries()
+                    .map(RebaseRecord::from)
+                    .collect::<Vec<_>>(),
+                u64::from(history.count) <= REBASE_HISTORY_LEN as u64,
+            ),
+            Err(_) => (vec![], false),
+        };
+        records.reverse();
+        records.truncate(limit);
+        if complete || records.len() == limit {
+            return Ok(records);
+        }
+
+        // the newest rebases in the logs are the ones the history holds
+        let kept = records.len();
+        let mut logged = 0;
+        let mut before = None;
+        // as long as the limit, in case every transaction is a rebase
+        let page_len = limit.min(MAX_TRANSACTION_LOGS_PAGE_LEN);
+        'pages: while records.len() < limit {
+            let page = self
+                .client
+                .get_transaction_logs(self.pubkey, before, page_len)
+                .await
+                .map_err(TokenError::Client)?;
+            let Some(last) = page.last() else {
+                break;
+            };
+            before = Some(last.signature);
+            let last_page = page.len() < page_len;
+
+            for TransactionLogs {
+                signature,
+                err,
+                logs,
+                ..
+            } in page
+            {
+                if err.is_some() {
+                    continue;
+                }
+                for record in rebase_records_from_logs(&logs, &self.program_id, &self.pubkey)
+                    .into_iter()
+                    .rev()
+                {
+                    if logged < kept {
+                        if records[logged].is_same_rebase(&record) {
+                            records[logged].signature = Some(signature);
+                        }
+                        logged += 1;
+                        continue;
+                    }
+                    records.push(RebaseRecord {
+                        signature: Some(signature),
+                        ..record
+                    });
+                    if records.len() == limit {
+                        break 'pages;
+                    }
+                }
+            }
+            if last_page {
+                break;
+            }
+        }
+        Ok(records)
+    }
+
+    async fn convert_at(
+        &self,
+        convert: impl FnOnce(
+            &RebaseMintConfig,
+            &rebase_mint::RebaseHistory,
+        ) -> Result<u64, ProgramError>,
+    ) -> TokenResult<u64> {
+        let mint_info = self.get_mint_info().await?;
+        let config = Self::unpack_rebase_mint_config(&mint_info)?;
+        let history = mint_info.get_extension::<rebase_mint::RebaseHistory>()?;
+        Ok(convert(&config, history)?)
+    }
+
+    /// Convert an amount of shares to the amount of tokens they were worth at
+    /// `unix_timestamp`, from the mint's `RebaseHistory`
+    pub async fn shares_to_amount_at(&self, shares: u64, unix_timestamp: i64) -> TokenResult<u64> {
+        self.convert_at(|config, history| {
+            config.shares_to_amount_at(history, shares, unix_timestamp)
+        })
+        .await
+    }
+
+    /// Convert an amount of tokens to the number of shares that represented
+    /// it at `unix_timestamp`, from the mint's `RebaseHistory`
+    pub async fn amount_to_shares_at(&self, amount: u64, unix_timestamp: i64) -> TokenResult<u64> {
+        self.convert_at(|config, history| {
+            config.amount_to_shares_at(history, amount, unix_timestamp)
+        })
+        .await
+    }
+
+    async fn get_rebased_balance_and_decimals(&self, account: &Pubkey) -> TokenResult<(u64, u8)> {
+        let (account_info, mint_info) =
+            try_join!(self.get_account_info(account), self.get_mint_info())?;
+        let config = Self::unpack_rebase_mint_config(&mint_info)?;
+        let amount = if config.is_lazy() {
+            let lazy_account = account_info.get_extension::<rebase_mint::LazyRebaseAccount>()?;
+            config.lazy_amount(
+                account_info.base.amount,
+                lazy_account.last_seen_rebase_index.into(),
+            )
+        } else {
+            let rebase_account = account_info.get_extension::<rebase_mint::RebaseAccount>()?;
+            if rebase_account.is_exempt() {
+                Some(account_info.base.amount)
+            } else {
+                config.shares_to_amount(rebase_account.shares.into())
+            }
+        }
+        .ok_or(ProgramError::from(
+            spl_token_2022::error::TokenError::Overflow,
+        ))?;
+        Ok((amount, mint_info.base.decimals))
+    }
+
+    /// Retrieve the amount of tokens that an account of the rebasing mint
+    /// holds at the current ratio, as returned by `GetRebasedBalance`
+    pub async fn get_rebased_balance(&self, account: &Pubkey) -> TokenResult<u64> {
+        let (amount, _) = self.get_rebased_balance_and_decimals(account).await?;
+        Ok(amount)
+    }
+
+    /// Retrieve the UI amount of tokens that an account of the rebasing mint
+    /// holds at the current ratio, as returned by `GetRebasedBalance`
+    pub async fn get_rebased_ui_balance(&self, account: &Pubkey) -> TokenResult<String> {
+        let (amount, decimals) = self.get_rebased_balance_and_decimals(account).await?;
+        Ok(spl_token_2022::amount_to_ui_amount_string_trimmed(
+            amount, decimals,
+        ))
+    }
+
+    /// Subscribe to the rebases of the mint through the websocket at
+    /// `websocket_url`, as they land at `commitment`.
+    ///
+    /// The mint is watched with `accountSubscribe`, and every update that
+    /// moves the supply but not the shares comes out of the stream as a
+    /// rebase, so a rebase landing in the same update as a mint or burn goes
+    /// unreported. When the websocket drops, the subscription reconnects, and
+    /// a rebase that landed in the meantime is reported with the next update
+    /// of the mint. The stream ends when dropped.
+    pub async fn subscribe_rebase_events(
+        &self,
+        websocket_url: &str,
+        commitment: CommitmentConfig,
+    ) -> TokenResult<impl Stream<Item = RebaseEvent>> {
+        let pubsub_client = PubsubClient::new(websocket_url)
+            .await
+            .map_err(|e| TokenError::Client(Box::new(e)))?;
+        let config = self.get_rebase_mint_config().await?;
+        let (sender, receiver) = mpsc::unbounded();
+        tokio::spawn(forward_rebase_events(
+            websocket_url.to_string(),
+            self.pubkey,
+            commitment,
+            pubsub_client,
+            config,
+            sender,
+        ));
+        Ok(receiver)
+    }
+
     /// Retrieve account information.
     pub async fn get_account_info(
         &self,
@@ -868,19 +1699,52 @@ where
         }
     }
 
-    /// Assign a new authority to the account.
-    pub async fn set_authority<S: Signers>(
+    /// Retrieve the associated account of the rebasing mint, or create one if
+    /// not found.
+    ///
+    /// The associated token account program sizes the account with
+    /// `GetAccountDataSize`, which includes the `RebaseAccount` extension
+    /// that the mint requires. The account is created idempotently, so
+    /// racing with another creation of the same account doesn't fail.
+    pub async fn get_or_create_rebase_ata(
         &self,
-        account: &Pubkey,
-        authority: &Pubkey,
-        new_authority: Option<&Pubkey>,
-        authority_type: instruction::AuthorityType,
-        signing_keypairs: &S,
-    ) -> TokenResult<T::Output> {
-        let signing_pubkeys = signing_keypairs.pubkeys();
-        let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
-
-        self.process_ixs(
+        owner: &Pubkey,
+    ) -> TokenResult<StateWithExtensionsOwned<Account>> {
+        self.get_rebase_mint_config().await?;
+        let account = self.get_associated_token_address(owner);
+        match self.get_account_info(&account).await {
+            Ok(account) => Ok(account),
+            // AccountInvalidOwner is possible if account already received some lamports.
+            Err(TokenError::AccountNotFound) | Err(TokenError::AccountInvalidOwner) => {
+                self.process_ixs::<[&dyn Signer; 0]>(
+                    &[create_associated_token_account_idempotent(
+                        &self.payer.pubkey(),
+                        owner,
+                        &self.pubkey,
+                        &self.program_id,
+                    )],
+                    &[],
+                )
+                .await?;
+                self.get_account_info(&account).await
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Assign a new authority to the account.
+    pub async fn set_authority<S: Signers>(
+        &self,
+        account: &Pubkey,
+        authority: &Pubkey,
+        new_authority: Option<&Pubkey>,
+        authority_type: instruction::AuthorityType,
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        let signing_pubkeys = signing_keypairs.pubkeys();
+        let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
+
+        self.process_ixs(
             &[instruction::set_authority(
                 &self.program_id,
                 account,
@@ -1454,76 +2318,565 @@ where
         .await
     }
 
-    /// Set transfer fee
-    pub async fn set_transfer_fee<S: Signers>(
+    /// Set transfer fee
+    pub async fn set_transfer_fee<S: Signers>(
+        &self,
+        authority: &Pubkey,
+        transfer_fee_basis_points: u16,
+        maximum_fee: u64,
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        let signing_pubkeys = signing_keypairs.pubkeys();
+        let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
+
+        self.process_ixs(
+            &[transfer_fee::instruction::set_transfer_fee(
+                &self.program_id,
+                &self.pubkey,
+                authority,
+                &multisig_signers,
+                transfer_fee_basis_points,
+                maximum_fee,
+            )?],
+            signing_keypairs,
+        )
+        .await
+    }
+
+    /// Set default account state on mint
+    pub async fn set_default_account_state<S: Signers>(
+        &self,
+        authority: &Pubkey,
+        state: &AccountState,
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        let signing_pubkeys = signing_keypairs.pubkeys();
+        let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
+
+        self.process_ixs(
+            &[
+                default_account_state::instruction::update_default_account_state(
+                    &self.program_id,
+                    &self.pubkey,
+                    authority,
+                    &multisig_signers,
+                    state,
+                )?,
+            ],
+            signing_keypairs,
+        )
+        .await
+    }
+
+    /// Harvest withheld tokens to mint
+    pub async fn harvest_withheld_tokens_to_mint(
+        &self,
+        sources: &[&Pubkey],
+    ) -> TokenResult<T::Output> {
+        self.process_ixs::<[&dyn Signer; 0]>(
+            &[transfer_fee::instruction::harvest_withheld_tokens_to_mint(
+                &self.program_id,
+                &self.pubkey,
+                sources,
+            )?],
+            &[],
+        )
+        .await
+    }
+
+    /// Withdraw withheld tokens from mint
+    pub async fn withdraw_withheld_tokens_from_mint<S: Signers>(
+        &self,
+        destination: &Pubkey,
+        authority: &Pubkey,
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        let signing_pubkeys = signing_keypairs.pubkeys();
+        let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
+
+        self.process_ixs(
+            &[
+                transfer_fee::instruction::withdraw_withheld_tokens_from_mint(
+                    &self.program_id,
+                    &self.pubkey,
+                    destination,
+                    authority,
+                    &multisig_signers,
+                )?,
+            ],
+            signing_keypairs,
+        )
+        .await
+    }
+
+    /// Withdraw withheld tokens from accounts
+    pub async fn withdraw_withheld_tokens_from_accounts<S: Signers>(
+        &self,
+        destination: &Pubkey,
+        authority: &Pubkey,
+        sources: &[&Pubkey],
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        let signing_pubkeys = signing_keypairs.pubkeys();
+        let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
+
+        self.process_ixs(
+            &[
+                transfer_fee::instruction::withdraw_withheld_tokens_from_accounts(
+                    &self.program_id,
+                    &self.pubkey,
+                    destination,
+                    authority,
+                    &multisig_signers,
+                    sources,
+                )?,
+            ],
+            signing_keypairs,
+        )
+        .await
+    }
+
+    /// Reallocate a token account to be large enough for a set of
+    /// ExtensionTypes
+    pub async fn reallocate<S: Signers>(
+        &self,
+        account: &Pubkey,
+        authority: &Pubkey,
+        extension_types: &[ExtensionType],
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        let signing_pubkeys = signing_keypairs.pubkeys();
+        let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
+
+        self.process_ixs(
+            &[instruction::reallocate(
+                &self.program_id,
+                account,
+                &self.payer.pubkey(),
+                authority,
+                &multisig_signers,
+                extension_types,
+            )?],
+            signing_keypairs,
+        )
+        .await
+    }
+
+    /// Require memos on transfers into this account
+    pub async fn enable_required_transfer_memos<S: Signers>(
+        &self,
+        account: &Pubkey,
+        authority: &Pubkey,
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        let signing_pubkeys = signing_keypairs.pubkeys();
+        let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
+
+        self.process_ixs(
+            &[memo_transfer::instruction::enable_required_transfer_memos(
+                &self.program_id,
+                account,
+                authority,
+                &multisig_signers,
+            )?],
+            signing_keypairs,
+        )
+        .await
+    }
+
+    /// Stop requiring memos on transfers into this account
+    pub async fn disable_required_transfer_memos<S: Signers>(
+        &self,
+        account: &Pubkey,
+        authority: &Pubkey,
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        let signing_pubkeys = signing_keypairs.pubkeys();
+        let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
+
+        self.process_ixs(
+            &[memo_transfer::instruction::disable_required_transfer_memos(
+                &self.program_id,
+                account,
+                authority,
+                &multisig_signers,
+            )?],
+            signing_keypairs,
+        )
+        .await
+    }
+
+    /// Prevent unsafe usage of token account through CPI
+    pub async fn enable_cpi_guard<S: Signers>(
+        &self,
+        account: &Pubkey,
+        authority: &Pubkey,
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        let signing_pubkeys = signing_keypairs.pubkeys();
+        let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
+
+        self.process_ixs(
+            &[cpi_guard::instruction::enable_cpi_guard(
+                &self.program_id,
+                account,
+                authority,
+                &multisig_signers,
+            )?],
+            signing_keypairs,
+        )
+        .await
+    }
+
+    /// Stop preventing unsafe usage of token account through CPI
+    pub async fn disable_cpi_guard<S: Signers>(
+        &self,
+        account: &Pubkey,
+        authority: &Pubkey,
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        let signing_pubkeys = signing_keypairs.pubkeys();
+        let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
+
+        self.process_ixs(
+            &[cpi_guard::instruction::disable_cpi_guard(
+                &self.program_id,
+                account,
+                authority,
+                &multisig_signers,
+            )?],
+            signing_keypairs,
+        )
+        .await
+    }
+
+    /// Update interest rate
+    pub async fn update_interest_rate<S: Signers>(
+        &self,
+        authority: &Pubkey,
+        new_rate: i16,
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        let signing_pubkeys = signing_keypairs.pubkeys();
+        let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
+
+        self.process_ixs(
+            &[interest_bearing_mint::instruction::update_rate(
+                &self.program_id,
+                self.get_address(),
+                authority,
+                &multisig_signers,
+                new_rate,
+            )?],
+            signing_keypairs,
+        )
+        .await
+    }
+
+    /// Fetch the optional accounts that a rebase of the mint requires, with
+    /// the payer paying for any metadata growth
+    pub async fn get_rebase_supply_accounts(&self) -> TokenResult<RebaseSupplyAccounts> {
+        let config = self.get_rebase_mint_config().await?;
+        Ok(RebaseSupplyAccounts::from_config(
+            &config,
+            &self.payer.pubkey(),
+        ))
+    }
+
+    /// Rebase the supply of a rebasing mint, passing along the accounts its
+    /// configuration requires. If the mint has a co-signer, it must be among
+    /// the signers.
+    pub async fn rebase_supply<S: Signers>(
+        &self,
+        authority: &Pubkey,
+        new_supply: u64,
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        let accounts = self.get_rebase_supply_accounts().await?;
+        self.rebase_supply_with_accounts(authority, &accounts, new_supply, signing_keypairs)
+            .await
+    }
+
+    /// Preview a rebase of the supply of a rebasing mint, returning the
+    /// outcome and the configuration as the program would store it.
+    ///
+    /// Runs the same computation as the program, but can't check the
+    /// reserve account or the time of the first allowed rebase.
+    pub async fn preview_rebase_supply(
+        &self,
+        new_supply: u64,
+    ) -> TokenResult<(RebasePreview, RebaseMintConfig)> {
+        let config = self.get_rebase_mint_config().await?;
+        let preview = config.preview_rebase(new_supply)?;
+        Ok(config.preview_with_fee(&preview)?)
+    }
+
+    /// Rebase the supply of a rebasing mint with the given accounts.
+    ///
+    /// Doesn't fetch the mint, so it also works with an offline client, for
+    /// example to sign a rebase against a durable nonce.
+    ///
+    /// With a multisig supply authority, members can sign separately: each
+    /// one signs offline, passing `NullSigner`s for the other members in the
+    /// same order so that every call builds the same message, and the
+    /// signatures are then gathered into one transaction.
+    pub async fn rebase_supply_with_accounts<S: Signers>(
+        &self,
+        authority: &Pubkey,
+        accounts: &RebaseSupplyAccounts,
+        new_supply: u64,
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        self.rebase_supply_with_reason(
+            authority,
+            accounts,
+            new_supply,
+            0,
+            [0; 32],
+            signing_keypairs,
+        )
+        .await
+    }
+
+    /// Rebase the supply of a rebasing mint like
+    /// `rebase_supply_with_accounts`, tagging the rebase with a reason code
+    /// and a reference, either of which may be zero to leave it unset
+    pub async fn rebase_supply_with_reason<S: Signers>(
+        &self,
+        authority: &Pubkey,
+        accounts: &RebaseSupplyAccounts,
+        new_supply: u64,
+        reason_code: u8,
+        reference: [u8; 32],
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        self.rebase_supply_with_deadline(
+            authority,
+            accounts,
+            new_supply,
+            reason_code,
+            reference,
+            0,
+            signing_keypairs,
+        )
+        .await
+    }
+
+    /// Rebase the supply of a rebasing mint like `rebase_supply_with_reason`,
+    /// failing with `RebaseExpired` if the rebase lands after
+    /// `not_after_unix_timestamp`, or never expiring with zero
+    #[allow(clippy::too_many_arguments)]
+    pub async fn rebase_supply_with_deadline<S: Signers>(
+        &self,
+        authority: &Pubkey,
+        accounts: &RebaseSupplyAccounts,
+        new_supply: u64,
+        reason_code: u8,
+        reference: [u8; 32],
+        not_after_unix_timestamp: i64,
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        let builder = RebaseSupplyBuilder::new(&self.program_id, new_supply)
+            .reason_code(reason_code)
+            .reference(reference)
+            .not_after_unix_timestamp(not_after_unix_timestamp);
+        self.process_rebase_supply(builder, authority, accounts, signing_keypairs)
+            .await
+    }
+
+    /// Rebase the supply of a rebasing mint like
+    /// `rebase_supply_with_deadline`, to `ui_supply` in UI units, which the
+    /// program converts with the mint's decimals, failing with
+    /// `RebaseUiSupplyMalformed` if it isn't a plain decimal amount
+    #[allow(clippy::too_many_arguments)]
+    pub async fn rebase_supply_ui<S: Signers>(
+        &self,
+        authority: &Pubkey,
+        accounts: &RebaseSupplyAccounts,
+        ui_supply: &str,
+        reason_code: u8,
+        reference: [u8; 32],
+        not_after_unix_timestamp: i64,
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        let builder = RebaseSupplyBuilder::new_ui(&self.program_id, ui_supply)
+            .reason_code(reason_code)
+            .reference(reference)
+            .not_after_unix_timestamp(not_after_unix_timestamp);
+        self.process_rebase_supply(builder, authority, accounts, signing_keypairs)
+            .await
+    }
+
+    /// Finish a rebase with the mint, the authority and `accounts`, and send
+    /// it
+    async fn process_rebase_supply<S: Signers>(
+        &self,
+        builder: RebaseSupplyBuilder,
+        authority: &Pubkey,
+        accounts: &RebaseSupplyAccounts,
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        let signing_pubkeys =
+            Self::without_co_signer(signing_keypairs, accounts.co_signer.as_ref());
+        let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
+        let builder = accounts
+            .apply(builder)
+            .mint(self.get_address())
+            .authority(authority)
+            .multisig_signers(&multisig_signers);
+
+        self.process_ixs(&[builder.build()?], signing_keypairs)
+            .await
+            .map_err(TokenError::into_rebase_error)
+    }
+
+    /// Rebase the supply of a rebasing mint after running the program's
+    /// checks locally, so that a rebase bound to fail isn't sent.
+    ///
+    /// Checks the rebase authority, the co-signature, the required memo, the
+    /// time of the first allowed rebase against the cluster clock, the new
+    /// supply against the outstanding shares and the reserve. A failed check
+    /// returns the error the program would have failed with, without sending
+    /// anything. The rebase passes every account the mint requires.
+    ///
+    /// The mint can still change between the checks and the rebase landing.
+    pub async fn safe_rebase_supply<S: Signers>(
+        &self,
+        authority: &Pubkey,
+        new_supply: u64,
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        self.safe_rebase_supply_with_deadline(authority, new_supply, 0, signing_keypairs)
+            .await
+    }
+
+    /// Rebase the supply of a rebasing mint like `safe_rebase_supply`, failing
+    /// with `RebaseExpired` if the rebase lands after
+    /// `not_after_unix_timestamp`, or never expiring if it's 0.
+    ///
+    /// The deadline bounds how long the mint can drift from the state the
+    /// new supply was computed against before the rebase lands.
+    pub async fn safe_rebase_supply_with_deadline<S: Signers>(
+        &self,
+        authority: &Pubkey,
+        new_supply: u64,
+        not_after_unix_timestamp: i64,
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        let mint_info = self.get_mint_info().await?;
+        let config = Self::unpack_rebase_mint_config(&mint_info)?;
+
+        let rebase_authority = config.rebase_authority_for(
+            authority,
+            Option::<Pubkey>::from(mint_info.base.mint_authority),
+        )?;
+        if rebase_authority != *authority {
+            return Err(
+                ProgramError::from(spl_token_2022::error::TokenError::OwnerMismatch).into(),
+            );
+        }
+        let accounts = RebaseSupplyAccounts::from_config(&config, &self.payer.pubkey());
+        if let Some(co_signer) = accounts.co_signer {
+            if !signing_keypairs.pubkeys().contains(&co_signer) {
+                return Err(ProgramError::from(
+                    spl_token_2022::error::TokenError::RebaseCoSignerMismatch,
+                )
+                .into());
+            }
+        }
+        if bool::from(config.require_rebase_memo) && self.memo.read().unwrap().is_none() {
+            return Err(
+                ProgramError::from(spl_token_2022::error::TokenError::RebaseMemoRequired).into(),
+            );
+        }
+
+        let clock_account = self.get_account(sysvar::clock::id()).await?;
+        let clock =
+            from_account::<Clock, _>(&clock_account).ok_or(ProgramError::InvalidAccountData)?;
+        if !config.is_rebase_allowed_at(clock.unix_timestamp) {
+            return Err(
+                ProgramError::from(spl_token_2022::error::TokenError::RebaseNotYetAllowed).into(),
+            );
+        }
+        if not_after_unix_timestamp != 0 && clock.unix_timestamp > not_after_unix_timestamp {
+            return Err(
+                ProgramError::from(spl_token_2022::error::TokenError::RebaseExpired).into(),
+            );
+        }
+
+        config.preview_rebase(new_supply)?;
+        if let Some(reserve_account) = accounts.reserve_account {
+            let reserve = self.get_account(reserve_account).await?;
+            let reserve = StateWithExtensionsOwned::<Account>::unpack(reserve.data)?;
+            config.check_reserve(new_supply, reserve.base.amount)?;
+        }
+
+        self.process_rebase_supply(
+            RebaseSupplyBuilder::new(&self.program_id, new_supply)
+                .not_after_unix_timestamp(not_after_unix_timestamp),
+            authority,
+            &accounts,
+            signing_keypairs,
+        )
+        .await
+    }
+
+    /// Set the rebase fee of a rebasing mint and the token account credited
+    /// with it, or charge no fee with `None`
+    pub async fn set_rebase_fee<S: Signers>(
         &self,
         authority: &Pubkey,
-        transfer_fee_basis_points: u16,
-        maximum_fee: u64,
+        rebase_fee_bps: u16,
+        fee_destination: Option<Pubkey>,
         signing_keypairs: &S,
     ) -> TokenResult<T::Output> {
         let signing_pubkeys = signing_keypairs.pubkeys();
         let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
 
         self.process_ixs(
-            &[transfer_fee::instruction::set_transfer_fee(
+            &[rebase_mint::instruction::set_rebase_fee(
                 &self.program_id,
-                &self.pubkey,
+                self.get_address(),
                 authority,
                 &multisig_signers,
-                transfer_fee_basis_points,
-                maximum_fee,
+                rebase_fee_bps,
+                fee_destination,
             )?],
             signing_keypairs,
         )
         .await
+        .map_err(TokenError::into_rebase_error)
     }
 
-    /// Set default account state on mint
-    pub async fn set_default_account_state<S: Signers>(
+    /// Sunset a rebasing mint, writing off its supply and every share. If the
+    /// mint has a co-signer, it must be among the signers.
+    pub async fn sunset_rebase<S: Signers>(
         &self,
         authority: &Pubkey,
-        state: &AccountState,
         signing_keypairs: &S,
     ) -> TokenResult<T::Output> {
-        let signing_pubkeys = signing_keypairs.pubkeys();
+        let config = self.get_rebase_mint_config().await?;
+        let co_signer = Option::<Pubkey>::from(config.co_signer);
+        let signing_pubkeys = Self::without_co_signer(signing_keypairs, co_signer.as_ref());
         let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
 
         self.process_ixs(
-            &[
-                default_account_state::instruction::update_default_account_state(
-                    &self.program_id,
-                    &self.pubkey,
-                    authority,
-                    &multisig_signers,
-                    state,
-                )?,
-            ],
-            signing_keypairs,
-        )
-        .await
-    }
-
-    /// Harvest withheld tokens to mint
-    pub async fn harvest_withheld_tokens_to_mint(
-        &self,
-        sources: &[&Pubkey],
-    ) -> TokenResult<T::Output> {
-        self.process_ixs::<[&dyn Signer; 0]>(
-            &[transfer_fee::instruction::harvest_withheld_tokens_to_mint(
+            &[rebase_mint::instruction::sunset_rebase_with_co_signer(
                 &self.program_id,
-                &self.pubkey,
-                sources,
+                self.get_address(),
+                co_signer.as_ref(),
+                authority,
+                &multisig_signers,
             )?],
-            &[],
+            signing_keypairs,
         )
         .await
+        .map_err(TokenError::into_rebase_error)
     }
 
-    /// Withdraw withheld tokens from mint
-    pub async fn withdraw_withheld_tokens_from_mint<S: Signers>(
+    /// Retire a rebasing mint, freezing its ratio of tokens to shares
+    pub async fn retire_rebase<S: Signers>(
         &self,
-        destination: &Pubkey,
         authority: &Pubkey,
         signing_keypairs: &S,
     ) -> TokenResult<T::Output> {
@@ -1531,182 +2884,368 @@ where
         let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
 
         self.process_ixs(
-            &[
-                transfer_fee::instruction::withdraw_withheld_tokens_from_mint(
-                    &self.program_id,
-                    &self.pubkey,
-                    destination,
-                    authority,
-                    &multisig_signers,
-                )?,
-            ],
+            &[rebase_mint::instruction::retire_rebase(
+                &self.program_id,
+                self.get_address(),
+                authority,
+                &multisig_signers,
+            )?],
             signing_keypairs,
         )
         .await
+        .map_err(TokenError::into_rebase_error)
     }
 
-    /// Withdraw withheld tokens from accounts
-    pub async fn withdraw_withheld_tokens_from_accounts<S: Signers>(
+    /// Sweep the rounding dust of a rebasing mint into a token account
+    pub async fn sweep_rounding_dust<S: Signers>(
         &self,
         destination: &Pubkey,
         authority: &Pubkey,
-        sources: &[&Pubkey],
         signing_keypairs: &S,
     ) -> TokenResult<T::Output> {
         let signing_pubkeys = signing_keypairs.pubkeys();
         let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
 
         self.process_ixs(
-            &[
-                transfer_fee::instruction::withdraw_withheld_tokens_from_accounts(
-                    &self.program_id,
-                    &self.pubkey,
-                    destination,
-                    authority,
-                    &multisig_signers,
-                    sources,
-                )?,
-            ],
+            &[rebase_mint::instruction::sweep_rounding_dust(
+                &self.program_id,
+                self.get_address(),
+                destination,
+                authority,
+                &multisig_signers,
+            )?],
             signing_keypairs,
         )
         .await
+        .map_err(TokenError::into_rebase_error)
     }
 
-    /// Reallocate a token account to be large enough for a set of
-    /// ExtensionTypes
-    pub async fn reallocate<S: Signers>(
+    /// Apply the rebases that an account of a lazy rebasing mint missed to its
+    /// amount
+    pub async fn claim_rebase(&self, account: &Pubkey) -> TokenResult<T::Output> {
+        self.process_ixs(
+            &[rebase_mint::instruction::claim_rebase(
+                &self.program_id,
+                account,
+                self.get_address(),
+            )?],
+            &[] as &[&dyn Signer; 0],
+        )
+        .await
+        .map_err(TokenError::into_rebase_error)
+    }
+
+    /// Set or revoke the supply authority of a rebasing mint
+    pub async fn set_rebase_supply_authority<S: Signers>(
         &self,
-        account: &Pubkey,
         authority: &Pubkey,
-        extension_types: &[ExtensionType],
+        new_authority: Option<Pubkey>,
         signing_keypairs: &S,
     ) -> TokenResult<T::Output> {
         let signing_pubkeys = signing_keypairs.pubkeys();
         let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
 
         self.process_ixs(
-            &[instruction::reallocate(
+            &[rebase_mint::instruction::set_supply_authority(
                 &self.program_id,
-                account,
-                &self.payer.pubkey(),
+                self.get_address(),
                 authority,
                 &multisig_signers,
-                extension_types,
+                new_authority,
             )?],
             signing_keypairs,
         )
         .await
+        .map_err(TokenError::into_rebase_error)
     }
 
-    /// Require memos on transfers into this account
-    pub async fn enable_required_transfer_memos<S: Signers>(
+    /// Transfer an exact number of shares between two accounts of a rebasing
+    /// mint.
+    ///
+    /// Transfer hook accounts are only added if they were given with
+    /// `with_transfer_hook_accounts`.
+    pub async fn transfer_shares<S: Signers>(
         &self,
-        account: &Pubkey,
+        source: &Pubkey,
+        destination: &Pubkey,
         authority: &Pubkey,
+        shares: u64,
         signing_keypairs: &S,
     ) -> TokenResult<T::Output> {
         let signing_pubkeys = signing_keypairs.pubkeys();
         let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
 
-        self.process_ixs(
-            &[memo_transfer::instruction::enable_required_transfer_memos(
+        let mut instruction = if let Some(decimals) = self.decimals {
+            rebase_mint::instruction::transfer_shares_checked(
                 &self.program_id,
-                account,
+                source,
+                self.get_address(),
+                destination,
                 authority,
                 &multisig_signers,
-            )?],
-            signing_keypairs,
-        )
-        .await
+                shares,
+                decimals,
+            )?
+        } else {
+            rebase_mint::instruction::transfer_shares(
+                &self.program_id,
+                source,
+                self.get_address(),
+                destination,
+                authority,
+                &multisig_signers,
+                shares,
+            )?
+        };
+        if let Some(transfer_hook_accounts) = &self.transfer_hook_accounts {
+            instruction.accounts.extend(transfer_hook_accounts.clone());
+        }
+
+        self.process_ixs(&[instruction], signing_keypairs)
+            .await
+            .map_err(TokenError::into_rebase_error)
     }
 
-    /// Stop requiring memos on transfers into this account
-    pub async fn disable_required_transfer_memos<S: Signers>(
+    /// Transfer a UI amount of tokens of a rebasing mint, such as "12.5".
+    ///
+    /// The amount is parsed against the decimals of the mint and converted
+    /// to shares at the current ratio. If a rebase lands before the transfer
+    /// does, the amount is worth a different number of shares, and the
+    /// transfer fails with `ShareSlippageExceeded` once they differ by more
+    /// than `max_slippage_bps` basis points.
+    ///
+    /// Transfer hook accounts are only added if they were given with
+    /// `with_transfer_hook_accounts`.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn transfer_rebased<S: Signers>(
         &self,
-        account: &Pubkey,
+        source: &Pubkey,
+        destination: &Pubkey,
+        authority: &Pubkey,
+        ui_amount: &str,
+        max_slippage_bps: u16,
+        signing_keypairs: &S,
+    ) -> TokenResult<T::Output> {
+        let mint_info = self.get_mint_info().await?;
+        let config = Self::unpack_rebase_mint_config(&mint_info)?;
+        let decimals = mint_info.base.decimals;
+        let amount = spl_token_2022::try_ui_amount_into_amount(ui_amount.to_string(), decimals)
+            .map_err(|_| TokenError::InvalidUiAmount)?;
+        let expected_shares = config.amount_to_shares(amount).ok_or(ProgramError::from(
+            spl_token_2022::error::TokenError::Overflow,
+        ))?;
+
+        let signing_pubkeys = signing_keypairs.pubkeys();
+        let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
+
+        let mut instruction = rebase_mint::instruction::transfer_checked_with_share_slippage(
+            &self.program_id,
+            source,
+            self.get_address(),
+            destination,
+            authority,
+            &multisig_signers,
+            amount,
+            decimals,
+            expected_shares,
+            max_slippage_bps,
+        )?;
+        if let Some(transfer_hook_accounts) = &self.transfer_hook_accounts {
+            instruction.accounts.extend(transfer_hook_accounts.clone());
+        }
+
+        self.process_ixs(&[instruction], signing_keypairs)
+            .await
+            .map_err(|error| match &error {
+                TokenError::Client(client_error)
+                    if custom_error_code(client_error)
+                        == Some(
+                            spl_token_2022::error::TokenError::ShareSlippageExceeded as u32,
+                        ) =>
+                {
+                    TokenError::ShareSlippageExceeded
+                }
+                _ => error.into_rebase_error(),
+            })
+    }
+
+    /// Mint an exact number of shares of a rebasing mint to an account
+    pub async fn mint_to_shares<S: Signers>(
+        &self,
+        destination: &Pubkey,
         authority: &Pubkey,
+        shares: u64,
         signing_keypairs: &S,
     ) -> TokenResult<T::Output> {
         let signing_pubkeys = signing_keypairs.pubkeys();
         let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
 
         self.process_ixs(
-            &[memo_transfer::instruction::disable_required_transfer_memos(
+            &[rebase_mint::instruction::mint_to_shares(
                 &self.program_id,
-                account,
+                self.get_address(),
+                destination,
                 authority,
                 &multisig_signers,
+                shares,
             )?],
             signing_keypairs,
         )
         .await
+        .map_err(TokenError::into_rebase_error)
     }
 
-    /// Prevent unsafe usage of token account through CPI
-    pub async fn enable_cpi_guard<S: Signers>(
+    /// Burn an exact number of shares of a rebasing mint from an account
+    pub async fn burn_shares<S: Signers>(
         &self,
-        account: &Pubkey,
+        source: &Pubkey,
         authority: &Pubkey,
+        shares: u64,
         signing_keypairs: &S,
     ) -> TokenResult<T::Output> {
         let signing_pubkeys = signing_keypairs.pubkeys();
         let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
 
         self.process_ixs(
-            &[cpi_guard::instruction::enable_cpi_guard(
+            &[rebase_mint::instruction::burn_shares(
                 &self.program_id,
-                account,
+                source,
+                self.get_address(),
                 authority,
                 &multisig_signers,
+                shares,
             )?],
             signing_keypairs,
         )
         .await
+        .map_err(TokenError::into_rebase_error)
     }
 
-    /// Stop preventing unsafe usage of token account through CPI
-    pub async fn disable_cpi_guard<S: Signers>(
+    /// Fetch an account of the rebasing mint along with the mint, and parse a
+    /// UI amount against the decimals of the mint
+    async fn get_rebased_account_and_amount(
         &self,
         account: &Pubkey,
-        authority: &Pubkey,
+        ui_amount: &str,
+    ) -> TokenResult<(StateWithExtensionsOwned<Account>, RebaseMintConfig, u64, u8)> {
+        let (account_info, mint_info) =
+            try_join!(self.get_account_info(account), self.get_mint_info())?;
+        let config = Self::unpack_rebase_mint_config(&mint_info)?;
+        let decimals = mint_info.base.decimals;
+        let amount = spl_token_2022::try_ui_amount_into_amount(ui_amount.to_string(), decimals)
+            .map_err(|_| TokenError::InvalidUiAmount)?;
+        Ok((account_info, config, amount, decimals))
+    }
+
+    /// Mint a UI amount of tokens of a rebasing mint, such as "12.5", to an
+    /// account.
+    ///
+    /// The amount is parsed against the decimals of the mint. To an account
+    /// holding shares, it's minted as the shares it's worth at the current
+    /// ratio, rounded down as the program does, and fails with
+    /// `ShareSlippageExceeded` if that's not a single share. Lazy mints and
+    /// exempt accounts are minted the amount itself.
+    ///
+    /// The shares are fixed when the instruction is built, so a rebase that
+    /// lands first scales the amount minted along with every other balance.
+    pub async fn mint_to_rebased<S: Signers>(
+        &self,
+        destination: &Pubkey,
+        mint_authority: &Pubkey,
+        ui_amount: &str,
         signing_keypairs: &S,
     ) -> TokenResult<T::Output> {
+        let (account_info, config, amount, decimals) = self
+            .get_rebased_account_and_amount(destination, ui_amount)
+            .await?;
         let signing_pubkeys = signing_keypairs.pubkeys();
-        let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
+        let multisig_signers = self.get_multisig_signers(mint_authority, &signing_pubkeys);
 
-        self.process_ixs(
-            &[cpi_guard::instruction::disable_cpi_guard(
+        let instruction = if config.is_lazy() || rebase_mint::is_exempt(&account_info) {
+            instruction::mint_to_checked(
                 &self.program_id,
-                account,
-                authority,
+                &self.pubkey,
+                destination,
+                mint_authority,
                 &multisig_signers,
-            )?],
-            signing_keypairs,
-        )
-        .await
+                amount,
+                decimals,
+            )?
+        } else {
+            let shares = config.amount_to_shares(amount).ok_or(ProgramError::from(
+                spl_token_2022::error::TokenError::Overflow,
+            ))?;
+            if shares == 0 && amount != 0 {
+                return Err(TokenError::ShareSlippageExceeded);
+            }
+            rebase_mint::instruction::mint_to_shares(
+                &self.program_id,
+                &self.pubkey,
+                destination,
+                mint_authority,
+                &multisig_signers,
+                shares,
+            )?
+        };
+
+        self.process_ixs(&[instruction], signing_keypairs)
+            .await
+            .map_err(TokenError::into_rebase_error)
     }
 
-    /// Update interest rate
-    pub async fn update_interest_rate<S: Signers>(
+    /// Burn a UI amount of tokens of a rebasing mint, such as "12.5", from an
+    /// account.
+    ///
+    /// The amount is parsed against the decimals of the mint. From an account
+    /// holding shares, it's burned as the shares it's worth at the current
+    /// ratio, rounded up as the program does, so burning the whole balance
+    /// leaves no shares behind. Lazy mints and exempt accounts are burned the
+    /// amount itself.
+    ///
+    /// The shares are fixed when the instruction is built, so a rebase that
+    /// lands first scales the amount burned along with every other balance.
+    pub async fn burn_rebased<S: Signers>(
         &self,
-        authority: &Pubkey,
-        new_rate: i16,
+        source: &Pubkey,
+        owner: &Pubkey,
+        ui_amount: &str,
         signing_keypairs: &S,
     ) -> TokenResult<T::Output> {
+        let (account_info, config, amount, decimals) = self
+            .get_rebased_account_and_amount(source, ui_amount)
+            .await?;
         let signing_pubkeys = signing_keypairs.pubkeys();
-        let multisig_signers = self.get_multisig_signers(authority, &signing_pubkeys);
+        let multisig_signers = self.get_multisig_signers(owner, &signing_pubkeys);
 
-        self.process_ixs(
-            &[interest_bearing_mint::instruction::update_rate(
+        let instruction = if config.is_lazy() || rebase_mint::is_exempt(&account_info) {
+            instruction::burn_checked(
                 &self.program_id,
-                self.get_address(),
-                authority,
+                source,
+                &self.pubkey,
+                owner,
                 &multisig_signers,
-                new_rate,
-            )?],
-            signing_keypairs,
-        )
-        .await
+                amount,
+                decimals,
+            )?
+        } else {
+            let shares = config
+                .amount_to_shares_rounding_up(amount)
+                .ok_or(ProgramError::from(
+                    spl_token_2022::error::TokenError::Overflow,
+                ))?;
+            rebase_mint::instruction::burn_shares(
+                &self.program_id,
+                source,
+                &self.pubkey,
+                owner,
+                &multisig_signers,
+                shares,
+            )?
+        };
+
+        self.process_ixs(&[instruction], signing_keypairs)
+            .await
+            .map_err(TokenError::into_rebase_error)
     }
 
     /// Update transfer hook program id
@@ -4003,4 +5542,46 @@ where
         ));
         self.process_ixs(&instructions, signing_keypairs).await
     }
+
+    /// Sum the rebase-adjusted supplies of the members of this group mint.
+    ///
+    /// Members can't be discovered from the group itself, so every member
+    /// mint must be given. Each one must point back to this group, and
+    /// together they must make up the group's full size. Rebasing members
+    /// count their rebased total plus exempt supply, and other members their
+    /// base supply, all in raw units.
+    pub async fn get_group_rebased_supply(&self, member_mints: &[Pubkey]) -> TokenResult<u64> {
+        let group_info = self.get_mint_info().await?;
+        let group = group_info.get_extension::<TokenGroup>()?;
+        let mut unique_members = member_mints.to_vec();
+        unique_members.sort();
+        unique_members.dedup();
+        if unique_members.len() != member_mints.len()
+            || member_mints.len() != u32::from(group.size) as usize
+        {
+            return Err(TokenError::InvalidGroupMembers);
+        }
+
+        let mut supply = 0u64;
+        for member_mint in member_mints {
+            let account = self.get_account(*member_mint).await?;
+            if account.owner != self.program_id {
+                return Err(TokenError::AccountInvalidOwner);
+            }
+            let mint = StateWithExtensionsOwned::<Mint>::unpack(account.data)?;
+            if mint.get_extension::<TokenGroupMember>()?.group != self.pubkey {
+                return Err(TokenError::InvalidGroupMembers);
+            }
+            let member_supply = match mint.get_extension::<RebaseMintConfig>() {
+                Ok(config) => u64::from(config.total_supply)
+                    .checked_add(u64::from(config.exempt_supply))
+                    .ok_or(ProgramError::ArithmeticOverflow)?,
+                Err(_) => mint.base.supply,
+            };
+            supply = supply
+                .checked_add(member_supply)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+        }
+        Ok(supply)
+    }
 }
@@ -0,0 +1,100 @@
+use {
+    solana_program_test::tokio,
+    solana_sdk::{
+        bpf_loader_upgradeable,
+        pubkey::Pubkey,
+        signer::{keypair::Keypair, Signer},
+    },
+    solana_test_validator::{TestValidatorGenesis, UpgradeableProgramInfo},
+    spl_token_2022::extension::{
+        rebase_mint::RebaseAccount, BaseStateWithExtensions, ExtensionType,
+    },
+    spl_token_client::{
+        client::{ProgramClient, ProgramRpcClient, ProgramRpcClientSendTransaction},
+        token::Token,
+    },
+    std::{path::PathBuf, sync::Arc},
+};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn rebase_ata_holds_shares() {
+    let mut test_validator_genesis = TestValidatorGenesis::default();
+    test_validator_genesis.add_upgradeable_programs_with_path(&[
+        UpgradeableProgramInfo {
+            program_id: spl_token_2022::id(),
+            loader: bpf_loader_upgradeable::id(),
+            program_path: PathBuf::from("../../target/deploy/spl_token_2022.so"),
+            upgrade_authority: Pubkey::new_unique(),
+        },
+        UpgradeableProgramInfo {
+            program_id: spl_associated_token_account::id(),
+            loader: bpf_loader_upgradeable::id(),
+            program_path: PathBuf::from("../../target/deploy/spl_associated_token_account.so"),
+            upgrade_authority: Pubkey::new_unique(),
+        },
+    ]);
+    let (test_validator, payer) = test_validator_genesis.start_async().await;
+    let client: Arc<dyn ProgramClient<ProgramRpcClientSendTransaction>> =
+        Arc::new(ProgramRpcClient::new(
+            Arc::new(test_validator.get_async_rpc_client()),
+            ProgramRpcClientSendTransaction,
+        ));
+
+    let mint = Keypair::new();
+    let mint_authority = Keypair::new();
+    let supply_authority = Keypair::new();
+    let token = Token::create_rebase_mint(
+        client,
+        &spl_token_2022::id(),
+        &mint.pubkey(),
+        6,
+        &mint_authority.pubkey(),
+        None,
+        Some(supply_authority.pubkey()),
+        0,
+        None,
+        Arc::new(payer),
+        &[&mint],
+    )
+    .await
+    .unwrap();
+
+    let alice = Keypair::new();
+    let account = token
+        .get_or_create_rebase_ata(&alice.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(
+        account.get_extension_types().unwrap(),
+        vec![ExtensionType::RebaseAccount]
+    );
+    let alice_account = token.get_associated_token_address(&alice.pubkey());
+
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            1_000,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    token
+        .rebase_supply(&supply_authority.pubkey(), 2_500, &[&supply_authority])
+        .await
+        .unwrap();
+
+    // the account exists now, so it's only read
+    let account = token
+        .get_or_create_rebase_ata(&alice.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(
+        u64::from(account.get_extension::<RebaseAccount>().unwrap().shares),
+        1_000
+    );
+    assert_eq!(
+        token.get_rebased_balance(&alice_account).await.unwrap(),
+        2_500
+    );
+}
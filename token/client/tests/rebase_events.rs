@@ -0,0 +1,113 @@
+use {
+    futures::StreamExt,
+    solana_program_test::tokio::{self, time},
+    solana_sdk::{
+        bpf_loader_upgradeable,
+        commitment_config::CommitmentConfig,
+        pubkey::Pubkey,
+        signer::{keypair::Keypair, Signer},
+    },
+    solana_test_validator::{TestValidatorGenesis, UpgradeableProgramInfo},
+    spl_token_client::{
+        client::{ProgramClient, ProgramRpcClient, ProgramRpcClientSendTransaction},
+        token::{RebaseEvent, Token},
+    },
+    std::{path::PathBuf, sync::Arc, time::Duration},
+};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn rebases_arrive_on_the_stream() {
+    let mut test_validator_genesis = TestValidatorGenesis::default();
+    test_validator_genesis.add_upgradeable_programs_with_path(&[UpgradeableProgramInfo {
+        program_id: spl_token_2022::id(),
+        loader: bpf_loader_upgradeable::id(),
+        program_path: PathBuf::from("../../target/deploy/spl_token_2022.so"),
+        upgrade_authority: Pubkey::new_unique(),
+    }]);
+    let (test_validator, payer) = test_validator_genesis.start_async().await;
+    let client: Arc<dyn ProgramClient<ProgramRpcClientSendTransaction>> =
+        Arc::new(ProgramRpcClient::new(
+            Arc::new(test_validator.get_async_rpc_client()),
+            ProgramRpcClientSendTransaction,
+        ));
+
+    let mint = Keypair::new();
+    let mint_authority = Keypair::new();
+    let supply_authority = Keypair::new();
+    let token = Token::create_rebase_mint(
+        client,
+        &spl_token_2022::id(),
+        &mint.pubkey(),
+        6,
+        &mint_authority.pubkey(),
+        None,
+        Some(supply_authority.pubkey()),
+        1_000,
+        None,
+        Arc::new(payer),
+        &[&mint],
+    )
+    .await
+    .unwrap();
+
+    let mut events = token
+        .subscribe_rebase_events(
+            &test_validator.rpc_pubsub_url(),
+            CommitmentConfig::processed(),
+        )
+        .await
+        .unwrap();
+
+    token
+        .rebase_supply(&supply_authority.pubkey(), 1_500, &[&supply_authority])
+        .await
+        .unwrap();
+    let event = time::timeout(Duration::from_secs(30), events.next())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        event,
+        RebaseEvent {
+            slot: event.slot,
+            old_supply: 1_000,
+            new_supply: 1_500,
+            total_shares: 1_000,
+        }
+    );
+
+    // minting moves the shares along with the supply, so it isn't a rebase
+    let alice = Keypair::new();
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    token
+        .mint_to(
+            &alice_account.pubkey(),
+            &mint_authority.pubkey(),
+            300,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    token
+        .rebase_supply(&supply_authority.pubkey(), 3_600, &[&supply_authority])
+        .await
+        .unwrap();
+    let next_event = time::timeout(Duration::from_secs(30), events.next())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        next_event,
+        RebaseEvent {
+            slot: next_event.slot,
+            old_supply: 1_800,
+            new_supply: 3_600,
+            total_shares: 1_200,
+        }
+    );
+    assert!(next_event.slot > event.slot);
+}
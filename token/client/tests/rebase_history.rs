@@ -0,0 +1,302 @@
+use {
+    base64::{prelude::BASE64_STANDARD, Engine},
+    solana_program_test::tokio,
+    solana_sdk::{
+        bpf_loader_upgradeable,
+        pubkey::Pubkey,
+        signer::{keypair::Keypair, Signer},
+    },
+    solana_test_validator::{TestValidator, TestValidatorGenesis, UpgradeableProgramInfo},
+    spl_token_2022::extension::rebase_mint::{
+        event::{RebaseEvent, RebaseEventHeader, RebaseEventType, RebaseTagged},
+        SupplyRebased, REBASE_HISTORY_LEN,
+    },
+    spl_token_client::{
+        client::{
+            ProgramClient, ProgramRpcClient, ProgramRpcClientSendTransaction, RpcClientResponse,
+        },
+        token::{
+            rebase_events_from_logs, rebase_records_from_logs, ExtensionInitializationParams,
+            RebaseRecord, Token,
+        },
+    },
+    std::{path::PathBuf, sync::Arc},
+};
+
+async fn new_validator_for_test() -> (TestValidator, Keypair) {
+    let mut test_validator_genesis = TestValidatorGenesis::default();
+    test_validator_genesis.add_upgradeable_programs_with_path(&[UpgradeableProgramInfo {
+        program_id: spl_token_2022::id(),
+        loader: bpf_loader_upgradeable::id(),
+        program_path: PathBuf::from("../../target/deploy/spl_token_2022.so"),
+        upgrade_authority: Pubkey::new_unique(),
+    }]);
+    test_validator_genesis.start_async().await
+}
+
+struct RebasingMint {
+    token: Token<ProgramRpcClientSendTransaction>,
+    mint_authority: Keypair,
+    supply_authority: Keypair,
+    account: Pubkey,
+}
+
+async fn create_mint(
+    test_validator: &TestValidator,
+    payer: &Keypair,
+    extension_initialization_params: Vec<ExtensionInitializationParams>,
+) -> RebasingMint {
+    let client: Arc<dyn ProgramClient<ProgramRpcClientSendTransaction>> =
+        Arc::new(ProgramRpcClient::new(
+            Arc::new(test_validator.get_async_rpc_client()),
+            ProgramRpcClientSendTransaction,
+        ));
+    let mint = Keypair::new();
+    let mint_authority = Keypair::new();
+    let supply_authority = Keypair::new();
+    let token = Token::new(
+        client,
+        &spl_token_2022::id(),
+        &mint.pubkey(),
+        Some(6),
+        Arc::new(payer.insecure_clone()),
+    );
+    let mut extension_initialization_params = extension_initialization_params;
+    extension_initialization_params.insert(
+        0,
+        ExtensionInitializationParams::RebaseMintConfig {
+            supply_authority: Some(supply_authority.pubkey()),
+            initial_supply: 1_000,
+        },
+    );
+    token
+        .create_mint(
+            &mint_authority.pubkey(),
+            None,
+            extension_initialization_params,
+            &[&mint],
+        )
+        .await
+        .unwrap();
+    let account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&account, &Pubkey::new_unique())
+        .await
+        .unwrap();
+    RebasingMint {
+        token,
+        mint_authority,
+        supply_authority,
+        account: account.pubkey(),
+    }
+}
+
+/// Rebase `count` times, each after a mint, which isn't a rebase, returning
+/// what each rebase should be recorded as, newest first
+async fn rebase(mint: &RebasingMint, count: u64) -> Vec<RebaseRecord> {
+    let mut records = vec![];
+    for _ in 0..count {
+        mint.token
+            .mint_to(
+                &mint.account,
+                &mint.mint_authority.pubkey(),
+                1,
+                &[&mint.mint_authority],
+            )
+            .await
+            .unwrap();
+        let old_supply = u64::from(
+            mint.token
+                .get_rebase_mint_config()
+                .await
+                .unwrap()
+                .total_supply,
+        );
+        let response = mint
+            .token
+            .rebase_supply(
+                &mint.supply_authority.pubkey(),
+                old_supply + 100,
+                &[&mint.supply_authority],
+            )
+            .await
+            .unwrap();
+        let RpcClientResponse::Signature(signature) = response else {
+            panic!("rebase wasn't sent");
+        };
+        let config = mint.token.get_rebase_mint_config().await.unwrap();
+        records.push(RebaseRecord {
+            unix_timestamp: 0,
+            old_supply,
+            new_supply: config.total_supply.into(),
+            total_shares: config.total_shares.into(),
+            reason_code: 0,
+            reference: [0; 32],
+            signature: Some(signature),
+        });
+    }
+    records.reverse();
+    records
+}
+
+/// The records without their timestamps, which the test can't predict
+fn untimed(records: &[RebaseRecord]) -> Vec<RebaseRecord> {
+    records
+        .iter()
+        .map(|record| RebaseRecord {
+            unix_timestamp: 0,
+            ..*record
+        })
+        .collect()
+}
+
+fn unsigned(records: &[RebaseRecord]) -> Vec<RebaseRecord> {
+    records
+        .iter()
+        .map(|record| RebaseRecord {
+            signature: None,
+            ..*record
+        })
+        .collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn records_beyond_the_history() {
+    let (test_validator, payer) = new_validator_for_test().await;
+    let mint = create_mint(
+        &test_validator,
+        &payer,
+        vec![ExtensionInitializationParams::RebaseHistory],
+    )
+    .await;
+
+    // more rebases than the history holds
+    let rebases = REBASE_HISTORY_LEN as u64 + 5;
+    let expected = rebase(&mint, rebases).await;
+
+    // the history alone has the newest ones, without signatures
+    let records = mint.token.get_rebase_records(3).await.unwrap();
+    assert_eq!(untimed(&records), unsigned(&expected[..3]));
+
+    // past it, the logs fill in the signatures and the older rebases, paging
+    // through the mints in between
+    for limit in [REBASE_HISTORY_LEN + 2, rebases as usize, usize::MAX] {
+        let records = mint.token.get_rebase_records(limit).await.unwrap();
+        assert_eq!(
+            untimed(&records),
+            expected[..limit.min(expected.len())],
+            "{}",
+            limit
+        );
+        assert!(records
+            .windows(2)
+            .all(|pair| pair[0].unix_timestamp >= pair[1].unix_timestamp));
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn records_without_history() {
+    let (test_validator, payer) = new_validator_for_test().await;
+    let mint = create_mint(&test_validator, &payer, vec![]).await;
+    // another mint's rebases share none of the logs
+    let other = create_mint(&test_validator, &payer, vec![]).await;
+
+    let expected = rebase(&mint, 3).await;
+    rebase(&other, 2).await;
+
+    let records = mint.token.get_rebase_records(2).await.unwrap();
+    assert_eq!(untimed(&records), expected[..2]);
+    let records = mint.token.get_rebase_records(usize::MAX).await.unwrap();
+    assert_eq!(untimed(&records), expected);
+    assert_eq!(mint.token.get_rebase_records(0).await.unwrap(), vec![]);
+}
+
+/// Logs of a rebase of `mint` by `program_id`, invoked at `depth`
+fn rebase_logs(program_id: &Pubkey, mint: &Pubkey, depth: usize, new_supply: u64) -> Vec<String> {
+    let supply_rebased = SupplyRebased {
+        previous_supply: 1_000.into(),
+        new_supply: new_supply.into(),
+        total_shares: 700.into(),
+        exempt_supply: 0.into(),
+        fee_shares: 0.into(),
+    };
+    let header = RebaseEventHeader {
+        mint: *mint,
+        unix_timestamp: 5.into(),
+    };
+    let tagged = RebaseTagged {
+        previous_supply: 1_000.into(),
+        new_supply: new_supply.into(),
+        reason_code: 2,
+        ..RebaseTagged::default()
+    };
+    vec![
+        format!("Program {} invoke [{}]", program_id, depth),
+        format!(
+            "Program data: {}",
+            BASE64_STANDARD.encode(bytemuck::bytes_of(&supply_rebased))
+        ),
+        format!(
+            "Program data: {} {} {}",
+            BASE64_STANDARD.encode([u8::from(RebaseEventType::RebaseTagged)]),
+            BASE64_STANDARD.encode(bytemuck::bytes_of(&header)),
+            BASE64_STANDARD.encode(bytemuck::bytes_of(&tagged)),
+        ),
+        format!("Program {} success", program_id),
+    ]
+}
+
+#[test]
+fn records_from_logs() {
+    let program_id = spl_token_2022::id();
+    let mint = Pubkey::new_unique();
+    let caller = Pubkey::new_unique();
+    let logs = [
+        vec![format!("Program {} invoke [1]", caller)],
+        rebase_logs(&program_id, &mint, 2, 1_500),
+        // another mint
+        rebase_logs(&program_id, &Pubkey::new_unique(), 2, 1_600),
+        // another program logging the same data
+        rebase_logs(&Pubkey::new_unique(), &mint, 2, 1_700),
+        vec![
+            "Program data: not base64".to_string(),
+            format!("Program {} success", caller),
+        ],
+        rebase_logs(&program_id, &mint, 1, 1_800),
+    ]
+    .concat();
+
+    let record = |new_supply| RebaseRecord {
+        unix_timestamp: 5,
+        old_supply: 1_000,
+        new_supply,
+        total_shares: 700,
+        reason_code: 2,
+        reference: [0; 32],
+        signature: None,
+    };
+    assert_eq!(
+        rebase_records_from_logs(&logs, &program_id, &mint),
+        vec![record(1_500), record(1_800)]
+    );
+}
+
+#[test]
+fn events_from_logs() {
+    let program_id = spl_token_2022::id();
+    let mint = Pubkey::new_unique();
+    let logs = [
+        rebase_logs(&program_id, &mint, 1, 1_500),
+        rebase_logs(&program_id, &Pubkey::new_unique(), 1, 1_600),
+        rebase_logs(&Pubkey::new_unique(), &mint, 1, 1_700),
+    ]
+    .concat();
+
+    // the bare `SupplyRebased` isn't an event with a header
+    let events = rebase_events_from_logs(&logs, &program_id, &mint);
+    assert_eq!(events.len(), 1);
+    let RebaseEvent::RebaseTagged(tagged) = &events[0] else {
+        panic!("not a tagged rebase");
+    };
+    assert_eq!(u64::from(tagged.new_supply), 1_500);
+}
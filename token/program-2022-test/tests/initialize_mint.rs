@@ -17,9 +17,9 @@ use {
     spl_token_2022::{
         error::TokenError,
         extension::{
-            confidential_transfer, confidential_transfer_fee,
-            mint_close_authority::MintCloseAuthority, transfer_fee, BaseStateWithExtensions,
-            ExtensionType,
+            confidential_transfer, confidential_transfer_fee, interest_bearing_mint,
+            mint_close_authority::MintCloseAuthority, rebase_mint, transfer_fee,
+            BaseStateWithExtensions, ExtensionType,
         },
         instruction, native_mint,
         solana_zk_token_sdk::zk_token_elgamal::pod::ElGamalPubkey,
@@ -652,3 +652,91 @@ async fn fail_invalid_extensions_combination() {
     );
     ctx.banks_client.process_transaction(tx).await.unwrap();
 }
+
+#[tokio::test]
+async fn fail_rebase_and_interest_bearing_combination() {
+    let context = TestContext::new().await;
+    let mut ctx = context.context.lock().await;
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let mint_space = ExtensionType::try_calculate_account_len::<Mint>(&[
+        ExtensionType::RebaseMintConfig,
+        ExtensionType::InterestBearingConfig,
+    ])
+    .unwrap();
+
+    for rebase_first in [true, false] {
+        for use_initialize_mint2 in [true, false] {
+            let mint_account = Keypair::new();
+            let mint_authority_pubkey = Pubkey::new_unique();
+
+            let rebase_mint_init_instruction =
+                rebase_mint::instruction::InitializeRebaseMintBuilder::new(&spl_token_2022::id())
+                    .mint(&mint_account.pubkey())
+                    .supply_authority(&Pubkey::new_unique())
+                    .allow_supply_decrease()
+                    .build()
+                    .unwrap();
+            let interest_bearing_init_instruction = interest_bearing_mint::instruction::initialize(
+                &spl_token_2022::id(),
+                &mint_account.pubkey(),
+                Some(Pubkey::new_unique()),
+                10,
+            )
+            .unwrap();
+            let initialize_mint_instruction = if use_initialize_mint2 {
+                instruction::initialize_mint2(
+                    &spl_token_2022::id(),
+                    &mint_account.pubkey(),
+                    &mint_authority_pubkey,
+                    None,
+                    9,
+                )
+            } else {
+                instruction::initialize_mint(
+                    &spl_token_2022::id(),
+                    &mint_account.pubkey(),
+                    &mint_authority_pubkey,
+                    None,
+                    9,
+                )
+            }
+            .unwrap();
+
+            let mut instructions = vec![system_instruction::create_account(
+                &ctx.payer.pubkey(),
+                &mint_account.pubkey(),
+                rent.minimum_balance(mint_space),
+                mint_space as u64,
+                &spl_token_2022::id(),
+            )];
+            if rebase_first {
+                instructions.push(rebase_mint_init_instruction);
+                instructions.push(interest_bearing_init_instruction);
+            } else {
+                instructions.push(interest_bearing_init_instruction);
+                instructions.push(rebase_mint_init_instruction);
+            }
+            instructions.push(initialize_mint_instruction);
+
+            let tx = Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&ctx.payer.pubkey()),
+                &[&ctx.payer, &mint_account],
+                ctx.last_blockhash,
+            );
+            let err = ctx
+                .banks_client
+                .process_transaction(tx)
+                .await
+                .unwrap_err()
+                .unwrap();
+            assert_eq!(
+                err,
+                TransactionError::InstructionError(
+                    3,
+                    InstructionError::Custom(TokenError::InvalidExtensionCombination as u32)
+                )
+            );
+        }
+    }
+}
@@ -3,17 +3,20 @@
 use {
     solana_program_test::{processor, tokio::sync::Mutex, ProgramTest, ProgramTestContext},
     solana_sdk::{
+        instruction::Instruction,
         pubkey::Pubkey,
         signer::{keypair::Keypair, Signer},
+        system_instruction,
     },
     spl_token_2022::{
         extension::{
             confidential_transfer::ConfidentialTransferAccount, BaseStateWithExtensions,
             ExtensionType,
         },
-        id, native_mint,
+        id, instruction, native_mint,
         processor::Processor,
         solana_zk_token_sdk::encryption::{auth_encryption::*, elgamal::*},
+        state::Mint,
     },
     spl_token_client::{
         client::{
@@ -145,6 +148,133 @@ impl TestContext {
         Ok(())
     }
 
+    /// Create a mint with the rebasing mint extension, which the token client
+    /// can't initialize on its own. `initialize_rebase_mint` builds the
+    /// extension's initialize instruction for the given mint address.
+    pub async fn init_token_with_rebase_mint(
+        &mut self,
+        initialize_rebase_mint: impl FnOnce(&Pubkey) -> Instruction,
+    ) -> TokenResult<()> {
+        self.init_token_with_rebase_mint_and_extensions(vec![], initialize_rebase_mint)
+            .await
+    }
+
+    /// Create a mint with the rebasing mint extension alongside other
+    /// extensions the token client can initialize
+    pub async fn init_token_with_rebase_mint_and_extensions(
+        &mut self,
+        extension_init_params: Vec<ExtensionInitializationParams>,
+        initialize_rebase_mint: impl FnOnce(&Pubkey) -> Instruction,
+    ) -> TokenResult<()> {
+        self.init_token_with_rebase_mint_and_freeze_authority(
+            extension_init_params,
+            None,
+            initialize_rebase_mint,
+        )
+        .await
+    }
+
+    pub async fn init_token_with_rebase_mint_and_freeze_authority(
+        &mut self,
+        extension_init_params: Vec<ExtensionInitializationParams>,
+        freeze_authority: Option<Keypair>,
+        initialize_rebase_mint: impl FnOnce(&Pubkey) -> Instruction,
+    ) -> TokenResult<()> {
+        self.init_token_with_rebase_mint_keypair_and_freeze_authority(
+            Keypair::new(),
+            extension_init_params,
+            freeze_authority,
+            initialize_rebase_mint,
+        )
+        .await
+    }
+
+    pub async fn init_token_with_rebase_mint_keypair_and_freeze_authority(
+        &mut self,
+        mint_account: Keypair,
+        extension_init_params: Vec<ExtensionInitializationParams>,
+        freeze_authority: Option<Keypair>,
+        initialize_rebase_mint: impl FnOnce(&Pubkey) -> Instruction,
+    ) -> TokenResult<()> {
+        let mut extension_types = extension_init_params
+            .iter()
+            .map(|params| params.extension())
+            .collect::<Vec<_>>();
+        extension_types.push(ExtensionType::RebaseMintConfig);
+        let (payer, lamports, space) = {
+            let mut context = self.context.lock().await;
+            let space = ExtensionType::try_calculate_account_len::<Mint>(&extension_types).unwrap();
+            let rent = context.banks_client.get_rent().await.unwrap();
+            (
+                keypair_clone(&context.payer),
+                rent.minimum_balance(space),
+                space,
+            )
+        };
+        let client: Arc<dyn ProgramClient<ProgramBanksClientProcessTransaction>> =
+            Arc::new(ProgramBanksClient::new_from_context(
+                Arc::clone(&self.context),
+                ProgramBanksClientProcessTransaction,
+            ));
+
+        let decimals: u8 = 9;
+        let mint_authority = Keypair::new();
+
+        let token = Token::new(
+            Arc::clone(&client),
+            &id(),
+            &mint_account.pubkey(),
+            Some(decimals),
+            Arc::new(keypair_clone(&payer)),
+        );
+
+        let token_unchecked = Token::new(
+            Arc::clone(&client),
+            &id(),
+            &mint_account.pubkey(),
+            None,
+            Arc::new(keypair_clone(&payer)),
+        );
+
+        let mut instructions = vec![system_instruction::create_account(
+            &payer.pubkey(),
+            &mint_account.pubkey(),
+            lamports,
+            space as u64,
+            &id(),
+        )];
+        for params in extension_init_params {
+            instructions.push(params.instruction(&id(), &mint_account.pubkey())?);
+        }
+        instructions.push(initialize_rebase_mint(&mint_account.pubkey()));
+        instructions.push(
+            instruction::initialize_mint(
+                &id(),
+                &mint_account.pubkey(),
+                &mint_authority.pubkey(),
+                freeze_authority
+                    .as_ref()
+                    .map(|authority| authority.pubkey())
+                    .as_ref(),
+                decimals,
+            )
+            .unwrap(),
+        );
+        token.process_ixs(&instructions, &[&mint_account]).await?;
+
+        self.token_context = Some(TokenContext {
+            decimals,
+            mint_authority,
+            token,
+            token_unchecked,
+            alice: Keypair::new(),
+            bob: Keypair::new(),
+            freeze_authority,
+        });
+
+        Ok(())
+    }
+
     pub async fn init_token_with_native_mint(&mut self) -> TokenResult<()> {
         let payer = keypair_clone(&self.context.lock().await.payer);
         let client: Arc<dyn ProgramClient<ProgramBanksClientProcessTransaction>> =
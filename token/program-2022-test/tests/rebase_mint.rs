@@ -0,0 +1,218 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{pubkey::Pubkey, signature::Signer, signer::keypair::Keypair},
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{instruction, RebaseAccount},
+            BaseStateWithExtensions,
+        },
+        id,
+    },
+    spl_token_client::{
+        client::ProgramBanksClientProcessTransaction,
+        token::{ExtensionInitializationParams, Token, TokenError as TokenClientError},
+    },
+};
+
+async fn get_shares(token: &Token<ProgramBanksClientProcessTransaction>, account: &Pubkey) -> u64 {
+    let state = token.get_account_info(account).await.unwrap();
+    u64::from(state.get_extension::<RebaseAccount>().unwrap().shares)
+}
+
+async fn create_account(
+    token: &Token<ProgramBanksClientProcessTransaction>,
+    owner: &Keypair,
+) -> Pubkey {
+    let account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&account, &owner.pubkey())
+        .await
+        .unwrap();
+    account.pubkey()
+}
+
+#[tokio::test]
+async fn success_initialize() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_mint(vec![ExtensionInitializationParams::RebaseMintConfig {
+            supply_authority: Some(supply_authority.pubkey()),
+            initial_supply: 0,
+        }])
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        decimals,
+        mint_authority,
+        alice,
+        ..
+    } = context.token_context.take().unwrap();
+
+    // InitializeMint2 ran after the extension
+    let state = token.get_mint_info().await.unwrap();
+    assert!(state.base.is_initialized);
+    assert_eq!(state.base.decimals, decimals);
+    let config = token.get_rebase_mint_config().await.unwrap();
+    assert_eq!(
+        Option::<Pubkey>::from(config.supply_authority),
+        Some(supply_authority.pubkey())
+    );
+    assert_eq!(u64::from(config.total_supply), 0);
+    assert_eq!(u64::from(config.total_shares), 0);
+    assert!(!bool::from(config.has_rebased));
+
+    let alice_account = create_account(&token, &alice).await;
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            2_000_000_000,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    token
+        .rebase_supply(
+            &supply_authority.pubkey(),
+            5_000_000_000,
+            &[&supply_authority],
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(get_shares(&token, &alice_account).await, 2_000_000_000);
+    assert_eq!(
+        token.get_rebased_balance(&alice_account).await.unwrap(),
+        5_000_000_000
+    );
+    assert_eq!(
+        token.get_rebased_ui_balance(&alice_account).await.unwrap(),
+        "5"
+    );
+    let config = token.get_rebase_mint_config().await.unwrap();
+    assert_eq!(u64::from(config.total_supply), 5_000_000_000);
+    assert_eq!(u64::from(config.total_shares), 2_000_000_000);
+    assert!(bool::from(config.has_rebased));
+}
+
+#[tokio::test]
+async fn rebase_up_and_down() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        bob,
+        ..
+    } = context.token_context.take().unwrap();
+    let alice_account = create_account(&token, &alice).await;
+    let bob_account = create_account(&token, &bob).await;
+    for (account, amount) in [(&alice_account, 1_000), (&bob_account, 500)] {
+        token
+            .mint_to(
+                account,
+                &mint_authority.pubkey(),
+                amount,
+                &[&mint_authority],
+            )
+            .await
+            .unwrap();
+    }
+
+    // rebasing scales balances, never shares
+    token
+        .rebase_supply(&supply_authority.pubkey(), 4_500, &[&supply_authority])
+        .await
+        .unwrap();
+    assert_eq!(get_shares(&token, &alice_account).await, 1_000);
+    assert_eq!(get_shares(&token, &bob_account).await, 500);
+    assert_eq!(
+        token.get_rebased_balance(&alice_account).await.unwrap(),
+        3_000
+    );
+    assert_eq!(
+        token.get_rebased_balance(&bob_account).await.unwrap(),
+        1_500
+    );
+    assert_eq!(
+        token.get_rebased_ui_balance(&alice_account).await.unwrap(),
+        "0.000003"
+    );
+
+    token
+        .rebase_supply(&supply_authority.pubkey(), 750, &[&supply_authority])
+        .await
+        .unwrap();
+    assert_eq!(get_shares(&token, &alice_account).await, 1_000);
+    assert_eq!(
+        token.get_rebased_balance(&alice_account).await.unwrap(),
+        500
+    );
+    assert_eq!(token.get_rebased_balance(&bob_account).await.unwrap(), 250);
+
+    // at 0.5 tokens per share, 100 tokens issue 200 shares
+    token
+        .mint_to(
+            &bob_account,
+            &mint_authority.pubkey(),
+            100,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    assert_eq!(get_shares(&token, &bob_account).await, 700);
+    assert_eq!(token.get_rebased_balance(&bob_account).await.unwrap(), 350);
+    let config = token.get_rebase_mint_config().await.unwrap();
+    assert_eq!(u64::from(config.total_supply), 850);
+    assert_eq!(u64::from(config.total_shares), 1_700);
+}
+
+#[tokio::test]
+async fn success_initialize_without_authority() {
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_mint(vec![ExtensionInitializationParams::RebaseMintConfig {
+            supply_authority: None,
+            initial_supply: 1_000,
+        }])
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        ..
+    } = context.token_context.take().unwrap();
+
+    let config = token.get_rebase_mint_config().await.unwrap();
+    assert_eq!(Option::<Pubkey>::from(config.supply_authority), None);
+    assert_eq!(u64::from(config.total_supply), 1_000);
+    assert_eq!(u64::from(config.total_shares), 1_000);
+
+    let err = token
+        .rebase_supply(&mint_authority.pubkey(), 2_000, &[&mint_authority])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Rebase(TokenError::RebaseSupplyImmutable)
+    );
+}
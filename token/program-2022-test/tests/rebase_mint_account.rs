@@ -0,0 +1,235 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        instruction::InstructionError, pubkey::Pubkey, signature::Signer, signer::keypair::Keypair,
+        transaction::TransactionError, transport::TransportError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{instruction, RebaseAccount, RebaseMintConfig},
+            BaseStateWithExtensions, ExtensionType,
+        },
+        id,
+    },
+    spl_token_client::{
+        client::ProgramBanksClientProcessTransaction,
+        token::{Token, TokenError as TokenClientError},
+    },
+};
+
+async fn setup(supply_authority: &Keypair, initial_supply: u64) -> TokenContext {
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .initial_supply(initial_supply, 0)
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    context.token_context.take().unwrap()
+}
+
+/// Returns the account's shares, stored amount, and the current value of its
+/// shares
+async fn get_balances(
+    token: &Token<ProgramBanksClientProcessTransaction>,
+    account: &Pubkey,
+) -> (u64, u64, u64) {
+    let mint = token.get_mint_info().await.unwrap();
+    let config = mint.get_extension::<RebaseMintConfig>().unwrap();
+    let state = token.get_account_info(account).await.unwrap();
+    let shares = u64::from(state.get_extension::<RebaseAccount>().unwrap().shares);
+    (
+        shares,
+        state.base.amount,
+        config.shares_to_amount(shares).unwrap(),
+    )
+}
+
+fn token_error(token_error: TokenError) -> TokenClientError {
+    TokenClientError::Client(Box::new(TransportError::TransactionError(
+        TransactionError::InstructionError(0, InstructionError::Custom(token_error as u32)),
+    )))
+}
+
+#[tokio::test]
+async fn account_requires_extension() {
+    let supply_authority = Keypair::new();
+    let TokenContext { token, alice, .. } = setup(&supply_authority, 1_000).await;
+
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    let state = token
+        .get_account_info(&alice_account.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(
+        state.get_extension_types().unwrap(),
+        vec![ExtensionType::RebaseAccount]
+    );
+    assert_eq!(
+        state.get_extension::<RebaseAccount>().unwrap().shares,
+        0.into()
+    );
+}
+
+#[tokio::test]
+async fn balances_follow_rebase() {
+    let supply_authority = Keypair::new();
+    let TokenContext {
+        token,
+        token_unchecked,
+        mint_authority,
+        alice,
+        bob,
+        ..
+    } = setup(&supply_authority, 1_000).await;
+
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    let alice_account = alice_account.pubkey();
+    let bob_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&bob_account, &bob.pubkey())
+        .await
+        .unwrap();
+    let bob_account = bob_account.pubkey();
+
+    // 1:1 before any rebase
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            1_000,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        get_balances(&token, &alice_account).await,
+        (1_000, 1_000, 1_000)
+    );
+
+    // doubling the supply doubles alice's balance without touching her account
+    token
+        .rebase_supply(&supply_authority.pubkey(), 4_000, &[&supply_authority])
+        .await
+        .unwrap();
+    assert_eq!(
+        get_balances(&token, &alice_account).await,
+        (1_000, 1_000, 2_000)
+    );
+
+    // she can spend more than her stale amount
+    token
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            1_500,
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    assert_eq!(get_balances(&token, &alice_account).await, (250, 500, 500));
+    assert_eq!(
+        get_balances(&token, &bob_account).await,
+        (750, 1_500, 1_500)
+    );
+
+    // but not more than her shares are worth
+    let err = token
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            502,
+            &[&alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err, token_error(TokenError::InsufficientFunds));
+
+    // the mint is needed to convert to shares
+    let err = token_unchecked
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            100,
+            &[&alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err, token_error(TokenError::MintRequiredForTransfer));
+
+    // minting at the new ratio
+    token
+        .mint_to(
+            &bob_account,
+            &mint_authority.pubkey(),
+            500,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        get_balances(&token, &bob_account).await,
+        (1_000, 2_000, 2_000)
+    );
+
+    // halving the supply halves both balances
+    token
+        .rebase_supply(&supply_authority.pubkey(), 2_250, &[&supply_authority])
+        .await
+        .unwrap();
+    assert_eq!(get_balances(&token, &alice_account).await, (250, 500, 250));
+    assert_eq!(
+        get_balances(&token, &bob_account).await,
+        (1_000, 2_000, 1_000)
+    );
+
+    // burning at the current ratio
+    token
+        .burn(&alice_account, &alice.pubkey(), 249, &[&alice])
+        .await
+        .unwrap();
+    assert_eq!(get_balances(&token, &alice_account).await, (1, 1, 1));
+    let err = token
+        .burn(&alice_account, &alice.pubkey(), 2, &[&alice])
+        .await
+        .unwrap_err();
+    assert_eq!(err, token_error(TokenError::InsufficientFunds));
+    token
+        .burn(&alice_account, &alice.pubkey(), 1, &[&alice])
+        .await
+        .unwrap();
+    assert_eq!(get_balances(&token, &alice_account).await, (0, 0, 0));
+
+    // only empty accounts can be closed
+    token
+        .close_account(&alice_account, &alice.pubkey(), &alice.pubkey(), &[&alice])
+        .await
+        .unwrap();
+    let err = token
+        .close_account(&bob_account, &bob.pubkey(), &bob.pubkey(), &[&bob])
+        .await
+        .unwrap_err();
+    assert_eq!(err, token_error(TokenError::NonNativeHasBalance));
+}
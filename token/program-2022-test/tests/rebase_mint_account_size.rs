@@ -0,0 +1,116 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        instruction::InstructionError, signature::Signer, signer::keypair::Keypair,
+        system_instruction, transaction::TransactionError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{instruction, RebaseAccount},
+            BaseStateWithExtensions, ExtensionType,
+        },
+        id,
+        instruction::{get_account_data_size, initialize_account3},
+        state::Account,
+    },
+};
+
+#[tokio::test]
+async fn account_data_size_fits_exactly() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext { token, alice, .. } = context.token_context.take().unwrap();
+
+    // the mint config itself can't go on an account
+    let simulation = token
+        .simulate_ixs(
+            &[get_account_data_size(
+                &id(),
+                token.get_address(),
+                &[ExtensionType::RebaseMintConfig],
+            )
+            .unwrap()],
+            &[] as &[&dyn Signer; 0],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        simulation.result.unwrap(),
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(TokenError::ExtensionTypeMismatch as u32)
+        ))
+    );
+
+    // the size includes the shares the account must track
+    let simulation = token
+        .simulate_ixs(
+            &[get_account_data_size(&id(), token.get_address(), &[]).unwrap()],
+            &[] as &[&dyn Signer; 0],
+        )
+        .await
+        .unwrap();
+    assert!(simulation.result.unwrap().is_ok());
+    let return_data = simulation.simulation_details.unwrap().return_data.unwrap();
+    let space = u64::from_le_bytes(return_data.data.try_into().unwrap()) as usize;
+    assert_eq!(
+        space,
+        ExtensionType::try_calculate_account_len::<Account>(&[ExtensionType::RebaseAccount])
+            .unwrap()
+    );
+
+    let account = Keypair::new();
+    let (payer, rent) = {
+        let mut context = context.context.lock().await;
+        (
+            context.payer.pubkey(),
+            context.banks_client.get_rent().await.unwrap(),
+        )
+    };
+    token
+        .process_ixs(
+            &[
+                system_instruction::create_account(
+                    &payer,
+                    &account.pubkey(),
+                    rent.minimum_balance(space),
+                    space as u64,
+                    &id(),
+                ),
+                initialize_account3(
+                    &id(),
+                    &account.pubkey(),
+                    token.get_address(),
+                    &alice.pubkey(),
+                )
+                .unwrap(),
+            ],
+            &[&account],
+        )
+        .await
+        .unwrap();
+
+    // with zero bytes to spare
+    let state = token.get_account_info(&account.pubkey()).await.unwrap();
+    assert_eq!(state.try_get_account_len().unwrap(), space);
+    assert_eq!(
+        u64::from(state.get_extension::<RebaseAccount>().unwrap().shares),
+        0
+    );
+}
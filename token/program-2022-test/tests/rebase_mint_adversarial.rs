@@ -0,0 +1,441 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{keypair_clone, TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        account::AccountSharedData,
+        instruction::{Instruction, InstructionError},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+        signature::Signer,
+        signer::keypair::Keypair,
+        system_instruction,
+        transaction::TransactionError,
+        transport::TransportError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{instruction, RebaseMintConfig},
+            BaseStateWithExtensions, ExtensionType,
+        },
+        id,
+        state::Mint,
+    },
+    spl_token_client::{
+        client::ProgramBanksClientProcessTransaction,
+        token::{Token, TokenError as TokenClientError},
+    },
+};
+
+// Token accounts owned by this key read as a mint with an invalid
+// `is_initialized` flag, since that byte of the mint falls inside the owner
+const TOKEN_ACCOUNT_OWNER: Pubkey = Pubkey::new_from_array([7; 32]);
+
+fn instruction_error(index: u8, error: InstructionError) -> TokenClientError {
+    TokenClientError::Client(Box::new(TransportError::TransactionError(
+        TransactionError::InstructionError(index, error),
+    )))
+}
+
+fn initialize_ix(mint: &Pubkey) -> Instruction {
+    instruction::InitializeRebaseMintBuilder::new(&id())
+        .mint(mint)
+        .supply_authority(&Pubkey::new_unique())
+        .initial_supply(1_000, 0)
+        .allow_supply_decrease()
+        .build()
+        .unwrap()
+}
+
+/// Every truncation of the instruction data, and one byte too many
+fn wrong_length_data(data: &[u8]) -> Vec<Vec<u8>> {
+    let mut wrong_lengths = (0..data.len())
+        .map(|len| data[..len].to_vec())
+        .collect::<Vec<_>>();
+    let mut too_long = data.to_vec();
+    too_long.push(0);
+    wrong_lengths.push(too_long);
+    wrong_lengths
+}
+
+fn wrong_length_error(data: &[u8]) -> InstructionError {
+    if data.is_empty() {
+        // not even a token instruction
+        InstructionError::Custom(TokenError::InvalidInstruction as u32)
+    } else {
+        InstructionError::InvalidInstructionData
+    }
+}
+
+async fn create_mint_account(
+    context: &TestContext,
+    token: &Token<ProgramBanksClientProcessTransaction>,
+    mint: &Keypair,
+    owner: &Pubkey,
+) {
+    let space =
+        ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::RebaseMintConfig])
+            .unwrap();
+    let (payer, lamports) = {
+        let mut context = context.context.lock().await;
+        let rent = context.banks_client.get_rent().await.unwrap();
+        (keypair_clone(&context.payer), rent.minimum_balance(space))
+    };
+    token
+        .process_ixs(
+            &[system_instruction::create_account(
+                &payer.pubkey(),
+                &mint.pubkey(),
+                lamports,
+                space as u64,
+                owner,
+            )],
+            &[mint],
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn hostile_initialize() {
+    let mut context = TestContext::new().await;
+    // only used to send transactions and create token accounts
+    context.init_token_with_mint(vec![]).await.unwrap();
+    let TokenContext { token, .. } = context.token_context.take().unwrap();
+
+    // wrong program id
+    assert_eq!(
+        instruction::InitializeRebaseMintBuilder::new(&Pubkey::new_unique())
+            .mint(&Pubkey::new_unique())
+            .build()
+            .unwrap_err(),
+        ProgramError::IncorrectProgramId
+    );
+
+    // mint owned by a different program
+    let mint = Keypair::new();
+    create_mint_account(&context, &token, &mint, &Pubkey::new_unique()).await;
+    let err = token
+        .process_ixs(&[initialize_ix(&mint.pubkey())], &[] as &[&dyn Signer; 0])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        instruction_error(0, InstructionError::IncorrectProgramId)
+    );
+
+    // non-writable mint
+    let mint = Keypair::new();
+    create_mint_account(&context, &token, &mint, &id()).await;
+    let mut ix = initialize_ix(&mint.pubkey());
+    ix.accounts[0].is_writable = false;
+    let err = token
+        .process_ixs(&[ix], &[] as &[&dyn Signer; 0])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        instruction_error(0, InstructionError::ReadonlyDataModified)
+    );
+
+    // token account passed as the mint
+    let token_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&token_account, &TOKEN_ACCOUNT_OWNER)
+        .await
+        .unwrap();
+    let err = token
+        .process_ixs(
+            &[initialize_ix(&token_account.pubkey())],
+            &[] as &[&dyn Signer; 0],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        instruction_error(0, InstructionError::InvalidAccountData)
+    );
+
+    // instruction data of every wrong length, on a mint that would otherwise
+    // initialize
+    let valid_ix = initialize_ix(&mint.pubkey());
+    for data in wrong_length_data(&valid_ix.data) {
+        let ix = Instruction {
+            data: data.clone(),
+            ..valid_ix.clone()
+        };
+        let err = token
+            .process_ixs(&[ix], &[] as &[&dyn Signer; 0])
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err,
+            instruction_error(0, wrong_length_error(&data)),
+            "{} bytes",
+            data.len()
+        );
+    }
+    token
+        .process_ixs(&[valid_ix], &[] as &[&dyn Signer; 0])
+        .await
+        .unwrap();
+
+    // Initialize takes no authority, so there are no signer cases to cover
+}
+
+#[tokio::test]
+async fn hostile_rebase_supply() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .initial_supply(1_000, 0)
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext { token, .. } = context.token_context.take().unwrap();
+    let rebase_ix = |mint: &Pubkey| {
+        instruction::update_supply(&id(), mint, None, &supply_authority.pubkey(), &[], 2_000)
+            .unwrap()
+    };
+
+    // wrong program id
+    assert_eq!(
+        instruction::update_supply(
+            &Pubkey::new_unique(),
+            token.get_address(),
+            None,
+            &supply_authority.pubkey(),
+            &[],
+            2_000
+        )
+        .unwrap_err(),
+        ProgramError::IncorrectProgramId
+    );
+
+    // a copy of the mint owned by a different program
+    let impostor = Pubkey::new_unique();
+    {
+        let mut context = context.context.lock().await;
+        let mut mint_account = context
+            .banks_client
+            .get_account(*token.get_address())
+            .await
+            .unwrap()
+            .unwrap();
+        mint_account.owner = Pubkey::new_unique();
+        context.set_account(&impostor, &AccountSharedData::from(mint_account));
+    }
+    let err = token
+        .process_ixs(&[rebase_ix(&impostor)], &[&supply_authority])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        instruction_error(0, InstructionError::IncorrectProgramId)
+    );
+
+    // non-writable mint
+    let mut ix = rebase_ix(token.get_address());
+    ix.accounts[0].is_writable = false;
+    let err = token
+        .process_ixs(&[ix], &[&supply_authority])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        instruction_error(0, InstructionError::ReadonlyDataModified)
+    );
+
+    // token account passed as the mint
+    let token_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&token_account, &TOKEN_ACCOUNT_OWNER)
+        .await
+        .unwrap();
+    let err = token
+        .process_ixs(&[rebase_ix(&token_account.pubkey())], &[&supply_authority])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        instruction_error(0, InstructionError::InvalidAccountData)
+    );
+
+    // authority that isn't a signer
+    let mut ix = rebase_ix(token.get_address());
+    ix.accounts[1].is_signer = false;
+    let err = token
+        .process_ixs(&[ix], &[] as &[&dyn Signer; 0])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        instruction_error(0, InstructionError::MissingRequiredSignature)
+    );
+
+    // instruction data of every wrong length, apart from the layouts from
+    // before the reason and the deadline, which still decode
+    let valid_ix = rebase_ix(token.get_address());
+    let earlier_layouts = [2 + 8, 2 + 8 + 1 + 32];
+    for data in wrong_length_data(&valid_ix.data)
+        .into_iter()
+        .filter(|data| !earlier_layouts.contains(&data.len()))
+    {
+        let ix = Instruction {
+            data: data.clone(),
+            ..valid_ix.clone()
+        };
+        let err = token
+            .process_ixs(&[ix], &[&supply_authority])
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err,
+            instruction_error(0, wrong_length_error(&data)),
+            "{} bytes",
+            data.len()
+        );
+    }
+
+    // none of it touched the mint
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(u64::from(extension.total_supply), 1_000);
+    assert_eq!(u64::from(extension.total_shares), 1_000);
+}
+
+#[tokio::test]
+async fn hostile_rebase_supply_multisig() {
+    let multisig = Keypair::new();
+    let multisig_members = [Keypair::new(), Keypair::new(), Keypair::new()];
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&multisig.pubkey())
+                .initial_supply(1_000, 0)
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext { token, .. } = context.token_context.take().unwrap();
+    token
+        .create_multisig(
+            &multisig,
+            &multisig_members
+                .iter()
+                .map(|member| member.pubkey())
+                .collect::<Vec<_>>()
+                .iter()
+                .collect::<Vec<_>>(),
+            2,
+        )
+        .await
+        .unwrap();
+
+    // one member listed twice still counts once
+    let ix = instruction::update_supply(
+        &id(),
+        token.get_address(),
+        None,
+        &multisig.pubkey(),
+        &[&multisig_members[0].pubkey(), &multisig_members[0].pubkey()],
+        2_000,
+    )
+    .unwrap();
+    let err = token
+        .process_ixs(&[ix], &[&multisig_members[0]])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        instruction_error(0, InstructionError::MissingRequiredSignature)
+    );
+
+    // a listed member that didn't sign
+    let mut ix = instruction::update_supply(
+        &id(),
+        token.get_address(),
+        None,
+        &multisig.pubkey(),
+        &[&multisig_members[0].pubkey(), &multisig_members[1].pubkey()],
+        2_000,
+    )
+    .unwrap();
+    ix.accounts[3].is_signer = false;
+    let err = token
+        .process_ixs(&[ix], &[&multisig_members[0]])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        instruction_error(0, InstructionError::MissingRequiredSignature)
+    );
+
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(u64::from(extension.total_supply), 1_000);
+}
+
+#[tokio::test]
+async fn rebase_supply_with_mint_as_authority() {
+    let supply_authority = Pubkey::new_unique();
+    for mint_is_supply_authority in [false, true] {
+        let mut context = TestContext::new().await;
+        context
+            .init_token_with_rebase_mint(|mint| {
+                instruction::InitializeRebaseMintBuilder::new(&id())
+                    .mint(mint)
+                    .supply_authority(if mint_is_supply_authority {
+                        mint
+                    } else {
+                        &supply_authority
+                    })
+                    .initial_supply(1_000, 0)
+                    .build()
+                    .unwrap()
+            })
+            .await
+            .unwrap();
+        let TokenContext { token, .. } = context.token_context.take().unwrap();
+
+        // the mint can't sign, so it's only passed as a writable account
+        let mut ix = instruction::update_supply(
+            &id(),
+            token.get_address(),
+            None,
+            token.get_address(),
+            &[],
+            2_000,
+        )
+        .unwrap();
+        ix.accounts[1].is_signer = false;
+        let err = token
+            .process_ixs(&[ix], &[] as &[&dyn Signer; 0])
+            .await
+            .unwrap_err();
+        let expected = if mint_is_supply_authority {
+            InstructionError::MissingRequiredSignature
+        } else {
+            InstructionError::Custom(TokenError::OwnerMismatch as u32)
+        };
+        assert_eq!(err, instruction_error(0, expected));
+
+        let state = token.get_mint_info().await.unwrap();
+        let extension = state.get_extension::<RebaseMintConfig>().unwrap();
+        assert_eq!(u64::from(extension.total_supply), 1_000);
+    }
+}
@@ -0,0 +1,121 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        instruction::InstructionError, pubkey::Pubkey, signature::Signer, signer::keypair::Keypair,
+        transaction::TransactionError, transport::TransportError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{instruction, RebaseAccount},
+            BaseStateWithExtensions,
+        },
+        id,
+    },
+    spl_token_client::{
+        client::ProgramBanksClientProcessTransaction,
+        token::{Token, TokenError as TokenClientError},
+    },
+};
+
+async fn get_shares(token: &Token<ProgramBanksClientProcessTransaction>, account: &Pubkey) -> u64 {
+    let state = token.get_account_info(account).await.unwrap();
+    u64::from(state.get_extension::<RebaseAccount>().unwrap().shares)
+}
+
+fn token_error(error: TokenError) -> TokenClientError {
+    TokenClientError::Client(Box::new(TransportError::TransactionError(
+        TransactionError::InstructionError(0, InstructionError::Custom(error as u32)),
+    )))
+}
+
+#[tokio::test]
+async fn transfers_worth_less_than_a_share_fail() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        bob,
+        ..
+    } = context.token_context.take().unwrap();
+    let mut accounts = vec![];
+    for owner in [&alice, &bob] {
+        let account = Keypair::new();
+        token
+            .create_auxiliary_token_account(&account, &owner.pubkey())
+            .await
+            .unwrap();
+        accounts.push(account.pubkey());
+    }
+    let (alice_account, bob_account) = (accounts[0], accounts[1]);
+
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            1_000,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    // each share is now worth 1,000 tokens
+    token
+        .rebase_supply(&supply_authority.pubkey(), 1_000_000, &[&supply_authority])
+        .await
+        .unwrap();
+
+    // one token short of a share would move nothing
+    let err = token
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            999,
+            &[&alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err, token_error(TokenError::AmountTooSmallForShares));
+    assert_eq!(get_shares(&token, &alice_account).await, 1_000);
+    assert_eq!(get_shares(&token, &bob_account).await, 0);
+
+    // while read-only conversions still report it
+    let config = token.get_rebase_mint_config().await.unwrap();
+    assert_eq!(config.amount_to_shares(999), Some(0));
+
+    token
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            1_000,
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    assert_eq!(get_shares(&token, &alice_account).await, 999);
+    assert_eq!(get_shares(&token, &bob_account).await, 1);
+
+    // burns round up, so a single token takes a whole share
+    token
+        .burn(&bob_account, &bob.pubkey(), 1, &[&bob])
+        .await
+        .unwrap();
+    assert_eq!(get_shares(&token, &bob_account).await, 0);
+}
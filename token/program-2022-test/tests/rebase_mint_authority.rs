@@ -0,0 +1,691 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        clock::Clock, instruction::InstructionError, pubkey::Pubkey, signature::Signer,
+        signer::keypair::Keypair, transaction::TransactionError, transport::TransportError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{instruction, RebaseMintConfig},
+            BaseStateWithExtensions,
+        },
+        id,
+        instruction::AuthorityType,
+    },
+    spl_token_client::{
+        client::ProgramBanksClientProcessTransaction,
+        token::{Token, TokenError as TokenClientError, TokenResult},
+    },
+};
+
+async fn rebase(
+    token: &Token<ProgramBanksClientProcessTransaction>,
+    authority: &Keypair,
+    new_supply: u64,
+) -> TokenResult<()> {
+    token
+        .process_ixs(
+            &[instruction::update_supply(
+                &id(),
+                token.get_address(),
+                None,
+                &authority.pubkey(),
+                &[],
+                new_supply,
+            )
+            .unwrap()],
+            &[authority],
+        )
+        .await
+        .map(|_| ())
+}
+
+async fn set_supply_authority(
+    token: &Token<ProgramBanksClientProcessTransaction>,
+    authority: &Keypair,
+    new_authority: Option<Pubkey>,
+) -> TokenResult<()> {
+    token
+        .set_rebase_supply_authority(&authority.pubkey(), new_authority, &[authority])
+        .await
+        .map(|_| ())
+}
+
+/// Set the clock, with a new blockhash so that retrying a transaction that
+/// failed before isn't rejected as a duplicate
+async fn set_unix_timestamp(context: &TestContext, unix_timestamp: i64) {
+    let mut context = context.context.lock().await;
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp = unix_timestamp;
+    context.set_sysvar(&clock);
+    context.get_new_latest_blockhash().await.unwrap();
+}
+
+async fn finalize_authority_change(
+    token: &Token<ProgramBanksClientProcessTransaction>,
+) -> TokenResult<()> {
+    token
+        .process_ixs(
+            &[instruction::finalize_authority_change(&id(), token.get_address()).unwrap()],
+            &[] as &[&dyn Signer; 0],
+        )
+        .await
+        .map(|_| ())
+}
+
+#[tokio::test]
+async fn rotate_and_revoke() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .initial_supply(1_000, 0)
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext { token, .. } = context.token_context.take().unwrap();
+
+    // non-authority can't rotate
+    let wrong_signer = Keypair::new();
+    let err = set_supply_authority(&token, &wrong_signer, Some(wrong_signer.pubkey()))
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::OwnerMismatch as u32)
+            )
+        )))
+    );
+
+    // rotate
+    let new_supply_authority = Keypair::new();
+    set_supply_authority(
+        &token,
+        &supply_authority,
+        Some(new_supply_authority.pubkey()),
+    )
+    .await
+    .unwrap();
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(
+        Option::<Pubkey>::from(extension.supply_authority),
+        Some(new_supply_authority.pubkey()),
+    );
+    let err = rebase(&token, &supply_authority, 2_000).await.unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::OwnerMismatch as u32)
+            )
+        )))
+    );
+    rebase(&token, &new_supply_authority, 2_000).await.unwrap();
+
+    // revoke
+    set_supply_authority(&token, &new_supply_authority, None)
+        .await
+        .unwrap();
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(Option::<Pubkey>::from(extension.supply_authority), None);
+    for authority in [&supply_authority, &new_supply_authority] {
+        let err = rebase(&token, authority, 3_000).await.unwrap_err();
+        assert_eq!(
+            err,
+            TokenClientError::Client(Box::new(TransportError::TransactionError(
+                TransactionError::InstructionError(
+                    0,
+                    InstructionError::Custom(TokenError::RebaseSupplyImmutable as u32)
+                )
+            )))
+        );
+    }
+}
+
+#[tokio::test]
+async fn two_step_transfer() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .initial_supply(1_000, 0)
+                .two_step_authority_transfer()
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext { token, .. } = context.token_context.take().unwrap();
+    let new_supply_authority = Keypair::new();
+
+    // one-step handoff is disabled
+    let err = set_supply_authority(
+        &token,
+        &supply_authority,
+        Some(new_supply_authority.pubkey()),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Rebase(TokenError::RebaseAuthorityRequiresAcceptance)
+    );
+
+    // propose
+    token
+        .process_ixs(
+            &[instruction::propose_supply_authority(
+                &id(),
+                token.get_address(),
+                &supply_authority.pubkey(),
+                &[],
+                &new_supply_authority.pubkey(),
+            )
+            .unwrap()],
+            &[&supply_authority],
+        )
+        .await
+        .unwrap();
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(
+        Option::<Pubkey>::from(extension.pending_supply_authority),
+        Some(new_supply_authority.pubkey()),
+    );
+
+    // proposing again while pending fails
+    let other_authority = Keypair::new();
+    let err = token
+        .process_ixs(
+            &[instruction::propose_supply_authority(
+                &id(),
+                token.get_address(),
+                &supply_authority.pubkey(),
+                &[],
+                &other_authority.pubkey(),
+            )
+            .unwrap()],
+            &[&supply_authority],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::RebaseAuthorityProposalPending as u32)
+            )
+        )))
+    );
+
+    // wrong key can't accept
+    let err = token
+        .process_ixs(
+            &[instruction::accept_supply_authority(
+                &id(),
+                token.get_address(),
+                &other_authority.pubkey(),
+                &[],
+            )
+            .unwrap()],
+            &[&other_authority],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::OwnerMismatch as u32)
+            )
+        )))
+    );
+
+    // cancel, then accepting fails
+    token
+        .process_ixs(
+            &[instruction::cancel_pending_authority(
+                &id(),
+                token.get_address(),
+                &supply_authority.pubkey(),
+                &[],
+            )
+            .unwrap()],
+            &[&supply_authority],
+        )
+        .await
+        .unwrap();
+    let err = token
+        .process_ixs(
+            &[instruction::accept_supply_authority(
+                &id(),
+                token.get_address(),
+                &new_supply_authority.pubkey(),
+                &[],
+            )
+            .unwrap()],
+            &[&new_supply_authority],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::NoPendingRebaseAuthority as u32)
+            )
+        )))
+    );
+
+    // propose again, then accept, with a new blockhash so that neither is a
+    // duplicate of the earlier attempts
+    token.get_new_latest_blockhash().await.unwrap();
+    token
+        .process_ixs(
+            &[instruction::propose_supply_authority(
+                &id(),
+                token.get_address(),
+                &supply_authority.pubkey(),
+                &[],
+                &new_supply_authority.pubkey(),
+            )
+            .unwrap()],
+            &[&supply_authority],
+        )
+        .await
+        .unwrap();
+    token
+        .process_ixs(
+            &[instruction::accept_supply_authority(
+                &id(),
+                token.get_address(),
+                &new_supply_authority.pubkey(),
+                &[],
+            )
+            .unwrap()],
+            &[&new_supply_authority],
+        )
+        .await
+        .unwrap();
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(
+        Option::<Pubkey>::from(extension.supply_authority),
+        Some(new_supply_authority.pubkey()),
+    );
+    assert_eq!(
+        Option::<Pubkey>::from(extension.pending_supply_authority),
+        None,
+    );
+    rebase(&token, &new_supply_authority, 2_000).await.unwrap();
+
+    // revoking is still direct
+    set_supply_authority(&token, &new_supply_authority, None)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn time_locked_transfer() {
+    let supply_authority = Keypair::new();
+    let delay = 100;
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .initial_supply(1_000, 0)
+                .authority_change_delay_seconds(delay)
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext { token, .. } = context.token_context.take().unwrap();
+    let new_supply_authority = Keypair::new();
+
+    // nothing to finalize yet
+    let err = finalize_authority_change(&token).await.unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::NoPendingRebaseAuthority as u32)
+            )
+        )))
+    );
+
+    // request, then cancel
+    set_supply_authority(
+        &token,
+        &supply_authority,
+        Some(new_supply_authority.pubkey()),
+    )
+    .await
+    .unwrap();
+    token
+        .process_ixs(
+            &[instruction::cancel_authority_change(
+                &id(),
+                token.get_address(),
+                &supply_authority.pubkey(),
+                &[],
+            )
+            .unwrap()],
+            &[&supply_authority],
+        )
+        .await
+        .unwrap();
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(
+        Option::<Pubkey>::from(extension.scheduled_supply_authority),
+        None,
+    );
+
+    // request again, the change only applies after the delay. A new
+    // blockhash keeps it from being a duplicate of the first request
+    token.get_new_latest_blockhash().await.unwrap();
+    set_supply_authority(
+        &token,
+        &supply_authority,
+        Some(new_supply_authority.pubkey()),
+    )
+    .await
+    .unwrap();
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(
+        Option::<Pubkey>::from(extension.supply_authority),
+        Some(supply_authority.pubkey()),
+    );
+    assert_eq!(
+        Option::<Pubkey>::from(extension.scheduled_supply_authority),
+        Some(new_supply_authority.pubkey()),
+    );
+    let requested_timestamp = i64::from(extension.authority_change_requested_timestamp);
+
+    // pending authority can't rebase before finalization
+    let err = rebase(&token, &new_supply_authority, 2_000)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::OwnerMismatch as u32)
+            )
+        )))
+    );
+
+    set_unix_timestamp(&context, requested_timestamp + delay as i64 - 1).await;
+    let err = finalize_authority_change(&token).await.unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::RebaseAuthorityChangeNotReady as u32)
+            )
+        )))
+    );
+
+    set_unix_timestamp(&context, requested_timestamp + delay as i64).await;
+    finalize_authority_change(&token).await.unwrap();
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(
+        Option::<Pubkey>::from(extension.supply_authority),
+        Some(new_supply_authority.pubkey()),
+    );
+
+    // old authority can't rebase after finalization
+    let err = rebase(&token, &supply_authority, 2_000).await.unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::OwnerMismatch as u32)
+            )
+        )))
+    );
+    rebase(&token, &new_supply_authority, 2_000).await.unwrap();
+}
+
+#[tokio::test]
+async fn rebase_delegate() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .initial_supply(1_000, 0)
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext { token, .. } = context.token_context.take().unwrap();
+    let rebase_delegate = Keypair::new();
+
+    // only the supply authority can set the delegate
+    let err = token
+        .process_ixs(
+            &[instruction::set_rebase_delegate(
+                &id(),
+                token.get_address(),
+                &rebase_delegate.pubkey(),
+                &[],
+                Some(rebase_delegate.pubkey()),
+            )
+            .unwrap()],
+            &[&rebase_delegate],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::OwnerMismatch as u32)
+            )
+        )))
+    );
+    token
+        .process_ixs(
+            &[instruction::set_rebase_delegate(
+                &id(),
+                token.get_address(),
+                &supply_authority.pubkey(),
+                &[],
+                Some(rebase_delegate.pubkey()),
+            )
+            .unwrap()],
+            &[&supply_authority],
+        )
+        .await
+        .unwrap();
+
+    // both can rebase
+    rebase(&token, &rebase_delegate, 2_000).await.unwrap();
+    rebase(&token, &supply_authority, 3_000).await.unwrap();
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(u64::from(extension.total_supply), 3_000);
+
+    // the delegate can't change the configuration
+    let err = set_supply_authority(&token, &rebase_delegate, Some(rebase_delegate.pubkey()))
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::OwnerMismatch as u32)
+            )
+        )))
+    );
+
+    // removed delegate can't rebase
+    token
+        .process_ixs(
+            &[instruction::set_rebase_delegate(
+                &id(),
+                token.get_address(),
+                &supply_authority.pubkey(),
+                &[],
+                None,
+            )
+            .unwrap()],
+            &[&supply_authority],
+        )
+        .await
+        .unwrap();
+    let err = rebase(&token, &rebase_delegate, 4_000).await.unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::OwnerMismatch as u32)
+            )
+        )))
+    );
+}
+
+#[tokio::test]
+async fn no_supply_authority_is_immutable() {
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .initial_supply(1_000, 0)
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        ..
+    } = context.token_context.take().unwrap();
+
+    for authority in [&mint_authority, &alice] {
+        let err = rebase(&token, authority, 2_000).await.unwrap_err();
+        assert_eq!(
+            err,
+            TokenClientError::Client(Box::new(TransportError::TransactionError(
+                TransactionError::InstructionError(
+                    0,
+                    InstructionError::Custom(TokenError::RebaseSupplyImmutable as u32)
+                )
+            )))
+        );
+    }
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(u64::from(extension.total_supply), 1_000);
+    assert_eq!(u64::from(extension.total_shares), 1_000);
+}
+
+#[tokio::test]
+async fn mint_authority_fallback() {
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .initial_supply(1_000, 0)
+                .use_mint_authority_fallback()
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        ..
+    } = context.token_context.take().unwrap();
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<RebaseMintConfig>().unwrap();
+    assert!(bool::from(extension.use_mint_authority_fallback));
+
+    // only the mint authority stands in for the supply authority
+    let err = rebase(&token, &alice, 2_000).await.unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::OwnerMismatch as u32)
+            )
+        )))
+    );
+    rebase(&token, &mint_authority, 2_000).await.unwrap();
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(u64::from(extension.total_supply), 2_000);
+
+    // revoking the mint authority makes the supply immutable
+    token
+        .set_authority(
+            token.get_address(),
+            &mint_authority.pubkey(),
+            None,
+            AuthorityType::MintTokens,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    let err = rebase(&token, &mint_authority, 3_000).await.unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::RebaseSupplyImmutable as u32)
+            )
+        )))
+    );
+}
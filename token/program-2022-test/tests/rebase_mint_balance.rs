@@ -0,0 +1,134 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{pubkey::Pubkey, signature::Signer, signer::keypair::Keypair},
+    spl_token_2022::{extension::rebase_mint::instruction, id},
+    spl_token_client::{
+        client::ProgramBanksClientProcessTransaction,
+        token::{Token, TokenError as TokenClientError},
+    },
+};
+
+async fn get_rebased_balance(
+    token: &Token<ProgramBanksClientProcessTransaction>,
+    account: &Pubkey,
+) -> (u64, String) {
+    // no signature needed
+    let simulation = token
+        .simulate_ixs(
+            &[instruction::get_rebased_balance(&id(), account, token.get_address()).unwrap()],
+            &[] as &[&dyn Signer; 0],
+        )
+        .await
+        .unwrap();
+    assert!(simulation.result.unwrap().is_ok());
+    let return_data = simulation.simulation_details.unwrap().return_data.unwrap();
+    assert_eq!(return_data.program_id, id());
+    let (amount, ui_amount) = return_data.data.split_at(8);
+    let balance = (
+        u64::from_le_bytes(amount.try_into().unwrap()),
+        String::from_utf8(ui_amount.to_vec()).unwrap(),
+    );
+
+    // the client computes the same balance off-chain
+    assert_eq!(
+        (
+            token.get_rebased_balance(account).await.unwrap(),
+            token.get_rebased_ui_balance(account).await.unwrap()
+        ),
+        balance
+    );
+    balance
+}
+
+#[tokio::test]
+async fn balance_follows_rebase() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .initial_supply(1_000, 0)
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        ..
+    } = context.token_context.take().unwrap();
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    let alice_account = alice_account.pubkey();
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            1_000,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        get_rebased_balance(&token, &alice_account).await,
+        (1_000, "0.000001".to_string())
+    );
+
+    token
+        .rebase_supply(&supply_authority.pubkey(), 3_000, &[&supply_authority])
+        .await
+        .unwrap();
+    assert_eq!(
+        get_rebased_balance(&token, &alice_account).await,
+        (1_500, "0.0000015".to_string())
+    );
+    // the stored amount is stale
+    assert_eq!(
+        token
+            .get_account_info(&alice_account)
+            .await
+            .unwrap()
+            .base
+            .amount,
+        1_000
+    );
+}
+
+#[tokio::test]
+async fn fail_client_balance_without_extension() {
+    let mut context = TestContext::new().await;
+    context.init_token_with_mint(vec![]).await.unwrap();
+    let TokenContext { token, alice, .. } = context.token_context.take().unwrap();
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+
+    assert_eq!(
+        token
+            .get_rebased_balance(&alice_account.pubkey())
+            .await
+            .unwrap_err(),
+        TokenClientError::MissingRebaseMintConfig
+    );
+    assert_eq!(
+        token
+            .get_rebased_ui_balance(&alice_account.pubkey())
+            .await
+            .unwrap_err(),
+        TokenClientError::MissingRebaseMintConfig
+    );
+}
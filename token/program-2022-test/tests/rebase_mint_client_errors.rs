@@ -0,0 +1,172 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::{tokio, BanksClientError},
+    solana_sdk::{
+        instruction::InstructionError, signature::Signer, signer::keypair::Keypair,
+        transaction::TransactionError, transport::TransportError,
+    },
+    spl_token_2022::{error::TokenError, extension::rebase_mint::instruction, id},
+    spl_token_client::token::{
+        rebase_error_from_transaction_error, TokenError as TokenClientError,
+    },
+};
+
+// bots match on these numbers, so they must never shift
+const REBASE_ERROR_CODES: [(TokenError, u32); 34] = [
+    (TokenError::RebaseReserveMismatch, 61),
+    (TokenError::RebaseExceedsReserve, 62),
+    (TokenError::RebaseNotYetAllowed, 63),
+    (TokenError::RebaseAuthorityRequiresAcceptance, 64),
+    (TokenError::RebaseAuthorityProposalPending, 65),
+    (TokenError::NoPendingRebaseAuthority, 66),
+    (TokenError::RebaseAuthorityChangeNotReady, 67),
+    (TokenError::RebaseSupplyImmutable, 68),
+    (TokenError::RebaseMemoRequired, 69),
+    (TokenError::RebaseSupplySyncNotAllowed, 70),
+    (TokenError::ShareSlippageExceeded, 71),
+    (TokenError::RebaseBelowOutstandingShares, 72),
+    (TokenError::RebaseInconsistent, 73),
+    (TokenError::SupplyDecreaseForbidden, 74),
+    (TokenError::RebaseExtensionNotReallocatable, 75),
+    (TokenError::RebaseMintHasShares, 76),
+    (TokenError::RebaseSupplyZero, 77),
+    (TokenError::RebaseSharesOverflow, 78),
+    (TokenError::RebaseTooLarge, 79),
+    (TokenError::RebaseHistoryUnavailable, 80),
+    (TokenError::RebaseFeeTooHigh, 81),
+    (TokenError::RebaseFeeDestinationMismatch, 82),
+    (TokenError::RebaseSharesCapExceeded, 83),
+    (TokenError::RebaseSunsetNotAllowed, 84),
+    (TokenError::RebaseMintSunset, 85),
+    (TokenError::RebaseMintRetired, 86),
+    (TokenError::RebaseCoSignerMismatch, 87),
+    (TokenError::RebaseModeMismatch, 88),
+    (TokenError::RebaseBaseSupplyMismatch, 89),
+    (TokenError::RebaseMintNotActive, 90),
+    (TokenError::AmountTooSmallForShares, 91),
+    (TokenError::RebaseExpired, 92),
+    (TokenError::RebaseUiSupplyTooLong, 93),
+    (TokenError::RebaseUiSupplyMalformed, 94),
+];
+
+fn failed_transaction(code: u32) -> TransactionError {
+    TransactionError::InstructionError(1, InstructionError::Custom(code))
+}
+
+#[test]
+fn rebase_error_codes_are_stable() {
+    for (error, code) in REBASE_ERROR_CODES {
+        assert_eq!(error.clone() as u32, code);
+        assert_eq!(
+            rebase_error_from_transaction_error(&failed_transaction(code)),
+            Some(error)
+        );
+    }
+
+    // other errors are left to the caller
+    assert_eq!(
+        rebase_error_from_transaction_error(&failed_transaction(TokenError::OwnerMismatch as u32)),
+        None
+    );
+    assert_eq!(
+        rebase_error_from_transaction_error(&failed_transaction(u32::MAX)),
+        None
+    );
+    assert_eq!(
+        rebase_error_from_transaction_error(&TransactionError::InstructionError(
+            0,
+            InstructionError::MissingRequiredSignature
+        )),
+        None
+    );
+    assert_eq!(
+        rebase_error_from_transaction_error(&TransactionError::AccountNotFound),
+        None
+    );
+}
+
+#[test]
+fn simulated_failure_maps_to_rebase_error() {
+    for (error, code) in REBASE_ERROR_CODES {
+        let client_error = TokenClientError::Client(Box::new(BanksClientError::TransactionError(
+            failed_transaction(code),
+        )));
+        assert_eq!(
+            client_error.into_rebase_error(),
+            TokenClientError::Rebase(error)
+        );
+    }
+
+    let client_error = TokenClientError::Client(Box::new(BanksClientError::TransactionError(
+        failed_transaction(TokenError::OwnerMismatch as u32),
+    )));
+    assert_eq!(
+        client_error.into_rebase_error(),
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            failed_transaction(TokenError::OwnerMismatch as u32)
+        )))
+    );
+    assert_eq!(
+        TokenClientError::MissingDecimals.into_rebase_error(),
+        TokenClientError::MissingDecimals
+    );
+}
+
+#[tokio::test]
+async fn rebase_methods_return_rebase_errors() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .initial_supply(1_000, 0)
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        ..
+    } = context.token_context.take().unwrap();
+
+    let err = token
+        .rebase_supply(&supply_authority.pubkey(), 500, &[&supply_authority])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Rebase(TokenError::SupplyDecreaseForbidden)
+    );
+    let err = token
+        .set_rebase_fee(
+            &supply_authority.pubkey(),
+            10_001,
+            None,
+            &[&supply_authority],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err, TokenClientError::Rebase(TokenError::RebaseFeeTooHigh));
+
+    // failures outside the rebasing mint checks keep their raw form
+    let err = token
+        .rebase_supply(&mint_authority.pubkey(), 2_000, &[&mint_authority])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::OwnerMismatch as u32)
+            )
+        )))
+    );
+}
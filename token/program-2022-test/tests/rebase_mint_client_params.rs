@@ -0,0 +1,68 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{signature::Signer, signer::keypair::Keypair},
+    spl_pod::optional_keys::OptionalNonZeroPubkey,
+    spl_token_2022::{
+        extension::{rebase_mint::RebaseMintConfig, BaseStateWithExtensions, ExtensionType},
+        state::Mint,
+    },
+    spl_token_client::token::ExtensionInitializationParams,
+    std::convert::TryFrom,
+};
+
+#[tokio::test]
+async fn create_through_init_params() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_mint(vec![
+            ExtensionInitializationParams::MintCloseAuthority {
+                close_authority: None,
+            },
+            ExtensionInitializationParams::RebaseMintConfig {
+                supply_authority: Some(supply_authority.pubkey()),
+                initial_supply: 1_000,
+            },
+        ])
+        .await
+        .unwrap();
+    let TokenContext { token, .. } = context.token_context.take().unwrap();
+
+    let state = token.get_mint_info().await.unwrap();
+    assert_eq!(
+        state.get_extension_types().unwrap(),
+        vec![
+            ExtensionType::MintCloseAuthority,
+            ExtensionType::RebaseMintConfig
+        ]
+    );
+    assert_eq!(
+        state.try_get_account_len().unwrap(),
+        ExtensionType::try_calculate_account_len::<Mint>(&[
+            ExtensionType::MintCloseAuthority,
+            ExtensionType::RebaseMintConfig
+        ])
+        .unwrap()
+    );
+    let extension = state.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(
+        extension.supply_authority,
+        OptionalNonZeroPubkey::try_from(Some(supply_authority.pubkey())).unwrap()
+    );
+    assert_eq!(u64::from(extension.total_supply), 1_000);
+    assert_eq!(u64::from(extension.total_shares), 1_000);
+    assert!(!bool::from(extension.allow_supply_decrease));
+
+    // and the mint is usable right away
+    token
+        .rebase_supply(&supply_authority.pubkey(), 2_000, &[&supply_authority])
+        .await
+        .unwrap();
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(u64::from(extension.total_supply), 2_000);
+}
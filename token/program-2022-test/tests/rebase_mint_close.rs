@@ -0,0 +1,159 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        instruction::InstructionError, pubkey::Pubkey, signature::Signer, signer::keypair::Keypair,
+        transaction::TransactionError, transport::TransportError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{instruction, RebaseMintConfig},
+            BaseStateWithExtensions,
+        },
+        id,
+    },
+    spl_token_client::token::{ExtensionInitializationParams, TokenError as TokenClientError},
+};
+
+async fn setup(supply_authority: &Pubkey, close_authority: &Pubkey) -> TestContext {
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint_and_extensions(
+            vec![ExtensionInitializationParams::MintCloseAuthority {
+                close_authority: Some(*close_authority),
+            }],
+            |mint| {
+                instruction::InitializeRebaseMintBuilder::new(&id())
+                    .mint(mint)
+                    .supply_authority(supply_authority)
+                    .allow_supply_decrease()
+                    .build()
+                    .unwrap()
+            },
+        )
+        .await
+        .unwrap();
+    context
+}
+
+#[tokio::test]
+async fn fail_close_with_shares_outstanding() {
+    let supply_authority = Keypair::new();
+    let close_authority = Keypair::new();
+    let mut context = setup(&supply_authority.pubkey(), &close_authority.pubkey()).await;
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        ..
+    } = context.token_context.take().unwrap();
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    let alice_account = alice_account.pubkey();
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            1_000,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    // after doubling, burning the minted amount leaves half the shares
+    token
+        .rebase_supply(&supply_authority.pubkey(), 2_000, &[&supply_authority])
+        .await
+        .unwrap();
+    token
+        .burn(&alice_account, &alice.pubkey(), 1_000, &[&alice])
+        .await
+        .unwrap();
+    let state = token.get_mint_info().await.unwrap();
+    assert_eq!(state.base.supply, 0);
+    let extension = state.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(u64::from(extension.total_shares), 500);
+
+    let destination = Pubkey::new_unique();
+    let error = token
+        .close_account(
+            token.get_address(),
+            &destination,
+            &close_authority.pubkey(),
+            &[&close_authority],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::RebaseMintHasShares as u32)
+            )
+        )))
+    );
+}
+
+#[tokio::test]
+async fn close_once_shares_are_gone() {
+    let supply_authority = Keypair::new();
+    let close_authority = Keypair::new();
+    let mut context = setup(&supply_authority.pubkey(), &close_authority.pubkey()).await;
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        ..
+    } = context.token_context.take().unwrap();
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    let alice_account = alice_account.pubkey();
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            1_000,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    token
+        .burn(&alice_account, &alice.pubkey(), 1_000, &[&alice])
+        .await
+        .unwrap();
+
+    // a leftover supply without shares doesn't hold anyone's tokens
+    token
+        .rebase_supply(&supply_authority.pubkey(), 5, &[&supply_authority])
+        .await
+        .unwrap();
+    let state = token.get_mint_info().await.unwrap();
+    assert_eq!(state.base.supply, 0);
+    let extension = state.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(u64::from(extension.total_supply), 5);
+    assert_eq!(u64::from(extension.total_shares), 0);
+    assert_eq!(u64::from(extension.outstanding_shares), 0);
+
+    let destination = Pubkey::new_unique();
+    token
+        .close_account(
+            token.get_address(),
+            &destination,
+            &close_authority.pubkey(),
+            &[&close_authority],
+        )
+        .await
+        .unwrap();
+    assert!(token.get_mint_info().await.is_err());
+}
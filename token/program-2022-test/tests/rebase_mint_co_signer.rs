@@ -0,0 +1,314 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        instruction::InstructionError, pubkey::Pubkey, signature::Signer, signer::keypair::Keypair,
+        transaction::TransactionError, transport::TransportError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::rebase_mint::instruction::{self, InitializeRebaseMintBuilder},
+        id,
+    },
+    spl_token_client::token::{RebaseSupplyAccounts, TokenError as TokenClientError},
+};
+
+async fn setup(supply_authority: &Pubkey, co_signer: Option<&Pubkey>) -> TestContext {
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            let builder = InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(supply_authority)
+                .allow_supply_decrease()
+                .allow_sunset();
+            match co_signer {
+                Some(co_signer) => builder.co_signer(co_signer),
+                None => builder,
+            }
+            .build()
+            .unwrap()
+        })
+        .await
+        .unwrap();
+    context
+}
+
+fn instruction_error(error: InstructionError) -> TokenClientError {
+    TokenClientError::Client(Box::new(TransportError::TransactionError(
+        TransactionError::InstructionError(0, error),
+    )))
+}
+
+#[tokio::test]
+async fn co_signed_rebase_and_sunset() {
+    let supply_authority = Keypair::new();
+    let co_signer = Keypair::new();
+    let mut context = setup(&supply_authority.pubkey(), Some(&co_signer.pubkey())).await;
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        ..
+    } = context.token_context.take().unwrap();
+
+    let config = token.get_rebase_mint_config().await.unwrap();
+    assert_eq!(
+        Option::<Pubkey>::from(config.co_signer),
+        Some(co_signer.pubkey())
+    );
+
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    token
+        .mint_to(
+            &alice_account.pubkey(),
+            &mint_authority.pubkey(),
+            1_000,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    token
+        .rebase_supply(
+            &supply_authority.pubkey(),
+            2_000,
+            &[&supply_authority, &co_signer],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        token
+            .get_rebased_balance(&alice_account.pubkey())
+            .await
+            .unwrap(),
+        2_000
+    );
+
+    token
+        .sunset_rebase(&supply_authority.pubkey(), &[&supply_authority, &co_signer])
+        .await
+        .unwrap();
+    let config = token.get_rebase_mint_config().await.unwrap();
+    assert!(bool::from(config.is_sunset));
+}
+
+#[tokio::test]
+async fn co_signed_rebase_through_every_client_path() {
+    let supply_authority = Keypair::new();
+    let co_signer = Keypair::new();
+    let mut context = setup(&supply_authority.pubkey(), Some(&co_signer.pubkey())).await;
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        ..
+    } = context.token_context.take().unwrap();
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    token
+        .mint_to(
+            &alice_account.pubkey(),
+            &mint_authority.pubkey(),
+            1_000,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    let accounts = token.get_rebase_supply_accounts().await.unwrap();
+    assert_eq!(accounts.co_signer, Some(co_signer.pubkey()));
+    let signers = [&supply_authority, &co_signer];
+    token
+        .rebase_supply_with_accounts(&supply_authority.pubkey(), &accounts, 2_000, &signers)
+        .await
+        .unwrap();
+    token
+        .rebase_supply_with_reason(
+            &supply_authority.pubkey(),
+            &accounts,
+            3_000,
+            1,
+            [1; 32],
+            &signers,
+        )
+        .await
+        .unwrap();
+    token
+        .rebase_supply_with_deadline(
+            &supply_authority.pubkey(),
+            &accounts,
+            4_000,
+            0,
+            [0; 32],
+            i64::MAX,
+            &signers,
+        )
+        .await
+        .unwrap();
+    token
+        .rebase_supply_ui(
+            &supply_authority.pubkey(),
+            &accounts,
+            "0.000005",
+            0,
+            [0; 32],
+            i64::MAX,
+            &signers,
+        )
+        .await
+        .unwrap();
+    token
+        .safe_rebase_supply(&supply_authority.pubkey(), 6_000, &signers)
+        .await
+        .unwrap();
+    let config = token.get_rebase_mint_config().await.unwrap();
+    assert_eq!(u64::from(config.total_supply), 6_000);
+}
+
+#[tokio::test]
+async fn fail_without_co_signature() {
+    let supply_authority = Keypair::new();
+    let co_signer = Keypair::new();
+    let mut context = setup(&supply_authority.pubkey(), Some(&co_signer.pubkey())).await;
+    let TokenContext { token, .. } = context.token_context.take().unwrap();
+
+    // the co-signer is passed, but doesn't sign
+    let mut rebase = instruction::RebaseSupplyBuilder::new(&id(), 2_000)
+        .mint(token.get_address())
+        .co_signer(&co_signer.pubkey())
+        .authority(&supply_authority.pubkey())
+        .build()
+        .unwrap();
+    rebase.accounts[1].is_signer = false;
+    let error = token
+        .process_ixs(&[rebase], &[&supply_authority])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        instruction_error(InstructionError::MissingRequiredSignature)
+    );
+
+    let mut sunset = instruction::sunset_rebase_with_co_signer(
+        &id(),
+        token.get_address(),
+        Some(&co_signer.pubkey()),
+        &supply_authority.pubkey(),
+        &[],
+    )
+    .unwrap();
+    sunset.accounts[1].is_signer = false;
+    let error = token
+        .process_ixs(&[sunset], &[&supply_authority])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        instruction_error(InstructionError::MissingRequiredSignature)
+    );
+
+    // without the account, the authority is taken for the co-signer
+    let error = token
+        .rebase_supply_with_accounts(
+            &supply_authority.pubkey(),
+            &RebaseSupplyAccounts::default(),
+            2_000,
+            &[&supply_authority],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Rebase(TokenError::RebaseCoSignerMismatch)
+    );
+
+    // the safe rebase catches the missing signature before sending
+    let error = token
+        .safe_rebase_supply(&supply_authority.pubkey(), 2_000, &[&supply_authority])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Program(TokenError::RebaseCoSignerMismatch.into())
+    );
+
+    let config = token.get_rebase_mint_config().await.unwrap();
+    assert!(!bool::from(config.has_rebased));
+    assert!(!bool::from(config.is_sunset));
+}
+
+#[tokio::test]
+async fn fail_with_wrong_co_signer() {
+    let supply_authority = Keypair::new();
+    let co_signer = Keypair::new();
+    let mut context = setup(&supply_authority.pubkey(), Some(&co_signer.pubkey())).await;
+    let TokenContext { token, .. } = context.token_context.take().unwrap();
+
+    let impostor = Keypair::new();
+    let rebase = instruction::RebaseSupplyBuilder::new(&id(), 2_000)
+        .mint(token.get_address())
+        .co_signer(&impostor.pubkey())
+        .authority(&supply_authority.pubkey())
+        .build()
+        .unwrap();
+    let error = token
+        .process_ixs(&[rebase], &[&supply_authority, &impostor])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        instruction_error(InstructionError::Custom(
+            TokenError::RebaseCoSignerMismatch as u32
+        ))
+    );
+
+    let sunset = instruction::sunset_rebase_with_co_signer(
+        &id(),
+        token.get_address(),
+        Some(&impostor.pubkey()),
+        &supply_authority.pubkey(),
+        &[],
+    )
+    .unwrap();
+    let error = token
+        .process_ixs(&[sunset], &[&supply_authority, &impostor])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        instruction_error(InstructionError::Custom(
+            TokenError::RebaseCoSignerMismatch as u32
+        ))
+    );
+}
+
+#[tokio::test]
+async fn single_control_without_co_signer() {
+    let supply_authority = Keypair::new();
+    let mut context = setup(&supply_authority.pubkey(), None).await;
+    let TokenContext { token, .. } = context.token_context.take().unwrap();
+
+    let config = token.get_rebase_mint_config().await.unwrap();
+    assert_eq!(Option::<Pubkey>::from(config.co_signer), None);
+
+    token
+        .rebase_supply(&supply_authority.pubkey(), 2_000, &[&supply_authority])
+        .await
+        .unwrap();
+    let config = token.get_rebase_mint_config().await.unwrap();
+    assert_eq!(u64::from(config.total_supply), 2_000);
+    token
+        .sunset_rebase(&supply_authority.pubkey(), &[&supply_authority])
+        .await
+        .unwrap();
+}
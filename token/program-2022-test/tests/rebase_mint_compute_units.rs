@@ -0,0 +1,284 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{keypair_clone, TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        account::AccountSharedData, instruction::Instruction, pubkey::Pubkey, signature::Signer,
+        signer::keypair::Keypair, system_instruction, transaction::Transaction,
+    },
+    spl_token_2022::{
+        extension::{
+            rebase_mint::{instruction, RebaseMintConfig},
+            ExtensionType, StateWithExtensionsMut,
+        },
+        id,
+        instruction::transfer_checked,
+        state::Mint,
+    },
+    spl_token_client::token::ExtensionInitializationParams,
+};
+
+// Ceilings on the compute units of each instruction, sent alone with a compute
+// unit limit, with some headroom over the current cost. Only raise them on
+// purpose: a rebase is often composed with other instructions in one
+// transaction.
+const INITIALIZE_COMPUTE_UNITS: u32 = 4_000;
+const REBASE_SUPPLY_COMPUTE_UNITS: u32 = 10_000;
+const TRANSFER_CHECKED_COMPUTE_UNITS: u32 = 16_000;
+
+#[tokio::test]
+async fn initialize_compute_units() {
+    let mut context = TestContext::new().await;
+    // only used to send transactions
+    context.init_token_with_mint(vec![]).await.unwrap();
+    let TokenContext { token, .. } = context.token_context.take().unwrap();
+
+    let mint = Keypair::new();
+    let space =
+        ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::RebaseMintConfig])
+            .unwrap();
+    let (payer, lamports) = {
+        let mut context = context.context.lock().await;
+        let rent = context.banks_client.get_rent().await.unwrap();
+        (keypair_clone(&context.payer), rent.minimum_balance(space))
+    };
+    token
+        .process_ixs(
+            &[system_instruction::create_account(
+                &payer.pubkey(),
+                &mint.pubkey(),
+                lamports,
+                space as u64,
+                &id(),
+            )],
+            &[&mint],
+        )
+        .await
+        .unwrap();
+
+    token
+        .process_ixs_with_additional_compute_budget(
+            &[instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(&mint.pubkey())
+                .supply_authority(&Pubkey::new_unique())
+                .initial_supply(1_000, 0)
+                .allow_supply_decrease()
+                .build()
+                .unwrap()],
+            INITIALIZE_COMPUTE_UNITS,
+            &[] as &[&dyn Signer; 0],
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn rebase_supply_and_transfer_compute_units() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_mint(vec![ExtensionInitializationParams::RebaseMintConfig {
+            supply_authority: Some(supply_authority.pubkey()),
+            initial_supply: 0,
+        }])
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        decimals,
+        mint_authority,
+        alice,
+        bob,
+        ..
+    } = context.token_context.take().unwrap();
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    let bob_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&bob_account, &bob.pubkey())
+        .await
+        .unwrap();
+    token
+        .mint_to(
+            &alice_account.pubkey(),
+            &mint_authority.pubkey(),
+            3_000_000,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    // an uneven ratio, so that every conversion divides
+    token
+        .process_ixs_with_additional_compute_budget(
+            &[instruction::update_supply(
+                &id(),
+                token.get_address(),
+                None,
+                &supply_authority.pubkey(),
+                &[],
+                7_000_001,
+            )
+            .unwrap()],
+            REBASE_SUPPLY_COMPUTE_UNITS,
+            &[&supply_authority],
+        )
+        .await
+        .unwrap();
+
+    token
+        .process_ixs_with_additional_compute_budget(
+            &[transfer_checked(
+                &id(),
+                &alice_account.pubkey(),
+                token.get_address(),
+                &bob_account.pubkey(),
+                &alice.pubkey(),
+                &[],
+                1_234_567,
+                decimals,
+            )
+            .unwrap()],
+            TRANSFER_CHECKED_COMPUTE_UNITS,
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        token
+            .get_rebased_balance(&bob_account.pubkey())
+            .await
+            .unwrap(),
+        1_234_566
+    );
+}
+
+async fn compute_units_consumed(context: &TestContext, ix: Instruction, signer: &Keypair) -> u64 {
+    let mut context = context.context.lock().await;
+    let blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let transaction = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, signer],
+        blockhash,
+    );
+    let outcome = context
+        .banks_client
+        .process_transaction_with_metadata(transaction)
+        .await
+        .unwrap();
+    outcome.result.unwrap();
+    outcome.metadata.unwrap().compute_units_consumed
+}
+
+#[tokio::test]
+async fn cached_ratio_saves_compute_units() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_mint(vec![ExtensionInitializationParams::RebaseMintConfig {
+            supply_authority: Some(supply_authority.pubkey()),
+            initial_supply: 0,
+        }])
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        decimals,
+        mint_authority,
+        alice,
+        bob,
+        ..
+    } = context.token_context.take().unwrap();
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    let bob_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&bob_account, &bob.pubkey())
+        .await
+        .unwrap();
+    token
+        .mint_to(
+            &alice_account.pubkey(),
+            &mint_authority.pubkey(),
+            3_000_000,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    token
+        .rebase_supply(&supply_authority.pubkey(), 7_000_001, &[&supply_authority])
+        .await
+        .unwrap();
+    let transfer_ix = || {
+        transfer_checked(
+            &id(),
+            &alice_account.pubkey(),
+            token.get_address(),
+            &bob_account.pubkey(),
+            &alice.pubkey(),
+            &[],
+            1_234_567,
+            decimals,
+        )
+        .unwrap()
+    };
+
+    let cached = compute_units_consumed(&context, transfer_ix(), &alice).await;
+
+    // the same transfer, with the conversions falling back to division: first
+    // from tokens to shares only, then both ways
+    let clear_cache = |clear_ratio: bool| {
+        let context = context.context.clone();
+        let mint_address = *token.get_address();
+        async move {
+            let mut context = context.lock().await;
+            let mut mint_account = context
+                .banks_client
+                .get_account(mint_address)
+                .await
+                .unwrap()
+                .unwrap();
+            let mut mint = StateWithExtensionsMut::<Mint>::unpack(&mut mint_account.data).unwrap();
+            let config = mint.get_extension_mut::<RebaseMintConfig>().unwrap();
+            if clear_ratio {
+                assert_ne!(u128::from(config.cached_ratio), 0);
+                config.cached_ratio = 0.into();
+            } else {
+                assert_ne!(u128::from(config.cached_inverse_ratio), 0);
+                config.cached_inverse_ratio = 0.into();
+            }
+            context.set_account(&mint_address, &AccountSharedData::from(mint_account));
+        }
+    };
+    clear_cache(false).await;
+    let uncached_shares = compute_units_consumed(&context, transfer_ix(), &alice).await;
+    clear_cache(true).await;
+    let uncached = compute_units_consumed(&context, transfer_ix(), &alice).await;
+
+    assert!(
+        cached <= TRANSFER_CHECKED_COMPUTE_UNITS as u64,
+        "{}",
+        cached
+    );
+    assert!(
+        cached < uncached_shares,
+        "{} >= {}",
+        cached,
+        uncached_shares
+    );
+    assert!(
+        uncached_shares < uncached,
+        "{} >= {}",
+        uncached_shares,
+        uncached
+    );
+}
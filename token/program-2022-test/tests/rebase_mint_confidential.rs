@@ -0,0 +1,157 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        account::Account as SolanaAccount,
+        instruction::{Instruction, InstructionError},
+        pubkey::Pubkey,
+        signature::Signer,
+        signer::keypair::Keypair,
+        transaction::TransactionError,
+        transport::TransportError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            confidential_transfer::ConfidentialTransferMint, rebase_mint::instruction,
+            ExtensionType, StateWithExtensionsMut,
+        },
+        id,
+        solana_zk_token_sdk::zk_token_elgamal::pod::ElGamalPubkey,
+        state::Mint,
+    },
+    spl_token_client::token::{ExtensionInitializationParams, TokenError as TokenClientError},
+};
+
+fn initialize_rebase_mint(mint: &Pubkey) -> Instruction {
+    instruction::InitializeRebaseMintBuilder::new(&id())
+        .mint(mint)
+        .supply_authority(&Pubkey::new_unique())
+        .allow_supply_decrease()
+        .build()
+        .unwrap()
+}
+
+fn invalid_extension_combination(instruction_index: u8) -> TokenClientError {
+    TokenClientError::Client(Box::new(TransportError::TransactionError(
+        TransactionError::InstructionError(
+            instruction_index,
+            InstructionError::Custom(TokenError::InvalidExtensionCombination as u32),
+        ),
+    )))
+}
+
+#[tokio::test]
+async fn fail_initialize_with_confidential_transfers() {
+    let mut context = TestContext::new().await;
+    let err = context
+        .init_token_with_rebase_mint_and_extensions(
+            vec![ExtensionInitializationParams::ConfidentialTransferMint {
+                authority: None,
+                auto_approve_new_accounts: true,
+                auditor_elgamal_pubkey: None,
+            }],
+            initialize_rebase_mint,
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err, invalid_extension_combination(3));
+
+    // the fee extensions are valid together, until the mint is rebasing
+    let err = context
+        .init_token_with_rebase_mint_and_extensions(
+            vec![
+                ExtensionInitializationParams::TransferFeeConfig {
+                    transfer_fee_config_authority: None,
+                    withdraw_withheld_authority: None,
+                    transfer_fee_basis_points: 100,
+                    maximum_fee: u64::MAX,
+                },
+                ExtensionInitializationParams::ConfidentialTransferMint {
+                    authority: None,
+                    auto_approve_new_accounts: true,
+                    auditor_elgamal_pubkey: None,
+                },
+                ExtensionInitializationParams::ConfidentialTransferFeeConfig {
+                    authority: None,
+                    withdraw_withheld_authority_elgamal_pubkey: ElGamalPubkey::default(),
+                },
+            ],
+            initialize_rebase_mint,
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err, invalid_extension_combination(5));
+}
+
+#[tokio::test]
+async fn fail_confidential_deposit_on_rebase_mint() {
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(initialize_rebase_mint)
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        decimals,
+        ..
+    } = context.token_context.take().unwrap();
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    let alice_account = alice_account.pubkey();
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            100,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    // forge a rebasing mint that also allows confidential transfers, which
+    // mint initialization can't produce
+    {
+        let mut context = context.context.lock().await;
+        let mint_account = context
+            .banks_client
+            .get_account(*token.get_address())
+            .await
+            .unwrap()
+            .unwrap();
+        let mut data = vec![
+            0;
+            ExtensionType::try_calculate_account_len::<Mint>(&[
+                ExtensionType::RebaseMintConfig,
+                ExtensionType::ConfidentialTransferMint,
+            ])
+            .unwrap()
+        ];
+        data[..mint_account.data.len()].copy_from_slice(&mint_account.data);
+        let mut mint = StateWithExtensionsMut::<Mint>::unpack(&mut data).unwrap();
+        mint.init_extension::<ConfidentialTransferMint>(true)
+            .unwrap();
+        context.set_account(
+            token.get_address(),
+            &SolanaAccount {
+                data,
+                ..mint_account
+            }
+            .into(),
+        );
+    }
+
+    let err = token
+        .confidential_transfer_deposit(&alice_account, &alice.pubkey(), 100, decimals, &[&alice])
+        .await
+        .unwrap_err();
+    assert_eq!(err, invalid_extension_combination(0));
+}
@@ -0,0 +1,174 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        account::Account as SolanaAccount, instruction::InstructionError, pubkey::Pubkey,
+        signature::Signer, signer::keypair::Keypair, transaction::TransactionError,
+        transport::TransportError,
+    },
+    spl_pod::bytemuck::pod_from_bytes,
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{instruction, RebaseConsistency, RebaseMintConfig},
+            StateWithExtensionsMut,
+        },
+        id,
+        state::Mint,
+    },
+    spl_token_client::token::TokenError as TokenClientError,
+};
+
+#[tokio::test]
+async fn assert_catches_corruption() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        ..
+    } = context.token_context.take().unwrap();
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    token
+        .mint_to(
+            &alice_account.pubkey(),
+            &mint_authority.pubkey(),
+            100,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    // no signature needed
+    let assert_ix = instruction::assert_rebase_consistency(&id(), token.get_address()).unwrap();
+    let simulation = token
+        .simulate_ixs(&[assert_ix.clone()], &[] as &[&dyn Signer; 0])
+        .await
+        .unwrap();
+    assert!(simulation.result.unwrap().is_ok());
+    let return_data = simulation.simulation_details.unwrap().return_data.unwrap();
+    let consistency = *pod_from_bytes::<RebaseConsistency>(&return_data.data).unwrap();
+    assert_eq!(
+        consistency,
+        RebaseConsistency {
+            mint_supply: 100.into(),
+            total_supply: 100.into(),
+            total_shares: 100.into(),
+            outstanding_shares: 100.into(),
+            exempt_supply: 0.into(),
+        }
+    );
+    token
+        .process_ixs(&[assert_ix.clone()], &[] as &[&dyn Signer; 0])
+        .await
+        .unwrap();
+
+    // drop the total supply to zero while shares remain
+    {
+        let mut context = context.context.lock().await;
+        let mint_account = context
+            .banks_client
+            .get_account(*token.get_address())
+            .await
+            .unwrap()
+            .unwrap();
+        let mut data = mint_account.data.clone();
+        let mut mint = StateWithExtensionsMut::<Mint>::unpack(&mut data).unwrap();
+        let extension = mint.get_extension_mut::<RebaseMintConfig>().unwrap();
+        extension.total_supply = 0.into();
+        context.set_account(
+            token.get_address(),
+            &SolanaAccount {
+                data,
+                ..mint_account
+            }
+            .into(),
+        );
+    }
+
+    // the same transaction as before would be a duplicate
+    token.get_new_latest_blockhash().await.unwrap();
+    let err = token
+        .process_ixs(&[assert_ix], &[] as &[&dyn Signer; 0])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::RebaseInconsistent as u32)
+            )
+        )))
+    );
+}
+
+#[tokio::test]
+async fn assert_rejects_foreign_mint() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext { token, .. } = context.token_context.take().unwrap();
+
+    // a copy of a real mint, owned by another program
+    let forged = Pubkey::new_unique();
+    {
+        let mut context = context.context.lock().await;
+        let mint_account = context
+            .banks_client
+            .get_account(*token.get_address())
+            .await
+            .unwrap()
+            .unwrap();
+        context.set_account(
+            &forged,
+            &SolanaAccount {
+                owner: Pubkey::new_unique(),
+                ..mint_account
+            }
+            .into(),
+        );
+    }
+
+    let err = token
+        .process_ixs(
+            &[instruction::assert_rebase_consistency(&id(), &forged).unwrap()],
+            &[] as &[&dyn Signer; 0],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(0, InstructionError::IncorrectProgramId)
+        )))
+    );
+}
@@ -0,0 +1,246 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::{
+        processor,
+        tokio::{self, sync::Mutex},
+        ProgramTest,
+    },
+    solana_sdk::{
+        account_info::{next_account_info, AccountInfo},
+        entrypoint::ProgramResult,
+        instruction::{AccountMeta, Instruction, InstructionError},
+        msg,
+        program::{get_return_data, invoke},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+        signature::Signer,
+        signer::keypair::Keypair,
+        transaction::TransactionError,
+        transport::TransportError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{instruction, RebaseMintConfig},
+            BaseStateWithExtensions, StateWithExtensions,
+        },
+        id,
+        processor::Processor,
+        state::Mint,
+    },
+    spl_token_client::{
+        client::{ProgramBanksClient, ProgramBanksClientProcessTransaction, ProgramClient},
+        token::{Token, TokenError as TokenClientError},
+    },
+    std::{convert::TryInto, sync::Arc},
+};
+
+/// Converts the little-endian `u64` in the instruction data both ways through
+/// CPI, and checks the results against the library conversions
+fn process_instruction(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    input: &[u8],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let mint_info = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let value = u64::from_le_bytes(
+        input
+            .try_into()
+            .map_err(|_| ProgramError::InvalidInstructionData)?,
+    );
+
+    invoke(
+        &instruction::convert_amount_to_shares(token_program.key, mint_info.key, value)?,
+        &[mint_info.clone(), token_program.clone()],
+    )?;
+    let (_, return_data) = get_return_data().unwrap();
+    let shares = u64::from_le_bytes(return_data[0..8].try_into().unwrap());
+    msg!("shares: {}", shares);
+
+    invoke(
+        &instruction::convert_shares_to_amount(token_program.key, mint_info.key, value)?,
+        &[mint_info.clone(), token_program.clone()],
+    )?;
+    let (_, return_data) = get_return_data().unwrap();
+    let amount = u64::from_le_bytes(return_data[0..8].try_into().unwrap());
+    msg!("amount: {}", amount);
+
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<Mint>::unpack(&mint_data)?;
+    let config = mint.get_extension::<RebaseMintConfig>()?;
+    if config.amount_to_shares(value) != Some(shares)
+        || config.shares_to_amount(value) != Some(amount)
+    {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn conversions_match_library() {
+    let supply_authority = Keypair::new();
+    let mut program_test = ProgramTest::new("spl_token_2022", id(), processor!(Processor::process));
+    let program_id = Pubkey::new_unique();
+    program_test.add_program(
+        "rebase_mint_convert",
+        program_id,
+        processor!(process_instruction),
+    );
+    let context = program_test.start_with_context().await;
+    let mut context = TestContext {
+        context: Arc::new(Mutex::new(context)),
+        token_context: None,
+    };
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .initial_supply(1_000, 0)
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext { token, .. } = context.token_context.take().unwrap();
+    let convert_ix = |value: u64| Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*token.get_address(), false),
+            AccountMeta::new_readonly(id(), false),
+        ],
+        data: value.to_le_bytes().to_vec(),
+    };
+
+    token
+        .rebase_supply(&supply_authority.pubkey(), 1_500, &[&supply_authority])
+        .await
+        .unwrap();
+    for value in [0, 1, 7, 1_000, 1_000_000_000_000] {
+        token
+            .process_ixs(&[convert_ix(value)], &[] as &[&dyn Signer; 0])
+            .await
+            .unwrap();
+    }
+
+    // conversions that don't fit fail instead of truncating
+    let err = token
+        .process_ixs(&[convert_ix(u64::MAX)], &[] as &[&dyn Signer; 0])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::Overflow as u32)
+            )
+        )))
+    );
+}
+
+async fn simulate_conversion(
+    token: &Token<ProgramBanksClientProcessTransaction>,
+    instruction: Instruction,
+) -> u64 {
+    let simulation = token
+        .simulate_ixs(&[instruction], &[] as &[&dyn Signer; 0])
+        .await
+        .unwrap();
+    assert!(simulation.result.unwrap().is_ok());
+    let return_data = simulation.simulation_details.unwrap().return_data.unwrap();
+    u64::from_le_bytes(return_data.data[0..8].try_into().unwrap())
+}
+
+#[tokio::test]
+async fn cached_conversions_follow_refresh() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .initial_supply(1_000, 0)
+                .virtual_shares_offset(3)
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext { token, .. } = context.token_context.take().unwrap();
+
+    assert_eq!(
+        token.amount_to_shares_cached(1).unwrap_err(),
+        TokenClientError::UncachedRebaseMintConfig
+    );
+    token.refresh_rebase_mint_config().await.unwrap();
+
+    let values = [0, 1, 7, 999, 1_000_000_000_000];
+    for new_supply in [1_501, 333] {
+        token
+            .rebase_supply(&supply_authority.pubkey(), new_supply, &[&supply_authority])
+            .await
+            .unwrap();
+
+        // the cache doesn't see the rebase until refreshed
+        let onchain_shares = simulate_conversion(
+            &token,
+            instruction::convert_amount_to_shares(&id(), token.get_address(), 999).unwrap(),
+        )
+        .await;
+        assert_ne!(token.amount_to_shares_cached(999).unwrap(), onchain_shares);
+        token.refresh_rebase_mint_config().await.unwrap();
+        assert_eq!(token.amount_to_shares_cached(999).unwrap(), onchain_shares);
+
+        for value in values {
+            assert_eq!(
+                token.amount_to_shares_cached(value).unwrap(),
+                simulate_conversion(
+                    &token,
+                    instruction::convert_amount_to_shares(&id(), token.get_address(), value)
+                        .unwrap()
+                )
+                .await
+            );
+            assert_eq!(
+                token.shares_to_amount_cached(value).unwrap(),
+                simulate_conversion(
+                    &token,
+                    instruction::convert_shares_to_amount(&id(), token.get_address(), value)
+                        .unwrap()
+                )
+                .await
+            );
+        }
+    }
+
+    // a snapshot handed in by the caller needs no fetch at all
+    let config = token.get_rebase_mint_config().await.unwrap();
+    let client: Arc<dyn ProgramClient<ProgramBanksClientProcessTransaction>> =
+        Arc::new(ProgramBanksClient::new_from_context(
+            Arc::clone(&context.context),
+            ProgramBanksClientProcessTransaction,
+        ));
+    let snapshot_token = Token::new(
+        client,
+        &id(),
+        token.get_address(),
+        None,
+        Arc::new(Keypair::new()),
+    )
+    .with_rebase_mint_config(config);
+    for value in values {
+        assert_eq!(
+            snapshot_token.shares_to_amount_cached(value).unwrap(),
+            token.shares_to_amount_cached(value).unwrap()
+        );
+    }
+}
@@ -0,0 +1,284 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::{
+        processor,
+        tokio::{self, sync::Mutex},
+        ProgramTest,
+    },
+    solana_sdk::{
+        account_info::{next_account_info, AccountInfo},
+        entrypoint::ProgramResult,
+        instruction::{AccountMeta, Instruction, InstructionError},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+        signature::Signer,
+        signer::keypair::Keypair,
+        transaction::TransactionError,
+        transport::TransportError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{self, instruction, RebaseMintConfig},
+            BaseStateWithExtensions,
+        },
+        id,
+        processor::Processor,
+    },
+    spl_token_client::token::TokenError as TokenClientError,
+    std::sync::Arc,
+};
+
+/// Rebases the mint through CPI, signing for the PDA derived from the seed in
+/// the instruction data
+///
+/// Instruction data is the new supply as a little-endian `u64` followed by
+/// the seed. Any accounts after the token program are the signing members
+/// of a multisig authority, which may include the PDA.
+fn process_rebase_with_pda(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    input: &[u8],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let mint_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let signer_infos = account_info_iter.as_slice();
+
+    if input.len() < 8 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let (new_supply, seed) = input.split_at(8);
+    let new_supply = u64::from_le_bytes(
+        new_supply
+            .try_into()
+            .map_err(|_| ProgramError::InvalidInstructionData)?,
+    );
+    let (authority, bump_seed) = Pubkey::find_program_address(&[seed], program_id);
+    if authority != *authority_info.key && !signer_infos.iter().any(|ai| *ai.key == authority) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    rebase_mint::onchain::invoke_rebase_supply(
+        token_program_info.key,
+        mint_info.clone(),
+        None,
+        authority_info.clone(),
+        signer_infos,
+        new_supply,
+        &[&[seed, &[bump_seed]]],
+    )
+}
+
+fn rebase_with_pda(
+    program_id: &Pubkey,
+    mint: &Pubkey,
+    seed: &[u8],
+    new_supply: u64,
+) -> Instruction {
+    let (authority, _) = Pubkey::find_program_address(&[seed], program_id);
+    let mut data = new_supply.to_le_bytes().to_vec();
+    data.extend_from_slice(seed);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*mint, false),
+            AccountMeta::new_readonly(authority, false),
+            AccountMeta::new_readonly(id(), false),
+        ],
+        data,
+    }
+}
+
+fn rebase_with_pda_multisig_member(
+    program_id: &Pubkey,
+    mint: &Pubkey,
+    multisig: &Pubkey,
+    seed: &[u8],
+    other_signers: &[&Pubkey],
+    new_supply: u64,
+) -> Instruction {
+    let (pda_member, _) = Pubkey::find_program_address(&[seed], program_id);
+    let mut data = new_supply.to_le_bytes().to_vec();
+    data.extend_from_slice(seed);
+    let mut accounts = vec![
+        AccountMeta::new(*mint, false),
+        AccountMeta::new_readonly(*multisig, false),
+        AccountMeta::new_readonly(id(), false),
+        AccountMeta::new_readonly(pda_member, false),
+    ];
+    for signer in other_signers {
+        accounts.push(AccountMeta::new_readonly(**signer, true));
+    }
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    }
+}
+
+#[tokio::test]
+async fn pda_supply_authority() {
+    let pda_program_id = Pubkey::new_unique();
+    let seed: &[u8] = b"treasury";
+    let (pda_authority, _) = Pubkey::find_program_address(&[seed], &pda_program_id);
+
+    let mut program_test = ProgramTest::new("spl_token_2022", id(), processor!(Processor::process));
+    program_test.add_program(
+        "rebase_with_pda",
+        pda_program_id,
+        processor!(process_rebase_with_pda),
+    );
+    let context = program_test.start_with_context().await;
+    let mut context = TestContext {
+        context: Arc::new(Mutex::new(context)),
+        token_context: None,
+    };
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&pda_authority)
+                .initial_supply(1_000, 0)
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext { token, .. } = context.token_context.take().unwrap();
+
+    token
+        .process_ixs(
+            &[rebase_with_pda(
+                &pda_program_id,
+                token.get_address(),
+                seed,
+                2_000,
+            )],
+            &[] as &[&dyn Signer; 0],
+        )
+        .await
+        .unwrap();
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(u64::from(extension.total_supply), 2_000);
+
+    // signing with the wrong seeds produces some other address
+    let err = token
+        .process_ixs(
+            &[rebase_with_pda(
+                &pda_program_id,
+                token.get_address(),
+                b"not the treasury",
+                3_000,
+            )],
+            &[] as &[&dyn Signer; 0],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::OwnerMismatch as u32)
+            )
+        )))
+    );
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(u64::from(extension.total_supply), 2_000);
+}
+
+#[tokio::test]
+async fn pda_multisig_member() {
+    let pda_program_id = Pubkey::new_unique();
+    let seed: &[u8] = b"treasury";
+    let (pda_member, _) = Pubkey::find_program_address(&[seed], &pda_program_id);
+    let multisig = Keypair::new();
+    let other_members = [Keypair::new(), Keypair::new()];
+
+    let mut program_test = ProgramTest::new("spl_token_2022", id(), processor!(Processor::process));
+    program_test.add_program(
+        "rebase_with_pda",
+        pda_program_id,
+        processor!(process_rebase_with_pda),
+    );
+    let context = program_test.start_with_context().await;
+    let mut context = TestContext {
+        context: Arc::new(Mutex::new(context)),
+        token_context: None,
+    };
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&multisig.pubkey())
+                .initial_supply(1_000, 0)
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext { token, .. } = context.token_context.take().unwrap();
+    token
+        .create_multisig(
+            &multisig,
+            &[
+                &pda_member,
+                &other_members[0].pubkey(),
+                &other_members[1].pubkey(),
+            ],
+            2,
+        )
+        .await
+        .unwrap();
+
+    // the PDA alone doesn't meet the threshold
+    let err = token
+        .process_ixs(
+            &[rebase_with_pda_multisig_member(
+                &pda_program_id,
+                token.get_address(),
+                &multisig.pubkey(),
+                seed,
+                &[],
+                2_000,
+            )],
+            &[] as &[&dyn Signer; 0],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature)
+        )))
+    );
+
+    // the PDA and one other member do
+    token
+        .process_ixs(
+            &[rebase_with_pda_multisig_member(
+                &pda_program_id,
+                token.get_address(),
+                &multisig.pubkey(),
+                seed,
+                &[&other_members[1].pubkey()],
+                2_000,
+            )],
+            &[&other_members[1]],
+        )
+        .await
+        .unwrap();
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(u64::from(extension.total_supply), 2_000);
+    assert_eq!(u64::from(extension.total_shares), 1_000);
+}
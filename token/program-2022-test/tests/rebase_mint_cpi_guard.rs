@@ -0,0 +1,190 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::{
+        processor,
+        tokio::{self, sync::Mutex},
+        ProgramTest,
+    },
+    solana_sdk::{
+        instruction::{Instruction, InstructionError},
+        signature::Signer,
+        signer::keypair::Keypair,
+        transaction::TransactionError,
+        transport::TransportError,
+    },
+    spl_instruction_padding::instruction::wrap_instruction,
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{instruction, RebaseAccount},
+            BaseStateWithExtensions, ExtensionType,
+        },
+        id,
+        processor::Processor as SplToken2022Processor,
+    },
+    spl_token_client::token::TokenError as TokenClientError,
+    std::sync::Arc,
+};
+
+fn client_error(token_error: TokenError) -> TokenClientError {
+    TokenClientError::Client(Box::new(TransportError::TransactionError(
+        TransactionError::InstructionError(0, InstructionError::Custom(token_error as u32)),
+    )))
+}
+
+fn wrap(instruction: Instruction) -> Instruction {
+    wrap_instruction(spl_instruction_padding::id(), instruction, vec![], 0).unwrap()
+}
+
+#[tokio::test]
+async fn share_instructions_honor_cpi_guard() {
+    // TODO this may be removed when we upgrade to a solana version with a fixed
+    // `get_stack_height()` stub
+    if std::env::var("BPF_OUT_DIR").is_err() && std::env::var("SBF_OUT_DIR").is_err() {
+        panic!("CpiGuard tests MUST be invoked with `cargo test-sbf`, NOT `cargo test --feature test-sbf`. \
+                In a non-BPF context, `get_stack_height()` always returns 0, and all tests WILL fail.");
+    }
+
+    let mut program_test = ProgramTest::new(
+        "spl_token_2022",
+        spl_token_2022::id(),
+        processor!(SplToken2022Processor::process),
+    );
+    program_test.add_program(
+        "spl_instruction_padding",
+        spl_instruction_padding::id(),
+        processor!(spl_instruction_padding::processor::process),
+    );
+    let program_context = program_test.start_with_context().await;
+    let mut context = TestContext {
+        context: Arc::new(Mutex::new(program_context)),
+        token_context: None,
+    };
+
+    let supply_authority = Keypair::new();
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        bob,
+        decimals,
+        ..
+    } = context.token_context.take().unwrap();
+    token
+        .create_auxiliary_token_account_with_extension_space(
+            &alice,
+            &alice.pubkey(),
+            vec![ExtensionType::CpiGuard],
+        )
+        .await
+        .unwrap();
+    token
+        .create_auxiliary_token_account(&bob, &bob.pubkey())
+        .await
+        .unwrap();
+    token
+        .mint_to(
+            &alice.pubkey(),
+            &mint_authority.pubkey(),
+            1_000,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    token
+        .enable_cpi_guard(&alice.pubkey(), &alice.pubkey(), &[&alice])
+        .await
+        .unwrap();
+
+    let transfer_shares = instruction::transfer_shares_checked(
+        &id(),
+        &alice.pubkey(),
+        token.get_address(),
+        &bob.pubkey(),
+        &alice.pubkey(),
+        &[],
+        100,
+        decimals,
+    )
+    .unwrap();
+    let transfer_with_share_slippage = instruction::transfer_checked_with_share_slippage(
+        &id(),
+        &alice.pubkey(),
+        token.get_address(),
+        &bob.pubkey(),
+        &alice.pubkey(),
+        &[],
+        100,
+        decimals,
+        100,
+        0,
+    )
+    .unwrap();
+    let burn_shares = instruction::burn_shares(
+        &id(),
+        &alice.pubkey(),
+        token.get_address(),
+        &alice.pubkey(),
+        &[],
+        100,
+    )
+    .unwrap();
+    let approve_shares = instruction::approve_shares(
+        &id(),
+        &alice.pubkey(),
+        token.get_address(),
+        &bob.pubkey(),
+        &alice.pubkey(),
+        &[],
+        100,
+    )
+    .unwrap();
+
+    // each share instruction is blocked through cpi, and works directly
+    for (share_instruction, token_error) in [
+        (transfer_shares, TokenError::CpiGuardTransferBlocked),
+        (
+            transfer_with_share_slippage,
+            TokenError::CpiGuardTransferBlocked,
+        ),
+        (burn_shares, TokenError::CpiGuardBurnBlocked),
+        (approve_shares, TokenError::CpiGuardApproveBlocked),
+    ] {
+        let error = token
+            .process_ixs(&[wrap(share_instruction.clone())], &[&alice])
+            .await
+            .unwrap_err();
+        assert_eq!(error, client_error(token_error));
+
+        token
+            .process_ixs(&[share_instruction], &[&alice])
+            .await
+            .unwrap();
+    }
+
+    let alice_state = token.get_account_info(&alice.pubkey()).await.unwrap();
+    assert_eq!(
+        u64::from(alice_state.get_extension::<RebaseAccount>().unwrap().shares),
+        700
+    );
+    assert_eq!(alice_state.base.delegated_amount, 100);
+    let bob_state = token.get_account_info(&bob.pubkey()).await.unwrap();
+    assert_eq!(
+        u64::from(bob_state.get_extension::<RebaseAccount>().unwrap().shares),
+        200
+    );
+}
@@ -0,0 +1,145 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{keypair_clone, TestContext},
+    solana_program_test::tokio,
+    solana_sdk::{pubkey::Pubkey, signature::Signer, signer::keypair::Keypair},
+    spl_token_2022::{
+        extension::{
+            metadata_pointer::MetadataPointer, rebase_mint::RebaseMintConfig,
+            BaseStateWithExtensions,
+        },
+        id,
+    },
+    spl_token_client::{
+        client::{ProgramBanksClient, ProgramBanksClientProcessTransaction, ProgramClient},
+        token::{RebaseMintMetadata, Token},
+    },
+    spl_token_metadata_interface::state::TokenMetadata,
+    std::sync::Arc,
+};
+
+#[tokio::test]
+async fn create_rebase_and_read_balances() {
+    let context = TestContext::new().await;
+    let payer = keypair_clone(&context.context.lock().await.payer);
+    let client: Arc<dyn ProgramClient<ProgramBanksClientProcessTransaction>> =
+        Arc::new(ProgramBanksClient::new_from_context(
+            Arc::clone(&context.context),
+            ProgramBanksClientProcessTransaction,
+        ));
+    let mint = Keypair::new();
+    let mint_authority = Keypair::new();
+    let freeze_authority = Keypair::new();
+    let supply_authority = Keypair::new();
+
+    let token = Token::create_rebase_mint(
+        client,
+        &id(),
+        &mint.pubkey(),
+        6,
+        &mint_authority.pubkey(),
+        Some(&freeze_authority.pubkey()),
+        Some(supply_authority.pubkey()),
+        0,
+        Some(RebaseMintMetadata {
+            update_authority: mint_authority.pubkey(),
+            name: "Rebasing".to_string(),
+            symbol: "RBS".to_string(),
+            uri: "https://example.com/rbs.json".to_string(),
+        }),
+        Arc::new(payer),
+        &[&mint, &mint_authority],
+    )
+    .await
+    .unwrap();
+
+    let state = token.get_mint_info().await.unwrap();
+    assert_eq!(state.base.decimals, 6);
+    assert_eq!(
+        Option::<Pubkey>::from(state.base.freeze_authority),
+        Some(freeze_authority.pubkey())
+    );
+    let extension = state.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(
+        Option::<Pubkey>::from(extension.supply_authority),
+        Some(supply_authority.pubkey())
+    );
+    let pointer = state.get_extension::<MetadataPointer>().unwrap();
+    assert_eq!(
+        Option::<Pubkey>::from(pointer.metadata_address),
+        Some(mint.pubkey())
+    );
+    let metadata = state.get_variable_len_extension::<TokenMetadata>().unwrap();
+    assert_eq!(metadata.symbol, "RBS");
+
+    let alice = Keypair::new();
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    token
+        .mint_to(
+            &alice_account.pubkey(),
+            &mint_authority.pubkey(),
+            2_000_000,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    token
+        .rebase_supply(&supply_authority.pubkey(), 3_000_000, &[&supply_authority])
+        .await
+        .unwrap();
+    assert_eq!(
+        token
+            .get_rebased_balance(&alice_account.pubkey())
+            .await
+            .unwrap(),
+        3_000_000
+    );
+    assert_eq!(
+        token
+            .get_rebased_ui_balance(&alice_account.pubkey())
+            .await
+            .unwrap(),
+        "3"
+    );
+}
+
+#[tokio::test]
+async fn create_without_metadata() {
+    let context = TestContext::new().await;
+    let payer = keypair_clone(&context.context.lock().await.payer);
+    let client: Arc<dyn ProgramClient<ProgramBanksClientProcessTransaction>> =
+        Arc::new(ProgramBanksClient::new_from_context(
+            Arc::clone(&context.context),
+            ProgramBanksClientProcessTransaction,
+        ));
+    let mint = Keypair::new();
+    let mint_authority = Keypair::new();
+
+    let token = Token::create_rebase_mint(
+        client,
+        &id(),
+        &mint.pubkey(),
+        9,
+        &mint_authority.pubkey(),
+        None,
+        None,
+        1_000,
+        None,
+        Arc::new(payer),
+        &[&mint],
+    )
+    .await
+    .unwrap();
+
+    let state = token.get_mint_info().await.unwrap();
+    assert!(state.get_extension::<MetadataPointer>().is_err());
+    let extension = token.get_rebase_mint_config().await.unwrap();
+    assert_eq!(Option::<Pubkey>::from(extension.supply_authority), None);
+    assert_eq!(u64::from(extension.total_supply), 1_000);
+}
@@ -0,0 +1,85 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{signature::Signer, signer::keypair::Keypair},
+    spl_token_2022::{extension::rebase_mint::instruction, id},
+    spl_token_client::{client::ProgramBanksClientProcessTransaction, token::Token},
+};
+
+async fn get_cumulative_changes(
+    token: &Token<ProgramBanksClientProcessTransaction>,
+) -> (u128, u128) {
+    let config = token.get_rebase_mint_config().await.unwrap();
+    (
+        config.cumulative_supply_increase.into(),
+        config.cumulative_supply_decrease.into(),
+    )
+}
+
+#[tokio::test]
+async fn cumulative_changes_add_up() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        ..
+    } = context.token_context.take().unwrap();
+    let account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&account, &alice.pubkey())
+        .await
+        .unwrap();
+    token
+        .mint_to(
+            &account.pubkey(),
+            &mint_authority.pubkey(),
+            1_000,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    // minting isn't rebasing
+    assert_eq!(get_cumulative_changes(&token).await, (0, 0));
+
+    for new_supply in [1_500, 1_200, 1_200, 2_000] {
+        token
+            .rebase_supply(&supply_authority.pubkey(), new_supply, &[&supply_authority])
+            .await
+            .unwrap();
+    }
+    assert_eq!(get_cumulative_changes(&token).await, (500 + 800, 300));
+
+    // neither is burning
+    token
+        .burn(&account.pubkey(), &alice.pubkey(), 400, &[&alice])
+        .await
+        .unwrap();
+    token
+        .rebase_supply(&supply_authority.pubkey(), 900, &[&supply_authority])
+        .await
+        .unwrap();
+    token
+        .rebase_supply(&supply_authority.pubkey(), 1_000, &[&supply_authority])
+        .await
+        .unwrap();
+    assert_eq!(
+        get_cumulative_changes(&token).await,
+        (500 + 800 + 100, 300 + 700)
+    );
+}
@@ -0,0 +1,137 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{clock::Clock, signature::Signer, signer::keypair::Keypair},
+    spl_token_2022::{
+        error::TokenError,
+        extension::rebase_mint::{instruction, RebaseMintConfig},
+        id,
+    },
+    spl_token_client::{
+        client::ProgramBanksClientProcessTransaction,
+        token::{RebaseSupplyAccounts, Token, TokenError as TokenClientError},
+    },
+};
+
+async fn get_unix_timestamp(context: &TestContext) -> i64 {
+    let mut context = context.context.lock().await;
+    context
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .unix_timestamp
+}
+
+async fn set_unix_timestamp(context: &TestContext, unix_timestamp: i64) {
+    let mut context = context.context.lock().await;
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp = unix_timestamp;
+    context.set_sysvar(&clock);
+}
+
+async fn get_config(token: &Token<ProgramBanksClientProcessTransaction>) -> RebaseMintConfig {
+    token.get_rebase_mint_config().await.unwrap()
+}
+
+async fn setup(supply_authority: &Keypair) -> (TestContext, TokenContext) {
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .initial_supply(1_000, 0)
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let token_context = context.token_context.take().unwrap();
+    (context, token_context)
+}
+
+#[tokio::test]
+async fn rebase_until_deadline() {
+    let supply_authority = Keypair::new();
+    let (context, TokenContext { token, .. }) = setup(&supply_authority).await;
+    let deadline = get_unix_timestamp(&context).await + 1_000;
+
+    // just before and exactly at the deadline
+    for (unix_timestamp, new_supply) in [(deadline - 1, 2_000), (deadline, 3_000)] {
+        set_unix_timestamp(&context, unix_timestamp).await;
+        token
+            .rebase_supply_with_deadline(
+                &supply_authority.pubkey(),
+                &RebaseSupplyAccounts::default(),
+                new_supply,
+                0,
+                [0; 32],
+                deadline,
+                &[&supply_authority],
+            )
+            .await
+            .unwrap();
+        assert_eq!(u64::from(get_config(&token).await.total_supply), new_supply);
+    }
+
+    // after the deadline
+    set_unix_timestamp(&context, deadline + 1).await;
+    let err = token
+        .rebase_supply_with_deadline(
+            &supply_authority.pubkey(),
+            &RebaseSupplyAccounts::default(),
+            4_000,
+            0,
+            [0; 32],
+            deadline,
+            &[&supply_authority],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err, TokenClientError::Rebase(TokenError::RebaseExpired));
+    assert_eq!(u64::from(get_config(&token).await.total_supply), 3_000);
+
+    // checked before the authority, so a stale rebase fails the same way
+    // whoever signed it
+    let stranger = Keypair::new();
+    let err = token
+        .rebase_supply_with_deadline(
+            &stranger.pubkey(),
+            &RebaseSupplyAccounts::default(),
+            4_000,
+            0,
+            [0; 32],
+            deadline,
+            &[&stranger],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err, TokenClientError::Rebase(TokenError::RebaseExpired));
+}
+
+#[tokio::test]
+async fn no_deadline() {
+    let supply_authority = Keypair::new();
+    let (context, TokenContext { token, .. }) = setup(&supply_authority).await;
+
+    // however late it lands
+    let now = get_unix_timestamp(&context).await;
+    set_unix_timestamp(&context, now + 1_000_000_000).await;
+    token
+        .rebase_supply_with_deadline(
+            &supply_authority.pubkey(),
+            &RebaseSupplyAccounts::default(),
+            2_000,
+            0,
+            [0; 32],
+            0,
+            &[&supply_authority],
+        )
+        .await
+        .unwrap();
+    assert_eq!(u64::from(get_config(&token).await.total_supply), 2_000);
+}
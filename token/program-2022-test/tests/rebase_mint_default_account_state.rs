@@ -0,0 +1,157 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        instruction::InstructionError, pubkey::Pubkey, signature::Signer, signer::keypair::Keypair,
+        transaction::TransactionError, transport::TransportError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{instruction, RebaseAccount, RebaseMintConfig},
+            BaseStateWithExtensions,
+        },
+        id,
+        state::AccountState,
+    },
+    spl_token_client::{
+        client::ProgramBanksClientProcessTransaction,
+        token::{ExtensionInitializationParams, Token, TokenError as TokenClientError},
+    },
+};
+
+async fn get_value(token: &Token<ProgramBanksClientProcessTransaction>, account: &Pubkey) -> u64 {
+    let state = token.get_account_info(account).await.unwrap();
+    let shares = u64::from(state.get_extension::<RebaseAccount>().unwrap().shares);
+    let mint = token.get_mint_info().await.unwrap();
+    let config = mint.get_extension::<RebaseMintConfig>().unwrap();
+    config.shares_to_amount(shares).unwrap()
+}
+
+#[tokio::test]
+async fn frozen_accounts_rebase() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint_and_freeze_authority(
+            vec![ExtensionInitializationParams::DefaultAccountState {
+                state: AccountState::Frozen,
+            }],
+            Some(Keypair::new()),
+            |mint| {
+                instruction::InitializeRebaseMintBuilder::new(&id())
+                    .mint(mint)
+                    .supply_authority(&supply_authority.pubkey())
+                    .allow_supply_decrease()
+                    .build()
+                    .unwrap()
+            },
+        )
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        bob,
+        freeze_authority,
+        decimals,
+        ..
+    } = context.token_context.take().unwrap();
+    let freeze_authority = freeze_authority.unwrap();
+    let mut accounts = vec![];
+    for owner in [&alice, &bob] {
+        let account = Keypair::new();
+        token
+            .create_auxiliary_token_account(&account, &owner.pubkey())
+            .await
+            .unwrap();
+        accounts.push(account.pubkey());
+    }
+    let (alice_account, bob_account) = (accounts[0], accounts[1]);
+    let state = token.get_account_info(&alice_account).await.unwrap();
+    assert_eq!(state.base.state, AccountState::Frozen);
+    assert_eq!(
+        u64::from(state.get_extension::<RebaseAccount>().unwrap().shares),
+        0
+    );
+
+    token
+        .thaw(
+            &alice_account,
+            &freeze_authority.pubkey(),
+            &[&freeze_authority],
+        )
+        .await
+        .unwrap();
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            1_000,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    token
+        .freeze(
+            &alice_account,
+            &freeze_authority.pubkey(),
+            &[&freeze_authority],
+        )
+        .await
+        .unwrap();
+
+    // the rebase reaches the frozen balance
+    token
+        .rebase_supply(&supply_authority.pubkey(), 3_000, &[&supply_authority])
+        .await
+        .unwrap();
+    assert_eq!(get_value(&token, &alice_account).await, 3_000);
+
+    // but its shares can't move
+    let transfer_shares = || {
+        instruction::transfer_shares_checked(
+            &id(),
+            &alice_account,
+            token.get_address(),
+            &bob_account,
+            &alice.pubkey(),
+            &[],
+            100,
+            decimals,
+        )
+        .unwrap()
+    };
+    let error = token
+        .process_ixs(&[transfer_shares()], &[&alice])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::AccountFrozen as u32)
+            )
+        )))
+    );
+
+    for account in [&alice_account, &bob_account] {
+        token
+            .thaw(account, &freeze_authority.pubkey(), &[&freeze_authority])
+            .await
+            .unwrap();
+    }
+    // the same transaction as the failed attempt would be a duplicate
+    token.get_new_latest_blockhash().await.unwrap();
+    token
+        .process_ixs(&[transfer_shares()], &[&alice])
+        .await
+        .unwrap();
+    assert_eq!(get_value(&token, &alice_account).await, 2_700);
+    assert_eq!(get_value(&token, &bob_account).await, 300);
+}
@@ -0,0 +1,168 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        instruction::InstructionError, program_option::COption, pubkey::Pubkey, signature::Signer,
+        signer::keypair::Keypair, transaction::TransactionError, transport::TransportError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{instruction, RebaseAccount},
+            BaseStateWithExtensions,
+        },
+        id,
+    },
+    spl_token_client::{
+        client::ProgramBanksClientProcessTransaction,
+        token::{Token, TokenError as TokenClientError},
+    },
+};
+
+async fn create_account(
+    token: &Token<ProgramBanksClientProcessTransaction>,
+    owner: &Pubkey,
+) -> Pubkey {
+    let account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&account, owner)
+        .await
+        .unwrap();
+    account.pubkey()
+}
+
+async fn get_shares(token: &Token<ProgramBanksClientProcessTransaction>, account: &Pubkey) -> u64 {
+    let state = token.get_account_info(account).await.unwrap();
+    u64::from(state.get_extension::<RebaseAccount>().unwrap().shares)
+}
+
+fn token_error(error: TokenError) -> TokenClientError {
+    TokenClientError::Client(Box::new(TransportError::TransactionError(
+        TransactionError::InstructionError(0, InstructionError::Custom(error as u32)),
+    )))
+}
+
+#[tokio::test]
+async fn allowance_scales_with_rebase() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .initial_supply(1_000, 0)
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        token_unchecked,
+        mint_authority,
+        alice,
+        bob,
+        ..
+    } = context.token_context.take().unwrap();
+    let alice_account = create_account(&token, &alice.pubkey()).await;
+    let destination = create_account(&token, &Pubkey::new_unique()).await;
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            100,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    // approving 50 tokens at 1 token per share allows 50 shares
+    token
+        .approve(
+            &alice_account,
+            &bob.pubkey(),
+            &alice.pubkey(),
+            50,
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    let state = token.get_account_info(&alice_account).await.unwrap();
+    assert_eq!(state.base.delegate, COption::Some(bob.pubkey()));
+    assert_eq!(state.base.delegated_amount, 50);
+
+    // after doubling the supply, those shares are worth 100 tokens
+    token
+        .rebase_supply(&supply_authority.pubkey(), 2_200, &[&supply_authority])
+        .await
+        .unwrap();
+
+    let err = token
+        .transfer(&alice_account, &destination, &bob.pubkey(), 102, &[&bob])
+        .await
+        .unwrap_err();
+    assert_eq!(err, token_error(TokenError::InsufficientFunds));
+
+    token
+        .transfer(&alice_account, &destination, &bob.pubkey(), 100, &[&bob])
+        .await
+        .unwrap();
+    assert_eq!(get_shares(&token, &alice_account).await, 50);
+    assert_eq!(get_shares(&token, &destination).await, 50);
+    let state = token.get_account_info(&alice_account).await.unwrap();
+    assert_eq!(state.base.delegate, COption::None);
+    assert_eq!(state.base.delegated_amount, 0);
+
+    // an exact share allowance is spent in shares by burning too
+    token
+        .process_ixs(
+            &[instruction::approve_shares(
+                &id(),
+                &alice_account,
+                token.get_address(),
+                &bob.pubkey(),
+                &alice.pubkey(),
+                &[],
+                10,
+            )
+            .unwrap()],
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    let state = token.get_account_info(&alice_account).await.unwrap();
+    assert_eq!(state.base.delegated_amount, 10);
+
+    let err = token
+        .burn(&alice_account, &bob.pubkey(), 22, &[&bob])
+        .await
+        .unwrap_err();
+    assert_eq!(err, token_error(TokenError::InsufficientFunds));
+
+    token
+        .burn(&alice_account, &bob.pubkey(), 20, &[&bob])
+        .await
+        .unwrap();
+    assert_eq!(get_shares(&token, &alice_account).await, 40);
+    let state = token.get_account_info(&alice_account).await.unwrap();
+    assert_eq!(state.base.delegate, COption::None);
+    assert_eq!(state.base.delegated_amount, 0);
+
+    // without the mint, the amount can't be converted to shares
+    let err = token_unchecked
+        .approve(
+            &alice_account,
+            &bob.pubkey(),
+            &alice.pubkey(),
+            50,
+            &[&alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err, token_error(TokenError::MintRequiredForTransfer));
+}
@@ -0,0 +1,162 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        instruction::InstructionError, pubkey::Pubkey, signature::Signer, signer::keypair::Keypair,
+        transaction::TransactionError, transport::TransportError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{instruction, RebaseAccount, RebaseMintConfig},
+            BaseStateWithExtensions,
+        },
+        id,
+    },
+    spl_token_client::{
+        client::ProgramBanksClientProcessTransaction,
+        token::{Token, TokenError as TokenClientError},
+    },
+};
+
+async fn setup(supply_authority: &Pubkey) -> TestContext {
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(supply_authority)
+                // 3 tokens as 2 shares, so that small mints round to nothing
+                .initial_supply(3, 2)
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    context
+}
+
+async fn get_config(token: &Token<ProgramBanksClientProcessTransaction>) -> RebaseMintConfig {
+    let mint = token.get_mint_info().await.unwrap();
+    *mint.get_extension::<RebaseMintConfig>().unwrap()
+}
+
+async fn get_shares(token: &Token<ProgramBanksClientProcessTransaction>, account: &Pubkey) -> u64 {
+    let state = token.get_account_info(account).await.unwrap();
+    u64::from(state.get_extension::<RebaseAccount>().unwrap().shares)
+}
+
+fn token_error(error: TokenError) -> TokenClientError {
+    TokenClientError::Client(Box::new(TransportError::TransactionError(
+        TransactionError::InstructionError(0, InstructionError::Custom(error as u32)),
+    )))
+}
+
+#[tokio::test]
+async fn sweep_conserves_shares() {
+    let supply_authority = Keypair::new();
+    let mut context = setup(&supply_authority.pubkey()).await;
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        bob,
+        ..
+    } = context.token_context.take().unwrap();
+
+    let mut accounts = vec![];
+    for owner in [&alice, &bob] {
+        let account = Keypair::new();
+        token
+            .create_auxiliary_token_account(&account, &owner.pubkey())
+            .await
+            .unwrap();
+        accounts.push(account.pubkey());
+    }
+    let (alice_account, treasury) = (accounts[0], accounts[1]);
+
+    // the initial supply was never minted, so its shares are dust
+    let config = get_config(&token).await;
+    assert_eq!(config.rounding_dust_shares(), 2);
+
+    // each token is worth less than a share, so these mints issue none
+    for _ in 0..3 {
+        // a new blockhash for each, so that they aren't duplicates
+        token.get_new_latest_blockhash().await.unwrap();
+        token
+            .mint_to(
+                &alice_account,
+                &mint_authority.pubkey(),
+                1,
+                &[&mint_authority],
+            )
+            .await
+            .unwrap();
+    }
+    assert_eq!(get_shares(&token, &alice_account).await, 0);
+
+    // dropping the unminted supply rescales the shares, rounding them down
+    let sync_ix = instruction::sync_supply_from_mint(&id(), token.get_address()).unwrap();
+    token
+        .process_ixs(&[sync_ix], &[] as &[&dyn Signer; 0])
+        .await
+        .unwrap();
+    let config = get_config(&token).await;
+    assert_eq!(u64::from(config.total_supply), 3);
+    assert_eq!(u64::from(config.total_shares), 1);
+    assert_eq!(u64::from(config.outstanding_shares), 0);
+    assert_eq!(config.rounding_dust_shares(), 1);
+
+    token
+        .sweep_rounding_dust(&treasury, &supply_authority.pubkey(), &[&supply_authority])
+        .await
+        .unwrap();
+
+    // every share is held, and the supply didn't move
+    let config = get_config(&token).await;
+    assert_eq!(config.rounding_dust_shares(), 0);
+    assert_eq!(u64::from(config.outstanding_shares), 1);
+    assert_eq!(u64::from(config.total_shares), 1);
+    assert_eq!(u64::from(config.total_supply), 3);
+    assert_eq!(token.get_mint_info().await.unwrap().base.supply, 3);
+    assert_eq!(
+        get_shares(&token, &alice_account).await + get_shares(&token, &treasury).await,
+        u64::from(config.total_shares)
+    );
+    assert_eq!(get_shares(&token, &treasury).await, 1);
+    assert_eq!(
+        token.get_rebased_balance(&alice_account).await.unwrap()
+            + token.get_rebased_balance(&treasury).await.unwrap(),
+        u64::from(config.total_supply)
+    );
+
+    // nothing is left to sweep
+    token
+        .sweep_rounding_dust(&treasury, &supply_authority.pubkey(), &[&supply_authority])
+        .await
+        .unwrap();
+    assert_eq!(get_shares(&token, &treasury).await, 1);
+}
+
+#[tokio::test]
+async fn fail_sweep_by_other_signer() {
+    let supply_authority = Keypair::new();
+    let mut context = setup(&supply_authority.pubkey()).await;
+    let TokenContext { token, alice, .. } = context.token_context.take().unwrap();
+
+    let account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&account, &alice.pubkey())
+        .await
+        .unwrap();
+    let error = token
+        .sweep_rounding_dust(&account.pubkey(), &alice.pubkey(), &[&alice])
+        .await
+        .unwrap_err();
+    assert_eq!(error, token_error(TokenError::OwnerMismatch));
+    assert_eq!(get_config(&token).await.rounding_dust_shares(), 2);
+}
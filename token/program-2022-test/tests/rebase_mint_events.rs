@@ -0,0 +1,346 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    base64::{prelude::BASE64_STANDARD, Engine},
+    program_test::TestContext,
+    solana_program_test::{
+        tokio::{self, sync::Mutex},
+        ProgramTestContext,
+    },
+    solana_sdk::{
+        clock::Clock, instruction::Instruction, pubkey::Pubkey, signature::Signer,
+        signer::keypair::Keypair, system_instruction, transaction::Transaction,
+    },
+    spl_pod::optional_keys::OptionalNonZeroPubkey,
+    spl_token_2022::{
+        extension::{
+            rebase_mint::{
+                event::{
+                    parse_event, RebaseDelegateChanged, RebaseEvent, RebaseFeeChanged,
+                    RebaseMintInitialized, ReserveAccountChanged, SupplyAuthorityChangeCancelled,
+                    SupplyAuthorityChangeScheduled, SupplyAuthorityChanged,
+                    SupplyAuthorityProposalCancelled, SupplyAuthorityProposed,
+                },
+                instruction,
+            },
+            ExtensionType,
+        },
+        id,
+        state::Mint,
+    },
+    std::sync::Arc,
+};
+
+const AUTHORITY_CHANGE_DELAY_SECONDS: u32 = 100;
+
+/// Process the instructions in a transaction, returning the rebasing mint
+/// events it logged
+async fn process(
+    context: &Arc<Mutex<ProgramTestContext>>,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Vec<(Pubkey, i64, RebaseEvent)> {
+    let mut context = context.lock().await;
+    let recent_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        recent_blockhash,
+    );
+    let outcome = context
+        .banks_client
+        .process_transaction_with_metadata(transaction)
+        .await
+        .unwrap();
+    outcome.result.unwrap();
+    outcome
+        .metadata
+        .unwrap()
+        .log_messages
+        .iter()
+        .filter_map(|log| log.strip_prefix("Program data: "))
+        .filter_map(|data| {
+            let fields = data
+                .split(' ')
+                .map(|field| BASE64_STANDARD.decode(field).unwrap())
+                .collect::<Vec<_>>();
+            let fields = fields.iter().map(Vec::as_slice).collect::<Vec<_>>();
+            parse_event(&fields).ok()
+        })
+        .map(|(header, event)| (header.mint, i64::from(header.unix_timestamp), event))
+        .collect()
+}
+
+fn some(pubkey: &Pubkey) -> OptionalNonZeroPubkey {
+    OptionalNonZeroPubkey::try_from(Some(*pubkey)).unwrap()
+}
+
+#[tokio::test]
+async fn events_follow_actions() {
+    let context = TestContext::new().await.context;
+    let mint = Keypair::new();
+    let supply_authority = Keypair::new();
+    let new_authority = Keypair::new();
+    let reserve_account = Pubkey::new_unique();
+    let fee_destination = Pubkey::new_unique();
+    let rebase_delegate = Pubkey::new_unique();
+    let (payer, lamports, space) = {
+        let mut context = context.lock().await;
+        let space =
+            ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::RebaseMintConfig])
+                .unwrap();
+        let rent = context.banks_client.get_rent().await.unwrap();
+        (context.payer.pubkey(), rent.minimum_balance(space), space)
+    };
+
+    let mut events = vec![];
+    events.extend(
+        process(
+            &context,
+            &[
+                system_instruction::create_account(
+                    &payer,
+                    &mint.pubkey(),
+                    lamports,
+                    space as u64,
+                    &id(),
+                ),
+                instruction::InitializeRebaseMintBuilder::new(&id())
+                    .mint(&mint.pubkey())
+                    .supply_authority(&supply_authority.pubkey())
+                    .initial_supply(1_000, 0)
+                    .authority_change_delay_seconds(AUTHORITY_CHANGE_DELAY_SECONDS)
+                    .build()
+                    .unwrap(),
+                spl_token_2022::instruction::initialize_mint(
+                    &id(),
+                    &mint.pubkey(),
+                    &payer,
+                    None,
+                    6,
+                )
+                .unwrap(),
+            ],
+            &[&mint],
+        )
+        .await,
+    );
+    events.extend(
+        process(
+            &context,
+            &[instruction::set_reserve_account(
+                &id(),
+                &mint.pubkey(),
+                &supply_authority.pubkey(),
+                &[],
+                Some(reserve_account),
+            )
+            .unwrap()],
+            &[&supply_authority],
+        )
+        .await,
+    );
+    events.extend(
+        process(
+            &context,
+            &[instruction::set_rebase_delegate(
+                &id(),
+                &mint.pubkey(),
+                &supply_authority.pubkey(),
+                &[],
+                Some(rebase_delegate),
+            )
+            .unwrap()],
+            &[&supply_authority],
+        )
+        .await,
+    );
+    events.extend(
+        process(
+            &context,
+            &[instruction::set_rebase_fee(
+                &id(),
+                &mint.pubkey(),
+                &supply_authority.pubkey(),
+                &[],
+                250,
+                Some(fee_destination),
+            )
+            .unwrap()],
+            &[&supply_authority],
+        )
+        .await,
+    );
+    let propose = instruction::propose_supply_authority(
+        &id(),
+        &mint.pubkey(),
+        &supply_authority.pubkey(),
+        &[],
+        &new_authority.pubkey(),
+    )
+    .unwrap();
+    events.extend(process(&context, &[propose.clone()], &[&supply_authority]).await);
+    events.extend(
+        process(
+            &context,
+            &[instruction::cancel_pending_authority(
+                &id(),
+                &mint.pubkey(),
+                &supply_authority.pubkey(),
+                &[],
+            )
+            .unwrap()],
+            &[&supply_authority],
+        )
+        .await,
+    );
+
+    // with a delay, accepting only schedules the change
+    events.extend(process(&context, &[propose], &[&supply_authority]).await);
+    events.extend(
+        process(
+            &context,
+            &[instruction::accept_supply_authority(
+                &id(),
+                &mint.pubkey(),
+                &new_authority.pubkey(),
+                &[],
+            )
+            .unwrap()],
+            &[&new_authority],
+        )
+        .await,
+    );
+    events.extend(
+        process(
+            &context,
+            &[instruction::cancel_authority_change(
+                &id(),
+                &mint.pubkey(),
+                &supply_authority.pubkey(),
+                &[],
+            )
+            .unwrap()],
+            &[&supply_authority],
+        )
+        .await,
+    );
+    events.extend(
+        process(
+            &context,
+            &[instruction::set_supply_authority(
+                &id(),
+                &mint.pubkey(),
+                &supply_authority.pubkey(),
+                &[],
+                Some(new_authority.pubkey()),
+            )
+            .unwrap()],
+            &[&supply_authority],
+        )
+        .await,
+    );
+    {
+        let mut context = context.lock().await;
+        let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        clock.unix_timestamp += AUTHORITY_CHANGE_DELAY_SECONDS as i64;
+        context.set_sysvar(&clock);
+    }
+    events.extend(
+        process(
+            &context,
+            &[instruction::finalize_authority_change(&id(), &mint.pubkey()).unwrap()],
+            &[],
+        )
+        .await,
+    );
+
+    // revoking is never delayed
+    events.extend(
+        process(
+            &context,
+            &[instruction::set_supply_authority(
+                &id(),
+                &mint.pubkey(),
+                &new_authority.pubkey(),
+                &[],
+                None,
+            )
+            .unwrap()],
+            &[&new_authority],
+        )
+        .await,
+    );
+
+    assert!(events
+        .iter()
+        .all(|(event_mint, _, _)| *event_mint == mint.pubkey()));
+    let scheduled_timestamps = events
+        .iter()
+        .filter(|(_, _, event)| matches!(event, RebaseEvent::SupplyAuthorityChangeScheduled(_)))
+        .map(|(_, unix_timestamp, _)| *unix_timestamp + AUTHORITY_CHANGE_DELAY_SECONDS as i64)
+        .collect::<Vec<_>>();
+    assert_eq!(scheduled_timestamps.len(), 2);
+    let events = events
+        .into_iter()
+        .map(|(_, _, event)| event)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        events,
+        vec![
+            RebaseEvent::Initialized(RebaseMintInitialized {
+                supply_authority: some(&supply_authority.pubkey()),
+                initial_supply: 1_000.into(),
+                first_rebase_after_timestamp: 0.into(),
+                authority_change_delay_seconds: AUTHORITY_CHANGE_DELAY_SECONDS.into(),
+                virtual_shares_offset: 0.into(),
+            }),
+            RebaseEvent::ReserveAccountChanged(ReserveAccountChanged {
+                previous_reserve_account: OptionalNonZeroPubkey::default(),
+                new_reserve_account: some(&reserve_account),
+            }),
+            RebaseEvent::RebaseDelegateChanged(RebaseDelegateChanged {
+                previous_delegate: OptionalNonZeroPubkey::default(),
+                new_delegate: some(&rebase_delegate),
+            }),
+            RebaseEvent::RebaseFeeChanged(RebaseFeeChanged {
+                previous_rebase_fee_bps: 0.into(),
+                previous_fee_destination: OptionalNonZeroPubkey::default(),
+                new_rebase_fee_bps: 250.into(),
+                new_fee_destination: some(&fee_destination),
+            }),
+            RebaseEvent::SupplyAuthorityProposed(SupplyAuthorityProposed {
+                pending_authority: some(&new_authority.pubkey()),
+            }),
+            RebaseEvent::SupplyAuthorityProposalCancelled(SupplyAuthorityProposalCancelled {
+                pending_authority: some(&new_authority.pubkey()),
+            }),
+            RebaseEvent::SupplyAuthorityProposed(SupplyAuthorityProposed {
+                pending_authority: some(&new_authority.pubkey()),
+            }),
+            RebaseEvent::SupplyAuthorityChangeScheduled(SupplyAuthorityChangeScheduled {
+                scheduled_authority: some(&new_authority.pubkey()),
+                ready_timestamp: scheduled_timestamps[0].into(),
+            }),
+            RebaseEvent::SupplyAuthorityChangeCancelled(SupplyAuthorityChangeCancelled {
+                scheduled_authority: some(&new_authority.pubkey()),
+            }),
+            RebaseEvent::SupplyAuthorityChangeScheduled(SupplyAuthorityChangeScheduled {
+                scheduled_authority: some(&new_authority.pubkey()),
+                ready_timestamp: scheduled_timestamps[1].into(),
+            }),
+            RebaseEvent::SupplyAuthorityChanged(SupplyAuthorityChanged {
+                previous_authority: some(&supply_authority.pubkey()),
+                new_authority: some(&new_authority.pubkey()),
+            }),
+            RebaseEvent::SupplyAuthorityChanged(SupplyAuthorityChanged {
+                previous_authority: some(&new_authority.pubkey()),
+                new_authority: OptionalNonZeroPubkey::default(),
+            }),
+        ]
+    );
+}
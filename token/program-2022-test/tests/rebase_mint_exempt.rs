@@ -0,0 +1,195 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        instruction::InstructionError, pubkey::Pubkey, signature::Signer, signer::keypair::Keypair,
+        transaction::TransactionError, transport::TransportError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{instruction, RebaseAccount, RebaseMintConfig},
+            BaseStateWithExtensions,
+        },
+        id, instruction as token_instruction,
+    },
+    spl_token_client::{
+        client::ProgramBanksClientProcessTransaction,
+        token::{Token, TokenError as TokenClientError},
+    },
+};
+
+const DECIMALS: u8 = 9;
+
+async fn create_account(
+    token: &Token<ProgramBanksClientProcessTransaction>,
+    owner: &Pubkey,
+) -> Pubkey {
+    let account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&account, owner)
+        .await
+        .unwrap();
+    account.pubkey()
+}
+
+async fn get_balance(
+    token: &Token<ProgramBanksClientProcessTransaction>,
+    account: &Pubkey,
+) -> (u64, u64, bool) {
+    let state = token.get_account_info(account).await.unwrap();
+    let extension = state.get_extension::<RebaseAccount>().unwrap();
+    (
+        u64::from(extension.shares),
+        state.base.amount,
+        extension.is_exempt(),
+    )
+}
+
+#[tokio::test]
+async fn exempt_account_keeps_amount() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        bob,
+        ..
+    } = context.token_context.take().unwrap();
+    let alice_account = create_account(&token, &alice.pubkey()).await;
+    let pool_account = create_account(&token, &bob.pubkey()).await;
+    for account in [&alice_account, &pool_account] {
+        token
+            .mint_to(account, &mint_authority.pubkey(), 100, &[&mint_authority])
+            .await
+            .unwrap();
+    }
+
+    // only the supply authority can exempt an account
+    let err = token
+        .process_ixs(
+            &[instruction::set_account_exempt(
+                &id(),
+                &pool_account,
+                token.get_address(),
+                &bob.pubkey(),
+                &[],
+                true,
+            )
+            .unwrap()],
+            &[&bob],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::OwnerMismatch as u32)
+            )
+        )))
+    );
+
+    token
+        .process_ixs(
+            &[instruction::set_account_exempt(
+                &id(),
+                &pool_account,
+                token.get_address(),
+                &supply_authority.pubkey(),
+                &[],
+                true,
+            )
+            .unwrap()],
+            &[&supply_authority],
+        )
+        .await
+        .unwrap();
+    assert_eq!(get_balance(&token, &pool_account).await, (0, 100, true));
+    let mint = token.get_mint_info().await.unwrap();
+    let config = mint.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(u64::from(config.total_supply), 100);
+    assert_eq!(u64::from(config.total_shares), 100);
+    assert_eq!(u64::from(config.exempt_supply), 100);
+
+    // doubling the supply only moves the rebasing account
+    token
+        .rebase_supply(&supply_authority.pubkey(), 200, &[&supply_authority])
+        .await
+        .unwrap();
+    let mint = token.get_mint_info().await.unwrap();
+    let config = mint.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(config.shares_to_amount(100), Some(200));
+    assert_eq!(get_balance(&token, &pool_account).await, (0, 100, true));
+
+    // transfers into the exempt account need a writable mint
+    let mut transfer = token_instruction::transfer_checked(
+        &id(),
+        &alice_account,
+        token.get_address(),
+        &pool_account,
+        &alice.pubkey(),
+        &[],
+        20,
+        DECIMALS,
+    )
+    .unwrap();
+    let err = token
+        .process_ixs(&[transfer.clone()], &[&alice])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(0, InstructionError::InvalidArgument)
+        )))
+    );
+    transfer.accounts[1].is_writable = true;
+    token.process_ixs(&[transfer], &[&alice]).await.unwrap();
+    assert_eq!(get_balance(&token, &alice_account).await.0, 90);
+    assert_eq!(get_balance(&token, &pool_account).await, (0, 120, true));
+    let mint = token.get_mint_info().await.unwrap();
+    let config = mint.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(u64::from(config.total_supply), 180);
+    assert_eq!(u64::from(config.total_shares), 90);
+    assert_eq!(u64::from(config.exempt_supply), 120);
+
+    // including the account again converts its amount at the current ratio
+    token
+        .process_ixs(
+            &[instruction::set_account_exempt(
+                &id(),
+                &pool_account,
+                token.get_address(),
+                &supply_authority.pubkey(),
+                &[],
+                false,
+            )
+            .unwrap()],
+            &[&supply_authority],
+        )
+        .await
+        .unwrap();
+    assert_eq!(get_balance(&token, &pool_account).await, (60, 120, false));
+    let mint = token.get_mint_info().await.unwrap();
+    let config = mint.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(u64::from(config.total_supply), 300);
+    assert_eq!(u64::from(config.total_shares), 150);
+    assert_eq!(u64::from(config.exempt_supply), 0);
+}
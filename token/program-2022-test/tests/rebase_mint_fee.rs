@@ -0,0 +1,361 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    base64::{prelude::BASE64_STANDARD, Engine},
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        instruction::InstructionError, pubkey::Pubkey, signature::Signer, signer::keypair::Keypair,
+        transaction::TransactionError, transport::TransportError,
+    },
+    spl_pod::bytemuck::pod_from_bytes,
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{instruction, RebaseAccount, RebasePreview, SupplyRebased},
+            BaseStateWithExtensions,
+        },
+        id,
+    },
+    spl_token_client::{
+        client::ProgramBanksClientProcessTransaction,
+        token::{RebaseSupplyAccounts, Token, TokenError as TokenClientError},
+    },
+};
+
+struct Setup {
+    token: Token<ProgramBanksClientProcessTransaction>,
+    supply_authority: Keypair,
+    alice_account: Pubkey,
+    treasury: Pubkey,
+}
+
+/// A mint with 1_000 tokens held by alice, and an empty treasury owned by bob
+async fn setup(rebase_fee_bps: u16, fee_set_at_initialize: bool) -> Setup {
+    let supply_authority = Keypair::new();
+    let treasury = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            let builder = instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .allow_supply_decrease();
+            if fee_set_at_initialize {
+                builder.rebase_fee(rebase_fee_bps, &treasury.pubkey())
+            } else {
+                builder
+            }
+            .build()
+            .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        bob,
+        ..
+    } = context.token_context.take().unwrap();
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    token
+        .create_auxiliary_token_account(&treasury, &bob.pubkey())
+        .await
+        .unwrap();
+    token
+        .mint_to(
+            &alice_account.pubkey(),
+            &mint_authority.pubkey(),
+            1_000,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    Setup {
+        token,
+        supply_authority,
+        alice_account: alice_account.pubkey(),
+        treasury: treasury.pubkey(),
+    }
+}
+
+async fn get_balance(
+    token: &Token<ProgramBanksClientProcessTransaction>,
+    account: &Pubkey,
+) -> (u64, u64) {
+    let state = token.get_account_info(account).await.unwrap();
+    let extension = state.get_extension::<RebaseAccount>().unwrap();
+    (
+        u64::from(extension.shares),
+        token.get_rebased_balance(account).await.unwrap(),
+    )
+}
+
+/// Checks the balances of alice and the treasury, and that together they
+/// hold the whole supply
+async fn check_balances(setup: &Setup, alice: (u64, u64), treasury: (u64, u64)) {
+    assert_eq!(get_balance(&setup.token, &setup.alice_account).await, alice);
+    assert_eq!(get_balance(&setup.token, &setup.treasury).await, treasury);
+    let config = setup.token.get_rebase_mint_config().await.unwrap();
+    assert_eq!(u64::from(config.total_supply), alice.1 + treasury.1);
+    assert_eq!(u64::from(config.total_shares), alice.0 + treasury.0);
+}
+
+fn instruction_error(error: InstructionError) -> TokenClientError {
+    TokenClientError::Client(Box::new(TransportError::TransactionError(
+        TransactionError::InstructionError(0, error),
+    )))
+}
+
+#[tokio::test]
+async fn fee_credited_to_treasury() {
+    let setup = setup(2_500, true).await;
+    let Setup {
+        token,
+        supply_authority,
+        ..
+    } = &setup;
+    let config = token.get_rebase_mint_config().await.unwrap();
+    assert_eq!(u16::from(config.rebase_fee_bps), 2_500);
+    assert_eq!(
+        Option::<Pubkey>::from(config.fee_destination),
+        Some(setup.treasury)
+    );
+
+    // a quarter of the 2_000 increase goes to the treasury
+    token
+        .rebase_supply(&supply_authority.pubkey(), 3_000, &[supply_authority])
+        .await
+        .unwrap();
+    check_balances(&setup, (1_000, 2_500), (200, 500)).await;
+
+    // nothing is charged on the way down
+    token
+        .rebase_supply(&supply_authority.pubkey(), 1_500, &[supply_authority])
+        .await
+        .unwrap();
+    check_balances(&setup, (1_000, 1_250), (200, 250)).await;
+
+    // the treasury's shares grow with the rest, on top of the new fee
+    token
+        .safe_rebase_supply(&supply_authority.pubkey(), 2_700, &[supply_authority])
+        .await
+        .unwrap();
+    check_balances(&setup, (1_000, 2_000), (350, 700)).await;
+}
+
+#[tokio::test]
+async fn preview_counts_fee_shares() {
+    let setup = setup(2_500, true).await;
+    let Setup {
+        token,
+        supply_authority,
+        ..
+    } = &setup;
+
+    let simulation = token
+        .simulate_ixs(
+            &[
+                instruction::preview_rebase_supply(&id(), token.get_address(), None, 3_000)
+                    .unwrap(),
+            ],
+            &[] as &[&dyn Signer; 0],
+        )
+        .await
+        .unwrap();
+    assert!(simulation.result.unwrap().is_ok());
+    let return_data = simulation.simulation_details.unwrap().return_data.unwrap();
+    let preview = *pod_from_bytes::<RebasePreview>(&return_data.data).unwrap();
+    assert_eq!(u64::from(preview.total_shares), 1_200);
+    let (client_preview, _) = token.preview_rebase_supply(3_000).await.unwrap();
+    assert_eq!(client_preview, preview);
+
+    token
+        .rebase_supply(&supply_authority.pubkey(), 3_000, &[supply_authority])
+        .await
+        .unwrap();
+    let config = token.get_rebase_mint_config().await.unwrap();
+    assert_eq!(config.total_supply, preview.new_supply);
+    assert_eq!(config.total_shares, preview.total_shares);
+}
+
+#[tokio::test]
+async fn rebase_event_counts_fee_shares() {
+    let setup = setup(2_500, true).await;
+    let Setup {
+        token,
+        supply_authority,
+        ..
+    } = &setup;
+
+    let rebase_ix = token
+        .get_rebase_supply_accounts()
+        .await
+        .unwrap()
+        .apply(instruction::RebaseSupplyBuilder::new(&id(), 3_000))
+        .mint(token.get_address())
+        .authority(&supply_authority.pubkey())
+        .build()
+        .unwrap();
+    let simulation = token
+        .simulate_ixs(&[rebase_ix.clone()], &[supply_authority])
+        .await
+        .unwrap();
+    assert!(simulation.result.unwrap().is_ok());
+    let events = simulation
+        .simulation_details
+        .unwrap()
+        .logs
+        .iter()
+        .filter_map(|log| log.strip_prefix("Program data: "))
+        .filter_map(|data| BASE64_STANDARD.decode(data).ok())
+        .filter_map(|data| pod_from_bytes::<SupplyRebased>(&data).ok().copied())
+        .collect::<Vec<_>>();
+    // a quarter of the 2_000 increase is issued to the treasury as 200 shares
+    assert_eq!(
+        events,
+        vec![SupplyRebased {
+            previous_supply: 1_000.into(),
+            new_supply: 3_000.into(),
+            total_shares: 1_200.into(),
+            exempt_supply: 0.into(),
+            fee_shares: 200.into(),
+        }]
+    );
+
+    token
+        .process_ixs(&[rebase_ix], &[supply_authority])
+        .await
+        .unwrap();
+    let config = token.get_rebase_mint_config().await.unwrap();
+    assert_eq!(config.total_shares, events[0].total_shares);
+}
+
+#[tokio::test]
+async fn fee_changed_by_supply_authority() {
+    let setup = setup(0, false).await;
+    let Setup {
+        token,
+        supply_authority,
+        treasury,
+        ..
+    } = &setup;
+
+    // without a fee, holders get the whole increase
+    token
+        .rebase_supply(&supply_authority.pubkey(), 2_000, &[supply_authority])
+        .await
+        .unwrap();
+    check_balances(&setup, (1_000, 2_000), (0, 0)).await;
+
+    let wrong_signer = Keypair::new();
+    let err = token
+        .set_rebase_fee(
+            &wrong_signer.pubkey(),
+            1_000,
+            Some(*treasury),
+            &[&wrong_signer],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        instruction_error(InstructionError::Custom(TokenError::OwnerMismatch as u32))
+    );
+    let err = token
+        .set_rebase_fee(
+            &supply_authority.pubkey(),
+            10_001,
+            Some(*treasury),
+            &[supply_authority],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err, TokenClientError::Rebase(TokenError::RebaseFeeTooHigh));
+
+    token
+        .set_rebase_fee(
+            &supply_authority.pubkey(),
+            5_000,
+            Some(*treasury),
+            &[supply_authority],
+        )
+        .await
+        .unwrap();
+
+    // once configured, the fee destination must be passed in
+    let err = token
+        .rebase_supply_with_accounts(
+            &supply_authority.pubkey(),
+            &RebaseSupplyAccounts::default(),
+            6_000,
+            &[supply_authority],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Rebase(TokenError::RebaseFeeDestinationMismatch)
+    );
+
+    // half of the 4_000 increase goes to the treasury
+    token
+        .rebase_supply(&supply_authority.pubkey(), 6_000, &[supply_authority])
+        .await
+        .unwrap();
+    check_balances(&setup, (1_000, 4_000), (500, 2_000)).await;
+
+    // a fee without destination charges nothing
+    token
+        .set_rebase_fee(&supply_authority.pubkey(), 5_000, None, &[supply_authority])
+        .await
+        .unwrap();
+    token
+        .rebase_supply_with_accounts(
+            &supply_authority.pubkey(),
+            &RebaseSupplyAccounts::default(),
+            7_500,
+            &[supply_authority],
+        )
+        .await
+        .unwrap();
+    check_balances(&setup, (1_000, 5_000), (500, 2_500)).await;
+}
+
+#[tokio::test]
+async fn fee_destination_must_hold_shares() {
+    let setup = setup(1_000, true).await;
+    let Setup {
+        token,
+        supply_authority,
+        treasury,
+        ..
+    } = &setup;
+    token
+        .process_ixs(
+            &[instruction::set_account_exempt(
+                &id(),
+                treasury,
+                token.get_address(),
+                &supply_authority.pubkey(),
+                &[],
+                true,
+            )
+            .unwrap()],
+            &[supply_authority],
+        )
+        .await
+        .unwrap();
+
+    let err = token
+        .rebase_supply(&supply_authority.pubkey(), 2_000, &[supply_authority])
+        .await
+        .unwrap_err();
+    assert_eq!(err, instruction_error(InstructionError::InvalidAccountData));
+}
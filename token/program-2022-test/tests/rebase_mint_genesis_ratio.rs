@@ -0,0 +1,142 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        instruction::InstructionError, pubkey::Pubkey, signature::Signer, signer::keypair::Keypair,
+        transaction::TransactionError, transport::TransportError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{instruction, RebaseAccount},
+            BaseStateWithExtensions,
+        },
+        id,
+    },
+    spl_token_client::{
+        client::ProgramBanksClientProcessTransaction,
+        token::{Token, TokenError as TokenClientError},
+    },
+};
+
+async fn init_mint(
+    context: &mut TestContext,
+    supply_authority: &Pubkey,
+    initial_supply: u64,
+    initial_shares: u64,
+) -> Result<(), TokenClientError> {
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(supply_authority)
+                .initial_supply(initial_supply, initial_shares)
+                .allow_supply_decrease()
+                .build_unchecked()
+                .unwrap()
+        })
+        .await
+}
+
+async fn get_balance(
+    token: &Token<ProgramBanksClientProcessTransaction>,
+    account: &Pubkey,
+) -> (u64, u64) {
+    let state = token.get_account_info(account).await.unwrap();
+    let extension = state.get_extension::<RebaseAccount>().unwrap();
+    (
+        u64::from(extension.shares),
+        token.get_rebased_balance(account).await.unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn genesis_ratio_carries_over() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    // three shares per token from the start
+    init_mint(&mut context, &supply_authority.pubkey(), 1_000, 3_000)
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        ..
+    } = context.token_context.take().unwrap();
+
+    let config = token.get_rebase_mint_config().await.unwrap();
+    assert_eq!(u64::from(config.total_supply), 1_000);
+    assert_eq!(u64::from(config.total_shares), 3_000);
+    assert_eq!(config.shares_to_amount(300), Some(100));
+    assert_eq!(config.amount_to_shares(100), Some(300));
+
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    token
+        .mint_to(
+            &alice_account.pubkey(),
+            &mint_authority.pubkey(),
+            100,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        get_balance(&token, &alice_account.pubkey()).await,
+        (300, 100)
+    );
+
+    // doubling the supply doubles every balance, at the same share count
+    token
+        .rebase_supply(&supply_authority.pubkey(), 2_200, &[&supply_authority])
+        .await
+        .unwrap();
+    assert_eq!(
+        get_balance(&token, &alice_account.pubkey()).await,
+        (300, 200)
+    );
+    let config = token.get_rebase_mint_config().await.unwrap();
+    assert_eq!(u64::from(config.total_supply), 2_200);
+    assert_eq!(u64::from(config.total_shares), 3_300);
+    assert_eq!(config.shares_to_amount(3), Some(2));
+    assert_eq!(config.amount_to_shares(2), Some(3));
+}
+
+#[tokio::test]
+async fn zero_initial_shares_means_one_per_token() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    init_mint(&mut context, &supply_authority.pubkey(), 1_000, 0)
+        .await
+        .unwrap();
+    let TokenContext { token, .. } = context.token_context.take().unwrap();
+
+    let config = token.get_rebase_mint_config().await.unwrap();
+    assert_eq!(u64::from(config.total_supply), 1_000);
+    assert_eq!(u64::from(config.total_shares), 1_000);
+}
+
+#[tokio::test]
+async fn fail_shares_without_supply() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    let err = init_mint(&mut context, &supply_authority.pubkey(), 0, 3_000)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                1,
+                InstructionError::Custom(TokenError::RebaseSupplyZero as u32)
+            )
+        )))
+    );
+}
@@ -0,0 +1,95 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{keypair_clone, TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{pubkey::Pubkey, signature::Signer, signer::keypair::Keypair},
+    spl_token_2022::{extension::rebase_mint::instruction, id},
+    spl_token_client::{
+        client::{ProgramBanksClient, ProgramBanksClientProcessTransaction, ProgramClient},
+        token::{Token, TokenError as TokenClientError},
+    },
+    std::sync::Arc,
+};
+
+#[tokio::test]
+async fn get_config_of_rebasing_mint() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .initial_supply(1_000, 0)
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext { token, .. } = context.token_context.take().unwrap();
+
+    token
+        .rebase_supply(&supply_authority.pubkey(), 1_500, &[&supply_authority])
+        .await
+        .unwrap();
+    let config = token.get_rebase_mint_config().await.unwrap();
+    assert_eq!(
+        Option::<Pubkey>::from(config.supply_authority),
+        Some(supply_authority.pubkey())
+    );
+    assert_eq!(u64::from(config.total_supply), 1_500);
+    assert_eq!(u64::from(config.total_shares), 1_000);
+    assert!(bool::from(config.has_rebased));
+}
+
+#[tokio::test]
+async fn fail_get_config_without_extension() {
+    let mut context = TestContext::new().await;
+    context.init_token_with_mint(vec![]).await.unwrap();
+    let TokenContext { token, .. } = context.token_context.take().unwrap();
+
+    assert_eq!(
+        token.get_rebase_mint_config().await.unwrap_err(),
+        TokenClientError::MissingRebaseMintConfig
+    );
+}
+
+#[tokio::test]
+async fn fail_get_config_of_token_account() {
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext { token, alice, .. } = context.token_context.take().unwrap();
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+
+    // point a client at the token account as if it were the mint
+    let payer = keypair_clone(&context.context.lock().await.payer);
+    let client: Arc<dyn ProgramClient<ProgramBanksClientProcessTransaction>> =
+        Arc::new(ProgramBanksClient::new_from_context(
+            Arc::clone(&context.context),
+            ProgramBanksClientProcessTransaction,
+        ));
+    let not_a_mint = Token::new(
+        client,
+        &id(),
+        &alice_account.pubkey(),
+        None,
+        Arc::new(payer),
+    );
+    let err = not_a_mint.get_rebase_mint_config().await.unwrap_err();
+    assert!(matches!(err, TokenClientError::Program(_)), "{:?}", err);
+}
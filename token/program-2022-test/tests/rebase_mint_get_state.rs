@@ -0,0 +1,98 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{pubkey::Pubkey, signature::Signer, signer::keypair::Keypair},
+    spl_pod::bytemuck::pod_from_bytes,
+    spl_token_2022::{
+        extension::{
+            rebase_mint::{instruction, RebaseMintConfig, RebaseState, REBASE_STATE_VERSION},
+            BaseStateWithExtensions,
+        },
+        id,
+    },
+    spl_token_client::{client::ProgramBanksClientProcessTransaction, token::Token},
+};
+
+async fn get_state(token: &Token<ProgramBanksClientProcessTransaction>) -> RebaseState {
+    // no signature needed
+    let simulation = token
+        .simulate_ixs(
+            &[instruction::get_rebase_state(&id(), token.get_address()).unwrap()],
+            &[] as &[&dyn Signer; 0],
+        )
+        .await
+        .unwrap();
+    assert!(simulation.result.unwrap().is_ok());
+    let return_data = simulation.simulation_details.unwrap().return_data.unwrap();
+    *pod_from_bytes::<RebaseState>(&return_data.data).unwrap()
+}
+
+#[tokio::test]
+async fn state_matches_config() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        ..
+    } = context.token_context.take().unwrap();
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    token
+        .mint_to(
+            &alice_account.pubkey(),
+            &mint_authority.pubkey(),
+            100,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    token
+        .rebase_supply(&supply_authority.pubkey(), 300, &[&supply_authority])
+        .await
+        .unwrap();
+
+    let state = get_state(&token).await;
+    let mint = token.get_mint_info().await.unwrap();
+    let config = mint.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(state.version, REBASE_STATE_VERSION);
+    assert_eq!(state.decimals, mint.base.decimals);
+    assert_eq!(u64::from(state.mint_supply), 100);
+    assert_eq!(state.total_supply, config.total_supply);
+    assert_eq!(state.total_shares, config.total_shares);
+    assert_eq!(state.outstanding_shares, config.outstanding_shares);
+    assert_eq!(state.ratio, config.cached_ratio);
+    assert_eq!(
+        Option::<Pubkey>::from(state.supply_authority),
+        Some(supply_authority.pubkey())
+    );
+    assert!(bool::from(state.rebase_allowed));
+    assert!(bool::from(state.has_rebased));
+
+    // once the authority is revoked, nobody may rebase
+    token
+        .set_rebase_supply_authority(&supply_authority.pubkey(), None, &[&supply_authority])
+        .await
+        .unwrap();
+    let state = get_state(&token).await;
+    assert_eq!(Option::<Pubkey>::from(state.supply_authority), None);
+    assert!(!bool::from(state.rebase_allowed));
+    assert_eq!(u64::from(state.total_supply), 300);
+}
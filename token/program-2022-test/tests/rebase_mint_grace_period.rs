@@ -0,0 +1,155 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        clock::Clock, instruction::InstructionError, signature::Signer, signer::keypair::Keypair,
+        transaction::TransactionError, transport::TransportError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{instruction, RebaseMintConfig},
+            BaseStateWithExtensions,
+        },
+        id,
+    },
+    spl_token_client::token::TokenError as TokenClientError,
+};
+
+/// Set the clock, with a new blockhash so that retrying a transaction that
+/// failed before isn't rejected as a duplicate
+async fn set_unix_timestamp(context: &TestContext, unix_timestamp: i64) {
+    let mut context = context.context.lock().await;
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp = unix_timestamp;
+    context.set_sysvar(&clock);
+    context.get_new_latest_blockhash().await.unwrap();
+}
+
+#[tokio::test]
+async fn rebase_after_grace_period() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    let now = context
+        .context
+        .lock()
+        .await
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .unix_timestamp;
+    let first_rebase_after_timestamp = now + 1_000;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .initial_supply(1_000, 0)
+                .first_rebase_after_timestamp(first_rebase_after_timestamp)
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext { token, .. } = context.token_context.take().unwrap();
+
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(
+        i64::from(extension.first_rebase_after_timestamp),
+        first_rebase_after_timestamp
+    );
+
+    // before the boundary
+    set_unix_timestamp(&context, first_rebase_after_timestamp - 1).await;
+    let err = token
+        .process_ixs(
+            &[instruction::update_supply(
+                &id(),
+                token.get_address(),
+                None,
+                &supply_authority.pubkey(),
+                &[],
+                2_000,
+            )
+            .unwrap()],
+            &[&supply_authority],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::RebaseNotYetAllowed as u32)
+            )
+        )))
+    );
+
+    // exactly at and after the boundary
+    for (unix_timestamp, new_supply) in [
+        (first_rebase_after_timestamp, 2_000),
+        (first_rebase_after_timestamp + 1, 3_000),
+    ] {
+        set_unix_timestamp(&context, unix_timestamp).await;
+        token
+            .process_ixs(
+                &[instruction::update_supply(
+                    &id(),
+                    token.get_address(),
+                    None,
+                    &supply_authority.pubkey(),
+                    &[],
+                    new_supply,
+                )
+                .unwrap()],
+                &[&supply_authority],
+            )
+            .await
+            .unwrap();
+        let state = token.get_mint_info().await.unwrap();
+        let extension = state.get_extension::<RebaseMintConfig>().unwrap();
+        assert_eq!(u64::from(extension.total_supply), new_supply);
+    }
+}
+
+#[tokio::test]
+async fn no_grace_period() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .initial_supply(1_000, 0)
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext { token, .. } = context.token_context.take().unwrap();
+
+    token
+        .process_ixs(
+            &[instruction::update_supply(
+                &id(),
+                token.get_address(),
+                None,
+                &supply_authority.pubkey(),
+                &[],
+                2_000,
+            )
+            .unwrap()],
+            &[&supply_authority],
+        )
+        .await
+        .unwrap();
+}
@@ -0,0 +1,156 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{signature::Signer, signer::keypair::Keypair},
+    spl_token_2022::{
+        extension::{rebase_mint::instruction, BaseStateWithExtensions},
+        id,
+    },
+    spl_token_client::token::{ExtensionInitializationParams, TokenError as TokenClientError},
+    spl_token_group_interface::state::TokenGroupMember,
+};
+
+#[tokio::test]
+async fn aggregate_supply_of_rebasing_group() {
+    let group_authority = Keypair::new();
+    let supply_authority = Keypair::new();
+    let mut group_context = TestContext::new().await;
+    let group_mint = Keypair::new();
+    let group_address = Some(group_mint.pubkey());
+    group_context
+        .init_token_with_mint_keypair_and_freeze_authority(
+            group_mint,
+            vec![ExtensionInitializationParams::GroupPointer {
+                authority: Some(group_authority.pubkey()),
+                group_address,
+            }],
+            None,
+        )
+        .await
+        .unwrap();
+    let payer_pubkey = group_context.context.lock().await.payer.pubkey();
+    let TokenContext {
+        token: group_token,
+        mint_authority: group_mint_authority,
+        ..
+    } = group_context.token_context.take().unwrap();
+    group_token
+        .token_group_initialize_with_rent_transfer(
+            &payer_pubkey,
+            &group_mint_authority.pubkey(),
+            &group_authority.pubkey(),
+            2,
+            &[&group_mint_authority],
+        )
+        .await
+        .unwrap();
+
+    let mut members = vec![];
+    for initial_supply in [0, 500] {
+        let mut member_context = TestContext {
+            context: group_context.context.clone(),
+            token_context: None,
+        };
+        let member_mint = Keypair::new();
+        let member_address = Some(member_mint.pubkey());
+        member_context
+            .init_token_with_rebase_mint_keypair_and_freeze_authority(
+                member_mint,
+                vec![ExtensionInitializationParams::GroupMemberPointer {
+                    authority: Some(group_authority.pubkey()),
+                    member_address,
+                }],
+                None,
+                |mint| {
+                    instruction::InitializeRebaseMintBuilder::new(&id())
+                        .mint(mint)
+                        .supply_authority(&supply_authority.pubkey())
+                        .initial_supply(initial_supply, 0)
+                        .allow_supply_decrease()
+                        .build()
+                        .unwrap()
+                },
+            )
+            .await
+            .unwrap();
+        let member = member_context.token_context.take().unwrap();
+        member
+            .token
+            .token_group_initialize_member_with_rent_transfer(
+                &payer_pubkey,
+                &member.mint_authority.pubkey(),
+                group_token.get_address(),
+                &group_authority.pubkey(),
+                &[&member.mint_authority, &group_authority],
+            )
+            .await
+            .unwrap();
+        let state = member.token.get_mint_info().await.unwrap();
+        assert_eq!(
+            state.get_extension::<TokenGroupMember>().unwrap().group,
+            *group_token.get_address()
+        );
+        members.push(member);
+    }
+    let member_mints = members
+        .iter()
+        .map(|member| *member.token.get_address())
+        .collect::<Vec<_>>();
+
+    let alice_account = Keypair::new();
+    members[0]
+        .token
+        .create_auxiliary_token_account(&alice_account, &members[0].alice.pubkey())
+        .await
+        .unwrap();
+    members[0]
+        .token
+        .mint_to(
+            &alice_account.pubkey(),
+            &members[0].mint_authority.pubkey(),
+            1_000,
+            &[&members[0].mint_authority],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        group_token
+            .get_group_rebased_supply(&member_mints)
+            .await
+            .unwrap(),
+        1_500
+    );
+
+    // only the rebased member moves the aggregate
+    members[0]
+        .token
+        .rebase_supply(&supply_authority.pubkey(), 1_200, &[&supply_authority])
+        .await
+        .unwrap();
+    assert_eq!(
+        group_token
+            .get_group_rebased_supply(&member_mints)
+            .await
+            .unwrap(),
+        1_700
+    );
+
+    // every member must be accounted for, once
+    assert_eq!(
+        group_token
+            .get_group_rebased_supply(&member_mints[..1])
+            .await
+            .unwrap_err(),
+        TokenClientError::InvalidGroupMembers
+    );
+    assert_eq!(
+        group_token
+            .get_group_rebased_supply(&[member_mints[0], member_mints[0]])
+            .await
+            .unwrap_err(),
+        TokenClientError::InvalidGroupMembers
+    );
+}
@@ -0,0 +1,285 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        clock::Clock, instruction::InstructionError, signature::Signer, signer::keypair::Keypair,
+        transaction::TransactionError, transport::TransportError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{
+                instruction::{self, decode_rebase_history, RebaseHistoryData},
+                RebaseHistory, RebaseMintConfig, REBASE_HISTORY_LEN,
+            },
+            BaseStateWithExtensions,
+        },
+        id,
+    },
+    spl_token_client::{
+        client::ProgramBanksClientProcessTransaction,
+        token::{
+            ExtensionInitializationParams, RebaseRecord, Token, TokenError as TokenClientError,
+        },
+    },
+};
+
+async fn setup(
+    supply_authority: &Keypair,
+) -> (TestContext, Token<ProgramBanksClientProcessTransaction>) {
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_mint(vec![
+            ExtensionInitializationParams::RebaseMintConfig {
+                supply_authority: Some(supply_authority.pubkey()),
+                initial_supply: 1_000,
+            },
+            ExtensionInitializationParams::RebaseHistory,
+        ])
+        .await
+        .unwrap();
+    let TokenContext { token, .. } = context.token_context.take().unwrap();
+    (context, token)
+}
+
+async fn set_unix_timestamp(context: &TestContext, unix_timestamp: i64) {
+    let mut context = context.context.lock().await;
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp = unix_timestamp;
+    context.set_sysvar(&clock);
+}
+
+async fn get_history(token: &Token<ProgramBanksClientProcessTransaction>) -> RebaseHistoryData {
+    // no signature needed
+    let simulation = token
+        .simulate_ixs(
+            &[instruction::get_rebase_history(&id(), token.get_address()).unwrap()],
+            &[] as &[&dyn Signer; 0],
+        )
+        .await
+        .unwrap();
+    assert!(simulation.result.unwrap().is_ok());
+    let return_data = simulation
+        .simulation_details
+        .unwrap()
+        .return_data
+        .map(|return_data| return_data.data)
+        .unwrap_or_default();
+    decode_rebase_history(&return_data).unwrap()
+}
+
+#[tokio::test]
+async fn history_keeps_last_rebases() {
+    let supply_authority = Keypair::new();
+    let (_context, token) = setup(&supply_authority).await;
+
+    let state = token.get_mint_info().await.unwrap();
+    let history = state.get_extension::<RebaseHistory>().unwrap();
+    assert_eq!(history.latest(), None);
+
+    // more rebases than the history holds
+    let rebases = REBASE_HISTORY_LEN as u64 + 3;
+    let supply = |i: u64| 1_000 + 100 * i;
+    for i in 1..=rebases {
+        token
+            .rebase_supply(&supply_authority.pubkey(), supply(i), &[&supply_authority])
+            .await
+            .unwrap();
+    }
+
+    let state = token.get_mint_info().await.unwrap();
+    let history = state.get_extension::<RebaseHistory>().unwrap();
+    assert_eq!(u64::from(history.count), rebases);
+    let entries = history.entries().collect::<Vec<_>>();
+    assert_eq!(entries.len(), REBASE_HISTORY_LEN);
+    // the oldest rebases were dropped, and the rest are in order
+    let first_kept = rebases - REBASE_HISTORY_LEN as u64 + 1;
+    for (entry, i) in entries.iter().zip(first_kept..) {
+        assert_eq!(u64::from(entry.old_supply), supply(i - 1));
+        assert_eq!(u64::from(entry.new_supply), supply(i));
+        assert_eq!(u64::from(entry.new_shares), 1_000);
+    }
+    assert!(entries
+        .windows(2)
+        .all(|pair| i64::from(pair[0].unix_timestamp) <= i64::from(pair[1].unix_timestamp)));
+
+    // the newest entry is the mint's current state
+    let config = state.get_extension::<RebaseMintConfig>().unwrap();
+    let latest = history.latest().unwrap();
+    assert_eq!(latest.new_supply, config.total_supply);
+    assert_eq!(latest.new_shares, config.total_shares);
+}
+
+#[tokio::test]
+async fn history_requires_rebasing_mint() {
+    let mut context = TestContext::new().await;
+    let err = context
+        .init_token_with_mint(vec![ExtensionInitializationParams::RebaseHistory])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                2,
+                InstructionError::Custom(TokenError::InvalidExtensionCombination as u32)
+            )
+        )))
+    );
+}
+
+#[tokio::test]
+async fn get_history_matches_ring_buffer() {
+    let supply_authority = Keypair::new();
+    let (_context, token) = setup(&supply_authority).await;
+
+    for i in 0..REBASE_HISTORY_LEN as u64 + 4 {
+        let state = token.get_mint_info().await.unwrap();
+        let history = state.get_extension::<RebaseHistory>().unwrap();
+        let mut expected = history.entries().copied().collect::<Vec<_>>();
+        expected.reverse();
+
+        let returned = get_history(&token).await;
+        assert_eq!(returned.count, i);
+        assert_eq!(returned.entries, expected);
+        assert_eq!(token.get_rebase_history().await.unwrap(), returned);
+
+        token
+            .rebase_supply(
+                &supply_authority.pubkey(),
+                2_000 + 100 * i,
+                &[&supply_authority],
+            )
+            .await
+            .unwrap();
+    }
+}
+
+#[tokio::test]
+async fn get_history_requires_history() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_mint(vec![ExtensionInitializationParams::RebaseMintConfig {
+            supply_authority: Some(supply_authority.pubkey()),
+            initial_supply: 1_000,
+        }])
+        .await
+        .unwrap();
+    let TokenContext { token, .. } = context.token_context.take().unwrap();
+
+    let simulation = token
+        .simulate_ixs(
+            &[instruction::get_rebase_history(&id(), token.get_address()).unwrap()],
+            &[] as &[&dyn Signer; 0],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        simulation.result.unwrap().unwrap_err(),
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+}
+
+#[tokio::test]
+async fn conversions_at_past_rebases() {
+    let supply_authority = Keypair::new();
+    let (context, token) = setup(&supply_authority).await;
+
+    // 1_000 shares, rebased to 2_000, 3_000 and 4_500 tokens
+    for (unix_timestamp, new_supply) in [(1_000, 2_000), (2_000, 3_000), (3_000, 4_500)] {
+        set_unix_timestamp(&context, unix_timestamp).await;
+        token
+            .rebase_supply(&supply_authority.pubkey(), new_supply, &[&supply_authority])
+            .await
+            .unwrap();
+    }
+
+    let unavailable = TokenClientError::Program(TokenError::RebaseHistoryUnavailable.into());
+    assert_eq!(
+        token.shares_to_amount_at(10, 999).await.unwrap_err(),
+        unavailable
+    );
+    assert_eq!(
+        token.amount_to_shares_at(10, 999).await.unwrap_err(),
+        unavailable
+    );
+    for (unix_timestamp, amount, shares) in [
+        (1_000, 20, 5),
+        (1_500, 20, 5),
+        (2_000, 30, 3),
+        (2_999, 30, 3),
+        (3_000, 45, 2),
+        (4_000, 45, 2),
+    ] {
+        assert_eq!(
+            token.shares_to_amount_at(10, unix_timestamp).await.unwrap(),
+            amount
+        );
+        assert_eq!(
+            token.amount_to_shares_at(10, unix_timestamp).await.unwrap(),
+            shares
+        );
+    }
+}
+
+#[tokio::test]
+async fn records_from_history() {
+    let supply_authority = Keypair::new();
+    let (context, token) = setup(&supply_authority).await;
+
+    // a history that has kept every rebase is enough on its own
+    for i in 1..=3 {
+        set_unix_timestamp(&context, 1_000 * i).await;
+        token
+            .rebase_supply(
+                &supply_authority.pubkey(),
+                1_000 + 100 * i as u64,
+                &[&supply_authority],
+            )
+            .await
+            .unwrap();
+    }
+    let records = token.get_rebase_records(usize::MAX).await.unwrap();
+    assert_eq!(
+        records,
+        (1..=3)
+            .rev()
+            .map(|i| RebaseRecord {
+                unix_timestamp: 1_000 * i,
+                old_supply: 1_000 + 100 * (i as u64 - 1),
+                new_supply: 1_000 + 100 * i as u64,
+                total_shares: 1_000,
+                reason_code: 0,
+                reference: [0; 32],
+                signature: None,
+            })
+            .collect::<Vec<_>>()
+    );
+
+    // once it drops some, the rest have to come from the logs, which a
+    // BanksClient can't search
+    for i in 4..=REBASE_HISTORY_LEN as u64 + 1 {
+        token
+            .rebase_supply(
+                &supply_authority.pubkey(),
+                1_000 + 100 * i,
+                &[&supply_authority],
+            )
+            .await
+            .unwrap();
+    }
+    let records = token.get_rebase_records(REBASE_HISTORY_LEN).await.unwrap();
+    assert_eq!(records.len(), REBASE_HISTORY_LEN);
+    assert_eq!(
+        records[0].new_supply,
+        1_000 + 100 * (REBASE_HISTORY_LEN as u64 + 1)
+    );
+    assert!(matches!(
+        token.get_rebase_records(REBASE_HISTORY_LEN + 1).await,
+        Err(TokenClientError::Client(_))
+    ));
+}
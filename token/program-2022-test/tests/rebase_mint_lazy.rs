@@ -0,0 +1,214 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        instruction::InstructionError, pubkey::Pubkey, signature::Signer, signer::keypair::Keypair,
+        transaction::TransactionError, transport::TransportError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{
+                instruction, LazyRebaseAccount, RebaseAccount, RebaseMode, REBASE_INDEX_SCALE,
+            },
+            BaseStateWithExtensions,
+        },
+        id,
+    },
+    spl_token_client::{
+        client::ProgramBanksClientProcessTransaction,
+        token::{Token, TokenError as TokenClientError},
+    },
+};
+
+async fn setup(supply_authority: &Pubkey) -> TestContext {
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(supply_authority)
+                .rebase_mode(RebaseMode::Lazy)
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    context
+}
+
+async fn get_stored_amount(
+    token: &Token<ProgramBanksClientProcessTransaction>,
+    account: &Pubkey,
+) -> u64 {
+    token.get_account_info(account).await.unwrap().base.amount
+}
+
+fn token_error(error: TokenError) -> TokenClientError {
+    TokenClientError::Client(Box::new(TransportError::TransactionError(
+        TransactionError::InstructionError(0, InstructionError::Custom(error as u32)),
+    )))
+}
+
+#[tokio::test]
+async fn dormant_account_catches_up() {
+    let supply_authority = Keypair::new();
+    let mut context = setup(&supply_authority.pubkey()).await;
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        bob,
+        ..
+    } = context.token_context.take().unwrap();
+
+    let mut accounts = vec![];
+    for (owner, amount) in [(&alice, 400), (&bob, 600)] {
+        let account = Keypair::new();
+        token
+            .create_auxiliary_token_account(&account, &owner.pubkey())
+            .await
+            .unwrap();
+        token
+            .mint_to(
+                &account.pubkey(),
+                &mint_authority.pubkey(),
+                amount,
+                &[&mint_authority],
+            )
+            .await
+            .unwrap();
+        accounts.push(account.pubkey());
+    }
+    let (alice_account, bob_account) = (accounts[0], accounts[1]);
+
+    // lazy accounts carry no shares
+    let state = token.get_account_info(&alice_account).await.unwrap();
+    assert!(state.get_extension::<RebaseAccount>().is_err());
+    assert_eq!(
+        u128::from(
+            state
+                .get_extension::<LazyRebaseAccount>()
+                .unwrap()
+                .last_seen_rebase_index
+        ),
+        REBASE_INDEX_SCALE
+    );
+
+    for new_supply in [2_000, 1_500, 3_000] {
+        token
+            .rebase_supply(&supply_authority.pubkey(), new_supply, &[&supply_authority])
+            .await
+            .unwrap();
+    }
+    let config = token.get_rebase_mint_config().await.unwrap();
+    assert_eq!(config.rebase_mode().unwrap(), RebaseMode::Lazy);
+    assert_eq!(u128::from(config.rebase_index), 3 * REBASE_INDEX_SCALE);
+    assert_eq!(u64::from(config.total_supply), 3_000);
+    assert_eq!(u64::from(config.total_shares), 0);
+
+    // the stored amount lags behind until the account catches up
+    assert_eq!(get_stored_amount(&token, &alice_account).await, 400);
+    assert_eq!(
+        token.get_rebased_balance(&alice_account).await.unwrap(),
+        1_200
+    );
+    token.claim_rebase(&alice_account).await.unwrap();
+    assert_eq!(get_stored_amount(&token, &alice_account).await, 1_200);
+    assert_eq!(
+        token.get_rebased_balance(&alice_account).await.unwrap(),
+        1_200
+    );
+
+    // claiming again changes nothing. A new blockhash keeps it from being a
+    // duplicate of the first claim
+    token.get_new_latest_blockhash().await.unwrap();
+    token.claim_rebase(&alice_account).await.unwrap();
+    assert_eq!(get_stored_amount(&token, &alice_account).await, 1_200);
+
+    // a transfer catches up both sides before moving the amount
+    token
+        .transfer(&bob_account, &alice_account, &bob.pubkey(), 300, &[&bob])
+        .await
+        .unwrap();
+    assert_eq!(get_stored_amount(&token, &bob_account).await, 1_500);
+    assert_eq!(get_stored_amount(&token, &alice_account).await, 1_500);
+    let config = token.get_rebase_mint_config().await.unwrap();
+    assert_eq!(u64::from(config.total_supply), 3_000);
+}
+
+#[tokio::test]
+async fn fail_share_instructions() {
+    let supply_authority = Keypair::new();
+    let mut context = setup(&supply_authority.pubkey()).await;
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        bob,
+        ..
+    } = context.token_context.take().unwrap();
+
+    let mut accounts = vec![];
+    for owner in [&alice, &bob] {
+        let account = Keypair::new();
+        token
+            .create_auxiliary_token_account(&account, &owner.pubkey())
+            .await
+            .unwrap();
+        accounts.push(account.pubkey());
+    }
+    token
+        .mint_to(
+            &accounts[0],
+            &mint_authority.pubkey(),
+            1_000,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    let error = token
+        .transfer_shares(&accounts[0], &accounts[1], &alice.pubkey(), 100, &[&alice])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Rebase(TokenError::RebaseModeMismatch)
+    );
+
+    let sync_ix = instruction::sync_supply_from_mint(&id(), token.get_address()).unwrap();
+    let error = token
+        .process_ixs(&[sync_ix], &[] as &[&dyn Signer; 0])
+        .await
+        .unwrap_err();
+    assert_eq!(error, token_error(TokenError::RebaseModeMismatch));
+
+    // shares mints have nothing to claim
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext { token, alice, .. } = context.token_context.take().unwrap();
+    let account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&account, &alice.pubkey())
+        .await
+        .unwrap();
+    let error = token.claim_rebase(&account.pubkey()).await.unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Rebase(TokenError::RebaseModeMismatch)
+    );
+}
@@ -0,0 +1,103 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        instruction::InstructionError, signature::Signer, signer::keypair::Keypair,
+        transaction::TransactionError, transport::TransportError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{instruction, RebaseMintConfig},
+            BaseStateWithExtensions,
+        },
+        id,
+    },
+    spl_token_client::token::TokenError as TokenClientError,
+};
+
+#[tokio::test]
+async fn require_rebase_memo() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .initial_supply(1_000, 0)
+                .require_rebase_memo()
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext { token, .. } = context.token_context.take().unwrap();
+    let rebase_ix = |new_supply| {
+        instruction::update_supply(
+            &id(),
+            token.get_address(),
+            None,
+            &supply_authority.pubkey(),
+            &[],
+            new_supply,
+        )
+        .unwrap()
+    };
+
+    // no memo
+    let err = token
+        .process_ixs(&[rebase_ix(2_000)], &[&supply_authority])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::RebaseMemoRequired as u32)
+            )
+        )))
+    );
+
+    // memo after the rebase
+    let err = token
+        .process_ixs(
+            &[rebase_ix(2_000), spl_memo::build_memo(b"rebase", &[])],
+            &[&supply_authority],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::RebaseMemoRequired as u32)
+            )
+        )))
+    );
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(u64::from(extension.total_supply), 1_000);
+
+    // memo before the rebase
+    token
+        .process_ixs(
+            &instruction::RebaseSupplyBuilder::new(&id(), 2_000)
+                .mint(token.get_address())
+                .authority(&supply_authority.pubkey())
+                .build_with_memo("quarterly yield")
+                .unwrap(),
+            &[&supply_authority],
+        )
+        .await
+        .unwrap();
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(u64::from(extension.total_supply), 2_000);
+}
@@ -0,0 +1,391 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{keypair_clone, TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{pubkey::Pubkey, signature::Signer, signer::keypair::Keypair},
+    spl_token_2022::{
+        extension::{
+            metadata_pointer::MetadataPointer,
+            rebase_mint::{
+                instruction::{self, get_mint_len_with_metadata, InitializeInstructionData},
+                RebaseMintConfig, REBASE_RATE_METADATA_KEY,
+            },
+            BaseStateWithExtensions,
+        },
+        id,
+    },
+    spl_token_client::{
+        client::{ProgramBanksClient, ProgramBanksClientProcessTransaction, ProgramClient},
+        token::Token,
+    },
+    spl_token_metadata_interface::state::TokenMetadata,
+    std::{convert::TryInto, sync::Arc},
+};
+
+fn rebase_config(
+    supply_authority: &Pubkey,
+    update_metadata_rate: bool,
+) -> InitializeInstructionData {
+    InitializeInstructionData {
+        supply_authority: Some(*supply_authority).try_into().unwrap(),
+        initial_supply: 0.into(),
+        first_rebase_after_timestamp: 0.into(),
+        two_step_authority_transfer: false.into(),
+        authority_change_delay_seconds: 0.into(),
+        use_mint_authority_fallback: false.into(),
+        require_rebase_memo: false.into(),
+        virtual_shares_offset: 0.into(),
+        allow_supply_decrease: true.into(),
+        rebase_fee_bps: 0.into(),
+        fee_destination: None.try_into().unwrap(),
+        initial_shares: 0.into(),
+        max_total_shares: 0.into(),
+        allow_sunset: false.into(),
+        update_metadata_rate: update_metadata_rate.into(),
+        co_signer: None.try_into().unwrap(),
+        rebase_mode: 0,
+        sync_base_supply: false.into(),
+    }
+}
+
+#[tokio::test]
+async fn create_with_metadata_then_rebase() {
+    let context = TestContext::new().await;
+    let payer = keypair_clone(&context.context.lock().await.payer);
+    let client: Arc<dyn ProgramClient<ProgramBanksClientProcessTransaction>> =
+        Arc::new(ProgramBanksClient::new_from_context(
+            Arc::clone(&context.context),
+            ProgramBanksClientProcessTransaction,
+        ));
+    let mint = Keypair::new();
+    let token = Token::new(client, &id(), &mint.pubkey(), Some(9), Arc::new(payer));
+
+    let mint_authority = Keypair::new();
+    let supply_authority = Keypair::new();
+    let update_authority = Pubkey::new_unique();
+    let config = rebase_config(&supply_authority.pubkey(), false);
+    let token_metadata = TokenMetadata {
+        update_authority: Some(update_authority).try_into().unwrap(),
+        mint: mint.pubkey(),
+        name: "Rebasing".to_string(),
+        symbol: "REB".to_string(),
+        uri: "rebasing.token.metadata".to_string(),
+        ..Default::default()
+    };
+
+    // everything lands in a single transaction
+    token
+        .create_rebase_mint_with_metadata(
+            &mint_authority.pubkey(),
+            None,
+            &config,
+            &update_authority,
+            token_metadata.name.clone(),
+            token_metadata.symbol.clone(),
+            token_metadata.uri.clone(),
+            &[&mint, &mint_authority],
+        )
+        .await
+        .unwrap();
+
+    let state = token.get_mint_info().await.unwrap();
+    let (_, full_len) = get_mint_len_with_metadata(&token_metadata).unwrap();
+    assert_eq!(state.try_get_account_len().unwrap(), full_len);
+    let pointer = state.get_extension::<MetadataPointer>().unwrap();
+    assert_eq!(
+        Option::<Pubkey>::from(pointer.metadata_address),
+        Some(mint.pubkey())
+    );
+    assert_eq!(
+        state.get_variable_len_extension::<TokenMetadata>().unwrap(),
+        token_metadata
+    );
+
+    let alice = Keypair::new();
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    let alice_account = alice_account.pubkey();
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            100,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    token
+        .rebase_supply(&supply_authority.pubkey(), 150, &[&supply_authority])
+        .await
+        .unwrap();
+
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(u64::from(extension.total_supply), 150);
+    assert_eq!(u64::from(extension.total_shares), 100);
+    // the metadata survives the rebase untouched
+    assert_eq!(
+        state.get_variable_len_extension::<TokenMetadata>().unwrap(),
+        token_metadata
+    );
+}
+
+#[tokio::test]
+async fn rebase_updates_metadata_rate() {
+    let context = TestContext::new().await;
+    let payer = keypair_clone(&context.context.lock().await.payer);
+    let client: Arc<dyn ProgramClient<ProgramBanksClientProcessTransaction>> =
+        Arc::new(ProgramBanksClient::new_from_context(
+            Arc::clone(&context.context),
+            ProgramBanksClientProcessTransaction,
+        ));
+    let mint = Keypair::new();
+    let token = Token::new(client, &id(), &mint.pubkey(), Some(9), Arc::new(payer));
+
+    let mint_authority = Keypair::new();
+    let supply_authority = Keypair::new();
+    let update_authority = Pubkey::new_unique();
+    token
+        .create_rebase_mint_with_metadata(
+            &mint_authority.pubkey(),
+            None,
+            &rebase_config(&supply_authority.pubkey(), true),
+            &update_authority,
+            "Rebasing".to_string(),
+            "REB".to_string(),
+            "rebasing.token.metadata".to_string(),
+            &[&mint, &mint_authority],
+        )
+        .await
+        .unwrap();
+
+    let alice = Keypair::new();
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    token
+        .mint_to(
+            &alice_account.pubkey(),
+            &mint_authority.pubkey(),
+            100,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    // the first rebase inserts the field, growing the mint, and the second
+    // one overwrites it
+    for (new_supply, rate) in [(150, "1500000000000000000"), (25, "250000000000000000")] {
+        token
+            .rebase_supply(&supply_authority.pubkey(), new_supply, &[&supply_authority])
+            .await
+            .unwrap();
+
+        let state = token.get_mint_info().await.unwrap();
+        let token_metadata = state.get_variable_len_extension::<TokenMetadata>().unwrap();
+        assert_eq!(
+            token_metadata.additional_metadata,
+            vec![(REBASE_RATE_METADATA_KEY.to_string(), rate.to_string())]
+        );
+        let extension = state.get_extension::<RebaseMintConfig>().unwrap();
+        assert_eq!(extension.tokens_per_share_scaled().to_string(), rate);
+    }
+
+    // the payer covered the rent of the grown mint
+    let mint_account = token.get_account(mint.pubkey()).await.unwrap();
+    let rent = context
+        .context
+        .lock()
+        .await
+        .banks_client
+        .get_rent()
+        .await
+        .unwrap();
+    assert!(rent.is_exempt(mint_account.lamports, mint_account.data.len()));
+}
+
+#[tokio::test]
+async fn metadata_rate_on_every_client_path() {
+    let context = TestContext::new().await;
+    let payer = keypair_clone(&context.context.lock().await.payer);
+    let client: Arc<dyn ProgramClient<ProgramBanksClientProcessTransaction>> =
+        Arc::new(ProgramBanksClient::new_from_context(
+            Arc::clone(&context.context),
+            ProgramBanksClientProcessTransaction,
+        ));
+    let mint = Keypair::new();
+    let payer_pubkey = payer.pubkey();
+    let token = Token::new(client, &id(), &mint.pubkey(), Some(9), Arc::new(payer));
+
+    let mint_authority = Keypair::new();
+    let supply_authority = Keypair::new();
+    let update_authority = Pubkey::new_unique();
+    token
+        .create_rebase_mint_with_metadata(
+            &mint_authority.pubkey(),
+            None,
+            &rebase_config(&supply_authority.pubkey(), true),
+            &update_authority,
+            "Rebasing".to_string(),
+            "REB".to_string(),
+            "rebasing.token.metadata".to_string(),
+            &[&mint, &mint_authority],
+        )
+        .await
+        .unwrap();
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &Pubkey::new_unique())
+        .await
+        .unwrap();
+    token
+        .mint_to(
+            &alice_account.pubkey(),
+            &mint_authority.pubkey(),
+            100,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    // the accounts come with the payer, which signs every transaction
+    let accounts = token.get_rebase_supply_accounts().await.unwrap();
+    assert_eq!(accounts.metadata_payer, Some(payer_pubkey));
+    let signers = [&supply_authority];
+    for new_supply in 2..=6 {
+        let new_supply = new_supply * 100;
+        match new_supply {
+            200 => token
+                .rebase_supply_with_accounts(
+                    &supply_authority.pubkey(),
+                    &accounts,
+                    new_supply,
+                    &signers,
+                )
+                .await
+                .unwrap(),
+            300 => token
+                .rebase_supply_with_reason(
+                    &supply_authority.pubkey(),
+                    &accounts,
+                    new_supply,
+                    1,
+                    [1; 32],
+                    &signers,
+                )
+                .await
+                .unwrap(),
+            400 => token
+                .rebase_supply_with_deadline(
+                    &supply_authority.pubkey(),
+                    &accounts,
+                    new_supply,
+                    0,
+                    [0; 32],
+                    i64::MAX,
+                    &signers,
+                )
+                .await
+                .unwrap(),
+            500 => token
+                .rebase_supply_ui(
+                    &supply_authority.pubkey(),
+                    &accounts,
+                    "0.0000005",
+                    0,
+                    [0; 32],
+                    i64::MAX,
+                    &signers,
+                )
+                .await
+                .unwrap(),
+            _ => token
+                .safe_rebase_supply(&supply_authority.pubkey(), new_supply, &signers)
+                .await
+                .unwrap(),
+        };
+
+        let state = token.get_mint_info().await.unwrap();
+        let extension = state.get_extension::<RebaseMintConfig>().unwrap();
+        assert_eq!(u64::from(extension.total_supply), new_supply);
+        let token_metadata = state.get_variable_len_extension::<TokenMetadata>().unwrap();
+        assert_eq!(
+            token_metadata.additional_metadata,
+            vec![(
+                REBASE_RATE_METADATA_KEY.to_string(),
+                extension.tokens_per_share_scaled().to_string()
+            )]
+        );
+    }
+}
+
+#[tokio::test]
+async fn rebase_without_metadata_skips_rate() {
+    let mut context = TestContext::new().await;
+    let supply_authority = Keypair::new();
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .allow_supply_decrease()
+                .update_metadata_rate()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        ..
+    } = context.token_context.take().unwrap();
+
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    token
+        .mint_to(
+            &alice_account.pubkey(),
+            &mint_authority.pubkey(),
+            100,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    let mint_len = token
+        .get_account(*token.get_address())
+        .await
+        .unwrap()
+        .data
+        .len();
+
+    token
+        .rebase_supply(&supply_authority.pubkey(), 150, &[&supply_authority])
+        .await
+        .unwrap();
+
+    let state = token.get_mint_info().await.unwrap();
+    assert!(state.get_variable_len_extension::<TokenMetadata>().is_err());
+    let extension = state.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(u64::from(extension.total_supply), 150);
+    assert_eq!(
+        token
+            .get_account(*token.get_address())
+            .await
+            .unwrap()
+            .data
+            .len(),
+        mint_len
+    );
+}
@@ -0,0 +1,224 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{pubkey::Pubkey, signature::Signer, signer::keypair::Keypair},
+    spl_token_2022::{
+        extension::{
+            rebase_mint::{instruction, RebaseAccount},
+            BaseStateWithExtensions,
+        },
+        id,
+    },
+    spl_token_client::{
+        client::ProgramBanksClientProcessTransaction,
+        token::{Token, TokenError as TokenClientError},
+    },
+};
+
+async fn get_shares(token: &Token<ProgramBanksClientProcessTransaction>, account: &Pubkey) -> u64 {
+    let state = token.get_account_info(account).await.unwrap();
+    state
+        .get_extension::<RebaseAccount>()
+        .unwrap()
+        .shares
+        .into()
+}
+
+async fn assert_totals(
+    token: &Token<ProgramBanksClientProcessTransaction>,
+    total_supply: u64,
+    total_shares: u64,
+) {
+    let config = token.get_rebase_mint_config().await.unwrap();
+    assert_eq!(u64::from(config.total_supply), total_supply);
+    assert_eq!(u64::from(config.total_shares), total_shares);
+    assert_eq!(u64::from(config.outstanding_shares), total_shares);
+}
+
+#[tokio::test]
+async fn mint_and_burn_ui_amounts_across_rebase() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        bob,
+        ..
+    } = context.token_context.take().unwrap();
+    let mut accounts = vec![];
+    for owner in [&alice, &bob] {
+        let account = Keypair::new();
+        token
+            .create_auxiliary_token_account(&account, &owner.pubkey())
+            .await
+            .unwrap();
+        accounts.push(account.pubkey());
+    }
+    let (alice_account, bob_account) = (accounts[0], accounts[1]);
+
+    token
+        .mint_to_rebased(
+            &alice_account,
+            &mint_authority.pubkey(),
+            "100",
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    assert_eq!(get_shares(&token, &alice_account).await, 100_000_000_000);
+    assert_totals(&token, 100_000_000_000, 100_000_000_000).await;
+
+    // each share is now worth 1.5 tokens
+    token
+        .rebase_supply(
+            &supply_authority.pubkey(),
+            150_000_000_000,
+            &[&supply_authority],
+        )
+        .await
+        .unwrap();
+
+    token
+        .mint_to_rebased(
+            &bob_account,
+            &mint_authority.pubkey(),
+            "30",
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    assert_eq!(get_shares(&token, &bob_account).await, 20_000_000_000);
+    assert_eq!(
+        token.get_rebased_ui_balance(&bob_account).await.unwrap(),
+        "30"
+    );
+    assert_totals(&token, 180_000_000_000, 120_000_000_000).await;
+
+    token
+        .burn_rebased(&alice_account, &alice.pubkey(), "60", &[&alice])
+        .await
+        .unwrap();
+    assert_eq!(get_shares(&token, &alice_account).await, 60_000_000_000);
+    assert_eq!(
+        token.get_rebased_ui_balance(&alice_account).await.unwrap(),
+        "90"
+    );
+    assert_totals(&token, 120_000_000_000, 80_000_000_000).await;
+
+    // burning the whole balance leaves no shares behind
+    token
+        .burn_rebased(&bob_account, &bob.pubkey(), "30", &[&bob])
+        .await
+        .unwrap();
+    assert_eq!(get_shares(&token, &bob_account).await, 0);
+    assert_totals(&token, 90_000_000_000, 60_000_000_000).await;
+
+    // amounts that don't parse never reach the program
+    for ui_amount in ["12.5.1", "", "abc", "0.0000000001"] {
+        let err = token
+            .mint_to_rebased(
+                &bob_account,
+                &mint_authority.pubkey(),
+                ui_amount,
+                &[&mint_authority],
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(err, TokenClientError::InvalidUiAmount);
+        let err = token
+            .burn_rebased(&alice_account, &alice.pubkey(), ui_amount, &[&alice])
+            .await
+            .unwrap_err();
+        assert_eq!(err, TokenClientError::InvalidUiAmount);
+    }
+
+    // a single base unit is worth less than a share, so minting it would
+    // credit nothing
+    let err = token
+        .mint_to_rebased(
+            &bob_account,
+            &mint_authority.pubkey(),
+            "0.000000001",
+            &[&mint_authority],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err, TokenClientError::ShareSlippageExceeded);
+    assert_totals(&token, 90_000_000_000, 60_000_000_000).await;
+}
+
+#[tokio::test]
+async fn burn_whole_balance_after_rebase() {
+    for sync_base_supply in [false, true] {
+        let supply_authority = Keypair::new();
+        let mut context = TestContext::new().await;
+        context
+            .init_token_with_rebase_mint(|mint| {
+                let builder = instruction::InitializeRebaseMintBuilder::new(&id())
+                    .mint(mint)
+                    .supply_authority(&supply_authority.pubkey());
+                if sync_base_supply {
+                    builder.sync_base_supply().build().unwrap()
+                } else {
+                    builder.build().unwrap()
+                }
+            })
+            .await
+            .unwrap();
+        let TokenContext {
+            token,
+            mint_authority,
+            alice,
+            ..
+        } = context.token_context.take().unwrap();
+        let alice_account = Keypair::new();
+        token
+            .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+            .await
+            .unwrap();
+        let alice_account = alice_account.pubkey();
+
+        token
+            .mint_to_rebased(
+                &alice_account,
+                &mint_authority.pubkey(),
+                "100",
+                &[&mint_authority],
+            )
+            .await
+            .unwrap();
+        token
+            .rebase_supply(
+                &supply_authority.pubkey(),
+                150_000_000_000,
+                &[&supply_authority],
+            )
+            .await
+            .unwrap();
+
+        // more than the 100 tokens minted
+        token
+            .burn_rebased(&alice_account, &alice.pubkey(), "150", &[&alice])
+            .await
+            .unwrap();
+        assert_eq!(get_shares(&token, &alice_account).await, 0);
+        assert_totals(&token, 0, 0).await;
+        let mint = token.get_mint_info().await.unwrap();
+        assert_eq!(mint.base.supply, 0, "{}", sync_base_supply);
+    }
+}
@@ -0,0 +1,276 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{keypair_clone, TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        hash::Hash,
+        instruction::InstructionError,
+        pubkey::Pubkey,
+        signature::Signer,
+        signer::{keypair::Keypair, null_signer::NullSigner, presigner::Presigner},
+        transaction::{Transaction, TransactionError},
+        transport::TransportError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{instruction, RebaseMintConfig},
+            BaseStateWithExtensions,
+        },
+        id,
+    },
+    spl_token_client::{
+        client::{
+            ProgramClient, ProgramOfflineClient, ProgramRpcClientSendTransaction, RpcClientResponse,
+        },
+        token::{RebaseSupplyAccounts, Token, TokenError as TokenClientError},
+    },
+    std::sync::Arc,
+};
+
+#[tokio::test]
+async fn multisig_supply_authority() {
+    let multisig = Keypair::new();
+    let multisig_members = [Keypair::new(), Keypair::new(), Keypair::new()];
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&multisig.pubkey())
+                .initial_supply(1_000, 0)
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext { token, .. } = context.token_context.take().unwrap();
+    token
+        .create_multisig(
+            &multisig,
+            &multisig_members
+                .iter()
+                .map(|member| member.pubkey())
+                .collect::<Vec<_>>()
+                .iter()
+                .collect::<Vec<_>>(),
+            2,
+        )
+        .await
+        .unwrap();
+
+    let missing_signature = TokenClientError::Client(Box::new(TransportError::TransactionError(
+        TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature),
+    )));
+
+    // M-1 signers
+    let err = token
+        .rebase_supply(&multisig.pubkey(), 2_000, &[&multisig_members[0]])
+        .await
+        .unwrap_err();
+    assert_eq!(err, missing_signature);
+
+    // a non-member can't stand in for a member
+    let non_member = Keypair::new();
+    let err = token
+        .rebase_supply(
+            &multisig.pubkey(),
+            2_000,
+            &[&multisig_members[0], &non_member],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err, missing_signature);
+
+    // exactly M signers, in any order
+    for (new_supply, signers) in [
+        (2_000, [&multisig_members[0], &multisig_members[1]]),
+        (3_000, [&multisig_members[2], &multisig_members[0]]),
+    ] {
+        token
+            .rebase_supply(&multisig.pubkey(), new_supply, &signers)
+            .await
+            .unwrap();
+        let state = token.get_mint_info().await.unwrap();
+        let extension = state.get_extension::<RebaseMintConfig>().unwrap();
+        assert_eq!(u64::from(extension.total_supply), new_supply);
+        assert_eq!(u64::from(extension.total_shares), 1_000);
+    }
+
+    // M-1 signers can't revoke
+    let err = token
+        .set_rebase_supply_authority(&multisig.pubkey(), None, &[&multisig_members[1]])
+        .await
+        .unwrap_err();
+    assert_eq!(err, missing_signature);
+
+    // exactly M signers revoke
+    token
+        .set_rebase_supply_authority(
+            &multisig.pubkey(),
+            None,
+            &[&multisig_members[1], &multisig_members[2]],
+        )
+        .await
+        .unwrap();
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(Option::<Pubkey>::from(extension.supply_authority), None);
+    let err = token
+        .rebase_supply(
+            &multisig.pubkey(),
+            4_000,
+            &[&multisig_members[0], &multisig_members[1]],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Rebase(TokenError::RebaseSupplyImmutable)
+    );
+}
+
+/// Sign a rebase as a member of the multisig who only holds their own key
+async fn sign_rebase_offline(
+    mint: &Pubkey,
+    payer: &Pubkey,
+    blockhash: Hash,
+    authority: &Pubkey,
+    new_supply: u64,
+    signers: Vec<&dyn Signer>,
+) -> Transaction {
+    let offline_client: Arc<dyn ProgramClient<ProgramRpcClientSendTransaction>> = Arc::new(
+        ProgramOfflineClient::new(blockhash, ProgramRpcClientSendTransaction),
+    );
+    let offline_token = Token::new(
+        offline_client,
+        &id(),
+        mint,
+        None,
+        Arc::new(NullSigner::new(payer)),
+    );
+    let response = offline_token
+        .rebase_supply_with_accounts(
+            authority,
+            &RebaseSupplyAccounts::default(),
+            new_supply,
+            &signers,
+        )
+        .await
+        .unwrap();
+    let RpcClientResponse::Transaction(transaction) = response else {
+        panic!("offline client must return the transaction");
+    };
+    transaction
+}
+
+fn signature_of(transaction: &Transaction, signer: &Pubkey) -> Presigner {
+    let index = transaction
+        .message
+        .account_keys
+        .iter()
+        .position(|key| key == signer)
+        .unwrap();
+    Presigner::new(signer, &transaction.signatures[index])
+}
+
+#[tokio::test]
+async fn multisig_signatures_collected_in_two_steps() {
+    let multisig = Keypair::new();
+    let multisig_members = [Keypair::new(), Keypair::new(), Keypair::new()];
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&multisig.pubkey())
+                .initial_supply(1_000, 0)
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext { token, .. } = context.token_context.take().unwrap();
+    token
+        .create_multisig(
+            &multisig,
+            &multisig_members
+                .iter()
+                .map(|member| member.pubkey())
+                .collect::<Vec<_>>()
+                .iter()
+                .collect::<Vec<_>>(),
+            2,
+        )
+        .await
+        .unwrap();
+
+    let payer = keypair_clone(&context.context.lock().await.payer);
+    let blockhash = context
+        .context
+        .lock()
+        .await
+        .banks_client
+        .get_latest_blockhash()
+        .await
+        .unwrap();
+    let first_member = &multisig_members[0];
+    let second_member = &multisig_members[2];
+
+    // each member signs on their own, standing in for the other
+    let first_transaction = sign_rebase_offline(
+        token.get_address(),
+        &payer.pubkey(),
+        blockhash,
+        &multisig.pubkey(),
+        2_500,
+        vec![
+            first_member as &dyn Signer,
+            &NullSigner::new(&second_member.pubkey()),
+        ],
+    )
+    .await;
+    let second_transaction = sign_rebase_offline(
+        token.get_address(),
+        &payer.pubkey(),
+        blockhash,
+        &multisig.pubkey(),
+        2_500,
+        vec![
+            &NullSigner::new(&first_member.pubkey()) as &dyn Signer,
+            second_member,
+        ],
+    )
+    .await;
+    assert_eq!(first_transaction.message, second_transaction.message);
+
+    // the payer gathers both and lands the rebase
+    let mut transaction = Transaction::new_unsigned(first_transaction.message.clone());
+    transaction
+        .try_sign(
+            &[
+                &payer as &dyn Signer,
+                &signature_of(&first_transaction, &first_member.pubkey()),
+                &signature_of(&second_transaction, &second_member.pubkey()),
+            ],
+            blockhash,
+        )
+        .unwrap();
+    context
+        .context
+        .lock()
+        .await
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(u64::from(extension.total_supply), 2_500);
+    assert_eq!(u64::from(extension.total_shares), 1_000);
+}
@@ -0,0 +1,173 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        instruction::InstructionError, signature::Signer, signer::keypair::Keypair,
+        transaction::TransactionError, transport::TransportError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{instruction, RebaseAccount, RebaseMintConfig},
+            BaseStateWithExtensions, ExtensionType,
+        },
+        id,
+    },
+    spl_token_client::token::{ExtensionInitializationParams, TokenError as TokenClientError},
+};
+
+fn non_transferable() -> TokenClientError {
+    TokenClientError::Client(Box::new(TransportError::TransactionError(
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(TokenError::NonTransferable as u32),
+        ),
+    )))
+}
+
+#[tokio::test]
+async fn rebase_locked_balances() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint_and_extensions(
+            vec![ExtensionInitializationParams::NonTransferable],
+            |mint| {
+                instruction::InitializeRebaseMintBuilder::new(&id())
+                    .mint(mint)
+                    .supply_authority(&supply_authority.pubkey())
+                    .allow_supply_decrease()
+                    .build()
+                    .unwrap()
+            },
+        )
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        bob,
+        decimals,
+        ..
+    } = context.token_context.take().unwrap();
+    let mut accounts = vec![];
+    for owner in [&alice, &bob] {
+        let account = Keypair::new();
+        token
+            .create_auxiliary_token_account_with_extension_space(
+                &account,
+                &owner.pubkey(),
+                vec![ExtensionType::ImmutableOwner],
+            )
+            .await
+            .unwrap();
+        accounts.push(account.pubkey());
+    }
+    let (alice_account, bob_account) = (accounts[0], accounts[1]);
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            1_000,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    // the rebase applies to the locked balance
+    token
+        .rebase_supply(&supply_authority.pubkey(), 2_000, &[&supply_authority])
+        .await
+        .unwrap();
+    let state = token.get_account_info(&alice_account).await.unwrap();
+    let shares = u64::from(state.get_extension::<RebaseAccount>().unwrap().shares);
+    assert_eq!(shares, 1_000);
+    let mint = token.get_mint_info().await.unwrap();
+    let config = mint.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(config.shares_to_amount(shares).unwrap(), 2_000);
+
+    // but it can't be moved, by amount or by shares
+    let error = token
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            100,
+            &[&alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(error, non_transferable());
+    for transfer_instruction in [
+        instruction::transfer_shares_checked(
+            &id(),
+            &alice_account,
+            token.get_address(),
+            &bob_account,
+            &alice.pubkey(),
+            &[],
+            100,
+            decimals,
+        )
+        .unwrap(),
+        instruction::transfer_checked_with_share_slippage(
+            &id(),
+            &alice_account,
+            token.get_address(),
+            &bob_account,
+            &alice.pubkey(),
+            &[],
+            200,
+            decimals,
+            100,
+            0,
+        )
+        .unwrap(),
+    ] {
+        let error = token
+            .process_ixs(&[transfer_instruction], &[&alice])
+            .await
+            .unwrap_err();
+        assert_eq!(error, non_transferable());
+    }
+
+    // burning retires shares for what they're worth
+    token
+        .process_ixs(
+            &[instruction::burn_shares(
+                &id(),
+                &alice_account,
+                token.get_address(),
+                &alice.pubkey(),
+                &[],
+                100,
+            )
+            .unwrap()],
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    let state = token.get_account_info(&alice_account).await.unwrap();
+    assert_eq!(
+        u64::from(state.get_extension::<RebaseAccount>().unwrap().shares),
+        900
+    );
+    assert_eq!(state.base.amount, 1_800);
+    let mint = token.get_mint_info().await.unwrap();
+    let config = mint.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(u64::from(config.total_supply), 1_800);
+    assert_eq!(u64::from(config.total_shares), 900);
+    assert_eq!(u64::from(config.outstanding_shares), 900);
+    assert_eq!(mint.base.supply, 800);
+    token
+        .process_ixs(
+            &[instruction::assert_rebase_consistency(&id(), token.get_address()).unwrap()],
+            &[] as &[&dyn Signer; 0],
+        )
+        .await
+        .unwrap();
+}
@@ -0,0 +1,163 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{keypair_clone, TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        hash::Hash,
+        nonce,
+        signature::Signer,
+        signer::{keypair::Keypair, null_signer::NullSigner, presigner::Presigner},
+        system_instruction,
+        transaction::Transaction,
+    },
+    spl_token_2022::{
+        extension::{
+            rebase_mint::{instruction, RebaseMintConfig},
+            BaseStateWithExtensions,
+        },
+        id,
+    },
+    spl_token_client::{
+        client::{
+            ProgramClient, ProgramOfflineClient, ProgramRpcClientSendTransaction, RpcClientResponse,
+        },
+        token::{RebaseSupplyAccounts, Token},
+    },
+    std::sync::Arc,
+};
+
+#[tokio::test]
+async fn rebase_signed_offline_with_nonce() {
+    // never handed to a connected client
+    let offline_supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&offline_supply_authority.pubkey())
+                .initial_supply(1_000, 0)
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext { token, .. } = context.token_context.take().unwrap();
+
+    // the payer also holds the nonce
+    let nonce = Keypair::new();
+    let payer = keypair_clone(&context.context.lock().await.payer);
+    let rent = context
+        .context
+        .lock()
+        .await
+        .banks_client
+        .get_rent()
+        .await
+        .unwrap();
+    token
+        .process_ixs(
+            &system_instruction::create_nonce_account(
+                &payer.pubkey(),
+                &nonce.pubkey(),
+                &payer.pubkey(),
+                rent.minimum_balance(nonce::State::size()),
+            ),
+            &[&nonce],
+        )
+        .await
+        .unwrap();
+    let nonce_account = context
+        .context
+        .lock()
+        .await
+        .banks_client
+        .get_account(nonce.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let start_hash_index = 4 + 4 + 32;
+    let nonce_hash = Hash::new(&nonce_account.data[start_hash_index..start_hash_index + 32]);
+    // the nonce can only advance once the blockhash moves on
+    context
+        .context
+        .lock()
+        .await
+        .get_new_latest_blockhash()
+        .await
+        .unwrap();
+
+    // the offline side only knows the addresses and the nonce
+    let offline_client: Arc<dyn ProgramClient<ProgramRpcClientSendTransaction>> = Arc::new(
+        ProgramOfflineClient::new(nonce_hash, ProgramRpcClientSendTransaction),
+    );
+    let payer_pubkey = Arc::new(NullSigner::new(&payer.pubkey()));
+    let offline_token = Token::new(
+        offline_client,
+        &id(),
+        token.get_address(),
+        None,
+        payer_pubkey.clone(),
+    )
+    .with_nonce(&nonce.pubkey(), payer_pubkey, &nonce_hash);
+    let response = offline_token
+        .rebase_supply_with_accounts(
+            &offline_supply_authority.pubkey(),
+            &RebaseSupplyAccounts::default(),
+            2_000,
+            &[&offline_supply_authority],
+        )
+        .await
+        .unwrap();
+    let RpcClientResponse::Transaction(offline_transaction) = response else {
+        panic!("offline client must return the transaction");
+    };
+    let message_data = offline_transaction.message_data();
+    let signature = offline_supply_authority.sign_message(&message_data);
+    assert!(offline_transaction.signatures.contains(&signature));
+
+    // the online side adds its own signature to the collected ones and lands
+    // the transaction
+    let mut transaction = Transaction::new_unsigned(offline_transaction.message);
+    transaction
+        .try_sign(
+            &[
+                &payer as &dyn Signer,
+                &Presigner::new(&offline_supply_authority.pubkey(), &signature),
+            ],
+            nonce_hash,
+        )
+        .unwrap();
+    let result = context
+        .context
+        .lock()
+        .await
+        .banks_client
+        .process_transaction_with_metadata(transaction)
+        .await
+        .unwrap();
+    assert_eq!(result.result, Ok(()));
+
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(u64::from(extension.total_supply), 2_000);
+    assert_eq!(u64::from(extension.total_shares), 1_000);
+
+    // and the nonce was consumed
+    let nonce_account = context
+        .context
+        .lock()
+        .await
+        .banks_client
+        .get_account(nonce.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_ne!(
+        Hash::new(&nonce_account.data[start_hash_index..start_hash_index + 32]),
+        nonce_hash
+    );
+}
@@ -0,0 +1,97 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{pubkey::Pubkey, signature::Signer, signer::keypair::Keypair},
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{instruction, RebaseMintConfig},
+            BaseStateWithExtensions,
+        },
+        id,
+    },
+    spl_token_client::{
+        client::ProgramBanksClientProcessTransaction,
+        token::{Token, TokenError as TokenClientError},
+    },
+};
+
+async fn get_config(token: &Token<ProgramBanksClientProcessTransaction>) -> RebaseMintConfig {
+    let mint = token.get_mint_info().await.unwrap();
+    *mint.get_extension::<RebaseMintConfig>().unwrap()
+}
+
+#[tokio::test]
+async fn rebase_floored_by_outstanding_shares() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .initial_supply(1_000, 0)
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        bob,
+        ..
+    } = context.token_context.take().unwrap();
+
+    let mut accounts: Vec<Pubkey> = vec![];
+    for (owner, amount) in [(&alice, 300), (&bob, 200)] {
+        let account = Keypair::new();
+        token
+            .create_auxiliary_token_account(&account, &owner.pubkey())
+            .await
+            .unwrap();
+        token
+            .mint_to(
+                &account.pubkey(),
+                &mint_authority.pubkey(),
+                amount,
+                &[&mint_authority],
+            )
+            .await
+            .unwrap();
+        accounts.push(account.pubkey());
+    }
+
+    // the initial supply was never minted, so its shares aren't outstanding
+    let config = get_config(&token).await;
+    assert_eq!(u64::from(config.total_shares), 1_500);
+    assert_eq!(u64::from(config.outstanding_shares), 500);
+
+    let err = token
+        .rebase_supply(&supply_authority.pubkey(), 0, &[&supply_authority])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Rebase(TokenError::RebaseBelowOutstandingShares)
+    );
+    assert_eq!(get_config(&token).await, config);
+
+    // a downward rebase that leaves the shares worth something goes through
+    token
+        .rebase_supply(&supply_authority.pubkey(), 750, &[&supply_authority])
+        .await
+        .unwrap();
+    token
+        .burn(&accounts[1], &bob.pubkey(), 100, &[&bob])
+        .await
+        .unwrap();
+    let config = get_config(&token).await;
+    assert_eq!(u64::from(config.total_shares), 1_300);
+    assert_eq!(u64::from(config.outstanding_shares), 300);
+}
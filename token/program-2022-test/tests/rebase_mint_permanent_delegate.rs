@@ -0,0 +1,126 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{pubkey::Pubkey, signature::Signer, signer::keypair::Keypair},
+    spl_token_2022::{
+        extension::{
+            rebase_mint::{instruction, RebaseAccount, RebaseMintConfig},
+            BaseStateWithExtensions,
+        },
+        id,
+    },
+    spl_token_client::{
+        client::ProgramBanksClientProcessTransaction,
+        token::{ExtensionInitializationParams, Token},
+    },
+};
+
+async fn get_value(token: &Token<ProgramBanksClientProcessTransaction>, account: &Pubkey) -> u64 {
+    let state = token.get_account_info(account).await.unwrap();
+    let shares = u64::from(state.get_extension::<RebaseAccount>().unwrap().shares);
+    let mint = token.get_mint_info().await.unwrap();
+    let config = mint.get_extension::<RebaseMintConfig>().unwrap();
+    config.shares_to_amount(shares).unwrap()
+}
+
+#[tokio::test]
+async fn seize_and_burn_after_rebase() {
+    let supply_authority = Keypair::new();
+    let permanent_delegate = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint_and_extensions(
+            vec![ExtensionInitializationParams::PermanentDelegate {
+                delegate: permanent_delegate.pubkey(),
+            }],
+            |mint| {
+                instruction::InitializeRebaseMintBuilder::new(&id())
+                    .mint(mint)
+                    .supply_authority(&supply_authority.pubkey())
+                    .allow_supply_decrease()
+                    .build()
+                    .unwrap()
+            },
+        )
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        bob,
+        ..
+    } = context.token_context.take().unwrap();
+    let mut accounts = vec![];
+    for owner in [&alice, &bob] {
+        let account = Keypair::new();
+        token
+            .create_auxiliary_token_account(&account, &owner.pubkey())
+            .await
+            .unwrap();
+        accounts.push(account.pubkey());
+    }
+    let (alice_account, bob_account) = (accounts[0], accounts[1]);
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            1_000,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    token
+        .rebase_supply(&supply_authority.pubkey(), 3_000, &[&supply_authority])
+        .await
+        .unwrap();
+    assert_eq!(get_value(&token, &alice_account).await, 3_000);
+
+    // the seized amount is taken at the new ratio
+    token
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &permanent_delegate.pubkey(),
+            300,
+            &[&permanent_delegate],
+        )
+        .await
+        .unwrap();
+    assert_eq!(get_value(&token, &alice_account).await, 2_700);
+    assert_eq!(get_value(&token, &bob_account).await, 300);
+
+    // and so is the burned amount, which leaves the totals with the shares
+    token
+        .burn(
+            &alice_account,
+            &permanent_delegate.pubkey(),
+            600,
+            &[&permanent_delegate],
+        )
+        .await
+        .unwrap();
+    assert_eq!(get_value(&token, &alice_account).await, 2_100);
+    let state = token.get_account_info(&alice_account).await.unwrap();
+    assert_eq!(
+        u64::from(state.get_extension::<RebaseAccount>().unwrap().shares),
+        700
+    );
+    let mint = token.get_mint_info().await.unwrap();
+    let config = mint.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(u64::from(config.total_supply), 2_400);
+    assert_eq!(u64::from(config.total_shares), 800);
+    assert_eq!(u64::from(config.outstanding_shares), 800);
+    assert_eq!(mint.base.supply, 400);
+    token
+        .process_ixs(
+            &[instruction::assert_rebase_consistency(&id(), token.get_address()).unwrap()],
+            &[] as &[&dyn Signer; 0],
+        )
+        .await
+        .unwrap();
+}
@@ -0,0 +1,230 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        instruction::InstructionError, pubkey::Pubkey, signature::Signer, signer::keypair::Keypair,
+        transaction::TransactionError,
+    },
+    spl_pod::bytemuck::{pod_bytes_of, pod_from_bytes},
+    spl_token_2022::{
+        extension::{
+            rebase_mint::{instruction, RebaseMintConfig, RebasePreview},
+            BaseStateWithExtensions,
+        },
+        id,
+    },
+};
+
+#[tokio::test]
+async fn preview_matches_rebase() {
+    let supply_authority = Keypair::new();
+    let initial_supply = 1_000;
+    let new_supply = 2_500;
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .initial_supply(initial_supply, 0)
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext { token, .. } = context.token_context.take().unwrap();
+
+    let mint_before = context
+        .context
+        .lock()
+        .await
+        .banks_client
+        .get_account(*token.get_address())
+        .await
+        .unwrap()
+        .unwrap();
+
+    // no signature needed
+    let simulation = token
+        .simulate_ixs(
+            &[
+                instruction::preview_rebase_supply(&id(), token.get_address(), None, new_supply)
+                    .unwrap(),
+            ],
+            &[] as &[&dyn Signer; 0],
+        )
+        .await
+        .unwrap();
+    assert!(simulation.result.unwrap().is_ok());
+    let return_data = simulation.simulation_details.unwrap().return_data.unwrap();
+    assert_eq!(return_data.program_id, id());
+    let preview = *pod_from_bytes::<RebasePreview>(&return_data.data).unwrap();
+    assert_eq!(u64::from(preview.new_supply), new_supply);
+    assert_eq!(u64::from(preview.total_shares), initial_supply);
+    assert_eq!(u64::from(preview.rounding_error), 500);
+
+    // running it for real doesn't change the account either
+    token
+        .process_ixs(
+            &[
+                instruction::preview_rebase_supply(&id(), token.get_address(), None, new_supply)
+                    .unwrap(),
+            ],
+            &[] as &[&dyn Signer; 0],
+        )
+        .await
+        .unwrap();
+    let mint_after = context
+        .context
+        .lock()
+        .await
+        .banks_client
+        .get_account(*token.get_address())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(mint_before, mint_after);
+
+    token
+        .process_ixs(
+            &[instruction::update_supply(
+                &id(),
+                token.get_address(),
+                None,
+                &supply_authority.pubkey(),
+                &[],
+                new_supply,
+            )
+            .unwrap()],
+            &[&supply_authority],
+        )
+        .await
+        .unwrap();
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(extension.total_supply, preview.new_supply);
+    assert_eq!(extension.total_shares, preview.total_shares);
+}
+
+#[tokio::test]
+async fn preview_fails_like_rebase() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .initial_supply(1_000, 0)
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext { token, .. } = context.token_context.take().unwrap();
+
+    // rebasing to zero with outstanding shares is rejected by both
+    let simulation = token
+        .simulate_ixs(
+            &[instruction::preview_rebase_supply(&id(), token.get_address(), None, 0).unwrap()],
+            &[] as &[&dyn Signer; 0],
+        )
+        .await
+        .unwrap();
+    assert!(simulation.result.unwrap().is_err());
+
+    // only the program's own mints can be previewed
+    let simulation = token
+        .simulate_ixs(
+            &[instruction::preview_rebase_supply(&id(), &Pubkey::new_unique(), None, 0).unwrap()],
+            &[] as &[&dyn Signer; 0],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        simulation.result.unwrap(),
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::IncorrectProgramId
+        ))
+    );
+    token
+        .process_ixs(
+            &[instruction::update_supply(
+                &id(),
+                token.get_address(),
+                None,
+                &supply_authority.pubkey(),
+                &[],
+                0,
+            )
+            .unwrap()],
+            &[&supply_authority],
+        )
+        .await
+        .unwrap_err();
+}
+
+#[tokio::test]
+async fn client_preview_matches_stored_config() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .virtual_shares_offset(7)
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        ..
+    } = context.token_context.take().unwrap();
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    token
+        .mint_to(
+            &alice_account.pubkey(),
+            &mint_authority.pubkey(),
+            999_999_937,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    // prime ratios, a shrink, an unchanged supply and a huge jump
+    for new_supply in [
+        1_000_000_007,
+        333_333_331,
+        333_333_331,
+        1,
+        u64::MAX - 7,
+        999_999_937,
+    ] {
+        let (preview, predicted) = token.preview_rebase_supply(new_supply).await.unwrap();
+        token
+            .rebase_supply(&supply_authority.pubkey(), new_supply, &[&supply_authority])
+            .await
+            .unwrap();
+        let state = token.get_mint_info().await.unwrap();
+        let stored = state.get_extension::<RebaseMintConfig>().unwrap();
+        assert_eq!(pod_bytes_of(&predicted), pod_bytes_of(stored));
+        assert_eq!(preview.new_supply, stored.total_supply);
+        assert_eq!(preview.total_shares, stored.total_shares);
+    }
+}
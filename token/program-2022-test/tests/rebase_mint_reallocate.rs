@@ -0,0 +1,147 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        instruction::InstructionError, signature::Signer, signer::keypair::Keypair,
+        transaction::TransactionError, transport::TransportError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{instruction, RebaseAccount},
+            BaseStateWithExtensions, ExtensionType,
+        },
+        id,
+        state::Account,
+    },
+    spl_token_client::token::TokenError as TokenClientError,
+};
+
+fn not_reallocatable() -> TokenClientError {
+    TokenClientError::Client(Box::new(TransportError::TransactionError(
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(TokenError::RebaseExtensionNotReallocatable as u32),
+        ),
+    )))
+}
+
+#[tokio::test]
+async fn reallocate_rebase_account() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        ..
+    } = context.token_context.take().unwrap();
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    let alice_account = alice_account.pubkey();
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            1_000,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    // the mint config never goes through reallocation
+    let error = token
+        .reallocate(
+            &alice_account,
+            &alice.pubkey(),
+            &[ExtensionType::RebaseMintConfig],
+            &[&alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(error, not_reallocatable());
+
+    // the account already tracks its shares, so it only grows for the others
+    token
+        .reallocate(
+            &alice_account,
+            &alice.pubkey(),
+            &[ExtensionType::RebaseAccount, ExtensionType::MemoTransfer],
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    let account = token.get_account(alice_account).await.unwrap();
+    let expected_len = ExtensionType::try_calculate_account_len::<Account>(&[
+        ExtensionType::RebaseAccount,
+        ExtensionType::MemoTransfer,
+    ])
+    .unwrap();
+    assert_eq!(account.data.len(), expected_len);
+    let rent = context
+        .context
+        .lock()
+        .await
+        .banks_client
+        .get_rent()
+        .await
+        .unwrap();
+    assert!(account.lamports >= rent.minimum_balance(expected_len));
+
+    let state = token.get_account_info(&alice_account).await.unwrap();
+    assert_eq!(
+        u64::from(state.get_extension::<RebaseAccount>().unwrap().shares),
+        1_000
+    );
+    token
+        .enable_required_transfer_memos(&alice_account, &alice.pubkey(), &[&alice])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn reallocate_rebase_account_without_rebase_mint() {
+    let mut context = TestContext::new().await;
+    context.init_token_with_mint(vec![]).await.unwrap();
+    let TokenContext { token, alice, .. } = context.token_context.unwrap();
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    let alice_account = alice_account.pubkey();
+
+    // shares can't be bolted onto an account after initialization
+    let error = token
+        .reallocate(
+            &alice_account,
+            &alice.pubkey(),
+            &[ExtensionType::RebaseAccount],
+            &[&alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(error, not_reallocatable());
+    let account = token.get_account(alice_account).await.unwrap();
+    assert_eq!(
+        account.data.len(),
+        ExtensionType::try_calculate_account_len::<Account>(&[]).unwrap()
+    );
+}
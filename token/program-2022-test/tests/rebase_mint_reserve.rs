@@ -0,0 +1,279 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        instruction::InstructionError, pubkey::Pubkey, signature::Signer, signer::keypair::Keypair,
+        system_instruction, transaction::TransactionError, transport::TransportError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{instruction as rebase_instruction, RebaseMintConfig},
+            BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+        },
+        id, instruction,
+        state::Mint,
+    },
+    spl_token_client::token::TokenError as TokenClientError,
+};
+
+const DECIMALS: u8 = 9;
+
+/// Creates a rebasing mint, using the test context's token to send the
+/// transaction, and returns its address
+async fn create_rebase_mint(
+    context: &TestContext,
+    supply_authority: &Pubkey,
+    initial_supply: u64,
+) -> Pubkey {
+    let TokenContext {
+        token,
+        mint_authority,
+        ..
+    } = context.token_context.as_ref().unwrap();
+    let mint_account = Keypair::new();
+    let space =
+        ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::RebaseMintConfig])
+            .unwrap();
+    let (payer, lamports) = {
+        let mut context = context.context.lock().await;
+        let rent = context.banks_client.get_rent().await.unwrap();
+        (context.payer.pubkey(), rent.minimum_balance(space))
+    };
+    token
+        .process_ixs(
+            &[
+                system_instruction::create_account(
+                    &payer,
+                    &mint_account.pubkey(),
+                    lamports,
+                    space as u64,
+                    &id(),
+                ),
+                rebase_instruction::InitializeRebaseMintBuilder::new(&id())
+                    .mint(&mint_account.pubkey())
+                    .supply_authority(supply_authority)
+                    .initial_supply(initial_supply, 0)
+                    .allow_supply_decrease()
+                    .build()
+                    .unwrap(),
+                instruction::initialize_mint(
+                    &id(),
+                    &mint_account.pubkey(),
+                    &mint_authority.pubkey(),
+                    None,
+                    DECIMALS,
+                )
+                .unwrap(),
+            ],
+            &[&mint_account],
+        )
+        .await
+        .unwrap();
+    mint_account.pubkey()
+}
+
+async fn get_config(context: &TestContext, mint: &Pubkey) -> RebaseMintConfig {
+    let account = context
+        .context
+        .lock()
+        .await
+        .banks_client
+        .get_account(*mint)
+        .await
+        .unwrap()
+        .unwrap();
+    let state = StateWithExtensions::<Mint>::unpack(&account.data).unwrap();
+    *state.get_extension::<RebaseMintConfig>().unwrap()
+}
+
+#[tokio::test]
+async fn rebase_capped_by_reserve() {
+    let supply_authority = Keypair::new();
+    let initial_supply = 1_000;
+    let reserve_amount = 5_000;
+    let mut context = TestContext::new().await;
+    // the reserve is held in a token account of an ordinary mint
+    context.init_token_with_mint(vec![]).await.unwrap();
+    let mint = create_rebase_mint(&context, &supply_authority.pubkey(), initial_supply).await;
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        ..
+    } = context.token_context.as_ref().unwrap();
+
+    let reserve_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&reserve_account, &alice.pubkey())
+        .await
+        .unwrap();
+    token
+        .mint_to(
+            &reserve_account.pubkey(),
+            &mint_authority.pubkey(),
+            reserve_amount,
+            &[mint_authority],
+        )
+        .await
+        .unwrap();
+
+    // wrong authority can't set the reserve
+    let wrong_signer = Keypair::new();
+    let err = token
+        .process_ixs(
+            &[rebase_instruction::set_reserve_account(
+                &id(),
+                &mint,
+                &wrong_signer.pubkey(),
+                &[],
+                Some(reserve_account.pubkey()),
+            )
+            .unwrap()],
+            &[&wrong_signer],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::OwnerMismatch as u32)
+            )
+        )))
+    );
+
+    token
+        .process_ixs(
+            &[rebase_instruction::set_reserve_account(
+                &id(),
+                &mint,
+                &supply_authority.pubkey(),
+                &[],
+                Some(reserve_account.pubkey()),
+            )
+            .unwrap()],
+            &[&supply_authority],
+        )
+        .await
+        .unwrap();
+    let config = get_config(&context, &mint).await;
+    assert_eq!(
+        Option::<Pubkey>::from(config.reserve_account),
+        Some(reserve_account.pubkey()),
+    );
+
+    // one unit above the reserve fails
+    let err = token
+        .process_ixs(
+            &[rebase_instruction::update_supply(
+                &id(),
+                &mint,
+                Some(&reserve_account.pubkey()),
+                &supply_authority.pubkey(),
+                &[],
+                reserve_amount + 1,
+            )
+            .unwrap()],
+            &[&supply_authority],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::RebaseExceedsReserve as u32)
+            )
+        )))
+    );
+
+    // some other account can't stand in for the reserve
+    let other_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&other_account, &alice.pubkey())
+        .await
+        .unwrap();
+    let err = token
+        .process_ixs(
+            &[rebase_instruction::update_supply(
+                &id(),
+                &mint,
+                Some(&other_account.pubkey()),
+                &supply_authority.pubkey(),
+                &[],
+                reserve_amount,
+            )
+            .unwrap()],
+            &[&supply_authority],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::RebaseReserveMismatch as u32)
+            )
+        )))
+    );
+
+    // exactly the reserve succeeds
+    token
+        .process_ixs(
+            &[rebase_instruction::update_supply(
+                &id(),
+                &mint,
+                Some(&reserve_account.pubkey()),
+                &supply_authority.pubkey(),
+                &[],
+                reserve_amount,
+            )
+            .unwrap()],
+            &[&supply_authority],
+        )
+        .await
+        .unwrap();
+    let config = get_config(&context, &mint).await;
+    assert_eq!(u64::from(config.total_supply), reserve_amount);
+    assert_eq!(u64::from(config.total_shares), initial_supply);
+
+    // clearing the reserve lifts the cap
+    token
+        .process_ixs(
+            &[rebase_instruction::set_reserve_account(
+                &id(),
+                &mint,
+                &supply_authority.pubkey(),
+                &[],
+                None,
+            )
+            .unwrap()],
+            &[&supply_authority],
+        )
+        .await
+        .unwrap();
+    token
+        .process_ixs(
+            &[rebase_instruction::update_supply(
+                &id(),
+                &mint,
+                None,
+                &supply_authority.pubkey(),
+                &[],
+                reserve_amount + 1,
+            )
+            .unwrap()],
+            &[&supply_authority],
+        )
+        .await
+        .unwrap();
+    let config = get_config(&context, &mint).await;
+    assert_eq!(u64::from(config.total_supply), reserve_amount + 1);
+}
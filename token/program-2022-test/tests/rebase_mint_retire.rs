@@ -0,0 +1,156 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        instruction::InstructionError, pubkey::Pubkey, signature::Signer, signer::keypair::Keypair,
+        transaction::TransactionError, transport::TransportError,
+    },
+    spl_token_2022::{error::TokenError, extension::rebase_mint::instruction, id},
+    spl_token_client::token::TokenError as TokenClientError,
+};
+
+async fn setup(supply_authority: &Pubkey) -> TestContext {
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(supply_authority)
+                // fall back to the mint authority, so that a rebase after
+                // retirement isn't stopped by the missing supply authority
+                .use_mint_authority_fallback()
+                .allow_supply_decrease()
+                .allow_sunset()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    context
+}
+
+fn token_error(error: TokenError) -> TokenClientError {
+    TokenClientError::Client(Box::new(TransportError::TransactionError(
+        TransactionError::InstructionError(0, InstructionError::Custom(error as u32)),
+    )))
+}
+
+#[tokio::test]
+async fn retire_freezes_ratio() {
+    let supply_authority = Keypair::new();
+    let mut context = setup(&supply_authority.pubkey()).await;
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        bob,
+        ..
+    } = context.token_context.take().unwrap();
+
+    let mut accounts = vec![];
+    for owner in [&alice, &bob] {
+        let account = Keypair::new();
+        token
+            .create_auxiliary_token_account(&account, &owner.pubkey())
+            .await
+            .unwrap();
+        accounts.push(account.pubkey());
+    }
+    token
+        .mint_to(
+            &accounts[0],
+            &mint_authority.pubkey(),
+            1_000,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    token
+        .rebase_supply(&supply_authority.pubkey(), 1_500, &[&supply_authority])
+        .await
+        .unwrap();
+
+    token
+        .retire_rebase(&supply_authority.pubkey(), &[&supply_authority])
+        .await
+        .unwrap();
+    let config = token.get_rebase_mint_config().await.unwrap();
+    assert!(bool::from(config.is_retired));
+    assert_eq!(u64::from(config.retired_supply), 1_500);
+    assert_eq!(u64::from(config.retired_shares), 1_000);
+    assert_eq!(Option::<Pubkey>::from(config.supply_authority), None);
+    assert_eq!(
+        token.get_rebased_balance(&accounts[0]).await.unwrap(),
+        1_500
+    );
+
+    // the supply can't move anymore, not even through the fallback
+    let error = token
+        .rebase_supply(&mint_authority.pubkey(), 3_000, &[&mint_authority])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Rebase(TokenError::RebaseMintRetired)
+    );
+    let error = token
+        .retire_rebase(&mint_authority.pubkey(), &[&mint_authority])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Rebase(TokenError::RebaseMintRetired)
+    );
+    let error = token
+        .sunset_rebase(&mint_authority.pubkey(), &[&mint_authority])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Rebase(TokenError::RebaseMintRetired)
+    );
+
+    // minting, transferring and burning go on at the frozen ratio
+    token
+        .mint_to(
+            &accounts[1],
+            &mint_authority.pubkey(),
+            300,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    token
+        .transfer(&accounts[0], &accounts[1], &alice.pubkey(), 600, &[&alice])
+        .await
+        .unwrap();
+    token
+        .burn(&accounts[1], &bob.pubkey(), 150, &[&bob])
+        .await
+        .unwrap();
+    assert_eq!(token.get_rebased_balance(&accounts[0]).await.unwrap(), 900);
+    assert_eq!(token.get_rebased_balance(&accounts[1]).await.unwrap(), 750);
+
+    let config = token.get_rebase_mint_config().await.unwrap();
+    assert_eq!(config.amount_to_shares(750), Some(500));
+    assert_eq!(u64::from(config.total_supply), 1_650);
+    assert_eq!(u64::from(config.total_shares), 1_100);
+    assert_eq!(u64::from(config.retired_supply), 1_500);
+    assert_eq!(u64::from(config.retired_shares), 1_000);
+}
+
+#[tokio::test]
+async fn fail_retire_by_other_signer() {
+    let supply_authority = Keypair::new();
+    let mut context = setup(&supply_authority.pubkey()).await;
+    let TokenContext { token, alice, .. } = context.token_context.take().unwrap();
+
+    let error = token
+        .retire_rebase(&alice.pubkey(), &[&alice])
+        .await
+        .unwrap_err();
+    assert_eq!(error, token_error(TokenError::OwnerMismatch));
+}
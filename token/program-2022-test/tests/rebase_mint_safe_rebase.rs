@@ -0,0 +1,237 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    async_trait::async_trait,
+    program_test::{keypair_clone, TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        account::Account,
+        hash::Hash,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+        signature::{Signature, Signer},
+        signer::keypair::Keypair,
+        transaction::Transaction,
+    },
+    spl_token_2022::{error::TokenError, extension::rebase_mint::instruction, id},
+    spl_token_client::{
+        client::{
+            ProgramBanksClient, ProgramBanksClientProcessTransaction, ProgramClient,
+            ProgramClientResult, SimulateTransaction, TransactionLogs,
+        },
+        token::{Token, TokenError as TokenClientError},
+    },
+    std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+/// Counts the transactions sent through it
+struct CountingClient {
+    inner: ProgramBanksClient<ProgramBanksClientProcessTransaction>,
+    sent: AtomicUsize,
+}
+
+impl CountingClient {
+    fn sent(&self) -> usize {
+        self.sent.load(Ordering::SeqCst)
+    }
+}
+
+#[async_trait]
+impl ProgramClient<ProgramBanksClientProcessTransaction> for CountingClient {
+    async fn get_minimum_balance_for_rent_exemption(
+        &self,
+        data_len: usize,
+    ) -> ProgramClientResult<u64> {
+        self.inner
+            .get_minimum_balance_for_rent_exemption(data_len)
+            .await
+    }
+
+    async fn get_latest_blockhash(&self) -> ProgramClientResult<Hash> {
+        self.inner.get_latest_blockhash().await
+    }
+
+    async fn send_transaction(&self, transaction: &Transaction) -> ProgramClientResult<()> {
+        self.sent.fetch_add(1, Ordering::SeqCst);
+        self.inner.send_transaction(transaction).await
+    }
+
+    async fn get_account(&self, address: Pubkey) -> ProgramClientResult<Option<Account>> {
+        self.inner.get_account(address).await
+    }
+
+    async fn simulate_transaction(
+        &self,
+        transaction: &Transaction,
+    ) -> ProgramClientResult<
+        <ProgramBanksClientProcessTransaction as SimulateTransaction>::SimulationOutput,
+    > {
+        self.inner.simulate_transaction(transaction).await
+    }
+
+    async fn get_transaction_logs(
+        &self,
+        address: Pubkey,
+        before: Option<Signature>,
+        limit: usize,
+    ) -> ProgramClientResult<Vec<TransactionLogs>> {
+        self.inner
+            .get_transaction_logs(address, before, limit)
+            .await
+    }
+}
+
+async fn setup(
+    supply_authority: &Pubkey,
+    require_rebase_memo: bool,
+) -> (
+    TestContext,
+    TokenContext,
+    Arc<CountingClient>,
+    Token<ProgramBanksClientProcessTransaction>,
+) {
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            let builder = instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(supply_authority)
+                .initial_supply(1_000, 0)
+                .allow_supply_decrease();
+            if require_rebase_memo {
+                builder.require_rebase_memo()
+            } else {
+                builder
+            }
+            .build()
+            .unwrap()
+        })
+        .await
+        .unwrap();
+    let token_context = context.token_context.take().unwrap();
+    let client = Arc::new(CountingClient {
+        inner: ProgramBanksClient::new_from_context(
+            Arc::clone(&context.context),
+            ProgramBanksClientProcessTransaction,
+        ),
+        sent: AtomicUsize::new(0),
+    });
+    let payer = keypair_clone(&context.context.lock().await.payer);
+    let token = Token::new(
+        client.clone(),
+        &id(),
+        token_context.token.get_address(),
+        Some(token_context.decimals),
+        Arc::new(payer),
+    );
+    (context, token_context, client, token)
+}
+
+fn program_error(error: TokenError) -> TokenClientError {
+    TokenClientError::Program(ProgramError::from(error))
+}
+
+#[tokio::test]
+async fn safe_rebase_capped_by_reserve() {
+    let supply_authority = Keypair::new();
+    let (context, token_context, client, token) = setup(&supply_authority.pubkey(), false).await;
+    let TokenContext {
+        mint_authority,
+        alice,
+        decimals,
+        ..
+    } = token_context;
+
+    // the reserve is held in a token account of an ordinary mint
+    let reserve_mint = Keypair::new();
+    let payer = keypair_clone(&context.context.lock().await.payer);
+    let reserve_token = Token::new(
+        client.clone(),
+        &id(),
+        &reserve_mint.pubkey(),
+        Some(decimals),
+        Arc::new(payer),
+    );
+    reserve_token
+        .create_mint(&mint_authority.pubkey(), None, vec![], &[&reserve_mint])
+        .await
+        .unwrap();
+    let reserve_account = Keypair::new();
+    reserve_token
+        .create_auxiliary_token_account(&reserve_account, &alice.pubkey())
+        .await
+        .unwrap();
+    reserve_token
+        .mint_to(
+            &reserve_account.pubkey(),
+            &mint_authority.pubkey(),
+            5_000,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    token
+        .process_ixs(
+            &[instruction::set_reserve_account(
+                &id(),
+                token.get_address(),
+                &supply_authority.pubkey(),
+                &[],
+                Some(reserve_account.pubkey()),
+            )
+            .unwrap()],
+            &[&supply_authority],
+        )
+        .await
+        .unwrap();
+
+    // rebases the program would reject are never sent
+    let sent = client.sent();
+    let err = token
+        .safe_rebase_supply(&supply_authority.pubkey(), 5_001, &[&supply_authority])
+        .await
+        .unwrap_err();
+    assert_eq!(err, program_error(TokenError::RebaseExceedsReserve));
+    let wrong_signer = Keypair::new();
+    let err = token
+        .safe_rebase_supply(&wrong_signer.pubkey(), 2_000, &[&wrong_signer])
+        .await
+        .unwrap_err();
+    assert_eq!(err, program_error(TokenError::OwnerMismatch));
+    assert_eq!(client.sent(), sent);
+
+    // up to the reserve, the rebase lands
+    token
+        .safe_rebase_supply(&supply_authority.pubkey(), 5_000, &[&supply_authority])
+        .await
+        .unwrap();
+    assert_eq!(client.sent(), sent + 1);
+    let config = token.get_rebase_mint_config().await.unwrap();
+    assert_eq!(u64::from(config.total_supply), 5_000);
+}
+
+#[tokio::test]
+async fn safe_rebase_requires_memo() {
+    let supply_authority = Keypair::new();
+    let (_context, _token_context, client, token) = setup(&supply_authority.pubkey(), true).await;
+
+    let err = token
+        .safe_rebase_supply(&supply_authority.pubkey(), 2_000, &[&supply_authority])
+        .await
+        .unwrap_err();
+    assert_eq!(err, program_error(TokenError::RebaseMemoRequired));
+    assert_eq!(client.sent(), 0);
+
+    token.with_memo("monthly yield", vec![]);
+    token
+        .safe_rebase_supply(&supply_authority.pubkey(), 2_000, &[&supply_authority])
+        .await
+        .unwrap();
+    assert_eq!(client.sent(), 1);
+    let config = token.get_rebase_mint_config().await.unwrap();
+    assert_eq!(u64::from(config.total_supply), 2_000);
+}
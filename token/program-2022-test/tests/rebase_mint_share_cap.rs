@@ -0,0 +1,150 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        instruction::InstructionError, pubkey::Pubkey, signature::Signer, signer::keypair::Keypair,
+        transaction::TransactionError, transport::TransportError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::rebase_mint::{instruction, DEFAULT_MAX_TOTAL_SHARES},
+        id,
+    },
+    spl_token_client::token::TokenError as TokenClientError,
+};
+
+async fn init_mint(
+    context: &mut TestContext,
+    supply_authority: &Pubkey,
+    initial_supply: u64,
+    initial_shares: u64,
+    max_total_shares: u64,
+) -> Result<(), TokenClientError> {
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(supply_authority)
+                .initial_supply(initial_supply, initial_shares)
+                .allow_supply_decrease()
+                .max_total_shares(max_total_shares)
+                .build_unchecked()
+                .unwrap()
+        })
+        .await
+}
+
+fn cap_exceeded(instruction_index: u8) -> TokenClientError {
+    TokenClientError::Client(Box::new(TransportError::TransactionError(
+        TransactionError::InstructionError(
+            instruction_index,
+            InstructionError::Custom(TokenError::RebaseSharesCapExceeded as u32),
+        ),
+    )))
+}
+
+#[tokio::test]
+async fn initial_shares_at_cap() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    init_mint(
+        &mut context,
+        &supply_authority.pubkey(),
+        1_000,
+        3_000,
+        3_000,
+    )
+    .await
+    .unwrap();
+    let TokenContext { token, .. } = context.token_context.take().unwrap();
+
+    let config = token.get_rebase_mint_config().await.unwrap();
+    assert_eq!(u64::from(config.total_shares), 3_000);
+    assert_eq!(config.max_total_shares(), 3_000);
+}
+
+#[tokio::test]
+async fn fail_initial_shares_above_cap() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    let err = init_mint(
+        &mut context,
+        &supply_authority.pubkey(),
+        1_000,
+        3_001,
+        3_000,
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err, cap_exceeded(1));
+
+    // the default cap applies without one
+    let mut context = TestContext::new().await;
+    let err = init_mint(
+        &mut context,
+        &supply_authority.pubkey(),
+        1,
+        DEFAULT_MAX_TOTAL_SHARES + 1,
+        0,
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err, cap_exceeded(1));
+}
+
+#[tokio::test]
+async fn mint_up_to_cap() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    init_mint(&mut context, &supply_authority.pubkey(), 0, 0, 1_000)
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        ..
+    } = context.token_context.take().unwrap();
+
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+
+    // exactly at the cap
+    token
+        .mint_to(
+            &alice_account.pubkey(),
+            &mint_authority.pubkey(),
+            1_000,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    let config = token.get_rebase_mint_config().await.unwrap();
+    assert_eq!(u64::from(config.total_shares), 1_000);
+
+    // one share above
+    let err = token
+        .mint_to(
+            &alice_account.pubkey(),
+            &mint_authority.pubkey(),
+            1,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err, cap_exceeded(0));
+
+    // rebases keep the share count, so they still go through
+    token
+        .rebase_supply(&supply_authority.pubkey(), 2_000, &[&supply_authority])
+        .await
+        .unwrap();
+    let config = token.get_rebase_mint_config().await.unwrap();
+    assert_eq!(u64::from(config.total_shares), 1_000);
+}
@@ -0,0 +1,143 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        instruction::InstructionError, pubkey::Pubkey, signature::Signer, signer::keypair::Keypair,
+        transaction::TransactionError, transport::TransportError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{instruction, RebaseAccount},
+            BaseStateWithExtensions,
+        },
+        id, instruction as token_instruction,
+    },
+    spl_token_client::{
+        client::ProgramBanksClientProcessTransaction,
+        token::{Token, TokenError as TokenClientError},
+    },
+};
+
+const DECIMALS: u8 = 9;
+
+async fn get_shares(token: &Token<ProgramBanksClientProcessTransaction>, account: &Pubkey) -> u64 {
+    let state = token.get_account_info(account).await.unwrap();
+    u64::from(state.get_extension::<RebaseAccount>().unwrap().shares)
+}
+
+#[tokio::test]
+async fn rebase_between_build_and_execute() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        bob,
+        ..
+    } = context.token_context.take().unwrap();
+    let mut accounts = vec![];
+    for owner in [&alice, &bob] {
+        let account = Keypair::new();
+        token
+            .create_auxiliary_token_account(&account, &owner.pubkey())
+            .await
+            .unwrap();
+        accounts.push(account.pubkey());
+    }
+    let (alice_account, bob_account) = (accounts[0], accounts[1]);
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            1_000,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    // at 1:1, 100 tokens are expected to move 100 shares exactly
+    let guarded = instruction::transfer_checked_with_share_slippage(
+        &id(),
+        &alice_account,
+        token.get_address(),
+        &bob_account,
+        &alice.pubkey(),
+        &[],
+        100,
+        DECIMALS,
+        100,
+        0,
+    )
+    .unwrap();
+    let unguarded = token_instruction::transfer_checked(
+        &id(),
+        &alice_account,
+        token.get_address(),
+        &bob_account,
+        &alice.pubkey(),
+        &[],
+        100,
+        DECIMALS,
+    )
+    .unwrap();
+
+    // a rebase lands before the transfers execute
+    token
+        .rebase_supply(&supply_authority.pubkey(), 2_000, &[&supply_authority])
+        .await
+        .unwrap();
+
+    let err = token.process_ixs(&[guarded], &[&alice]).await.unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::ShareSlippageExceeded as u32)
+            )
+        )))
+    );
+    assert_eq!(get_shares(&token, &alice_account).await, 1_000);
+
+    token.process_ixs(&[unguarded], &[&alice]).await.unwrap();
+    assert_eq!(get_shares(&token, &alice_account).await, 950);
+    assert_eq!(get_shares(&token, &bob_account).await, 50);
+
+    // a small rebase stays within a 2% tolerance
+    let guarded = instruction::transfer_checked_with_share_slippage(
+        &id(),
+        &alice_account,
+        token.get_address(),
+        &bob_account,
+        &alice.pubkey(),
+        &[],
+        100,
+        DECIMALS,
+        50,
+        200,
+    )
+    .unwrap();
+    token
+        .rebase_supply(&supply_authority.pubkey(), 2_020, &[&supply_authority])
+        .await
+        .unwrap();
+    token.process_ixs(&[guarded], &[&alice]).await.unwrap();
+    assert_eq!(get_shares(&token, &alice_account).await, 901);
+    assert_eq!(get_shares(&token, &bob_account).await, 99);
+}
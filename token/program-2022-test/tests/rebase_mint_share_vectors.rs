@@ -0,0 +1,198 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    serde_json::Value,
+    solana_program_test::{tokio, BanksClientError},
+    solana_sdk::{
+        decode_error::DecodeError, instruction::InstructionError, pubkey::Pubkey,
+        signature::Signer, signer::keypair::Keypair, transaction::TransactionError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{instruction, RebaseAccount, RebaseMintConfig},
+            BaseStateWithExtensions,
+        },
+        id,
+    },
+    spl_token_client::{
+        client::ProgramBanksClientProcessTransaction,
+        token::{Token, TokenError as TokenClientError},
+    },
+};
+
+// The vectors and their format are documented in the fixtures directory of
+// the program
+const VECTORS: &str = include_str!("../../program-2022/tests/fixtures/rebase_share_vectors.json");
+
+fn quantity(value: &Value) -> u64 {
+    value.as_str().unwrap().parse().unwrap()
+}
+
+fn error_name(error: TokenClientError) -> String {
+    match &error {
+        // the client already decodes the errors of rebases
+        TokenClientError::Rebase(error) => return format!("{:?}", error),
+        TokenClientError::Client(client_error) => {
+            if let Some(BanksClientError::TransactionError(TransactionError::InstructionError(
+                _,
+                InstructionError::Custom(code),
+            ))) = client_error.downcast_ref::<BanksClientError>()
+            {
+                let error: TokenError = TokenError::decode_custom_error_to_enum(*code).unwrap();
+                return format!("{:?}", error);
+            }
+        }
+        _ => {}
+    }
+    panic!("not a token error: {:?}", error);
+}
+
+async fn check_state(
+    token: &Token<ProgramBanksClientProcessTransaction>,
+    accounts: &[Pubkey],
+    expect: &Value,
+    context: &str,
+) {
+    let quantities = |value: &Value| {
+        value
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(quantity)
+            .collect::<Vec<_>>()
+    };
+    let mint = token.get_mint_info().await.unwrap();
+    let config = mint.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(
+        u64::from(config.total_supply),
+        quantity(&expect["totalSupply"]),
+        "{}",
+        context
+    );
+    assert_eq!(
+        u64::from(config.total_shares),
+        quantity(&expect["totalShares"]),
+        "{}",
+        context
+    );
+    assert_eq!(
+        mint.base.supply,
+        quantity(&expect["mintSupply"]),
+        "{}",
+        context
+    );
+
+    let mut shares = vec![];
+    let mut balances = vec![];
+    for account in accounts {
+        let state = token.get_account_info(account).await.unwrap();
+        shares.push(u64::from(
+            state.get_extension::<RebaseAccount>().unwrap().shares,
+        ));
+        balances.push(token.get_rebased_balance(account).await.unwrap());
+    }
+    assert_eq!(shares, quantities(&expect["shares"]), "{}", context);
+    assert_eq!(balances, quantities(&expect["balances"]), "{}", context);
+}
+
+async fn replay_scenario(scenario: &Value) {
+    let name = scenario["name"].as_str().unwrap();
+    let initial_supply = quantity(&scenario["initialSupply"]);
+    let virtual_shares_offset = quantity(&scenario["virtualSharesOffset"]);
+    let max_total_shares = quantity(&scenario["maxTotalShares"]);
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .initial_supply(initial_supply, 0)
+                .virtual_shares_offset(virtual_shares_offset)
+                .allow_supply_decrease()
+                .max_total_shares(max_total_shares)
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        ..
+    } = context.token_context.take().unwrap();
+
+    // alice owns every account, so that she can burn from any of them
+    let mut accounts = vec![];
+    for _ in 0..scenario["accounts"].as_u64().unwrap() {
+        let account = Keypair::new();
+        token
+            .create_auxiliary_token_account(&account, &alice.pubkey())
+            .await
+            .unwrap();
+        accounts.push(account.pubkey());
+    }
+
+    for (index, step) in scenario["steps"].as_array().unwrap().iter().enumerate() {
+        let context = format!("{}, step {}", name, index);
+        let account = || accounts[step["account"].as_u64().unwrap() as usize];
+        // some steps repeat an earlier one exactly
+        token.get_new_latest_blockhash().await.unwrap();
+        let result = match step["op"].as_str().unwrap() {
+            "mint" => {
+                token
+                    .mint_to(
+                        &account(),
+                        &mint_authority.pubkey(),
+                        quantity(&step["amount"]),
+                        &[&mint_authority],
+                    )
+                    .await
+            }
+            "burn" => {
+                token
+                    .burn(
+                        &account(),
+                        &alice.pubkey(),
+                        quantity(&step["amount"]),
+                        &[&alice],
+                    )
+                    .await
+            }
+            "rebase" => {
+                token
+                    .rebase_supply(
+                        &supply_authority.pubkey(),
+                        quantity(&step["newSupply"]),
+                        &[&supply_authority],
+                    )
+                    .await
+            }
+            op => panic!("unknown op {}", op),
+        };
+        if let Some(expect) = step.get("expect") {
+            result.unwrap_or_else(|e| panic!("{}: {:?}", context, e));
+            check_state(&token, &accounts, expect, &context).await;
+        } else {
+            assert_eq!(
+                error_name(result.unwrap_err()),
+                step["expectError"].as_str().unwrap(),
+                "{}",
+                context
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn replay_share_vectors() {
+    let vectors: Value = serde_json::from_str(VECTORS).unwrap();
+    assert_eq!(vectors["version"], 2);
+    for scenario in vectors["scenarios"].as_array().unwrap() {
+        replay_scenario(scenario).await;
+    }
+}
@@ -0,0 +1,317 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        instruction::InstructionError, pubkey::Pubkey, signature::Signer, signer::keypair::Keypair,
+        transaction::TransactionError, transport::TransportError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{instruction, RebaseAccount, RebaseMintConfig},
+            BaseStateWithExtensions,
+        },
+        id,
+    },
+    spl_token_client::{
+        client::ProgramBanksClientProcessTransaction,
+        token::{Token, TokenError as TokenClientError},
+    },
+};
+
+enum Op {
+    Mint(usize, u64),
+    Burn(usize, u64),
+    Rebase(u64),
+}
+
+async fn get_shares(token: &Token<ProgramBanksClientProcessTransaction>, account: &Pubkey) -> u64 {
+    let state = token.get_account_info(account).await.unwrap();
+    u64::from(state.get_extension::<RebaseAccount>().unwrap().shares)
+}
+
+/// Returns the config's total supply and total shares, and the base supply
+async fn get_totals(token: &Token<ProgramBanksClientProcessTransaction>) -> (u64, u64, u64) {
+    let mint = token.get_mint_info().await.unwrap();
+    let config = mint.get_extension::<RebaseMintConfig>().unwrap();
+    (
+        u64::from(config.total_supply),
+        u64::from(config.total_shares),
+        mint.base.supply,
+    )
+}
+
+#[tokio::test]
+async fn shares_are_conserved() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        bob,
+        ..
+    } = context.token_context.take().unwrap();
+    let owners = [&alice, &bob];
+    let mut accounts = vec![];
+    for owner in owners {
+        let account = Keypair::new();
+        token
+            .create_auxiliary_token_account(&account, &owner.pubkey())
+            .await
+            .unwrap();
+        accounts.push(account.pubkey());
+    }
+
+    // the base supply doesn't follow rebases, so they only go down here to
+    // leave enough of it for the burns
+    let ops = [
+        Op::Mint(0, 1_000),
+        Op::Mint(1, 333),
+        Op::Rebase(1_000),
+        Op::Mint(0, 7),
+        Op::Burn(1, 100),
+        Op::Rebase(900),
+        Op::Burn(0, 1_006),
+        Op::Mint(1, 1),
+        Op::Rebase(470),
+        Op::Burn(1, 234),
+        Op::Burn(0, 1),
+    ];
+    for op in ops {
+        let (old_supply, _, old_base_supply) = get_totals(&token).await;
+        match op {
+            Op::Mint(index, shares) => {
+                token
+                    .process_ixs(
+                        &[instruction::mint_to_shares(
+                            &id(),
+                            token.get_address(),
+                            &accounts[index],
+                            &mint_authority.pubkey(),
+                            &[],
+                            shares,
+                        )
+                        .unwrap()],
+                        &[&mint_authority],
+                    )
+                    .await
+                    .unwrap();
+            }
+            Op::Burn(index, shares) => {
+                token
+                    .process_ixs(
+                        &[instruction::burn_shares(
+                            &id(),
+                            &accounts[index],
+                            token.get_address(),
+                            &owners[index].pubkey(),
+                            &[],
+                            shares,
+                        )
+                        .unwrap()],
+                        &[owners[index]],
+                    )
+                    .await
+                    .unwrap();
+            }
+            Op::Rebase(new_supply) => {
+                token
+                    .rebase_supply(&supply_authority.pubkey(), new_supply, &[&supply_authority])
+                    .await
+                    .unwrap();
+            }
+        }
+
+        let (total_supply, total_shares, base_supply) = get_totals(&token).await;
+        let mut held_shares = 0;
+        for account in &accounts {
+            held_shares += get_shares(&token, account).await;
+        }
+        assert_eq!(held_shares, total_shares);
+        if !matches!(op, Op::Rebase(_)) {
+            assert_eq!(
+                total_supply.abs_diff(old_supply),
+                base_supply.abs_diff(old_base_supply)
+            );
+        }
+    }
+    assert_eq!(get_totals(&token).await.1, 0);
+}
+
+#[tokio::test]
+async fn share_mint_keeps_ratio() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .initial_supply(1_000, 0)
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        ..
+    } = context.token_context.take().unwrap();
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    let alice_account = alice_account.pubkey();
+    token
+        .rebase_supply(&supply_authority.pubkey(), 1_500, &[&supply_authority])
+        .await
+        .unwrap();
+
+    // 1 share is worth 1.5 tokens, so minting it adds 2 tokens to the supply
+    token
+        .process_ixs(
+            &[instruction::mint_to_shares(
+                &id(),
+                token.get_address(),
+                &alice_account,
+                &mint_authority.pubkey(),
+                &[],
+                1,
+            )
+            .unwrap()],
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    assert_eq!(get_shares(&token, &alice_account).await, 1);
+    assert_eq!(get_totals(&token).await, (1_502, 1_001, 2));
+
+    // and burning it only removes 1 token
+    token
+        .process_ixs(
+            &[instruction::burn_shares(
+                &id(),
+                &alice_account,
+                token.get_address(),
+                &alice.pubkey(),
+                &[],
+                1,
+            )
+            .unwrap()],
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    assert_eq!(get_shares(&token, &alice_account).await, 0);
+    assert_eq!(get_totals(&token).await, (1_501, 1_000, 1));
+
+    // only the mint authority can mint shares
+    let err = token
+        .process_ixs(
+            &[instruction::mint_to_shares(
+                &id(),
+                token.get_address(),
+                &alice_account,
+                &alice.pubkey(),
+                &[],
+                1,
+            )
+            .unwrap()],
+            &[&alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::OwnerMismatch as u32)
+            )
+        )))
+    );
+
+    // and only shares held can be burned
+    let err = token
+        .process_ixs(
+            &[instruction::burn_shares(
+                &id(),
+                &alice_account,
+                token.get_address(),
+                &alice.pubkey(),
+                &[],
+                1,
+            )
+            .unwrap()],
+            &[&alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::InsufficientFunds as u32)
+            )
+        )))
+    );
+}
+
+#[tokio::test]
+async fn fail_without_rebase_mint() {
+    let mut context = TestContext::new().await;
+    context.init_token_with_mint(vec![]).await.unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        ..
+    } = context.token_context.take().unwrap();
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+
+    let err = token
+        .process_ixs(
+            &[instruction::mint_to_shares(
+                &id(),
+                token.get_address(),
+                &alice_account.pubkey(),
+                &mint_authority.pubkey(),
+                &[],
+                1,
+            )
+            .unwrap()],
+            &[&mint_authority],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+        )))
+    );
+}
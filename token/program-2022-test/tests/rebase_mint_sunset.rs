@@ -0,0 +1,189 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        instruction::InstructionError, pubkey::Pubkey, signature::Signer, signer::keypair::Keypair,
+        transaction::TransactionError, transport::TransportError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{instruction, RebaseMintConfig},
+            BaseStateWithExtensions,
+        },
+        id,
+    },
+    spl_token_client::token::{ExtensionInitializationParams, TokenError as TokenClientError},
+};
+
+async fn setup(
+    supply_authority: &Pubkey,
+    close_authority: &Pubkey,
+    allow_sunset: bool,
+) -> TestContext {
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint_and_extensions(
+            vec![ExtensionInitializationParams::MintCloseAuthority {
+                close_authority: Some(*close_authority),
+            }],
+            |mint| {
+                let builder = instruction::InitializeRebaseMintBuilder::new(&id())
+                    .mint(mint)
+                    .supply_authority(supply_authority)
+                    .allow_supply_decrease();
+                if allow_sunset {
+                    builder.allow_sunset()
+                } else {
+                    builder
+                }
+                .build()
+                .unwrap()
+            },
+        )
+        .await
+        .unwrap();
+    context
+}
+
+fn token_error(error: TokenError) -> TokenClientError {
+    TokenClientError::Client(Box::new(TransportError::TransactionError(
+        TransactionError::InstructionError(0, InstructionError::Custom(error as u32)),
+    )))
+}
+
+#[tokio::test]
+async fn sunset_and_close() {
+    let supply_authority = Keypair::new();
+    let close_authority = Keypair::new();
+    let mut context = setup(&supply_authority.pubkey(), &close_authority.pubkey(), true).await;
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        bob,
+        ..
+    } = context.token_context.take().unwrap();
+
+    let mut accounts = vec![];
+    for (owner, amount) in [(&alice, 1_000), (&bob, 3_000)] {
+        let account = Keypair::new();
+        token
+            .create_auxiliary_token_account(&account, &owner.pubkey())
+            .await
+            .unwrap();
+        token
+            .mint_to(
+                &account.pubkey(),
+                &mint_authority.pubkey(),
+                amount,
+                &[&mint_authority],
+            )
+            .await
+            .unwrap();
+        accounts.push(account.pubkey());
+    }
+    token
+        .rebase_supply(&supply_authority.pubkey(), 6_000, &[&supply_authority])
+        .await
+        .unwrap();
+    assert_eq!(
+        token.get_rebased_balance(&accounts[0]).await.unwrap(),
+        1_500
+    );
+
+    token
+        .sunset_rebase(&supply_authority.pubkey(), &[&supply_authority])
+        .await
+        .unwrap();
+
+    // holders keep their shares, which are worth nothing
+    for account in &accounts {
+        assert_eq!(token.get_rebased_balance(account).await.unwrap(), 0);
+    }
+    let state = token.get_mint_info().await.unwrap();
+    assert_eq!(state.base.supply, 0);
+    let config = state.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(u64::from(config.total_supply), 0);
+    assert_eq!(u64::from(config.total_shares), 0);
+    assert!(bool::from(config.is_sunset));
+    assert_eq!(Option::<Pubkey>::from(config.supply_authority), None);
+
+    // the supply is frozen for good
+    let error = token
+        .rebase_supply(&supply_authority.pubkey(), 6_000, &[&supply_authority])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Rebase(TokenError::RebaseMintSunset)
+    );
+    let error = token
+        .mint_to(
+            &accounts[0],
+            &mint_authority.pubkey(),
+            1,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(error, token_error(TokenError::RebaseMintSunset));
+    let error = token
+        .sunset_rebase(&supply_authority.pubkey(), &[&supply_authority])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Rebase(TokenError::RebaseMintSunset)
+    );
+
+    let destination = Pubkey::new_unique();
+    token
+        .close_account(
+            token.get_address(),
+            &destination,
+            &close_authority.pubkey(),
+            &[&close_authority],
+        )
+        .await
+        .unwrap();
+    assert!(token.get_mint_info().await.is_err());
+}
+
+#[tokio::test]
+async fn fail_sunset_without_opt_in() {
+    let supply_authority = Keypair::new();
+    let close_authority = Keypair::new();
+    let mut context = setup(&supply_authority.pubkey(), &close_authority.pubkey(), false).await;
+    let TokenContext { token, .. } = context.token_context.take().unwrap();
+
+    let error = token
+        .sunset_rebase(&supply_authority.pubkey(), &[&supply_authority])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        TokenClientError::Rebase(TokenError::RebaseSunsetNotAllowed)
+    );
+}
+
+#[tokio::test]
+async fn fail_sunset_by_other_signer() {
+    let supply_authority = Keypair::new();
+    let close_authority = Keypair::new();
+    let mut context = setup(&supply_authority.pubkey(), &close_authority.pubkey(), true).await;
+    let TokenContext {
+        token,
+        mint_authority,
+        ..
+    } = context.token_context.take().unwrap();
+
+    let error = token
+        .sunset_rebase(&mint_authority.pubkey(), &[&mint_authority])
+        .await
+        .unwrap_err();
+    assert_eq!(error, token_error(TokenError::OwnerMismatch));
+}
@@ -0,0 +1,129 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        instruction::InstructionError, signature::Signer, signer::keypair::Keypair,
+        transaction::TransactionError, transport::TransportError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{instruction, RebaseMintConfig},
+            BaseStateWithExtensions,
+        },
+        id,
+    },
+    spl_token_client::{
+        client::ProgramBanksClientProcessTransaction,
+        token::{Token, TokenError as TokenClientError},
+    },
+};
+
+/// Returns the config's total supply and total shares, and the base supply
+async fn get_totals(token: &Token<ProgramBanksClientProcessTransaction>) -> (u64, u64, u64) {
+    let mint = token.get_mint_info().await.unwrap();
+    let config = mint.get_extension::<RebaseMintConfig>().unwrap();
+    (
+        u64::from(config.total_supply),
+        u64::from(config.total_shares),
+        mint.base.supply,
+    )
+}
+
+#[tokio::test]
+async fn mint_and_burn_follow_ratio() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        bob,
+        ..
+    } = context.token_context.take().unwrap();
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    let alice_account = alice_account.pubkey();
+    let bob_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&bob_account, &bob.pubkey())
+        .await
+        .unwrap();
+    let bob_account = bob_account.pubkey();
+
+    // the first mint issues shares 1:1
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            1_000,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    assert_eq!(get_totals(&token).await, (1_000, 1_000, 1_000));
+
+    // minting after a rebase issues shares at the new ratio
+    token
+        .rebase_supply(&supply_authority.pubkey(), 3_000, &[&supply_authority])
+        .await
+        .unwrap();
+    token
+        .mint_to(
+            &bob_account,
+            &mint_authority.pubkey(),
+            300,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    assert_eq!(get_totals(&token).await, (3_300, 1_100, 1_300));
+
+    // 10 tokens are worth 3.33 shares, so burning them retires 4
+    token
+        .burn(&alice_account, &alice.pubkey(), 10, &[&alice])
+        .await
+        .unwrap();
+    assert_eq!(get_totals(&token).await, (3_290, 1_096, 1_290));
+
+    // alice is left with 996 shares, worth 996 tokens at 1:1
+    token
+        .rebase_supply(&supply_authority.pubkey(), 1_096, &[&supply_authority])
+        .await
+        .unwrap();
+    let err = token
+        .burn(&alice_account, &alice.pubkey(), 997, &[&alice])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::InsufficientFunds as u32)
+            )
+        )))
+    );
+    token
+        .burn(&alice_account, &alice.pubkey(), 996, &[&alice])
+        .await
+        .unwrap();
+    assert_eq!(get_totals(&token).await, (100, 100, 294));
+}
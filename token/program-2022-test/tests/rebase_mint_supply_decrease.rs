@@ -0,0 +1,88 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{signature::Signer, signer::keypair::Keypair},
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{instruction, RebaseMintConfig},
+            BaseStateWithExtensions,
+        },
+        id,
+    },
+    spl_token_client::token::TokenError as TokenClientError,
+};
+
+#[tokio::test]
+async fn supply_only_goes_up() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .initial_supply(1_000, 0)
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext { token, .. } = context.token_context.take().unwrap();
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<RebaseMintConfig>().unwrap();
+    assert!(!bool::from(extension.allow_supply_decrease));
+
+    let err = token
+        .rebase_supply(&supply_authority.pubkey(), 999, &[&supply_authority])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Rebase(TokenError::SupplyDecreaseForbidden)
+    );
+
+    // rebasing to the same supply is a no-op
+    token
+        .rebase_supply(&supply_authority.pubkey(), 1_000, &[&supply_authority])
+        .await
+        .unwrap();
+
+    token
+        .rebase_supply(&supply_authority.pubkey(), 1_500, &[&supply_authority])
+        .await
+        .unwrap();
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(u64::from(extension.total_supply), 1_500);
+    assert_eq!(u64::from(extension.total_shares), 1_000);
+}
+
+#[tokio::test]
+async fn fail_rebase_to_zero_with_shares() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .initial_supply(1_000, 0)
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext { token, .. } = context.token_context.take().unwrap();
+
+    // none of the shares are outstanding, but they would still be worthless
+    let err = token
+        .rebase_supply(&supply_authority.pubkey(), 0, &[&supply_authority])
+        .await
+        .unwrap_err();
+    assert_eq!(err, TokenClientError::Rebase(TokenError::RebaseSupplyZero));
+}
@@ -0,0 +1,105 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        instruction::InstructionError, signature::Signer, signer::keypair::Keypair,
+        transaction::TransactionError, transport::TransportError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{instruction, RebaseMintConfig},
+            BaseStateWithExtensions,
+        },
+        id,
+    },
+    spl_token_client::{
+        client::ProgramBanksClientProcessTransaction,
+        token::{Token, TokenError as TokenClientError},
+    },
+};
+
+async fn get_config(token: &Token<ProgramBanksClientProcessTransaction>) -> RebaseMintConfig {
+    let mint = token.get_mint_info().await.unwrap();
+    *mint.get_extension::<RebaseMintConfig>().unwrap()
+}
+
+#[tokio::test]
+async fn sync_supply_from_mint() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .initial_supply(1_000, 0)
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        ..
+    } = context.token_context.take().unwrap();
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    let alice_account = alice_account.pubkey();
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            400,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    let config = get_config(&token).await;
+    assert_eq!(u64::from(config.total_supply), 1_400);
+    assert_eq!(u64::from(config.total_shares), 1_400);
+
+    // anyone can sync, dropping the initial supply that was never minted
+    let sync_ix = instruction::sync_supply_from_mint(&id(), token.get_address()).unwrap();
+    token
+        .process_ixs(&[sync_ix.clone()], &[] as &[&dyn Signer; 0])
+        .await
+        .unwrap();
+    let config = get_config(&token).await;
+    assert_eq!(u64::from(config.total_supply), 400);
+    assert_eq!(u64::from(config.total_shares), 400);
+    assert_eq!(config.shares_to_amount(400), Some(400));
+
+    // after a rebase, syncing would undo it
+    token
+        .rebase_supply(&supply_authority.pubkey(), 800, &[&supply_authority])
+        .await
+        .unwrap();
+    // the same transaction as the first sync would be a duplicate
+    token.get_new_latest_blockhash().await.unwrap();
+    let err = token
+        .process_ixs(&[sync_ix], &[] as &[&dyn Signer; 0])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::RebaseSupplySyncNotAllowed as u32)
+            )
+        )))
+    );
+    let config = get_config(&token).await;
+    assert_eq!(u64::from(config.total_supply), 800);
+    assert_eq!(config.shares_to_amount(400), Some(800));
+}
@@ -0,0 +1,157 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{pubkey::Pubkey, signature::Signer, signer::keypair::Keypair},
+    spl_token_2022::{extension::rebase_mint::instruction, id},
+    spl_token_client::{client::ProgramBanksClientProcessTransaction, token::Token},
+};
+
+async fn setup(supply_authority: &Pubkey, sync_base_supply: bool) -> TestContext {
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            let builder = instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(supply_authority)
+                .allow_supply_decrease();
+            if sync_base_supply {
+                builder.sync_base_supply()
+            } else {
+                builder
+            }
+            .build()
+            .unwrap()
+        })
+        .await
+        .unwrap();
+    context
+}
+
+/// The supply as `getTokenSupply` reports it, read from the base mint, and
+/// the rebased total supply
+async fn get_supplies(token: &Token<ProgramBanksClientProcessTransaction>) -> (u64, u64) {
+    let mint = token.get_mint_info().await.unwrap();
+    let config = token.get_rebase_mint_config().await.unwrap();
+    (mint.base.supply, config.total_supply.into())
+}
+
+#[tokio::test]
+async fn base_supply_follows_rebases() {
+    let supply_authority = Keypair::new();
+    let mut context = setup(&supply_authority.pubkey(), true).await;
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        bob,
+        ..
+    } = context.token_context.take().unwrap();
+    let mut accounts = vec![];
+    for owner in [&alice, &bob] {
+        let account = Keypair::new();
+        token
+            .create_auxiliary_token_account(&account, &owner.pubkey())
+            .await
+            .unwrap();
+        accounts.push(account.pubkey());
+    }
+    let (alice_account, bob_account) = (accounts[0], accounts[1]);
+    let config = token.get_rebase_mint_config().await.unwrap();
+    assert!(bool::from(config.sync_base_supply));
+
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            1_000,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    assert_eq!(get_supplies(&token).await, (1_000, 1_000));
+
+    token
+        .rebase_supply(&supply_authority.pubkey(), 1_500, &[&supply_authority])
+        .await
+        .unwrap();
+    assert_eq!(get_supplies(&token).await, (1_500, 1_500));
+
+    // mints and burns move both supplies by the same amount
+    token
+        .mint_to(
+            &bob_account,
+            &mint_authority.pubkey(),
+            300,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    assert_eq!(get_supplies(&token).await, (1_800, 1_800));
+    token
+        .burn(&alice_account, &alice.pubkey(), 600, &[&alice])
+        .await
+        .unwrap();
+    assert_eq!(get_supplies(&token).await, (1_200, 1_200));
+
+    // the balances left add up to the synced supply
+    token
+        .rebase_supply(&supply_authority.pubkey(), 600, &[&supply_authority])
+        .await
+        .unwrap();
+    assert_eq!(get_supplies(&token).await, (600, 600));
+    assert_eq!(
+        token.get_rebased_balance(&alice_account).await.unwrap()
+            + token.get_rebased_balance(&bob_account).await.unwrap(),
+        600
+    );
+
+    // so a holder can burn everything after a rebase
+    let alice_balance = token.get_rebased_balance(&alice_account).await.unwrap();
+    token
+        .burn(&alice_account, &alice.pubkey(), alice_balance, &[&alice])
+        .await
+        .unwrap();
+    assert_eq!(get_supplies(&token).await, (150, 150));
+    token
+        .process_ixs(
+            &[instruction::assert_rebase_consistency(&id(), token.get_address()).unwrap()],
+            &[] as &[&dyn Signer; 0],
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn base_supply_untouched_by_default() {
+    let supply_authority = Keypair::new();
+    let mut context = setup(&supply_authority.pubkey(), false).await;
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        ..
+    } = context.token_context.take().unwrap();
+    let account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&account, &alice.pubkey())
+        .await
+        .unwrap();
+    token
+        .mint_to(
+            &account.pubkey(),
+            &mint_authority.pubkey(),
+            1_000,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    token
+        .rebase_supply(&supply_authority.pubkey(), 1_500, &[&supply_authority])
+        .await
+        .unwrap();
+    assert_eq!(get_supplies(&token).await, (1_000, 1_500));
+}
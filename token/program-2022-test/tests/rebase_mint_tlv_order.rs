@@ -0,0 +1,176 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{keypair_clone, TestContext},
+    solana_program_test::tokio,
+    solana_sdk::{pubkey::Pubkey, signature::Signer, signer::keypair::Keypair, system_instruction},
+    spl_token_2022::{
+        extension::{
+            metadata_pointer::MetadataPointer,
+            mint_close_authority::MintCloseAuthority,
+            rebase_mint::{instruction, RebaseMintConfig},
+            transfer_fee::TransferFeeConfig,
+            BaseStateWithExtensions, Extension, ExtensionType, StateWithExtensionsOwned,
+        },
+        id,
+        instruction::initialize_mint,
+        state::Mint,
+    },
+    spl_token_client::{
+        client::{ProgramBanksClient, ProgramBanksClientProcessTransaction, ProgramClient},
+        token::{ExtensionInitializationParams, Token},
+    },
+    spl_token_metadata_interface::state::TokenMetadata,
+    std::sync::Arc,
+};
+
+const PERMUTATIONS: [[usize; 3]; 6] = [
+    [0, 1, 2],
+    [0, 2, 1],
+    [1, 0, 2],
+    [1, 2, 0],
+    [2, 0, 1],
+    [2, 1, 0],
+];
+
+fn neighbor_bytes(state: &StateWithExtensionsOwned<Mint>) -> Vec<Vec<u8>> {
+    fn bytes<V: Extension>(state: &StateWithExtensionsOwned<Mint>) -> Vec<u8> {
+        state.get_extension_bytes::<V>().unwrap().to_vec()
+    }
+    vec![
+        bytes::<TransferFeeConfig>(state),
+        bytes::<MetadataPointer>(state),
+        bytes::<MintCloseAuthority>(state),
+        bytes::<TokenMetadata>(state),
+    ]
+}
+
+#[tokio::test]
+async fn rebase_in_every_tlv_position() {
+    let context = TestContext::new().await;
+    let payer = keypair_clone(&context.context.lock().await.payer);
+    let client: Arc<dyn ProgramClient<ProgramBanksClientProcessTransaction>> =
+        Arc::new(ProgramBanksClient::new_from_context(
+            Arc::clone(&context.context),
+            ProgramBanksClientProcessTransaction,
+        ));
+    let rent = context
+        .context
+        .lock()
+        .await
+        .banks_client
+        .get_rent()
+        .await
+        .unwrap();
+    let supply_authority = Keypair::new();
+    let mint_authority = Keypair::new();
+    let authority = Pubkey::new_unique();
+
+    for permutation in PERMUTATIONS {
+        for rebase_position in 0..=permutation.len() {
+            let mint = Keypair::new();
+            let token = Token::new(
+                Arc::clone(&client),
+                &id(),
+                &mint.pubkey(),
+                Some(9),
+                Arc::new(keypair_clone(&payer)),
+            );
+            let params = [
+                ExtensionInitializationParams::TransferFeeConfig {
+                    transfer_fee_config_authority: Some(authority),
+                    withdraw_withheld_authority: Some(authority),
+                    transfer_fee_basis_points: 100,
+                    maximum_fee: 1_000,
+                },
+                ExtensionInitializationParams::MetadataPointer {
+                    authority: Some(authority),
+                    metadata_address: Some(mint.pubkey()),
+                },
+                ExtensionInitializationParams::MintCloseAuthority {
+                    close_authority: Some(authority),
+                },
+            ];
+            let mut extension_types = vec![];
+            let mut extension_instructions = vec![];
+            for index in permutation {
+                extension_types.push(params[index].extension());
+                extension_instructions.push(
+                    params[index]
+                        .clone()
+                        .instruction(&id(), &mint.pubkey())
+                        .unwrap(),
+                );
+            }
+            extension_types.insert(rebase_position, ExtensionType::RebaseMintConfig);
+            extension_instructions.insert(
+                rebase_position,
+                instruction::InitializeRebaseMintBuilder::new(&id())
+                    .mint(&mint.pubkey())
+                    .supply_authority(&supply_authority.pubkey())
+                    .initial_supply(1_000, 0)
+                    .allow_supply_decrease()
+                    .build()
+                    .unwrap(),
+            );
+
+            let space = ExtensionType::try_calculate_account_len::<Mint>(&extension_types).unwrap();
+            let mut instructions = vec![system_instruction::create_account(
+                &payer.pubkey(),
+                &mint.pubkey(),
+                rent.minimum_balance(space),
+                space as u64,
+                &id(),
+            )];
+            instructions.extend(extension_instructions);
+            instructions.push(
+                initialize_mint(&id(), &mint.pubkey(), &mint_authority.pubkey(), None, 9).unwrap(),
+            );
+            token.process_ixs(&instructions, &[&mint]).await.unwrap();
+
+            // the variable-length metadata goes after everything else
+            token
+                .token_metadata_initialize_with_rent_transfer(
+                    &payer.pubkey(),
+                    &authority,
+                    &mint_authority.pubkey(),
+                    "Rebasing".to_string(),
+                    "REB".to_string(),
+                    "rebasing.token.metadata".to_string(),
+                    &[&mint_authority],
+                )
+                .await
+                .unwrap();
+
+            let state = token.get_mint_info().await.unwrap();
+            let mut expected_types = extension_types.clone();
+            expected_types.push(ExtensionType::TokenMetadata);
+            assert_eq!(state.get_extension_types().unwrap(), expected_types);
+            let neighbors = neighbor_bytes(&state);
+
+            token
+                .rebase_supply(&supply_authority.pubkey(), 1_500, &[&supply_authority])
+                .await
+                .unwrap();
+            token
+                .rebase_supply(&supply_authority.pubkey(), 1_200, &[&supply_authority])
+                .await
+                .unwrap();
+
+            let state = token.get_mint_info().await.unwrap();
+            let extension = state.get_extension::<RebaseMintConfig>().unwrap();
+            assert_eq!(u64::from(extension.total_supply), 1_200);
+            assert_eq!(u64::from(extension.total_shares), 1_000);
+            assert!(bool::from(extension.has_rebased));
+            assert_eq!(state.get_extension_types().unwrap(), expected_types);
+            assert_eq!(
+                neighbor_bytes(&state),
+                neighbors,
+                "{:?} with rebase at {}",
+                permutation,
+                rebase_position
+            );
+        }
+    }
+}
@@ -0,0 +1,180 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{pubkey::Pubkey, signature::Signer, signer::keypair::Keypair},
+    spl_token_2022::{
+        extension::{
+            rebase_mint::{instruction, RebaseAccount, RebaseMintConfig},
+            transfer_fee::{TransferFeeAmount, TransferFeeConfig},
+            BaseStateWithExtensions,
+        },
+        id,
+    },
+    spl_token_client::{
+        client::ProgramBanksClientProcessTransaction,
+        token::{ExtensionInitializationParams, Token},
+    },
+};
+
+const TRANSFER_FEE_BASIS_POINTS: u16 = 100;
+
+async fn get_shares(token: &Token<ProgramBanksClientProcessTransaction>, account: &Pubkey) -> u64 {
+    let state = token.get_account_info(account).await.unwrap();
+    u64::from(state.get_extension::<RebaseAccount>().unwrap().shares)
+}
+
+async fn get_withheld(
+    token: &Token<ProgramBanksClientProcessTransaction>,
+    account: &Pubkey,
+) -> u64 {
+    let state = token.get_account_info(account).await.unwrap();
+    u64::from(
+        state
+            .get_extension::<TransferFeeAmount>()
+            .unwrap()
+            .withheld_amount,
+    )
+}
+
+#[tokio::test]
+async fn fees_keep_their_value_across_rebases() {
+    let supply_authority = Keypair::new();
+    let withdraw_withheld_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint_and_extensions(
+            vec![ExtensionInitializationParams::TransferFeeConfig {
+                transfer_fee_config_authority: None,
+                withdraw_withheld_authority: Some(withdraw_withheld_authority.pubkey()),
+                transfer_fee_basis_points: TRANSFER_FEE_BASIS_POINTS,
+                maximum_fee: u64::MAX,
+            }],
+            |mint| {
+                instruction::InitializeRebaseMintBuilder::new(&id())
+                    .mint(mint)
+                    .supply_authority(&supply_authority.pubkey())
+                    .allow_supply_decrease()
+                    .build()
+                    .unwrap()
+            },
+        )
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        bob,
+        ..
+    } = context.token_context.take().unwrap();
+    let mut accounts = vec![];
+    for owner in [&alice, &bob, &withdraw_withheld_authority] {
+        let account = Keypair::new();
+        token
+            .create_auxiliary_token_account(&account, &owner.pubkey())
+            .await
+            .unwrap();
+        accounts.push(account.pubkey());
+    }
+    let (alice_account, bob_account, fee_account) = (accounts[0], accounts[1], accounts[2]);
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            10_000,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    // at 1:1, a 1% fee on 1_000 tokens withholds 10 shares
+    token
+        .transfer_with_fee(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            1_000,
+            10,
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    assert_eq!(get_shares(&token, &alice_account).await, 9_000);
+    assert_eq!(get_shares(&token, &bob_account).await, 990);
+    assert_eq!(get_withheld(&token, &bob_account).await, 10);
+
+    // the supply doubles before the fee is withdrawn
+    token
+        .rebase_supply(&supply_authority.pubkey(), 20_000, &[&supply_authority])
+        .await
+        .unwrap();
+    token
+        .withdraw_withheld_tokens_from_accounts(
+            &fee_account,
+            &withdraw_withheld_authority.pubkey(),
+            &[&bob_account],
+            &[&withdraw_withheld_authority],
+        )
+        .await
+        .unwrap();
+    assert_eq!(get_withheld(&token, &bob_account).await, 0);
+    assert_eq!(get_shares(&token, &fee_account).await, 10);
+    let state = token.get_account_info(&fee_account).await.unwrap();
+    assert_eq!(state.base.amount, 20);
+
+    // at 2:1, a 1% fee on 1_000 tokens withholds 5 shares, and harvesting
+    // keeps them in shares
+    token
+        .transfer_with_fee(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            1_000,
+            10,
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    assert_eq!(get_shares(&token, &alice_account).await, 8_500);
+    assert_eq!(get_shares(&token, &bob_account).await, 1_485);
+    assert_eq!(get_withheld(&token, &bob_account).await, 5);
+    token
+        .harvest_withheld_tokens_to_mint(&[&bob_account])
+        .await
+        .unwrap();
+    let state = token.get_mint_info().await.unwrap();
+    assert_eq!(
+        u64::from(
+            state
+                .get_extension::<TransferFeeConfig>()
+                .unwrap()
+                .withheld_amount
+        ),
+        5
+    );
+
+    token
+        .rebase_supply(&supply_authority.pubkey(), 30_000, &[&supply_authority])
+        .await
+        .unwrap();
+    token
+        .withdraw_withheld_tokens_from_mint(
+            &fee_account,
+            &withdraw_withheld_authority.pubkey(),
+            &[&withdraw_withheld_authority],
+        )
+        .await
+        .unwrap();
+    assert_eq!(get_shares(&token, &fee_account).await, 15);
+    let state = token.get_account_info(&fee_account).await.unwrap();
+    assert_eq!(state.base.amount, 45);
+
+    // the fee shares never left the share count
+    let state = token.get_mint_info().await.unwrap();
+    let extension = state.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(u64::from(extension.total_shares), 10_000);
+    assert_eq!(u64::from(extension.outstanding_shares), 10_000);
+}
@@ -0,0 +1,384 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::{processor, tokio, ProgramTest},
+    solana_sdk::{
+        account::Account,
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        instruction::{AccountMeta, InstructionError},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+        signature::Signer,
+        signer::keypair::Keypair,
+        system_instruction,
+        transaction::TransactionError,
+        transport::TransportError,
+    },
+    spl_tlv_account_resolution::{account::ExtraAccountMeta, seeds::Seed},
+    spl_token_2022::{extension::rebase_mint::instruction, id, processor::Processor},
+    spl_token_client::token::{ExtensionInitializationParams, TokenError as TokenClientError},
+    spl_transfer_hook_example::{
+        error::ExampleError,
+        instruction::set_max_transfer,
+        state::{get_max_transfer_address, MAX_TRANSFER_LEN, MAX_TRANSFER_SEED},
+    },
+    spl_transfer_hook_interface::{
+        get_extra_account_metas_address, instruction::TransferHookInstruction,
+    },
+    std::sync::Arc,
+};
+
+/// Test program that records the amount it's executed with, conforms to
+/// transfer-hook-interface
+pub fn process_instruction_record_amount(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    input: &[u8],
+) -> ProgramResult {
+    let amount = match TransferHookInstruction::unpack(input)? {
+        TransferHookInstruction::Execute { amount } => amount,
+        _ => return Err(ProgramError::InvalidInstructionData),
+    };
+    // source, mint, destination, authority, validation state, then the record
+    let record_info = accounts.get(5).ok_or(ProgramError::NotEnoughAccountKeys)?;
+    record_info.data.borrow_mut()[..8].copy_from_slice(&amount.to_le_bytes());
+    Ok(())
+}
+
+async fn get_recorded_amount(context: &TestContext, record: &Pubkey) -> u64 {
+    let account = context
+        .context
+        .lock()
+        .await
+        .banks_client
+        .get_account(*record)
+        .await
+        .unwrap()
+        .unwrap();
+    u64::from_le_bytes(account.data[..8].try_into().unwrap())
+}
+
+#[tokio::test]
+async fn hook_sees_rebased_amount() {
+    let program_id = Pubkey::new_unique();
+    let record = Pubkey::new_unique();
+    let mut program_test = ProgramTest::default();
+    program_test.prefer_bpf(false);
+    program_test.add_program(
+        "spl_token_2022",
+        spl_token_2022::id(),
+        processor!(Processor::process),
+    );
+    program_test.add_program(
+        "my_transfer_hook",
+        program_id,
+        processor!(process_instruction_record_amount),
+    );
+    program_test.add_account(
+        record,
+        Account {
+            lamports: 1_000_000_000,
+            data: vec![0; 8],
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+    let context = program_test.start_with_context().await;
+    let mut context = TestContext {
+        context: Arc::new(tokio::sync::Mutex::new(context)),
+        token_context: None,
+    };
+
+    let supply_authority = Keypair::new();
+    context
+        .init_token_with_rebase_mint_and_extensions(
+            vec![ExtensionInitializationParams::TransferHook {
+                authority: None,
+                program_id: Some(program_id),
+            }],
+            |mint| {
+                instruction::InitializeRebaseMintBuilder::new(&id())
+                    .mint(mint)
+                    .supply_authority(&supply_authority.pubkey())
+                    .allow_supply_decrease()
+                    .build()
+                    .unwrap()
+            },
+        )
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        bob,
+        decimals,
+        ..
+    } = context.token_context.take().unwrap();
+
+    // the validation account depends on the mint, so it's added once known
+    let validation_address = get_extra_account_metas_address(token.get_address(), &program_id);
+    context.context.lock().await.set_account(
+        &validation_address,
+        &Account {
+            lamports: 1_000_000_000,
+            data: spl_transfer_hook_example::state::example_data(&[
+                ExtraAccountMeta::new_with_pubkey(&record, false, true).unwrap(),
+            ])
+            .unwrap(),
+            owner: program_id,
+            ..Account::default()
+        }
+        .into(),
+    );
+
+    let mut accounts = vec![];
+    for owner in [&alice, &bob] {
+        let account = Keypair::new();
+        token
+            .create_auxiliary_token_account(&account, &owner.pubkey())
+            .await
+            .unwrap();
+        accounts.push(account.pubkey());
+    }
+    let (alice_account, bob_account) = (accounts[0], accounts[1]);
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            1_000,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    token
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            100,
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    assert_eq!(get_recorded_amount(&context, &record).await, 100);
+
+    // after doubling the supply, the amount is what the user asked for
+    token
+        .rebase_supply(&supply_authority.pubkey(), 2_000, &[&supply_authority])
+        .await
+        .unwrap();
+    token
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            300,
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    assert_eq!(get_recorded_amount(&context, &record).await, 300);
+
+    // and a transfer of shares is given what the shares are worth
+    let mut transfer_instruction = instruction::transfer_shares_checked(
+        &id(),
+        &alice_account,
+        token.get_address(),
+        &bob_account,
+        &alice.pubkey(),
+        &[],
+        50,
+        decimals,
+    )
+    .unwrap();
+    transfer_instruction.accounts.extend([
+        AccountMeta::new(record, false),
+        AccountMeta::new_readonly(program_id, false),
+        AccountMeta::new_readonly(validation_address, false),
+    ]);
+    token
+        .process_ixs(&[transfer_instruction], &[&alice])
+        .await
+        .unwrap();
+    assert_eq!(get_recorded_amount(&context, &record).await, 100);
+}
+
+#[tokio::test]
+async fn example_hook_max_transfer_across_rebase() {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::default();
+    program_test.prefer_bpf(false);
+    program_test.add_program(
+        "spl_token_2022",
+        spl_token_2022::id(),
+        processor!(Processor::process),
+    );
+    program_test.add_program(
+        "spl_transfer_hook_example",
+        program_id,
+        processor!(spl_transfer_hook_example::processor::process),
+    );
+    let context = program_test.start_with_context().await;
+    let mut context = TestContext {
+        context: Arc::new(tokio::sync::Mutex::new(context)),
+        token_context: None,
+    };
+
+    let supply_authority = Keypair::new();
+    context
+        .init_token_with_rebase_mint_and_extensions(
+            vec![ExtensionInitializationParams::TransferHook {
+                authority: None,
+                program_id: Some(program_id),
+            }],
+            |mint| {
+                instruction::InitializeRebaseMintBuilder::new(&id())
+                    .mint(mint)
+                    .supply_authority(&supply_authority.pubkey())
+                    .allow_supply_decrease()
+                    .build()
+                    .unwrap()
+            },
+        )
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        bob,
+        ..
+    } = context.token_context.take().unwrap();
+
+    // the max transfer account is resolved from the mint, like the hook does
+    let validation_address = get_extra_account_metas_address(token.get_address(), &program_id);
+    context.context.lock().await.set_account(
+        &validation_address,
+        &Account {
+            lamports: 1_000_000_000,
+            data: spl_transfer_hook_example::state::example_data(&[
+                ExtraAccountMeta::new_with_seeds(
+                    &[
+                        Seed::Literal {
+                            bytes: MAX_TRANSFER_SEED.to_vec(),
+                        },
+                        Seed::AccountKey { index: 1 },
+                    ],
+                    false,
+                    false,
+                )
+                .unwrap(),
+            ])
+            .unwrap(),
+            owner: program_id,
+            ..Account::default()
+        }
+        .into(),
+    );
+
+    let max_transfer_address = get_max_transfer_address(token.get_address(), &program_id);
+    let (payer, lamports) = {
+        let mut context = context.context.lock().await;
+        let rent = context.banks_client.get_rent().await.unwrap();
+        (
+            context.payer.pubkey(),
+            rent.minimum_balance(MAX_TRANSFER_LEN),
+        )
+    };
+    token
+        .process_ixs(
+            &[
+                system_instruction::transfer(&payer, &max_transfer_address, lamports),
+                set_max_transfer(
+                    &program_id,
+                    &max_transfer_address,
+                    token.get_address(),
+                    &mint_authority.pubkey(),
+                    500,
+                ),
+            ],
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    let mut accounts = vec![];
+    for owner in [&alice, &bob] {
+        let account = Keypair::new();
+        token
+            .create_auxiliary_token_account(&account, &owner.pubkey())
+            .await
+            .unwrap();
+        accounts.push(account.pubkey());
+    }
+    let (alice_account, bob_account) = (accounts[0], accounts[1]);
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            1_000,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    token
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            500,
+            &[&alice],
+        )
+        .await
+        .unwrap();
+
+    // after doubling the supply, 501 tokens are only 250 shares, but the
+    // limit is in tokens
+    token
+        .rebase_supply(&supply_authority.pubkey(), 2_000, &[&supply_authority])
+        .await
+        .unwrap();
+    let err = token
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            501,
+            &[&alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(ExampleError::AmountExceedsMaxTransfer as u32)
+            )
+        )))
+    );
+    token
+        .transfer(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            500,
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        token.get_rebased_balance(&alice_account).await.unwrap(),
+        500
+    );
+    assert_eq!(
+        token.get_rebased_balance(&bob_account).await.unwrap(),
+        1_500
+    );
+}
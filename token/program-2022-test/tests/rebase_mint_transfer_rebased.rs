@@ -0,0 +1,293 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    async_trait::async_trait,
+    program_test::{keypair_clone, TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        account::Account,
+        hash::Hash,
+        instruction::InstructionError,
+        pubkey::Pubkey,
+        signature::{Signature, Signer},
+        signer::keypair::Keypair,
+        transaction::{Transaction, TransactionError},
+        transport::TransportError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{instruction, RebaseAccount},
+            BaseStateWithExtensions,
+        },
+        id,
+    },
+    spl_token_client::{
+        client::{
+            ProgramBanksClient, ProgramBanksClientProcessTransaction, ProgramClient,
+            ProgramClientResult, SimulateTransaction, TransactionLogs,
+        },
+        token::{Token, TokenError as TokenClientError},
+    },
+    std::sync::{Arc, Mutex},
+};
+
+/// Lands a queued transaction right before the next one it's asked to send,
+/// as if it had raced it to the cluster
+struct RacingClient {
+    inner: ProgramBanksClient<ProgramBanksClientProcessTransaction>,
+    queued: Mutex<Option<Transaction>>,
+}
+
+#[async_trait]
+impl ProgramClient<ProgramBanksClientProcessTransaction> for RacingClient {
+    async fn get_minimum_balance_for_rent_exemption(
+        &self,
+        data_len: usize,
+    ) -> ProgramClientResult<u64> {
+        self.inner
+            .get_minimum_balance_for_rent_exemption(data_len)
+            .await
+    }
+
+    async fn get_latest_blockhash(&self) -> ProgramClientResult<Hash> {
+        self.inner.get_latest_blockhash().await
+    }
+
+    async fn send_transaction(&self, transaction: &Transaction) -> ProgramClientResult<()> {
+        let queued = self.queued.lock().unwrap().take();
+        if let Some(queued) = queued {
+            self.inner.send_transaction(&queued).await?;
+        }
+        self.inner.send_transaction(transaction).await
+    }
+
+    async fn get_account(&self, address: Pubkey) -> ProgramClientResult<Option<Account>> {
+        self.inner.get_account(address).await
+    }
+
+    async fn simulate_transaction(
+        &self,
+        transaction: &Transaction,
+    ) -> ProgramClientResult<
+        <ProgramBanksClientProcessTransaction as SimulateTransaction>::SimulationOutput,
+    > {
+        self.inner.simulate_transaction(transaction).await
+    }
+
+    async fn get_transaction_logs(
+        &self,
+        address: Pubkey,
+        before: Option<Signature>,
+        limit: usize,
+    ) -> ProgramClientResult<Vec<TransactionLogs>> {
+        self.inner
+            .get_transaction_logs(address, before, limit)
+            .await
+    }
+}
+
+async fn get_shares(token: &Token<ProgramBanksClientProcessTransaction>, account: &Pubkey) -> u64 {
+    let state = token.get_account_info(account).await.unwrap();
+    state
+        .get_extension::<RebaseAccount>()
+        .unwrap()
+        .shares
+        .into()
+}
+
+#[tokio::test]
+async fn transfer_ui_amount_across_rebases() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        bob,
+        ..
+    } = context.token_context.take().unwrap();
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    let alice_account = alice_account.pubkey();
+    let bob_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&bob_account, &bob.pubkey())
+        .await
+        .unwrap();
+    let bob_account = bob_account.pubkey();
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            100_000_000_000,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    token
+        .transfer_rebased(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            "12.5",
+            0,
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    assert_eq!(get_shares(&token, &bob_account).await, 12_500_000_000);
+    assert_eq!(
+        token.get_rebased_ui_balance(&bob_account).await.unwrap(),
+        "12.5"
+    );
+
+    // after the supply doubles, the same amount takes half the shares
+    token
+        .rebase_supply(
+            &supply_authority.pubkey(),
+            200_000_000_000,
+            &[&supply_authority],
+        )
+        .await
+        .unwrap();
+    token
+        .transfer_rebased(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            "12.5",
+            0,
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    assert_eq!(get_shares(&token, &bob_account).await, 18_750_000_000);
+    assert_eq!(
+        token.get_rebased_ui_balance(&bob_account).await.unwrap(),
+        "37.5"
+    );
+
+    // amounts that don't parse never reach the program
+    for ui_amount in ["12.5.1", "", "abc", "0.0000000001"] {
+        let err = token
+            .transfer_rebased(
+                &alice_account,
+                &bob_account,
+                &alice.pubkey(),
+                ui_amount,
+                0,
+                &[&alice],
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(err, TokenClientError::InvalidUiAmount);
+    }
+
+    // other failures come from the program
+    let err = token
+        .transfer_rebased(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            "1000",
+            0,
+            &[&alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::InsufficientFunds as u32)
+            )
+        )))
+    );
+
+    // a rebase of 1% lands between the conversion and the transfer
+    let racing_client = Arc::new(RacingClient {
+        inner: ProgramBanksClient::new_from_context(
+            Arc::clone(&context.context),
+            ProgramBanksClientProcessTransaction,
+        ),
+        queued: Mutex::new(None),
+    });
+    let payer = keypair_clone(&context.context.lock().await.payer);
+    let racing_token = Token::new(
+        racing_client.clone(),
+        &id(),
+        token.get_address(),
+        None,
+        Arc::new(keypair_clone(&payer)),
+    );
+    let queue_rebase = |new_supply: u64, recent_blockhash: Hash| {
+        *racing_client.queued.lock().unwrap() = Some(Transaction::new_signed_with_payer(
+            &[instruction::update_supply(
+                &id(),
+                token.get_address(),
+                None,
+                &supply_authority.pubkey(),
+                &[],
+                new_supply,
+            )
+            .unwrap()],
+            Some(&payer.pubkey()),
+            &[&payer, &supply_authority],
+            recent_blockhash,
+        ));
+    };
+
+    // so 2.02 tokens, or 1.01 shares at the old ratio, take 1 share, which is
+    // 99 bps short
+    queue_rebase(
+        202_000_000_000,
+        token.get_new_latest_blockhash().await.unwrap(),
+    );
+    let err = racing_token
+        .transfer_rebased(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            "2.02",
+            50,
+            &[&alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err, TokenClientError::ShareSlippageExceeded);
+    assert_eq!(get_shares(&token, &bob_account).await, 18_750_000_000);
+
+    queue_rebase(
+        204_020_000_000,
+        token.get_new_latest_blockhash().await.unwrap(),
+    );
+    racing_token
+        .transfer_rebased(
+            &alice_account,
+            &bob_account,
+            &alice.pubkey(),
+            "2.0402",
+            100,
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    assert_eq!(get_shares(&token, &bob_account).await, 19_750_000_000);
+}
@@ -0,0 +1,245 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        instruction::InstructionError, pubkey::Pubkey, signature::Signer, signer::keypair::Keypair,
+        transaction::TransactionError, transport::TransportError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{instruction, RebaseAccount, RebaseMintConfig},
+            BaseStateWithExtensions,
+        },
+        id,
+    },
+    spl_token_client::{
+        client::ProgramBanksClientProcessTransaction,
+        token::{Token, TokenError as TokenClientError},
+    },
+};
+
+const DECIMALS: u8 = 9;
+
+async fn create_account(
+    token: &Token<ProgramBanksClientProcessTransaction>,
+    owner: &Pubkey,
+) -> Pubkey {
+    let account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&account, owner)
+        .await
+        .unwrap();
+    account.pubkey()
+}
+
+async fn get_shares(token: &Token<ProgramBanksClientProcessTransaction>, account: &Pubkey) -> u64 {
+    let state = token.get_account_info(account).await.unwrap();
+    u64::from(state.get_extension::<RebaseAccount>().unwrap().shares)
+}
+
+#[tokio::test]
+async fn sweep_in_shares() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .initial_supply(1_000, 0)
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        bob,
+        ..
+    } = context.token_context.take().unwrap();
+    let alice_account = create_account(&token, &alice.pubkey()).await;
+    let bob_account = create_account(&token, &bob.pubkey()).await;
+    let destination = create_account(&token, &Pubkey::new_unique()).await;
+    for account in [&alice_account, &bob_account] {
+        token
+            .mint_to(account, &mint_authority.pubkey(), 7, &[&mint_authority])
+            .await
+            .unwrap();
+    }
+
+    // 1.5 tokens per share, so 7 shares are worth 10 tokens, but 10 tokens
+    // only convert back to 6 shares
+    token
+        .rebase_supply(&supply_authority.pubkey(), 1_521, &[&supply_authority])
+        .await
+        .unwrap();
+    let mint = token.get_mint_info().await.unwrap();
+    let config = mint.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(config.shares_to_amount(7), Some(10));
+
+    // sweeping the full balance by amount leaves a share behind
+    token
+        .transfer(&alice_account, &destination, &alice.pubkey(), 10, &[&alice])
+        .await
+        .unwrap();
+    assert_eq!(get_shares(&token, &alice_account).await, 1);
+
+    // sweeping in shares leaves nothing
+    token
+        .process_ixs(
+            &[instruction::transfer_shares_checked(
+                &id(),
+                &bob_account,
+                token.get_address(),
+                &destination,
+                &bob.pubkey(),
+                &[],
+                7,
+                DECIMALS,
+            )
+            .unwrap()],
+            &[&bob],
+        )
+        .await
+        .unwrap();
+    assert_eq!(get_shares(&token, &bob_account).await, 0);
+    assert_eq!(
+        token
+            .get_account_info(&bob_account)
+            .await
+            .unwrap()
+            .base
+            .amount,
+        0
+    );
+    assert_eq!(get_shares(&token, &destination).await, 13);
+
+    // the unchecked variant moves the dust too
+    token
+        .process_ixs(
+            &[instruction::transfer_shares(
+                &id(),
+                &alice_account,
+                token.get_address(),
+                &destination,
+                &alice.pubkey(),
+                &[],
+                1,
+            )
+            .unwrap()],
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    assert_eq!(get_shares(&token, &alice_account).await, 0);
+    assert_eq!(get_shares(&token, &destination).await, 14);
+
+    // can't move more shares than held
+    let err = token
+        .process_ixs(
+            &[instruction::transfer_shares(
+                &id(),
+                &alice_account,
+                token.get_address(),
+                &destination,
+                &alice.pubkey(),
+                &[],
+                1,
+            )
+            .unwrap()],
+            &[&alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::InsufficientFunds as u32)
+            )
+        )))
+    );
+
+    // decimals are checked
+    let err = token
+        .process_ixs(
+            &[instruction::transfer_shares_checked(
+                &id(),
+                &destination,
+                token.get_address(),
+                &alice_account,
+                &alice.pubkey(),
+                &[],
+                1,
+                DECIMALS + 1,
+            )
+            .unwrap()],
+            &[&alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::MintDecimalsMismatch as u32)
+            )
+        )))
+    );
+}
+
+#[tokio::test]
+async fn fail_without_rebase_mint() {
+    let mut context = TestContext::new().await;
+    context.init_token_with_mint(vec![]).await.unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        bob,
+        ..
+    } = context.token_context.take().unwrap();
+    let alice_account = create_account(&token, &alice.pubkey()).await;
+    let bob_account = create_account(&token, &bob.pubkey()).await;
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            10,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    let err = token
+        .process_ixs(
+            &[instruction::transfer_shares(
+                &id(),
+                &alice_account,
+                token.get_address(),
+                &bob_account,
+                &alice.pubkey(),
+                &[],
+                1,
+            )
+            .unwrap()],
+            &[&alice],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+        )))
+    );
+}
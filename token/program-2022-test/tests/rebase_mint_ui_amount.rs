@@ -0,0 +1,137 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{instruction::Instruction, signature::Signer, signer::keypair::Keypair},
+    spl_token_2022::{
+        extension::rebase_mint::{instruction, RebaseMode},
+        id,
+        instruction::{amount_to_ui_amount, ui_amount_to_amount},
+    },
+    spl_token_client::{client::ProgramBanksClientProcessTransaction, token::Token},
+};
+
+async fn simulate_getter(
+    token: &Token<ProgramBanksClientProcessTransaction>,
+    instruction: Instruction,
+) -> Vec<u8> {
+    let simulation = token
+        .simulate_ixs(&[instruction], &[] as &[&dyn Signer; 0])
+        .await
+        .unwrap();
+    assert!(simulation.result.unwrap().is_ok());
+    simulation
+        .simulation_details
+        .unwrap()
+        .return_data
+        .unwrap()
+        .data
+}
+
+async fn get_ui_amount(token: &Token<ProgramBanksClientProcessTransaction>, shares: u64) -> String {
+    let return_data = simulate_getter(
+        token,
+        amount_to_ui_amount(&id(), token.get_address(), shares).unwrap(),
+    )
+    .await;
+    String::from_utf8(return_data).unwrap()
+}
+
+async fn get_shares(token: &Token<ProgramBanksClientProcessTransaction>, ui_amount: &str) -> u64 {
+    let return_data = simulate_getter(
+        token,
+        ui_amount_to_amount(&id(), token.get_address(), ui_amount).unwrap(),
+    )
+    .await;
+    u64::from_le_bytes(return_data.try_into().unwrap())
+}
+
+#[tokio::test]
+async fn ui_amounts_follow_rebase() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .initial_supply(2_000_000_000, 0)
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext { token, .. } = context.token_context.take().unwrap();
+
+    // 1:1 before any rebase
+    assert_eq!(get_ui_amount(&token, 1_000_000_000).await, "1");
+    assert_eq!(get_shares(&token, "1").await, 1_000_000_000);
+
+    // 1.5 tokens per share
+    token
+        .rebase_supply(
+            &supply_authority.pubkey(),
+            3_000_000_000,
+            &[&supply_authority],
+        )
+        .await
+        .unwrap();
+    assert_eq!(get_ui_amount(&token, 1_000_000_000).await, "1.5");
+    assert_eq!(get_ui_amount(&token, 1).await, "0.000000001");
+    assert_eq!(get_shares(&token, "1.5").await, 1_000_000_000);
+    assert_eq!(get_shares(&token, "1").await, 666_666_666);
+}
+
+#[tokio::test]
+async fn lazy_ui_amounts_follow_rebase() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .rebase_mode(RebaseMode::Lazy)
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        ..
+    } = context.token_context.take().unwrap();
+    let account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&account, &alice.pubkey())
+        .await
+        .unwrap();
+    token
+        .mint_to(
+            &account.pubkey(),
+            &mint_authority.pubkey(),
+            2_000_000_000,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    // amounts are scaled by the rebase index
+    token
+        .rebase_supply(
+            &supply_authority.pubkey(),
+            3_000_000_000,
+            &[&supply_authority],
+        )
+        .await
+        .unwrap();
+    assert_eq!(get_ui_amount(&token, 1_000_000_000).await, "1.5");
+    assert_eq!(get_ui_amount(&token, 1).await, "0.000000001");
+    assert_eq!(get_shares(&token, "1.5").await, 1_000_000_000);
+    assert_eq!(get_shares(&token, "1").await, 666_666_666);
+}
@@ -0,0 +1,276 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{
+        instruction::InstructionError, signature::Signer, signer::keypair::Keypair,
+        transaction::TransactionError, transport::TransportError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{instruction, RebaseHistory, RebaseMintConfig},
+            BaseStateWithExtensions,
+        },
+        id,
+        instruction::TokenInstruction,
+    },
+    spl_token_client::{
+        client::ProgramBanksClientProcessTransaction,
+        token::{
+            ExtensionInitializationParams, RebaseSupplyAccounts, Token,
+            TokenError as TokenClientError,
+        },
+    },
+};
+
+// the test context's mints have 9 decimals
+const INITIAL_SUPPLY: u64 = 1_000_000_000_000;
+
+async fn get_config(token: &Token<ProgramBanksClientProcessTransaction>) -> RebaseMintConfig {
+    token.get_rebase_mint_config().await.unwrap()
+}
+
+async fn setup(supply_authority: &Keypair) -> TokenContext {
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .initial_supply(INITIAL_SUPPLY, 0)
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    context.token_context.take().unwrap()
+}
+
+#[tokio::test]
+async fn ui_rebase_matches_raw_rebase() {
+    let supply_authority = Keypair::new();
+    let TokenContext { token: ui, .. } = setup(&supply_authority).await;
+    let TokenContext { token: raw, .. } = setup(&supply_authority).await;
+
+    for (ui_supply, new_supply) in [
+        ("1250.5", 1_250_500_000_000),
+        ("1250.500000001", 1_250_500_000_001),
+        ("0001300.000000000", 1_300_000_000_000),
+    ] {
+        ui.rebase_supply_ui(
+            &supply_authority.pubkey(),
+            &RebaseSupplyAccounts::default(),
+            ui_supply,
+            0,
+            [0; 32],
+            0,
+            &[&supply_authority],
+        )
+        .await
+        .unwrap();
+        raw.rebase_supply(&supply_authority.pubkey(), new_supply, &[&supply_authority])
+            .await
+            .unwrap();
+        let (ui_config, raw_config) = (get_config(&ui).await, get_config(&raw).await);
+        assert_eq!(
+            u64::from(ui_config.total_supply),
+            new_supply,
+            "{}",
+            ui_supply
+        );
+        assert_eq!(ui_config.total_supply, raw_config.total_supply);
+        assert_eq!(ui_config.total_shares, raw_config.total_shares);
+        assert_eq!(
+            ui_config.shares_to_amount(INITIAL_SUPPLY),
+            raw_config.shares_to_amount(INITIAL_SUPPLY)
+        );
+    }
+
+    // through the same checks as any other rebase
+    let err = ui
+        .rebase_supply_ui(
+            &supply_authority.pubkey(),
+            &RebaseSupplyAccounts::default(),
+            "999",
+            0,
+            [0; 32],
+            0,
+            &[&supply_authority],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Rebase(TokenError::SupplyDecreaseForbidden)
+    );
+    let stranger = Keypair::new();
+    let err = ui
+        .rebase_supply_ui(
+            &stranger.pubkey(),
+            &RebaseSupplyAccounts::default(),
+            "2000",
+            0,
+            [0; 32],
+            0,
+            &[&stranger],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::OwnerMismatch as u32)
+            )
+        )))
+    );
+}
+
+#[tokio::test]
+async fn malformed_ui_supply() {
+    let supply_authority = Keypair::new();
+    let TokenContext { token, .. } = setup(&supply_authority).await;
+
+    for ui_supply in [
+        "",
+        "1,250",
+        "1_250",
+        "+1250",
+        "-1250",
+        " 1250",
+        "1250 ",
+        "1e4",
+        "1250.",
+        ".5",
+        "1.2.3",
+        "0x4e2",
+        // finer than the mint's decimals
+        "1250.0000000001",
+        // more than a u64 holds
+        "18446744073.709551616",
+    ] {
+        let err = token
+            .rebase_supply_ui(
+                &supply_authority.pubkey(),
+                &RebaseSupplyAccounts::default(),
+                ui_supply,
+                0,
+                [0; 32],
+                0,
+                &[&supply_authority],
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err,
+            TokenClientError::Rebase(TokenError::RebaseUiSupplyMalformed),
+            "{:?}",
+            ui_supply
+        );
+    }
+    assert_eq!(
+        u64::from(get_config(&token).await.total_supply),
+        INITIAL_SUPPLY
+    );
+}
+
+#[tokio::test]
+async fn ui_supply_too_long() {
+    let supply_authority = Keypair::new();
+    let TokenContext { token, .. } = setup(&supply_authority).await;
+
+    // the builder refuses it, so the data is written by hand
+    let mut rebase = instruction::update_supply_ui(
+        &id(),
+        token.get_address(),
+        None,
+        None,
+        None,
+        None,
+        &supply_authority.pubkey(),
+        &[],
+        "1",
+    )
+    .unwrap();
+    let ui_supply = format!("1{}", "0".repeat(instruction::MAX_UI_SUPPLY_LEN));
+    rebase.data = TokenInstruction::RebaseMintExtension.pack();
+    rebase
+        .data
+        .push(instruction::RebaseMintInstruction::RebaseSupplyUi.into());
+    rebase
+        .data
+        .extend_from_slice(&(ui_supply.len() as u32).to_le_bytes());
+    rebase.data.extend_from_slice(ui_supply.as_bytes());
+
+    let err = token
+        .process_ixs(&[rebase], &[&supply_authority])
+        .await
+        .unwrap_err()
+        .into_rebase_error();
+    assert_eq!(
+        err,
+        TokenClientError::Rebase(TokenError::RebaseUiSupplyTooLong)
+    );
+}
+
+#[tokio::test]
+async fn ui_rebase_is_tagged_and_dated() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint_and_extensions(
+            vec![ExtensionInitializationParams::RebaseHistory],
+            |mint| {
+                instruction::InitializeRebaseMintBuilder::new(&id())
+                    .mint(mint)
+                    .supply_authority(&supply_authority.pubkey())
+                    .initial_supply(INITIAL_SUPPLY, 0)
+                    .build()
+                    .unwrap()
+            },
+        )
+        .await
+        .unwrap();
+    let TokenContext { token, .. } = context.token_context.take().unwrap();
+
+    // long past
+    let err = token
+        .rebase_supply_ui(
+            &supply_authority.pubkey(),
+            &RebaseSupplyAccounts::default(),
+            "1250",
+            3,
+            [7; 32],
+            1,
+            &[&supply_authority],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err, TokenClientError::Rebase(TokenError::RebaseExpired));
+
+    token
+        .rebase_supply_ui(
+            &supply_authority.pubkey(),
+            &RebaseSupplyAccounts::default(),
+            "1250",
+            3,
+            [7; 32],
+            i64::MAX,
+            &[&supply_authority],
+        )
+        .await
+        .unwrap();
+    let state = token.get_mint_info().await.unwrap();
+    let latest = *state
+        .get_extension::<RebaseHistory>()
+        .unwrap()
+        .latest()
+        .unwrap();
+    assert_eq!(u64::from(latest.new_supply), 1_250_000_000_000);
+    assert_eq!(latest.reason_code, 3);
+    assert_eq!(latest.reference, [7; 32]);
+}
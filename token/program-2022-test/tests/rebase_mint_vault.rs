@@ -0,0 +1,240 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::{
+        processor,
+        tokio::{self, sync::Mutex},
+        ProgramTest,
+    },
+    solana_sdk::{
+        account_info::{next_account_info, AccountInfo},
+        entrypoint::ProgramResult,
+        instruction::{AccountMeta, Instruction, InstructionError},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+        signature::Signer,
+        signer::keypair::Keypair,
+        transaction::TransactionError,
+        transport::TransportError,
+    },
+    spl_token_2022::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{instruction, RebaseAccount},
+            BaseStateWithExtensions,
+        },
+        id, onchain,
+        processor::Processor,
+    },
+    spl_token_client::{
+        client::ProgramBanksClientProcessTransaction,
+        token::{Token, TokenError as TokenClientError},
+    },
+    std::{convert::TryInto, sync::Arc},
+};
+
+const DECIMALS: u8 = 9;
+const VAULT_SEED: &[u8] = b"vault";
+
+/// Moves tokens into or out of the vault, by value
+///
+/// Instruction data is a tag, 0 to deposit or 1 to withdraw, followed by the
+/// amount and the minimum number of shares as little-endian `u64`s.
+fn process_vault_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    input: &[u8],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let source_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let destination_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+
+    let (&tag, rest) = input
+        .split_first()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    if rest.len() != 16 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let amount = u64::from_le_bytes(rest[..8].try_into().unwrap());
+    let min_shares_out = u64::from_le_bytes(rest[8..].try_into().unwrap());
+
+    let (vault_authority, bump_seed) = Pubkey::find_program_address(&[VAULT_SEED], program_id);
+    let vault_seeds: &[&[&[u8]]] = &[&[VAULT_SEED, &[bump_seed]]];
+    let seeds: &[&[&[u8]]] = match tag {
+        0 => &[],
+        1 if *authority_info.key == vault_authority => vault_seeds,
+        _ => return Err(ProgramError::InvalidInstructionData),
+    };
+
+    onchain::invoke_transfer_checked_rebased(
+        token_program_info.key,
+        source_info.clone(),
+        mint_info.clone(),
+        destination_info.clone(),
+        authority_info.clone(),
+        &[],
+        amount,
+        DECIMALS,
+        min_shares_out,
+        seeds,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn vault_instruction(
+    program_id: &Pubkey,
+    tag: u8,
+    source: &Pubkey,
+    mint: &Pubkey,
+    destination: &Pubkey,
+    authority: &Pubkey,
+    amount: u64,
+    min_shares_out: u64,
+) -> Instruction {
+    let mut data = vec![tag];
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.extend_from_slice(&min_shares_out.to_le_bytes());
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*source, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(*authority, tag == 0),
+            AccountMeta::new_readonly(id(), false),
+        ],
+        data,
+    }
+}
+
+async fn get_shares(token: &Token<ProgramBanksClientProcessTransaction>, account: &Pubkey) -> u64 {
+    let state = token.get_account_info(account).await.unwrap();
+    u64::from(state.get_extension::<RebaseAccount>().unwrap().shares)
+}
+
+#[tokio::test]
+async fn deposit_across_rebase() {
+    let supply_authority = Keypair::new();
+    let vault_program_id = Pubkey::new_unique();
+    let (vault_authority, _) = Pubkey::find_program_address(&[VAULT_SEED], &vault_program_id);
+
+    let mut program_test = ProgramTest::new("spl_token_2022", id(), processor!(Processor::process));
+    program_test.add_program(
+        "rebase_mint_vault",
+        vault_program_id,
+        processor!(process_vault_instruction),
+    );
+    let context = program_test.start_with_context().await;
+    let mut context = TestContext {
+        context: Arc::new(Mutex::new(context)),
+        token_context: None,
+    };
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        ..
+    } = context.token_context.take().unwrap();
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    let alice_account = alice_account.pubkey();
+    let vault_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&vault_account, &vault_authority)
+        .await
+        .unwrap();
+    let vault_account = vault_account.pubkey();
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            1_000,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    let deposit = |amount, min_shares_out| {
+        vault_instruction(
+            &vault_program_id,
+            0,
+            &alice_account,
+            token.get_address(),
+            &vault_account,
+            &alice.pubkey(),
+            amount,
+            min_shares_out,
+        )
+    };
+
+    // 1:1 before any rebase
+    token
+        .process_ixs(&[deposit(400, 400)], &[&alice])
+        .await
+        .unwrap();
+    assert_eq!(get_shares(&token, &vault_account).await, 400);
+
+    // after doubling the supply, the same deposit buys half the shares
+    token
+        .rebase_supply(&supply_authority.pubkey(), 2_000, &[&supply_authority])
+        .await
+        .unwrap();
+    let err = token
+        .process_ixs(&[deposit(400, 201)], &[&alice])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TokenClientError::Client(Box::new(TransportError::TransactionError(
+            TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(TokenError::ShareSlippageExceeded as u32)
+            )
+        )))
+    );
+    token
+        .process_ixs(&[deposit(400, 200)], &[&alice])
+        .await
+        .unwrap();
+    assert_eq!(get_shares(&token, &vault_account).await, 600);
+    assert_eq!(get_shares(&token, &alice_account).await, 400);
+
+    // the vault signs for withdrawals with its seeds
+    token
+        .process_ixs(
+            &[vault_instruction(
+                &vault_program_id,
+                1,
+                &vault_account,
+                token.get_address(),
+                &alice_account,
+                &vault_authority,
+                100,
+                50,
+            )],
+            &[] as &[&dyn Signer; 0],
+        )
+        .await
+        .unwrap();
+    assert_eq!(get_shares(&token, &vault_account).await, 550);
+    assert_eq!(get_shares(&token, &alice_account).await, 450);
+}
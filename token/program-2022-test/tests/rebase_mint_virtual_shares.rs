@@ -0,0 +1,100 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{pubkey::Pubkey, signature::Signer, signer::keypair::Keypair},
+    spl_token_2022::{
+        extension::{
+            rebase_mint::{instruction, RebaseAccount, RebaseMintConfig},
+            BaseStateWithExtensions,
+        },
+        id,
+    },
+    spl_token_client::{client::ProgramBanksClientProcessTransaction, token::Token},
+};
+
+async fn get_value(token: &Token<ProgramBanksClientProcessTransaction>, account: &Pubkey) -> u64 {
+    let state = token.get_account_info(account).await.unwrap();
+    let shares = u64::from(state.get_extension::<RebaseAccount>().unwrap().shares);
+    let mint = token.get_mint_info().await.unwrap();
+    let config = mint.get_extension::<RebaseMintConfig>().unwrap();
+    config.shares_to_amount(shares).unwrap()
+}
+
+/// The supply authority holds the only share of a near-empty mint, and
+/// rebases it up before a deposit, so the deposit rounds down in its favor.
+/// Returns how much the attacker's balance grew from the deposit, and the
+/// depositor's balance.
+async fn run_attack(virtual_shares_offset: u64) -> (u64, u64) {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .virtual_shares_offset(virtual_shares_offset)
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let TokenContext {
+        token,
+        mint_authority,
+        alice,
+        bob,
+        ..
+    } = context.token_context.take().unwrap();
+    let mut accounts = vec![];
+    for owner in [&alice, &bob] {
+        let account = Keypair::new();
+        token
+            .create_auxiliary_token_account(&account, &owner.pubkey())
+            .await
+            .unwrap();
+        accounts.push(account.pubkey());
+    }
+    let (attacker_account, victim_account) = (accounts[0], accounts[1]);
+
+    token
+        .mint_to(
+            &attacker_account,
+            &mint_authority.pubkey(),
+            1,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    token
+        .rebase_supply(&supply_authority.pubkey(), 1_000_000, &[&supply_authority])
+        .await
+        .unwrap();
+    let attacker_value = get_value(&token, &attacker_account).await;
+
+    token
+        .mint_to(
+            &victim_account,
+            &mint_authority.pubkey(),
+            999_999,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+    let attacker_gain = get_value(&token, &attacker_account).await - attacker_value;
+    (attacker_gain, get_value(&token, &victim_account).await)
+}
+
+#[tokio::test]
+async fn offset_bounds_ratio_manipulation() {
+    // without an offset, the whole deposit goes to the attacker
+    assert_eq!(run_attack(0).await, (999_999, 0));
+
+    // with one, the attacker gains less than one base unit
+    let (attacker_gain, victim_value) = run_attack(1_000).await;
+    assert_eq!(attacker_gain, 0);
+    assert!(victim_value > 998_000);
+}
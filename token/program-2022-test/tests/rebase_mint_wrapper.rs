@@ -0,0 +1,167 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+use {
+    program_test::{keypair_clone, TestContext, TokenContext},
+    solana_program_test::tokio,
+    solana_sdk::{pubkey::Pubkey, signature::Signer, signer::keypair::Keypair},
+    spl_token_2022::{
+        extension::{
+            rebase_mint::{
+                instruction,
+                wrapper::{get_escrow_address, get_wrapper_mint_address},
+                RebaseAccount, RebaseMintConfig,
+            },
+            BaseStateWithExtensions,
+        },
+        id,
+    },
+    spl_token_client::{
+        client::{ProgramBanksClient, ProgramBanksClientProcessTransaction, ProgramClient},
+        token::Token,
+    },
+    std::sync::Arc,
+};
+
+async fn get_shares(token: &Token<ProgramBanksClientProcessTransaction>, account: &Pubkey) -> u64 {
+    let state = token.get_account_info(account).await.unwrap();
+    u64::from(state.get_extension::<RebaseAccount>().unwrap().shares)
+}
+
+#[tokio::test]
+async fn wrapped_shares_capture_rebase() {
+    let supply_authority = Keypair::new();
+    let mut context = TestContext::new().await;
+    context
+        .init_token_with_rebase_mint(|mint| {
+            instruction::InitializeRebaseMintBuilder::new(&id())
+                .mint(mint)
+                .supply_authority(&supply_authority.pubkey())
+                .allow_supply_decrease()
+                .build()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    let payer = keypair_clone(&context.context.lock().await.payer);
+    let TokenContext {
+        decimals,
+        token,
+        mint_authority,
+        alice,
+        ..
+    } = context.token_context.take().unwrap();
+
+    let alice_account = Keypair::new();
+    token
+        .create_auxiliary_token_account(&alice_account, &alice.pubkey())
+        .await
+        .unwrap();
+    let alice_account = alice_account.pubkey();
+    token
+        .mint_to(
+            &alice_account,
+            &mint_authority.pubkey(),
+            100,
+            &[&mint_authority],
+        )
+        .await
+        .unwrap();
+
+    token
+        .process_ixs(
+            &[
+                instruction::initialize_wrapper(&id(), &payer.pubkey(), token.get_address())
+                    .unwrap(),
+            ],
+            &[&payer],
+        )
+        .await
+        .unwrap();
+    let wrapper_mint = get_wrapper_mint_address(token.get_address(), &id());
+    let escrow = get_escrow_address(token.get_address(), &id());
+
+    let client: Arc<dyn ProgramClient<ProgramBanksClientProcessTransaction>> =
+        Arc::new(ProgramBanksClient::new_from_context(
+            Arc::clone(&context.context),
+            ProgramBanksClientProcessTransaction,
+        ));
+    let wrapper = Token::new(
+        client,
+        &id(),
+        &wrapper_mint,
+        Some(decimals),
+        Arc::new(keypair_clone(&payer)),
+    );
+    let alice_wrapper_account = Keypair::new();
+    wrapper
+        .create_auxiliary_token_account(&alice_wrapper_account, &alice.pubkey())
+        .await
+        .unwrap();
+    let alice_wrapper_account = alice_wrapper_account.pubkey();
+
+    token
+        .process_ixs(
+            &[instruction::wrap_shares(
+                &id(),
+                &alice_account,
+                token.get_address(),
+                &alice_wrapper_account,
+                &alice.pubkey(),
+                &[],
+                40,
+            )
+            .unwrap()],
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    assert_eq!(get_shares(&token, &alice_account).await, 60);
+    assert_eq!(get_shares(&token, &escrow).await, 40);
+    let wrapped = wrapper
+        .get_account_info(&alice_wrapper_account)
+        .await
+        .unwrap();
+    assert_eq!(wrapped.base.amount, 40);
+
+    // the wrapper balance stays put through a rebase
+    token
+        .rebase_supply(&supply_authority.pubkey(), 200, &[&supply_authority])
+        .await
+        .unwrap();
+    let wrapped = wrapper
+        .get_account_info(&alice_wrapper_account)
+        .await
+        .unwrap();
+    assert_eq!(wrapped.base.amount, 40);
+
+    token
+        .process_ixs(
+            &[instruction::unwrap_shares(
+                &id(),
+                &alice_wrapper_account,
+                token.get_address(),
+                &alice_account,
+                &alice.pubkey(),
+                &[],
+                40,
+            )
+            .unwrap()],
+            &[&alice],
+        )
+        .await
+        .unwrap();
+    let wrapped = wrapper
+        .get_account_info(&alice_wrapper_account)
+        .await
+        .unwrap();
+    assert_eq!(wrapped.base.amount, 0);
+    assert_eq!(wrapper.get_mint_info().await.unwrap().base.supply, 0);
+    assert_eq!(get_shares(&token, &escrow).await, 0);
+
+    // the unwrapped shares are worth twice what was wrapped
+    assert_eq!(get_shares(&token, &alice_account).await, 100);
+    let mint = token.get_mint_info().await.unwrap();
+    let config = mint.get_extension::<RebaseMintConfig>().unwrap();
+    assert_eq!(config.shares_to_amount(100), Some(200));
+}
@@ -0,0 +1,182 @@
+//! Host-side benchmarks of the rebasing mint's conversion and rebase math.
+//!
+//! Each function runs against a small, a mid-sized, and a `u64`-extreme
+//! config, alongside the floating-point formulas used by interest-bearing
+//! mints, which the integer math replaces. The float results are not exact;
+//! they're only there to compare costs.
+//!
+//! Record a baseline before changing the math, then compare against it:
+//!
+//! ```sh
+//! cargo bench -p spl-token-2022 --bench rebase_math -- --save-baseline before
+//! cargo bench -p spl-token-2022 --bench rebase_math -- --baseline before
+//! ```
+
+use {
+    criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion},
+    spl_token_2022::extension::rebase_mint::RebaseMintConfig,
+};
+
+const DECIMALS: u8 = 6;
+
+struct Case {
+    name: &'static str,
+    config: RebaseMintConfig,
+    amount: u64,
+    ui_amount: &'static str,
+    new_supply: u64,
+}
+
+fn config(total_supply: u64, total_shares: u64) -> RebaseMintConfig {
+    let mut config = RebaseMintConfig {
+        total_supply: total_supply.into(),
+        total_shares: total_shares.into(),
+        allow_supply_decrease: true.into(),
+        ..RebaseMintConfig::default()
+    };
+    config.refresh_cached_ratio();
+    config
+}
+
+fn cases() -> Vec<Case> {
+    vec![
+        Case {
+            name: "small",
+            config: config(1_500, 1_000),
+            amount: 150,
+            ui_amount: "0.00015",
+            new_supply: 2_000,
+        },
+        Case {
+            name: "mid",
+            config: config(7_000_000_000_001, 3_000_000_000_000),
+            amount: 1_234_567_891,
+            ui_amount: "1234.567891",
+            new_supply: 7_700_000_000_003,
+        },
+        Case {
+            name: "extreme",
+            config: config(u64::MAX - 1, u64::MAX / 3),
+            amount: u64::MAX / 4,
+            ui_amount: "4611686018427.387903",
+            new_supply: u64::MAX,
+        },
+    ]
+}
+
+/// Floating-point versions of the conversions, for comparison only
+mod f64_reference {
+    use spl_token_2022::extension::rebase_mint::RebaseMintConfig;
+
+    fn ratio(config: &RebaseMintConfig) -> f64 {
+        u64::from(config.total_supply) as f64 / u64::from(config.total_shares) as f64
+    }
+
+    pub fn amount_to_shares(config: &RebaseMintConfig, amount: u64) -> u64 {
+        (amount as f64 / ratio(config)) as u64
+    }
+
+    pub fn shares_to_amount(config: &RebaseMintConfig, shares: u64) -> u64 {
+        (shares as f64 * ratio(config)) as u64
+    }
+
+    pub fn try_ui_amount_into_shares(
+        config: &RebaseMintConfig,
+        ui_amount: &str,
+        decimals: u8,
+    ) -> Option<u64> {
+        let ui_amount = ui_amount.parse::<f64>().ok()?;
+        Some((ui_amount * 10_f64.powi(decimals as i32) / ratio(config)) as u64)
+    }
+
+    pub fn preview_rebase_rounding_error(config: &RebaseMintConfig, new_supply: u64) -> u64 {
+        let total_shares = u64::from(config.total_shares) as f64;
+        let represented = (new_supply as f64 / total_shares).floor() * total_shares;
+        new_supply.saturating_sub(represented as u64)
+    }
+}
+
+fn bench_amount_to_shares(c: &mut Criterion) {
+    let mut group = c.benchmark_group("amount_to_shares");
+    for case in cases() {
+        group.bench_with_input(BenchmarkId::new("u128", case.name), &case, |b, case| {
+            b.iter(|| black_box(&case.config).amount_to_shares(black_box(case.amount)))
+        });
+        group.bench_with_input(BenchmarkId::new("f64", case.name), &case, |b, case| {
+            b.iter(|| {
+                f64_reference::amount_to_shares(black_box(&case.config), black_box(case.amount))
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_shares_to_amount(c: &mut Criterion) {
+    let mut group = c.benchmark_group("shares_to_amount");
+    for case in cases() {
+        let shares = case.config.amount_to_shares(case.amount).unwrap();
+        let mut uncached = case.config;
+        uncached.cached_ratio = 0.into();
+        group.bench_with_input(BenchmarkId::new("cached", case.name), &case, |b, case| {
+            b.iter(|| black_box(&case.config).shares_to_amount(black_box(shares)))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("u128", case.name),
+            &uncached,
+            |b, config| b.iter(|| black_box(config).shares_to_amount(black_box(shares))),
+        );
+        group.bench_with_input(BenchmarkId::new("f64", case.name), &case, |b, case| {
+            b.iter(|| f64_reference::shares_to_amount(black_box(&case.config), black_box(shares)))
+        });
+    }
+    group.finish();
+}
+
+fn bench_try_ui_amount_into_shares(c: &mut Criterion) {
+    let mut group = c.benchmark_group("try_ui_amount_into_shares");
+    for case in cases() {
+        group.bench_with_input(BenchmarkId::new("u128", case.name), &case, |b, case| {
+            b.iter(|| {
+                black_box(&case.config)
+                    .try_ui_amount_into_shares(black_box(case.ui_amount), DECIMALS)
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("f64", case.name), &case, |b, case| {
+            b.iter(|| {
+                f64_reference::try_ui_amount_into_shares(
+                    black_box(&case.config),
+                    black_box(case.ui_amount),
+                    DECIMALS,
+                )
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_preview_rebase(c: &mut Criterion) {
+    let mut group = c.benchmark_group("preview_rebase");
+    for case in cases() {
+        group.bench_with_input(BenchmarkId::new("u128", case.name), &case, |b, case| {
+            b.iter(|| black_box(&case.config).preview_rebase(black_box(case.new_supply)))
+        });
+        group.bench_with_input(BenchmarkId::new("f64", case.name), &case, |b, case| {
+            b.iter(|| {
+                f64_reference::preview_rebase_rounding_error(
+                    black_box(&case.config),
+                    black_box(case.new_supply),
+                )
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_amount_to_shares,
+    bench_shares_to_amount,
+    bench_try_ui_amount_into_shares,
+    bench_preview_rebase
+);
+criterion_main!(benches);
@@ -0,0 +1,27 @@
+//! Input layout shared by the instruction fuzz target and its seed corpus
+
+/// Default directory of the seed corpus, relative to the repository root
+pub const REBASE_INSTRUCTIONS_INPUT_DIR: &str =
+    "hfuzz_workspace/token-2022-rebase-instructions/input";
+
+/// Split a fuzz input into the instruction data, whose length is given by the
+/// first byte, and the rest, which is used to generate the accounts
+pub fn split_instruction_input(input: &[u8]) -> Option<(&[u8], &[u8])> {
+    let (len, rest) = input.split_first()?;
+    let len = usize::from(*len);
+    if rest.len() < len {
+        None
+    } else {
+        Some(rest.split_at(len))
+    }
+}
+
+/// Lay out `instruction_data` at the start of a fuzz input, followed by the
+/// bytes that generate the accounts
+pub fn instruction_input(instruction_data: &[u8], state: &[u8]) -> Vec<u8> {
+    let len = u8::try_from(instruction_data.len()).expect("instruction data fits in a seed");
+    let mut input = vec![len];
+    input.extend_from_slice(instruction_data);
+    input.extend_from_slice(state);
+    input
+}
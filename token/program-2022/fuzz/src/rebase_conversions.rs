@@ -0,0 +1,21 @@
+use {
+    arbitrary::Arbitrary,
+    honggfuzz::fuzz,
+    spl_token_2022::extension::rebase_mint::{
+        fuzz::check_conversions_against_reference, RebaseMintConfig,
+    },
+};
+
+#[derive(Debug, Arbitrary)]
+struct FuzzData {
+    config: RebaseMintConfig,
+    value: u64,
+}
+
+fn main() {
+    loop {
+        fuzz!(|fuzz_data: FuzzData| {
+            check_conversions_against_reference(&fuzz_data.config, fuzz_data.value)
+        });
+    }
+}
@@ -0,0 +1,168 @@
+//! Writes the instructions built by the rebasing mint's instruction builders
+//! as the seed corpus of the `token-2022-rebase-instructions` target, each on
+//! the target's fixture mint.
+//!
+//! Usage: token-2022-rebase-instruction-seeds [output-dir]
+
+use {
+    solana_program::{instruction::Instruction, pubkey::Pubkey},
+    spl_token_2022::{
+        extension::rebase_mint::{
+            instruction::{self, decode},
+            RebaseMode,
+        },
+        id,
+    },
+    spl_token_2022_fuzz::{instruction_input, REBASE_INSTRUCTIONS_INPUT_DIR},
+    std::{env, fs, path::PathBuf},
+};
+
+fn builder_instructions() -> Vec<Instruction> {
+    let program_id = id();
+    let mint = Pubkey::new_unique();
+    let authority = Pubkey::new_unique();
+    let account = Pubkey::new_unique();
+    let destination = Pubkey::new_unique();
+    vec![
+        instruction::InitializeRebaseMintBuilder::new(&program_id)
+            .mint(&mint)
+            .supply_authority(&authority)
+            .initial_supply(1_000, 0)
+            .first_rebase_after_timestamp(1)
+            .two_step_authority_transfer()
+            .authority_change_delay_seconds(60)
+            .use_mint_authority_fallback()
+            .require_rebase_memo()
+            .virtual_shares_offset(1)
+            .allow_supply_decrease()
+            .build(),
+        instruction::update_supply(&program_id, &mint, None, &authority, &[], 2_000),
+        instruction::RebaseSupplyBuilder::new(&program_id, 2_000)
+            .mint(&mint)
+            .authority(&authority)
+            .reason_code(1)
+            .reference([1; 32])
+            .build(),
+        instruction::set_reserve_account(&program_id, &mint, &authority, &[], Some(account)),
+        instruction::preview_rebase_supply(&program_id, &mint, None, 500),
+        instruction::set_supply_authority(&program_id, &mint, &authority, &[], None),
+        instruction::propose_supply_authority(&program_id, &mint, &authority, &[], &destination),
+        instruction::accept_supply_authority(&program_id, &mint, &authority, &[]),
+        instruction::cancel_pending_authority(&program_id, &mint, &authority, &[]),
+        instruction::finalize_authority_change(&program_id, &mint),
+        instruction::cancel_authority_change(&program_id, &mint, &authority, &[]),
+        instruction::set_rebase_delegate(&program_id, &mint, &authority, &[], Some(destination)),
+        instruction::transfer_shares(
+            &program_id,
+            &account,
+            &mint,
+            &destination,
+            &authority,
+            &[],
+            100,
+        ),
+        instruction::transfer_shares_checked(
+            &program_id,
+            &account,
+            &mint,
+            &destination,
+            &authority,
+            &[],
+            100,
+            6,
+        ),
+        instruction::mint_to_shares(&program_id, &mint, &account, &authority, &[], 100),
+        instruction::burn_shares(&program_id, &account, &mint, &authority, &[], 100),
+        instruction::sync_supply_from_mint(&program_id, &mint),
+        instruction::get_rebased_balance(&program_id, &account, &mint),
+        instruction::convert_amount_to_shares(&program_id, &mint, 100),
+        instruction::convert_shares_to_amount(&program_id, &mint, 100),
+        instruction::approve_shares(
+            &program_id,
+            &account,
+            &mint,
+            &destination,
+            &authority,
+            &[],
+            100,
+        ),
+        instruction::set_account_exempt(&program_id, &account, &mint, &authority, &[], true),
+        instruction::initialize_wrapper(&program_id, &authority, &mint),
+        instruction::wrap_shares(
+            &program_id,
+            &account,
+            &mint,
+            &destination,
+            &authority,
+            &[],
+            100,
+        ),
+        instruction::unwrap_shares(
+            &program_id,
+            &account,
+            &mint,
+            &destination,
+            &authority,
+            &[],
+            100,
+        ),
+        instruction::assert_rebase_consistency(&program_id, &mint),
+        instruction::transfer_checked_with_share_slippage(
+            &program_id,
+            &account,
+            &mint,
+            &destination,
+            &authority,
+            &[],
+            100,
+            6,
+            100,
+            50,
+        ),
+        instruction::get_rebase_state(&program_id, &mint),
+        instruction::initialize_history(&program_id, &mint),
+        instruction::get_rebase_history(&program_id, &mint),
+        instruction::set_rebase_fee(&program_id, &mint, &authority, &[], 250, Some(account)),
+        instruction::sunset_rebase(&program_id, &mint, &authority, &[]),
+        instruction::retire_rebase(&program_id, &mint, &authority, &[]),
+        instruction::InitializeRebaseMintBuilder::new(&program_id)
+            .mint(&mint)
+            .supply_authority(&authority)
+            .co_signer(&account)
+            .build(),
+        instruction::sweep_rounding_dust(&program_id, &mint, &account, &authority, &[]),
+        instruction::InitializeRebaseMintBuilder::new(&program_id)
+            .mint(&mint)
+            .supply_authority(&authority)
+            .rebase_mode(RebaseMode::Lazy)
+            .build(),
+        instruction::claim_rebase(&program_id, &account, &mint),
+        instruction::InitializeRebaseMintBuilder::new(&program_id)
+            .mint(&mint)
+            .supply_authority(&authority)
+            .sync_base_supply()
+            .build(),
+    ]
+    .into_iter()
+    .map(Result::unwrap)
+    .collect()
+}
+
+fn main() {
+    let output_dir = env::args().nth(1).map_or_else(
+        || PathBuf::from(REBASE_INSTRUCTIONS_INPUT_DIR),
+        PathBuf::from,
+    );
+    fs::create_dir_all(&output_dir).unwrap();
+    for (index, instruction) in builder_instructions().into_iter().enumerate() {
+        decode(&instruction.data).unwrap();
+        // the target adds the token instruction tag itself
+        let instruction_data = &instruction.data[1..];
+        // no state bytes, so the target uses its fixture mint
+        fs::write(
+            output_dir.join(format!("builder-{:02}", index)),
+            instruction_input(instruction_data, &[]),
+        )
+        .unwrap();
+    }
+}
@@ -0,0 +1,280 @@
+use {
+    arbitrary::{Arbitrary, Unstructured},
+    honggfuzz::fuzz,
+    solana_program::{
+        account_info::AccountInfo,
+        clock::{Clock, Epoch},
+        entrypoint::{ProgramResult, SUCCESS},
+        instruction::Instruction,
+        program_error::{ProgramError, UNSUPPORTED_SYSVAR},
+        program_option::COption,
+        program_stubs,
+        pubkey::Pubkey,
+        sysvar::rent::Rent,
+    },
+    spl_token_2022::{
+        extension::{
+            mint_close_authority::MintCloseAuthority,
+            rebase_mint::{fuzz::ConsistentRebaseMintConfig, RebaseMintConfig},
+            BaseStateWithExtensions, ExtensionType, StateWithExtensions, StateWithExtensionsMut,
+        },
+        instruction::TokenInstruction,
+        processor::Processor,
+        state::Mint,
+    },
+    spl_token_2022_fuzz::split_instruction_input,
+    std::sync::{
+        atomic::{AtomicI64, Ordering},
+        Once,
+    },
+};
+
+/// Signs for any authority that the mint doesn't have
+const MISSING_AUTHORITY: Pubkey = Pubkey::new_from_array([1; 32]);
+
+/// Clock time seen by the processor, set before each run
+static UNIX_TIMESTAMP: AtomicI64 = AtomicI64::new(0);
+
+struct SyscallStubs {}
+impl program_stubs::SyscallStubs for SyscallStubs {
+    fn sol_log(&self, _message: &str) {}
+
+    fn sol_log_data(&self, _fields: &[&[u8]]) {}
+
+    fn sol_invoke_signed(
+        &self,
+        _instruction: &Instruction,
+        _account_infos: &[AccountInfo],
+        _signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        Err(ProgramError::Custom(42)) // Not supported
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe {
+            *(var_addr as *mut _ as *mut Clock) = Clock {
+                unix_timestamp: UNIX_TIMESTAMP.load(Ordering::Relaxed),
+                ..Clock::default()
+            };
+        }
+        SUCCESS
+    }
+
+    fn sol_get_epoch_schedule_sysvar(&self, _var_addr: *mut u8) -> u64 {
+        UNSUPPORTED_SYSVAR
+    }
+
+    #[allow(deprecated)]
+    fn sol_get_fees_sysvar(&self, _var_addr: *mut u8) -> u64 {
+        UNSUPPORTED_SYSVAR
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe {
+            *(var_addr as *mut _ as *mut Rent) = Rent::default();
+        }
+        SUCCESS
+    }
+}
+
+#[derive(Debug, Arbitrary)]
+enum FuzzAccount {
+    Mint,
+    SupplyAuthority { is_signer: bool },
+    PendingAuthority { is_signer: bool },
+    RebaseDelegate { is_signer: bool },
+    MintAuthority { is_signer: bool },
+    Other { key: [u8; 32], is_signer: bool },
+}
+
+#[derive(Debug, Arbitrary)]
+struct FuzzState {
+    config: ConsistentRebaseMintConfig,
+    mint_supply: u64,
+    decimals: u8,
+    mint_authority: Option<[u8; 32]>,
+    /// Put another extension ahead of the rebasing config
+    with_close_authority: bool,
+    unix_timestamp: i64,
+    accounts: Vec<FuzzAccount>,
+}
+
+impl FuzzState {
+    /// Mint that any builder's instruction can act on, used for the seeds
+    fn fixture() -> Self {
+        let config = RebaseMintConfig {
+            supply_authority: Some(Pubkey::new_from_array([2; 32])).try_into().unwrap(),
+            total_supply: 1_000.into(),
+            total_shares: 1_000.into(),
+            ..RebaseMintConfig::default()
+        };
+        Self {
+            config: ConsistentRebaseMintConfig(config),
+            mint_supply: 1_000,
+            decimals: 6,
+            mint_authority: Some([3; 32]),
+            with_close_authority: false,
+            unix_timestamp: 0,
+            accounts: vec![
+                FuzzAccount::Mint,
+                FuzzAccount::SupplyAuthority { is_signer: true },
+            ],
+        }
+    }
+
+    fn mint_data(&self) -> Vec<u8> {
+        let mut extension_types = vec![ExtensionType::RebaseMintConfig];
+        if self.with_close_authority {
+            extension_types.insert(0, ExtensionType::MintCloseAuthority);
+        }
+        let mut data =
+            vec![0; ExtensionType::try_calculate_account_len::<Mint>(&extension_types).unwrap()];
+        let mut mint = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+        if self.with_close_authority {
+            mint.init_extension::<MintCloseAuthority>(true).unwrap();
+        }
+        *mint.init_extension::<RebaseMintConfig>(true).unwrap() = self.config.0;
+        mint.base.mint_authority = self
+            .mint_authority
+            .map(Pubkey::new_from_array)
+            .map_or(COption::None, COption::Some);
+        mint.base.supply = self.mint_supply;
+        mint.base.decimals = self.decimals;
+        mint.base.is_initialized = true;
+        mint.pack_base();
+        mint.init_account_type().unwrap();
+        data
+    }
+
+    fn account_key(&self, account: &FuzzAccount) -> (Pubkey, bool) {
+        let config = &self.config.0;
+        let key_or_missing = |key: Option<Pubkey>| key.unwrap_or(MISSING_AUTHORITY);
+        match *account {
+            FuzzAccount::Mint => unreachable!("the mint is shared"),
+            FuzzAccount::SupplyAuthority { is_signer } => {
+                (key_or_missing(config.supply_authority.into()), is_signer)
+            }
+            FuzzAccount::PendingAuthority { is_signer } => (
+                key_or_missing(config.pending_supply_authority.into()),
+                is_signer,
+            ),
+            FuzzAccount::RebaseDelegate { is_signer } => {
+                (key_or_missing(config.rebase_delegate.into()), is_signer)
+            }
+            FuzzAccount::MintAuthority { is_signer } => (
+                key_or_missing(self.mint_authority.map(Pubkey::new_from_array)),
+                is_signer,
+            ),
+            FuzzAccount::Other { key, is_signer } => (Pubkey::new_from_array(key), is_signer),
+        }
+    }
+}
+
+struct OtherAccount {
+    key: Pubkey,
+    is_signer: bool,
+    lamports: u64,
+    data: Vec<u8>,
+}
+
+fn run_fuzz(instruction_data: &[u8], state: FuzzState) {
+    static ONCE: Once = Once::new();
+    ONCE.call_once(|| {
+        program_stubs::set_syscall_stubs(Box::new(SyscallStubs {}));
+    });
+    UNIX_TIMESTAMP.store(state.unix_timestamp, Ordering::Relaxed);
+
+    let program_id = spl_token_2022::id();
+    let system_program_id = Pubkey::default();
+    let mint_key = Pubkey::new_from_array([4; 32]);
+    let mut mint_lamports = 0;
+    let mut mint_data = state.mint_data();
+    let initial_mint_data = mint_data.clone();
+    // the mint is shared between its positions, as in the runtime
+    let mint_info = AccountInfo::new(
+        &mint_key,
+        false,
+        true,
+        &mut mint_lamports,
+        &mut mint_data,
+        &program_id,
+        false,
+        Epoch::default(),
+    );
+
+    let mut other_accounts = state
+        .accounts
+        .iter()
+        .filter(|account| !matches!(account, FuzzAccount::Mint))
+        .map(|account| {
+            let (key, is_signer) = state.account_key(account);
+            OtherAccount {
+                key,
+                is_signer,
+                lamports: 0,
+                data: vec![],
+            }
+        })
+        .collect::<Vec<_>>();
+    let mut other_account_infos = other_accounts
+        .iter_mut()
+        .map(|account| {
+            AccountInfo::new(
+                &account.key,
+                account.is_signer,
+                true,
+                &mut account.lamports,
+                &mut account.data,
+                &system_program_id,
+                false,
+                Epoch::default(),
+            )
+        })
+        .collect::<Vec<_>>()
+        .into_iter();
+    let account_infos = state
+        .accounts
+        .iter()
+        .map(|account| match account {
+            FuzzAccount::Mint => mint_info.clone(),
+            _ => other_account_infos.next().unwrap(),
+        })
+        .collect::<Vec<_>>();
+
+    let mut data = TokenInstruction::RebaseMintExtension.pack();
+    data.extend_from_slice(instruction_data);
+    let result = Processor::process(&program_id, &account_infos, &data);
+
+    let mint_data = mint_info.data.borrow();
+    match result {
+        // the runtime would roll back the accounts, but the processor
+        // shouldn't count on it
+        Err(_) => assert_eq!(&mint_data[..], &initial_mint_data[..]),
+        Ok(()) => {
+            let mint = StateWithExtensions::<Mint>::unpack(&mint_data).unwrap();
+            mint.get_extension::<RebaseMintConfig>()
+                .unwrap()
+                .validate()
+                .unwrap();
+        }
+    }
+}
+
+fn main() {
+    loop {
+        fuzz!(|input: &[u8]| {
+            let Some((instruction_data, state)) = split_instruction_input(input) else {
+                return;
+            };
+            let state = if state.is_empty() {
+                FuzzState::fixture()
+            } else {
+                match FuzzState::arbitrary_take_rest(Unstructured::new(state)) {
+                    Ok(state) => state,
+                    Err(_) => return,
+                }
+            };
+            run_fuzz(instruction_data, state);
+        });
+    }
+}
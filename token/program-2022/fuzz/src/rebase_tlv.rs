@@ -0,0 +1,12 @@
+use {
+    honggfuzz::fuzz,
+    spl_token_2022::extension::rebase_mint::fuzz::{check_rebase_mint_tlv, RebaseMintBuffer},
+};
+
+fn main() {
+    loop {
+        fuzz!(|buffer: RebaseMintBuffer| {
+            check_rebase_mint_tlv(&buffer.data());
+        });
+    }
+}
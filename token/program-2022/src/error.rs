@@ -243,6 +243,153 @@ pub enum TokenError {
     /// Ciphertext arithmetic failed
     #[error("Ciphertext arithmetic failed")]
     CiphertextArithmeticFailed,
+    /// Account does not match the reserve account configured on the mint
+    #[error("Account does not match the reserve account configured on the mint")]
+    RebaseReserveMismatch,
+    /// Rebased supply exceeds the amount held in the reserve account
+    #[error("Rebased supply exceeds the amount held in the reserve account")]
+    RebaseExceedsReserve,
+    /// Rebasing is not allowed yet
+    #[error("Rebasing is not allowed yet")]
+    RebaseNotYetAllowed,
+    /// Supply authority must be transferred by proposal and acceptance
+    #[error("Supply authority must be transferred by proposal and acceptance")]
+    RebaseAuthorityRequiresAcceptance,
+
+    // 65
+    /// A supply authority proposal is already pending
+    #[error("A supply authority proposal is already pending")]
+    RebaseAuthorityProposalPending,
+    /// No supply authority proposal is pending
+    #[error("No supply authority proposal is pending")]
+    NoPendingRebaseAuthority,
+    /// Supply authority change delay has not elapsed
+    #[error("Supply authority change delay has not elapsed")]
+    RebaseAuthorityChangeNotReady,
+    /// Supply cannot be rebased, since the mint has no supply authority
+    #[error("Supply cannot be rebased, since the mint has no supply authority")]
+    RebaseSupplyImmutable,
+    /// No memo in previous instruction; required to rebase the supply
+    #[error("No memo in previous instruction; required to rebase the supply")]
+    RebaseMemoRequired,
+    /// Supply cannot be synced from the mint once it has been rebased
+    #[error("Supply cannot be synced from the mint once it has been rebased")]
+    RebaseSupplySyncNotAllowed,
+    /// Number of shares moved is outside the allowed slippage
+    #[error("Number of shares moved is outside the allowed slippage")]
+    ShareSlippageExceeded,
+    /// Total shares would fall below the shares held by token accounts
+    #[error("Total shares would fall below the shares held by token accounts")]
+    RebaseBelowOutstandingShares,
+    /// Rebasing mint extension is inconsistent with the mint
+    #[error("Rebasing mint extension is inconsistent with the mint")]
+    RebaseInconsistent,
+    /// Supply of this mint cannot be rebased lower
+    #[error("Supply of this mint cannot be rebased lower")]
+    SupplyDecreaseForbidden,
+    /// Rebasing extensions cannot be added by reallocating
+    #[error("Rebasing extensions cannot be added by reallocating")]
+    RebaseExtensionNotReallocatable,
+    /// Mint still has shares outstanding
+    #[error("Mint still has shares outstanding")]
+    RebaseMintHasShares,
+    /// Supply cannot be rebased to zero while shares remain
+    #[error("Supply cannot be rebased to zero while shares remain")]
+    RebaseSupplyZero,
+    /// Total shares would overflow
+    #[error("Total shares would overflow")]
+    RebaseSharesOverflow,
+    /// Rebased supply is too large
+    #[error("Rebased supply is too large")]
+    RebaseTooLarge,
+    /// Timestamp predates the retained rebase history
+    #[error("Timestamp predates the retained rebase history")]
+    RebaseHistoryUnavailable,
+    /// Rebase fee exceeds the whole increase of the supply
+    #[error("Rebase fee exceeds the whole increase of the supply")]
+    RebaseFeeTooHigh,
+    /// Account does not match the fee destination configured on the mint
+    #[error("Account does not match the fee destination configured on the mint")]
+    RebaseFeeDestinationMismatch,
+    /// Total shares would exceed the maximum allowed by the mint
+    #[error("Total shares would exceed the maximum allowed by the mint")]
+    RebaseSharesCapExceeded,
+    /// The mint does not allow sunsetting its supply
+    #[error("The mint does not allow sunsetting its supply")]
+    RebaseSunsetNotAllowed,
+    /// The rebasing mint has been sunset
+    #[error("The rebasing mint has been sunset")]
+    RebaseMintSunset,
+    /// The rebasing mint has been retired, freezing its ratio
+    #[error("The rebasing mint has been retired")]
+    RebaseMintRetired,
+    /// The co-signer account doesn't match the rebasing mint's co-signer
+    #[error("The co-signer doesn't match the rebasing mint's co-signer")]
+    RebaseCoSignerMismatch,
+    /// Instruction is not supported by the rebase mode of the mint
+    #[error("Instruction is not supported by the rebase mode of the mint")]
+    RebaseModeMismatch,
+    /// The base mint's supply doesn't account for the rebasing mint's supply
+    #[error("The base mint's supply doesn't account for the rebasing mint's supply")]
+    RebaseBaseSupplyMismatch,
+    /// Rebasing mint instructions are not active on this deployment
+    #[error("Rebasing mint instructions are not active on this deployment")]
+    RebaseMintNotActive,
+    /// A nonzero amount converts to zero shares at the mint's current ratio
+    #[error("Amount is too small to be represented in shares")]
+    AmountTooSmallForShares,
+    /// The rebase landed after the deadline it was signed with
+    #[error("Rebase expired")]
+    RebaseExpired,
+    /// The new supply in UI units is longer than any valid supply
+    #[error("Rebase UI supply too long")]
+    RebaseUiSupplyTooLong,
+    /// The new supply in UI units is not a plain decimal amount within the
+    /// mint's decimals and the range of a u64
+    #[error("Rebase UI supply malformed")]
+    RebaseUiSupplyMalformed,
+}
+impl TokenError {
+    /// True for the errors returned by the checks specific to rebasing mints
+    pub fn is_rebase_error(&self) -> bool {
+        matches!(
+            self,
+            Self::RebaseReserveMismatch
+                | Self::RebaseExceedsReserve
+                | Self::RebaseNotYetAllowed
+                | Self::RebaseAuthorityRequiresAcceptance
+                | Self::RebaseAuthorityProposalPending
+                | Self::NoPendingRebaseAuthority
+                | Self::RebaseAuthorityChangeNotReady
+                | Self::RebaseSupplyImmutable
+                | Self::RebaseMemoRequired
+                | Self::RebaseSupplySyncNotAllowed
+                | Self::ShareSlippageExceeded
+                | Self::RebaseBelowOutstandingShares
+                | Self::RebaseInconsistent
+                | Self::SupplyDecreaseForbidden
+                | Self::RebaseExtensionNotReallocatable
+                | Self::RebaseMintHasShares
+                | Self::RebaseSupplyZero
+                | Self::RebaseSharesOverflow
+                | Self::RebaseTooLarge
+                | Self::RebaseHistoryUnavailable
+                | Self::RebaseFeeTooHigh
+                | Self::RebaseFeeDestinationMismatch
+                | Self::RebaseSharesCapExceeded
+                | Self::RebaseSunsetNotAllowed
+                | Self::RebaseMintSunset
+                | Self::RebaseMintRetired
+                | Self::RebaseCoSignerMismatch
+                | Self::RebaseModeMismatch
+                | Self::RebaseBaseSupplyMismatch
+                | Self::RebaseMintNotActive
+                | Self::AmountTooSmallForShares
+                | Self::RebaseExpired
+                | Self::RebaseUiSupplyTooLong
+                | Self::RebaseUiSupplyMalformed
+        )
+    }
 }
 impl From<TokenError> for ProgramError {
     fn from(e: TokenError) -> Self {
@@ -418,6 +565,160 @@ impl PrintProgramError for TokenError {
             TokenError::CiphertextArithmeticFailed => {
                 msg!("Ciphertext arithmetic failed")
             }
+            TokenError::RebaseReserveMismatch => {
+                msg!("Account does not match the reserve account configured on the mint")
+            }
+            TokenError::RebaseExceedsReserve => {
+                msg!("Rebased supply exceeds the amount held in the reserve account")
+            }
+            TokenError::RebaseNotYetAllowed => {
+                msg!("Rebasing is not allowed yet")
+            }
+            TokenError::RebaseAuthorityRequiresAcceptance => {
+                msg!("Supply authority must be transferred by proposal and acceptance")
+            }
+            TokenError::RebaseAuthorityProposalPending => {
+                msg!("A supply authority proposal is already pending")
+            }
+            TokenError::NoPendingRebaseAuthority => {
+                msg!("No supply authority proposal is pending")
+            }
+            TokenError::RebaseAuthorityChangeNotReady => {
+                msg!("Supply authority change delay has not elapsed")
+            }
+            TokenError::RebaseSupplyImmutable => {
+                msg!("Supply cannot be rebased, since the mint has no supply authority")
+            }
+            TokenError::RebaseMemoRequired => {
+                msg!("Error: No memo in previous instruction; required to rebase the supply")
+            }
+            TokenError::RebaseSupplySyncNotAllowed => {
+                msg!("Supply cannot be synced from the mint once it has been rebased")
+            }
+            TokenError::ShareSlippageExceeded => {
+                msg!("Number of shares moved is outside the allowed slippage")
+            }
+            TokenError::RebaseBelowOutstandingShares => {
+                msg!("Total shares would fall below the shares held by token accounts")
+            }
+            TokenError::RebaseInconsistent => {
+                msg!("Rebasing mint extension is inconsistent with the mint")
+            }
+            TokenError::SupplyDecreaseForbidden => {
+                msg!("Supply of this mint cannot be rebased lower")
+            }
+            TokenError::RebaseExtensionNotReallocatable => {
+                msg!("Rebasing extensions cannot be added by reallocating")
+            }
+            TokenError::RebaseMintHasShares => {
+                msg!("Mint still has shares outstanding")
+            }
+            TokenError::RebaseSupplyZero => {
+                msg!("Supply cannot be rebased to zero while shares remain")
+            }
+            TokenError::RebaseSharesOverflow => {
+                msg!("Total shares would overflow")
+            }
+            TokenError::RebaseTooLarge => {
+                msg!("Rebased supply is too large")
+            }
+            TokenError::RebaseHistoryUnavailable => {
+                msg!("Timestamp predates the retained rebase history")
+            }
+            TokenError::RebaseFeeTooHigh => {
+                msg!("Rebase fee exceeds the whole increase of the supply")
+            }
+            TokenError::RebaseFeeDestinationMismatch => {
+                msg!("Account does not match the fee destination configured on the mint")
+            }
+            TokenError::RebaseSharesCapExceeded => {
+                msg!("Total shares would exceed the maximum allowed by the mint")
+            }
+            TokenError::RebaseSunsetNotAllowed => {
+                msg!("The mint does not allow sunsetting its supply")
+            }
+            TokenError::RebaseMintSunset => {
+                msg!("The rebasing mint has been sunset")
+            }
+            TokenError::RebaseMintRetired => {
+                msg!("The rebasing mint has been retired")
+            }
+            TokenError::RebaseCoSignerMismatch => {
+                msg!("The co-signer doesn't match the rebasing mint's co-signer")
+            }
+            TokenError::RebaseModeMismatch => {
+                msg!("Instruction is not supported by the rebase mode of the mint")
+            }
+            TokenError::RebaseBaseSupplyMismatch => {
+                msg!("The base mint's supply doesn't account for the rebasing mint's supply")
+            }
+            TokenError::RebaseMintNotActive => {
+                msg!("Rebasing mint instructions are not active on this deployment")
+            }
+            TokenError::AmountTooSmallForShares => {
+                msg!("Amount is too small to be represented in shares")
+            }
+            TokenError::RebaseExpired => {
+                msg!("Rebase expired")
+            }
+            TokenError::RebaseUiSupplyTooLong => {
+                msg!("Rebase UI supply too long")
+            }
+            TokenError::RebaseUiSupplyMalformed => {
+                msg!("Rebase UI supply malformed")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rebase_error_codes_are_stable() {
+        // explorers map these numbers, so they must never shift
+        let codes = [
+            (TokenError::RebaseReserveMismatch, 61),
+            (TokenError::RebaseExceedsReserve, 62),
+            (TokenError::RebaseNotYetAllowed, 63),
+            (TokenError::RebaseAuthorityRequiresAcceptance, 64),
+            (TokenError::RebaseAuthorityProposalPending, 65),
+            (TokenError::NoPendingRebaseAuthority, 66),
+            (TokenError::RebaseAuthorityChangeNotReady, 67),
+            (TokenError::RebaseSupplyImmutable, 68),
+            (TokenError::RebaseMemoRequired, 69),
+            (TokenError::RebaseSupplySyncNotAllowed, 70),
+            (TokenError::ShareSlippageExceeded, 71),
+            (TokenError::RebaseBelowOutstandingShares, 72),
+            (TokenError::RebaseInconsistent, 73),
+            (TokenError::SupplyDecreaseForbidden, 74),
+            (TokenError::RebaseExtensionNotReallocatable, 75),
+            (TokenError::RebaseMintHasShares, 76),
+            (TokenError::RebaseSupplyZero, 77),
+            (TokenError::RebaseSharesOverflow, 78),
+            (TokenError::RebaseTooLarge, 79),
+            (TokenError::RebaseHistoryUnavailable, 80),
+            (TokenError::RebaseFeeTooHigh, 81),
+            (TokenError::RebaseFeeDestinationMismatch, 82),
+            (TokenError::RebaseSharesCapExceeded, 83),
+            (TokenError::RebaseSunsetNotAllowed, 84),
+            (TokenError::RebaseMintSunset, 85),
+            (TokenError::RebaseMintRetired, 86),
+            (TokenError::RebaseCoSignerMismatch, 87),
+            (TokenError::RebaseModeMismatch, 88),
+            (TokenError::RebaseBaseSupplyMismatch, 89),
+            (TokenError::RebaseMintNotActive, 90),
+            (TokenError::AmountTooSmallForShares, 91),
+            (TokenError::RebaseExpired, 92),
+            (TokenError::RebaseUiSupplyTooLong, 93),
+            (TokenError::RebaseUiSupplyMalformed, 94),
+        ];
+        for (error, code) in codes {
+            assert!(error.is_rebase_error());
+            assert_eq!(ProgramError::from(error), ProgramError::Custom(code));
         }
+        assert!(!TokenError::OwnerMismatch.is_rebase_error());
+        assert!(!TokenError::CiphertextArithmeticFailed.is_rebase_error());
     }
 }
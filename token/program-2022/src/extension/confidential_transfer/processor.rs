@@ -17,6 +17,7 @@ use {
                 EncryptedWithheldAmount,
             },
             memo_transfer::{check_previous_sibling_instruction_is_memo, memo_required},
+            rebase_mint::RebaseMintConfig,
             transfer_fee::TransferFeeConfig,
             BaseStateWithExtensions, StateWithExtensions, StateWithExtensionsMut,
         },
@@ -35,6 +36,17 @@ use {
     },
 };
 
+/// Confidential balances are encrypted over fixed amounts, which a rebase
+/// can't rescale, so a rebasing mint never holds them. Mint initialization
+/// rejects the combination; this guards against any such mint anyway.
+fn check_not_rebasing(mint: &StateWithExtensions<Mint>) -> ProgramResult {
+    if mint.get_extension::<RebaseMintConfig>().is_ok() {
+        msg!("Confidential transfers are not supported for rebasing mints");
+        return Err(TokenError::InvalidExtensionCombination.into());
+    }
+    Ok(())
+}
+
 /// Processes an [InitializeMint] instruction.
 fn process_initialize_mint(
     accounts: &[AccountInfo],
@@ -127,6 +139,7 @@ fn process_configure_account(
     check_program_account(mint_info.owner)?;
     let mint_data = &mut mint_info.data.borrow();
     let mint = StateWithExtensions::<Mint>::unpack(mint_data)?;
+    check_not_rebasing(&mint)?;
     let confidential_transfer_mint = mint.get_extension::<ConfidentialTransferMint>()?;
 
     // Note: The caller is expected to use the `Reallocate` instruction to ensure
@@ -263,6 +276,7 @@ fn process_deposit(
     check_program_account(mint_info.owner)?;
     let mint_data = &mint_info.data.borrow_mut();
     let mint = StateWithExtensions::<Mint>::unpack(mint_data)?;
+    check_not_rebasing(&mint)?;
 
     if expected_decimals != mint.base.decimals {
         return Err(TokenError::MintDecimalsMismatch.into());
@@ -363,6 +377,7 @@ fn process_withdraw(
     check_program_account(mint_info.owner)?;
     let mint_data = &mint_info.data.borrow_mut();
     let mint = StateWithExtensions::<Mint>::unpack(mint_data)?;
+    check_not_rebasing(&mint)?;
 
     if expected_decimals != mint.base.decimals {
         return Err(TokenError::MintDecimalsMismatch.into());
@@ -452,6 +467,7 @@ fn process_transfer(
     check_program_account(mint_info.owner)?;
     let mint_data = mint_info.data.borrow_mut();
     let mint = StateWithExtensions::<Mint>::unpack(&mint_data)?;
+    check_not_rebasing(&mint)?;
 
     if mint.get_extension::<NonTransferable>().is_ok() {
         return Err(TokenError::NonTransferable.into());
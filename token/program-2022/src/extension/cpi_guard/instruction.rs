@@ -26,6 +26,9 @@ pub enum CpiGuardInstruction {
     /// * SetAuthority can only be used to remove an existing close authority.
     /// * Approve is disallowed entirely.
     ///
+    /// The share variants of these on a rebasing mint, such as `TransferShares`,
+    /// `BurnShares` and `ApproveShares`, are locked in the same way.
+    ///
     /// In addition, CPI Guard cannot be enabled or disabled via CPI.
     ///
     /// Accounts expected by this instruction:
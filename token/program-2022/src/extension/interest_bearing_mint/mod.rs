@@ -73,7 +73,7 @@ impl InterestBearingConfig {
         Some(exponent.exp())
     }
 
-    fn total_scale(&self, decimals: u8, unix_timestamp: i64) -> Option<f64> {
+    pub(crate) fn total_scale(&self, decimals: u8, unix_timestamp: i64) -> Option<f64> {
         Some(
             self.pre_update_exp()? * self.post_update_exp(unix_timestamp)?
                 / 10_f64.powi(decimals as i32),
@@ -21,6 +21,7 @@ use {
             mint_close_authority::MintCloseAuthority,
             non_transferable::{NonTransferable, NonTransferableAccount},
             permanent_delegate::PermanentDelegate,
+            rebase_mint::{LazyRebaseAccount, RebaseAccount, RebaseHistory, RebaseMintConfig},
             transfer_fee::{TransferFeeAmount, TransferFeeConfig},
             transfer_hook::{TransferHook, TransferHookAccount},
         },
@@ -74,6 +75,8 @@ pub mod non_transferable;
 pub mod permanent_delegate;
 /// Utility to reallocate token accounts
 pub mod reallocate;
+/// Rebasing Mint extension
+pub mod rebase_mint;
 /// Token-group extension
 pub mod token_group;
 /// Token-metadata extension
@@ -818,6 +821,10 @@ impl<'data, S: BaseState> StateWithExtensionsMut<'data, S> {
             ExtensionType::TransferHookAccount => {
                 self.init_extension::<TransferHookAccount>(true).map(|_| ())
             }
+            ExtensionType::RebaseAccount => self.init_extension::<RebaseAccount>(true).map(|_| ()),
+            ExtensionType::LazyRebaseAccount => {
+                self.init_extension::<LazyRebaseAccount>(true).map(|_| ())
+            }
             // ConfidentialTransfers are currently opt-in only, so this is a no-op for extra safety
             // on InitializeAccount
             ExtensionType::ConfidentialTransferAccount => Ok(()),
@@ -961,6 +968,14 @@ pub enum ExtensionType {
     GroupMemberPointer,
     /// Mint contains token group member configurations
     TokenGroupMember,
+    /// Mint balances are shares of a supply that can be rebased
+    RebaseMintConfig,
+    /// Tracks the shares held by an account of a rebasing mint
+    RebaseAccount,
+    /// Keeps the last rebases of a rebasing mint
+    RebaseHistory,
+    /// Tracks the rebase index an account of a lazy rebasing mint caught up to
+    LazyRebaseAccount,
     /// Test variable-length mint extension
     #[cfg(test)]
     VariableLenMintTest = u16::MAX - 2,
@@ -1041,6 +1056,10 @@ impl ExtensionType {
             ExtensionType::TokenGroup => pod_get_packed_len::<TokenGroup>(),
             ExtensionType::GroupMemberPointer => pod_get_packed_len::<GroupMemberPointer>(),
             ExtensionType::TokenGroupMember => pod_get_packed_len::<TokenGroupMember>(),
+            ExtensionType::RebaseMintConfig => pod_get_packed_len::<RebaseMintConfig>(),
+            ExtensionType::RebaseAccount => pod_get_packed_len::<RebaseAccount>(),
+            ExtensionType::RebaseHistory => pod_get_packed_len::<RebaseHistory>(),
+            ExtensionType::LazyRebaseAccount => pod_get_packed_len::<LazyRebaseAccount>(),
             #[cfg(test)]
             ExtensionType::AccountPaddingTest => pod_get_packed_len::<AccountPaddingTest>(),
             #[cfg(test)]
@@ -1104,7 +1123,9 @@ impl ExtensionType {
             | ExtensionType::GroupPointer
             | ExtensionType::TokenGroup
             | ExtensionType::GroupMemberPointer
-            | ExtensionType::TokenGroupMember => AccountType::Mint,
+            | ExtensionType::TokenGroupMember
+            | ExtensionType::RebaseMintConfig
+            | ExtensionType::RebaseHistory => AccountType::Mint,
             ExtensionType::ImmutableOwner
             | ExtensionType::TransferFeeAmount
             | ExtensionType::ConfidentialTransferAccount
@@ -1112,7 +1133,9 @@ impl ExtensionType {
             | ExtensionType::NonTransferableAccount
             | ExtensionType::TransferHookAccount
             | ExtensionType::CpiGuard
-            | ExtensionType::ConfidentialTransferFeeAmount => AccountType::Account,
+            | ExtensionType::ConfidentialTransferFeeAmount
+            | ExtensionType::RebaseAccount
+            | ExtensionType::LazyRebaseAccount => AccountType::Account,
             #[cfg(test)]
             ExtensionType::VariableLenMintTest => AccountType::Mint,
             #[cfg(test)]
@@ -1137,6 +1160,9 @@ impl ExtensionType {
                 ExtensionType::TransferHook => {
                     account_extension_types.push(ExtensionType::TransferHookAccount);
                 }
+                ExtensionType::RebaseMintConfig => {
+                    account_extension_types.push(ExtensionType::RebaseAccount);
+                }
                 #[cfg(test)]
                 ExtensionType::MintPaddingTest => {
                     account_extension_types.push(ExtensionType::AccountPaddingTest);
@@ -1154,6 +1180,9 @@ impl ExtensionType {
         let mut transfer_fee_config = false;
         let mut confidential_transfer_mint = false;
         let mut confidential_transfer_fee_config = false;
+        let mut rebase_mint_config = false;
+        let mut rebase_history = false;
+        let mut interest_bearing_config = false;
 
         for extension_type in mint_extension_types {
             match extension_type {
@@ -1162,6 +1191,9 @@ impl ExtensionType {
                 ExtensionType::ConfidentialTransferFeeConfig => {
                     confidential_transfer_fee_config = true
                 }
+                ExtensionType::RebaseMintConfig => rebase_mint_config = true,
+                ExtensionType::RebaseHistory => rebase_history = true,
+                ExtensionType::InterestBearingConfig => interest_bearing_config = true,
                 _ => (),
             }
         }
@@ -1175,6 +1207,23 @@ impl ExtensionType {
             return Err(TokenError::InvalidExtensionCombination);
         }
 
+        // confidential balances are denominated in tokens, so they can't be
+        // kept in sync with balances denominated in shares
+        if rebase_mint_config && (confidential_transfer_mint || confidential_transfer_fee_config) {
+            return Err(TokenError::InvalidExtensionCombination);
+        }
+
+        // both scale the UI amount, and there's no meaningful way to compose
+        // interest with a rebase
+        if rebase_mint_config && interest_bearing_config {
+            return Err(TokenError::InvalidExtensionCombination);
+        }
+
+        // there's no history without rebases
+        if rebase_history && !rebase_mint_config {
+            return Err(TokenError::InvalidExtensionCombination);
+        }
+
         Ok(())
     }
 }
@@ -2142,6 +2191,141 @@ mod test {
                 ExtensionType::TransferFeeAmount
             ]
         );
+
+        // Rebasing mints need accounts to track shares
+        let mint_extensions = vec![
+            ExtensionType::RebaseMintConfig,
+            ExtensionType::MintCloseAuthority,
+        ];
+        assert_eq!(
+            ExtensionType::get_required_init_account_extensions(&mint_extensions),
+            vec![ExtensionType::RebaseAccount]
+        );
+    }
+
+    #[test]
+    fn test_rebase_mint_extension_combinations() {
+        assert_eq!(
+            ExtensionType::check_for_invalid_mint_extension_combinations(&[
+                ExtensionType::RebaseMintConfig,
+                ExtensionType::MintCloseAuthority,
+            ]),
+            Ok(())
+        );
+        assert_eq!(
+            ExtensionType::check_for_invalid_mint_extension_combinations(&[
+                ExtensionType::RebaseMintConfig,
+                ExtensionType::TransferFeeConfig,
+            ]),
+            Ok(())
+        );
+        assert_eq!(
+            ExtensionType::check_for_invalid_mint_extension_combinations(&[
+                ExtensionType::GroupMemberPointer,
+                ExtensionType::RebaseMintConfig,
+                ExtensionType::GroupPointer,
+            ]),
+            Ok(())
+        );
+        for extension_type in [
+            ExtensionType::ConfidentialTransferMint,
+            ExtensionType::ConfidentialTransferFeeConfig,
+            ExtensionType::InterestBearingConfig,
+        ] {
+            assert_eq!(
+                ExtensionType::check_for_invalid_mint_extension_combinations(&[
+                    ExtensionType::RebaseMintConfig,
+                    extension_type,
+                ]),
+                Err(TokenError::InvalidExtensionCombination)
+            );
+        }
+        assert_eq!(
+            ExtensionType::check_for_invalid_mint_extension_combinations(&[
+                ExtensionType::RebaseHistory,
+                ExtensionType::RebaseMintConfig,
+            ]),
+            Ok(())
+        );
+        assert_eq!(
+            ExtensionType::check_for_invalid_mint_extension_combinations(&[
+                ExtensionType::RebaseHistory
+            ]),
+            Err(TokenError::InvalidExtensionCombination)
+        );
+    }
+
+    #[test]
+    fn rebase_mint_len_any_order() {
+        let mint_extensions = [
+            ExtensionType::RebaseMintConfig,
+            ExtensionType::MintCloseAuthority,
+            ExtensionType::PermanentDelegate,
+        ];
+        let mint_size = ExtensionType::try_calculate_account_len::<Mint>(&mint_extensions).unwrap();
+        assert_eq!(
+            mint_size,
+            BASE_ACCOUNT_AND_TYPE_LENGTH
+                + add_type_and_length_to_len(size_of::<RebaseMintConfig>())
+                + add_type_and_length_to_len(size_of::<MintCloseAuthority>())
+                + add_type_and_length_to_len(size_of::<PermanentDelegate>())
+        );
+
+        let orders = [
+            [0, 1, 2],
+            [0, 2, 1],
+            [1, 0, 2],
+            [1, 2, 0],
+            [2, 0, 1],
+            [2, 1, 0],
+        ];
+        for order in orders {
+            let mut buffer = vec![0; mint_size];
+            let mut state =
+                StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut buffer).unwrap();
+            for index in order {
+                match mint_extensions[index] {
+                    ExtensionType::RebaseMintConfig => {
+                        state.init_extension::<RebaseMintConfig>(true).unwrap();
+                    }
+                    ExtensionType::MintCloseAuthority => {
+                        state.init_extension::<MintCloseAuthority>(true).unwrap();
+                    }
+                    ExtensionType::PermanentDelegate => {
+                        state.init_extension::<PermanentDelegate>(true).unwrap();
+                    }
+                    _ => unreachable!(),
+                }
+            }
+            state.base = TEST_MINT;
+            state.pack_base();
+            state.init_account_type().unwrap();
+
+            // the buffer is used exactly
+            let state = StateWithExtensions::<Mint>::unpack(&buffer).unwrap();
+            assert_eq!(state.try_get_account_len().unwrap(), mint_size);
+            assert_eq!(
+                state.get_extension_types().unwrap(),
+                order.map(|index| mint_extensions[index])
+            );
+        }
+
+        // the accounts of a rebasing mint hold shares
+        let account_extensions =
+            ExtensionType::get_required_init_account_extensions(&mint_extensions);
+        assert_eq!(account_extensions, vec![ExtensionType::RebaseAccount]);
+        let account_size =
+            ExtensionType::try_calculate_account_len::<Account>(&account_extensions).unwrap();
+        let mut buffer = vec![0; account_size];
+        let mut state =
+            StateWithExtensionsMut::<Account>::unpack_uninitialized(&mut buffer).unwrap();
+        state.init_extension::<RebaseAccount>(true).unwrap();
+
+        // but can't hold the mint's config
+        assert_eq!(
+            state.init_extension::<RebaseMintConfig>(true),
+            Err(ProgramError::InvalidAccountData),
+        );
     }
 
     #[test]
@@ -48,6 +48,25 @@ pub fn process_reallocate(
         (account.get_extension_types()?, native_token_amount)
     };
 
+    // the rebasing extensions can't be retrofitted: the mint config is only
+    // set before `InitializeMint`, and an account of a rebasing mint gets its
+    // shares or rebase index during `InitializeAccount`, so requesting them is
+    // only a no-op
+    if new_extension_types.contains(&ExtensionType::RebaseMintConfig)
+        || [
+            ExtensionType::RebaseAccount,
+            ExtensionType::LazyRebaseAccount,
+        ]
+        .iter()
+        .any(|extension_type| {
+            new_extension_types.contains(extension_type)
+                && !current_extension_types.contains(extension_type)
+        })
+    {
+        msg!("Rebasing extensions can only be set during initialization");
+        return Err(TokenError::RebaseExtensionNotReallocatable.into());
+    }
+
     // check that all desired extensions are for the right account type
     if new_extension_types
         .iter()
@@ -0,0 +1,135 @@
+//! Staged activation of the `RebaseMintExtension` instructions.
+//!
+//! Clusters that want to deploy the program before turning on rebasing can
+//! either build it without the `rebase-mint` feature, which rejects every
+//! `RebaseMintExtension` instruction, or set `REBASE_MINT_ACTIVATION_EPOCH`
+//! in the environment of the build, which rejects them until the cluster
+//! reaches that epoch.
+//!
+//! Only these instructions are gated. Mints, burns and transfers of rebasing
+//! mints created while they were active go through the base instructions, so
+//! they keep converting through the shares either way.
+
+use {
+    crate::error::TokenError,
+    solana_program::{clock::Clock, entrypoint::ProgramResult, msg, sysvar::Sysvar},
+};
+
+/// Epoch from which the rebasing mint instructions are processed, taken from
+/// `REBASE_MINT_ACTIVATION_EPOCH` at build time, or zero if unset
+pub const REBASE_MINT_ACTIVATION_EPOCH: u64 =
+    parse_epoch(option_env!("REBASE_MINT_ACTIVATION_EPOCH"));
+
+/// Parse a decimal epoch at compile time, so that a typo fails the build
+/// rather than leaving the instructions gated forever
+const fn parse_epoch(epoch: Option<&str>) -> u64 {
+    let digits = match epoch {
+        Some(epoch) => epoch.as_bytes(),
+        None => return 0,
+    };
+    assert!(
+        !digits.is_empty(),
+        "REBASE_MINT_ACTIVATION_EPOCH must not be empty"
+    );
+    let mut value: u64 = 0;
+    let mut i = 0;
+    while i < digits.len() {
+        let digit = digits[i];
+        assert!(
+            digit.is_ascii_digit(),
+            "REBASE_MINT_ACTIVATION_EPOCH must be a decimal number"
+        );
+        value = match value.checked_mul(10) {
+            Some(value) => match value.checked_add((digit - b'0') as u64) {
+                Some(value) => value,
+                None => panic!("REBASE_MINT_ACTIVATION_EPOCH must fit in a u64"),
+            },
+            None => panic!("REBASE_MINT_ACTIVATION_EPOCH must fit in a u64"),
+        };
+        i += 1;
+    }
+    value
+}
+
+/// Whether a build with the `rebase-mint` feature set to `enabled`, activating
+/// the instructions at `activation_epoch`, processes them during `epoch`
+pub fn is_active_at(enabled: bool, activation_epoch: u64, epoch: u64) -> bool {
+    enabled && epoch >= activation_epoch
+}
+
+/// Check that this build processes the rebasing mint instructions in the
+/// current epoch
+pub fn check_active() -> ProgramResult {
+    // builds active from genesis don't pay for the clock
+    let epoch = if REBASE_MINT_ACTIVATION_EPOCH == 0 {
+        0
+    } else {
+        Clock::get()?.epoch
+    };
+    if is_active_at(
+        cfg!(feature = "rebase-mint"),
+        REBASE_MINT_ACTIVATION_EPOCH,
+        epoch,
+    ) {
+        Ok(())
+    } else {
+        if cfg!(feature = "rebase-mint") {
+            msg!(
+                "Rebasing mint instructions activate at epoch {}",
+                REBASE_MINT_ACTIVATION_EPOCH
+            );
+        } else {
+            msg!("The program was built without the rebase-mint feature");
+        }
+        Err(TokenError::RebaseMintNotActive.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_activation_epoch() {
+        assert_eq!(parse_epoch(None), 0);
+        assert_eq!(parse_epoch(Some("0")), 0);
+        assert_eq!(parse_epoch(Some("512")), 512);
+        assert_eq!(parse_epoch(Some("18446744073709551615")), u64::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "decimal number")]
+    fn parse_invalid_activation_epoch() {
+        parse_epoch(Some("12a"));
+    }
+
+    #[test]
+    #[should_panic(expected = "fit in a u64")]
+    fn parse_overflowing_activation_epoch() {
+        parse_epoch(Some("18446744073709551616"));
+    }
+
+    #[test]
+    fn activation() {
+        assert!(is_active_at(true, 0, 0));
+        assert!(!is_active_at(true, 10, 9));
+        assert!(is_active_at(true, 10, 10));
+        assert!(is_active_at(true, 10, 11));
+        // the feature gates the instructions whatever the epoch
+        assert!(!is_active_at(false, 0, 0));
+        assert!(!is_active_at(false, 10, 11));
+    }
+
+    #[test]
+    fn gated_by_feature() {
+        // the clock sysvar isn't available here
+        if REBASE_MINT_ACTIVATION_EPOCH != 0 {
+            return;
+        }
+        if cfg!(feature = "rebase-mint") {
+            assert_eq!(check_active(), Ok(()));
+        } else {
+            assert_eq!(check_active(), Err(TokenError::RebaseMintNotActive.into()));
+        }
+    }
+}
@@ -0,0 +1,173 @@
+//! Off-chain helpers to estimate the yield of a rebasing mint from its recent
+//! rebases, as recorded in its `RebaseHistory`, rather than from a promised
+//! rate.
+//!
+//! Each rebase scales the ratio by `new_supply / old_supply`, so the yield is
+//! the one accrued by the total supply, before any rebase fee.
+
+use {
+    crate::extension::rebase_mint::RebaseHistoryEntry,
+    std::{convert::TryFrom, time::Duration},
+};
+
+/// Seconds in a year, as used by the interest-bearing mint
+pub const SECONDS_PER_YEAR: f64 = 60. * 60. * 24. * 365.24;
+
+/// Growth of the ratio over the rebases recorded within `window` of the
+/// latest one, and the years it took, or `None` if fewer than two rebases at
+/// different times fall inside the window.
+///
+/// The growth runs from the oldest rebase in the window to the latest, so the
+/// oldest only marks the start and its own change isn't counted. Rebases of a
+/// zero supply didn't change any holder's ratio, and are skipped.
+fn growth_over_window(history: &[RebaseHistoryEntry], window: Duration) -> Option<(f64, f64)> {
+    let end = i64::from(history.last()?.unix_timestamp);
+    let window = i64::try_from(window.as_secs()).unwrap_or(i64::MAX);
+    let start = end.saturating_sub(window);
+    let mut in_window = history
+        .iter()
+        .skip_while(|entry| i64::from(entry.unix_timestamp) < start);
+    let first = i64::from(in_window.next()?.unix_timestamp);
+    let elapsed = end.checked_sub(first).filter(|elapsed| *elapsed > 0)?;
+
+    let growth = in_window
+        .filter(|entry| u64::from(entry.old_supply) != 0)
+        .map(|entry| u64::from(entry.new_supply) as f64 / u64::from(entry.old_supply) as f64)
+        .product::<f64>();
+    Some((growth, elapsed as f64 / SECONDS_PER_YEAR))
+}
+
+/// Estimate the annual percentage yield of a rebasing mint from its rebases
+/// within `window` of the latest one, compounding the growth of the ratio
+/// over the window for a year.
+///
+/// The rebases must be oldest first, as returned by `RebaseHistory::entries`.
+/// Gaps between them are fine, since the growth is annualized over the time
+/// it actually took. Downward rebases give a negative yield, down to `-1.`
+/// for a supply rebased to zero.
+///
+/// Returns the yield as a fraction, such as `0.05` for 5%, or `None` if fewer
+/// than two rebases at different times fall inside the window.
+pub fn estimate_apy(history: &[RebaseHistoryEntry], window: Duration) -> Option<f64> {
+    let (growth, years) = growth_over_window(history, window)?;
+    Some(growth.powf(years.recip()) - 1.)
+}
+
+/// Estimate the annual percentage rate of a rebasing mint from its rebases
+/// within `window` of the latest one, scaling the growth of the ratio over the
+/// window linearly to a year, without compounding.
+///
+/// Takes the same arguments and has the same limits as `estimate_apy`.
+pub fn estimate_apr(history: &[RebaseHistoryEntry], window: Duration) -> Option<f64> {
+    let (growth, years) = growth_over_window(history, window)?;
+    Some((growth - 1.) / years)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const YEAR: i64 = SECONDS_PER_YEAR as i64;
+    const WINDOW: Duration = Duration::from_secs(YEAR as u64);
+
+    fn entry(unix_timestamp: i64, old_supply: u64, new_supply: u64) -> RebaseHistoryEntry {
+        RebaseHistoryEntry {
+            unix_timestamp: unix_timestamp.into(),
+            old_supply: old_supply.into(),
+            new_supply: new_supply.into(),
+            ..Default::default()
+        }
+    }
+
+    fn assert_close(actual: Option<f64>, expected: f64) {
+        let actual = actual.unwrap();
+        assert!(
+            (actual - expected).abs() <= 1e-12,
+            "{} vs {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn half_year_compounds() {
+        let history = [entry(0, 100, 1_000), entry(YEAR / 2, 1_000, 1_050)];
+        // 5% in half a year
+        assert_close(estimate_apr(&history, WINDOW), 0.10);
+        assert_close(estimate_apy(&history, WINDOW), 0.1025);
+    }
+
+    #[test]
+    fn gaps_are_annualized_over_elapsed_time() {
+        // 2% in the first quarter, then nothing for half a year, then 3%
+        let history = [
+            entry(0, 100_000, 100_000),
+            entry(YEAR / 4, 100_000, 102_000),
+            entry(YEAR, 102_000, 105_060),
+        ];
+        assert_close(estimate_apr(&history, WINDOW), 0.0506);
+        assert_close(estimate_apy(&history, WINDOW), 0.0506);
+    }
+
+    #[test]
+    fn downward_rebases_are_negative() {
+        let history = [entry(0, 1_000, 1_000), entry(YEAR / 2, 1_000, 950)];
+        assert_close(estimate_apr(&history, WINDOW), -0.10);
+        assert_close(estimate_apy(&history, WINDOW), -0.0975);
+
+        let history = [entry(0, 1_000, 1_000), entry(YEAR / 2, 1_000, 0)];
+        assert_close(estimate_apr(&history, WINDOW), -2.);
+        assert_close(estimate_apy(&history, WINDOW), -1.);
+    }
+
+    #[test]
+    fn only_rebases_inside_the_window_count() {
+        let history = [
+            entry(0, 1_000, 1_000),
+            // doubles just before the window opens
+            entry(YEAR / 2 - 1, 1_000, 2_000),
+            entry(YEAR / 2, 2_000, 2_000),
+            entry(YEAR, 2_000, 2_100),
+            entry(YEAR * 3 / 2, 2_100, 2_205),
+        ];
+        assert_close(estimate_apr(&history, WINDOW), 0.1025);
+        assert_close(estimate_apy(&history, WINDOW), 0.1025);
+
+        // a longer window reaches the doubling
+        let window = Duration::from_secs(YEAR as u64 * 2);
+        let growth = 2. * 1.1025;
+        let years = (YEAR * 3 / 2) as f64 / SECONDS_PER_YEAR;
+        assert_close(estimate_apr(&history, window), (growth - 1.) / years);
+        assert_close(
+            estimate_apy(&history, window),
+            growth.powf(years.recip()) - 1.,
+        );
+    }
+
+    #[test]
+    fn zero_supply_rebases_are_skipped() {
+        let history = [
+            entry(0, 0, 0),
+            entry(YEAR / 4, 0, 1_000),
+            entry(YEAR / 2, 1_000, 1_050),
+        ];
+        assert_close(estimate_apr(&history, WINDOW), 0.10);
+    }
+
+    #[test]
+    fn fewer_than_two_rebases() {
+        assert_eq!(estimate_apy(&[], WINDOW), None);
+        assert_eq!(estimate_apr(&[], WINDOW), None);
+        assert_eq!(estimate_apy(&[entry(0, 1_000, 2_000)], WINDOW), None);
+
+        // the older rebase falls outside the window
+        let history = [entry(0, 1_000, 1_000), entry(YEAR * 2, 1_000, 2_000)];
+        assert_eq!(estimate_apy(&history, WINDOW), None);
+        assert_eq!(estimate_apr(&history, WINDOW), None);
+
+        // no time elapsed between them
+        let history = [entry(YEAR, 1_000, 1_000), entry(YEAR, 1_000, 2_000)];
+        assert_eq!(estimate_apy(&history, WINDOW), None);
+        assert_eq!(estimate_apr(&history, WINDOW), None);
+    }
+}
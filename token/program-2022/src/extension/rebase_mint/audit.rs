@@ -0,0 +1,226 @@
+//! Invariant assertions run after every successful instruction in builds with
+//! the `audit-assertions` feature, so that a corrupted rebasing mint fails at
+//! the instruction that corrupted it
+
+use {
+    crate::{
+        extension::{
+            rebase_mint::{RebaseAccount, RebaseMintConfig},
+            BaseStateWithExtensions, StateWithExtensions,
+        },
+        state::{Account, Mint},
+    },
+    solana_program::{account_info::AccountInfo, pubkey::Pubkey},
+    std::collections::HashMap,
+};
+
+/// Totals of the rebasing token accounts of one mint seen by an instruction
+#[derive(Default)]
+struct AccountTotals {
+    shares: u64,
+    exempt_amount: u64,
+}
+
+/// Assert the invariants of every rebasing mint among `accounts`, and of the
+/// rebasing token accounts of those mints.
+///
+/// Besides the checks of `AssertRebaseConsistency`, the shares held by the
+/// token accounts can't exceed the outstanding shares, nor their exempt
+/// amounts the exempt supply.
+///
+/// # Panics
+///
+/// Panics if any invariant doesn't hold.
+pub fn assert_invariants(program_id: &Pubkey, accounts: &[AccountInfo]) {
+    let mut mints = HashMap::new();
+    let mut totals = HashMap::<Pubkey, AccountTotals>::new();
+    let mut seen = vec![];
+    for account_info in accounts {
+        if account_info.owner != program_id || seen.contains(account_info.key) {
+            continue;
+        }
+        seen.push(*account_info.key);
+        let Ok(data) = account_info.try_borrow_data() else {
+            continue;
+        };
+
+        if let Ok(mint) = StateWithExtensions::<Mint>::unpack(&data) {
+            if let Ok(config) = mint.get_extension::<RebaseMintConfig>() {
+                if let Err(error) = config.check_consistency(mint.base.supply) {
+                    panic!(
+                        "rebasing mint {} is inconsistent: {:?}",
+                        account_info.key, error
+                    );
+                }
+                mints.insert(*account_info.key, *config);
+            }
+        } else if let Ok(account) = StateWithExtensions::<Account>::unpack(&data) {
+            if let Ok(extension) = account.get_extension::<RebaseAccount>() {
+                let account_totals = totals.entry(account.base.mint).or_default();
+                if extension.is_exempt() {
+                    account_totals.exempt_amount = account_totals
+                        .exempt_amount
+                        .saturating_add(account.base.amount);
+                } else {
+                    account_totals.shares = account_totals
+                        .shares
+                        .saturating_add(u64::from(extension.shares));
+                }
+            }
+        }
+    }
+
+    for (mint, account_totals) in totals {
+        let Some(config) = mints.get(&mint) else {
+            continue;
+        };
+        let outstanding_shares = u64::from(config.outstanding_shares);
+        assert!(
+            account_totals.shares <= outstanding_shares,
+            "token accounts of rebasing mint {} hold {} shares, more than the {} outstanding",
+            mint,
+            account_totals.shares,
+            outstanding_shares
+        );
+        let exempt_supply = u64::from(config.exempt_supply);
+        assert!(
+            account_totals.exempt_amount <= exempt_supply,
+            "exempt token accounts of rebasing mint {} hold {}, more than the exempt supply {}",
+            mint,
+            account_totals.exempt_amount,
+            exempt_supply
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            extension::{ExtensionType, StateWithExtensionsMut},
+            state::AccountState,
+        },
+        solana_program::clock::Epoch,
+    };
+
+    fn config(total_supply: u64, total_shares: u64, outstanding_shares: u64) -> RebaseMintConfig {
+        RebaseMintConfig {
+            total_supply: total_supply.into(),
+            total_shares: total_shares.into(),
+            outstanding_shares: outstanding_shares.into(),
+            ..RebaseMintConfig::default()
+        }
+    }
+
+    fn mint_data(config: RebaseMintConfig, supply: u64) -> Vec<u8> {
+        let mut data = vec![
+            0;
+            ExtensionType::try_calculate_account_len::<Mint>(&[
+                ExtensionType::RebaseMintConfig
+            ])
+            .unwrap()
+        ];
+        let mut mint = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+        *mint.init_extension::<RebaseMintConfig>(true).unwrap() = config;
+        mint.base.supply = supply;
+        mint.base.is_initialized = true;
+        mint.pack_base();
+        mint.init_account_type().unwrap();
+        data
+    }
+
+    fn account_data(mint: Pubkey, amount: u64, shares: u64, exempt: bool) -> Vec<u8> {
+        let mut data = vec![
+            0;
+            ExtensionType::try_calculate_account_len::<Account>(&[
+                ExtensionType::RebaseAccount
+            ])
+            .unwrap()
+        ];
+        let mut account =
+            StateWithExtensionsMut::<Account>::unpack_uninitialized(&mut data).unwrap();
+        *account.init_extension::<RebaseAccount>(true).unwrap() = RebaseAccount {
+            shares: shares.into(),
+            rebase_exempt: exempt.into(),
+        };
+        account.base.mint = mint;
+        account.base.owner = Pubkey::new_unique();
+        account.base.amount = amount;
+        account.base.state = AccountState::Initialized;
+        account.pack_base();
+        account.init_account_type().unwrap();
+        data
+    }
+
+    /// Assert the invariants of a mint and its token accounts, each of which
+    /// is passed twice, as instructions may do
+    fn check(config: RebaseMintConfig, supply: u64, accounts: &[(u64, u64, bool)]) {
+        let program_id = crate::id();
+        let mint = Pubkey::new_unique();
+        let mut keys = vec![mint];
+        let mut datas = vec![mint_data(config, supply)];
+        for (amount, shares, exempt) in accounts {
+            keys.push(Pubkey::new_unique());
+            datas.push(account_data(mint, *amount, *shares, *exempt));
+        }
+        let mut lamports = vec![0; keys.len()];
+        let account_infos = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(datas.iter_mut())
+            .map(|((key, lamports), data)| {
+                AccountInfo::new(
+                    key,
+                    false,
+                    true,
+                    lamports,
+                    data,
+                    &program_id,
+                    false,
+                    Epoch::default(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let account_infos = account_infos
+            .iter()
+            .chain(account_infos.iter())
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_invariants(&program_id, &account_infos);
+    }
+
+    #[test]
+    fn consistent() {
+        check(
+            RebaseMintConfig {
+                exempt_supply: 500.into(),
+                ..config(2_000, 1_000, 1_000)
+            },
+            1_500,
+            &[(0, 600, false), (0, 400, false), (500, 0, true)],
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "is inconsistent")]
+    fn dust_without_shares() {
+        check(config(1, 0, 0), 0, &[]);
+    }
+
+    #[test]
+    #[should_panic(expected = "more than the 1000 outstanding")]
+    fn shares_above_outstanding() {
+        check(
+            config(2_000, 1_000, 1_000),
+            1_000,
+            &[(0, 600, false), (0, 401, false)],
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "more than the exempt supply 0")]
+    fn exempt_amount_above_exempt_supply() {
+        check(config(2_000, 1_000, 1_000), 1_000, &[(1, 0, true)]);
+    }
+}
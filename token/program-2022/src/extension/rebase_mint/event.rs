@@ -0,0 +1,518 @@
+//! Typed events logged with `sol_log_data` by the rebasing mint instructions
+//! that change its configuration, and by rebases.
+//!
+//! Each event is logged as three fields: its one-byte `RebaseEventType`, a
+//! `RebaseEventHeader`, then the event itself. `SupplyRebased` and
+//! `SupplySynced` predate this format and are still logged as a single bare
+//! field, so that existing indexers keep parsing them.
+
+use {
+    bytemuck::{Pod, Zeroable},
+    num_enum::{IntoPrimitive, TryFromPrimitive},
+    solana_program::{
+        clock::Clock, entrypoint::ProgramResult, log::sol_log_data, program_error::ProgramError,
+        pubkey::Pubkey, sysvar::Sysvar,
+    },
+    spl_pod::{
+        optional_keys::OptionalNonZeroPubkey,
+        primitives::{PodI64, PodU128, PodU16, PodU32, PodU64},
+    },
+    std::convert::TryFrom,
+};
+#[cfg(feature = "serde-traits")]
+use {
+    serde::{Deserialize, Serialize},
+    serde_with::{As, DisplayFromStr},
+};
+
+/// Discriminator of a rebasing mint event, logged as its first field
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[repr(u8)]
+pub enum RebaseEventType {
+    /// `RebaseMintInitialized`
+    Initialized,
+    /// `SupplyAuthorityChanged`
+    SupplyAuthorityChanged,
+    /// `SupplyAuthorityProposed`
+    SupplyAuthorityProposed,
+    /// `SupplyAuthorityProposalCancelled`
+    SupplyAuthorityProposalCancelled,
+    /// `SupplyAuthorityChangeScheduled`
+    SupplyAuthorityChangeScheduled,
+    /// `SupplyAuthorityChangeCancelled`
+    SupplyAuthorityChangeCancelled,
+    /// `RebaseDelegateChanged`
+    RebaseDelegateChanged,
+    /// `ReserveAccountChanged`
+    ReserveAccountChanged,
+    /// `RebaseFeeChanged`
+    RebaseFeeChanged,
+    /// `RebaseTagged`
+    RebaseTagged,
+    /// `RebaseMintSunset`
+    Sunset,
+    /// `RebaseMintRetired`
+    Retired,
+    /// `RoundingDustSwept`
+    RoundingDustSwept,
+    /// `RebaseClaimed`
+    RebaseClaimed,
+}
+
+/// Fields shared by every rebasing mint event, logged as its second field
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct RebaseEventHeader {
+    /// The rebasing mint
+    #[cfg_attr(feature = "serde-traits", serde(with = "As::<DisplayFromStr>"))]
+    pub mint: Pubkey,
+    /// Unix timestamp of the slot that logged the event
+    pub unix_timestamp: PodI64,
+}
+
+/// An event body, logged as the third field after its type and the header
+pub trait RebaseEventData: Pod {
+    /// Discriminator of the event
+    const TYPE: RebaseEventType;
+}
+
+/// Event logged by `RebaseMintInstruction::Initialize`
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct RebaseMintInitialized {
+    /// The account that can rebase the supply
+    pub supply_authority: OptionalNonZeroPubkey,
+    /// The initial supply
+    pub initial_supply: PodU64,
+    /// Unix timestamp before which the supply cannot be rebased
+    pub first_rebase_after_timestamp: PodI64,
+    /// Delay before a change of supply authority can be finalized
+    pub authority_change_delay_seconds: PodU32,
+    /// Virtual shares and tokens added to both sides of every conversion
+    pub virtual_shares_offset: PodU64,
+}
+impl RebaseEventData for RebaseMintInitialized {
+    const TYPE: RebaseEventType = RebaseEventType::Initialized;
+}
+
+/// Event logged when the supply authority changes, by `SetSupplyAuthority`,
+/// `AcceptSupplyAuthority` and `FinalizeAuthorityChange`, and by
+/// `SetAuthority` with the `RebaseSupply` authority type. A missing new
+/// authority means it was revoked.
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct SupplyAuthorityChanged {
+    /// Supply authority before the change
+    pub previous_authority: OptionalNonZeroPubkey,
+    /// Supply authority after the change
+    pub new_authority: OptionalNonZeroPubkey,
+}
+impl RebaseEventData for SupplyAuthorityChanged {
+    const TYPE: RebaseEventType = RebaseEventType::SupplyAuthorityChanged;
+}
+
+/// Event logged by `RebaseMintInstruction::ProposeSupplyAuthority`
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct SupplyAuthorityProposed {
+    /// The proposed supply authority
+    pub pending_authority: OptionalNonZeroPubkey,
+}
+impl RebaseEventData for SupplyAuthorityProposed {
+    const TYPE: RebaseEventType = RebaseEventType::SupplyAuthorityProposed;
+}
+
+/// Event logged by `RebaseMintInstruction::CancelPendingAuthority`
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct SupplyAuthorityProposalCancelled {
+    /// The supply authority that had been proposed
+    pub pending_authority: OptionalNonZeroPubkey,
+}
+impl RebaseEventData for SupplyAuthorityProposalCancelled {
+    const TYPE: RebaseEventType = RebaseEventType::SupplyAuthorityProposalCancelled;
+}
+
+/// Event logged instead of `SupplyAuthorityChanged` when the mint delays
+/// changes of supply authority
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct SupplyAuthorityChangeScheduled {
+    /// The supply authority once the change is finalized
+    pub scheduled_authority: OptionalNonZeroPubkey,
+    /// Unix timestamp from which the change can be finalized
+    pub ready_timestamp: PodI64,
+}
+impl RebaseEventData for SupplyAuthorityChangeScheduled {
+    const TYPE: RebaseEventType = RebaseEventType::SupplyAuthorityChangeScheduled;
+}
+
+/// Event logged by `RebaseMintInstruction::CancelAuthorityChange`
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct SupplyAuthorityChangeCancelled {
+    /// The supply authority that had been scheduled
+    pub scheduled_authority: OptionalNonZeroPubkey,
+}
+impl RebaseEventData for SupplyAuthorityChangeCancelled {
+    const TYPE: RebaseEventType = RebaseEventType::SupplyAuthorityChangeCancelled;
+}
+
+/// Event logged by `RebaseMintInstruction::SetRebaseDelegate`
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct RebaseDelegateChanged {
+    /// Rebase delegate before the change
+    pub previous_delegate: OptionalNonZeroPubkey,
+    /// Rebase delegate after the change
+    pub new_delegate: OptionalNonZeroPubkey,
+}
+impl RebaseEventData for RebaseDelegateChanged {
+    const TYPE: RebaseEventType = RebaseEventType::RebaseDelegateChanged;
+}
+
+/// Event logged by `RebaseMintInstruction::SetReserveAccount`
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct ReserveAccountChanged {
+    /// Reserve account before the change
+    pub previous_reserve_account: OptionalNonZeroPubkey,
+    /// Reserve account after the change
+    pub new_reserve_account: OptionalNonZeroPubkey,
+}
+impl RebaseEventData for ReserveAccountChanged {
+    const TYPE: RebaseEventType = RebaseEventType::ReserveAccountChanged;
+}
+
+/// Event logged by `RebaseMintInstruction::SetRebaseFee`
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct RebaseFeeChanged {
+    /// Rebase fee before the change, in basis points
+    pub previous_rebase_fee_bps: PodU16,
+    /// Fee destination before the change
+    pub previous_fee_destination: OptionalNonZeroPubkey,
+    /// Rebase fee after the change, in basis points
+    pub new_rebase_fee_bps: PodU16,
+    /// Fee destination after the change
+    pub new_fee_destination: OptionalNonZeroPubkey,
+}
+impl RebaseEventData for RebaseFeeChanged {
+    const TYPE: RebaseEventType = RebaseEventType::RebaseFeeChanged;
+}
+
+/// Event logged by `RebaseMintInstruction::RebaseSupply`, after the bare
+/// `SupplyRebased`, with the reason code and reference the rebase was tagged
+/// with
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct RebaseTagged {
+    /// Total supply before the rebase
+    pub previous_supply: PodU64,
+    /// Total supply after the rebase
+    pub new_supply: PodU64,
+    /// Machine-readable reason for the rebase, or zero if unset
+    pub reason_code: u8,
+    /// Operator-supplied reference, or zeroes if unset
+    pub reference: [u8; 32],
+    /// `RebaseMintConfig::cumulative_supply_increase` after the rebase
+    pub cumulative_supply_increase: PodU128,
+    /// `RebaseMintConfig::cumulative_supply_decrease` after the rebase
+    pub cumulative_supply_decrease: PodU128,
+}
+impl RebaseEventData for RebaseTagged {
+    const TYPE: RebaseEventType = RebaseEventType::RebaseTagged;
+}
+
+/// Event logged by `RebaseMintInstruction::SunsetRebase`
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct RebaseMintSunset {
+    /// Total supply written off
+    pub previous_supply: PodU64,
+    /// Total shares written off
+    pub previous_shares: PodU64,
+    /// Supply authority that sunset the mint
+    pub previous_authority: OptionalNonZeroPubkey,
+    /// `RebaseMintConfig::cumulative_supply_increase`, final as of the sunset
+    pub cumulative_supply_increase: PodU128,
+    /// `RebaseMintConfig::cumulative_supply_decrease`, including the supply
+    /// written off
+    pub cumulative_supply_decrease: PodU128,
+}
+impl RebaseEventData for RebaseMintSunset {
+    const TYPE: RebaseEventType = RebaseEventType::Sunset;
+}
+
+/// Event logged by `RebaseMintInstruction::RetireRebase`
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct RebaseMintRetired {
+    /// Total supply of the frozen ratio
+    pub retired_supply: PodU64,
+    /// Total shares of the frozen ratio
+    pub retired_shares: PodU64,
+    /// Supply authority that retired the mint
+    pub previous_authority: OptionalNonZeroPubkey,
+}
+impl RebaseEventData for RebaseMintRetired {
+    const TYPE: RebaseEventType = RebaseEventType::Retired;
+}
+
+/// Event logged by `RebaseMintInstruction::SweepRoundingDust`
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct RoundingDustSwept {
+    /// Token account credited with the dust
+    #[cfg_attr(feature = "serde-traits", serde(with = "As::<DisplayFromStr>"))]
+    pub destination: Pubkey,
+    /// Shares swept
+    pub shares: PodU64,
+    /// What the swept shares are worth at the current ratio
+    pub amount: PodU64,
+}
+impl RebaseEventData for RoundingDustSwept {
+    const TYPE: RebaseEventType = RebaseEventType::RoundingDustSwept;
+}
+
+/// Event logged by `RebaseMintInstruction::ClaimRebase`
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct RebaseClaimed {
+    /// Token account caught up
+    #[cfg_attr(feature = "serde-traits", serde(with = "As::<DisplayFromStr>"))]
+    pub account: Pubkey,
+    /// Amount of the account before the catch-up
+    pub previous_amount: PodU64,
+    /// Amount of the account at the current rebase index
+    pub amount: PodU64,
+    /// Rebase index the account caught up to
+    pub rebase_index: PodU128,
+}
+impl RebaseEventData for RebaseClaimed {
+    const TYPE: RebaseEventType = RebaseEventType::RebaseClaimed;
+}
+
+/// A rebasing mint event, decoded from the fields of its log
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RebaseEvent {
+    /// `RebaseMintInitialized`
+    Initialized(RebaseMintInitialized),
+    /// `SupplyAuthorityChanged`
+    SupplyAuthorityChanged(SupplyAuthorityChanged),
+    /// `SupplyAuthorityProposed`
+    SupplyAuthorityProposed(SupplyAuthorityProposed),
+    /// `SupplyAuthorityProposalCancelled`
+    SupplyAuthorityProposalCancelled(SupplyAuthorityProposalCancelled),
+    /// `SupplyAuthorityChangeScheduled`
+    SupplyAuthorityChangeScheduled(SupplyAuthorityChangeScheduled),
+    /// `SupplyAuthorityChangeCancelled`
+    SupplyAuthorityChangeCancelled(SupplyAuthorityChangeCancelled),
+    /// `RebaseDelegateChanged`
+    RebaseDelegateChanged(RebaseDelegateChanged),
+    /// `ReserveAccountChanged`
+    ReserveAccountChanged(ReserveAccountChanged),
+    /// `RebaseFeeChanged`
+    RebaseFeeChanged(RebaseFeeChanged),
+    /// `RebaseTagged`
+    RebaseTagged(RebaseTagged),
+    /// `RebaseMintSunset`
+    Sunset(RebaseMintSunset),
+    /// `RebaseMintRetired`
+    Retired(RebaseMintRetired),
+    /// `RoundingDustSwept`
+    RoundingDustSwept(RoundingDustSwept),
+    /// `RebaseClaimed`
+    RebaseClaimed(RebaseClaimed),
+}
+
+fn read_field<T: Pod>(field: &[u8]) -> Result<T, ProgramError> {
+    bytemuck::try_pod_read_unaligned(field).map_err(|_| ProgramError::InvalidArgument)
+}
+
+/// Decode the fields of a `sol_log_data` log into the event and its header.
+///
+/// Fails on the bare `SupplyRebased` and `SupplySynced` events, and on
+/// anything else that isn't a rebasing mint event.
+pub fn parse_event(fields: &[&[u8]]) -> Result<(RebaseEventHeader, RebaseEvent), ProgramError> {
+    let [event_type, header, data] = fields else {
+        return Err(ProgramError::InvalidArgument);
+    };
+    let event_type = match event_type {
+        [event_type] => {
+            RebaseEventType::try_from(*event_type).map_err(|_| ProgramError::InvalidArgument)?
+        }
+        _ => return Err(ProgramError::InvalidArgument),
+    };
+    let header = read_field::<RebaseEventHeader>(header)?;
+    let event = match event_type {
+        RebaseEventType::Initialized => RebaseEvent::Initialized(read_field(data)?),
+        RebaseEventType::SupplyAuthorityChanged => {
+            RebaseEvent::SupplyAuthorityChanged(read_field(data)?)
+        }
+        RebaseEventType::SupplyAuthorityProposed => {
+            RebaseEvent::SupplyAuthorityProposed(read_field(data)?)
+        }
+        RebaseEventType::SupplyAuthorityProposalCancelled => {
+            RebaseEvent::SupplyAuthorityProposalCancelled(read_field(data)?)
+        }
+        RebaseEventType::SupplyAuthorityChangeScheduled => {
+            RebaseEvent::SupplyAuthorityChangeScheduled(read_field(data)?)
+        }
+        RebaseEventType::SupplyAuthorityChangeCancelled => {
+            RebaseEvent::SupplyAuthorityChangeCancelled(read_field(data)?)
+        }
+        RebaseEventType::RebaseDelegateChanged => {
+            RebaseEvent::RebaseDelegateChanged(read_field(data)?)
+        }
+        RebaseEventType::ReserveAccountChanged => {
+            RebaseEvent::ReserveAccountChanged(read_field(data)?)
+        }
+        RebaseEventType::RebaseFeeChanged => RebaseEvent::RebaseFeeChanged(read_field(data)?),
+        RebaseEventType::RebaseTagged => RebaseEvent::RebaseTagged(read_field(data)?),
+        RebaseEventType::Sunset => RebaseEvent::Sunset(read_field(data)?),
+        RebaseEventType::Retired => RebaseEvent::Retired(read_field(data)?),
+        RebaseEventType::RoundingDustSwept => RebaseEvent::RoundingDustSwept(read_field(data)?),
+        RebaseEventType::RebaseClaimed => RebaseEvent::RebaseClaimed(read_field(data)?),
+    };
+    Ok((header, event))
+}
+
+/// Log an event of the rebasing mint `mint`, stamped with the current time
+pub(crate) fn log_event<E: RebaseEventData>(mint: &Pubkey, event: &E) -> ProgramResult {
+    let header = RebaseEventHeader {
+        mint: *mint,
+        unix_timestamp: Clock::get()?.unix_timestamp.into(),
+    };
+    sol_log_data(&[
+        &[E::TYPE.into()],
+        bytemuck::bytes_of(&header),
+        bytemuck::bytes_of(event),
+    ]);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_types_are_stable() {
+        // indexers match on these, so they can only ever be appended to
+        let expected = [
+            (RebaseEventType::Initialized, 0u8),
+            (RebaseEventType::SupplyAuthorityChanged, 1),
+            (RebaseEventType::SupplyAuthorityProposed, 2),
+            (RebaseEventType::SupplyAuthorityProposalCancelled, 3),
+            (RebaseEventType::SupplyAuthorityChangeScheduled, 4),
+            (RebaseEventType::SupplyAuthorityChangeCancelled, 5),
+            (RebaseEventType::RebaseDelegateChanged, 6),
+            (RebaseEventType::ReserveAccountChanged, 7),
+            (RebaseEventType::RebaseFeeChanged, 8),
+            (RebaseEventType::RebaseTagged, 9),
+            (RebaseEventType::Sunset, 10),
+            (RebaseEventType::Retired, 11),
+            (RebaseEventType::RoundingDustSwept, 12),
+            (RebaseEventType::RebaseClaimed, 13),
+        ];
+        for (event_type, discriminator) in expected {
+            assert_eq!(u8::from(event_type), discriminator);
+        }
+        assert!(RebaseEventType::try_from(14).is_err());
+    }
+
+    #[test]
+    fn parse_round_trip() {
+        let header = RebaseEventHeader {
+            mint: Pubkey::new_unique(),
+            unix_timestamp: 1_700_000_000.into(),
+        };
+        let event = SupplyAuthorityChanged {
+            previous_authority: OptionalNonZeroPubkey::try_from(Some(Pubkey::new_unique()))
+                .unwrap(),
+            new_authority: OptionalNonZeroPubkey::default(),
+        };
+        let event_type = [u8::from(SupplyAuthorityChanged::TYPE)];
+        let fields: [&[u8]; 3] = [
+            &event_type,
+            bytemuck::bytes_of(&header),
+            bytemuck::bytes_of(&event),
+        ];
+        assert_eq!(
+            parse_event(&fields).unwrap(),
+            (header, RebaseEvent::SupplyAuthorityChanged(event))
+        );
+
+        // the body doesn't match the type
+        let event_type = [u8::from(RebaseEventType::SupplyAuthorityProposed)];
+        let fields: [&[u8]; 3] = [
+            &event_type,
+            bytemuck::bytes_of(&header),
+            bytemuck::bytes_of(&event),
+        ];
+        assert_eq!(parse_event(&fields), Err(ProgramError::InvalidArgument));
+
+        // the reference comes back byte for byte
+        let mut reference = [0; 32];
+        for (byte, value) in reference.iter_mut().zip(1..) {
+            *byte = value;
+        }
+        let event = RebaseTagged {
+            previous_supply: 1_000.into(),
+            new_supply: 1_050.into(),
+            reason_code: 2,
+            reference,
+            cumulative_supply_increase: 50.into(),
+            cumulative_supply_decrease: 0.into(),
+        };
+        let event_type = [u8::from(RebaseTagged::TYPE)];
+        let fields: [&[u8]; 3] = [
+            &event_type,
+            bytemuck::bytes_of(&header),
+            bytemuck::bytes_of(&event),
+        ];
+        let (_, parsed) = parse_event(&fields).unwrap();
+        let RebaseEvent::RebaseTagged(parsed) = parsed else {
+            panic!("not a tagged rebase");
+        };
+        assert_eq!(parsed.reason_code, 2);
+        assert_eq!(parsed.reference, reference);
+        assert_eq!(parsed, event);
+
+        // a bare legacy event
+        let legacy = [0u8; 32];
+        assert_eq!(parse_event(&[&legacy]), Err(ProgramError::InvalidArgument));
+    }
+}
@@ -0,0 +1,679 @@
+//! `Arbitrary` implementations for fuzzing programs that integrate with
+//! rebasing mints
+
+use {
+    crate::{
+        extension::{
+            mint_close_authority::MintCloseAuthority,
+            rebase_mint::{
+                instruction::{DecodedRebaseInstruction, RebaseSupplyData},
+                pod::{get_current_ratio, get_rebase_config},
+                RebaseHistory, RebaseMintConfig, RebaseMode,
+            },
+            BaseStateWithExtensions, ExtensionType, StateWithExtensions, StateWithExtensionsMut,
+            StateWithExtensionsOwned,
+        },
+        state::{Account, Mint},
+    },
+    arbitrary::{Arbitrary, Result, Unstructured},
+    solana_program::{program_option::COption, program_pack::Pack, pubkey::Pubkey},
+    spl_pod::optional_keys::OptionalNonZeroPubkey,
+};
+
+/// Largest virtual offset generated for consistent configs, well above what
+/// mints use in practice
+const MAX_CONSISTENT_VIRTUAL_SHARES_OFFSET: u64 = 1_000_000_000;
+
+fn arbitrary_pubkey(u: &mut Unstructured<'_>) -> Result<Pubkey> {
+    Ok(Pubkey::new_from_array(u.arbitrary()?))
+}
+
+fn arbitrary_optional_pubkey(u: &mut Unstructured<'_>) -> Result<Option<Pubkey>> {
+    // go through a flag, otherwise the empty key would almost never come up
+    if u.arbitrary()? {
+        Ok(Some(arbitrary_pubkey(u)?))
+    } else {
+        Ok(None)
+    }
+}
+
+fn arbitrary_optional_nonzero_pubkey(u: &mut Unstructured<'_>) -> Result<OptionalNonZeroPubkey> {
+    Ok(OptionalNonZeroPubkey::try_from(arbitrary_optional_pubkey(u)?).unwrap_or_default())
+}
+
+/// Fully random config, which may break any of the extension's invariants
+impl<'a> Arbitrary<'a> for RebaseMintConfig {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self {
+            supply_authority: arbitrary_optional_nonzero_pubkey(u)?,
+            total_supply: u.arbitrary::<u64>()?.into(),
+            total_shares: u.arbitrary::<u64>()?.into(),
+            reserve_account: arbitrary_optional_nonzero_pubkey(u)?,
+            first_rebase_after_timestamp: u.arbitrary::<i64>()?.into(),
+            pending_supply_authority: arbitrary_optional_nonzero_pubkey(u)?,
+            two_step_authority_transfer: u.arbitrary::<bool>()?.into(),
+            authority_change_delay_seconds: u.arbitrary::<u32>()?.into(),
+            scheduled_supply_authority: arbitrary_optional_nonzero_pubkey(u)?,
+            authority_change_requested_timestamp: u.arbitrary::<i64>()?.into(),
+            rebase_delegate: arbitrary_optional_nonzero_pubkey(u)?,
+            use_mint_authority_fallback: u.arbitrary::<bool>()?.into(),
+            require_rebase_memo: u.arbitrary::<bool>()?.into(),
+            has_rebased: u.arbitrary::<bool>()?.into(),
+            exempt_supply: u.arbitrary::<u64>()?.into(),
+            outstanding_shares: u.arbitrary::<u64>()?.into(),
+            virtual_shares_offset: u.arbitrary::<u64>()?.into(),
+            allow_supply_decrease: u.arbitrary::<bool>()?.into(),
+            cached_ratio: u.arbitrary::<u128>()?.into(),
+            rebase_fee_bps: u.arbitrary::<u16>()?.into(),
+            fee_destination: arbitrary_optional_nonzero_pubkey(u)?,
+            max_total_shares: u.arbitrary::<u64>()?.into(),
+            allow_sunset: u.arbitrary::<bool>()?.into(),
+            is_sunset: u.arbitrary::<bool>()?.into(),
+            update_metadata_rate: u.arbitrary::<bool>()?.into(),
+            is_retired: u.arbitrary::<bool>()?.into(),
+            retired_supply: u.arbitrary::<u64>()?.into(),
+            retired_shares: u.arbitrary::<u64>()?.into(),
+            co_signer: arbitrary_optional_nonzero_pubkey(u)?,
+            rebase_mode: u.arbitrary()?,
+            rebase_index: u.arbitrary::<u128>()?.into(),
+            sync_base_supply: u.arbitrary::<bool>()?.into(),
+            cumulative_supply_increase: u.arbitrary::<u128>()?.into(),
+            cumulative_supply_decrease: u.arbitrary::<u128>()?.into(),
+            cached_inverse_ratio: u.arbitrary::<u128>()?.into(),
+        })
+    }
+}
+
+/// Config that satisfies the invariants kept by the program: outstanding
+/// shares never exceed the total, which never exceeds the cap, shares always
+/// have a nonzero supply behind them, the supply and exempt supply add up
+/// without overflow, a sunset mint has no supply or shares left, a retired
+/// mint has no authority and a frozen ratio taken from its totals, and the
+/// mint holds shares in `RebaseMode::Shares`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ConsistentRebaseMintConfig(pub RebaseMintConfig);
+
+impl<'a> Arbitrary<'a> for ConsistentRebaseMintConfig {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut config = RebaseMintConfig::arbitrary(u)?;
+        config.rebase_mode = RebaseMode::Shares.into();
+        config.rebase_index = 0.into();
+        let is_sunset = bool::from(config.is_sunset);
+        let max_total_shares = if is_sunset {
+            0
+        } else {
+            config.max_total_shares()
+        };
+        let total_shares = u.int_in_range(0..=max_total_shares)?;
+        config.total_shares = total_shares.into();
+        let total_supply = if is_sunset {
+            0
+        } else if total_shares == 0 {
+            u.arbitrary::<u64>()?
+        } else {
+            u.int_in_range(1..=u64::MAX)?
+        };
+        config.total_supply = total_supply.into();
+        config.outstanding_shares = u.int_in_range(0..=total_shares)?.into();
+        let max_exempt_supply = if is_sunset {
+            0
+        } else {
+            u64::MAX - total_supply
+        };
+        config.exempt_supply = u.int_in_range(0..=max_exempt_supply)?.into();
+        config.virtual_shares_offset = u
+            .int_in_range(0..=MAX_CONSISTENT_VIRTUAL_SHARES_OFFSET)?
+            .into();
+        config.is_retired = false.into();
+        if !is_sunset && u.arbitrary()? {
+            // can't fail on a mint that is neither sunset nor retired
+            let _ = config.retire();
+        }
+        // the cache may be stale, but is current often enough to be used
+        if u.arbitrary()? {
+            config.refresh_cached_ratio();
+        }
+        Ok(Self(config))
+    }
+}
+
+impl<'a> Arbitrary<'a> for RebaseSupplyData {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self {
+            new_supply: u.arbitrary::<u64>()?.into(),
+            reason_code: u.arbitrary()?,
+            reference: u.arbitrary()?,
+            not_after_unix_timestamp: u.arbitrary::<i64>()?.into(),
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for DecodedRebaseInstruction {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(match u.int_in_range(0..=34u8)? {
+            0 => Self::Initialize {
+                supply_authority: arbitrary_optional_pubkey(u)?,
+                initial_supply: u.arbitrary()?,
+                first_rebase_after_timestamp: u.arbitrary()?,
+                two_step_authority_transfer: u.arbitrary()?,
+                authority_change_delay_seconds: u.arbitrary()?,
+                use_mint_authority_fallback: u.arbitrary()?,
+                require_rebase_memo: u.arbitrary()?,
+                virtual_shares_offset: u.arbitrary()?,
+                allow_supply_decrease: u.arbitrary()?,
+                rebase_fee_bps: u.arbitrary()?,
+                fee_destination: arbitrary_optional_pubkey(u)?,
+                initial_shares: u.arbitrary()?,
+                max_total_shares: u.arbitrary()?,
+                allow_sunset: u.arbitrary()?,
+                update_metadata_rate: u.arbitrary()?,
+                co_signer: arbitrary_optional_pubkey(u)?,
+                rebase_mode: if u.arbitrary()? {
+                    RebaseMode::Lazy
+                } else {
+                    RebaseMode::Shares
+                },
+                sync_base_supply: u.arbitrary()?,
+            },
+            1 => Self::RebaseSupply {
+                new_supply: u.arbitrary()?,
+                reason_code: u.arbitrary()?,
+                reference: u.arbitrary()?,
+                not_after_unix_timestamp: u.arbitrary()?,
+            },
+            2 => Self::SetReserveAccount {
+                reserve_account: arbitrary_optional_pubkey(u)?,
+            },
+            3 => Self::PreviewRebaseSupply {
+                new_supply: u.arbitrary()?,
+            },
+            4 => Self::SetSupplyAuthority {
+                new_authority: arbitrary_optional_pubkey(u)?,
+            },
+            5 => Self::ProposeSupplyAuthority {
+                pending_authority: arbitrary_optional_pubkey(u)?,
+            },
+            6 => Self::AcceptSupplyAuthority,
+            7 => Self::CancelPendingAuthority,
+            8 => Self::FinalizeAuthorityChange,
+            9 => Self::CancelAuthorityChange,
+            10 => Self::SetRebaseDelegate {
+                rebase_delegate: arbitrary_optional_pubkey(u)?,
+            },
+            11 => Self::TransferShares {
+                shares: u.arbitrary()?,
+            },
+            12 => Self::TransferSharesChecked {
+                shares: u.arbitrary()?,
+                decimals: u.arbitrary()?,
+            },
+            13 => Self::MintToShares {
+                shares: u.arbitrary()?,
+            },
+            14 => Self::BurnShares {
+                shares: u.arbitrary()?,
+            },
+            15 => Self::SyncSupplyFromMint,
+            16 => Self::GetRebasedBalance,
+            17 => Self::ConvertAmountToShares {
+                amount: u.arbitrary()?,
+            },
+            18 => Self::ConvertSharesToAmount {
+                shares: u.arbitrary()?,
+            },
+            19 => Self::ApproveShares {
+                shares: u.arbitrary()?,
+            },
+            20 => Self::SetAccountExempt {
+                exempt: u.arbitrary()?,
+            },
+            21 => Self::InitializeWrapper,
+            22 => Self::WrapShares {
+                shares: u.arbitrary()?,
+            },
+            23 => Self::UnwrapShares {
+                shares: u.arbitrary()?,
+            },
+            24 => Self::AssertRebaseConsistency,
+            25 => Self::TransferCheckedWithShareSlippage {
+                amount: u.arbitrary()?,
+                decimals: u.arbitrary()?,
+                expected_shares: u.arbitrary()?,
+                max_share_slippage_bps: u.arbitrary()?,
+            },
+            26 => Self::GetRebaseState,
+            27 => Self::InitializeHistory,
+            28 => Self::GetRebaseHistory,
+            29 => Self::SetRebaseFee {
+                rebase_fee_bps: u.arbitrary()?,
+                fee_destination: arbitrary_optional_pubkey(u)?,
+            },
+            30 => Self::SunsetRebase,
+            31 => Self::RetireRebase,
+            32 => Self::SweepRoundingDust,
+            33 => Self::ClaimRebase,
+            _ => Self::RebaseSupplyUi {
+                ui_supply: u.arbitrary()?,
+                reason_code: u.arbitrary()?,
+                reference: u.arbitrary()?,
+                not_after_unix_timestamp: u.arbitrary()?,
+            },
+        })
+    }
+}
+
+/// `numerator / denominator` rounded down or up, or `None` if it doesn't fit
+/// in a `u64`
+fn reference_div(numerator: u128, denominator: u128, round_up: bool) -> Option<u64> {
+    let quotient = numerator / denominator;
+    let quotient = if round_up && numerator % denominator != 0 {
+        quotient + 1
+    } else {
+        quotient
+    };
+    u64::try_from(quotient).ok()
+}
+
+/// `value * to / from` with the given rounding, as the conversions define it:
+/// 1:1 while there are no shares, and `None` if the result doesn't fit in a
+/// `u64` or nothing backs the shares.
+///
+/// The outer `None` means the product overflows a `u128`, which only happens
+/// when the virtual offset pushes both totals past `u64::MAX`, so the
+/// reference can't tell.
+fn reference_convert(
+    value: u64,
+    to: u128,
+    from: u128,
+    total_shares: u128,
+    round_up: bool,
+) -> Option<Option<u64>> {
+    if total_shares == 0 {
+        return Some(Some(value));
+    }
+    if from == 0 {
+        return Some(None);
+    }
+    let product = (value as u128).checked_mul(to)?;
+    Some(reference_div(product, from, round_up))
+}
+
+/// Compare every conversion of `value` under `config` against an independent
+/// `u128` computation of the exact quotient, panicking on any divergence from
+/// the documented rounding
+pub fn check_conversions_against_reference(config: &RebaseMintConfig, value: u64) {
+    let (total_supply, total_shares) = if bool::from(config.is_retired) {
+        (config.retired_supply, config.retired_shares)
+    } else {
+        (config.total_supply, config.total_shares)
+    };
+    let offset = u64::from(config.virtual_shares_offset) as u128;
+    let supply = u64::from(total_supply) as u128 + offset;
+    let shares = u64::from(total_shares) as u128 + offset;
+    // shares of a sunset mint are worth nothing
+    let to_amount = |round_up| {
+        if bool::from(config.is_sunset) {
+            Some(Some(0))
+        } else {
+            reference_convert(value, supply, shares, shares, round_up)
+        }
+    };
+    for (round_up, actual, expected) in [
+        (false, config.shares_to_amount(value), to_amount(false)),
+        (
+            true,
+            config.shares_to_amount_rounding_up(value),
+            to_amount(true),
+        ),
+        (
+            false,
+            config.amount_to_shares(value),
+            reference_convert(value, shares, supply, shares, false),
+        ),
+        (
+            true,
+            config.amount_to_shares_rounding_up(value),
+            reference_convert(value, shares, supply, shares, true),
+        ),
+    ] {
+        if let Some(expected) = expected {
+            assert_eq!(
+                actual, expected,
+                "value {} round up {} config {:?}",
+                value, round_up, config
+            );
+        }
+    }
+}
+
+/// Corruption of a rebasing mint buffer, aimed at the TLV entries
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TlvMutation {
+    /// Overwrite the length of an entry, taken modulo the number of entries
+    SetLength {
+        /// Entry to corrupt
+        entry: u8,
+        /// New length
+        length: u16,
+    },
+    /// Overwrite the type of an entry, taken modulo the number of entries
+    SetType {
+        /// Entry to corrupt
+        entry: u8,
+        /// New type, which may not be a known extension
+        extension_type: u16,
+    },
+    /// Append a second rebasing config entry, holding `config`
+    DuplicateRebaseConfig {
+        /// Config of the duplicate, which the parsers should never read
+        config: RebaseMintConfig,
+    },
+    /// Overwrite the account type, e.g. to make the buffer look like a token
+    /// account carrying the mint's extensions
+    SetAccountType(u8),
+    /// Cut the buffer down to this many bytes, often in the middle of an
+    /// entry
+    Truncate(u16),
+    /// Flip bits of a byte, taken modulo the length of the buffer
+    FlipByte {
+        /// Byte to corrupt
+        index: u16,
+        /// Bits to flip
+        mask: u8,
+    },
+}
+
+impl<'a> Arbitrary<'a> for TlvMutation {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(match u.int_in_range(0..=5u8)? {
+            0 => Self::SetLength {
+                entry: u.arbitrary()?,
+                length: u.arbitrary()?,
+            },
+            1 => Self::SetType {
+                entry: u.arbitrary()?,
+                extension_type: u.arbitrary()?,
+            },
+            2 => Self::DuplicateRebaseConfig {
+                config: u.arbitrary()?,
+            },
+            3 => Self::SetAccountType(u.arbitrary()?),
+            4 => Self::Truncate(u.arbitrary()?),
+            _ => Self::FlipByte {
+                index: u.arbitrary()?,
+                mask: u.arbitrary()?,
+            },
+        })
+    }
+}
+
+/// Valid rebasing mint account, corrupted by a series of mutations
+#[derive(Clone, Debug, PartialEq)]
+pub struct RebaseMintBuffer {
+    /// Config written in the rebasing entry
+    pub config: ConsistentRebaseMintConfig,
+    /// Put another extension ahead of the rebasing config
+    pub with_close_authority: bool,
+    /// Put the rebase history after the rebasing config
+    pub with_history: bool,
+    /// Mutations, applied in order
+    pub mutations: Vec<TlvMutation>,
+}
+
+impl<'a> Arbitrary<'a> for RebaseMintBuffer {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self {
+            config: u.arbitrary()?,
+            with_close_authority: u.arbitrary()?,
+            with_history: u.arbitrary()?,
+            mutations: u.arbitrary()?,
+        })
+    }
+}
+
+impl RebaseMintBuffer {
+    /// Extensions of the mint, in the order of their entries
+    fn extension_types(&self) -> Vec<ExtensionType> {
+        let mut extension_types = vec![ExtensionType::RebaseMintConfig];
+        if self.with_close_authority {
+            extension_types.insert(0, ExtensionType::MintCloseAuthority);
+        }
+        if self.with_history {
+            extension_types.push(ExtensionType::RebaseHistory);
+        }
+        extension_types
+    }
+
+    /// Offsets of the TLV entries in the buffer before any mutation
+    fn entry_starts(&self) -> Vec<usize> {
+        let mut start = Account::LEN + 1;
+        self.extension_types()
+            .into_iter()
+            .map(|extension_type| {
+                let entry_start = start;
+                start += extension_type.try_get_tlv_len().unwrap();
+                entry_start
+            })
+            .collect()
+    }
+
+    /// Serialize the valid mint, then apply the mutations
+    pub fn data(&self) -> Vec<u8> {
+        let account_len =
+            ExtensionType::try_calculate_account_len::<Mint>(&self.extension_types()).unwrap();
+        let mut data = vec![0; account_len];
+        let mut mint = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+        if self.with_close_authority {
+            mint.init_extension::<MintCloseAuthority>(true).unwrap();
+        }
+        *mint.init_extension::<RebaseMintConfig>(true).unwrap() = self.config.0;
+        if self.with_history {
+            mint.init_extension::<RebaseHistory>(true).unwrap();
+        }
+        mint.base.mint_authority = COption::Some(Pubkey::new_from_array([1; 32]));
+        mint.base.is_initialized = true;
+        mint.pack_base();
+        mint.init_account_type().unwrap();
+
+        let entry_starts = self.entry_starts();
+        let rebase_entry = usize::from(self.with_close_authority);
+        let config_entry_len = ExtensionType::RebaseMintConfig.try_get_tlv_len().unwrap();
+        let write = |data: &mut Vec<u8>, index: usize, bytes: &[u8]| {
+            if let Some(dst) = data.get_mut(index..index + bytes.len()) {
+                dst.copy_from_slice(bytes);
+            }
+        };
+        for mutation in &self.mutations {
+            let entry_start = |entry: u8| entry_starts[usize::from(entry) % entry_starts.len()];
+            match *mutation {
+                TlvMutation::SetLength { entry, length } => {
+                    write(&mut data, entry_start(entry) + 2, &length.to_le_bytes())
+                }
+                TlvMutation::SetType {
+                    entry,
+                    extension_type,
+                } => write(&mut data, entry_start(entry), &extension_type.to_le_bytes()),
+                TlvMutation::DuplicateRebaseConfig { config } => {
+                    let start = entry_starts[rebase_entry];
+                    let mut entry = data
+                        .get(start..start + config_entry_len)
+                        .map(<[u8]>::to_vec)
+                        .unwrap_or_default();
+                    if let Some(value) = entry.get_mut(4..) {
+                        value.copy_from_slice(bytemuck::bytes_of(&config));
+                    }
+                    data.extend_from_slice(&entry);
+                }
+                TlvMutation::SetAccountType(account_type) => {
+                    write(&mut data, Account::LEN, &[account_type])
+                }
+                TlvMutation::Truncate(len) => data.truncate(usize::from(len)),
+                TlvMutation::FlipByte { index, mask } => {
+                    if !data.is_empty() {
+                        let index = usize::from(index) % data.len();
+                        data[index] ^= mask;
+                    }
+                }
+            }
+        }
+        data
+    }
+}
+
+/// Parse `data` as a rebasing mint through every reader, panicking if the
+/// zero-copy accessor and the unpacked mint disagree on whether it holds a
+/// rebasing config or on its contents.
+///
+/// Any of the parsers panicking on the data is a failure too.
+pub fn check_rebase_mint_tlv(data: &[u8]) {
+    let zero_copy = get_rebase_config(data);
+    assert_eq!(zero_copy.is_ok(), get_current_ratio(data).is_ok());
+
+    let state = StateWithExtensions::<Mint>::unpack(data);
+    let unpacked = state
+        .as_ref()
+        .map_err(Clone::clone)
+        .and_then(|state| state.get_extension::<RebaseMintConfig>());
+    match (&zero_copy, &unpacked) {
+        (Ok(zero_copy), Ok(unpacked)) => {
+            assert_eq!(zero_copy, unpacked);
+            // both read the first entry, in place
+            assert!(std::ptr::eq(*zero_copy, *unpacked));
+        }
+        (Err(_), Err(_)) => {}
+        _ => panic!(
+            "zero-copy read {:?} disagrees with unpacked mint {:?}",
+            zero_copy, unpacked
+        ),
+    }
+    if let Ok(state) = &state {
+        let _ = state.get_extension_types();
+        let _ = state.get_first_extension_type();
+        let _ = state.try_get_account_len();
+        let _ = state.try_get_new_account_len::<RebaseMintConfig>();
+        let _ = state.get_extension::<RebaseHistory>();
+    }
+
+    let owned = StateWithExtensionsOwned::<Mint>::unpack(data.to_vec());
+    assert_eq!(owned.is_ok(), state.is_ok());
+    if let Ok(owned) = owned {
+        assert_eq!(
+            owned.get_extension::<RebaseMintConfig>().ok(),
+            unpacked.as_ref().ok().copied()
+        );
+    }
+
+    let mut mut_data = data.to_vec();
+    let state_mut = StateWithExtensionsMut::<Mint>::unpack(&mut mut_data);
+    assert_eq!(state_mut.is_ok(), state.is_ok());
+    if let Ok(mut state_mut) = state_mut {
+        assert_eq!(
+            state_mut
+                .get_extension_mut::<RebaseMintConfig>()
+                .ok()
+                .map(|config| *config),
+            unpacked.ok().copied()
+        );
+    }
+
+    // the same bytes read as a token account
+    if let Ok(account) = StateWithExtensions::<Account>::unpack(data) {
+        let _ = account.get_extension_types();
+        let _ = account.try_get_account_len();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, proptest::prelude::*};
+
+    fn check_conversions(config: &RebaseMintConfig, value: u64) {
+        let _ = config.validate();
+        let _ = config.preview_rebase(value);
+        let _ = config.shares_to_amount_rounding_up(value);
+        let _ = config.amount_to_shares_rounding_up(value);
+        let _ = config.shares_to_ui_amount(value, 9);
+        if let Some(amount) = config.shares_to_amount(value) {
+            if let Some(shares) = config.amount_to_shares(amount) {
+                assert!(shares <= value);
+            }
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(4096))]
+        #[test]
+        fn random_configs_never_panic(data in prop::collection::vec(any::<u8>(), 0..512)) {
+            let mut u = Unstructured::new(&data);
+            if let Ok(config) = RebaseMintConfig::arbitrary(&mut u) {
+                let value = u.arbitrary().unwrap_or_default();
+                check_conversions(&config, value);
+            }
+        }
+
+        #[test]
+        fn consistent_configs_validate(data in prop::collection::vec(any::<u8>(), 0..512)) {
+            let mut u = Unstructured::new(&data);
+            if let Ok(ConsistentRebaseMintConfig(config)) = u.arbitrary() {
+                assert_eq!(config.validate(), Ok(()));
+                assert!(u64::from(config.total_supply)
+                    .checked_add(config.exempt_supply.into())
+                    .is_some());
+                let value = u.arbitrary().unwrap_or_default();
+                check_conversions(&config, value);
+            }
+        }
+
+        #[test]
+        fn conversions_match_reference(data in prop::collection::vec(any::<u8>(), 0..512)) {
+            let mut u = Unstructured::new(&data);
+            if let Ok(config) = RebaseMintConfig::arbitrary(&mut u) {
+                let value = u.arbitrary().unwrap_or_default();
+                check_conversions_against_reference(&config, value);
+            }
+        }
+
+        #[test]
+        fn small_conversions_match_reference(
+            total_supply in 0..1_000u64,
+            total_shares in 0..1_000u64,
+            virtual_shares_offset in 0..3u64,
+            value in 0..10_000u64,
+        ) {
+            let mut config = RebaseMintConfig {
+                total_supply: total_supply.into(),
+                total_shares: total_shares.into(),
+                virtual_shares_offset: virtual_shares_offset.into(),
+                ..Default::default()
+            };
+            check_conversions_against_reference(&config, value);
+            config.refresh_cached_ratio();
+            check_conversions_against_reference(&config, value);
+        }
+
+        #[test]
+        fn rebase_mint_tlv_parses_consistently(
+            data in prop::collection::vec(any::<u8>(), 0..512),
+        ) {
+            let mut u = Unstructured::new(&data);
+            if let Ok(buffer) = RebaseMintBuffer::arbitrary(&mut u) {
+                check_rebase_mint_tlv(&buffer.data());
+            }
+        }
+
+        #[test]
+        fn decoded_instructions_never_panic(data in prop::collection::vec(any::<u8>(), 0..512)) {
+            let mut u = Unstructured::new(&data);
+            if let Ok(instruction) = DecodedRebaseInstruction::arbitrary(&mut u) {
+                let _ = instruction.account_roles(
+                    &[],
+                    u.arbitrary().unwrap_or_default(),
+                    u.arbitrary().unwrap_or_default(),
+                    u.arbitrary().unwrap_or_default(),
+                    u.arbitrary().unwrap_or_default(),
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,4619 @@
+use {
+    crate::{
+        check_program_account,
+        error::TokenError,
+        extension::{
+            add_type_and_length_to_len, metadata_pointer,
+            rebase_mint::{
+                wrapper::{
+                    get_escrow_address, get_wrapper_authority_address, get_wrapper_mint_address,
+                },
+                RebaseHistory, RebaseHistoryEntry, RebaseMode, DEFAULT_MAX_TOTAL_SHARES,
+                MAX_REBASE_FEE_BPS, REBASE_HISTORY_LEN,
+            },
+            ExtensionType,
+        },
+        instruction::{
+            decode_instruction_data, decode_instruction_type, encode_instruction, initialize_mint2,
+            TokenInstruction,
+        },
+        state::Mint,
+    },
+    bytemuck::{Pod, Zeroable},
+    num_enum::{IntoPrimitive, TryFromPrimitive},
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+        system_instruction, system_program,
+    },
+    spl_pod::{
+        optional_keys::OptionalNonZeroPubkey,
+        primitives::{PodBool, PodI64, PodU16, PodU32, PodU64},
+    },
+    spl_token_metadata_interface::state::TokenMetadata,
+    spl_type_length_value::variable_len_pack::VariableLenPack,
+    std::{convert::TryInto, mem::size_of},
+};
+#[cfg(feature = "serde-traits")]
+use {
+    serde::{Deserialize, Serialize},
+    serde_with::{As, DisplayFromStr},
+};
+
+/// Rebasing mint extension instructions
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[repr(u8)]
+pub enum RebaseMintInstruction {
+    /// Initialize a new mint with a rebasing supply.
+    ///
+    /// Fails if the mint has already been initialized, so must be called before
+    /// `InitializeMint`.
+    ///
+    /// The mint must have exactly enough space allocated for the base mint (82
+    /// bytes), plus 83 bytes of padding, 1 byte reserved for the account type,
+    /// then space required for this extension, plus any others.
+    ///
+    /// Logs a `crate::extension::rebase_mint::event::RebaseMintInitialized`
+    /// event.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The mint to initialize.
+    ///
+    /// Data expected by this instruction:
+    ///   `crate::extension::rebase_mint::instruction::InitializeInstructionData`
+    Initialize,
+    /// Rebase the total supply, scaling every account's balance
+    /// proportionally. Only supported for mints that include the
+    /// `RebaseMintConfig` extension.
+    ///
+    /// If the mint has a reserve account configured, it must be provided, and
+    /// the new supply cannot exceed the amount it holds. If the mint does not
+    /// allow supply decreases, the new supply cannot be lower than the current
+    /// one.
+    ///
+    /// May be signed by either the supply authority or the rebase delegate.
+    /// If the mint has no supply authority but was initialized with
+    /// `use_mint_authority_fallback`, the mint authority signs instead.
+    ///
+    /// If the mint requires rebase memos, the previous instruction in the
+    /// transaction must be a memo.
+    ///
+    /// If the mint has a fee destination configured, it must be provided, and
+    /// a rebase increasing the supply credits it with `rebase_fee_bps` of the
+    /// increase, as new shares.
+    ///
+    /// The reason code and reference in the data aren't interpreted by the
+    /// program, only logged in a `crate::extension::rebase_mint::event::RebaseTagged`
+    /// event and recorded in the `RebaseHistory`, if the mint has one.
+    ///
+    /// If the data carries a nonzero `not_after_unix_timestamp`, the rebase
+    /// fails with `TokenError::RebaseExpired` once the cluster clock is past
+    /// it, before any other check. This keeps a rebase signed ahead of time
+    /// from landing on a much newer state of the mint.
+    ///
+    /// If the mint was initialized with `update_metadata_rate` and holds its
+    /// own token-metadata, the new scaled exchange rate is written to the
+    /// `rebaseRate` field, and the payer tops up the mint's rent if the
+    /// metadata grows. The payer and system program must be provided whenever
+    /// the mint has `update_metadata_rate` set, but the payer only needs to
+    /// sign if the metadata grows.
+    ///
+    /// If the mint has a co-signer, it must sign alongside the authority.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single authority
+    ///   0. `[writable]` The mint.
+    ///   1. `[]` The reserve token account, only if configured on the mint.
+    ///   2. `[writable]` The fee destination token account, only if
+    ///      configured on the mint.
+    ///   3. `[writable, signer]` The payer for metadata growth, only if the
+    ///      mint has `update_metadata_rate` set.
+    ///   4. `[]` The system program, only if the mint has
+    ///      `update_metadata_rate` set.
+    ///   5. `[signer]` The mint's co-signer, only if configured on the mint.
+    ///   6. `[signer]` The mint's supply authority or rebase delegate.
+    ///
+    ///   * Multisignature authority
+    ///   0. `[writable]` The mint.
+    ///   1. `[]` The reserve token account, only if configured on the mint.
+    ///   2. `[writable]` The fee destination token account, only if
+    ///      configured on the mint.
+    ///   3. `[writable, signer]` The payer for metadata growth, only if the
+    ///      mint has `update_metadata_rate` set.
+    ///   4. `[]` The system program, only if the mint has
+    ///      `update_metadata_rate` set.
+    ///   5. `[signer]` The mint's co-signer, only if configured on the mint.
+    ///   6. `[]` The mint's multisignature supply authority or rebase
+    ///      delegate.
+    ///   7. ..7+M `[signer]` M signer accounts.
+    ///
+    /// Data expected by this instruction:
+    ///   `crate::extension::rebase_mint::instruction::RebaseSupplyData`
+    RebaseSupply,
+    /// Set or clear the reserve account that caps rebases. Only supported for
+    /// mints that include the `RebaseMintConfig` extension.
+    ///
+    /// Logs a `crate::extension::rebase_mint::event::ReserveAccountChanged`
+    /// event.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single authority
+    ///   0. `[writable]` The mint.
+    ///   1. `[signer]` The mint's supply authority.
+    ///
+    ///   * Multisignature authority
+    ///   0. `[writable]` The mint.
+    ///   1. `[]` The mint's multisignature supply authority.
+    ///   2. ..2+M `[signer]` M signer accounts.
+    ///
+    /// Data expected by this instruction:
+    ///   `crate::extension::rebase_mint::instruction::SetReserveAccountData`
+    SetReserveAccount,
+    /// Run all the checks and computation of `RebaseSupply` without modifying
+    /// the mint, and return the outcome as a
+    /// `crate::extension::rebase_mint::RebasePreview` through return data.
+    ///
+    /// Since nothing is written, the supply authority does not need to sign.
+    /// Intended to be used through transaction simulation.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[]` The mint.
+    ///   1. `[]` The reserve token account, only if configured on the mint.
+    ///
+    /// Data expected by this instruction:
+    ///   `crate::extension::rebase_mint::instruction::RebaseSupplyData`
+    PreviewRebaseSupply,
+    /// Set or revoke the supply authority. Revoking the authority permanently
+    /// disables rebasing, and clears any pending proposal. Only supported for
+    /// mints that include the `RebaseMintConfig` extension.
+    ///
+    /// If the mint requires a two-step authority transfer, this can only be
+    /// used to revoke the authority.
+    ///
+    /// If the mint has an authority change delay, a new authority is only
+    /// scheduled, and must be applied with `FinalizeAuthorityChange` once the
+    /// delay has elapsed.
+    ///
+    /// Logs a `crate::extension::rebase_mint::event::SupplyAuthorityChanged`, or
+    /// `SupplyAuthorityChangeScheduled` if the change is delayed, event.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single authority
+    ///   0. `[writable]` The mint.
+    ///   1. `[signer]` The mint's current supply authority.
+    ///
+    ///   * Multisignature authority
+    ///   0. `[writable]` The mint.
+    ///   1. `[]` The mint's multisignature supply authority.
+    ///   2. ..2+M `[signer]` M signer accounts.
+    ///
+    /// Data expected by this instruction:
+    ///   `crate::extension::rebase_mint::instruction::SetSupplyAuthorityData`
+    SetSupplyAuthority,
+    /// Propose a new supply authority, which takes over once it signs
+    /// `AcceptSupplyAuthority`. Fails if a proposal is already pending. Only
+    /// supported for mints that include the `RebaseMintConfig` extension.
+    ///
+    /// Logs a `crate::extension::rebase_mint::event::SupplyAuthorityProposed`
+    /// event.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single authority
+    ///   0. `[writable]` The mint.
+    ///   1. `[signer]` The mint's current supply authority.
+    ///
+    ///   * Multisignature authority
+    ///   0. `[writable]` The mint.
+    ///   1. `[]` The mint's multisignature supply authority.
+    ///   2. ..2+M `[signer]` M signer accounts.
+    ///
+    /// Data expected by this instruction:
+    ///   `crate::extension::rebase_mint::instruction::ProposeSupplyAuthorityData`
+    ProposeSupplyAuthority,
+    /// Accept a proposal to become the supply authority. If the mint has an
+    /// authority change delay, the change is scheduled rather than applied.
+    ///
+    /// Logs a `crate::extension::rebase_mint::event::SupplyAuthorityChanged`, or
+    /// `SupplyAuthorityChangeScheduled` if the change is delayed, event.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single authority
+    ///   0. `[writable]` The mint.
+    ///   1. `[signer]` The proposed supply authority.
+    ///
+    ///   * Multisignature authority
+    ///   0. `[writable]` The mint.
+    ///   1. `[]` The proposed multisignature supply authority.
+    ///   2. ..2+M `[signer]` M signer accounts.
+    ///
+    /// Data expected by this instruction:
+    ///   None
+    AcceptSupplyAuthority,
+    /// Cancel a pending supply authority proposal.
+    ///
+    /// Logs a `crate::extension::rebase_mint::event::SupplyAuthorityProposalCancelled`
+    /// event.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single authority
+    ///   0. `[writable]` The mint.
+    ///   1. `[signer]` The mint's current supply authority.
+    ///
+    ///   * Multisignature authority
+    ///   0. `[writable]` The mint.
+    ///   1. `[]` The mint's multisignature supply authority.
+    ///   2. ..2+M `[signer]` M signer accounts.
+    ///
+    /// Data expected by this instruction:
+    ///   None
+    CancelPendingAuthority,
+    /// Apply a scheduled change of supply authority once the mint's authority
+    /// change delay has elapsed. Anyone may call this.
+    ///
+    /// Logs a `crate::extension::rebase_mint::event::SupplyAuthorityChanged`
+    /// event.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The mint.
+    ///
+    /// Data expected by this instruction:
+    ///   None
+    FinalizeAuthorityChange,
+    /// Cancel a scheduled change of supply authority.
+    ///
+    /// Logs a `crate::extension::rebase_mint::event::SupplyAuthorityChangeCancelled`
+    /// event.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single authority
+    ///   0. `[writable]` The mint.
+    ///   1. `[signer]` The mint's current supply authority.
+    ///
+    ///   * Multisignature authority
+    ///   0. `[writable]` The mint.
+    ///   1. `[]` The mint's multisignature supply authority.
+    ///   2. ..2+M `[signer]` M signer accounts.
+    ///
+    /// Data expected by this instruction:
+    ///   None
+    CancelAuthorityChange,
+    /// Set or remove the rebase delegate, which may execute rebases but not
+    /// change the configuration. Only supported for mints that include the
+    /// `RebaseMintConfig` extension.
+    ///
+    /// Logs a `crate::extension::rebase_mint::event::RebaseDelegateChanged`
+    /// event.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single authority
+    ///   0. `[writable]` The mint.
+    ///   1. `[signer]` The mint's supply authority.
+    ///
+    ///   * Multisignature authority
+    ///   0. `[writable]` The mint.
+    ///   1. `[]` The mint's multisignature supply authority.
+    ///   2. ..2+M `[signer]` M signer accounts.
+    ///
+    /// Data expected by this instruction:
+    ///   `crate::extension::rebase_mint::instruction::SetRebaseDelegateData`
+    SetRebaseDelegate,
+    /// Transfer an exact number of shares between two accounts of a rebasing
+    /// mint, without converting through an amount of tokens.
+    ///
+    /// Otherwise behaves like `TransferChecked`, without checking decimals.
+    /// A transfer hook is given the amount of tokens the shares are worth,
+    /// rounding down, rather than the number of shares.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single owner/delegate
+    ///   0. `[writable]` The source account.
+    ///   1. `[]` The token mint.
+    ///   2. `[writable]` The destination account.
+    ///   3. `[signer]` The source account's owner/delegate.
+    ///
+    ///   * Multisignature owner/delegate
+    ///   0. `[writable]` The source account.
+    ///   1. `[]` The token mint.
+    ///   2. `[writable]` The destination account.
+    ///   3. `[]` The source account's multisignature owner/delegate.
+    ///   4. ..4+M `[signer]` M signer accounts.
+    ///
+    /// Data expected by this instruction:
+    ///   `crate::extension::rebase_mint::instruction::TransferSharesData`
+    TransferShares,
+    /// Transfer an exact number of shares between two accounts of a rebasing
+    /// mint, checking the mint's decimals like `TransferChecked`. A transfer
+    /// hook is given the amount of tokens the shares are worth, rounding down.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single owner/delegate
+    ///   0. `[writable]` The source account.
+    ///   1. `[]` The token mint.
+    ///   2. `[writable]` The destination account.
+    ///   3. `[signer]` The source account's owner/delegate.
+    ///
+    ///   * Multisignature owner/delegate
+    ///   0. `[writable]` The source account.
+    ///   1. `[]` The token mint.
+    ///   2. `[writable]` The destination account.
+    ///   3. `[]` The source account's multisignature owner/delegate.
+    ///   4. ..4+M `[signer]` M signer accounts.
+    ///
+    /// Data expected by this instruction:
+    ///   `crate::extension::rebase_mint::instruction::TransferSharesCheckedData`
+    TransferSharesChecked,
+    /// Mint an exact number of new shares to an account of a rebasing mint.
+    ///
+    /// The tokens backing the shares, rounded up, are added to the mint's
+    /// total supply, so the value of existing shares is unchanged.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single authority
+    ///   0. `[writable]` The mint.
+    ///   1. `[writable]` The account to mint shares to.
+    ///   2. `[signer]` The mint's minting authority.
+    ///
+    ///   * Multisignature authority
+    ///   0. `[writable]` The mint.
+    ///   1. `[writable]` The account to mint shares to.
+    ///   2. `[]` The mint's multisignature mint-tokens authority.
+    ///   3. ..3+M `[signer]` M signer accounts.
+    ///
+    /// Data expected by this instruction:
+    ///   `crate::extension::rebase_mint::instruction::MintToSharesData`
+    MintToShares,
+    /// Burn an exact number of shares from an account of a rebasing mint.
+    ///
+    /// The tokens backed by the shares, rounded down, are removed from the
+    /// mint's total supply, so the value of remaining shares is unchanged.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single owner/delegate
+    ///   0. `[writable]` The account to burn from.
+    ///   1. `[writable]` The token mint.
+    ///   2. `[signer]` The account's owner/delegate.
+    ///
+    ///   * Multisignature owner/delegate
+    ///   0. `[writable]` The account to burn from.
+    ///   1. `[writable]` The token mint.
+    ///   2. `[]` The account's multisignature owner/delegate.
+    ///   3. ..3+M `[signer]` M signer accounts.
+    ///
+    /// Data expected by this instruction:
+    ///   `crate::extension::rebase_mint::instruction::BurnSharesData`
+    BurnShares,
+    /// Set the total supply to the base mint's supply, reconciling an
+    /// `initial_supply` that disagrees with what was actually minted. The
+    /// total shares are scaled to keep the ratio. Anyone may call this, but
+    /// only until the supply is first rebased.
+    ///
+    /// Logs a `crate::extension::rebase_mint::SupplySynced` event.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The mint.
+    ///
+    /// Data expected by this instruction:
+    ///   None
+    SyncSupplyFromMint,
+    /// Get the current balance of an account of a rebasing mint, converting
+    /// its shares at the current ratio. No signer is needed, so this can be
+    /// simulated to read the balance.
+    ///
+    /// Return data is the balance as a little-endian `u64`, followed by its
+    /// UI representation as a UTF-8 string, like `AmountToUiAmount`.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[]` The token account.
+    ///   1. `[]` The token mint.
+    ///
+    /// Data expected by this instruction:
+    ///   None
+    GetRebasedBalance,
+    /// Convert an amount of tokens to the number of shares that represent it
+    /// at the current ratio, rounding down. No signer is needed.
+    ///
+    /// Return data is the number of shares as a little-endian `u64`.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[]` The token mint.
+    ///
+    /// Data expected by this instruction:
+    ///   `crate::extension::rebase_mint::instruction::AmountToSharesData`
+    ConvertAmountToShares,
+    /// Convert a number of shares to the amount of tokens they are worth at
+    /// the current ratio, rounding down. No signer is needed.
+    ///
+    /// Return data is the amount as a little-endian `u64`.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[]` The token mint.
+    ///
+    /// Data expected by this instruction:
+    ///   `crate::extension::rebase_mint::instruction::SharesToAmountData`
+    ConvertSharesToAmount,
+    /// Approve a delegate for an exact number of shares of a rebasing mint.
+    ///
+    /// Allowances on rebasing accounts are always kept in shares, so the
+    /// delegate may spend these shares whatever they're worth at the time.
+    /// `ApproveChecked` instead approves the shares an amount of tokens is
+    /// worth at approval time.
+    ///
+    /// Otherwise behaves like `ApproveChecked`, without checking decimals.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single owner
+    ///   0. `[writable]` The source account.
+    ///   1. `[]` The token mint.
+    ///   2. `[]` The delegate.
+    ///   3. `[signer]` The source account owner.
+    ///
+    ///   * Multisignature owner
+    ///   0. `[writable]` The source account.
+    ///   1. `[]` The token mint.
+    ///   2. `[]` The delegate.
+    ///   3. `[]` The source account's multisignature owner.
+    ///   4. ..4+M `[signer]` M signer accounts
+    ///
+    /// Data expected by this instruction:
+    ///   `crate::extension::rebase_mint::instruction::ApproveSharesData`
+    ApproveShares,
+    /// Exempt a token account from rebasing, or include it again. Only the
+    /// supply authority may do this.
+    ///
+    /// An exempt account's shares are converted at the current ratio to a
+    /// fixed amount of tokens, which rebases leave untouched. Transfers,
+    /// mints, and burns involving an exempt account convert between its
+    /// amount and shares, so the mint must be writable for them.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single authority
+    ///   0. `[writable]` The token account.
+    ///   1. `[writable]` The mint.
+    ///   2. `[signer]` The mint's supply authority.
+    ///
+    ///   * Multisignature authority
+    ///   0. `[writable]` The token account.
+    ///   1. `[writable]` The mint.
+    ///   2. `[]` The mint's multisignature supply authority.
+    ///   3. ..3+M `[signer]` M signer accounts.
+    ///
+    /// Data expected by this instruction:
+    ///   `crate::extension::rebase_mint::instruction::SetAccountExemptData`
+    SetAccountExempt,
+    /// Create the non-rebasing wrapper of a rebasing mint: a plain wrapper
+    /// mint with the same decimals, and an escrow token account holding the
+    /// wrapped shares. Anyone may pay for this, once per mint.
+    ///
+    /// Both accounts, and the authority that mints the wrapper and owns the
+    /// escrow, are program-derived addresses of the rebasing mint, see
+    /// `crate::extension::rebase_mint::wrapper`.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable,signer]` Funding account (must be a system account).
+    ///   1. `[]` The rebasing mint.
+    ///   2. `[writable]` The wrapper mint.
+    ///   3. `[writable]` The escrow token account.
+    ///   4. `[]` System program for mint account funding.
+    ///
+    /// Data expected by this instruction:
+    ///   None
+    InitializeWrapper,
+    /// Wrap an exact number of shares of a rebasing mint, moving them into
+    /// the escrow and minting the same number of wrapper tokens. Since the
+    /// wrapper doesn't rebase, the escrowed shares capture every rebase until
+    /// they're unwrapped.
+    ///
+    /// The shares move like `TransferShares` from the source account.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single owner/delegate
+    ///   0. `[writable]` The source account of the rebasing mint.
+    ///   1. `[]` The rebasing mint.
+    ///   2. `[writable]` The escrow token account.
+    ///   3. `[writable]` The wrapper mint.
+    ///   4. `[writable]` The destination account of the wrapper mint.
+    ///   5. `[]` The wrapper authority.
+    ///   6. `[]` This token program, to mint the wrapper.
+    ///   7. `[signer]` The source account's owner/delegate.
+    ///
+    ///   * Multisignature owner/delegate
+    ///   0-6. As above.
+    ///   7. `[]` The source account's multisignature owner/delegate.
+    ///   8. ..8+M `[signer]` M signer accounts.
+    ///
+    /// Data expected by this instruction:
+    ///   `crate::extension::rebase_mint::instruction::WrapSharesData`
+    WrapShares,
+    /// Unwrap an exact number of shares of a rebasing mint, burning the same
+    /// number of wrapper tokens and releasing the shares from the escrow.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single owner/delegate
+    ///   0. `[writable]` The source account of the wrapper mint.
+    ///   1. `[writable]` The wrapper mint.
+    ///   2. `[writable]` The escrow token account.
+    ///   3. `[]` The rebasing mint.
+    ///   4. `[writable]` The destination account of the rebasing mint.
+    ///   5. `[]` The wrapper authority.
+    ///   6. `[]` This token program, to release the shares.
+    ///   7. `[signer]` The source account's owner/delegate.
+    ///
+    ///   * Multisignature owner/delegate
+    ///   0-6. As above.
+    ///   7. `[]` The source account's multisignature owner/delegate.
+    ///   8. ..8+M `[signer]` M signer accounts.
+    ///
+    /// Data expected by this instruction:
+    ///   `crate::extension::rebase_mint::instruction::UnwrapSharesData`
+    UnwrapShares,
+    /// Check that the extension is consistent with the base mint, failing if
+    /// any invariant is violated. No signer is needed, so anyone can run it,
+    /// for example ahead of other instructions in a transaction.
+    ///
+    /// Return data is the checked values as a
+    /// `crate::extension::rebase_mint::RebaseConsistency`.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[]` The mint.
+    ///
+    /// Data expected by this instruction:
+    ///   None
+    AssertRebaseConsistency,
+    /// Transfer an amount of tokens between two accounts of a rebasing mint,
+    /// like `TransferChecked`, failing if the number of shares it moves is
+    /// outside `max_share_slippage_bps` basis points of `expected_shares`.
+    ///
+    /// Protects the sender from a rebase landing between building and
+    /// executing the transaction. A tolerance of zero requires the shares to
+    /// match exactly.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single owner/delegate
+    ///   0. `[writable]` The source account.
+    ///   1. `[]` The token mint.
+    ///   2. `[writable]` The destination account.
+    ///   3. `[signer]` The source account's owner/delegate.
+    ///
+    ///   * Multisignature owner/delegate
+    ///   0. `[writable]` The source account.
+    ///   1. `[]` The token mint.
+    ///   2. `[writable]` The destination account.
+    ///   3. `[]` The source account's multisignature owner/delegate.
+    ///   4. ..4+M `[signer]` M signer accounts.
+    ///
+    /// Data expected by this instruction:
+    ///   `crate::extension::rebase_mint::instruction::TransferCheckedWithShareSlippageData`
+    TransferCheckedWithShareSlippage,
+    /// Return a snapshot of the mint's rebasing state, as a
+    /// `crate::extension::rebase_mint::RebaseState` through return data. No
+    /// signer is needed.
+    ///
+    /// Intended to be used through transaction simulation, so that clients
+    /// can read the state without decoding the mint with a matching version
+    /// of this library.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[]` The mint.
+    ///
+    /// Data expected by this instruction:
+    ///   None
+    GetRebaseState,
+    /// Initialize the `RebaseHistory` extension of a rebasing mint, which
+    /// keeps its last `crate::extension::rebase_mint::REBASE_HISTORY_LEN`
+    /// rebases.
+    ///
+    /// Fails if the mint has already been initialized, so must be called before
+    /// `InitializeMint`. The mint must also include the `RebaseMintConfig`
+    /// extension.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The mint to initialize.
+    ///
+    /// Data expected by this instruction:
+    ///   None
+    InitializeHistory,
+    /// Return the rebases kept by the mint's `RebaseHistory` extension
+    /// through return data. No signer is needed.
+    ///
+    /// The return data holds the number of rebases recorded since the history
+    /// was initialized, as a little-endian `u64`, followed by the kept
+    /// `crate::extension::rebase_mint::RebaseHistoryEntry`s, newest first.
+    /// Use `decode_rebase_history` to read it.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[]` The mint.
+    ///
+    /// Data expected by this instruction:
+    ///   None
+    GetRebaseHistory,
+    /// Set the share of every increase of the supply taken as fee, and the
+    /// token account of the mint credited with it. Only supported for mints
+    /// that include the `RebaseMintConfig` extension.
+    ///
+    /// Logs a `crate::extension::rebase_mint::event::RebaseFeeChanged` event.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single authority
+    ///   0. `[writable]` The mint.
+    ///   1. `[signer]` The mint's supply authority.
+    ///
+    ///   * Multisignature authority
+    ///   0. `[writable]` The mint.
+    ///   1. `[]` The mint's multisignature supply authority.
+    ///   2. ..2+M `[signer]` M signer accounts.
+    ///
+    /// Data expected by this instruction:
+    ///   `crate::extension::rebase_mint::instruction::SetRebaseFeeData`
+    SetRebaseFee,
+    /// Wind down a rebasing mint for good by rebasing its supply to zero,
+    /// which `RebaseSupply` never allows. Every share, including those held by
+    /// token accounts, becomes worth nothing, the mint's supply and shares are
+    /// written off, and every authority over the config is dropped, so that
+    /// the supply can never be rebased nor shares minted again. The mint can
+    /// then be closed if it has a close authority.
+    ///
+    /// Only supported for mints initialized with `allow_sunset`, and fails
+    /// while accounts exempt from rebasing hold tokens. If the mint has a
+    /// co-signer, it must sign too.
+    ///
+    /// Logs a `crate::extension::rebase_mint::event::RebaseMintSunset` event.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single authority
+    ///   0. `[writable]` The mint.
+    ///   1. `[signer]` The mint's co-signer, only if configured on the mint.
+    ///   2. `[signer]` The mint's supply authority.
+    ///
+    ///   * Multisignature authority
+    ///   0. `[writable]` The mint.
+    ///   1. `[signer]` The mint's co-signer, only if configured on the mint.
+    ///   2. `[]` The mint's multisignature supply authority.
+    ///   3. ..3+M `[signer]` M signer accounts.
+    ///
+    /// Data expected by this instruction:
+    ///   None
+    SunsetRebase,
+    /// Retire a rebasing mint by freezing the ratio between its tokens and
+    /// shares for good. The mint's current supply and shares are recorded as
+    /// the fixed basis for all conversions, so that holders keep exactly the
+    /// balance they had at retirement, and every authority over the config is
+    /// dropped. Tokens can still be minted, burned and transferred at the
+    /// frozen ratio, but the supply can never be rebased or synced again.
+    ///
+    /// Fails if the mint was sunset.
+    ///
+    /// Logs a `crate::extension::rebase_mint::event::RebaseMintRetired` event.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single authority
+    ///   0. `[writable]` The mint.
+    ///   1. `[signer]` The mint's supply authority.
+    ///
+    ///   * Multisignature authority
+    ///   0. `[writable]` The mint.
+    ///   1. `[]` The mint's multisignature supply authority.
+    ///   2. ..2+M `[signer]` M signer accounts.
+    ///
+    /// Data expected by this instruction:
+    ///   None
+    RetireRebase,
+    /// Sweep the rounding dust of a rebasing mint into a token account. The
+    /// dust is the shares counted in the totals that no token account holds,
+    /// left over when the totals are rescaled and by any initial supply that
+    /// was never minted. It is credited to the token account, which must be a
+    /// rebasing account of the mint, and counted as outstanding from then on.
+    /// The totals, and so every other holder's balance, don't change.
+    ///
+    /// Logs a `crate::extension::rebase_mint::event::RoundingDustSwept`
+    /// event.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single authority
+    ///   0. `[writable]` The mint.
+    ///   1. `[writable]` The token account credited with the dust.
+    ///   2. `[signer]` The mint's supply authority.
+    ///
+    ///   * Multisignature authority
+    ///   0. `[writable]` The mint.
+    ///   1. `[writable]` The token account credited with the dust.
+    ///   2. `[]` The mint's multisignature supply authority.
+    ///   3. ..3+M `[signer]` M signer accounts.
+    ///
+    /// Data expected by this instruction:
+    ///   None
+    SweepRoundingDust,
+    /// Catch a token account of a mint in `RebaseMode::Lazy` up with the
+    /// rebases it missed, scaling its amount to the mint's current rebase
+    /// index. No signer is needed, since the account's value doesn't change,
+    /// only its stored amount. Transfers, mints and burns catch up the
+    /// accounts they touch on their own.
+    ///
+    /// Fails if the mint isn't lazy.
+    ///
+    /// Logs a `crate::extension::rebase_mint::event::RebaseClaimed` event.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writable]` The token account.
+    ///   1. `[]` The token mint.
+    ///
+    /// Data expected by this instruction:
+    ///   None
+    ClaimRebase,
+    /// Rebase the total supply like `RebaseSupply`, to a new supply given in
+    /// UI units, such as `"1250000.5"`, rather than base units.
+    ///
+    /// The program converts the string with the mint's own `decimals`, so the
+    /// signer never has to. It must be plain ASCII digits with at most one
+    /// decimal point between them: signs, separators, exponents, and
+    /// fractions finer than the mint's decimals all fail with
+    /// `TokenError::RebaseUiSupplyMalformed`. A string longer than
+    /// `MAX_UI_SUPPLY_LEN` fails with `TokenError::RebaseUiSupplyTooLong`.
+    ///
+    /// Everything else, including the reserve cap, the fee, the authority
+    /// checks, the reason code, reference and deadline, follows
+    /// `RebaseSupply`. As with `RebaseSupply`, there is no check of the
+    /// current supply, so the new supply is absolute.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   The same as `RebaseSupply`.
+    ///
+    /// Data expected by this instruction:
+    ///   The new supply in UI units, as a little-endian `u32` byte length
+    ///   followed by that many bytes of UTF-8, then
+    ///   `crate::extension::rebase_mint::instruction::RebaseSupplyUiTags`,
+    ///   which may be left out. See
+    ///   `crate::extension::rebase_mint::instruction::decode_rebase_supply_ui_data`.
+    RebaseSupplyUi,
+}
+
+/// Data expected by `RebaseMintInstruction::Initialize`
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+#[repr(C)]
+pub struct InitializeInstructionData {
+    /// The public key for the account that can rebase the supply
+    pub supply_authority: OptionalNonZeroPubkey,
+    /// The initial supply, divided into `initial_shares` shares
+    pub initial_supply: PodU64,
+    /// Unix timestamp before which the supply cannot be rebased, or zero to
+    /// allow rebasing right away
+    pub first_rebase_after_timestamp: PodI64,
+    /// If true, the supply authority can only be transferred by proposal and
+    /// acceptance
+    pub two_step_authority_transfer: PodBool,
+    /// Number of seconds a change of supply authority must wait before it can
+    /// be finalized, or zero to apply changes immediately
+    pub authority_change_delay_seconds: PodU32,
+    /// If true, the mint authority can rebase the supply while there is no
+    /// supply authority
+    pub use_mint_authority_fallback: PodBool,
+    /// If true, every rebase must be preceded by a memo in the same
+    /// transaction
+    pub require_rebase_memo: PodBool,
+    /// Virtual shares and tokens added to the totals in every conversion
+    pub virtual_shares_offset: PodU64,
+    /// If false, the supply can never be rebased lower than it is
+    pub allow_supply_decrease: PodBool,
+    /// Share of every increase of the supply taken as fee, in basis points
+    pub rebase_fee_bps: PodU16,
+    /// Token account of the mint credited with the rebase fee, or `None` to
+    /// charge no fee
+    pub fee_destination: OptionalNonZeroPubkey,
+    /// Number of shares that the initial supply is divided into, or zero for
+    /// one share per token. Must be zero if the initial supply is.
+    pub initial_shares: PodU64,
+    /// Most shares that may ever exist at once, or zero for
+    /// `DEFAULT_MAX_TOTAL_SHARES`
+    pub max_total_shares: PodU64,
+    /// If true, the supply authority can sunset the mint with
+    /// `RebaseMintInstruction::SunsetRebase`
+    pub allow_sunset: PodBool,
+    /// If true, every rebase writes the exchange rate into the mint's
+    /// token-metadata, if it has any
+    pub update_metadata_rate: PodBool,
+    /// Key that must sign alongside the supply authority on every rebase and
+    /// sunset, or `None` for single control
+    pub co_signer: OptionalNonZeroPubkey,
+    /// `RebaseMode` of the mint, as a byte
+    pub rebase_mode: u8,
+    /// If true, every rebase also writes the new supply into the base mint's
+    /// `supply`. Requires an initial supply of zero.
+    pub sync_base_supply: PodBool,
+}
+
+/// Data expected by `RebaseMintInstruction::ProposeSupplyAuthority`
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+#[repr(C)]
+pub struct ProposeSupplyAuthorityData {
+    /// The proposed supply authority
+    pub pending_authority: OptionalNonZeroPubkey,
+}
+
+/// Data expected by `RebaseMintInstruction::RebaseSupply`
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+#[repr(C)]
+pub struct RebaseSupplyData {
+    /// The new total supply
+    pub new_supply: PodU64,
+    /// Machine-readable reason for the rebase, or zero if unset
+    #[cfg_attr(feature = "serde-traits", serde(default))]
+    pub reason_code: u8,
+    /// Operator-supplied reference, such as the hash of a report, or zeroes
+    /// if unset
+    #[cfg_attr(feature = "serde-traits", serde(default))]
+    pub reference: [u8; 32],
+    /// Last unix timestamp at which the rebase may execute, or zero for no
+    /// deadline
+    #[cfg_attr(feature = "serde-traits", serde(default))]
+    pub not_after_unix_timestamp: PodI64,
+}
+
+/// Decode `RebaseSupplyData` from instruction data, also accepting the data of
+/// rebases built before the reason code and reference, or the deadline,
+/// existed, which leaves them unset
+pub fn decode_rebase_supply_data(input: &[u8]) -> Result<RebaseSupplyData, ProgramError> {
+    // every earlier layout is a prefix of the current one
+    const UNTAGGED_LEN: usize = size_of::<PodU64>();
+    const UNDATED_LEN: usize = size_of::<RebaseSupplyData>() - size_of::<PodI64>();
+    let data = input.get(1..).ok_or(ProgramError::InvalidInstructionData)?;
+    if data.len() == UNTAGGED_LEN || data.len() == UNDATED_LEN {
+        let mut rebase_supply_data = RebaseSupplyData::zeroed();
+        bytemuck::bytes_of_mut(&mut rebase_supply_data)[..data.len()].copy_from_slice(data);
+        Ok(rebase_supply_data)
+    } else {
+        decode_instruction_data(input).copied()
+    }
+}
+
+/// Longest new supply accepted by `RebaseMintInstruction::RebaseSupplyUi`, in
+/// bytes. The largest `u64` is 20 digits, so this leaves room for any number
+/// of decimals a mint can have.
+pub const MAX_UI_SUPPLY_LEN: usize = 64;
+
+/// Tags following the new supply in the data of
+/// `RebaseMintInstruction::RebaseSupplyUi`, as in `RebaseSupplyData`
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+#[repr(C)]
+pub struct RebaseSupplyUiTags {
+    /// Machine-readable reason for the rebase, or zero if unset
+    #[cfg_attr(feature = "serde-traits", serde(default))]
+    pub reason_code: u8,
+    /// Operator-supplied reference, such as the hash of a report, or zeroes
+    /// if unset
+    #[cfg_attr(feature = "serde-traits", serde(default))]
+    pub reference: [u8; 32],
+    /// Last unix timestamp at which the rebase may execute, or zero for no
+    /// deadline
+    #[cfg_attr(feature = "serde-traits", serde(default))]
+    pub not_after_unix_timestamp: PodI64,
+}
+
+/// Decode the new supply in UI units of
+/// `RebaseMintInstruction::RebaseSupplyUi` from instruction data, checking its
+/// length and encoding but not its contents, along with its tags, which are
+/// left unset in the data of rebases built before they existed
+pub fn decode_rebase_supply_ui_data(
+    input: &[u8],
+) -> Result<(&str, RebaseSupplyUiTags), ProgramError> {
+    let data = input.get(1..).ok_or(ProgramError::InvalidInstructionData)?;
+    if data.len() < size_of::<u32>() {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let (len, rest) = data.split_at(size_of::<u32>());
+    let len = u32::from_le_bytes(len.try_into().unwrap()) as usize;
+    let (ui_supply, tags) = if rest.len() == len {
+        (rest, RebaseSupplyUiTags::zeroed())
+    } else if rest.len() == len.saturating_add(size_of::<RebaseSupplyUiTags>()) {
+        let (ui_supply, tags) = rest.split_at(len);
+        (ui_supply, *bytemuck::from_bytes::<RebaseSupplyUiTags>(tags))
+    } else {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+    if len > MAX_UI_SUPPLY_LEN {
+        return Err(TokenError::RebaseUiSupplyTooLong.into());
+    }
+    let ui_supply =
+        std::str::from_utf8(ui_supply).map_err(|_| TokenError::RebaseUiSupplyMalformed)?;
+    Ok((ui_supply, tags))
+}
+
+/// Data expected by `RebaseMintInstruction::SetReserveAccount`
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+#[repr(C)]
+pub struct SetReserveAccountData {
+    /// The new reserve token account, or `None` to remove the cap
+    pub reserve_account: OptionalNonZeroPubkey,
+}
+
+/// Data expected by `RebaseMintInstruction::SetRebaseFee`
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+#[repr(C)]
+pub struct SetRebaseFeeData {
+    /// Share of every increase of the supply taken as fee, in basis points
+    pub rebase_fee_bps: PodU16,
+    /// The new fee destination token account, or `None` to charge no fee
+    pub fee_destination: OptionalNonZeroPubkey,
+}
+
+/// Data expected by `RebaseMintInstruction::SetSupplyAuthority`
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+#[repr(C)]
+pub struct SetSupplyAuthorityData {
+    /// The new supply authority, or `None` to disable rebasing
+    pub new_authority: OptionalNonZeroPubkey,
+}
+
+/// Data expected by `RebaseMintInstruction::SetRebaseDelegate`
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+#[repr(C)]
+pub struct SetRebaseDelegateData {
+    /// The new rebase delegate, or `None` to remove it
+    pub rebase_delegate: OptionalNonZeroPubkey,
+}
+
+/// Data expected by `RebaseMintInstruction::TransferShares`
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+#[repr(C)]
+pub struct TransferSharesData {
+    /// The number of shares to transfer
+    pub shares: PodU64,
+}
+
+/// Data expected by `RebaseMintInstruction::TransferSharesChecked`
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+#[repr(C)]
+pub struct TransferSharesCheckedData {
+    /// The number of shares to transfer
+    pub shares: PodU64,
+    /// Expected number of base 10 digits to the right of the decimal place
+    pub decimals: u8,
+}
+
+/// Data expected by `RebaseMintInstruction::TransferCheckedWithShareSlippage`
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+#[repr(C)]
+pub struct TransferCheckedWithShareSlippageData {
+    /// The amount of tokens to transfer
+    pub amount: PodU64,
+    /// Expected number of base 10 digits to the right of the decimal place
+    pub decimals: u8,
+    /// The number of shares the amount is expected to move
+    pub expected_shares: PodU64,
+    /// Maximum deviation from `expected_shares`, in basis points
+    pub max_share_slippage_bps: PodU16,
+}
+
+/// Data expected by `RebaseMintInstruction::MintToShares`
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+#[repr(C)]
+pub struct MintToSharesData {
+    /// The number of shares to mint
+    pub shares: PodU64,
+}
+
+/// Data expected by `RebaseMintInstruction::BurnShares`
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+#[repr(C)]
+pub struct BurnSharesData {
+    /// The number of shares to burn
+    pub shares: PodU64,
+}
+
+/// Data expected by `RebaseMintInstruction::ConvertAmountToShares`
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+#[repr(C)]
+pub struct AmountToSharesData {
+    /// The amount of tokens to convert
+    pub amount: PodU64,
+}
+
+/// Data expected by `RebaseMintInstruction::ConvertSharesToAmount`
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+#[repr(C)]
+pub struct SharesToAmountData {
+    /// The number of shares to convert
+    pub shares: PodU64,
+}
+
+/// Data expected by `RebaseMintInstruction::ApproveShares`
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+#[repr(C)]
+pub struct ApproveSharesData {
+    /// The number of shares the delegate is approved for
+    pub shares: PodU64,
+}
+
+/// Data expected by `RebaseMintInstruction::SetAccountExempt`
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+#[repr(C)]
+pub struct SetAccountExemptData {
+    /// If true, exempt the account from rebasing, otherwise include it again
+    pub exempt: PodBool,
+}
+
+/// Data expected by `RebaseMintInstruction::WrapShares`
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+#[repr(C)]
+pub struct WrapSharesData {
+    /// The number of shares to wrap
+    pub shares: PodU64,
+}
+
+/// Data expected by `RebaseMintInstruction::UnwrapShares`
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+#[repr(C)]
+pub struct UnwrapSharesData {
+    /// The number of shares to unwrap
+    pub shares: PodU64,
+}
+
+/// Builder for `RebaseMintInstruction::Initialize` instructions, starting
+/// from the same defaults as passing zeroes and `None` to `initialize`
+///
+/// Unlike `initialize`, `build` catches the mistakes that the program would
+/// reject before the instruction is sent.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InitializeRebaseMintBuilder {
+    token_program_id: Pubkey,
+    mint: Option<Pubkey>,
+    supply_authority: Option<Pubkey>,
+    initial_supply: u64,
+    first_rebase_after_timestamp: Option<i64>,
+    two_step_authority_transfer: bool,
+    authority_change_delay_seconds: u32,
+    use_mint_authority_fallback: bool,
+    require_rebase_memo: bool,
+    virtual_shares_offset: u64,
+    allow_supply_decrease: bool,
+    rebase_fee_bps: u16,
+    fee_destination: Option<Pubkey>,
+    initial_shares: u64,
+    max_total_shares: u64,
+    allow_sunset: bool,
+    update_metadata_rate: bool,
+    co_signer: Option<Pubkey>,
+    rebase_mode: RebaseMode,
+    sync_base_supply: bool,
+}
+
+impl InitializeRebaseMintBuilder {
+    /// Start building an `Initialize` instruction for `token_program_id`
+    pub fn new(token_program_id: &Pubkey) -> Self {
+        Self {
+            token_program_id: *token_program_id,
+            mint: None,
+            supply_authority: None,
+            initial_supply: 0,
+            first_rebase_after_timestamp: None,
+            two_step_authority_transfer: false,
+            authority_change_delay_seconds: 0,
+            use_mint_authority_fallback: false,
+            require_rebase_memo: false,
+            virtual_shares_offset: 0,
+            allow_supply_decrease: false,
+            rebase_fee_bps: 0,
+            fee_destination: None,
+            initial_shares: 0,
+            max_total_shares: 0,
+            allow_sunset: false,
+            update_metadata_rate: false,
+            co_signer: None,
+            rebase_mode: RebaseMode::Shares,
+            sync_base_supply: false,
+        }
+    }
+
+    /// Set the mint to initialize, which is required
+    pub fn mint(mut self, mint: &Pubkey) -> Self {
+        self.mint = Some(*mint);
+        self
+    }
+
+    /// Set the account that can rebase the supply
+    pub fn supply_authority(mut self, supply_authority: &Pubkey) -> Self {
+        self.supply_authority = Some(*supply_authority);
+        self
+    }
+
+    /// Set the initial supply, and the number of shares it is divided into,
+    /// or zero for one share per token
+    pub fn initial_supply(mut self, initial_supply: u64, initial_shares: u64) -> Self {
+        self.initial_supply = initial_supply;
+        self.initial_shares = initial_shares;
+        self
+    }
+
+    /// Set the unix timestamp before which the supply cannot be rebased
+    pub fn first_rebase_after_timestamp(mut self, unix_timestamp: i64) -> Self {
+        self.first_rebase_after_timestamp = Some(unix_timestamp);
+        self
+    }
+
+    /// Only allow transfers of the supply authority by proposal and
+    /// acceptance
+    pub fn two_step_authority_transfer(mut self) -> Self {
+        self.two_step_authority_transfer = true;
+        self
+    }
+
+    /// Set the number of seconds a change of supply authority must wait
+    /// before it can be finalized
+    pub fn authority_change_delay_seconds(mut self, delay_seconds: u32) -> Self {
+        self.authority_change_delay_seconds = delay_seconds;
+        self
+    }
+
+    /// Let the mint authority rebase the supply while there is no supply
+    /// authority
+    pub fn use_mint_authority_fallback(mut self) -> Self {
+        self.use_mint_authority_fallback = true;
+        self
+    }
+
+    /// Require every rebase to be preceded by a memo
+    pub fn require_rebase_memo(mut self) -> Self {
+        self.require_rebase_memo = true;
+        self
+    }
+
+    /// Set the virtual shares and tokens added to the totals in every
+    /// conversion
+    pub fn virtual_shares_offset(mut self, virtual_shares_offset: u64) -> Self {
+        self.virtual_shares_offset = virtual_shares_offset;
+        self
+    }
+
+    /// Allow rebasing the supply lower than it is
+    pub fn allow_supply_decrease(mut self) -> Self {
+        self.allow_supply_decrease = true;
+        self
+    }
+
+    /// Take `rebase_fee_bps` of every increase of the supply as fee, credited
+    /// to `fee_destination`
+    pub fn rebase_fee(mut self, rebase_fee_bps: u16, fee_destination: &Pubkey) -> Self {
+        self.rebase_fee_bps = rebase_fee_bps;
+        self.fee_destination = Some(*fee_destination);
+        self
+    }
+
+    /// Set the most shares that may ever exist at once
+    pub fn max_total_shares(mut self, max_total_shares: u64) -> Self {
+        self.max_total_shares = max_total_shares;
+        self
+    }
+
+    /// Let the supply authority sunset the mint
+    pub fn allow_sunset(mut self) -> Self {
+        self.allow_sunset = true;
+        self
+    }
+
+    /// Write the exchange rate into the mint's token-metadata on every rebase
+    pub fn update_metadata_rate(mut self) -> Self {
+        self.update_metadata_rate = true;
+        self
+    }
+
+    /// Require `co_signer` to sign alongside the supply authority on every
+    /// rebase and sunset
+    pub fn co_signer(mut self, co_signer: &Pubkey) -> Self {
+        self.co_signer = Some(*co_signer);
+        self
+    }
+
+    /// Set how token accounts follow the rebases, `RebaseMode::Shares` unless
+    /// set
+    pub fn rebase_mode(mut self, rebase_mode: RebaseMode) -> Self {
+        self.rebase_mode = rebase_mode;
+        self
+    }
+
+    /// Write the new supply into the base mint's `supply` on every rebase
+    pub fn sync_base_supply(mut self) -> Self {
+        self.sync_base_supply = true;
+        self
+    }
+
+    /// Build the instruction data, without validating it
+    pub fn data(&self) -> Result<InitializeInstructionData, ProgramError> {
+        Ok(InitializeInstructionData {
+            supply_authority: self.supply_authority.try_into()?,
+            initial_supply: self.initial_supply.into(),
+            first_rebase_after_timestamp: self.first_rebase_after_timestamp.unwrap_or(0).into(),
+            two_step_authority_transfer: self.two_step_authority_transfer.into(),
+            authority_change_delay_seconds: self.authority_change_delay_seconds.into(),
+            use_mint_authority_fallback: self.use_mint_authority_fallback.into(),
+            require_rebase_memo: self.require_rebase_memo.into(),
+            virtual_shares_offset: self.virtual_shares_offset.into(),
+            allow_supply_decrease: self.allow_supply_decrease.into(),
+            rebase_fee_bps: self.rebase_fee_bps.into(),
+            fee_destination: self.fee_destination.try_into()?,
+            initial_shares: self.initial_shares.into(),
+            max_total_shares: self.max_total_shares.into(),
+            allow_sunset: self.allow_sunset.into(),
+            update_metadata_rate: self.update_metadata_rate.into(),
+            co_signer: self.co_signer.try_into()?,
+            rebase_mode: self.rebase_mode.into(),
+            sync_base_supply: self.sync_base_supply.into(),
+        })
+    }
+
+    /// Build the instruction.
+    ///
+    /// Fails with `ProgramError::NotEnoughAccountKeys` without a mint, and
+    /// with the error the program would return if the initial supply is zero
+    /// but divided into shares, the fee is above `MAX_REBASE_FEE_BPS`, the
+    /// initial shares exceed the cap, a lazy mint sets anything that only
+    /// applies to shares, or a mint syncing its base supply has an initial
+    /// supply.
+    pub fn build(self) -> Result<Instruction, ProgramError> {
+        if self.mint.is_none() {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        if self.rebase_mode == RebaseMode::Lazy && self.sets_share_options() {
+            return Err(TokenError::RebaseModeMismatch.into());
+        }
+        if self.initial_supply == 0 && self.initial_shares != 0 {
+            return Err(TokenError::RebaseSupplyZero.into());
+        }
+        if self.sync_base_supply && self.initial_supply != 0 {
+            return Err(TokenError::RebaseBaseSupplyMismatch.into());
+        }
+        if self.rebase_fee_bps > MAX_REBASE_FEE_BPS {
+            return Err(TokenError::RebaseFeeTooHigh.into());
+        }
+        let initial_shares = if self.initial_shares == 0 {
+            self.initial_supply
+        } else {
+            self.initial_shares
+        };
+        let max_total_shares = if self.max_total_shares == 0 {
+            DEFAULT_MAX_TOTAL_SHARES
+        } else {
+            self.max_total_shares
+        };
+        if initial_shares > max_total_shares {
+            return Err(TokenError::RebaseSharesCapExceeded.into());
+        }
+        self.instruction()
+    }
+
+    /// Build the instruction without checking the settings, so that the
+    /// program is the one to reject them. Still fails with
+    /// `ProgramError::NotEnoughAccountKeys` without a mint.
+    pub fn build_unchecked(self) -> Result<Instruction, ProgramError> {
+        self.instruction()
+    }
+
+    /// Check if any setting that only applies to `RebaseMode::Shares` is set
+    fn sets_share_options(&self) -> bool {
+        self.initial_supply != 0
+            || self.initial_shares != 0
+            || self.virtual_shares_offset != 0
+            || self.rebase_fee_bps != 0
+            || self.fee_destination.is_some()
+            || self.max_total_shares != 0
+            || self.allow_sunset
+            || self.update_metadata_rate
+    }
+
+    /// Encode the instruction, leaving every check of the settings to the
+    /// program
+    fn instruction(&self) -> Result<Instruction, ProgramError> {
+        check_program_account(&self.token_program_id)?;
+        let mint = self.mint.ok_or(ProgramError::NotEnoughAccountKeys)?;
+        Ok(encode_instruction(
+            &self.token_program_id,
+            vec![AccountMeta::new(mint, false)],
+            TokenInstruction::RebaseMintExtension,
+            RebaseMintInstruction::Initialize,
+            &self.data()?,
+        ))
+    }
+}
+
+/// Create an `Initialize` instruction
+///
+/// If `supply_authority` is `None`, the supply can never be rebased, so the
+/// mint stays fixed at one token per share, unless
+/// `use_mint_authority_fallback` lets the mint authority rebase instead.
+#[allow(clippy::too_many_arguments)]
+pub fn initialize(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    supply_authority: Option<Pubkey>,
+    initial_supply: u64,
+    first_rebase_after_timestamp: Option<i64>,
+    two_step_authority_transfer: bool,
+    authority_change_delay_seconds: u32,
+    use_mint_authority_fallback: bool,
+    require_rebase_memo: bool,
+    virtual_shares_offset: u64,
+    allow_supply_decrease: bool,
+    rebase_fee_bps: u16,
+    fee_destination: Option<Pubkey>,
+    initial_shares: u64,
+    max_total_shares: u64,
+    allow_sunset: bool,
+    update_metadata_rate: bool,
+) -> Result<Instruction, ProgramError> {
+    InitializeRebaseMintBuilder {
+        token_program_id: *token_program_id,
+        mint: Some(*mint),
+        supply_authority,
+        initial_supply,
+        first_rebase_after_timestamp,
+        two_step_authority_transfer,
+        authority_change_delay_seconds,
+        use_mint_authority_fallback,
+        require_rebase_memo,
+        virtual_shares_offset,
+        allow_supply_decrease,
+        rebase_fee_bps,
+        fee_destination,
+        initial_shares,
+        max_total_shares,
+        allow_sunset,
+        update_metadata_rate,
+        co_signer: None,
+        rebase_mode: RebaseMode::Shares,
+        sync_base_supply: false,
+    }
+    .instruction()
+}
+
+/// Greatest common divisor, by Euclid's algorithm
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Create an `Initialize` instruction for a mint that starts at
+/// `tokens_per_share_numerator / tokens_per_share_denominator` tokens per
+/// share, with every other setting left at its default. In particular, the
+/// supply can never be rebased lower than it is.
+///
+/// The rate is reduced to lowest terms, so that it is stored exactly, and then
+/// scaled up by `share_scale` on both sides, so that a virtual shares offset or
+/// later rounding barely moves it. Fails if any input is zero, the scaled
+/// supply doesn't fit in a `u64`, or the scaled shares exceed
+/// `DEFAULT_MAX_TOTAL_SHARES`.
+pub fn initialize_with_rate(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    supply_authority: Option<Pubkey>,
+    tokens_per_share_numerator: u64,
+    tokens_per_share_denominator: u64,
+    share_scale: u64,
+) -> Result<Instruction, ProgramError> {
+    if tokens_per_share_numerator == 0 || tokens_per_share_denominator == 0 || share_scale == 0 {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let divisor = gcd(tokens_per_share_numerator, tokens_per_share_denominator);
+    let initial_supply = (tokens_per_share_numerator / divisor)
+        .checked_mul(share_scale)
+        .ok_or(ProgramError::InvalidArgument)?;
+    let initial_shares = (tokens_per_share_denominator / divisor)
+        .checked_mul(share_scale)
+        .filter(|shares| *shares <= DEFAULT_MAX_TOTAL_SHARES)
+        .ok_or(ProgramError::InvalidArgument)?;
+    let mut builder = InitializeRebaseMintBuilder::new(token_program_id)
+        .mint(mint)
+        .initial_supply(initial_supply, initial_shares);
+    if let Some(supply_authority) = supply_authority {
+        builder = builder.supply_authority(&supply_authority);
+    }
+    builder.instruction()
+}
+
+// Wallets hard-code these layouts, so changing any of them breaks the wire
+// format. Instruction data isn't aligned either, so the structs must keep an
+// alignment of one to be cast from it.
+const _: () = {
+    use std::mem::align_of;
+    assert!(align_of::<InitializeInstructionData>() == 1);
+    assert!(align_of::<RebaseSupplyData>() == 1);
+    assert!(size_of::<InitializeInstructionData>() == 150);
+    // the whole history must fit in the return data of `GetRebaseHistory`
+    assert!(
+        size_of::<PodU64>() + REBASE_HISTORY_LEN * size_of::<RebaseHistoryEntry>()
+            <= solana_program::program::MAX_RETURN_DATA
+    );
+    assert!(size_of::<ProposeSupplyAuthorityData>() == 32);
+    assert!(size_of::<RebaseSupplyData>() == 49);
+    assert!(size_of::<SetReserveAccountData>() == 32);
+    assert!(size_of::<SetRebaseFeeData>() == 34);
+    assert!(size_of::<SetSupplyAuthorityData>() == 32);
+    assert!(size_of::<SetRebaseDelegateData>() == 32);
+    assert!(size_of::<TransferSharesData>() == 8);
+    assert!(size_of::<TransferSharesCheckedData>() == 9);
+    assert!(size_of::<TransferCheckedWithShareSlippageData>() == 19);
+    assert!(size_of::<MintToSharesData>() == 8);
+    assert!(size_of::<BurnSharesData>() == 8);
+    assert!(size_of::<AmountToSharesData>() == 8);
+    assert!(size_of::<SharesToAmountData>() == 8);
+    assert!(size_of::<ApproveSharesData>() == 8);
+    assert!(size_of::<SetAccountExemptData>() == 1);
+    assert!(size_of::<WrapSharesData>() == 8);
+    assert!(size_of::<UnwrapSharesData>() == 8);
+};
+
+/// Get the space to allocate for a rebasing mint that points to its own
+/// token-metadata, and the length it grows to once `token_metadata` is
+/// written into it.
+///
+/// The account must be created with exactly the first length, since
+/// `InitializeMint` rejects any spare bytes, but funded for the second, since
+/// the metadata is reallocated into the account without a transfer.
+pub fn get_mint_len_with_metadata(
+    token_metadata: &TokenMetadata,
+) -> Result<(usize, usize), ProgramError> {
+    let space = ExtensionType::try_calculate_account_len::<Mint>(&[
+        ExtensionType::RebaseMintConfig,
+        ExtensionType::MetadataPointer,
+    ])?;
+    let metadata_len = add_type_and_length_to_len(token_metadata.get_packed_len()?);
+    Ok((space, space.saturating_add(metadata_len)))
+}
+
+/// Create the full list of instructions to set up a rebasing mint with a
+/// metadata pointer to itself and its initial token-metadata, in the order
+/// the program requires: account creation, the extensions, `InitializeMint2`,
+/// then the metadata.
+///
+/// `lamports` must cover the rent for the full length returned by
+/// `get_mint_len_with_metadata`. The payer, the mint and the mint authority
+/// must all sign the transaction.
+#[allow(clippy::too_many_arguments)]
+pub fn create_mint_with_metadata(
+    token_program_id: &Pubkey,
+    payer: &Pubkey,
+    mint: &Pubkey,
+    lamports: u64,
+    mint_authority: &Pubkey,
+    freeze_authority: Option<&Pubkey>,
+    decimals: u8,
+    config: &InitializeInstructionData,
+    update_authority: &Pubkey,
+    name: String,
+    symbol: String,
+    uri: String,
+) -> Result<Vec<Instruction>, ProgramError> {
+    check_program_account(token_program_id)?;
+    let (space, _) = get_mint_len_with_metadata(&TokenMetadata::default())?;
+    Ok(vec![
+        system_instruction::create_account(payer, mint, lamports, space as u64, token_program_id),
+        encode_instruction(
+            token_program_id,
+            vec![AccountMeta::new(*mint, false)],
+            TokenInstruction::RebaseMintExtension,
+            RebaseMintInstruction::Initialize,
+            config,
+        ),
+        metadata_pointer::instruction::initialize(
+            token_program_id,
+            mint,
+            Some(*update_authority),
+            Some(*mint),
+        )?,
+        initialize_mint2(
+            token_program_id,
+            mint,
+            mint_authority,
+            freeze_authority,
+            decimals,
+        )?,
+        spl_token_metadata_interface::instruction::initialize(
+            token_program_id,
+            mint,
+            update_authority,
+            mint,
+            mint_authority,
+            name,
+            symbol,
+            uri,
+        ),
+    ])
+}
+
+/// Builder for `RebaseMintInstruction::RebaseSupply` instructions, covering
+/// every optional account and tag of the `update_supply_*` functions, and for
+/// `RebaseMintInstruction::RebaseSupplyUi` instructions
+///
+/// Unlike those functions, `build` rejects a missing mint or authority.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RebaseSupplyBuilder {
+    token_program_id: Pubkey,
+    mint: Option<Pubkey>,
+    reserve_account: Option<Pubkey>,
+    fee_destination: Option<Pubkey>,
+    metadata_payer: Option<Pubkey>,
+    co_signer: Option<Pubkey>,
+    authority: Option<Pubkey>,
+    signers: Vec<Pubkey>,
+    new_supply: u64,
+    reason_code: u8,
+    reference: [u8; 32],
+    not_after_unix_timestamp: i64,
+    ui_supply: Option<String>,
+}
+
+impl RebaseSupplyBuilder {
+    /// Start building a `RebaseSupply` instruction for `token_program_id`,
+    /// rebasing to `new_supply`
+    pub fn new(token_program_id: &Pubkey, new_supply: u64) -> Self {
+        Self {
+            token_program_id: *token_program_id,
+            mint: None,
+            reserve_account: None,
+            fee_destination: None,
+            metadata_payer: None,
+            co_signer: None,
+            authority: None,
+            signers: vec![],
+            new_supply,
+            reason_code: 0,
+            reference: [0; 32],
+            not_after_unix_timestamp: 0,
+            ui_supply: None,
+        }
+    }
+
+    /// Start building a `RebaseSupplyUi` instruction for `token_program_id`,
+    /// rebasing to `ui_supply` in UI units, which the program converts with
+    /// the mint's decimals
+    pub fn new_ui(token_program_id: &Pubkey, ui_supply: &str) -> Self {
+        Self {
+            ui_supply: Some(ui_supply.to_string()),
+            ..Self::new(token_program_id, 0)
+        }
+    }
+
+    /// Set the mint to rebase, which is required
+    pub fn mint(mut self, mint: &Pubkey) -> Self {
+        self.mint = Some(*mint);
+        self
+    }
+
+    /// Pass the reserve account, which is required if the mint has one
+    pub fn reserve_account(mut self, reserve_account: &Pubkey) -> Self {
+        self.reserve_account = Some(*reserve_account);
+        self
+    }
+
+    /// Pass the fee destination, which is required if the mint has one
+    pub fn fee_destination(mut self, fee_destination: &Pubkey) -> Self {
+        self.fee_destination = Some(*fee_destination);
+        self
+    }
+
+    /// Pass the payer and the system program, which are required if the mint
+    /// has `update_metadata_rate` set
+    pub fn metadata_payer(mut self, metadata_payer: &Pubkey) -> Self {
+        self.metadata_payer = Some(*metadata_payer);
+        self
+    }
+
+    /// Pass the co-signer, which is required and must sign if the mint has
+    /// one
+    pub fn co_signer(mut self, co_signer: &Pubkey) -> Self {
+        self.co_signer = Some(*co_signer);
+        self
+    }
+
+    /// Set the signing authority, either the supply authority or the rebase
+    /// delegate, which is required
+    pub fn authority(mut self, authority: &Pubkey) -> Self {
+        self.authority = Some(*authority);
+        self
+    }
+
+    /// Add signers of a multisignature authority, in order
+    pub fn multisig_signers(mut self, signers: &[&Pubkey]) -> Self {
+        self.signers
+            .extend(signers.iter().map(|signer_pubkey| **signer_pubkey));
+        self
+    }
+
+    /// Tag the rebase with a machine-readable reason code
+    pub fn reason_code(mut self, reason_code: u8) -> Self {
+        self.reason_code = reason_code;
+        self
+    }
+
+    /// Tag the rebase with an operator-supplied reference
+    pub fn reference(mut self, reference: [u8; 32]) -> Self {
+        self.reference = reference;
+        self
+    }
+
+    /// Make the rebase fail if it executes after `not_after_unix_timestamp`
+    pub fn not_after_unix_timestamp(mut self, not_after_unix_timestamp: i64) -> Self {
+        self.not_after_unix_timestamp = not_after_unix_timestamp;
+        self
+    }
+
+    /// Build the instruction.
+    ///
+    /// Fails with `ProgramError::NotEnoughAccountKeys` without a mint or an
+    /// authority. A new supply of zero is left to the program, which fails
+    /// differently depending on whether any shares are held. A
+    /// `RebaseSupplyUi` instruction fails with
+    /// `TokenError::RebaseUiSupplyTooLong` for a supply longer than
+    /// `MAX_UI_SUPPLY_LEN`.
+    pub fn build(self) -> Result<Instruction, ProgramError> {
+        if self.mint.is_none() || self.authority.is_none() {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        if let Some(ui_supply) = &self.ui_supply {
+            if ui_supply.len() > MAX_UI_SUPPLY_LEN {
+                return Err(TokenError::RebaseUiSupplyTooLong.into());
+            }
+        }
+        self.instruction()
+    }
+
+    /// Build a memo instruction followed by the `RebaseSupply` instruction,
+    /// as required by mints with `require_rebase_memo` set
+    pub fn build_with_memo(self, memo: &str) -> Result<Vec<Instruction>, ProgramError> {
+        Ok(vec![
+            spl_memo::build_memo(memo.as_bytes(), &[]),
+            self.build()?,
+        ])
+    }
+
+    /// Encode the instruction, leaving every check of the supply to the
+    /// program
+    fn instruction(&self) -> Result<Instruction, ProgramError> {
+        check_program_account(&self.token_program_id)?;
+        let mint = self.mint.ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let authority = self.authority.ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let mut accounts = vec![AccountMeta::new(mint, false)];
+        if let Some(reserve_account) = self.reserve_account {
+            accounts.push(AccountMeta::new_readonly(reserve_account, false));
+        }
+        if let Some(fee_destination) = self.fee_destination {
+            accounts.push(AccountMeta::new(fee_destination, false));
+        }
+        if let Some(metadata_payer) = self.metadata_payer {
+            accounts.push(AccountMeta::new(metadata_payer, true));
+            accounts.push(AccountMeta::new_readonly(system_program::id(), false));
+        }
+        if let Some(co_signer) = self.co_signer {
+            accounts.push(AccountMeta::new_readonly(co_signer, true));
+        }
+        accounts.push(AccountMeta::new_readonly(
+            authority,
+            self.signers.is_empty(),
+        ));
+        for signer_pubkey in self.signers.iter() {
+            accounts.push(AccountMeta::new_readonly(*signer_pubkey, true));
+        }
+        if let Some(ui_supply) = &self.ui_supply {
+            let mut data = TokenInstruction::RebaseMintExtension.pack();
+            data.push(RebaseMintInstruction::RebaseSupplyUi.into());
+            data.extend_from_slice(&(ui_supply.len() as u32).to_le_bytes());
+            data.extend_from_slice(ui_supply.as_bytes());
+            data.extend_from_slice(bytemuck::bytes_of(&RebaseSupplyUiTags {
+                reason_code: self.reason_code,
+                reference: self.reference,
+                not_after_unix_timestamp: self.not_after_unix_timestamp.into(),
+            }));
+            return Ok(Instruction {
+                program_id: self.token_program_id,
+                accounts,
+                data,
+            });
+        }
+        Ok(encode_instruction(
+            &self.token_program_id,
+            accounts,
+            TokenInstruction::RebaseMintExtension,
+            RebaseMintInstruction::RebaseSupply,
+            &RebaseSupplyData {
+                new_supply: self.new_supply.into(),
+                reason_code: self.reason_code,
+                reference: self.reference,
+                not_after_unix_timestamp: self.not_after_unix_timestamp.into(),
+            },
+        ))
+    }
+}
+
+/// Create a `RebaseSupply` instruction
+///
+/// `reserve_account` must be provided if the mint has one configured.
+/// `authority` may be either the supply authority or the rebase delegate.
+pub fn update_supply(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    reserve_account: Option<&Pubkey>,
+    authority: &Pubkey,
+    signers: &[&Pubkey],
+    new_supply: u64,
+) -> Result<Instruction, ProgramError> {
+    let mut builder = RebaseSupplyBuilder::new(token_program_id, new_supply)
+        .mint(mint)
+        .authority(authority)
+        .multisig_signers(signers);
+    if let Some(reserve_account) = reserve_account {
+        builder = builder.reserve_account(reserve_account);
+    }
+    builder.build()
+}
+
+/// Create a `RebaseSupply` instruction for a mint that may have a fee
+/// destination configured
+pub fn update_supply_with_fee_destination(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    reserve_account: Option<&Pubkey>,
+    fee_destination: Option<&Pubkey>,
+    authority: &Pubkey,
+    signers: &[&Pubkey],
+    new_supply: u64,
+) -> Result<Instruction, ProgramError> {
+    update_supply_with_reason(
+        token_program_id,
+        mint,
+        reserve_account,
+        fee_destination,
+        authority,
+        signers,
+        new_supply,
+        0,
+        [0; 32],
+    )
+}
+
+/// Create a `RebaseSupply` instruction tagged with a reason code and a
+/// reference, either of which may be zero to leave it unset
+#[allow(clippy::too_many_arguments)]
+pub fn update_supply_with_reason(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    reserve_account: Option<&Pubkey>,
+    fee_destination: Option<&Pubkey>,
+    authority: &Pubkey,
+    signers: &[&Pubkey],
+    new_supply: u64,
+    reason_code: u8,
+    reference: [u8; 32],
+) -> Result<Instruction, ProgramError> {
+    update_supply_with_metadata_payer(
+        token_program_id,
+        mint,
+        reserve_account,
+        fee_destination,
+        None,
+        authority,
+        signers,
+        new_supply,
+        reason_code,
+        reference,
+    )
+}
+
+/// Create a `RebaseSupply` instruction like `update_supply_with_reason`, also
+/// passing the payer and system program needed by mints with
+/// `update_metadata_rate` set
+#[allow(clippy::too_many_arguments)]
+pub fn update_supply_with_metadata_payer(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    reserve_account: Option<&Pubkey>,
+    fee_destination: Option<&Pubkey>,
+    metadata_payer: Option<&Pubkey>,
+    authority: &Pubkey,
+    signers: &[&Pubkey],
+    new_supply: u64,
+    reason_code: u8,
+    reference: [u8; 32],
+) -> Result<Instruction, ProgramError> {
+    update_supply_with_co_signer(
+        token_program_id,
+        mint,
+        reserve_account,
+        fee_destination,
+        metadata_payer,
+        None,
+        authority,
+        signers,
+        new_supply,
+        reason_code,
+        reference,
+    )
+}
+
+/// Create a `RebaseSupply` instruction like
+/// `update_supply_with_metadata_payer`, also passing the co-signer needed by
+/// mints that have one
+#[allow(clippy::too_many_arguments)]
+pub fn update_supply_with_co_signer(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    reserve_account: Option<&Pubkey>,
+    fee_destination: Option<&Pubkey>,
+    metadata_payer: Option<&Pubkey>,
+    co_signer: Option<&Pubkey>,
+    authority: &Pubkey,
+    signers: &[&Pubkey],
+    new_supply: u64,
+    reason_code: u8,
+    reference: [u8; 32],
+) -> Result<Instruction, ProgramError> {
+    RebaseSupplyBuilder {
+        token_program_id: *token_program_id,
+        mint: Some(*mint),
+        reserve_account: reserve_account.copied(),
+        fee_destination: fee_destination.copied(),
+        metadata_payer: metadata_payer.copied(),
+        co_signer: co_signer.copied(),
+        authority: Some(*authority),
+        signers: signers
+            .iter()
+            .map(|signer_pubkey| **signer_pubkey)
+            .collect(),
+        new_supply,
+        reason_code,
+        reference,
+        not_after_unix_timestamp: 0,
+        ui_supply: None,
+    }
+    .instruction()
+}
+
+/// Create a memo instruction followed by a `RebaseSupply` instruction, as
+/// required by mints with `require_rebase_memo` set
+pub fn update_supply_with_memo(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    reserve_account: Option<&Pubkey>,
+    authority: &Pubkey,
+    signers: &[&Pubkey],
+    new_supply: u64,
+    memo: &str,
+) -> Result<Vec<Instruction>, ProgramError> {
+    Ok(vec![
+        spl_memo::build_memo(memo.as_bytes(), &[]),
+        update_supply(
+            token_program_id,
+            mint,
+            reserve_account,
+            authority,
+            signers,
+            new_supply,
+        )?,
+    ])
+}
+
+/// Create a `RebaseSupplyUi` instruction, rebasing to `ui_supply` in UI units
+///
+/// `reserve_account`, `fee_destination`, `metadata_payer` and `co_signer`
+/// must be provided if the mint requires them. `authority` may be either the
+/// supply authority or the rebase delegate. Use `RebaseSupplyBuilder::new_ui`
+/// to tag the rebase or give it a deadline.
+#[allow(clippy::too_many_arguments)]
+pub fn update_supply_ui(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    reserve_account: Option<&Pubkey>,
+    fee_destination: Option<&Pubkey>,
+    metadata_payer: Option<&Pubkey>,
+    co_signer: Option<&Pubkey>,
+    authority: &Pubkey,
+    signers: &[&Pubkey],
+    ui_supply: &str,
+) -> Result<Instruction, ProgramError> {
+    let mut builder = RebaseSupplyBuilder::new_ui(token_program_id, ui_supply)
+        .mint(mint)
+        .authority(authority)
+        .multisig_signers(signers);
+    if let Some(reserve_account) = reserve_account {
+        builder = builder.reserve_account(reserve_account);
+    }
+    if let Some(fee_destination) = fee_destination {
+        builder = builder.fee_destination(fee_destination);
+    }
+    if let Some(metadata_payer) = metadata_payer {
+        builder = builder.metadata_payer(metadata_payer);
+    }
+    if let Some(co_signer) = co_signer {
+        builder = builder.co_signer(co_signer);
+    }
+    builder.build()
+}
+
+/// Create a `SetRebaseFee` instruction
+pub fn set_rebase_fee(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    supply_authority: &Pubkey,
+    signers: &[&Pubkey],
+    rebase_fee_bps: u16,
+    fee_destination: Option<Pubkey>,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let mut accounts = vec![
+        AccountMeta::new(*mint, false),
+        AccountMeta::new_readonly(*supply_authority, signers.is_empty()),
+    ];
+    for signer_pubkey in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::RebaseMintExtension,
+        RebaseMintInstruction::SetRebaseFee,
+        &SetRebaseFeeData {
+            rebase_fee_bps: rebase_fee_bps.into(),
+            fee_destination: fee_destination.try_into()?,
+        },
+    ))
+}
+
+/// Create a `SunsetRebase` instruction
+pub fn sunset_rebase(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    supply_authority: &Pubkey,
+    signers: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    sunset_rebase_with_co_signer(token_program_id, mint, None, supply_authority, signers)
+}
+
+/// Create a `SunsetRebase` instruction, also passing the co-signer needed by
+/// mints that have one
+pub fn sunset_rebase_with_co_signer(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    co_signer: Option<&Pubkey>,
+    supply_authority: &Pubkey,
+    signers: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let mut accounts = vec![AccountMeta::new(*mint, false)];
+    if let Some(co_signer) = co_signer {
+        accounts.push(AccountMeta::new_readonly(*co_signer, true));
+    }
+    accounts.push(AccountMeta::new_readonly(
+        *supply_authority,
+        signers.is_empty(),
+    ));
+    for signer_pubkey in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::RebaseMintExtension,
+        RebaseMintInstruction::SunsetRebase,
+        &(),
+    ))
+}
+
+/// Create a `RetireRebase` instruction
+pub fn retire_rebase(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    supply_authority: &Pubkey,
+    signers: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let mut accounts = vec![
+        AccountMeta::new(*mint, false),
+        AccountMeta::new_readonly(*supply_authority, signers.is_empty()),
+    ];
+    for signer_pubkey in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::RebaseMintExtension,
+        RebaseMintInstruction::RetireRebase,
+        &(),
+    ))
+}
+
+/// Create a `SweepRoundingDust` instruction
+pub fn sweep_rounding_dust(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    destination: &Pubkey,
+    supply_authority: &Pubkey,
+    signers: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let mut accounts = vec![
+        AccountMeta::new(*mint, false),
+        AccountMeta::new(*destination, false),
+        AccountMeta::new_readonly(*supply_authority, signers.is_empty()),
+    ];
+    for signer_pubkey in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::RebaseMintExtension,
+        RebaseMintInstruction::SweepRoundingDust,
+        &(),
+    ))
+}
+
+/// Create a `ClaimRebase` instruction
+pub fn claim_rebase(
+    token_program_id: &Pubkey,
+    account: &Pubkey,
+    mint: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let accounts = vec![
+        AccountMeta::new(*account, false),
+        AccountMeta::new_readonly(*mint, false),
+    ];
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::RebaseMintExtension,
+        RebaseMintInstruction::ClaimRebase,
+        &(),
+    ))
+}
+
+/// Create a `SetReserveAccount` instruction
+pub fn set_reserve_account(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    supply_authority: &Pubkey,
+    signers: &[&Pubkey],
+    reserve_account: Option<Pubkey>,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let mut accounts = vec![
+        AccountMeta::new(*mint, false),
+        AccountMeta::new_readonly(*supply_authority, signers.is_empty()),
+    ];
+    for signer_pubkey in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::RebaseMintExtension,
+        RebaseMintInstruction::SetReserveAccount,
+        &SetReserveAccountData {
+            reserve_account: reserve_account.try_into()?,
+        },
+    ))
+}
+
+/// Create a `PreviewRebaseSupply` instruction
+///
+/// `reserve_account` must be provided if the mint has one configured.
+pub fn preview_rebase_supply(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    reserve_account: Option<&Pubkey>,
+    new_supply: u64,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let mut accounts = vec![AccountMeta::new_readonly(*mint, false)];
+    if let Some(reserve_account) = reserve_account {
+        accounts.push(AccountMeta::new_readonly(*reserve_account, false));
+    }
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::RebaseMintExtension,
+        RebaseMintInstruction::PreviewRebaseSupply,
+        &RebaseSupplyData {
+            new_supply: new_supply.into(),
+            ..RebaseSupplyData::zeroed()
+        },
+    ))
+}
+
+/// Create a `SetSupplyAuthority` instruction
+pub fn set_supply_authority(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    supply_authority: &Pubkey,
+    signers: &[&Pubkey],
+    new_authority: Option<Pubkey>,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let mut accounts = vec![
+        AccountMeta::new(*mint, false),
+        AccountMeta::new_readonly(*supply_authority, signers.is_empty()),
+    ];
+    for signer_pubkey in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::RebaseMintExtension,
+        RebaseMintInstruction::SetSupplyAuthority,
+        &SetSupplyAuthorityData {
+            new_authority: new_authority.try_into()?,
+        },
+    ))
+}
+
+/// Create a `ProposeSupplyAuthority` instruction
+pub fn propose_supply_authority(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    supply_authority: &Pubkey,
+    signers: &[&Pubkey],
+    pending_authority: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let mut accounts = vec![
+        AccountMeta::new(*mint, false),
+        AccountMeta::new_readonly(*supply_authority, signers.is_empty()),
+    ];
+    for signer_pubkey in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::RebaseMintExtension,
+        RebaseMintInstruction::ProposeSupplyAuthority,
+        &ProposeSupplyAuthorityData {
+            pending_authority: Some(*pending_authority).try_into()?,
+        },
+    ))
+}
+
+/// Create an `AcceptSupplyAuthority` instruction
+pub fn accept_supply_authority(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    pending_authority: &Pubkey,
+    signers: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let mut accounts = vec![
+        AccountMeta::new(*mint, false),
+        AccountMeta::new_readonly(*pending_authority, signers.is_empty()),
+    ];
+    for signer_pubkey in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::RebaseMintExtension,
+        RebaseMintInstruction::AcceptSupplyAuthority,
+        &(),
+    ))
+}
+
+/// Create a `CancelPendingAuthority` instruction
+pub fn cancel_pending_authority(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    supply_authority: &Pubkey,
+    signers: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let mut accounts = vec![
+        AccountMeta::new(*mint, false),
+        AccountMeta::new_readonly(*supply_authority, signers.is_empty()),
+    ];
+    for signer_pubkey in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::RebaseMintExtension,
+        RebaseMintInstruction::CancelPendingAuthority,
+        &(),
+    ))
+}
+
+/// Create a `FinalizeAuthorityChange` instruction
+pub fn finalize_authority_change(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let accounts = vec![AccountMeta::new(*mint, false)];
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::RebaseMintExtension,
+        RebaseMintInstruction::FinalizeAuthorityChange,
+        &(),
+    ))
+}
+
+/// Create a `CancelAuthorityChange` instruction
+pub fn cancel_authority_change(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    supply_authority: &Pubkey,
+    signers: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let mut accounts = vec![
+        AccountMeta::new(*mint, false),
+        AccountMeta::new_readonly(*supply_authority, signers.is_empty()),
+    ];
+    for signer_pubkey in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::RebaseMintExtension,
+        RebaseMintInstruction::CancelAuthorityChange,
+        &(),
+    ))
+}
+
+/// Create a `SetRebaseDelegate` instruction
+pub fn set_rebase_delegate(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    supply_authority: &Pubkey,
+    signers: &[&Pubkey],
+    rebase_delegate: Option<Pubkey>,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let mut accounts = vec![
+        AccountMeta::new(*mint, false),
+        AccountMeta::new_readonly(*supply_authority, signers.is_empty()),
+    ];
+    for signer_pubkey in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::RebaseMintExtension,
+        RebaseMintInstruction::SetRebaseDelegate,
+        &SetRebaseDelegateData {
+            rebase_delegate: rebase_delegate.try_into()?,
+        },
+    ))
+}
+
+fn transfer_shares_accounts(
+    source: &Pubkey,
+    mint: &Pubkey,
+    destination: &Pubkey,
+    authority: &Pubkey,
+    signers: &[&Pubkey],
+) -> Vec<AccountMeta> {
+    let mut accounts = vec![
+        AccountMeta::new(*source, false),
+        AccountMeta::new_readonly(*mint, false),
+        AccountMeta::new(*destination, false),
+        AccountMeta::new_readonly(*authority, signers.is_empty()),
+    ];
+    for signer_pubkey in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+    accounts
+}
+
+/// Create a `TransferShares` instruction
+pub fn transfer_shares(
+    token_program_id: &Pubkey,
+    source: &Pubkey,
+    mint: &Pubkey,
+    destination: &Pubkey,
+    authority: &Pubkey,
+    signers: &[&Pubkey],
+    shares: u64,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    Ok(encode_instruction(
+        token_program_id,
+        transfer_shares_accounts(source, mint, destination, authority, signers),
+        TokenInstruction::RebaseMintExtension,
+        RebaseMintInstruction::TransferShares,
+        &TransferSharesData {
+            shares: shares.into(),
+        },
+    ))
+}
+
+/// Create a `TransferSharesChecked` instruction
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_shares_checked(
+    token_program_id: &Pubkey,
+    source: &Pubkey,
+    mint: &Pubkey,
+    destination: &Pubkey,
+    authority: &Pubkey,
+    signers: &[&Pubkey],
+    shares: u64,
+    decimals: u8,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    Ok(encode_instruction(
+        token_program_id,
+        transfer_shares_accounts(source, mint, destination, authority, signers),
+        TokenInstruction::RebaseMintExtension,
+        RebaseMintInstruction::TransferSharesChecked,
+        &TransferSharesCheckedData {
+            shares: shares.into(),
+            decimals,
+        },
+    ))
+}
+
+/// Create a `MintToShares` instruction
+pub fn mint_to_shares(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    account: &Pubkey,
+    owner: &Pubkey,
+    signers: &[&Pubkey],
+    shares: u64,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let mut accounts = vec![
+        AccountMeta::new(*mint, false),
+        AccountMeta::new(*account, false),
+        AccountMeta::new_readonly(*owner, signers.is_empty()),
+    ];
+    for signer_pubkey in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::RebaseMintExtension,
+        RebaseMintInstruction::MintToShares,
+        &MintToSharesData {
+            shares: shares.into(),
+        },
+    ))
+}
+
+/// Create a `BurnShares` instruction
+pub fn burn_shares(
+    token_program_id: &Pubkey,
+    account: &Pubkey,
+    mint: &Pubkey,
+    authority: &Pubkey,
+    signers: &[&Pubkey],
+    shares: u64,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let mut accounts = vec![
+        AccountMeta::new(*account, false),
+        AccountMeta::new(*mint, false),
+        AccountMeta::new_readonly(*authority, signers.is_empty()),
+    ];
+    for signer_pubkey in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::RebaseMintExtension,
+        RebaseMintInstruction::BurnShares,
+        &BurnSharesData {
+            shares: shares.into(),
+        },
+    ))
+}
+
+/// Create a `SyncSupplyFromMint` instruction
+pub fn sync_supply_from_mint(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let accounts = vec![AccountMeta::new(*mint, false)];
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::RebaseMintExtension,
+        RebaseMintInstruction::SyncSupplyFromMint,
+        &(),
+    ))
+}
+
+/// Create a `GetRebasedBalance` instruction
+pub fn get_rebased_balance(
+    token_program_id: &Pubkey,
+    account: &Pubkey,
+    mint: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let accounts = vec![
+        AccountMeta::new_readonly(*account, false),
+        AccountMeta::new_readonly(*mint, false),
+    ];
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::RebaseMintExtension,
+        RebaseMintInstruction::GetRebasedBalance,
+        &(),
+    ))
+}
+
+/// Create a `ConvertAmountToShares` instruction
+pub fn convert_amount_to_shares(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let accounts = vec![AccountMeta::new_readonly(*mint, false)];
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::RebaseMintExtension,
+        RebaseMintInstruction::ConvertAmountToShares,
+        &AmountToSharesData {
+            amount: amount.into(),
+        },
+    ))
+}
+
+/// Create a `ConvertSharesToAmount` instruction
+pub fn convert_shares_to_amount(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    shares: u64,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let accounts = vec![AccountMeta::new_readonly(*mint, false)];
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::RebaseMintExtension,
+        RebaseMintInstruction::ConvertSharesToAmount,
+        &SharesToAmountData {
+            shares: shares.into(),
+        },
+    ))
+}
+
+/// Create an `ApproveShares` instruction
+pub fn approve_shares(
+    token_program_id: &Pubkey,
+    source: &Pubkey,
+    mint: &Pubkey,
+    delegate: &Pubkey,
+    owner: &Pubkey,
+    signers: &[&Pubkey],
+    shares: u64,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let mut accounts = vec![
+        AccountMeta::new(*source, false),
+        AccountMeta::new_readonly(*mint, false),
+        AccountMeta::new_readonly(*delegate, false),
+        AccountMeta::new_readonly(*owner, signers.is_empty()),
+    ];
+    for signer_pubkey in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::RebaseMintExtension,
+        RebaseMintInstruction::ApproveShares,
+        &ApproveSharesData {
+            shares: shares.into(),
+        },
+    ))
+}
+
+/// Create a `SetAccountExempt` instruction
+pub fn set_account_exempt(
+    token_program_id: &Pubkey,
+    account: &Pubkey,
+    mint: &Pubkey,
+    authority: &Pubkey,
+    signers: &[&Pubkey],
+    exempt: bool,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let mut accounts = vec![
+        AccountMeta::new(*account, false),
+        AccountMeta::new(*mint, false),
+        AccountMeta::new_readonly(*authority, signers.is_empty()),
+    ];
+    for signer_pubkey in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::RebaseMintExtension,
+        RebaseMintInstruction::SetAccountExempt,
+        &SetAccountExemptData {
+            exempt: exempt.into(),
+        },
+    ))
+}
+
+/// Create an `InitializeWrapper` instruction
+pub fn initialize_wrapper(
+    token_program_id: &Pubkey,
+    funding_address: &Pubkey,
+    mint: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let accounts = vec![
+        AccountMeta::new(*funding_address, true),
+        AccountMeta::new_readonly(*mint, false),
+        AccountMeta::new(get_wrapper_mint_address(mint, token_program_id), false),
+        AccountMeta::new(get_escrow_address(mint, token_program_id), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::RebaseMintExtension,
+        RebaseMintInstruction::InitializeWrapper,
+        &(),
+    ))
+}
+
+/// Create a `WrapShares` instruction
+///
+/// `destination` is an account of the wrapper mint.
+pub fn wrap_shares(
+    token_program_id: &Pubkey,
+    source: &Pubkey,
+    mint: &Pubkey,
+    destination: &Pubkey,
+    authority: &Pubkey,
+    signers: &[&Pubkey],
+    shares: u64,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let mut accounts = vec![
+        AccountMeta::new(*source, false),
+        AccountMeta::new_readonly(*mint, false),
+        AccountMeta::new(get_escrow_address(mint, token_program_id), false),
+        AccountMeta::new(get_wrapper_mint_address(mint, token_program_id), false),
+        AccountMeta::new(*destination, false),
+        AccountMeta::new_readonly(get_wrapper_authority_address(mint, token_program_id), false),
+        AccountMeta::new_readonly(*token_program_id, false),
+        AccountMeta::new_readonly(*authority, signers.is_empty()),
+    ];
+    for signer_pubkey in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::RebaseMintExtension,
+        RebaseMintInstruction::WrapShares,
+        &WrapSharesData {
+            shares: shares.into(),
+        },
+    ))
+}
+
+/// Create an `UnwrapShares` instruction
+///
+/// `source` is an account of the wrapper mint, and `mint` the rebasing mint.
+pub fn unwrap_shares(
+    token_program_id: &Pubkey,
+    source: &Pubkey,
+    mint: &Pubkey,
+    destination: &Pubkey,
+    authority: &Pubkey,
+    signers: &[&Pubkey],
+    shares: u64,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let mut accounts = vec![
+        AccountMeta::new(*source, false),
+        AccountMeta::new(get_wrapper_mint_address(mint, token_program_id), false),
+        AccountMeta::new(get_escrow_address(mint, token_program_id), false),
+        AccountMeta::new_readonly(*mint, false),
+        AccountMeta::new(*destination, false),
+        AccountMeta::new_readonly(get_wrapper_authority_address(mint, token_program_id), false),
+        AccountMeta::new_readonly(*token_program_id, false),
+        AccountMeta::new_readonly(*authority, signers.is_empty()),
+    ];
+    for signer_pubkey in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::RebaseMintExtension,
+        RebaseMintInstruction::UnwrapShares,
+        &UnwrapSharesData {
+            shares: shares.into(),
+        },
+    ))
+}
+
+/// Create an `AssertRebaseConsistency` instruction
+pub fn assert_rebase_consistency(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let accounts = vec![AccountMeta::new_readonly(*mint, false)];
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::RebaseMintExtension,
+        RebaseMintInstruction::AssertRebaseConsistency,
+        &(),
+    ))
+}
+
+/// Create a `GetRebaseState` instruction
+pub fn get_rebase_state(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let accounts = vec![AccountMeta::new_readonly(*mint, false)];
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::RebaseMintExtension,
+        RebaseMintInstruction::GetRebaseState,
+        &(),
+    ))
+}
+
+/// Create an `InitializeHistory` instruction
+pub fn initialize_history(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let accounts = vec![AccountMeta::new(*mint, false)];
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::RebaseMintExtension,
+        RebaseMintInstruction::InitializeHistory,
+        &(),
+    ))
+}
+
+/// Create a `GetRebaseHistory` instruction
+pub fn get_rebase_history(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    let accounts = vec![AccountMeta::new_readonly(*mint, false)];
+    Ok(encode_instruction(
+        token_program_id,
+        accounts,
+        TokenInstruction::RebaseMintExtension,
+        RebaseMintInstruction::GetRebaseHistory,
+        &(),
+    ))
+}
+
+/// Rebase history returned by `GetRebaseHistory`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RebaseHistoryData {
+    /// Number of rebases recorded since the history was initialized
+    pub count: u64,
+    /// The kept rebases, newest first
+    pub entries: Vec<RebaseHistoryEntry>,
+}
+impl RebaseHistoryData {
+    /// Pack into the return data of `GetRebaseHistory`
+    pub fn pack(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(
+            size_of::<PodU64>() + self.entries.len() * size_of::<RebaseHistoryEntry>(),
+        );
+        data.extend_from_slice(&self.count.to_le_bytes());
+        for entry in &self.entries {
+            data.extend_from_slice(bytemuck::bytes_of(entry));
+        }
+        data
+    }
+}
+impl From<&RebaseHistory> for RebaseHistoryData {
+    fn from(history: &RebaseHistory) -> Self {
+        let mut entries = history.entries().copied().collect::<Vec<_>>();
+        entries.reverse();
+        Self {
+            count: history.count.into(),
+            entries,
+        }
+    }
+}
+
+/// Decode the return data of `GetRebaseHistory`.
+///
+/// The runtime trims trailing zeros from return data, so they're restored
+/// from the count before decoding the entries.
+pub fn decode_rebase_history(data: &[u8]) -> Result<RebaseHistoryData, ProgramError> {
+    let mut count_bytes = [0; size_of::<u64>()];
+    let count_len = data.len().min(count_bytes.len());
+    count_bytes[..count_len].copy_from_slice(&data[..count_len]);
+    let count = u64::from_le_bytes(count_bytes);
+
+    let kept = count.min(REBASE_HISTORY_LEN as u64) as usize;
+    let mut entries_data = vec![0; kept * size_of::<RebaseHistoryEntry>()];
+    let data_entries = &data[count_len..];
+    if data_entries.len() > entries_data.len() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    entries_data[..data_entries.len()].copy_from_slice(data_entries);
+    let entries = entries_data
+        .chunks_exact(size_of::<RebaseHistoryEntry>())
+        .map(bytemuck::pod_read_unaligned::<RebaseHistoryEntry>)
+        .collect();
+    Ok(RebaseHistoryData { count, entries })
+}
+
+/// Create a `TransferCheckedWithShareSlippage` instruction
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_checked_with_share_slippage(
+    token_program_id: &Pubkey,
+    source: &Pubkey,
+    mint: &Pubkey,
+    destination: &Pubkey,
+    authority: &Pubkey,
+    signers: &[&Pubkey],
+    amount: u64,
+    decimals: u8,
+    expected_shares: u64,
+    max_share_slippage_bps: u16,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(token_program_id)?;
+    Ok(encode_instruction(
+        token_program_id,
+        transfer_shares_accounts(source, mint, destination, authority, signers),
+        TokenInstruction::RebaseMintExtension,
+        RebaseMintInstruction::TransferCheckedWithShareSlippage,
+        &TransferCheckedWithShareSlippageData {
+            amount: amount.into(),
+            decimals,
+            expected_shares: expected_shares.into(),
+            max_share_slippage_bps: max_share_slippage_bps.into(),
+        },
+    ))
+}
+
+/// A rebasing mint instruction decoded into owned, typed fields
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde-traits",
+    serde(rename_all = "camelCase", rename_all_fields = "camelCase")
+)]
+#[derive(Clone, Debug, PartialEq)]
+pub enum DecodedRebaseInstruction {
+    /// `RebaseMintInstruction::Initialize`
+    Initialize {
+        /// The account that can rebase the supply
+        #[cfg_attr(feature = "serde-traits", serde(with = "As::<Option<DisplayFromStr>>"))]
+        supply_authority: Option<Pubkey>,
+        /// The initial supply
+        initial_supply: u64,
+        /// Unix timestamp before which the supply cannot be rebased
+        first_rebase_after_timestamp: i64,
+        /// Whether the supply authority is transferred in two steps
+        two_step_authority_transfer: bool,
+        /// Delay before a change of supply authority can be finalized
+        authority_change_delay_seconds: u32,
+        /// Whether the mint authority can rebase without a supply authority
+        use_mint_authority_fallback: bool,
+        /// Whether every rebase must be preceded by a memo
+        require_rebase_memo: bool,
+        /// Virtual shares and tokens added to the totals in conversions
+        virtual_shares_offset: u64,
+        /// Whether the supply can be rebased lower
+        allow_supply_decrease: bool,
+        /// Share of every increase of the supply taken as fee, in basis points
+        rebase_fee_bps: u16,
+        /// The token account credited with the rebase fee
+        #[cfg_attr(feature = "serde-traits", serde(with = "As::<Option<DisplayFromStr>>"))]
+        fee_destination: Option<Pubkey>,
+        /// Shares of the initial supply, or zero for one per token
+        initial_shares: u64,
+        /// Cap on the total shares, or zero for the default
+        max_total_shares: u64,
+        /// Whether the supply authority can sunset the mint
+        allow_sunset: bool,
+        /// Whether rebases write the exchange rate into the token-metadata
+        update_metadata_rate: bool,
+        /// The key that must co-sign every rebase and sunset
+        #[cfg_attr(feature = "serde-traits", serde(with = "As::<Option<DisplayFromStr>>"))]
+        co_signer: Option<Pubkey>,
+        /// How token accounts follow the rebases
+        rebase_mode: RebaseMode,
+        /// Whether rebases write the new supply into the base mint's supply
+        sync_base_supply: bool,
+    },
+    /// `RebaseMintInstruction::RebaseSupply`
+    RebaseSupply {
+        /// The new total supply
+        new_supply: u64,
+        /// Machine-readable reason for the rebase, or zero if unset
+        #[cfg_attr(feature = "serde-traits", serde(default))]
+        reason_code: u8,
+        /// Operator-supplied reference, or zeroes if unset
+        #[cfg_attr(feature = "serde-traits", serde(default))]
+        reference: [u8; 32],
+        /// Last unix timestamp at which the rebase may execute, or zero for
+        /// no deadline
+        #[cfg_attr(feature = "serde-traits", serde(default))]
+        not_after_unix_timestamp: i64,
+    },
+    /// `RebaseMintInstruction::SetReserveAccount`
+    SetReserveAccount {
+        /// The new reserve token account
+        #[cfg_attr(feature = "serde-traits", serde(with = "As::<Option<DisplayFromStr>>"))]
+        reserve_account: Option<Pubkey>,
+    },
+    /// `RebaseMintInstruction::PreviewRebaseSupply`
+    PreviewRebaseSupply {
+        /// The total supply to preview
+        new_supply: u64,
+    },
+    /// `RebaseMintInstruction::SetSupplyAuthority`
+    SetSupplyAuthority {
+        /// The new supply authority
+        #[cfg_attr(feature = "serde-traits", serde(with = "As::<Option<DisplayFromStr>>"))]
+        new_authority: Option<Pubkey>,
+    },
+    /// `RebaseMintInstruction::ProposeSupplyAuthority`
+    ProposeSupplyAuthority {
+        /// The proposed supply authority
+        #[cfg_attr(feature = "serde-traits", serde(with = "As::<Option<DisplayFromStr>>"))]
+        pending_authority: Option<Pubkey>,
+    },
+    /// `RebaseMintInstruction::AcceptSupplyAuthority`
+    AcceptSupplyAuthority,
+    /// `RebaseMintInstruction::CancelPendingAuthority`
+    CancelPendingAuthority,
+    /// `RebaseMintInstruction::FinalizeAuthorityChange`
+    FinalizeAuthorityChange,
+    /// `RebaseMintInstruction::CancelAuthorityChange`
+    CancelAuthorityChange,
+    /// `RebaseMintInstruction::SetRebaseDelegate`
+    SetRebaseDelegate {
+        /// The new rebase delegate
+        #[cfg_attr(feature = "serde-traits", serde(with = "As::<Option<DisplayFromStr>>"))]
+        rebase_delegate: Option<Pubkey>,
+    },
+    /// `RebaseMintInstruction::TransferShares`
+    TransferShares {
+        /// The number of shares to transfer
+        shares: u64,
+    },
+    /// `RebaseMintInstruction::TransferSharesChecked`
+    TransferSharesChecked {
+        /// The number of shares to transfer
+        shares: u64,
+        /// Expected decimals of the mint
+        decimals: u8,
+    },
+    /// `RebaseMintInstruction::MintToShares`
+    MintToShares {
+        /// The number of shares to mint
+        shares: u64,
+    },
+    /// `RebaseMintInstruction::BurnShares`
+    BurnShares {
+        /// The number of shares to burn
+        shares: u64,
+    },
+    /// `RebaseMintInstruction::SyncSupplyFromMint`
+    SyncSupplyFromMint,
+    /// `RebaseMintInstruction::GetRebasedBalance`
+    GetRebasedBalance,
+    /// `RebaseMintInstruction::ConvertAmountToShares`
+    ConvertAmountToShares {
+        /// The amount of tokens to convert
+        amount: u64,
+    },
+    /// `RebaseMintInstruction::ConvertSharesToAmount`
+    ConvertSharesToAmount {
+        /// The number of shares to convert
+        shares: u64,
+    },
+    /// `RebaseMintInstruction::ApproveShares`
+    ApproveShares {
+        /// The number of shares the delegate is approved for
+        shares: u64,
+    },
+    /// `RebaseMintInstruction::SetAccountExempt`
+    SetAccountExempt {
+        /// Whether the account is exempt from rebasing
+        exempt: bool,
+    },
+    /// `RebaseMintInstruction::InitializeWrapper`
+    InitializeWrapper,
+    /// `RebaseMintInstruction::WrapShares`
+    WrapShares {
+        /// The number of shares to wrap
+        shares: u64,
+    },
+    /// `RebaseMintInstruction::UnwrapShares`
+    UnwrapShares {
+        /// The number of shares to unwrap
+        shares: u64,
+    },
+    /// `RebaseMintInstruction::AssertRebaseConsistency`
+    AssertRebaseConsistency,
+    /// `RebaseMintInstruction::TransferCheckedWithShareSlippage`
+    TransferCheckedWithShareSlippage {
+        /// The amount of tokens to transfer
+        amount: u64,
+        /// Expected decimals of the mint
+        decimals: u8,
+        /// The number of shares the amount is expected to move
+        expected_shares: u64,
+        /// Maximum deviation from `expected_shares`, in basis points
+        max_share_slippage_bps: u16,
+    },
+    /// `RebaseMintInstruction::GetRebaseState`
+    GetRebaseState,
+    /// `RebaseMintInstruction::InitializeHistory`
+    InitializeHistory,
+    /// `RebaseMintInstruction::GetRebaseHistory`
+    GetRebaseHistory,
+    /// `RebaseMintInstruction::SetRebaseFee`
+    SetRebaseFee {
+        /// Share of every increase of the supply taken as fee, in basis points
+        rebase_fee_bps: u16,
+        /// The new fee destination token account
+        #[cfg_attr(feature = "serde-traits", serde(with = "As::<Option<DisplayFromStr>>"))]
+        fee_destination: Option<Pubkey>,
+    },
+    /// `RebaseMintInstruction::SunsetRebase`
+    SunsetRebase,
+    /// `RebaseMintInstruction::RetireRebase`
+    RetireRebase,
+    /// `RebaseMintInstruction::SweepRoundingDust`
+    SweepRoundingDust,
+    /// `RebaseMintInstruction::ClaimRebase`
+    ClaimRebase,
+    /// `RebaseMintInstruction::RebaseSupplyUi`
+    RebaseSupplyUi {
+        /// The new total supply in UI units, as sent
+        ui_supply: String,
+        /// Machine-readable reason for the rebase, or zero if unset
+        #[cfg_attr(feature = "serde-traits", serde(default))]
+        reason_code: u8,
+        /// Operator-supplied reference, or zeroes if unset
+        #[cfg_attr(feature = "serde-traits", serde(default))]
+        reference: [u8; 32],
+        /// Last unix timestamp at which the rebase may execute, or zero for
+        /// no deadline
+        #[cfg_attr(feature = "serde-traits", serde(default))]
+        not_after_unix_timestamp: i64,
+    },
+}
+
+/// Role of an account in a rebasing mint instruction
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RebaseAccountRole {
+    /// The rebasing mint
+    Mint,
+    /// The mint's reserve token account
+    ReserveAccount,
+    /// The signing authority, or a multisig authority
+    Authority,
+    /// A signer of a multisig authority
+    Signer,
+    /// Token account that shares move out of
+    Source,
+    /// Token account that shares move into
+    Destination,
+    /// Token account that the instruction acts on
+    Account,
+    /// Delegate being approved
+    Delegate,
+    /// Account funding the new accounts
+    Payer,
+    /// Token account credited with the rebase fee
+    FeeDestination,
+    /// Key co-signing alongside the supply authority
+    CoSigner,
+    /// Mint of the non-rebasing wrapper
+    WrapperMint,
+    /// Token account escrowing the wrapped shares
+    Escrow,
+    /// Authority of the wrapper mint and escrow
+    WrapperAuthority,
+    /// The token program
+    TokenProgram,
+    /// The system program
+    SystemProgram,
+    /// Any other account, such as those required by a transfer hook
+    Other,
+}
+
+/// Decode rebasing mint instruction data, including the leading
+/// `TokenInstruction::RebaseMintExtension` byte
+pub fn decode(input: &[u8]) -> Result<DecodedRebaseInstruction, ProgramError> {
+    let input = input
+        .strip_prefix(TokenInstruction::RebaseMintExtension.pack().as_slice())
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    Ok(match decode_instruction_type(input)? {
+        RebaseMintInstruction::Initialize => {
+            let data = decode_instruction_data::<InitializeInstructionData>(input)?;
+            DecodedRebaseInstruction::Initialize {
+                supply_authority: data.supply_authority.into(),
+                initial_supply: data.initial_supply.into(),
+                first_rebase_after_timestamp: data.first_rebase_after_timestamp.into(),
+                two_step_authority_transfer: data.two_step_authority_transfer.into(),
+                authority_change_delay_seconds: data.authority_change_delay_seconds.into(),
+                use_mint_authority_fallback: data.use_mint_authority_fallback.into(),
+                require_rebase_memo: data.require_rebase_memo.into(),
+                virtual_shares_offset: data.virtual_shares_offset.into(),
+                allow_supply_decrease: data.allow_supply_decrease.into(),
+                rebase_fee_bps: data.rebase_fee_bps.into(),
+                fee_destination: data.fee_destination.into(),
+                initial_shares: data.initial_shares.into(),
+                max_total_shares: data.max_total_shares.into(),
+                allow_sunset: data.allow_sunset.into(),
+                update_metadata_rate: data.update_metadata_rate.into(),
+                co_signer: data.co_signer.into(),
+                rebase_mode: RebaseMode::try_from(data.rebase_mode)
+                    .map_err(|_| ProgramError::InvalidInstructionData)?,
+                sync_base_supply: data.sync_base_supply.into(),
+            }
+        }
+        RebaseMintInstruction::RebaseSupply => {
+            let RebaseSupplyData {
+                new_supply,
+                reason_code,
+                reference,
+                not_after_unix_timestamp,
+            } = decode_rebase_supply_data(input)?;
+            DecodedRebaseInstruction::RebaseSupply {
+                new_supply: new_supply.into(),
+                reason_code,
+                reference,
+                not_after_unix_timestamp: not_after_unix_timestamp.into(),
+            }
+        }
+        RebaseMintInstruction::SetReserveAccount => {
+            let SetReserveAccountData { reserve_account } = decode_instruction_data(input)?;
+            DecodedRebaseInstruction::SetReserveAccount {
+                reserve_account: (*reserve_account).into(),
+            }
+        }
+        RebaseMintInstruction::PreviewRebaseSupply => {
+            let RebaseSupplyData { new_supply, .. } = decode_rebase_supply_data(input)?;
+            DecodedRebaseInstruction::PreviewRebaseSupply {
+                new_supply: new_supply.into(),
+            }
+        }
+        RebaseMintInstruction::SetSupplyAuthority => {
+            let SetSupplyAuthorityData { new_authority } = decode_instruction_data(input)?;
+            DecodedRebaseInstruction::SetSupplyAuthority {
+                new_authority: (*new_authority).into(),
+            }
+        }
+        RebaseMintInstruction::ProposeSupplyAuthority => {
+            let ProposeSupplyAuthorityData { pending_authority } = decode_instruction_data(input)?;
+            DecodedRebaseInstruction::ProposeSupplyAuthority {
+                pending_authority: (*pending_authority).into(),
+            }
+        }
+        RebaseMintInstruction::AcceptSupplyAuthority => {
+            DecodedRebaseInstruction::AcceptSupplyAuthority
+        }
+        RebaseMintInstruction::CancelPendingAuthority => {
+            DecodedRebaseInstruction::CancelPendingAuthority
+        }
+        RebaseMintInstruction::FinalizeAuthorityChange => {
+            DecodedRebaseInstruction::FinalizeAuthorityChange
+        }
+        RebaseMintInstruction::CancelAuthorityChange => {
+            DecodedRebaseInstruction::CancelAuthorityChange
+        }
+        RebaseMintInstruction::SetRebaseDelegate => {
+            let SetRebaseDelegateData { rebase_delegate } = decode_instruction_data(input)?;
+            DecodedRebaseInstruction::SetRebaseDelegate {
+                rebase_delegate: (*rebase_delegate).into(),
+            }
+        }
+        RebaseMintInstruction::TransferShares => {
+            let TransferSharesData { shares } = decode_instruction_data(input)?;
+            DecodedRebaseInstruction::TransferShares {
+                shares: (*shares).into(),
+            }
+        }
+        RebaseMintInstruction::TransferSharesChecked => {
+            let TransferSharesCheckedData { shares, decimals } = decode_instruction_data(input)?;
+            DecodedRebaseInstruction::TransferSharesChecked {
+                shares: (*shares).into(),
+                decimals: *decimals,
+            }
+        }
+        RebaseMintInstruction::MintToShares => {
+            let MintToSharesData { shares } = decode_instruction_data(input)?;
+            DecodedRebaseInstruction::MintToShares {
+                shares: (*shares).into(),
+            }
+        }
+        RebaseMintInstruction::BurnShares => {
+            let BurnSharesData { shares } = decode_instruction_data(input)?;
+            DecodedRebaseInstruction::BurnShares {
+                shares: (*shares).into(),
+            }
+        }
+        RebaseMintInstruction::SyncSupplyFromMint => DecodedRebaseInstruction::SyncSupplyFromMint,
+        RebaseMintInstruction::GetRebasedBalance => DecodedRebaseInstruction::GetRebasedBalance,
+        RebaseMintInstruction::ConvertAmountToShares => {
+            let AmountToSharesData { amount } = decode_instruction_data(input)?;
+            DecodedRebaseInstruction::ConvertAmountToShares {
+                amount: (*amount).into(),
+            }
+        }
+        RebaseMintInstruction::ConvertSharesToAmount => {
+            let SharesToAmountData { shares } = decode_instruction_data(input)?;
+            DecodedRebaseInstruction::ConvertSharesToAmount {
+                shares: (*shares).into(),
+            }
+        }
+        RebaseMintInstruction::ApproveShares => {
+            let ApproveSharesData { shares } = decode_instruction_data(input)?;
+            DecodedRebaseInstruction::ApproveShares {
+                shares: (*shares).into(),
+            }
+        }
+        RebaseMintInstruction::SetAccountExempt => {
+            let SetAccountExemptData { exempt } = decode_instruction_data(input)?;
+            DecodedRebaseInstruction::SetAccountExempt {
+                exempt: (*exempt).into(),
+            }
+        }
+        RebaseMintInstruction::InitializeWrapper => DecodedRebaseInstruction::InitializeWrapper,
+        RebaseMintInstruction::WrapShares => {
+            let WrapSharesData { shares } = decode_instruction_data(input)?;
+            DecodedRebaseInstruction::WrapShares {
+                shares: (*shares).into(),
+            }
+        }
+        RebaseMintInstruction::UnwrapShares => {
+            let UnwrapSharesData { shares } = decode_instruction_data(input)?;
+            DecodedRebaseInstruction::UnwrapShares {
+                shares: (*shares).into(),
+            }
+        }
+        RebaseMintInstruction::AssertRebaseConsistency => {
+            DecodedRebaseInstruction::AssertRebaseConsistency
+        }
+        RebaseMintInstruction::TransferCheckedWithShareSlippage => {
+            let data = decode_instruction_data::<TransferCheckedWithShareSlippageData>(input)?;
+            DecodedRebaseInstruction::TransferCheckedWithShareSlippage {
+                amount: data.amount.into(),
+                decimals: data.decimals,
+                expected_shares: data.expected_shares.into(),
+                max_share_slippage_bps: data.max_share_slippage_bps.into(),
+            }
+        }
+        RebaseMintInstruction::GetRebaseState => DecodedRebaseInstruction::GetRebaseState,
+        RebaseMintInstruction::InitializeHistory => DecodedRebaseInstruction::InitializeHistory,
+        RebaseMintInstruction::GetRebaseHistory => DecodedRebaseInstruction::GetRebaseHistory,
+        RebaseMintInstruction::SetRebaseFee => {
+            let SetRebaseFeeData {
+                rebase_fee_bps,
+                fee_destination,
+            } = decode_instruction_data(input)?;
+            DecodedRebaseInstruction::SetRebaseFee {
+                rebase_fee_bps: (*rebase_fee_bps).into(),
+                fee_destination: (*fee_destination).into(),
+            }
+        }
+        RebaseMintInstruction::SunsetRebase => DecodedRebaseInstruction::SunsetRebase,
+        RebaseMintInstruction::RetireRebase => DecodedRebaseInstruction::RetireRebase,
+        RebaseMintInstruction::SweepRoundingDust => DecodedRebaseInstruction::SweepRoundingDust,
+        RebaseMintInstruction::ClaimRebase => DecodedRebaseInstruction::ClaimRebase,
+        RebaseMintInstruction::RebaseSupplyUi => {
+            let (
+                ui_supply,
+                RebaseSupplyUiTags {
+                    reason_code,
+                    reference,
+                    not_after_unix_timestamp,
+                },
+            ) = decode_rebase_supply_ui_data(input)?;
+            DecodedRebaseInstruction::RebaseSupplyUi {
+                ui_supply: ui_supply.to_string(),
+                reason_code,
+                reference,
+                not_after_unix_timestamp: not_after_unix_timestamp.into(),
+            }
+        }
+    })
+}
+
+impl DecodedRebaseInstruction {
+    /// Map each of the instruction's accounts to its role.
+    ///
+    /// Whether a reserve account is passed to `RebaseSupply` and
+    /// `PreviewRebaseSupply`, a fee destination or metadata payer to
+    /// `RebaseSupply`, and a co-signer to `RebaseSupply` and `SunsetRebase`
+    /// depends on the mint, and `RebaseSupplyUi` takes the same accounts as
+    /// `RebaseSupply`, so `has_reserve_account`, `has_fee_destination`,
+    /// `updates_metadata_rate` and `has_co_signer` must reflect the mint's
+    /// configuration. Signer accounts after a
+    /// multisig authority are reported as `Signer`, and anything else left
+    /// over as `Other`.
+    pub fn account_roles(
+        &self,
+        accounts: &[AccountMeta],
+        has_reserve_account: bool,
+        has_fee_destination: bool,
+        updates_metadata_rate: bool,
+        has_co_signer: bool,
+    ) -> Vec<RebaseAccountRole> {
+        let mint_and_reserve = if has_reserve_account {
+            vec![RebaseAccountRole::Mint, RebaseAccountRole::ReserveAccount]
+        } else {
+            vec![RebaseAccountRole::Mint]
+        };
+        let co_signer = if has_co_signer {
+            vec![RebaseAccountRole::CoSigner]
+        } else {
+            vec![]
+        };
+        let fixed_roles = match self {
+            Self::Initialize { .. }
+            | Self::FinalizeAuthorityChange
+            | Self::SyncSupplyFromMint
+            | Self::ConvertAmountToShares { .. }
+            | Self::ConvertSharesToAmount { .. }
+            | Self::AssertRebaseConsistency
+            | Self::GetRebaseState
+            | Self::InitializeHistory
+            | Self::GetRebaseHistory => vec![RebaseAccountRole::Mint],
+            Self::RebaseSupply { .. } | Self::RebaseSupplyUi { .. } => {
+                let fee_destination = if has_fee_destination {
+                    vec![RebaseAccountRole::FeeDestination]
+                } else {
+                    vec![]
+                };
+                let metadata_payer = if updates_metadata_rate {
+                    vec![RebaseAccountRole::Payer, RebaseAccountRole::SystemProgram]
+                } else {
+                    vec![]
+                };
+                [
+                    mint_and_reserve,
+                    fee_destination,
+                    metadata_payer,
+                    co_signer,
+                    vec![RebaseAccountRole::Authority],
+                ]
+                .concat()
+            }
+            Self::SunsetRebase => [
+                vec![RebaseAccountRole::Mint],
+                co_signer,
+                vec![RebaseAccountRole::Authority],
+            ]
+            .concat(),
+            Self::PreviewRebaseSupply { .. } => mint_and_reserve,
+            Self::SetReserveAccount { .. }
+            | Self::SetSupplyAuthority { .. }
+            | Self::ProposeSupplyAuthority { .. }
+            | Self::AcceptSupplyAuthority
+            | Self::CancelPendingAuthority
+            | Self::CancelAuthorityChange
+            | Self::SetRebaseDelegate { .. }
+            | Self::SetRebaseFee { .. }
+            | Self::RetireRebase => {
+                vec![RebaseAccountRole::Mint, RebaseAccountRole::Authority]
+            }
+            Self::TransferShares { .. }
+            | Self::TransferSharesChecked { .. }
+            | Self::TransferCheckedWithShareSlippage { .. } => {
+                vec![
+                    RebaseAccountRole::Source,
+                    RebaseAccountRole::Mint,
+                    RebaseAccountRole::Destination,
+                    RebaseAccountRole::Authority,
+                ]
+            }
+            Self::MintToShares { .. } => vec![
+                RebaseAccountRole::Mint,
+                RebaseAccountRole::Account,
+                RebaseAccountRole::Authority,
+            ],
+            Self::BurnShares { .. } | Self::SetAccountExempt { .. } => {
+                vec![
+                    RebaseAccountRole::Account,
+                    RebaseAccountRole::Mint,
+                    RebaseAccountRole::Authority,
+                ]
+            }
+            Self::GetRebasedBalance | Self::ClaimRebase => {
+                vec![RebaseAccountRole::Account, RebaseAccountRole::Mint]
+            }
+            Self::SweepRoundingDust => vec![
+                RebaseAccountRole::Mint,
+                RebaseAccountRole::Destination,
+                RebaseAccountRole::Authority,
+            ],
+            Self::ApproveShares { .. } => vec![
+                RebaseAccountRole::Source,
+                RebaseAccountRole::Mint,
+                RebaseAccountRole::Delegate,
+                RebaseAccountRole::Authority,
+            ],
+            Self::InitializeWrapper => vec![
+                RebaseAccountRole::Payer,
+                RebaseAccountRole::Mint,
+                RebaseAccountRole::WrapperMint,
+                RebaseAccountRole::Escrow,
+                RebaseAccountRole::SystemProgram,
+            ],
+            Self::WrapShares { .. } => vec![
+                RebaseAccountRole::Source,
+                RebaseAccountRole::Mint,
+                RebaseAccountRole::Escrow,
+                RebaseAccountRole::WrapperMint,
+                RebaseAccountRole::Destination,
+                RebaseAccountRole::WrapperAuthority,
+                RebaseAccountRole::TokenProgram,
+                RebaseAccountRole::Authority,
+            ],
+            Self::UnwrapShares { .. } => vec![
+                RebaseAccountRole::Source,
+                RebaseAccountRole::WrapperMint,
+                RebaseAccountRole::Escrow,
+                RebaseAccountRole::Mint,
+                RebaseAccountRole::Destination,
+                RebaseAccountRole::WrapperAuthority,
+                RebaseAccountRole::TokenProgram,
+                RebaseAccountRole::Authority,
+            ],
+        };
+        let mut in_signers = fixed_roles.last() == Some(&RebaseAccountRole::Authority);
+        accounts
+            .iter()
+            .enumerate()
+            .map(|(index, account)| match fixed_roles.get(index) {
+                Some(role) => *role,
+                None if in_signers && account.is_signer => RebaseAccountRole::Signer,
+                None => {
+                    in_signers = false;
+                    RebaseAccountRole::Other
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        crate::{
+            error::TokenError,
+            extension::rebase_mint::{RebaseMintConfig, SHARES_PER_TOKEN_SCALE},
+            id,
+        },
+    };
+
+    fn check_round_trip(
+        instruction: Result<Instruction, ProgramError>,
+        expected: DecodedRebaseInstruction,
+    ) {
+        let instruction = instruction.unwrap();
+        assert_eq!(decode(&instruction.data), Ok(expected));
+        // data-carrying instructions must reject truncated data
+        if instruction.data.len() > 2 {
+            assert_eq!(
+                decode(&instruction.data[..instruction.data.len() - 1]),
+                Err(ProgramError::InvalidInstructionData)
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_round_trip() {
+        let mint = Pubkey::new_unique();
+        let account = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        check_round_trip(
+            InitializeRebaseMintBuilder::new(&id())
+                .mint(&mint)
+                .supply_authority(&authority)
+                .initial_supply(1, 6)
+                .first_rebase_after_timestamp(2)
+                .two_step_authority_transfer()
+                .authority_change_delay_seconds(3)
+                .use_mint_authority_fallback()
+                .require_rebase_memo()
+                .virtual_shares_offset(4)
+                .allow_supply_decrease()
+                .rebase_fee(5, &other)
+                .max_total_shares(7)
+                .allow_sunset()
+                .update_metadata_rate()
+                .build(),
+            DecodedRebaseInstruction::Initialize {
+                supply_authority: Some(authority),
+                initial_supply: 1,
+                first_rebase_after_timestamp: 2,
+                two_step_authority_transfer: true,
+                authority_change_delay_seconds: 3,
+                use_mint_authority_fallback: true,
+                require_rebase_memo: true,
+                virtual_shares_offset: 4,
+                allow_supply_decrease: true,
+                rebase_fee_bps: 5,
+                fee_destination: Some(other),
+                initial_shares: 6,
+                max_total_shares: 7,
+                allow_sunset: true,
+                update_metadata_rate: true,
+                co_signer: None,
+                rebase_mode: RebaseMode::Shares,
+                sync_base_supply: false,
+            },
+        );
+        check_round_trip(
+            InitializeRebaseMintBuilder::new(&id()).mint(&mint).build(),
+            DecodedRebaseInstruction::Initialize {
+                supply_authority: None,
+                initial_supply: 0,
+                first_rebase_after_timestamp: 0,
+                two_step_authority_transfer: false,
+                authority_change_delay_seconds: 0,
+                use_mint_authority_fallback: false,
+                require_rebase_memo: false,
+                virtual_shares_offset: 0,
+                allow_supply_decrease: false,
+                rebase_fee_bps: 0,
+                fee_destination: None,
+                initial_shares: 0,
+                max_total_shares: 0,
+                allow_sunset: false,
+                update_metadata_rate: false,
+                co_signer: None,
+                rebase_mode: RebaseMode::Shares,
+                sync_base_supply: false,
+            },
+        );
+        check_round_trip(
+            update_supply(&id(), &mint, Some(&other), &authority, &[], u64::MAX),
+            DecodedRebaseInstruction::RebaseSupply {
+                new_supply: u64::MAX,
+                reason_code: 0,
+                reference: [0; 32],
+                not_after_unix_timestamp: 0,
+            },
+        );
+        check_round_trip(
+            RebaseSupplyBuilder::new(&id(), 7)
+                .mint(&mint)
+                .fee_destination(&other)
+                .authority(&authority)
+                .reason_code(3)
+                .reference([9; 32])
+                .build(),
+            DecodedRebaseInstruction::RebaseSupply {
+                new_supply: 7,
+                reason_code: 3,
+                reference: [9; 32],
+                not_after_unix_timestamp: 0,
+            },
+        );
+        check_round_trip(
+            RebaseSupplyBuilder::new(&id(), 7)
+                .mint(&mint)
+                .authority(&authority)
+                .not_after_unix_timestamp(1_700_000_000)
+                .build(),
+            DecodedRebaseInstruction::RebaseSupply {
+                new_supply: 7,
+                reason_code: 0,
+                reference: [0; 32],
+                not_after_unix_timestamp: 1_700_000_000,
+            },
+        );
+        check_round_trip(
+            update_supply_ui(
+                &id(),
+                &mint,
+                Some(&other),
+                None,
+                None,
+                None,
+                &authority,
+                &[],
+                "1250000.5",
+            ),
+            DecodedRebaseInstruction::RebaseSupplyUi {
+                ui_supply: "1250000.5".to_string(),
+                reason_code: 0,
+                reference: [0; 32],
+                not_after_unix_timestamp: 0,
+            },
+        );
+        check_round_trip(
+            RebaseSupplyBuilder::new_ui(&id(), "1250000.5")
+                .mint(&mint)
+                .authority(&authority)
+                .reason_code(3)
+                .reference([9; 32])
+                .not_after_unix_timestamp(1_700_000_000)
+                .build(),
+            DecodedRebaseInstruction::RebaseSupplyUi {
+                ui_supply: "1250000.5".to_string(),
+                reason_code: 3,
+                reference: [9; 32],
+                not_after_unix_timestamp: 1_700_000_000,
+            },
+        );
+        check_round_trip(
+            set_reserve_account(&id(), &mint, &authority, &[], Some(other)),
+            DecodedRebaseInstruction::SetReserveAccount {
+                reserve_account: Some(other),
+            },
+        );
+        check_round_trip(
+            preview_rebase_supply(&id(), &mint, None, 5),
+            DecodedRebaseInstruction::PreviewRebaseSupply { new_supply: 5 },
+        );
+        check_round_trip(
+            set_supply_authority(&id(), &mint, &authority, &[], None),
+            DecodedRebaseInstruction::SetSupplyAuthority {
+                new_authority: None,
+            },
+        );
+        check_round_trip(
+            propose_supply_authority(&id(), &mint, &authority, &[], &other),
+            DecodedRebaseInstruction::ProposeSupplyAuthority {
+                pending_authority: Some(other),
+            },
+        );
+        check_round_trip(
+            accept_supply_authority(&id(), &mint, &other, &[]),
+            DecodedRebaseInstruction::AcceptSupplyAuthority,
+        );
+        check_round_trip(
+            cancel_pending_authority(&id(), &mint, &authority, &[]),
+            DecodedRebaseInstruction::CancelPendingAuthority,
+        );
+        check_round_trip(
+            finalize_authority_change(&id(), &mint),
+            DecodedRebaseInstruction::FinalizeAuthorityChange,
+        );
+        check_round_trip(
+            cancel_authority_change(&id(), &mint, &authority, &[]),
+            DecodedRebaseInstruction::CancelAuthorityChange,
+        );
+        check_round_trip(
+            set_rebase_delegate(&id(), &mint, &authority, &[], Some(other)),
+            DecodedRebaseInstruction::SetRebaseDelegate {
+                rebase_delegate: Some(other),
+            },
+        );
+        check_round_trip(
+            transfer_shares(&id(), &account, &mint, &other, &authority, &[], 6),
+            DecodedRebaseInstruction::TransferShares { shares: 6 },
+        );
+        check_round_trip(
+            transfer_shares_checked(&id(), &account, &mint, &other, &authority, &[], 7, 9),
+            DecodedRebaseInstruction::TransferSharesChecked {
+                shares: 7,
+                decimals: 9,
+            },
+        );
+        check_round_trip(
+            mint_to_shares(&id(), &mint, &account, &authority, &[], 8),
+            DecodedRebaseInstruction::MintToShares { shares: 8 },
+        );
+        check_round_trip(
+            burn_shares(&id(), &account, &mint, &authority, &[], 9),
+            DecodedRebaseInstruction::BurnShares { shares: 9 },
+        );
+        check_round_trip(
+            sync_supply_from_mint(&id(), &mint),
+            DecodedRebaseInstruction::SyncSupplyFromMint,
+        );
+        check_round_trip(
+            get_rebased_balance(&id(), &account, &mint),
+            DecodedRebaseInstruction::GetRebasedBalance,
+        );
+        check_round_trip(
+            convert_amount_to_shares(&id(), &mint, 10),
+            DecodedRebaseInstruction::ConvertAmountToShares { amount: 10 },
+        );
+        check_round_trip(
+            convert_shares_to_amount(&id(), &mint, 11),
+            DecodedRebaseInstruction::ConvertSharesToAmount { shares: 11 },
+        );
+        check_round_trip(
+            approve_shares(&id(), &account, &mint, &other, &authority, &[], 12),
+            DecodedRebaseInstruction::ApproveShares { shares: 12 },
+        );
+        check_round_trip(
+            set_account_exempt(&id(), &account, &mint, &authority, &[], true),
+            DecodedRebaseInstruction::SetAccountExempt { exempt: true },
+        );
+        check_round_trip(
+            initialize_wrapper(&id(), &authority, &mint),
+            DecodedRebaseInstruction::InitializeWrapper,
+        );
+        check_round_trip(
+            wrap_shares(&id(), &account, &mint, &other, &authority, &[], 13),
+            DecodedRebaseInstruction::WrapShares { shares: 13 },
+        );
+        check_round_trip(
+            unwrap_shares(&id(), &account, &mint, &other, &authority, &[], 14),
+            DecodedRebaseInstruction::UnwrapShares { shares: 14 },
+        );
+        check_round_trip(
+            assert_rebase_consistency(&id(), &mint),
+            DecodedRebaseInstruction::AssertRebaseConsistency,
+        );
+        check_round_trip(
+            transfer_checked_with_share_slippage(
+                &id(),
+                &account,
+                &mint,
+                &other,
+                &authority,
+                &[],
+                15,
+                9,
+                16,
+                17,
+            ),
+            DecodedRebaseInstruction::TransferCheckedWithShareSlippage {
+                amount: 15,
+                decimals: 9,
+                expected_shares: 16,
+                max_share_slippage_bps: 17,
+            },
+        );
+        check_round_trip(
+            get_rebase_state(&id(), &mint),
+            DecodedRebaseInstruction::GetRebaseState,
+        );
+        check_round_trip(
+            initialize_history(&id(), &mint),
+            DecodedRebaseInstruction::InitializeHistory,
+        );
+        check_round_trip(
+            get_rebase_history(&id(), &mint),
+            DecodedRebaseInstruction::GetRebaseHistory,
+        );
+        check_round_trip(
+            set_rebase_fee(&id(), &mint, &authority, &[], 250, Some(other)),
+            DecodedRebaseInstruction::SetRebaseFee {
+                rebase_fee_bps: 250,
+                fee_destination: Some(other),
+            },
+        );
+        check_round_trip(
+            set_rebase_fee(&id(), &mint, &authority, &[], 0, None),
+            DecodedRebaseInstruction::SetRebaseFee {
+                rebase_fee_bps: 0,
+                fee_destination: None,
+            },
+        );
+        check_round_trip(
+            sunset_rebase(&id(), &mint, &authority, &[]),
+            DecodedRebaseInstruction::SunsetRebase,
+        );
+        check_round_trip(
+            retire_rebase(&id(), &mint, &authority, &[]),
+            DecodedRebaseInstruction::RetireRebase,
+        );
+        check_round_trip(
+            sweep_rounding_dust(&id(), &mint, &Pubkey::new_unique(), &authority, &[]),
+            DecodedRebaseInstruction::SweepRoundingDust,
+        );
+        check_round_trip(
+            claim_rebase(&id(), &account, &mint),
+            DecodedRebaseInstruction::ClaimRebase,
+        );
+    }
+
+    fn check_rate(numerator: u64, denominator: u64, share_scale: u64) {
+        let instruction = initialize_with_rate(
+            &id(),
+            &Pubkey::new_unique(),
+            None,
+            numerator,
+            denominator,
+            share_scale,
+        )
+        .unwrap();
+        let Ok(DecodedRebaseInstruction::Initialize {
+            initial_supply,
+            initial_shares,
+            ..
+        }) = decode(&instruction.data)
+        else {
+            panic!("not an initialize instruction");
+        };
+        let config = RebaseMintConfig {
+            total_supply: initial_supply.into(),
+            total_shares: initial_shares.into(),
+            ..Default::default()
+        };
+        let expected = denominator as f64 / numerator as f64;
+        let actual =
+            config.shares_per_token_scaled().unwrap() as f64 / SHARES_PER_TOKEN_SCALE as f64;
+        assert!(
+            ((actual - expected) / expected).abs() <= 1e-12,
+            "{} vs {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_initialize_with_rate() {
+        check_rate(1, 1, 1);
+        check_rate(3, 1, 1_000_000);
+        check_rate(1, 3, 1_000_000_000);
+        check_rate(1_050_000, 1_000_000, 1_000_000_000);
+        check_rate(123_456_789, 987_654_321, 1_000);
+        check_rate(1_000_000, 1, 1);
+        check_rate(1, DEFAULT_MAX_TOTAL_SHARES, 1);
+        check_rate(DEFAULT_MAX_TOTAL_SHARES - 1, DEFAULT_MAX_TOTAL_SHARES, 1);
+        // common factors leave room for a larger scale
+        check_rate(u64::MAX / 5 * 3, u64::MAX / 5 * 2, u64::MAX / 5);
+
+        let mint = Pubkey::new_unique();
+        for (numerator, denominator, share_scale) in [
+            (0, 1, 1),
+            (1, 0, 1),
+            (1, 1, 0),
+            (u64::MAX, 1, 2),
+            (2, 3, u64::MAX),
+            // more shares than mints may hold by default
+            (1, DEFAULT_MAX_TOTAL_SHARES + 1, 1),
+        ] {
+            assert_eq!(
+                initialize_with_rate(&id(), &mint, None, numerator, denominator, share_scale),
+                Err(ProgramError::InvalidArgument)
+            );
+        }
+    }
+
+    #[test]
+    fn test_initialize_builder() {
+        let mint = Pubkey::new_unique();
+        let supply_authority = Pubkey::new_unique();
+        let fee_destination = Pubkey::new_unique();
+        assert_eq!(
+            InitializeRebaseMintBuilder::new(&id()).mint(&mint).build(),
+            initialize(
+                &id(),
+                &mint,
+                None,
+                0,
+                None,
+                false,
+                0,
+                false,
+                false,
+                0,
+                false,
+                0,
+                None,
+                0,
+                0,
+                false,
+                false,
+            )
+        );
+        assert_eq!(
+            InitializeRebaseMintBuilder::new(&id())
+                .mint(&mint)
+                .supply_authority(&supply_authority)
+                .initial_supply(1_000, 3_000)
+                .first_rebase_after_timestamp(1_700_000_000)
+                .two_step_authority_transfer()
+                .authority_change_delay_seconds(86_400)
+                .use_mint_authority_fallback()
+                .require_rebase_memo()
+                .virtual_shares_offset(1_000)
+                .allow_supply_decrease()
+                .rebase_fee(250, &fee_destination)
+                .max_total_shares(1_000_000)
+                .allow_sunset()
+                .update_metadata_rate()
+                .build(),
+            initialize(
+                &id(),
+                &mint,
+                Some(supply_authority),
+                1_000,
+                Some(1_700_000_000),
+                true,
+                86_400,
+                true,
+                true,
+                1_000,
+                true,
+                250,
+                Some(fee_destination),
+                3_000,
+                1_000_000,
+                true,
+                true,
+            )
+        );
+
+        let co_signer = Pubkey::new_unique();
+        let instruction = InitializeRebaseMintBuilder::new(&id())
+            .mint(&mint)
+            .supply_authority(&supply_authority)
+            .co_signer(&co_signer)
+            .build()
+            .unwrap();
+        assert!(matches!(
+            decode(&instruction.data),
+            Ok(DecodedRebaseInstruction::Initialize {
+                co_signer: Some(key),
+                ..
+            }) if key == co_signer
+        ));
+
+        let lazy = InitializeRebaseMintBuilder::new(&id())
+            .mint(&mint)
+            .supply_authority(&supply_authority)
+            .rebase_mode(RebaseMode::Lazy);
+        let instruction = lazy.clone().allow_supply_decrease().build().unwrap();
+        assert!(matches!(
+            decode(&instruction.data),
+            Ok(DecodedRebaseInstruction::Initialize {
+                rebase_mode: RebaseMode::Lazy,
+                allow_supply_decrease: true,
+                ..
+            })
+        ));
+        assert_eq!(
+            lazy.allow_sunset().build(),
+            Err(TokenError::RebaseModeMismatch.into())
+        );
+
+        let synced = InitializeRebaseMintBuilder::new(&id())
+            .mint(&mint)
+            .supply_authority(&supply_authority)
+            .sync_base_supply();
+        let instruction = synced.clone().build().unwrap();
+        assert!(matches!(
+            decode(&instruction.data),
+            Ok(DecodedRebaseInstruction::Initialize {
+                sync_base_supply: true,
+                ..
+            })
+        ));
+        assert_eq!(
+            synced.initial_supply(1_000, 0).build(),
+            Err(TokenError::RebaseBaseSupplyMismatch.into())
+        );
+
+        let builder = InitializeRebaseMintBuilder::new(&id());
+        assert_eq!(
+            builder.clone().build(),
+            Err(ProgramError::NotEnoughAccountKeys)
+        );
+        let builder = builder.mint(&mint);
+        assert_eq!(
+            builder.clone().initial_supply(0, 1).build(),
+            Err(TokenError::RebaseSupplyZero.into())
+        );
+        assert_eq!(
+            builder
+                .clone()
+                .rebase_fee(MAX_REBASE_FEE_BPS + 1, &fee_destination)
+                .build(),
+            Err(TokenError::RebaseFeeTooHigh.into())
+        );
+        assert!(builder
+            .clone()
+            .initial_supply(1, 0)
+            .max_total_shares(1)
+            .build()
+            .is_ok());
+        assert_eq!(
+            builder
+                .clone()
+                .initial_supply(1, 2)
+                .max_total_shares(1)
+                .build(),
+            Err(TokenError::RebaseSharesCapExceeded.into())
+        );
+        assert_eq!(
+            builder
+                .clone()
+                .initial_supply(1, DEFAULT_MAX_TOTAL_SHARES + 1)
+                .build(),
+            Err(TokenError::RebaseSharesCapExceeded.into())
+        );
+        // left to the program
+        let unchecked = builder
+            .initial_supply(1, DEFAULT_MAX_TOTAL_SHARES + 1)
+            .build_unchecked()
+            .unwrap();
+        assert!(matches!(
+            decode(&unchecked.data),
+            Ok(DecodedRebaseInstruction::Initialize {
+                initial_shares,
+                ..
+            }) if initial_shares == DEFAULT_MAX_TOTAL_SHARES + 1
+        ));
+    }
+
+    #[test]
+    fn test_rebase_supply_builder() {
+        let mint = Pubkey::new_unique();
+        let reserve = Pubkey::new_unique();
+        let fee_destination = Pubkey::new_unique();
+        let payer = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let signer = Pubkey::new_unique();
+        assert_eq!(
+            RebaseSupplyBuilder::new(&id(), 1_000)
+                .mint(&mint)
+                .authority(&authority)
+                .build(),
+            update_supply(&id(), &mint, None, &authority, &[], 1_000)
+        );
+        assert_eq!(
+            RebaseSupplyBuilder::new(&id(), 1_000)
+                .mint(&mint)
+                .reserve_account(&reserve)
+                .fee_destination(&fee_destination)
+                .metadata_payer(&payer)
+                .authority(&authority)
+                .multisig_signers(&[&signer])
+                .multisig_signers(&[&payer])
+                .reason_code(3)
+                .reference([7; 32])
+                .build(),
+            update_supply_with_metadata_payer(
+                &id(),
+                &mint,
+                Some(&reserve),
+                Some(&fee_destination),
+                Some(&payer),
+                &authority,
+                &[&signer, &payer],
+                1_000,
+                3,
+                [7; 32],
+            )
+        );
+        let co_signer = Pubkey::new_unique();
+        let instruction = RebaseSupplyBuilder::new(&id(), 1_000)
+            .mint(&mint)
+            .fee_destination(&fee_destination)
+            .co_signer(&co_signer)
+            .authority(&authority)
+            .build()
+            .unwrap();
+        assert_eq!(
+            Ok(instruction.clone()),
+            update_supply_with_co_signer(
+                &id(),
+                &mint,
+                None,
+                Some(&fee_destination),
+                None,
+                Some(&co_signer),
+                &authority,
+                &[],
+                1_000,
+                0,
+                [0; 32],
+            )
+        );
+        assert_eq!(
+            instruction.accounts,
+            vec![
+                AccountMeta::new(mint, false),
+                AccountMeta::new(fee_destination, false),
+                AccountMeta::new_readonly(co_signer, true),
+                AccountMeta::new_readonly(authority, true),
+            ]
+        );
+        assert_eq!(
+            RebaseSupplyBuilder::new(&id(), 1_000)
+                .mint(&mint)
+                .authority(&authority)
+                .build_with_memo("quarterly report"),
+            update_supply_with_memo(
+                &id(),
+                &mint,
+                None,
+                &authority,
+                &[],
+                1_000,
+                "quarterly report"
+            )
+        );
+
+        assert_eq!(
+            RebaseSupplyBuilder::new(&id(), 1_000)
+                .authority(&authority)
+                .build(),
+            Err(ProgramError::NotEnoughAccountKeys)
+        );
+        assert_eq!(
+            RebaseSupplyBuilder::new(&id(), 1_000).mint(&mint).build(),
+            Err(ProgramError::NotEnoughAccountKeys)
+        );
+        // a zero supply is left to the program, as with the legacy function
+        assert!(RebaseSupplyBuilder::new(&id(), 0)
+            .mint(&mint)
+            .authority(&authority)
+            .build()
+            .is_ok());
+        assert!(update_supply(&id(), &mint, None, &authority, &[], 0).is_ok());
+
+        // a UI supply is only checked by the program, apart from its length
+        assert_eq!(
+            RebaseSupplyBuilder::new_ui(&id(), "0")
+                .mint(&mint)
+                .reserve_account(&reserve)
+                .authority(&authority)
+                .build(),
+            update_supply_ui(
+                &id(),
+                &mint,
+                Some(&reserve),
+                None,
+                None,
+                None,
+                &authority,
+                &[],
+                "0"
+            )
+        );
+        assert_eq!(
+            RebaseSupplyBuilder::new_ui(&id(), &"1".repeat(MAX_UI_SUPPLY_LEN + 1))
+                .mint(&mint)
+                .authority(&authority)
+                .build(),
+            Err(TokenError::RebaseUiSupplyTooLong.into())
+        );
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        assert_eq!(decode(&[]), Err(ProgramError::InvalidInstructionData));
+        // not a rebasing mint instruction
+        let data = TokenInstruction::InitializeNonTransferableMint.pack();
+        assert_eq!(decode(&data), Err(ProgramError::InvalidInstructionData));
+        let prefix = TokenInstruction::RebaseMintExtension.pack();
+        assert_eq!(decode(&prefix), Err(ProgramError::InvalidInstructionData));
+        assert_eq!(
+            decode(&[prefix.as_slice(), &[u8::MAX]].concat()),
+            Err(TokenError::InvalidInstruction.into())
+        );
+
+        // a UI supply must fill the data exactly, or up to its tags, within the
+        // length bound
+        let ui_supply_data = |len: u32, ui_supply: &[u8]| {
+            [prefix.as_slice(), &[34], &len.to_le_bytes(), ui_supply].concat()
+        };
+        let tags = [0; size_of::<RebaseSupplyUiTags>()];
+        assert_eq!(
+            decode(&ui_supply_data(
+                3,
+                &[b"2.5".as_slice(), &tags[1..]].concat()
+            )),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            decode(&ui_supply_data(
+                3,
+                &[b"2.5".as_slice(), &tags, &[0]].concat()
+            )),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            decode(&ui_supply_data(2, b"2.5")),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            decode(&ui_supply_data(4, b"2.5")),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            decode(&ui_supply_data(u32::MAX, b"2.5")),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            decode(&ui_supply_data(65, &[b'1'; 65])),
+            Err(TokenError::RebaseUiSupplyTooLong.into())
+        );
+        // and be UTF-8
+        assert_eq!(
+            decode(&ui_supply_data(2, &[0xc3, 0x28])),
+            Err(TokenError::RebaseUiSupplyMalformed.into())
+        );
+    }
+
+    #[test]
+    fn test_decode_rebase_history() {
+        let entry = |i: u64| RebaseHistoryEntry {
+            unix_timestamp: (i as i64).into(),
+            old_supply: i.into(),
+            new_supply: (i + 1).into(),
+            new_shares: 0.into(),
+            reason_code: 1,
+            reference: [i as u8; 32],
+        };
+        let mut history = RebaseHistory::default();
+        assert_eq!(decode_rebase_history(&[]), Ok(RebaseHistoryData::default()));
+        for i in 0..REBASE_HISTORY_LEN as u64 + 2 {
+            history.record(entry(i));
+            let expected = RebaseHistoryData::from(&history);
+            assert_eq!(expected.entries[0], entry(i));
+            let mut data = expected.pack();
+            assert_eq!(decode_rebase_history(&data), Ok(expected.clone()));
+            // as the runtime returns it
+            while data.last() == Some(&0) {
+                data.pop();
+            }
+            assert_eq!(decode_rebase_history(&data), Ok(expected));
+        }
+        let mut data = RebaseHistoryData::from(&history).pack();
+        data.push(1);
+        assert_eq!(
+            decode_rebase_history(&data),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_account_roles() {
+        let mint = Pubkey::new_unique();
+        let reserve = Pubkey::new_unique();
+        let multisig = Pubkey::new_unique();
+        let signers = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let instruction = update_supply(
+            &id(),
+            &mint,
+            Some(&reserve),
+            &multisig,
+            &[&signers[0], &signers[1]],
+            1,
+        )
+        .unwrap();
+        let decoded = decode(&instruction.data).unwrap();
+        assert_eq!(
+            decoded.account_roles(&instruction.accounts, true, false, false, false),
+            vec![
+                RebaseAccountRole::Mint,
+                RebaseAccountRole::ReserveAccount,
+                RebaseAccountRole::Authority,
+                RebaseAccountRole::Signer,
+                RebaseAccountRole::Signer,
+            ]
+        );
+
+        let fee_destination = Pubkey::new_unique();
+        let instruction = RebaseSupplyBuilder::new(&id(), 1)
+            .mint(&mint)
+            .fee_destination(&fee_destination)
+            .authority(&multisig)
+            .build()
+            .unwrap();
+        let decoded = decode(&instruction.data).unwrap();
+        assert_eq!(
+            decoded.account_roles(&instruction.accounts, false, true, false, false),
+            vec![
+                RebaseAccountRole::Mint,
+                RebaseAccountRole::FeeDestination,
+                RebaseAccountRole::Authority,
+            ]
+        );
+
+        let instruction = update_supply_ui(
+            &id(),
+            &mint,
+            Some(&reserve),
+            Some(&fee_destination),
+            None,
+            None,
+            &multisig,
+            &[],
+            "1",
+        )
+        .unwrap();
+        let decoded = decode(&instruction.data).unwrap();
+        assert_eq!(
+            decoded.account_roles(&instruction.accounts, true, true, false, false),
+            vec![
+                RebaseAccountRole::Mint,
+                RebaseAccountRole::ReserveAccount,
+                RebaseAccountRole::FeeDestination,
+                RebaseAccountRole::Authority,
+            ]
+        );
+
+        let payer = Pubkey::new_unique();
+        let instruction = RebaseSupplyBuilder::new(&id(), 1)
+            .mint(&mint)
+            .metadata_payer(&payer)
+            .authority(&multisig)
+            .build()
+            .unwrap();
+        let decoded = decode(&instruction.data).unwrap();
+        assert_eq!(
+            decoded.account_roles(&instruction.accounts, false, false, true, false),
+            vec![
+                RebaseAccountRole::Mint,
+                RebaseAccountRole::Payer,
+                RebaseAccountRole::SystemProgram,
+                RebaseAccountRole::Authority,
+            ]
+        );
+
+        let co_signer = Pubkey::new_unique();
+        let instruction = RebaseSupplyBuilder::new(&id(), 1)
+            .mint(&mint)
+            .co_signer(&co_signer)
+            .authority(&multisig)
+            .multisig_signers(&[&signers[0]])
+            .build()
+            .unwrap();
+        let decoded = decode(&instruction.data).unwrap();
+        assert_eq!(
+            decoded.account_roles(&instruction.accounts, false, false, false, true),
+            vec![
+                RebaseAccountRole::Mint,
+                RebaseAccountRole::CoSigner,
+                RebaseAccountRole::Authority,
+                RebaseAccountRole::Signer,
+            ]
+        );
+        let instruction =
+            sunset_rebase_with_co_signer(&id(), &mint, Some(&co_signer), &multisig, &[]).unwrap();
+        let decoded = decode(&instruction.data).unwrap();
+        assert_eq!(
+            decoded.account_roles(&instruction.accounts, false, false, false, true),
+            vec![
+                RebaseAccountRole::Mint,
+                RebaseAccountRole::CoSigner,
+                RebaseAccountRole::Authority,
+            ]
+        );
+
+        // extra accounts after a single signer, as for a transfer hook
+        let source = Pubkey::new_unique();
+        let destination = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let mut instruction =
+            transfer_shares(&id(), &source, &mint, &destination, &owner, &[], 1).unwrap();
+        instruction
+            .accounts
+            .push(AccountMeta::new_readonly(Pubkey::new_unique(), false));
+        let decoded = decode(&instruction.data).unwrap();
+        assert_eq!(
+            decoded.account_roles(&instruction.accounts, false, false, false, false),
+            vec![
+                RebaseAccountRole::Source,
+                RebaseAccountRole::Mint,
+                RebaseAccountRole::Destination,
+                RebaseAccountRole::Authority,
+                RebaseAccountRole::Other,
+            ]
+        );
+
+        let instruction = preview_rebase_supply(&id(), &mint, None, 1).unwrap();
+        let decoded = decode(&instruction.data).unwrap();
+        assert_eq!(
+            decoded.account_roles(&instruction.accounts, false, false, false, false),
+            vec![RebaseAccountRole::Mint]
+        );
+    }
+
+    fn to_hex(data: &[u8]) -> String {
+        use std::fmt::Write;
+        data.iter().fold(String::new(), |mut hex, byte| {
+            write!(hex, "{:02x}", byte).unwrap();
+            hex
+        })
+    }
+
+    #[test]
+    fn test_wire_format() {
+        // Changing any of these fixtures breaks every client that encodes
+        // rebasing mint instructions by hand
+        let instruction = InitializeRebaseMintBuilder::new(&id())
+            .mint(&Pubkey::new_unique())
+            .supply_authority(&Pubkey::new_from_array([7; 32]))
+            .initial_supply(1_000, 3_000)
+            .first_rebase_after_timestamp(1_700_000_000)
+            .two_step_authority_transfer()
+            .authority_change_delay_seconds(3_600)
+            .require_rebase_memo()
+            .virtual_shares_offset(10)
+            .rebase_fee(250, &Pubkey::new_from_array([8; 32]))
+            .max_total_shares(1_000_000)
+            .allow_sunset()
+            .update_metadata_rate()
+            .build()
+            .unwrap();
+        assert_eq!(
+            to_hex(&instruction.data),
+            "2a00\
+             0707070707070707070707070707070707070707070707070707070707070707\
+             e803000000000000\
+             00f1536500000000\
+             01\
+             100e0000\
+             00\
+             01\
+             0a00000000000000\
+             00\
+             fa00\
+             0808080808080808080808080808080808080808080808080808080808080808\
+             b80b000000000000\
+             40420f0000000000\
+             01\
+             01\
+             0000000000000000000000000000000000000000000000000000000000000000\
+             00\
+             00"
+        );
+        let instruction = update_supply(
+            &id(),
+            &Pubkey::new_unique(),
+            None,
+            &Pubkey::new_unique(),
+            &[],
+            2_500,
+        )
+        .unwrap();
+        assert_eq!(
+            to_hex(&instruction.data),
+            "2a01\
+             c409000000000000\
+             00\
+             0000000000000000000000000000000000000000000000000000000000000000\
+             0000000000000000"
+        );
+        let mut reference = [0; 32];
+        reference[0] = 0xab;
+        reference[31] = 0xcd;
+        let instruction = RebaseSupplyBuilder::new(&id(), 2_500)
+            .mint(&Pubkey::new_unique())
+            .authority(&Pubkey::new_unique())
+            .reason_code(4)
+            .reference(reference)
+            .build()
+            .unwrap();
+        assert_eq!(
+            to_hex(&instruction.data),
+            "2a01\
+             c409000000000000\
+             04\
+             ab000000000000000000000000000000000000000000000000000000000000cd\
+             0000000000000000"
+        );
+        let instruction = RebaseSupplyBuilder::new(&id(), 2_500)
+            .mint(&Pubkey::new_unique())
+            .authority(&Pubkey::new_unique())
+            .not_after_unix_timestamp(1_700_000_000)
+            .build()
+            .unwrap();
+        assert_eq!(
+            to_hex(&instruction.data),
+            "2a01\
+             c409000000000000\
+             00\
+             0000000000000000000000000000000000000000000000000000000000000000\
+             00f1536500000000"
+        );
+
+        let instruction = update_supply_ui(
+            &id(),
+            &Pubkey::new_unique(),
+            None,
+            None,
+            None,
+            None,
+            &Pubkey::new_unique(),
+            &[],
+            "2.5",
+        )
+        .unwrap();
+        assert_eq!(
+            to_hex(&instruction.data),
+            "2a22\
+             03000000\
+             322e35\
+             00\
+             0000000000000000000000000000000000000000000000000000000000000000\
+             0000000000000000"
+        );
+
+        // rebases built before the reason existed leave it unset
+        assert_eq!(
+            decode(&[0x2a, 0x01, 0xc4, 0x09, 0, 0, 0, 0, 0, 0]),
+            Ok(DecodedRebaseInstruction::RebaseSupply {
+                new_supply: 2_500,
+                reason_code: 0,
+                reference: [0; 32],
+                not_after_unix_timestamp: 0,
+            })
+        );
+        // and those built before the deadline existed have none
+        let mut undated = vec![0x2a, 0x01, 0xc4, 0x09, 0, 0, 0, 0, 0, 0, 4];
+        undated.extend_from_slice(&reference);
+        assert_eq!(
+            decode(&undated),
+            Ok(DecodedRebaseInstruction::RebaseSupply {
+                new_supply: 2_500,
+                reason_code: 4,
+                reference,
+                not_after_unix_timestamp: 0,
+            })
+        );
+        assert_eq!(
+            decode(&[0x2a, 0x03, 0x05, 0, 0, 0, 0, 0, 0, 0]),
+            Ok(DecodedRebaseInstruction::PreviewRebaseSupply { new_supply: 5 })
+        );
+        // and UI rebases built before their tags existed have none
+        assert_eq!(
+            decode(&[0x2a, 0x22, 0x03, 0, 0, 0, b'2', b'.', b'5']),
+            Ok(DecodedRebaseInstruction::RebaseSupplyUi {
+                ui_supply: "2.5".to_string(),
+                reason_code: 0,
+                reference: [0; 32],
+                not_after_unix_timestamp: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn test_instruction_discriminants() {
+        let discriminants = [
+            (RebaseMintInstruction::Initialize, 0),
+            (RebaseMintInstruction::RebaseSupply, 1),
+            (RebaseMintInstruction::SetReserveAccount, 2),
+            (RebaseMintInstruction::PreviewRebaseSupply, 3),
+            (RebaseMintInstruction::SetSupplyAuthority, 4),
+            (RebaseMintInstruction::ProposeSupplyAuthority, 5),
+            (RebaseMintInstruction::AcceptSupplyAuthority, 6),
+            (RebaseMintInstruction::CancelPendingAuthority, 7),
+            (RebaseMintInstruction::FinalizeAuthorityChange, 8),
+            (RebaseMintInstruction::CancelAuthorityChange, 9),
+            (RebaseMintInstruction::SetRebaseDelegate, 10),
+            (RebaseMintInstruction::TransferShares, 11),
+            (RebaseMintInstruction::TransferSharesChecked, 12),
+            (RebaseMintInstruction::MintToShares, 13),
+            (RebaseMintInstruction::BurnShares, 14),
+            (RebaseMintInstruction::SyncSupplyFromMint, 15),
+            (RebaseMintInstruction::GetRebasedBalance, 16),
+            (RebaseMintInstruction::ConvertAmountToShares, 17),
+            (RebaseMintInstruction::ConvertSharesToAmount, 18),
+            (RebaseMintInstruction::ApproveShares, 19),
+            (RebaseMintInstruction::SetAccountExempt, 20),
+            (RebaseMintInstruction::InitializeWrapper, 21),
+            (RebaseMintInstruction::WrapShares, 22),
+            (RebaseMintInstruction::UnwrapShares, 23),
+            (RebaseMintInstruction::AssertRebaseConsistency, 24),
+            (RebaseMintInstruction::TransferCheckedWithShareSlippage, 25),
+            (RebaseMintInstruction::GetRebaseState, 26),
+            (RebaseMintInstruction::InitializeHistory, 27),
+            (RebaseMintInstruction::GetRebaseHistory, 28),
+            (RebaseMintInstruction::SetRebaseFee, 29),
+            (RebaseMintInstruction::SunsetRebase, 30),
+            (RebaseMintInstruction::RetireRebase, 31),
+            (RebaseMintInstruction::SweepRoundingDust, 32),
+            (RebaseMintInstruction::ClaimRebase, 33),
+            (RebaseMintInstruction::RebaseSupplyUi, 34),
+        ];
+        for (instruction, discriminant) in discriminants {
+            assert_eq!(u8::from(instruction), discriminant);
+            assert_eq!(
+                RebaseMintInstruction::try_from(discriminant),
+                Ok(instruction)
+            );
+        }
+        assert!(RebaseMintInstruction::try_from(35).is_err());
+        assert_eq!(
+            TokenInstruction::RebaseMintExtension.pack(),
+            vec![42],
+            "TokenInstruction::RebaseMintExtension prefix"
+        );
+    }
+}
@@ -0,0 +1,252 @@
+//! Off-chain helpers to move holders of an interest-bearing mint to a
+//! rebasing mint with the same economic behavior.
+//!
+//! Each holder receives one share per raw token held on the interest-bearing
+//! mint, and the supply authority then rebases the total supply to the
+//! accrued value of the old supply at every step of the schedule. At each
+//! scheduled timestamp, balances on the new mint match the UI amounts of the
+//! old mint, up to rounding.
+
+use {
+    crate::{
+        check_program_account,
+        extension::{
+            interest_bearing_mint::InterestBearingConfig,
+            rebase_mint::instruction::InitializeRebaseMintBuilder, ExtensionType,
+        },
+        instruction::initialize_mint2,
+        state::Mint,
+    },
+    solana_program::{
+        instruction::Instruction, program_error::ProgramError, pubkey::Pubkey, system_instruction,
+    },
+};
+
+/// A rebase of the total supply at a given time
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScheduledRebase {
+    /// Unix timestamp at which to rebase
+    pub unix_timestamp: i64,
+    /// The total supply to rebase to
+    pub new_supply: u64,
+}
+
+/// Everything needed to launch a rebasing successor of an interest-bearing
+/// mint
+#[derive(Clone, Debug, PartialEq)]
+pub struct MigrationPlan {
+    /// Shares to mint, one per raw token of the interest-bearing supply
+    pub shares_to_mint: u64,
+    /// Whether the schedule ever lowers the supply, as with negative rates
+    pub allow_supply_decrease: bool,
+    /// Rebases to perform, starting at the migration timestamp
+    pub schedule: Vec<ScheduledRebase>,
+}
+
+/// Get the accrued value, in raw tokens, of `amount` tokens of an
+/// interest-bearing mint at the given time.
+///
+/// Rounds up, so that converting shares back down at the rebased ratio never
+/// leaves a holder a full token short.
+pub fn accrued_amount(
+    config: &InterestBearingConfig,
+    amount: u64,
+    unix_timestamp: i64,
+) -> Option<u64> {
+    let accrued = (amount as f64) * config.total_scale(0, unix_timestamp)?;
+    if accrued > (u64::MAX as f64) || accrued < 0. || accrued.is_nan() {
+        None
+    } else {
+        Some(accrued.ceil() as u64)
+    }
+}
+
+/// Compute the rebase schedule that follows `config` for a supply of
+/// `supply` raw tokens, rebasing at `migration_timestamp` and then every
+/// `period_seconds` for `periods` periods.
+///
+/// Only the current rate can be projected, so the schedule must be recomputed
+/// if the rate changes on the interest-bearing mint before it is retired.
+pub fn plan_migration(
+    config: &InterestBearingConfig,
+    supply: u64,
+    migration_timestamp: i64,
+    period_seconds: u32,
+    periods: u32,
+) -> Result<MigrationPlan, ProgramError> {
+    if period_seconds == 0 {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let schedule = (0..=periods)
+        .map(|period| {
+            let unix_timestamp = (period as i64)
+                .checked_mul(period_seconds as i64)
+                .and_then(|offset| migration_timestamp.checked_add(offset))?;
+            Some(ScheduledRebase {
+                unix_timestamp,
+                new_supply: accrued_amount(config, supply, unix_timestamp)?,
+            })
+        })
+        .collect::<Option<Vec<_>>>()
+        .ok_or(ProgramError::InvalidArgument)?;
+    // holders arrive with their raw amounts, so the first rebase goes from
+    // there
+    let allow_supply_decrease = schedule
+        .iter()
+        .try_fold(supply, |previous_supply, rebase| {
+            (rebase.new_supply >= previous_supply).then_some(rebase.new_supply)
+        })
+        .is_none();
+    Ok(MigrationPlan {
+        shares_to_mint: supply,
+        allow_supply_decrease,
+        schedule,
+    })
+}
+
+/// Create the instructions to set up the rebasing successor mint for `plan`.
+///
+/// The mint starts without supply, so that minting `shares_to_mint` to the
+/// holders issues shares one-to-one before the first rebase. `lamports` must
+/// cover the rent for a mint with only the `RebaseMintConfig` extension.
+#[allow(clippy::too_many_arguments)]
+pub fn create_migrated_mint(
+    token_program_id: &Pubkey,
+    payer: &Pubkey,
+    mint: &Pubkey,
+    lamports: u64,
+    mint_authority: &Pubkey,
+    supply_authority: &Pubkey,
+    decimals: u8,
+    plan: &MigrationPlan,
+) -> Result<Vec<Instruction>, ProgramError> {
+    check_program_account(token_program_id)?;
+    let space =
+        ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::RebaseMintConfig])?;
+    let mut initialize = InitializeRebaseMintBuilder::new(token_program_id)
+        .mint(mint)
+        .supply_authority(supply_authority);
+    if plan.allow_supply_decrease {
+        initialize = initialize.allow_supply_decrease();
+    }
+    Ok(vec![
+        system_instruction::create_account(payer, mint, lamports, space as u64, token_program_id),
+        initialize.build()?,
+        initialize_mint2(token_program_id, mint, mint_authority, None, decimals)?,
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::extension::rebase_mint::RebaseMintConfig,
+        spl_pod::{optional_keys::OptionalNonZeroPubkey, primitives::PodI16},
+    };
+
+    const INT_SECONDS_PER_YEAR: i64 = 6 * 6 * 24 * 36524;
+    const TEST_DECIMALS: u8 = 6;
+    const DAY: u32 = 24 * 60 * 60;
+
+    fn interest_config(pre_update_average_rate: i16, current_rate: i16) -> InterestBearingConfig {
+        InterestBearingConfig {
+            rate_authority: OptionalNonZeroPubkey::default(),
+            initialization_timestamp: 0.into(),
+            pre_update_average_rate: PodI16::from(pre_update_average_rate),
+            last_update_timestamp: INT_SECONDS_PER_YEAR.into(),
+            current_rate: PodI16::from(current_rate),
+        }
+    }
+
+    fn check_trajectory(interest: &InterestBearingConfig, balances: &[u64]) {
+        let supply = balances.iter().sum();
+        let plan = plan_migration(interest, supply, INT_SECONDS_PER_YEAR, 30 * DAY, 24).unwrap();
+        assert_eq!(plan.schedule.len(), 25);
+        let scale = 10f64.powi(TEST_DECIMALS as i32);
+        for rebase in &plan.schedule {
+            let config = RebaseMintConfig {
+                total_supply: rebase.new_supply.into(),
+                total_shares: plan.shares_to_mint.into(),
+                ..Default::default()
+            };
+            for balance in balances {
+                let expected = interest
+                    .amount_to_ui_amount(*balance, TEST_DECIMALS, rebase.unix_timestamp)
+                    .unwrap()
+                    .parse::<f64>()
+                    .unwrap();
+                let migrated = config
+                    .shares_to_ui_amount(*balance, TEST_DECIMALS)
+                    .unwrap()
+                    .parse::<f64>()
+                    .unwrap();
+                assert!(
+                    ((expected - migrated) * scale).abs() <= 1.,
+                    "{} vs {} at {}",
+                    expected,
+                    migrated,
+                    rebase.unix_timestamp
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn golden_positive_rate() {
+        let interest = interest_config(500, 700);
+        check_trajectory(&interest, &[1, 999_999, 5_000_000_000, 123_456_789_012]);
+        let plan = plan_migration(&interest, 1_000_000, INT_SECONDS_PER_YEAR, DAY, 3).unwrap();
+        assert!(!plan.allow_supply_decrease);
+        // a year at 5%
+        assert_eq!(plan.schedule[0].new_supply, 1_051_272);
+        assert_eq!(plan.schedule[0].unix_timestamp, INT_SECONDS_PER_YEAR);
+        assert_eq!(
+            plan.schedule[3].unix_timestamp,
+            INT_SECONDS_PER_YEAR + 3 * DAY as i64
+        );
+    }
+
+    #[test]
+    fn golden_negative_rate() {
+        let interest = interest_config(-500, -200);
+        check_trajectory(&interest, &[7, 1_000_000, 987_654_321_000]);
+        let plan = plan_migration(&interest, 1_000_000, INT_SECONDS_PER_YEAR, DAY, 3).unwrap();
+        assert!(plan.allow_supply_decrease);
+        assert_eq!(plan.schedule[0].new_supply, 951_230);
+    }
+
+    #[test]
+    fn plan_needs_period() {
+        let interest = interest_config(500, 500);
+        assert_eq!(
+            plan_migration(&interest, 1, 0, 0, 1),
+            Err(ProgramError::InvalidArgument)
+        );
+    }
+
+    #[test]
+    fn migrated_mint_instructions() {
+        let interest = interest_config(500, 500);
+        let plan = plan_migration(&interest, 1_000, INT_SECONDS_PER_YEAR, DAY, 1).unwrap();
+        let payer = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let instructions = create_migrated_mint(
+            &crate::id(),
+            &payer,
+            &mint,
+            1,
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            TEST_DECIMALS,
+            &plan,
+        )
+        .unwrap();
+        assert_eq!(instructions.len(), 3);
+        assert_eq!(
+            instructions[0].program_id,
+            solana_program::system_program::id()
+        );
+        assert_eq!(instructions[1].program_id, crate::id());
+        assert_eq!(instructions[2].program_id, crate::id());
+    }
+}
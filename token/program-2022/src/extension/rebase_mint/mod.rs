@@ -0,0 +1,3426 @@
+#[cfg(feature = "serde-traits")]
+use serde::{Deserialize, Serialize};
+use {
+    crate::{
+        error::TokenError,
+        extension::{BaseStateWithExtensions, Extension, ExtensionType, StateWithExtensionsMut},
+        state::{Account, Mint},
+    },
+    bytemuck::{Pod, Zeroable},
+    num_enum::{IntoPrimitive, TryFromPrimitive},
+    solana_program::{entrypoint::ProgramResult, msg, program_error::ProgramError, pubkey::Pubkey},
+    spl_pod::{
+        optional_keys::OptionalNonZeroPubkey,
+        primitives::{PodBool, PodI64, PodU128, PodU16, PodU32, PodU64},
+    },
+    std::convert::TryFrom,
+};
+
+/// Rebasing mint extension instructions
+pub mod instruction;
+
+/// Rebasing mint extension processor
+pub mod processor;
+
+/// Staged activation of the rebasing mint instructions
+pub mod activation;
+
+/// On-chain helpers to CPI into the rebasing mint extension
+pub mod onchain;
+
+/// Addresses of the non-rebasing wrapper of a rebasing mint
+pub mod wrapper;
+
+/// Off-chain helpers to migrate from an interest-bearing mint
+pub mod migration;
+
+/// Off-chain helpers to read rebase-adjusted balances in bulk
+pub mod offchain;
+
+/// Off-chain helpers to estimate the yield of a rebasing mint
+pub mod apy;
+
+/// Zero-copy reads of the rebasing mint configuration from account data
+pub mod pod;
+
+/// Typed events logged by the rebasing mint instructions
+pub mod event;
+
+/// Invariant assertions for audit builds
+#[cfg(feature = "audit-assertions")]
+pub mod audit;
+
+/// JSON display types for rebasing mint state
+#[cfg(feature = "serde-traits")]
+pub mod ui;
+
+/// `Arbitrary` implementations for fuzzing
+#[cfg(feature = "fuzz")]
+pub mod fuzz;
+
+/// Proptest strategies and mint account fixtures for downstream tests
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+
+/// Rebasing mint extension data for mints
+///
+/// Token accounts for a rebasing mint hold shares of the total supply rather
+/// than a fixed amount of tokens. When the supply authority rebases the
+/// supply, every holder's balance scales proportionally without touching any
+/// token account.
+///
+/// Each token account tracks its shares in the `RebaseAccount` extension, so
+/// transfers must provide the mint, as with `TransferChecked`. Minting and
+/// burning issue and retire shares at the current ratio, rounding in favor of
+/// the other holders.
+///
+/// The supply authority can exempt token accounts, such as protocol-owned
+/// pools, from rebasing. Exempt accounts hold a fixed amount of tokens outside
+/// of the shares, tracked in `exempt_supply`.
+///
+/// If `reserve_account` is set, the supply can never be rebased above the
+/// amount held in that token account.
+///
+/// In `RebaseMode::Lazy`, token accounts hold plain amounts instead, along
+/// with the `rebase_index` they last caught up to in a `LazyRebaseAccount`
+/// extension, and there are no shares: see `RebaseMode`.
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct RebaseMintConfig {
+    /// Authority that can rebase the supply
+    pub supply_authority: OptionalNonZeroPubkey,
+    /// Total supply of tokens, as of the last rebase, mint, or burn
+    pub total_supply: PodU64,
+    /// Total number of shares that the supply is divided into
+    pub total_shares: PodU64,
+    /// Optional token account holding the reserve backing the supply
+    pub reserve_account: OptionalNonZeroPubkey,
+    /// Unix timestamp before which the supply cannot be rebased, or zero if
+    /// rebasing is allowed right away
+    pub first_rebase_after_timestamp: PodI64,
+    /// Proposed supply authority, waiting to accept the role
+    pub pending_supply_authority: OptionalNonZeroPubkey,
+    /// If true, the supply authority can only be handed to a new key through
+    /// a proposal that the new key accepts. Revoking is still direct.
+    pub two_step_authority_transfer: PodBool,
+    /// Number of seconds that a change of supply authority must wait before
+    /// it can be finalized, or zero if changes apply immediately
+    pub authority_change_delay_seconds: PodU32,
+    /// Supply authority waiting for the change delay to elapse
+    pub scheduled_supply_authority: OptionalNonZeroPubkey,
+    /// Unix timestamp at which `scheduled_supply_authority` was requested, or
+    /// zero if no change is scheduled
+    pub authority_change_requested_timestamp: PodI64,
+    /// Key that can rebase the supply on behalf of the supply authority, but
+    /// not change the configuration
+    pub rebase_delegate: OptionalNonZeroPubkey,
+    /// If true, the mint authority can rebase the supply while there is no
+    /// supply authority
+    pub use_mint_authority_fallback: PodBool,
+    /// If true, every rebase must be preceded by a memo in the same
+    /// transaction
+    pub require_rebase_memo: PodBool,
+    /// True once the supply has been rebased, after which it can no longer be
+    /// synced from the mint
+    pub has_rebased: PodBool,
+    /// Total amount of tokens held by accounts exempt from rebasing, which is
+    /// not part of `total_supply`
+    pub exempt_supply: PodU64,
+    /// Shares held by token accounts, issued by minting and retired by
+    /// burning. Unlike `total_shares`, it leaves out an initial supply that
+    /// was never minted.
+    pub outstanding_shares: PodU64,
+    /// Virtual shares and tokens added to the totals in every conversion, so
+    /// that the ratio can't be skewed while the totals are tiny. Set at
+    /// initialization and immutable.
+    pub virtual_shares_offset: PodU64,
+    /// If false, the supply can never be rebased lower than it is. Set at
+    /// initialization and immutable.
+    pub allow_supply_decrease: PodBool,
+    /// Tokens per share including the virtual offset, as a fixed-point number
+    /// with `CACHED_RATIO_FRACTIONAL_BITS` fractional bits, recomputed at
+    /// initialization and at every rebase. Zero if there are no shares or the
+    /// ratio doesn't fit.
+    ///
+    /// Only an accelerator: minting and burning leave it slightly stale, so
+    /// conversions check the result it gives and divide if it's off.
+    pub cached_ratio: PodU128,
+    /// Share of every increase of the supply, in basis points, taken as fee
+    /// by `fee_destination` rather than going to all holders
+    pub rebase_fee_bps: PodU16,
+    /// Token account of this mint credited with the rebase fee, or none if
+    /// rebases charge no fee
+    pub fee_destination: OptionalNonZeroPubkey,
+    /// Most shares that may ever exist at once, so that balances computed
+    /// downstream from shares can't overflow, or zero for
+    /// `DEFAULT_MAX_TOTAL_SHARES`. Set at initialization and immutable.
+    pub max_total_shares: PodU64,
+    /// If true, the supply authority can sunset the mint, writing off its
+    /// whole supply for good. Set at initialization and immutable.
+    pub allow_sunset: PodBool,
+    /// True once the mint has been sunset, after which every share is worth
+    /// nothing and the config can't change anymore
+    pub is_sunset: PodBool,
+    /// If true, every rebase writes the new `tokens_per_share_scaled` into
+    /// the mint's token-metadata under `REBASE_RATE_METADATA_KEY`, if the mint
+    /// has any. Set at initialization and immutable.
+    pub update_metadata_rate: PodBool,
+    /// True once the mint has been retired, after which the supply can't be
+    /// rebased anymore and conversions use the frozen ratio
+    pub is_retired: PodBool,
+    /// Total supply when the mint was retired, which with `retired_shares`
+    /// gives the frozen ratio
+    pub retired_supply: PodU64,
+    /// Total shares when the mint was retired
+    pub retired_shares: PodU64,
+    /// Key that must sign alongside the supply authority on every
+    /// instruction changing the supply, or none for single control. Set at
+    /// initialization and immutable.
+    pub co_signer: OptionalNonZeroPubkey,
+    /// `RebaseMode` of the mint, as a byte. Set at initialization and
+    /// immutable.
+    pub rebase_mode: u8,
+    /// In `RebaseMode::Lazy`, tokens an account caught up at index
+    /// `REBASE_INDEX_SCALE` is now worth per `REBASE_INDEX_SCALE` tokens,
+    /// scaled by every rebase. Zero in `RebaseMode::Shares`.
+    pub rebase_index: PodU128,
+    /// If true, every rebase also writes the new total supply, plus the
+    /// exempt supply, into the base mint's `supply`, so that readers of the
+    /// base mint see the rebased supply. Set at initialization and immutable.
+    ///
+    /// Mints and burns move the base supply and the totals by the same
+    /// amount, so they stay in step between rebases. Retiring the last shares
+    /// also retires the rounding dust from the total supply, which leaves
+    /// the base supply that much higher until the next rebase.
+    ///
+    /// If false, the base supply counts tokens at their value when they were
+    /// minted. Burns take it no lower than the exempt supply, since holders
+    /// may burn more than was minted after a rebase up.
+    pub sync_base_supply: PodBool,
+    /// Sum of every increase of the total supply by a rebase, fee included,
+    /// over the lifetime of the mint. Mints, burns and syncs from the base
+    /// mint don't change the ratio, and aren't counted.
+    ///
+    /// Saturates at `u128::MAX` rather than failing a rebase, which would take
+    /// more than 2^64 rebases.
+    pub cumulative_supply_increase: PodU128,
+    /// Sum of every decrease of the total supply by a rebase, including the
+    /// supply written off by a sunset, over the lifetime of the mint.
+    /// Saturates like `cumulative_supply_increase`.
+    pub cumulative_supply_decrease: PodU128,
+    /// Shares per token including the virtual offset, the inverse of
+    /// `cached_ratio`, with as many fractional bits and kept alongside it.
+    /// Zero if there is no supply or the ratio doesn't fit.
+    pub cached_inverse_ratio: PodU128,
+}
+
+/// How the balances of a rebasing mint's token accounts follow its rebases,
+/// selected at initialization and immutable
+#[repr(u8)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, PartialEq, IntoPrimitive, TryFromPrimitive)]
+pub enum RebaseMode {
+    /// Accounts hold shares of the total supply in a `RebaseAccount`, so
+    /// their balances follow every rebase at once
+    Shares,
+    /// Accounts hold amounts in tokens, along with the `rebase_index` they
+    /// last caught up to in a `LazyRebaseAccount`. The rebases they missed
+    /// are applied the next time they are transferred from or to, minted to,
+    /// burned from, or claimed for with `ClaimRebase`.
+    ///
+    /// Share quantities, exempt accounts, rebase fees, wrapping, sunsetting
+    /// and metadata rate updates are unavailable.
+    Lazy,
+}
+
+/// Rebase index of a lazy mint that has never been rebased.
+///
+/// With 27 decimals, rounding the index up on every rebase leaves it within
+/// a fraction of a token of the exact ratio over any `u64` supply, even
+/// after millions of rebases, while still leaving room for the index to grow
+/// a hundred billionfold in a `u128`.
+pub const REBASE_INDEX_SCALE: u128 = 1_000_000_000_000_000_000_000_000_000;
+
+/// Largest `RebaseMintConfig::rebase_fee_bps`, taking the whole increase
+pub const MAX_REBASE_FEE_BPS: u16 = 10_000;
+
+/// Cap on the total shares of mints that don't set one, leaving a factor of
+/// two of headroom below `u64::MAX` for sums of shares, and keeping totals
+/// representable as an `i64`
+pub const DEFAULT_MAX_TOTAL_SHARES: u64 = 1 << 63;
+
+/// Fractional bits of `RebaseMintConfig::cached_ratio` and
+/// `RebaseMintConfig::cached_inverse_ratio`.
+///
+/// A power of two rather than a decimal scale such as 1e12, so that scaling a
+/// product back down is a shift, and a full 64 bits so that the truncated
+/// ratio is off by less than one token over any `u64` number of shares, and
+/// the inverse by less than one share over any `u64` amount.
+pub const CACHED_RATIO_FRACTIONAL_BITS: u32 = 64;
+
+/// `numerator / denominator` with `CACHED_RATIO_FRACTIONAL_BITS` fractional
+/// bits, rounded down, or zero if the denominator is zero or the scaled
+/// numerator would overflow
+fn fixed_point_ratio(numerator: u128, denominator: u128) -> u128 {
+    if denominator == 0 || numerator.leading_zeros() < CACHED_RATIO_FRACTIONAL_BITS {
+        0
+    } else {
+        (numerator << CACHED_RATIO_FRACTIONAL_BITS) / denominator
+    }
+}
+
+/// Scale of `RebaseMintConfig::shares_per_token_scaled`
+pub const SHARES_PER_TOKEN_SCALE: u128 = 1_000_000_000_000_000_000;
+
+/// Scale of `RebaseMintConfig::tokens_per_share_scaled`
+pub const TOKENS_PER_SHARE_SCALE: u128 = 1_000_000_000_000_000_000;
+
+/// Key of the token-metadata field holding the scaled exchange rate, for
+/// mints with `update_metadata_rate` set
+pub const REBASE_RATE_METADATA_KEY: &str = "rebaseRate";
+
+impl RebaseMintConfig {
+    /// Get the rebase mode of the mint
+    pub fn rebase_mode(&self) -> Result<RebaseMode, ProgramError> {
+        RebaseMode::try_from(self.rebase_mode).map_err(|_| TokenError::InvalidState.into())
+    }
+
+    /// Check if the mint applies rebases lazily
+    pub fn is_lazy(&self) -> bool {
+        self.rebase_mode == u8::from(RebaseMode::Lazy)
+    }
+
+    /// Fail unless the mint is in `rebase_mode`
+    pub fn check_rebase_mode(&self, rebase_mode: RebaseMode) -> ProgramResult {
+        if self.rebase_mode()? == rebase_mode {
+            Ok(())
+        } else {
+            Err(TokenError::RebaseModeMismatch.into())
+        }
+    }
+
+    /// Scale the amount of a lazy account, which last caught up at
+    /// `last_seen_rebase_index`, to the current `rebase_index`, rounding down.
+    ///
+    /// Accounts that never caught up hold nothing yet, so their amount is
+    /// kept as is.
+    pub fn lazy_amount(&self, amount: u64, last_seen_rebase_index: u128) -> Option<u64> {
+        let rebase_index = u128::from(self.rebase_index);
+        if last_seen_rebase_index == 0 || last_seen_rebase_index == rebase_index {
+            return Some(amount);
+        }
+        let amount = mul_div_floor(amount, rebase_index, last_seen_rebase_index)?;
+        u64::try_from(amount).ok()
+    }
+
+    /// Rebase index after rebasing the supply of a lazy mint to
+    /// `new_supply`, or `None` if it would overflow or reach zero.
+    ///
+    /// The index rounds up, while the amounts scaled by it round down, so
+    /// that the amounts of every account add up to no more than the new
+    /// supply, and an account holding all of it is scaled to exactly the new
+    /// supply.
+    pub fn lazy_rebase_index(&self, new_supply: u64) -> Option<u128> {
+        let rebase_index = u128::from(self.rebase_index);
+        let total_supply = u64::from(self.total_supply) as u128;
+        if total_supply == 0 {
+            return None;
+        }
+        let new_supply = new_supply as u128;
+        // both factors of the remainder's product are below 2^64
+        let remainder = (rebase_index % total_supply) * new_supply;
+        (rebase_index / total_supply)
+            .checked_mul(new_supply)?
+            .checked_add((remainder + total_supply - 1) / total_supply)
+            .filter(|index| *index != 0)
+    }
+
+    /// Add tokens minted to a lazy account to the total supply
+    pub fn add_lazy_supply(&mut self, amount: u64) -> ProgramResult {
+        let total_supply = u64::from(self.total_supply)
+            .checked_add(amount)
+            .ok_or(TokenError::Overflow)?;
+        self.total_supply = total_supply.into();
+        Ok(())
+    }
+
+    /// Remove tokens burned from a lazy account from the total supply
+    pub fn remove_lazy_supply(&mut self, amount: u64) -> ProgramResult {
+        let total_supply = u64::from(self.total_supply)
+            .checked_sub(amount)
+            .ok_or(TokenError::Overflow)?;
+        self.total_supply = total_supply.into();
+        Ok(())
+    }
+
+    /// Check if the supply may be rebased at the given time
+    pub fn is_rebase_allowed_at(&self, unix_timestamp: i64) -> bool {
+        let first_rebase_after_timestamp = i64::from(self.first_rebase_after_timestamp);
+        first_rebase_after_timestamp == 0 || unix_timestamp >= first_rebase_after_timestamp
+    }
+
+    /// Total shares used in conversions, including the virtual offset, and
+    /// frozen once the mint is retired
+    fn virtual_total_shares(&self) -> u128 {
+        let total_shares = if bool::from(self.is_retired) {
+            self.retired_shares
+        } else {
+            self.total_shares
+        };
+        u64::from(total_shares) as u128 + u64::from(self.virtual_shares_offset) as u128
+    }
+
+    /// Total supply used in conversions, including the virtual offset, and
+    /// frozen once the mint is retired
+    fn virtual_total_supply(&self) -> u128 {
+        let total_supply = if bool::from(self.is_retired) {
+            self.retired_supply
+        } else {
+            self.total_supply
+        };
+        u64::from(total_supply) as u128 + u64::from(self.virtual_shares_offset) as u128
+    }
+
+    /// Recompute `cached_ratio` and `cached_inverse_ratio` from the current
+    /// totals
+    pub fn refresh_cached_ratio(&mut self) {
+        let total_shares = self.virtual_total_shares();
+        let total_supply = self.virtual_total_supply();
+        self.cached_ratio = fixed_point_ratio(total_supply, total_shares).into();
+        self.cached_inverse_ratio = fixed_point_ratio(total_shares, total_supply).into();
+    }
+
+    /// Convert tokens to shares with `cached_inverse_ratio`, rounding down,
+    /// or `None` if the cache can't give the exact result, like
+    /// `cached_shares_to_amount`
+    fn cached_amount_to_shares(
+        &self,
+        amount: u64,
+        total_supply: u128,
+        total_shares: u128,
+    ) -> Option<u64> {
+        let ratio = u128::from(self.cached_inverse_ratio);
+        if ratio == 0 {
+            return None;
+        }
+        let estimate = (amount as u128).checked_mul(ratio)? >> CACHED_RATIO_FRACTIONAL_BITS;
+        // the exact quotient `q` is the one with
+        // `q * total_supply <= amount * total_shares < (q + 1) * total_supply`
+        let product = (amount as u128).checked_mul(total_shares)?;
+        let remainder = product.checked_sub(estimate.checked_mul(total_supply)?)?;
+        let shares = if remainder < total_supply {
+            estimate
+        } else if remainder - total_supply < total_supply {
+            estimate + 1
+        } else {
+            return None;
+        };
+        u64::try_from(shares).ok()
+    }
+
+    /// Convert shares to tokens with `cached_ratio`, rounding down, or `None`
+    /// if the cache can't give the exact result.
+    ///
+    /// While the cache is current, the truncated ratio puts the estimate at
+    /// most one token below the exact quotient, so after checking it against
+    /// the totals with two multiplications, it only needs a single correction.
+    fn cached_shares_to_amount(
+        &self,
+        shares: u64,
+        total_supply: u128,
+        total_shares: u128,
+    ) -> Option<u64> {
+        let ratio = u128::from(self.cached_ratio);
+        if ratio == 0 {
+            return None;
+        }
+        let estimate = (shares as u128).checked_mul(ratio)? >> CACHED_RATIO_FRACTIONAL_BITS;
+        // the exact quotient `q` is the one with
+        // `q * total_shares <= shares * total_supply < (q + 1) * total_shares`
+        let product = (shares as u128).checked_mul(total_supply)?;
+        let remainder = product.checked_sub(estimate.checked_mul(total_shares)?)?;
+        let amount = if remainder < total_shares {
+            estimate
+        } else if remainder - total_shares < total_shares {
+            estimate + 1
+        } else {
+            return None;
+        };
+        u64::try_from(amount).ok()
+    }
+
+    /// Convert an amount of shares to the amount of tokens they are worth,
+    /// rounding down.
+    ///
+    /// Shares and tokens are 1:1 until shares have been issued, and shares
+    /// are worth nothing once the mint has been sunset.
+    pub fn shares_to_amount(&self, shares: u64) -> Option<u64> {
+        if bool::from(self.is_sunset) {
+            return Some(0);
+        }
+        let total_shares = self.virtual_total_shares();
+        if total_shares == 0 {
+            return Some(shares);
+        }
+        let total_supply = self.virtual_total_supply();
+        if let Some(amount) = self.cached_shares_to_amount(shares, total_supply, total_shares) {
+            return Some(amount);
+        }
+        let amount = (shares as u128)
+            .checked_mul(total_supply)?
+            .checked_div(total_shares)?;
+        u64::try_from(amount).ok()
+    }
+
+    /// Convert an amount of shares to the amount of tokens needed to back
+    /// them, rounding up
+    pub fn shares_to_amount_rounding_up(&self, shares: u64) -> Option<u64> {
+        if bool::from(self.is_sunset) {
+            return Some(0);
+        }
+        let total_shares = self.virtual_total_shares();
+        if total_shares == 0 {
+            return Some(shares);
+        }
+        let amount = (shares as u128)
+            .checked_mul(self.virtual_total_supply())?
+            .checked_add(total_shares.checked_sub(1)?)?
+            .checked_div(total_shares)?;
+        u64::try_from(amount).ok()
+    }
+
+    /// Convert an amount of tokens to the number of shares that represent it,
+    /// rounding down.
+    ///
+    /// Fails if shares have been issued but the supply is zero, since no
+    /// number of shares is worth a nonzero amount.
+    pub fn amount_to_shares(&self, amount: u64) -> Option<u64> {
+        let total_shares = self.virtual_total_shares();
+        if total_shares == 0 {
+            return Some(amount);
+        }
+        let total_supply = self.virtual_total_supply();
+        if let Some(shares) = self.cached_amount_to_shares(amount, total_supply, total_shares) {
+            return Some(shares);
+        }
+        let shares = (amount as u128)
+            .checked_mul(total_shares)?
+            .checked_div(total_supply)?;
+        u64::try_from(shares).ok()
+    }
+
+    /// Convert an amount of tokens to the number of shares needed to cover it,
+    /// rounding up
+    pub fn amount_to_shares_rounding_up(&self, amount: u64) -> Option<u64> {
+        let total_shares = self.virtual_total_shares();
+        if total_shares == 0 {
+            return Some(amount);
+        }
+        let total_supply = self.virtual_total_supply();
+        let shares = (amount as u128)
+            .checked_mul(total_shares)?
+            .checked_add(total_supply.checked_sub(1)?)?
+            .checked_div(total_supply)?;
+        u64::try_from(shares).ok()
+    }
+
+    /// Convert an amount of tokens that moves value to the shares that move
+    /// with it, rounding down.
+    ///
+    /// Fails if a nonzero amount is worth less than one share, which would
+    /// otherwise move nothing. Read-only conversions still report zero.
+    pub fn amount_to_nonzero_shares(&self, amount: u64) -> Result<u64, ProgramError> {
+        let shares = self.amount_to_shares(amount).ok_or(TokenError::Overflow)?;
+        if amount != 0 && shares == 0 {
+            msg!("{} tokens are worth less than one share", amount);
+            Err(TokenError::AmountTooSmallForShares.into())
+        } else {
+            Ok(shares)
+        }
+    }
+
+    /// Get the number of shares per token, multiplied by
+    /// `SHARES_PER_TOKEN_SCALE` and rounded down, or `None` if shares have
+    /// been issued but the supply is zero
+    pub fn shares_per_token_scaled(&self) -> Option<u128> {
+        let total_shares = self.virtual_total_shares();
+        if total_shares == 0 {
+            return Some(SHARES_PER_TOKEN_SCALE);
+        }
+        total_shares
+            .checked_mul(SHARES_PER_TOKEN_SCALE)?
+            .checked_div(self.virtual_total_supply())
+    }
+
+    /// Get the number of tokens per share, multiplied by
+    /// `TOKENS_PER_SHARE_SCALE` and rounded down, like `shares_to_amount`
+    pub fn tokens_per_share_scaled(&self) -> u128 {
+        if bool::from(self.is_sunset) {
+            return 0;
+        }
+        let total_shares = self.virtual_total_shares();
+        if total_shares == 0 {
+            return TOKENS_PER_SHARE_SCALE;
+        }
+        // the virtual supply is below 2^65, so the product fits
+        self.virtual_total_supply() * TOKENS_PER_SHARE_SCALE / total_shares
+    }
+
+    /// Convert an amount of shares to the UI representation of the tokens they
+    /// are worth, using the given decimals field. Excess zeroes or unneeded
+    /// decimal point are trimmed.
+    ///
+    /// Lazy mints have no shares, so the amount is taken as held since
+    /// `REBASE_INDEX_SCALE` and scaled to the current `rebase_index`, rounding
+    /// down like `lazy_amount`.
+    pub fn shares_to_ui_amount(&self, shares: u64, decimals: u8) -> Option<String> {
+        let amount = if self.is_lazy() {
+            self.lazy_amount(shares, REBASE_INDEX_SCALE)?
+        } else {
+            self.shares_to_amount(shares)?
+        };
+        Some(crate::amount_to_ui_amount_string_trimmed(amount, decimals))
+    }
+
+    /// Try to convert a UI representation of a token amount to the number of
+    /// shares that represent it, using the given decimals field.
+    ///
+    /// Lazy mints scale the amount back to `REBASE_INDEX_SCALE`, the inverse
+    /// of `shares_to_ui_amount`, rounding down.
+    pub fn try_ui_amount_into_shares(
+        &self,
+        ui_amount: &str,
+        decimals: u8,
+    ) -> Result<u64, ProgramError> {
+        let amount = crate::try_ui_amount_into_amount(ui_amount.to_string(), decimals)?;
+        if self.is_lazy() {
+            return mul_div_floor(amount, REBASE_INDEX_SCALE, u128::from(self.rebase_index))
+                .and_then(|amount| u64::try_from(amount).ok())
+                .ok_or(ProgramError::InvalidArgument);
+        }
+        self.amount_to_shares(amount)
+            .ok_or(ProgramError::InvalidArgument)
+    }
+
+    /// Get the most shares that may exist at once, applying the default if
+    /// the mint doesn't set a cap
+    pub fn max_total_shares(&self) -> u64 {
+        match u64::from(self.max_total_shares) {
+            0 => DEFAULT_MAX_TOTAL_SHARES,
+            max_total_shares => max_total_shares,
+        }
+    }
+
+    /// Check that `total_shares` shares fit under the cap
+    pub fn check_total_shares(&self, total_shares: u64) -> ProgramResult {
+        if total_shares > self.max_total_shares() {
+            Err(TokenError::RebaseSharesCapExceeded.into())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Add newly issued shares, backed by `amount` tokens, to the totals.
+    ///
+    /// Fails if the total shares would exceed the cap, or if the mint has
+    /// been sunset.
+    pub fn issue_shares(&mut self, shares: u64, amount: u64) -> ProgramResult {
+        self.check_not_sunset()?;
+        let total_shares = u64::from(self.total_shares)
+            .checked_add(shares)
+            .ok_or(TokenError::RebaseSharesOverflow)?;
+        self.check_total_shares(total_shares)?;
+        let total_supply = u64::from(self.total_supply)
+            .checked_add(amount)
+            .ok_or(TokenError::Overflow)?;
+        let outstanding_shares = u64::from(self.outstanding_shares)
+            .checked_add(shares)
+            .ok_or(TokenError::RebaseSharesOverflow)?;
+        self.total_shares = total_shares.into();
+        self.total_supply = total_supply.into();
+        self.outstanding_shares = outstanding_shares.into();
+        Ok(())
+    }
+
+    /// Remove retired shares, backed by `amount` tokens, from the totals.
+    ///
+    /// Retiring the last shares also retires the rounding dust left in the
+    /// total supply, which no shares could claim anymore.
+    pub fn retire_shares(&mut self, shares: u64, amount: u64) -> ProgramResult {
+        let total_shares = u64::from(self.total_shares)
+            .checked_sub(shares)
+            .ok_or(TokenError::Overflow)?;
+        let total_supply = u64::from(self.total_supply)
+            .checked_sub(amount)
+            .ok_or(TokenError::Overflow)?;
+        let total_supply = if total_shares == 0 { 0 } else { total_supply };
+        let outstanding_shares = u64::from(self.outstanding_shares)
+            .checked_sub(shares)
+            .ok_or(TokenError::Overflow)?;
+        self.total_shares = total_shares.into();
+        self.total_supply = total_supply.into();
+        self.outstanding_shares = outstanding_shares.into();
+        Ok(())
+    }
+
+    /// Check the totals against each other and against the base mint's
+    /// supply, logging the first one that's off
+    pub fn check_consistency(&self, mint_supply: u64) -> ProgramResult {
+        let total_supply = u64::from(self.total_supply);
+        let total_shares = u64::from(self.total_shares);
+        let outstanding_shares = u64::from(self.outstanding_shares);
+        let exempt_supply = u64::from(self.exempt_supply);
+        if self.is_lazy() {
+            if total_shares != 0 || outstanding_shares != 0 {
+                msg!(
+                    "Lazy mint has {} total shares and {} outstanding shares",
+                    total_shares,
+                    outstanding_shares
+                );
+                return Err(TokenError::RebaseInconsistent.into());
+            }
+            if self.rebase_index == 0.into() {
+                msg!("Lazy mint has a zero rebase index");
+                return Err(TokenError::RebaseInconsistent.into());
+            }
+        } else if (total_supply == 0) != (total_shares == 0) {
+            msg!(
+                "Total supply {} and total shares {} must both be zero or nonzero",
+                total_supply,
+                total_shares
+            );
+            return Err(TokenError::RebaseInconsistent.into());
+        }
+        if outstanding_shares > total_shares {
+            msg!(
+                "Outstanding shares {} exceed total shares {}",
+                outstanding_shares,
+                total_shares
+            );
+            return Err(TokenError::RebaseInconsistent.into());
+        }
+        if exempt_supply > mint_supply {
+            msg!(
+                "Exempt supply {} exceeds mint supply {}",
+                exempt_supply,
+                mint_supply
+            );
+            return Err(TokenError::RebaseInconsistent.into());
+        }
+        // until the first rebase, the mint's supply only moves with mints and
+        // burns, which move the extension's supply by the same amount, so it can
+        // only fall short of it by an initial supply that was never minted
+        if !bool::from(self.has_rebased)
+            && total_supply
+                .checked_add(exempt_supply)
+                .map_or(false, |backed_supply| backed_supply < mint_supply)
+        {
+            msg!(
+                "Total supply {} and exempt supply {} fall short of mint supply {}",
+                total_supply,
+                exempt_supply,
+                mint_supply
+            );
+            return Err(TokenError::RebaseInconsistent.into());
+        }
+        // a synced base supply may only exceed the totals by rounding dust
+        if bool::from(self.sync_base_supply)
+            && total_supply
+                .checked_add(exempt_supply)
+                .map_or(true, |backed_supply| backed_supply > mint_supply)
+        {
+            msg!(
+                "Total supply {} and exempt supply {} exceed synced mint supply {}",
+                total_supply,
+                exempt_supply,
+                mint_supply
+            );
+            return Err(TokenError::RebaseInconsistent.into());
+        }
+        Ok(())
+    }
+
+    /// Add tokens credited to an exempt account to the exempt supply
+    pub fn add_exempt_supply(&mut self, amount: u64) -> ProgramResult {
+        let exempt_supply = u64::from(self.exempt_supply)
+            .checked_add(amount)
+            .ok_or(TokenError::Overflow)?;
+        self.exempt_supply = exempt_supply.into();
+        Ok(())
+    }
+
+    /// Remove tokens debited from an exempt account from the exempt supply
+    pub fn remove_exempt_supply(&mut self, amount: u64) -> ProgramResult {
+        let exempt_supply = u64::from(self.exempt_supply)
+            .checked_sub(amount)
+            .ok_or(TokenError::Overflow)?;
+        self.exempt_supply = exempt_supply.into();
+        Ok(())
+    }
+
+    /// Set the total supply to the base mint's supply, less the exempt
+    /// supply, scaling the total shares to keep the ratio, or dividing the
+    /// supply 1:1 if there are no shares yet.
+    ///
+    /// Only allowed before the first rebase, so that it can't be used to undo
+    /// one, and fails if the total shares would fall below the outstanding
+    /// shares or exceed the cap.
+    pub fn sync_supply(&mut self, mint_supply: u64) -> Result<SupplySynced, ProgramError> {
+        self.check_not_sunset()?;
+        self.check_not_retired()?;
+        if bool::from(self.has_rebased) {
+            return Err(TokenError::RebaseSupplySyncNotAllowed.into());
+        }
+        let mint_supply = mint_supply
+            .checked_sub(u64::from(self.exempt_supply))
+            .ok_or(TokenError::Overflow)?;
+        let previous_supply = u64::from(self.total_supply);
+        let previous_shares = u64::from(self.total_shares);
+        let total_shares = if previous_shares == 0 {
+            mint_supply
+        } else {
+            (previous_shares as u128)
+                .checked_mul(mint_supply as u128)
+                .and_then(|shares| shares.checked_div(previous_supply as u128))
+                .and_then(|shares| u64::try_from(shares).ok())
+                .ok_or(TokenError::Overflow)?
+        };
+        if total_shares < u64::from(self.outstanding_shares) {
+            return Err(TokenError::RebaseBelowOutstandingShares.into());
+        }
+        self.check_total_shares(total_shares)?;
+        self.total_supply = mint_supply.into();
+        self.total_shares = total_shares.into();
+        Ok(SupplySynced {
+            previous_supply: previous_supply.into(),
+            previous_shares: previous_shares.into(),
+            total_supply: mint_supply.into(),
+            total_shares: total_shares.into(),
+        })
+    }
+
+    /// Check if the mint can be closed, which requires that no shares remain,
+    /// even if the base supply is already zero
+    pub fn closable(&self) -> ProgramResult {
+        if self.total_shares == 0.into() && self.outstanding_shares == 0.into() {
+            Ok(())
+        } else {
+            Err(TokenError::RebaseMintHasShares.into())
+        }
+    }
+
+    /// Check that the shares held by token accounts are all part of the total
+    /// shares, that the total shares fit under the cap, and that a retired
+    /// mint has no authority left and a frozen ratio with a supply behind its
+    /// shares
+    pub fn validate(&self) -> ProgramResult {
+        if u64::from(self.total_shares) < u64::from(self.outstanding_shares) {
+            return Err(TokenError::RebaseBelowOutstandingShares.into());
+        }
+        if bool::from(self.is_retired)
+            && (bool::from(self.is_sunset)
+                || Option::<Pubkey>::from(self.supply_authority).is_some()
+                || Option::<Pubkey>::from(self.pending_supply_authority).is_some()
+                || Option::<Pubkey>::from(self.rebase_delegate).is_some()
+                || (self.retired_supply == 0.into() && self.retired_shares != 0.into()))
+        {
+            return Err(TokenError::InvalidState.into());
+        }
+        self.check_total_shares(self.total_shares.into())
+    }
+
+    /// Get the key allowed to sign a rebase as `signer`, which is either the
+    /// supply authority or the rebase delegate. The delegate can only act
+    /// while a supply authority exists.
+    ///
+    /// Without a supply authority, the mint authority takes over if the mint
+    /// opted into the fallback, otherwise the supply is immutable.
+    pub fn rebase_authority_for(
+        &self,
+        signer: &Pubkey,
+        mint_authority: Option<Pubkey>,
+    ) -> Result<Pubkey, ProgramError> {
+        let supply_authority = match Option::<Pubkey>::from(self.supply_authority) {
+            Some(supply_authority) => supply_authority,
+            None if bool::from(self.use_mint_authority_fallback) => {
+                return mint_authority.ok_or_else(|| TokenError::RebaseSupplyImmutable.into());
+            }
+            None => return Err(TokenError::RebaseSupplyImmutable.into()),
+        };
+        match Option::<Pubkey>::from(self.rebase_delegate) {
+            Some(rebase_delegate) if rebase_delegate == *signer => Ok(rebase_delegate),
+            _ => Ok(supply_authority),
+        }
+    }
+
+    /// Fail if the mint has been sunset
+    pub fn check_not_sunset(&self) -> ProgramResult {
+        if bool::from(self.is_sunset) {
+            Err(TokenError::RebaseMintSunset.into())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Fail if the mint has been retired
+    pub fn check_not_retired(&self) -> ProgramResult {
+        if bool::from(self.is_retired) {
+            Err(TokenError::RebaseMintRetired.into())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Retire the mint: freeze the current ratio for every later conversion
+    /// and drop every authority, so that the supply can never be rebased
+    /// again. Tokens can still be minted, burned and transferred at the
+    /// frozen ratio.
+    pub fn retire(&mut self) -> ProgramResult {
+        self.check_not_sunset()?;
+        self.check_not_retired()?;
+        self.retired_supply = self.total_supply;
+        self.retired_shares = self.total_shares;
+        self.supply_authority = OptionalNonZeroPubkey::default();
+        self.pending_supply_authority = OptionalNonZeroPubkey::default();
+        self.rebase_delegate = OptionalNonZeroPubkey::default();
+        self.cancel_authority_change();
+        self.is_retired = true.into();
+        self.refresh_cached_ratio();
+        Ok(())
+    }
+
+    /// Sunset the mint: write off the whole supply and every share, including
+    /// those held by token accounts, and drop every authority, so that the
+    /// config can never change again.
+    ///
+    /// Only allowed if the mint opted in at initialization, and while no
+    /// tokens are held outside of the shares by exempt accounts, since those
+    /// can't be written off.
+    pub fn sunset(&mut self) -> ProgramResult {
+        self.check_not_sunset()?;
+        self.check_not_retired()?;
+        if !bool::from(self.allow_sunset) {
+            return Err(TokenError::RebaseSunsetNotAllowed.into());
+        }
+        if u64::from(self.exempt_supply) != 0 {
+            return Err(TokenError::RebaseSunsetNotAllowed.into());
+        }
+        self.record_supply_change(self.total_supply.into(), 0);
+        self.total_supply = 0.into();
+        self.total_shares = 0.into();
+        self.outstanding_shares = 0.into();
+        self.supply_authority = OptionalNonZeroPubkey::default();
+        self.pending_supply_authority = OptionalNonZeroPubkey::default();
+        self.rebase_delegate = OptionalNonZeroPubkey::default();
+        self.cancel_authority_change();
+        self.has_rebased = true.into();
+        self.is_sunset = true.into();
+        self.refresh_cached_ratio();
+        Ok(())
+    }
+
+    /// Shares counted in `total_shares` that no token account holds: the
+    /// rounding left over when the totals are rescaled from the mint's
+    /// supply, and any initial supply that was never minted
+    pub fn rounding_dust_shares(&self) -> u64 {
+        u64::from(self.total_shares).saturating_sub(u64::from(self.outstanding_shares))
+    }
+
+    /// Count the rounding dust as outstanding, returning its shares, which the
+    /// caller credits to a token account. The totals don't move, since they
+    /// already include the dust.
+    pub fn sweep_rounding_dust(&mut self) -> Result<u64, ProgramError> {
+        self.check_not_sunset()?;
+        let shares = self.rounding_dust_shares();
+        let outstanding_shares = u64::from(self.outstanding_shares)
+            .checked_add(shares)
+            .ok_or(TokenError::Overflow)?;
+        self.outstanding_shares = outstanding_shares.into();
+        Ok(shares)
+    }
+
+    /// Check that the supply authority may be set to `new_authority` without a
+    /// proposal
+    pub fn check_direct_authority_change(
+        &self,
+        new_authority: &OptionalNonZeroPubkey,
+    ) -> ProgramResult {
+        if bool::from(self.two_step_authority_transfer)
+            && Option::<Pubkey>::from(*new_authority).is_some()
+        {
+            Err(TokenError::RebaseAuthorityRequiresAcceptance.into())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Change the supply authority at the given time, clearing any pending
+    /// proposal.
+    ///
+    /// If the mint has an authority change delay, handing over to a new key is
+    /// only scheduled, and takes effect through `finalize_authority_change`
+    /// once the delay elapses. Revoking always applies immediately. Returns
+    /// true if the change was scheduled.
+    pub fn change_supply_authority(
+        &mut self,
+        new_authority: OptionalNonZeroPubkey,
+        unix_timestamp: i64,
+    ) -> bool {
+        self.pending_supply_authority = OptionalNonZeroPubkey::default();
+        if u32::from(self.authority_change_delay_seconds) != 0
+            && Option::<Pubkey>::from(new_authority).is_some()
+        {
+            self.scheduled_supply_authority = new_authority;
+            self.authority_change_requested_timestamp = unix_timestamp.into();
+            true
+        } else {
+            self.supply_authority = new_authority;
+            self.cancel_authority_change();
+            false
+        }
+    }
+
+    /// Apply the scheduled change of supply authority, if its delay has
+    /// elapsed at the given time
+    pub fn finalize_authority_change(&mut self, unix_timestamp: i64) -> ProgramResult {
+        let requested_timestamp = i64::from(self.authority_change_requested_timestamp);
+        if requested_timestamp == 0 {
+            return Err(TokenError::NoPendingRebaseAuthority.into());
+        }
+        let ready_timestamp = requested_timestamp
+            .checked_add(u32::from(self.authority_change_delay_seconds) as i64)
+            .ok_or(TokenError::Overflow)?;
+        if unix_timestamp < ready_timestamp {
+            return Err(TokenError::RebaseAuthorityChangeNotReady.into());
+        }
+        self.supply_authority = self.scheduled_supply_authority;
+        self.cancel_authority_change();
+        Ok(())
+    }
+
+    /// Drop the scheduled change of supply authority, if any
+    pub fn cancel_authority_change(&mut self) {
+        self.scheduled_supply_authority = OptionalNonZeroPubkey::default();
+        self.authority_change_requested_timestamp = 0.into();
+    }
+
+    /// Compute the outcome of rebasing the supply to `new_supply`, without
+    /// modifying the config
+    pub fn preview_rebase(&self, new_supply: u64) -> Result<RebasePreview, ProgramError> {
+        self.check_not_sunset()?;
+        self.check_not_retired()?;
+        self.validate()?;
+        if !bool::from(self.allow_supply_decrease) && new_supply < u64::from(self.total_supply) {
+            return Err(TokenError::SupplyDecreaseForbidden.into());
+        }
+        let total_shares = u64::from(self.total_shares);
+        // Shares held by token accounts would no longer be redeemable for
+        // anything
+        if new_supply == 0 && u64::from(self.outstanding_shares) != 0 {
+            return Err(TokenError::RebaseBelowOutstandingShares.into());
+        }
+        // Outstanding shares must always be worth something, otherwise the
+        // conversion back to shares is undefined
+        if new_supply == 0 && total_shares != 0 {
+            return Err(TokenError::RebaseSupplyZero.into());
+        }
+        // a lazy mint scales its index by the rebase, which needs a supply to
+        // scale from and to
+        if self.is_lazy() && self.lazy_rebase_index(new_supply).is_none() {
+            return Err(if new_supply == 0 || self.total_supply == 0.into() {
+                TokenError::RebaseSupplyZero.into()
+            } else {
+                TokenError::RebaseTooLarge.into()
+            });
+        }
+        // the base mint's supply is a u64 too, so it must hold the exempt
+        // supply on top of the new supply
+        if bool::from(self.sync_base_supply) && self.backed_supply(new_supply).is_none() {
+            return Err(TokenError::RebaseTooLarge.into());
+        }
+        let rounding_error = new_supply.checked_rem(total_shares).unwrap_or(0);
+        Ok(RebasePreview {
+            new_supply: new_supply.into(),
+            total_shares: total_shares.into(),
+            rounding_error: rounding_error.into(),
+        })
+    }
+
+    /// Check that the reserve, holding `reserve_amount` tokens, backs a new
+    /// supply of `new_supply` along with the exempt supply
+    pub fn check_reserve(&self, new_supply: u64, reserve_amount: u64) -> ProgramResult {
+        let backed_supply = self
+            .backed_supply(new_supply)
+            .ok_or(TokenError::RebaseTooLarge)?;
+        if backed_supply > reserve_amount {
+            Err(TokenError::RebaseExceedsReserve.into())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Tokens that the base mint's supply must account for with a total
+    /// supply of `total_supply`: that supply plus the exempt supply, or
+    /// `None` on overflow
+    pub fn backed_supply(&self, total_supply: u64) -> Option<u64> {
+        total_supply.checked_add(u64::from(self.exempt_supply))
+    }
+
+    /// For mints with `sync_base_supply`, check that the base mint's supply
+    /// accounts for the current total and exempt supplies, so that writing
+    /// them into it can't lose any minted token.
+    ///
+    /// The base supply may only exceed them by rounding dust retired with the
+    /// last shares.
+    pub fn check_base_supply(&self, mint_supply: u64) -> ProgramResult {
+        if !bool::from(self.sync_base_supply) {
+            return Ok(());
+        }
+        let backed_supply = self
+            .backed_supply(self.total_supply.into())
+            .ok_or(TokenError::Overflow)?;
+        if backed_supply > mint_supply {
+            msg!(
+                "Total supply {} and exempt supply {} exceed mint supply {}",
+                u64::from(self.total_supply),
+                u64::from(self.exempt_supply),
+                mint_supply
+            );
+            return Err(TokenError::RebaseBaseSupplyMismatch.into());
+        }
+        Ok(())
+    }
+
+    /// Apply a rebase previewed by `preview_rebase`, exactly as the program
+    /// stores it
+    pub fn apply_rebase(&mut self, preview: &RebasePreview) {
+        if self.is_lazy() {
+            if let Some(rebase_index) = self.lazy_rebase_index(preview.new_supply.into()) {
+                self.rebase_index = rebase_index.into();
+            }
+        }
+        self.record_supply_change(self.total_supply.into(), preview.new_supply.into());
+        self.total_supply = preview.new_supply;
+        self.total_shares = preview.total_shares;
+        self.has_rebased = true.into();
+        self.refresh_cached_ratio();
+    }
+
+    /// Set the rebase fee and the account credited with it
+    pub fn set_rebase_fee(
+        &mut self,
+        rebase_fee_bps: u16,
+        fee_destination: OptionalNonZeroPubkey,
+    ) -> ProgramResult {
+        if rebase_fee_bps > MAX_REBASE_FEE_BPS {
+            return Err(TokenError::RebaseFeeTooHigh.into());
+        }
+        self.rebase_fee_bps = rebase_fee_bps.into();
+        self.fee_destination = fee_destination;
+        Ok(())
+    }
+
+    /// Tokens taken as fee by a rebase to `new_supply`: `rebase_fee_bps` of
+    /// the increase, rounded down.
+    ///
+    /// Rebases lowering the supply charge nothing, and neither do mints
+    /// without a fee destination or without shares to credit the fee against.
+    pub fn rebase_fee(&self, new_supply: u64) -> u64 {
+        if Option::<Pubkey>::from(self.fee_destination).is_none()
+            || u64::from(self.total_shares) == 0
+        {
+            return 0;
+        }
+        let increase = new_supply.saturating_sub(self.total_supply.into());
+        let rebase_fee_bps = u16::from(self.rebase_fee_bps).min(MAX_REBASE_FEE_BPS);
+        // the fee can't exceed the increase, so it fits
+        (increase as u128 * rebase_fee_bps as u128 / MAX_REBASE_FEE_BPS as u128) as u64
+    }
+
+    /// Apply a rebase previewed by `preview_rebase`, issuing its fee as new
+    /// shares, which the caller credits to the fee destination.
+    ///
+    /// The increase net of the fee goes to all holders through the ratio,
+    /// then the fee is issued as shares at the new ratio, rounded down in
+    /// favor of the holders, so that the total supply still lands on the
+    /// previewed supply.
+    pub fn apply_rebase_with_fee(
+        &mut self,
+        preview: &RebasePreview,
+    ) -> Result<RebaseFee, ProgramError> {
+        let new_supply = u64::from(preview.new_supply);
+        let amount = self.rebase_fee(new_supply);
+        self.apply_rebase(&RebasePreview {
+            new_supply: (new_supply - amount).into(),
+            ..*preview
+        });
+        if amount == 0 {
+            return Ok(RebaseFee::default());
+        }
+        let shares = self.amount_to_shares(amount).ok_or(TokenError::Overflow)?;
+        self.issue_shares(shares, amount)?;
+        self.refresh_cached_ratio();
+        // the fee is part of the increase, even though it's issued as shares
+        self.record_supply_change(new_supply - amount, new_supply);
+        Ok(RebaseFee { amount, shares })
+    }
+
+    /// Complete a preview from `preview_rebase` with the shares its fee
+    /// issues, returning it with the config as the rebase leaves it
+    pub fn preview_with_fee(
+        &self,
+        preview: &RebasePreview,
+    ) -> Result<(RebasePreview, RebaseMintConfig), ProgramError> {
+        let mut rebased = *self;
+        rebased.apply_rebase_with_fee(preview)?;
+        let rounding_error = u64::from(preview.new_supply)
+            .checked_rem(rebased.total_shares.into())
+            .unwrap_or(0);
+        let preview = RebasePreview {
+            total_shares: rebased.total_shares,
+            rounding_error: rounding_error.into(),
+            ..*preview
+        };
+        Ok((preview, rebased))
+    }
+
+    /// Add a change of the total supply from `old_supply` to `new_supply` to
+    /// the cumulative increase or decrease, saturating so that the statistics
+    /// can never fail a rebase
+    fn record_supply_change(&mut self, old_supply: u64, new_supply: u64) {
+        if let Some(increase) = new_supply.checked_sub(old_supply) {
+            self.cumulative_supply_increase = u128::from(self.cumulative_supply_increase)
+                .saturating_add(increase as u128)
+                .into();
+        } else if let Some(decrease) = old_supply.checked_sub(new_supply) {
+            self.cumulative_supply_decrease = u128::from(self.cumulative_supply_decrease)
+                .saturating_add(decrease as u128)
+                .into();
+        }
+    }
+
+    /// The config with the totals set by the last rebase in `history` at or
+    /// before `unix_timestamp`.
+    ///
+    /// Every rebase in the history predates a sunset or retirement, so the
+    /// copy is neither.
+    fn at(
+        &self,
+        history: &RebaseHistory,
+        unix_timestamp: i64,
+    ) -> Result<RebaseMintConfig, ProgramError> {
+        let entry = history.entry_at(unix_timestamp)?;
+        let mut config = *self;
+        config.total_supply = entry.new_supply;
+        config.total_shares = entry.new_shares;
+        config.is_sunset = false.into();
+        config.is_retired = false.into();
+        config.refresh_cached_ratio();
+        Ok(config)
+    }
+
+    /// Convert an amount of shares to the amount of tokens they were worth at
+    /// `unix_timestamp`, rounding down like `shares_to_amount`.
+    ///
+    /// Uses the totals recorded by the last rebase in `history` at or before
+    /// that time, so mints and burns of shares since that rebase are
+    /// ignored. Fails if the timestamp predates the kept rebases.
+    pub fn shares_to_amount_at(
+        &self,
+        history: &RebaseHistory,
+        shares: u64,
+        unix_timestamp: i64,
+    ) -> Result<u64, ProgramError> {
+        self.at(history, unix_timestamp)?
+            .shares_to_amount(shares)
+            .ok_or_else(|| TokenError::Overflow.into())
+    }
+
+    /// Convert an amount of tokens to the number of shares that represented
+    /// it at `unix_timestamp`, rounding down like `amount_to_shares`, with the
+    /// same caveats as `shares_to_amount_at`
+    pub fn amount_to_shares_at(
+        &self,
+        history: &RebaseHistory,
+        amount: u64,
+        unix_timestamp: i64,
+    ) -> Result<u64, ProgramError> {
+        self.at(history, unix_timestamp)?
+            .amount_to_shares(amount)
+            .ok_or_else(|| TokenError::Overflow.into())
+    }
+}
+impl Extension for RebaseMintConfig {
+    const TYPE: ExtensionType = ExtensionType::RebaseMintConfig;
+}
+
+// Account data isn't aligned, so the extensions are cast from it only as long
+// as every field is made of bytes, and existing accounts only deserialize as
+// long as the sizes don't change
+const _: () = {
+    use std::mem::{align_of, size_of};
+    assert!(size_of::<RebaseMintConfig>() == 401);
+    assert!(align_of::<RebaseMintConfig>() == 1);
+    assert!(size_of::<RebaseAccount>() == 9);
+    assert!(align_of::<RebaseAccount>() == 1);
+    assert!(size_of::<LazyRebaseAccount>() == 16);
+    assert!(align_of::<LazyRebaseAccount>() == 1);
+    assert!(size_of::<RebaseHistory>() == 8 + 65 * REBASE_HISTORY_LEN);
+    assert!(align_of::<RebaseHistory>() == 1);
+};
+
+/// Rebasing mint extension data for token accounts
+///
+/// The account's balance is the number of shares it holds. The base `amount`
+/// is only refreshed to the value of those shares whenever they change, so it
+/// goes stale after a rebase.
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct RebaseAccount {
+    /// Shares of the mint's total supply held by the account
+    pub shares: PodU64,
+    /// If true, the account holds a fixed `amount` instead of shares, and is
+    /// unaffected by rebases
+    pub rebase_exempt: PodBool,
+}
+impl RebaseAccount {
+    /// Check if the account is exempt from rebasing
+    pub fn is_exempt(&self) -> bool {
+        bool::from(self.rebase_exempt)
+    }
+
+    /// Check if the extension is in a closable state
+    pub fn closable(&self) -> ProgramResult {
+        if self.shares == 0.into() {
+            Ok(())
+        } else {
+            Err(TokenError::NonNativeHasBalance.into())
+        }
+    }
+}
+impl Extension for RebaseAccount {
+    const TYPE: ExtensionType = ExtensionType::RebaseAccount;
+}
+
+/// Rebasing mint extension data for token accounts of a mint in
+/// `RebaseMode::Lazy`
+///
+/// The base `amount` is the balance as of `last_seen_rebase_index`, and is
+/// scaled to the mint's current `rebase_index` whenever the account is caught
+/// up.
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct LazyRebaseAccount {
+    /// Rebase index of the mint when the account last caught up, or zero if
+    /// it never has
+    pub last_seen_rebase_index: PodU128,
+}
+impl Extension for LazyRebaseAccount {
+    const TYPE: ExtensionType = ExtensionType::LazyRebaseAccount;
+}
+
+/// Number of rebases kept in `RebaseHistory`, as many as `GetRebaseHistory`
+/// can return at once
+pub const REBASE_HISTORY_LEN: usize = 15;
+
+/// A rebase recorded in `RebaseHistory`
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct RebaseHistoryEntry {
+    /// Unix timestamp of the rebase
+    pub unix_timestamp: PodI64,
+    /// Total supply before the rebase
+    pub old_supply: PodU64,
+    /// Total supply after the rebase
+    pub new_supply: PodU64,
+    /// Total shares after the rebase
+    pub new_shares: PodU64,
+    /// Reason code the rebase was tagged with, or zero if unset
+    pub reason_code: u8,
+    /// Reference the rebase was tagged with, or zeroes if unset
+    pub reference: [u8; 32],
+}
+
+/// Optional extension for rebasing mints that keeps the last
+/// `REBASE_HISTORY_LEN` rebases, so that they can be read from the mint
+/// without going through archived transactions.
+///
+/// Mints opt into the extra rent by allocating space for it and initializing
+/// it with `InitializeHistory`, alongside the `RebaseMintConfig`.
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct RebaseHistory {
+    /// Number of rebases recorded since the history was initialized. The
+    /// next one is written at `count % REBASE_HISTORY_LEN`, over the oldest.
+    pub count: PodU64,
+    /// Ring buffer of the recorded rebases
+    pub entries: [RebaseHistoryEntry; REBASE_HISTORY_LEN],
+}
+impl RebaseHistory {
+    /// Record a rebase, dropping the oldest one if the history is full
+    pub fn record(&mut self, entry: RebaseHistoryEntry) {
+        let count = u64::from(self.count);
+        self.entries[(count % REBASE_HISTORY_LEN as u64) as usize] = entry;
+        self.count = count.saturating_add(1).into();
+    }
+
+    /// Number of kept rebases
+    fn len(&self) -> usize {
+        u64::from(self.count).min(REBASE_HISTORY_LEN as u64) as usize
+    }
+
+    /// Kept rebase at `index`, counting from the oldest
+    fn get(&self, index: usize) -> &RebaseHistoryEntry {
+        let start = if self.len() < REBASE_HISTORY_LEN {
+            0
+        } else {
+            (u64::from(self.count) % REBASE_HISTORY_LEN as u64) as usize
+        };
+        &self.entries[(start + index) % REBASE_HISTORY_LEN]
+    }
+
+    /// The recorded rebases, oldest first
+    pub fn entries(&self) -> impl Iterator<Item = &RebaseHistoryEntry> {
+        (0..self.len()).map(|index| self.get(index))
+    }
+
+    /// The last rebase recorded at or before `unix_timestamp`, which set the
+    /// supply and shares in effect at that time.
+    ///
+    /// Fails if the timestamp predates the kept rebases.
+    pub fn entry_at(&self, unix_timestamp: i64) -> Result<&RebaseHistoryEntry, ProgramError> {
+        // the clock never goes back, so the entries are sorted by timestamp
+        let (mut low, mut high) = (0, self.len());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if i64::from(self.get(mid).unix_timestamp) <= unix_timestamp {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        low.checked_sub(1)
+            .map(|index| self.get(index))
+            .ok_or_else(|| TokenError::RebaseHistoryUnavailable.into())
+    }
+
+    /// The most recent rebase, if any
+    pub fn latest(&self) -> Option<&RebaseHistoryEntry> {
+        self.entries().last()
+    }
+}
+impl Extension for RebaseHistory {
+    const TYPE: ExtensionType = ExtensionType::RebaseHistory;
+}
+
+/// Add shares to a token account, refreshing its amount
+pub fn credit_shares(
+    config: &RebaseMintConfig,
+    account: &mut StateWithExtensionsMut<Account>,
+    shares: u64,
+) -> ProgramResult {
+    let extension = account.get_extension_mut::<RebaseAccount>()?;
+    let new_shares = u64::from(extension.shares)
+        .checked_add(shares)
+        .ok_or(TokenError::Overflow)?;
+    extension.shares = new_shares.into();
+    account.base.amount = config
+        .shares_to_amount(new_shares)
+        .ok_or(TokenError::Overflow)?;
+    Ok(())
+}
+
+/// Remove shares from a token account, refreshing its amount
+pub fn debit_shares(
+    config: &RebaseMintConfig,
+    account: &mut StateWithExtensionsMut<Account>,
+    shares: u64,
+) -> ProgramResult {
+    let extension = account.get_extension_mut::<RebaseAccount>()?;
+    let new_shares = u64::from(extension.shares)
+        .checked_sub(shares)
+        .ok_or(TokenError::InsufficientFunds)?;
+    extension.shares = new_shares.into();
+    account.base.amount = config
+        .shares_to_amount(new_shares)
+        .ok_or(TokenError::Overflow)?;
+    Ok(())
+}
+
+/// Get the account extensions that `InitializeAccount` requires for `mint`,
+/// as `ExtensionType::get_required_init_account_extensions` does, but with a
+/// `LazyRebaseAccount` instead of a `RebaseAccount` if the mint applies
+/// rebases lazily
+pub fn get_required_init_account_extensions<S: BaseStateWithExtensions<Mint>>(
+    mint: &S,
+) -> Result<Vec<ExtensionType>, ProgramError> {
+    let mut account_extension_types =
+        ExtensionType::get_required_init_account_extensions(&mint.get_extension_types()?);
+    if mint
+        .get_extension::<RebaseMintConfig>()
+        .map_or(false, RebaseMintConfig::is_lazy)
+    {
+        for extension_type in account_extension_types.iter_mut() {
+            if *extension_type == ExtensionType::RebaseAccount {
+                *extension_type = ExtensionType::LazyRebaseAccount;
+            }
+        }
+    }
+    Ok(account_extension_types)
+}
+
+/// Compute `value * numerator / denominator`, rounding down, or `None` if it
+/// overflows a `u128` or the denominator is zero.
+///
+/// Rebase indexes are too wide for the product to fit in a `u128`, so the
+/// part of the numerator below the denominator is multiplied by long
+/// multiplication, one bit of the value at a time, keeping the running
+/// product as a quotient and a remainder below the denominator.
+fn mul_div_floor(value: u64, numerator: u128, denominator: u128) -> Option<u128> {
+    if denominator == 0 {
+        return None;
+    }
+    if let Some(product) = (value as u128).checked_mul(numerator) {
+        return Some(product / denominator);
+    }
+    let whole = (value as u128).checked_mul(numerator / denominator)?;
+    let remainder = numerator % denominator;
+    // `prefix * remainder == quotient * denominator + partial` for the bits
+    // of the value consumed so far, with `partial < denominator`
+    let (mut quotient, mut partial) = (0u128, 0u128);
+    for bit in (0..u64::BITS).rev() {
+        quotient <<= 1;
+        if partial >= denominator - partial {
+            partial -= denominator - partial;
+            quotient += 1;
+        } else {
+            partial <<= 1;
+        }
+        if (value >> bit) & 1 == 1 {
+            if partial >= denominator - remainder {
+                partial -= denominator - remainder;
+                quotient += 1;
+            } else {
+                partial += remainder;
+            }
+        }
+    }
+    whole.checked_add(quotient)
+}
+
+/// Apply the rebases that a token account of a lazy mint missed, scaling its
+/// amount to the current rebase index, and return its previous amount.
+///
+/// The caller packs the base account.
+pub fn catch_up_lazy_account(
+    config: &RebaseMintConfig,
+    account: &mut StateWithExtensionsMut<Account>,
+) -> Result<u64, ProgramError> {
+    let previous_amount = account.base.amount;
+    let extension = account.get_extension_mut::<LazyRebaseAccount>()?;
+    let amount = config
+        .lazy_amount(previous_amount, extension.last_seen_rebase_index.into())
+        .ok_or(TokenError::Overflow)?;
+    extension.last_seen_rebase_index = config.rebase_index;
+    account.base.amount = amount;
+    Ok(previous_amount)
+}
+
+/// Check if a token account is exempt from rebasing
+pub fn is_exempt<S: BaseStateWithExtensions<Account>>(account: &S) -> bool {
+    account
+        .get_extension::<RebaseAccount>()
+        .map_or(false, RebaseAccount::is_exempt)
+}
+
+/// Add tokens to an exempt token account, counting them in the exempt supply
+pub fn credit_exempt(
+    config: &mut RebaseMintConfig,
+    account: &mut StateWithExtensionsMut<Account>,
+    amount: u64,
+) -> ProgramResult {
+    config.add_exempt_supply(amount)?;
+    account.base.amount = account
+        .base
+        .amount
+        .checked_add(amount)
+        .ok_or(TokenError::Overflow)?;
+    Ok(())
+}
+
+/// Remove tokens from an exempt token account and from the exempt supply
+pub fn debit_exempt(
+    config: &mut RebaseMintConfig,
+    account: &mut StateWithExtensionsMut<Account>,
+    amount: u64,
+) -> ProgramResult {
+    account.base.amount = account
+        .base
+        .amount
+        .checked_sub(amount)
+        .ok_or(TokenError::InsufficientFunds)?;
+    config.remove_exempt_supply(amount)
+}
+
+/// Check that a transfer moving `shares` is within `max_share_slippage_bps`
+/// basis points of the `expected_shares`. Zero tolerance requires an exact
+/// match.
+pub fn check_share_slippage(
+    shares: u64,
+    expected_shares: u64,
+    max_share_slippage_bps: u16,
+) -> ProgramResult {
+    let slippage = (shares.abs_diff(expected_shares) as u128).saturating_mul(10_000);
+    let tolerance = (expected_shares as u128).saturating_mul(max_share_slippage_bps as u128);
+    if slippage > tolerance {
+        Err(TokenError::ShareSlippageExceeded.into())
+    } else {
+        Ok(())
+    }
+}
+
+/// Convert a supply in UI units, such as `"1250000.5"`, to base units for a
+/// mint with `decimals`.
+///
+/// Stricter than `crate::try_ui_amount_into_amount`: the supply must be ASCII
+/// digits with at most one decimal point between them, so signs, spaces,
+/// separators and exponents are all rejected, as are fractions finer than the
+/// mint's decimals and supplies that don't fit a `u64`. Trailing zeros of the
+/// fraction are ignored.
+pub fn try_ui_supply_into_supply(ui_supply: &str, decimals: u8) -> Result<u64, ProgramError> {
+    let is_digits = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+    let well_formed = match ui_supply.split_once('.') {
+        Some((whole, fraction)) => is_digits(whole) && is_digits(fraction),
+        None => is_digits(ui_supply),
+    };
+    if !well_formed {
+        return Err(TokenError::RebaseUiSupplyMalformed.into());
+    }
+    crate::try_ui_amount_into_amount(ui_supply.to_string(), decimals)
+        .map_err(|_| TokenError::RebaseUiSupplyMalformed.into())
+}
+
+/// Move `shares`, or `amount` tokens, between two token accounts when either
+/// is exempt from rebasing, updating the totals on the mint. The destination
+/// receives all but the `fee_shares`, which the caller withholds.
+///
+/// Between two exempt accounts without a fee, the amount moves as is.
+/// Otherwise, tokens leaving an exempt account are issued as shares, backed by
+/// what the shares are worth rounding up, and shares entering one are retired
+/// for what they are worth rounding down, so that the rest of the holders
+/// never lose out.
+pub fn transfer_exempt(
+    config: &mut RebaseMintConfig,
+    source: &mut StateWithExtensionsMut<Account>,
+    destination: &mut StateWithExtensionsMut<Account>,
+    shares: u64,
+    fee_shares: u64,
+    amount: u64,
+) -> ProgramResult {
+    let source_exempt = is_exempt(&*source);
+    let destination_exempt = is_exempt(&*destination);
+    if source_exempt && destination_exempt && fee_shares == 0 {
+        debit_exempt(config, source, amount)?;
+        return credit_exempt(config, destination, amount);
+    }
+
+    if source_exempt {
+        let backing_amount = config
+            .shares_to_amount_rounding_up(shares)
+            .ok_or(TokenError::Overflow)?;
+        debit_exempt(config, source, backing_amount)?;
+        config.issue_shares(shares, backing_amount)?;
+    } else {
+        debit_shares(config, source, shares)?;
+    }
+
+    let shares = shares.checked_sub(fee_shares).ok_or(TokenError::Overflow)?;
+    if destination_exempt {
+        let amount = config
+            .shares_to_amount(shares)
+            .ok_or(TokenError::Overflow)?;
+        config.retire_shares(shares, amount)?;
+        credit_exempt(config, destination, amount)
+    } else {
+        credit_shares(config, destination, shares)
+    }
+}
+
+/// Pay out withheld transfer fees, which are kept in shares, to a token
+/// account. An exempt account gets what the shares are worth, rounding down.
+pub fn credit_withheld(
+    config: &mut RebaseMintConfig,
+    account: &mut StateWithExtensionsMut<Account>,
+    shares: u64,
+) -> ProgramResult {
+    if is_exempt(&*account) {
+        let amount = config
+            .shares_to_amount(shares)
+            .ok_or(TokenError::Overflow)?;
+        config.retire_shares(shares, amount)?;
+        credit_exempt(config, account, amount)
+    } else {
+        credit_shares(config, account, shares)
+    }
+}
+
+/// Exempt a token account from rebasing, or include it again, converting its
+/// balance at the current ratio.
+///
+/// The shares of a newly exempt account are retired for what they are worth,
+/// rounding down, and become a fixed amount. An account included again gets
+/// the shares its amount is worth, rounding down.
+pub fn set_account_exempt(
+    config: &mut RebaseMintConfig,
+    account: &mut StateWithExtensionsMut<Account>,
+    exempt: bool,
+) -> ProgramResult {
+    let extension = account.get_extension::<RebaseAccount>()?;
+    if extension.is_exempt() == exempt {
+        return Ok(());
+    }
+    if exempt {
+        let shares = u64::from(extension.shares);
+        let amount = config
+            .shares_to_amount(shares)
+            .ok_or(TokenError::Overflow)?;
+        config.retire_shares(shares, amount)?;
+        config.add_exempt_supply(amount)?;
+        let extension = account.get_extension_mut::<RebaseAccount>()?;
+        extension.shares = 0.into();
+        extension.rebase_exempt = true.into();
+        account.base.amount = amount;
+    } else {
+        let amount = account.base.amount;
+        let shares = config.amount_to_nonzero_shares(amount)?;
+        config.remove_exempt_supply(amount)?;
+        config.issue_shares(shares, amount)?;
+        let extension = account.get_extension_mut::<RebaseAccount>()?;
+        extension.rebase_exempt = false.into();
+        credit_shares(config, account, shares)?;
+    }
+    Ok(())
+}
+
+/// Outcome of a rebase, returned by `RebaseMintInstruction::PreviewRebaseSupply`
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct RebasePreview {
+    /// Total supply after the rebase
+    pub new_supply: PodU64,
+    /// Total shares after the rebase
+    pub total_shares: PodU64,
+    /// Tokens left unattributed if every share were held separately, which
+    /// bounds what holders lose to rounding down
+    pub rounding_error: PodU64,
+}
+
+/// Fee taken by a rebase, returned by `RebaseMintConfig::apply_rebase_with_fee`
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RebaseFee {
+    /// Tokens taken from the increase
+    pub amount: u64,
+    /// Shares issued to the fee destination for them
+    pub shares: u64,
+}
+
+/// Values checked by `RebaseMintInstruction::AssertRebaseConsistency`
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct RebaseConsistency {
+    /// Supply of the base mint
+    pub mint_supply: PodU64,
+    /// Total supply of the extension
+    pub total_supply: PodU64,
+    /// Total shares of the extension
+    pub total_shares: PodU64,
+    /// Shares held by token accounts
+    pub outstanding_shares: PodU64,
+    /// Tokens held by accounts exempt from rebasing
+    pub exempt_supply: PodU64,
+}
+
+/// Version of the `RebaseState` layout, bumped whenever fields are appended
+pub const REBASE_STATE_VERSION: u8 = 2;
+
+/// Snapshot of a rebasing mint, returned by
+/// `RebaseMintInstruction::GetRebaseState`
+///
+/// The layout is stable, so that clients can decode it without a matching
+/// version of this library: `version` always comes first, and fields are only
+/// appended, along with a bump of `REBASE_STATE_VERSION`. Readers should
+/// decode the fields of the versions they know about and ignore the rest.
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct RebaseState {
+    /// Version of the layout, `REBASE_STATE_VERSION` when returned
+    pub version: u8,
+    /// Decimals of the mint
+    pub decimals: u8,
+    /// Supply of the base mint
+    pub mint_supply: PodU64,
+    /// Total supply of the extension
+    pub total_supply: PodU64,
+    /// Total shares of the extension
+    pub total_shares: PodU64,
+    /// Shares held by token accounts
+    pub outstanding_shares: PodU64,
+    /// Tokens held by accounts exempt from rebasing
+    pub exempt_supply: PodU64,
+    /// Virtual shares and tokens added to the totals in every conversion
+    pub virtual_shares_offset: PodU64,
+    /// Tokens per share including the virtual offset, in the format of
+    /// `RebaseMintConfig::cached_ratio`, but computed from the current totals
+    pub ratio: PodU128,
+    /// Authority that can rebase the supply
+    pub supply_authority: OptionalNonZeroPubkey,
+    /// Proposed supply authority, waiting to accept the role
+    pub pending_supply_authority: OptionalNonZeroPubkey,
+    /// Supply authority waiting for the change delay to elapse
+    pub scheduled_supply_authority: OptionalNonZeroPubkey,
+    /// Key that can rebase the supply on behalf of the supply authority
+    pub rebase_delegate: OptionalNonZeroPubkey,
+    /// Token account holding the reserve backing the supply
+    pub reserve_account: OptionalNonZeroPubkey,
+    /// Unix timestamp before which the supply cannot be rebased, or zero
+    pub first_rebase_after_timestamp: PodI64,
+    /// True if the supply can be rebased at the time of the snapshot: some
+    /// key may rebase it, and the first rebase timestamp has passed
+    pub rebase_allowed: PodBool,
+    /// True once the supply has been rebased
+    pub has_rebased: PodBool,
+    /// If false, the supply can never be rebased lower than it is
+    pub allow_supply_decrease: PodBool,
+    /// If true, every rebase must be preceded by a memo
+    pub require_rebase_memo: PodBool,
+    /// `RebaseMode` of the mint, as a byte, since version 2
+    pub rebase_mode: u8,
+    /// Rebase index of a lazy mint, or zero, since version 2
+    pub rebase_index: PodU128,
+}
+
+/// Event logged by `RebaseMintInstruction::SyncSupplyFromMint`
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct SupplySynced {
+    /// Total supply before the sync
+    pub previous_supply: PodU64,
+    /// Total shares before the sync
+    pub previous_shares: PodU64,
+    /// Total supply after the sync, equal to the base mint's supply less the
+    /// exempt supply
+    pub total_supply: PodU64,
+    /// Total shares after the sync
+    pub total_shares: PodU64,
+}
+
+/// Event logged by `RebaseMintInstruction::RebaseSupply`
+#[repr(C)]
+#[cfg_attr(feature = "serde-traits", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct SupplyRebased {
+    /// Total supply before the rebase
+    pub previous_supply: PodU64,
+    /// Total supply after the rebase
+    pub new_supply: PodU64,
+    /// Total shares after the rebase, including `fee_shares`
+    pub total_shares: PodU64,
+    /// Tokens held by exempt accounts, which the rebase leaves untouched
+    pub exempt_supply: PodU64,
+    /// Shares issued to the fee destination as the rebase fee, or zero if
+    /// the rebase charged none
+    pub fee_shares: PodU64,
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, proptest::prelude::*};
+
+    const TEST_DECIMALS: u8 = 2;
+
+    fn config(total_supply: u64, total_shares: u64) -> RebaseMintConfig {
+        RebaseMintConfig {
+            total_supply: total_supply.into(),
+            total_shares: total_shares.into(),
+            allow_supply_decrease: true.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn ui_supply_conversions() {
+        for (ui_supply, decimals, supply) in [
+            ("0", 2, 0),
+            ("1250000", 2, 125_000_000),
+            ("1250000.5", 2, 125_000_050),
+            ("1250000.50", 2, 125_000_050),
+            ("1250000.500000", 2, 125_000_050),
+            ("0.01", 2, 1),
+            ("007", 0, 7),
+            ("7.0", 0, 7),
+            ("18446744073709551615", 0, u64::MAX),
+            ("18446744073.709551615", 9, u64::MAX),
+        ] {
+            assert_eq!(
+                try_ui_supply_into_supply(ui_supply, decimals),
+                Ok(supply),
+                "{}",
+                ui_supply
+            );
+        }
+        for (ui_supply, decimals) in [
+            ("", 2),
+            (".", 2),
+            ("1.", 2),
+            (".5", 2),
+            ("+1", 2),
+            ("-1", 2),
+            (" 1", 2),
+            ("1 ", 2),
+            ("1,250,000", 2),
+            ("1_250_000", 2),
+            ("1e6", 2),
+            ("1.2.3", 2),
+            ("\u{0661}", 2),
+            ("0.001", 2),
+            ("0.5", 0),
+            ("18446744073709551616", 0),
+            ("18446744073709551615", 1),
+        ] {
+            assert_eq!(
+                try_ui_supply_into_supply(ui_supply, decimals),
+                Err(TokenError::RebaseUiSupplyMalformed.into()),
+                "{}",
+                ui_supply
+            );
+        }
+    }
+
+    #[test]
+    fn no_shares_is_one_to_one() {
+        let config = config(0, 0);
+        for amount in [0, 1, 1_000, u64::MAX] {
+            assert_eq!(config.shares_to_amount(amount), Some(amount));
+            assert_eq!(config.amount_to_shares(amount), Some(amount));
+        }
+    }
+
+    #[test]
+    fn specific_conversions() {
+        // supply doubled since genesis
+        let rebase_config = config(2_000, 1_000);
+        assert_eq!(rebase_config.shares_to_amount(1), Some(2));
+        assert_eq!(rebase_config.shares_to_amount(500), Some(1_000));
+        assert_eq!(rebase_config.amount_to_shares(1_000), Some(500));
+        // rounds down
+        assert_eq!(rebase_config.amount_to_shares(3), Some(1));
+
+        // supply cut to a third
+        let rebase_config = config(1_000, 3_000);
+        assert_eq!(rebase_config.shares_to_amount(1), Some(0));
+        assert_eq!(rebase_config.shares_to_amount(2), Some(0));
+        assert_eq!(rebase_config.shares_to_amount(3), Some(1));
+        assert_eq!(rebase_config.amount_to_shares(1), Some(3));
+    }
+
+    #[test]
+    fn shares_per_token() {
+        assert_eq!(
+            config(0, 0).shares_per_token_scaled(),
+            Some(SHARES_PER_TOKEN_SCALE)
+        );
+        assert_eq!(
+            config(2_000, 1_000).shares_per_token_scaled(),
+            Some(SHARES_PER_TOKEN_SCALE / 2)
+        );
+        assert_eq!(
+            config(1, 3).shares_per_token_scaled(),
+            Some(3 * SHARES_PER_TOKEN_SCALE)
+        );
+        // rounds down
+        assert_eq!(
+            config(3, 1).shares_per_token_scaled(),
+            Some(333_333_333_333_333_333)
+        );
+        assert_eq!(
+            config(u64::MAX, u64::MAX).shares_per_token_scaled(),
+            Some(SHARES_PER_TOKEN_SCALE)
+        );
+        assert_eq!(config(0, 1).shares_per_token_scaled(), None);
+    }
+
+    #[test]
+    fn tokens_per_share() {
+        assert_eq!(
+            config(0, 0).tokens_per_share_scaled(),
+            TOKENS_PER_SHARE_SCALE
+        );
+        assert_eq!(
+            config(2_000, 1_000).tokens_per_share_scaled(),
+            2 * TOKENS_PER_SHARE_SCALE
+        );
+        // rounds down
+        assert_eq!(
+            config(1, 3).tokens_per_share_scaled(),
+            333_333_333_333_333_333
+        );
+        assert_eq!(config(0, 1).tokens_per_share_scaled(), 0);
+        assert_eq!(
+            config(u64::MAX, 1).tokens_per_share_scaled(),
+            u64::MAX as u128 * TOKENS_PER_SHARE_SCALE
+        );
+        let sunset = RebaseMintConfig {
+            is_sunset: true.into(),
+            ..config(2_000, 1_000)
+        };
+        assert_eq!(sunset.tokens_per_share_scaled(), 0);
+    }
+
+    #[test]
+    fn rounding_up() {
+        let rebase_config = config(2_000, 1_000);
+        assert_eq!(rebase_config.amount_to_shares_rounding_up(1_000), Some(500));
+        assert_eq!(rebase_config.amount_to_shares_rounding_up(3), Some(2));
+        assert_eq!(rebase_config.amount_to_shares_rounding_up(0), Some(0));
+
+        let rebase_config = config(1_000, 3_000);
+        assert_eq!(rebase_config.amount_to_shares_rounding_up(1), Some(3));
+
+        let rebase_config = config(0, 1_000);
+        assert_eq!(rebase_config.amount_to_shares_rounding_up(1), None);
+
+        let rebase_config = config(1, u64::MAX);
+        assert_eq!(
+            rebase_config.amount_to_shares_rounding_up(1),
+            Some(u64::MAX)
+        );
+        assert_eq!(rebase_config.amount_to_shares_rounding_up(2), None);
+    }
+
+    #[test]
+    fn shares_rounding_up() {
+        let rebase_config = config(1_500, 1_000);
+        assert_eq!(rebase_config.shares_to_amount_rounding_up(1), Some(2));
+        assert_eq!(rebase_config.shares_to_amount_rounding_up(2), Some(3));
+        assert_eq!(rebase_config.shares_to_amount_rounding_up(0), Some(0));
+
+        let rebase_config = config(0, 0);
+        assert_eq!(rebase_config.shares_to_amount_rounding_up(7), Some(7));
+
+        let rebase_config = config(u64::MAX, 1);
+        assert_eq!(
+            rebase_config.shares_to_amount_rounding_up(1),
+            Some(u64::MAX)
+        );
+        assert_eq!(rebase_config.shares_to_amount_rounding_up(2), None);
+    }
+
+    #[test]
+    fn issue_and_retire_shares() {
+        let mut rebase_config = RebaseMintConfig {
+            outstanding_shares: 1_000.into(),
+            ..config(1_500, 1_000)
+        };
+        rebase_config.issue_shares(10, 15).unwrap();
+        assert_eq!(
+            rebase_config,
+            RebaseMintConfig {
+                outstanding_shares: 1_010.into(),
+                ..config(1_515, 1_010)
+            }
+        );
+        rebase_config.retire_shares(1_010, 1_515).unwrap();
+        assert_eq!(rebase_config, config(0, 0));
+
+        // the last shares take the rounding dust with them
+        let mut rebase_config = RebaseMintConfig {
+            outstanding_shares: 1_000.into(),
+            ..config(1_500, 1_000)
+        };
+        rebase_config.retire_shares(1_000, 1_499).unwrap();
+        assert_eq!(rebase_config, config(0, 0));
+        assert_eq!(rebase_config.check_consistency(0), Ok(()));
+        assert_eq!(
+            rebase_config.retire_shares(1, 0),
+            Err(TokenError::Overflow.into())
+        );
+
+        // totals are left untouched on failure
+        let mut rebase_config = config(1, u64::MAX);
+        assert_eq!(
+            rebase_config.issue_shares(1, 1),
+            Err(TokenError::RebaseSharesOverflow.into())
+        );
+        assert_eq!(rebase_config, config(1, u64::MAX));
+    }
+
+    #[test]
+    fn total_shares_cap() {
+        let mut rebase_config = config(1_000, 1_000);
+        assert_eq!(rebase_config.max_total_shares(), DEFAULT_MAX_TOTAL_SHARES);
+        rebase_config.max_total_shares = 1_500.into();
+        assert_eq!(rebase_config.max_total_shares(), 1_500);
+
+        // exactly at the cap
+        rebase_config.issue_shares(500, 500).unwrap();
+        assert_eq!(u64::from(rebase_config.total_shares), 1_500);
+        assert_eq!(rebase_config.validate(), Ok(()));
+        rebase_config.preview_rebase(3_000).unwrap();
+
+        // one share above
+        let capped = rebase_config;
+        assert_eq!(
+            rebase_config.issue_shares(1, 1),
+            Err(TokenError::RebaseSharesCapExceeded.into())
+        );
+        assert_eq!(rebase_config, capped);
+        rebase_config.total_shares = 1_501.into();
+        assert_eq!(
+            rebase_config.validate(),
+            Err(TokenError::RebaseSharesCapExceeded.into())
+        );
+        assert_eq!(
+            rebase_config.preview_rebase(3_000),
+            Err(TokenError::RebaseSharesCapExceeded.into())
+        );
+
+        // the default cap
+        let mut rebase_config = config(DEFAULT_MAX_TOTAL_SHARES, DEFAULT_MAX_TOTAL_SHARES - 1);
+        rebase_config.issue_shares(1, 0).unwrap();
+        assert_eq!(rebase_config.validate(), Ok(()));
+        assert_eq!(
+            rebase_config.issue_shares(1, 0),
+            Err(TokenError::RebaseSharesCapExceeded.into())
+        );
+
+        // scaling the shares to a synced supply is capped too
+        let mut rebase_config = config(1_000, 1_000);
+        rebase_config.max_total_shares = 2_000.into();
+        rebase_config.sync_supply(2_000).unwrap();
+        assert_eq!(
+            rebase_config.sync_supply(2_001),
+            Err(TokenError::RebaseSharesCapExceeded.into())
+        );
+    }
+
+    #[test]
+    fn retire() {
+        let supply_authority = OptionalNonZeroPubkey::try_from(Some(Pubkey::new_unique())).unwrap();
+        let mut rebase_config = RebaseMintConfig {
+            supply_authority,
+            rebase_delegate: supply_authority,
+            outstanding_shares: 1_000.into(),
+            ..config(1_500, 1_000)
+        };
+        rebase_config.retire().unwrap();
+        assert_eq!(rebase_config.validate(), Ok(()));
+        assert_eq!(Option::<Pubkey>::from(rebase_config.supply_authority), None);
+        assert_eq!(Option::<Pubkey>::from(rebase_config.rebase_delegate), None);
+        assert_eq!(u64::from(rebase_config.retired_supply), 1_500);
+        assert_eq!(u64::from(rebase_config.retired_shares), 1_000);
+        assert_eq!(rebase_config.shares_to_amount(600), Some(900));
+
+        // the totals still move with mints and burns, and rounding may skew
+        // them, but conversions keep the frozen ratio
+        rebase_config.issue_shares(1_000, 1_501).unwrap();
+        assert_eq!(rebase_config.shares_to_amount(600), Some(900));
+        assert_eq!(rebase_config.amount_to_shares(900), Some(600));
+        assert_eq!(
+            rebase_config.tokens_per_share_scaled(),
+            3 * TOKENS_PER_SHARE_SCALE / 2
+        );
+
+        assert_eq!(
+            rebase_config.preview_rebase(3_000),
+            Err(TokenError::RebaseMintRetired.into())
+        );
+        assert_eq!(
+            rebase_config.sync_supply(3_000),
+            Err(TokenError::RebaseMintRetired.into())
+        );
+        assert_eq!(
+            rebase_config.retire(),
+            Err(TokenError::RebaseMintRetired.into())
+        );
+
+        // a retired mint can't get an authority back
+        rebase_config.supply_authority = supply_authority;
+        assert_eq!(
+            rebase_config.validate(),
+            Err(TokenError::InvalidState.into())
+        );
+    }
+
+    #[test]
+    fn sweep_rounding_dust() {
+        let mut rebase_config = RebaseMintConfig {
+            outstanding_shares: 997.into(),
+            ..config(1_500, 1_000)
+        };
+        assert_eq!(rebase_config.rounding_dust_shares(), 3);
+        assert_eq!(rebase_config.sweep_rounding_dust(), Ok(3));
+        assert_eq!(u64::from(rebase_config.outstanding_shares), 1_000);
+        assert_eq!(u64::from(rebase_config.total_shares), 1_000);
+        assert_eq!(u64::from(rebase_config.total_supply), 1_500);
+        assert_eq!(rebase_config.shares_to_amount(1_000), Some(1_500));
+
+        // nothing left to sweep
+        assert_eq!(rebase_config.rounding_dust_shares(), 0);
+        assert_eq!(rebase_config.sweep_rounding_dust(), Ok(0));
+        assert_eq!(u64::from(rebase_config.outstanding_shares), 1_000);
+
+        rebase_config.allow_sunset = true.into();
+        rebase_config.sunset().unwrap();
+        assert_eq!(
+            rebase_config.sweep_rounding_dust(),
+            Err(TokenError::RebaseMintSunset.into())
+        );
+    }
+
+    fn lazy_config(total_supply: u64) -> RebaseMintConfig {
+        RebaseMintConfig {
+            total_supply: total_supply.into(),
+            allow_supply_decrease: true.into(),
+            rebase_mode: RebaseMode::Lazy.into(),
+            rebase_index: REBASE_INDEX_SCALE.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn lazy_rebases_scale_the_index() {
+        let mut rebase_config = lazy_config(1_000);
+        assert_eq!(rebase_config.rebase_mode(), Ok(RebaseMode::Lazy));
+        assert_eq!(rebase_config.check_consistency(1_000), Ok(()));
+        assert_eq!(
+            rebase_config.check_rebase_mode(RebaseMode::Shares),
+            Err(TokenError::RebaseModeMismatch.into())
+        );
+
+        // an account caught up now, then missed three rebases
+        let last_seen_rebase_index = u128::from(rebase_config.rebase_index);
+        for new_supply in [2_000, 1_500, 3_000] {
+            let preview = rebase_config.preview_rebase(new_supply).unwrap();
+            rebase_config.apply_rebase(&preview);
+        }
+        assert_eq!(
+            u128::from(rebase_config.rebase_index),
+            3 * REBASE_INDEX_SCALE
+        );
+        assert_eq!(u64::from(rebase_config.total_shares), 0);
+        assert_eq!(
+            rebase_config.lazy_amount(400, last_seen_rebase_index),
+            Some(1_200)
+        );
+        // caught up already, or never held anything
+        assert_eq!(
+            rebase_config.lazy_amount(400, 3 * REBASE_INDEX_SCALE),
+            Some(400)
+        );
+        assert_eq!(rebase_config.lazy_amount(0, 0), Some(0));
+
+        assert_eq!(
+            rebase_config.preview_rebase(0),
+            Err(TokenError::RebaseSupplyZero.into())
+        );
+        assert_eq!(
+            lazy_config(0).preview_rebase(1_000),
+            Err(TokenError::RebaseSupplyZero.into())
+        );
+        assert_eq!(
+            lazy_config(1).preview_rebase(u64::MAX),
+            Err(TokenError::RebaseTooLarge.into())
+        );
+
+        rebase_config.total_shares = 1.into();
+        assert_eq!(
+            rebase_config.check_consistency(3_000),
+            Err(TokenError::RebaseInconsistent.into())
+        );
+    }
+
+    #[test]
+    fn lazy_ui_amounts_follow_the_index() {
+        let mut rebase_config = lazy_config(100);
+        assert_eq!(
+            rebase_config
+                .shares_to_ui_amount(100, TEST_DECIMALS)
+                .unwrap(),
+            "1"
+        );
+        assert_eq!(
+            rebase_config.try_ui_amount_into_shares("1", TEST_DECIMALS),
+            Ok(100)
+        );
+
+        // 1.5 tokens per token held since the start
+        let preview = rebase_config.preview_rebase(150).unwrap();
+        rebase_config.apply_rebase(&preview);
+        assert_eq!(
+            rebase_config
+                .shares_to_ui_amount(100, TEST_DECIMALS)
+                .unwrap(),
+            "1.5"
+        );
+        assert_eq!(
+            rebase_config.shares_to_ui_amount(1, TEST_DECIMALS).unwrap(),
+            "0.01"
+        );
+        assert_eq!(
+            rebase_config.try_ui_amount_into_shares("1.5", TEST_DECIMALS),
+            Ok(100)
+        );
+        assert_eq!(
+            rebase_config.try_ui_amount_into_shares("1", TEST_DECIMALS),
+            Ok(66)
+        );
+        assert_eq!(
+            rebase_config.try_ui_amount_into_shares("0.01", TEST_DECIMALS),
+            Ok(0)
+        );
+    }
+
+    #[test]
+    fn wide_mul_div_floor() {
+        let max = u64::MAX as u128;
+        assert_eq!(
+            mul_div_floor(u64::MAX, 3 * REBASE_INDEX_SCALE, REBASE_INDEX_SCALE),
+            Some(3 * max)
+        );
+        assert_eq!(
+            mul_div_floor(
+                u64::MAX,
+                3 * REBASE_INDEX_SCALE + REBASE_INDEX_SCALE / 2,
+                REBASE_INDEX_SCALE
+            ),
+            Some(3 * max + max / 2)
+        );
+        assert_eq!(
+            mul_div_floor(u64::MAX, REBASE_INDEX_SCALE - 1, REBASE_INDEX_SCALE),
+            Some(max - 1)
+        );
+        assert_eq!(mul_div_floor(u64::MAX, u128::MAX, u128::MAX - 1), Some(max));
+        assert_eq!(mul_div_floor(2, u128::MAX, 1), None);
+        assert_eq!(mul_div_floor(1, 1, 0), None);
+    }
+
+    #[test]
+    fn many_small_lazy_rebases_keep_balances_at_the_supply() {
+        let initial_supply = 1_000_000_000_000;
+        let mut rebase_config = lazy_config(initial_supply);
+        let last_seen_rebase_index = u128::from(rebase_config.rebase_index);
+        // every rebase keeps the supply a multiple of four, so the balances
+        // of a half, a quarter and a quarter of it stay whole tokens
+        let even_holders = [initial_supply / 2, initial_supply / 4, initial_supply / 4];
+        let uneven_holders = [
+            1,
+            initial_supply / 3,
+            initial_supply - initial_supply / 3 - 1,
+        ];
+        for rebase in 0..10_000 {
+            let total_supply = u64::from(rebase_config.total_supply);
+            let new_supply = if rebase % 3 == 2 {
+                total_supply - 4
+            } else {
+                total_supply + 4
+            };
+            let preview = rebase_config.preview_rebase(new_supply).unwrap();
+            rebase_config.apply_rebase(&preview);
+
+            let balances = |holders: &[u64]| -> u64 {
+                holders
+                    .iter()
+                    .map(|amount| {
+                        rebase_config
+                            .lazy_amount(*amount, last_seen_rebase_index)
+                            .unwrap()
+                    })
+                    .sum()
+            };
+            assert_eq!(balances(&even_holders), new_supply, "rebase {}", rebase);
+            // otherwise each balance is short of its exact value by less
+            // than a token, and never over it
+            let uneven_balances = balances(&uneven_holders);
+            assert!(uneven_balances <= new_supply, "rebase {}", rebase);
+            assert!(
+                new_supply - uneven_balances < uneven_holders.len() as u64,
+                "rebase {}",
+                rebase
+            );
+        }
+    }
+
+    #[test]
+    fn sunset() {
+        let supply_authority = OptionalNonZeroPubkey::try_from(Some(Pubkey::new_unique())).unwrap();
+        let mut rebase_config = RebaseMintConfig {
+            supply_authority,
+            outstanding_shares: 1_000.into(),
+            virtual_shares_offset: 10.into(),
+            ..config(1_500, 1_000)
+        };
+        assert_eq!(
+            rebase_config.sunset(),
+            Err(TokenError::RebaseSunsetNotAllowed.into())
+        );
+
+        rebase_config.allow_sunset = true.into();
+        rebase_config.exempt_supply = 1.into();
+        assert_eq!(
+            rebase_config.sunset(),
+            Err(TokenError::RebaseSunsetNotAllowed.into())
+        );
+
+        rebase_config.exempt_supply = 0.into();
+        rebase_config.sunset().unwrap();
+        assert_eq!(u64::from(rebase_config.total_supply), 0);
+        assert_eq!(u64::from(rebase_config.total_shares), 0);
+        assert_eq!(u64::from(rebase_config.outstanding_shares), 0);
+        assert_eq!(Option::<Pubkey>::from(rebase_config.supply_authority), None);
+        assert_eq!(rebase_config.closable(), Ok(()));
+
+        // shares are worth nothing, not one token each
+        assert_eq!(rebase_config.shares_to_amount(600), Some(0));
+        assert_eq!(rebase_config.shares_to_amount_rounding_up(600), Some(0));
+
+        // and nothing can bring them back
+        assert_eq!(
+            rebase_config.preview_rebase(1_000),
+            Err(TokenError::RebaseMintSunset.into())
+        );
+        assert_eq!(
+            rebase_config.issue_shares(1, 1),
+            Err(TokenError::RebaseMintSunset.into())
+        );
+        assert_eq!(
+            rebase_config.sync_supply(1_000),
+            Err(TokenError::RebaseMintSunset.into())
+        );
+        assert_eq!(
+            rebase_config.sunset(),
+            Err(TokenError::RebaseMintSunset.into())
+        );
+    }
+
+    #[test]
+    fn sync_supply() {
+        // an initial supply that was never minted
+        let mut rebase_config = config(1_400, 1_400);
+        let event = rebase_config.sync_supply(400).unwrap();
+        assert_eq!(rebase_config, config(400, 400));
+        assert_eq!(
+            event,
+            SupplySynced {
+                previous_supply: 1_400.into(),
+                previous_shares: 1_400.into(),
+                total_supply: 400.into(),
+                total_shares: 400.into(),
+            }
+        );
+
+        // the ratio is kept
+        let mut rebase_config = config(3_000, 1_000);
+        rebase_config.sync_supply(1_500).unwrap();
+        assert_eq!(rebase_config, config(1_500, 500));
+
+        // no shares yet
+        let mut rebase_config = config(0, 0);
+        rebase_config.sync_supply(700).unwrap();
+        assert_eq!(rebase_config, config(700, 700));
+
+        // shares with no supply can't be scaled
+        let mut rebase_config = config(0, 1_000);
+        assert_eq!(
+            rebase_config.sync_supply(700),
+            Err(TokenError::Overflow.into())
+        );
+
+        // tokens held by exempt accounts aren't divided into shares
+        let mut rebase_config = config(1_000, 1_000);
+        rebase_config.exempt_supply = 300.into();
+        rebase_config.sync_supply(700).unwrap();
+        assert_eq!(u64::from(rebase_config.total_supply), 400);
+        assert_eq!(u64::from(rebase_config.total_shares), 400);
+        assert_eq!(
+            rebase_config.sync_supply(200),
+            Err(TokenError::Overflow.into())
+        );
+
+        // shares held by token accounts can't be dropped
+        let mut rebase_config = config(1_400, 1_400);
+        rebase_config.outstanding_shares = 800.into();
+        assert_eq!(
+            rebase_config.sync_supply(400),
+            Err(TokenError::RebaseBelowOutstandingShares.into())
+        );
+        assert_eq!(u64::from(rebase_config.total_shares), 1_400);
+
+        let mut rebase_config = config(1_000, 1_000);
+        rebase_config.has_rebased = true.into();
+        assert_eq!(
+            rebase_config.sync_supply(700),
+            Err(TokenError::RebaseSupplySyncNotAllowed.into())
+        );
+    }
+
+    #[test]
+    fn zero_supply_with_shares() {
+        let config = config(0, 1_000);
+        assert_eq!(config.shares_to_amount(1_000), Some(0));
+        assert_eq!(config.amount_to_shares(1), None);
+    }
+
+    #[test]
+    fn overflow() {
+        let rebase_config = config(u64::MAX, 1);
+        assert_eq!(rebase_config.shares_to_amount(1), Some(u64::MAX));
+        assert_eq!(rebase_config.shares_to_amount(2), None);
+        let rebase_config = config(1, u64::MAX);
+        assert_eq!(rebase_config.amount_to_shares(1), Some(u64::MAX));
+        assert_eq!(rebase_config.amount_to_shares(2), None);
+    }
+
+    #[test]
+    fn amount_too_small_for_shares() {
+        let offset_config = RebaseMintConfig {
+            virtual_shares_offset: 1_000.into(),
+            ..config(1_000_000, 1)
+        };
+        // configs with the smallest amount worth one share
+        for (rebase_config, threshold) in [
+            (config(2, 1), 2),
+            (config(1_000_000, 1), 1_000_000),
+            (config(1_000_000_000_000, 3), 333_333_333_334),
+            (config(u64::MAX, 1), u64::MAX),
+            (offset_config, 1_000),
+        ] {
+            assert_eq!(rebase_config.amount_to_nonzero_shares(0), Ok(0));
+            assert_eq!(
+                rebase_config.amount_to_nonzero_shares(threshold - 1),
+                Err(TokenError::AmountTooSmallForShares.into())
+            );
+            assert_eq!(rebase_config.amount_to_nonzero_shares(threshold), Ok(1));
+            // read-only conversions still report zero
+            assert_eq!(rebase_config.amount_to_shares(threshold - 1), Some(0));
+            // and burns round up, so any nonzero amount takes a share
+            assert_eq!(rebase_config.amount_to_shares_rounding_up(1), Some(1));
+        }
+        assert_eq!(
+            config(0, 1_000).amount_to_nonzero_shares(1),
+            Err(TokenError::Overflow.into())
+        );
+    }
+
+    #[test]
+    fn virtual_shares_offset() {
+        // an attacker holding the only share rebases the supply up, so the
+        // next deposit rounds down to no shares and accrues to the attacker
+        let mut rebase_config = config(1_000_000, 1);
+        let attacker_value = rebase_config.shares_to_amount(1).unwrap();
+        let shares = rebase_config.amount_to_shares(999_999).unwrap();
+        assert_eq!(shares, 0);
+        rebase_config.issue_shares(shares, 999_999).unwrap();
+        let attacker_gain = rebase_config.shares_to_amount(1).unwrap() - attacker_value;
+        assert_eq!(attacker_gain, 999_999);
+
+        // with virtual shares, the deposit gets shares and the attacker gains
+        // less than one base unit
+        let mut rebase_config = RebaseMintConfig {
+            virtual_shares_offset: 1_000.into(),
+            ..config(1_000_000, 1)
+        };
+        let attacker_value = rebase_config.shares_to_amount(1).unwrap();
+        let shares = rebase_config.amount_to_shares(999_999).unwrap();
+        assert_eq!(shares, 999);
+        rebase_config.issue_shares(shares, 999_999).unwrap();
+        let attacker_gain = rebase_config.shares_to_amount(1).unwrap() - attacker_value;
+        assert_eq!(attacker_gain, 0);
+
+        // no shares yet is still 1:1
+        let rebase_config = RebaseMintConfig {
+            virtual_shares_offset: 1_000.into(),
+            ..config(0, 0)
+        };
+        assert_eq!(rebase_config.amount_to_shares(42), Some(42));
+        assert_eq!(rebase_config.shares_to_amount(42), Some(42));
+    }
+
+    #[test]
+    fn specific_ui_conversions() {
+        let config = config(4_200, 2_100);
+        for (shares, expected) in [(23, "0.46"), (55, "1.1"), (2_100, "42"), (0, "0")] {
+            let ui_amount = config.shares_to_ui_amount(shares, TEST_DECIMALS).unwrap();
+            assert_eq!(ui_amount, expected);
+        }
+        for (ui_amount, expected) in [("0.46", 23), ("1.1", 55), ("42", 2_100), ("0", 0)] {
+            let shares = config
+                .try_ui_amount_into_shares(ui_amount, TEST_DECIMALS)
+                .unwrap();
+            assert_eq!(shares, expected);
+        }
+        for ui_amount in ["", ".", "0.t", "0.111"] {
+            assert_eq!(
+                Err(ProgramError::InvalidArgument),
+                config.try_ui_amount_into_shares(ui_amount, TEST_DECIMALS),
+            );
+        }
+    }
+
+    #[test]
+    fn rebase_allowed_at() {
+        let config = RebaseMintConfig::default();
+        assert!(config.is_rebase_allowed_at(0));
+        let config = RebaseMintConfig {
+            first_rebase_after_timestamp: 1_000.into(),
+            ..Default::default()
+        };
+        assert!(!config.is_rebase_allowed_at(999));
+        assert!(config.is_rebase_allowed_at(1_000));
+        assert!(config.is_rebase_allowed_at(1_001));
+    }
+
+    #[test]
+    fn preview() {
+        let rebase_config = config(1_000, 1_000);
+        let preview = rebase_config.preview_rebase(2_500).unwrap();
+        assert_eq!(u64::from(preview.new_supply), 2_500);
+        assert_eq!(u64::from(preview.total_shares), 1_000);
+        assert_eq!(u64::from(preview.rounding_error), 500);
+        let preview = rebase_config.preview_rebase(3_000).unwrap();
+        assert_eq!(u64::from(preview.rounding_error), 0);
+        assert_eq!(
+            rebase_config.preview_rebase(0),
+            Err(TokenError::RebaseSupplyZero.into())
+        );
+
+        let rebase_config = config(0, 0);
+        let preview = rebase_config.preview_rebase(0).unwrap();
+        assert_eq!(u64::from(preview.rounding_error), 0);
+    }
+
+    #[test]
+    fn apply_preview() {
+        let mut config = config(1_000, 1_000);
+        let preview = config.preview_rebase(2_500).unwrap();
+        config.apply_rebase(&preview);
+        assert_eq!(u64::from(config.total_supply), 2_500);
+        assert_eq!(u64::from(config.total_shares), 1_000);
+        assert!(bool::from(config.has_rebased));
+        // 2.5 tokens per share
+        assert_eq!(u128::from(config.cached_ratio), 5u128 << 63);
+    }
+
+    #[test]
+    fn history_wraps_around() {
+        let entry = |i: u64| RebaseHistoryEntry {
+            unix_timestamp: (i as i64).into(),
+            old_supply: i.into(),
+            new_supply: (i + 1).into(),
+            new_shares: 1.into(),
+            reason_code: i as u8,
+            reference: [i as u8; 32],
+        };
+        let mut history = RebaseHistory::default();
+        assert_eq!(history.entries().count(), 0);
+        assert_eq!(history.latest(), None);
+
+        for i in 0..3 {
+            history.record(entry(i));
+        }
+        assert_eq!(
+            history.entries().copied().collect::<Vec<_>>(),
+            (0..3).map(entry).collect::<Vec<_>>()
+        );
+        assert_eq!(history.latest(), Some(&entry(2)));
+
+        // the oldest entries are overwritten, and the rest stay in order
+        let total = REBASE_HISTORY_LEN as u64 + 5;
+        for i in 3..total {
+            history.record(entry(i));
+        }
+        assert_eq!(u64::from(history.count), total);
+        assert_eq!(
+            history.entries().copied().collect::<Vec<_>>(),
+            (5..total).map(entry).collect::<Vec<_>>()
+        );
+        assert_eq!(history.latest(), Some(&entry(total - 1)));
+    }
+
+    #[test]
+    fn conversions_at() {
+        // 1_000 shares, rebased from 1_000 to 2_000, 3_000 and 4_500 tokens
+        let mut config = config(1_000, 1_000);
+        let mut history = RebaseHistory::default();
+        for (unix_timestamp, new_supply) in [(100, 2_000), (200, 3_000), (300, 4_500)] {
+            let preview = config.preview_rebase(new_supply).unwrap();
+            history.record(RebaseHistoryEntry {
+                unix_timestamp: PodI64::from(unix_timestamp),
+                old_supply: config.total_supply,
+                new_supply: preview.new_supply,
+                new_shares: preview.total_shares,
+                ..Default::default()
+            });
+            config.apply_rebase(&preview);
+        }
+
+        let unavailable = Err(TokenError::RebaseHistoryUnavailable.into());
+        assert_eq!(config.shares_to_amount_at(&history, 10, 99), unavailable);
+        assert_eq!(config.amount_to_shares_at(&history, 10, 99), unavailable);
+        for (unix_timestamp, amount, shares) in [
+            (100, 20, 5),
+            (150, 20, 5),
+            (200, 30, 3),
+            (299, 30, 3),
+            (300, 45, 2),
+            (i64::MAX, 45, 2),
+        ] {
+            assert_eq!(
+                config.shares_to_amount_at(&history, 10, unix_timestamp),
+                Ok(amount)
+            );
+            assert_eq!(
+                config.amount_to_shares_at(&history, 10, unix_timestamp),
+                Ok(shares)
+            );
+        }
+
+        // the oldest rebases are dropped from the history
+        for unix_timestamp in 400..400 + REBASE_HISTORY_LEN as i64 - 2 {
+            let latest = *history.latest().unwrap();
+            history.record(RebaseHistoryEntry {
+                unix_timestamp: unix_timestamp.into(),
+                ..latest
+            });
+        }
+        assert_eq!(config.shares_to_amount_at(&history, 10, 150), unavailable);
+        assert_eq!(config.shares_to_amount_at(&history, 10, 200), Ok(30));
+    }
+
+    #[test]
+    fn conversions_at_after_sunset_or_retire() {
+        // 1_000 shares, rebased from 1_000 to 2_000 and 3_000 tokens
+        let mut config = RebaseMintConfig {
+            allow_sunset: true.into(),
+            ..config(1_000, 1_000)
+        };
+        let mut history = RebaseHistory::default();
+        for (unix_timestamp, new_supply) in [(100, 2_000), (200, 3_000)] {
+            let preview = config.preview_rebase(new_supply).unwrap();
+            history.record(RebaseHistoryEntry {
+                unix_timestamp: PodI64::from(unix_timestamp),
+                old_supply: config.total_supply,
+                new_supply: preview.new_supply,
+                new_shares: preview.total_shares,
+                ..Default::default()
+            });
+            config.apply_rebase(&preview);
+        }
+
+        // the ratio frozen at retirement doesn't reach back
+        let mut retired = config;
+        retired.retire().unwrap();
+        assert_eq!(retired.shares_to_amount(10), Some(30));
+        assert_eq!(retired.shares_to_amount_at(&history, 10, 100), Ok(20));
+        assert_eq!(retired.amount_to_shares_at(&history, 20, 100), Ok(10));
+
+        // nor does the written off supply
+        let mut sunset = config;
+        sunset.sunset().unwrap();
+        assert_eq!(sunset.shares_to_amount(10), Some(0));
+        assert_eq!(sunset.shares_to_amount_at(&history, 10, 100), Ok(20));
+        assert_eq!(sunset.shares_to_amount_at(&history, 10, 200), Ok(30));
+        assert_eq!(sunset.amount_to_shares_at(&history, 30, 200), Ok(10));
+    }
+
+    #[test]
+    fn reserve() {
+        let config = RebaseMintConfig {
+            exempt_supply: 100.into(),
+            ..config(1_000, 1_000)
+        };
+        assert_eq!(config.check_reserve(1_400, 1_500), Ok(()));
+        assert_eq!(
+            config.check_reserve(1_401, 1_500),
+            Err(TokenError::RebaseExceedsReserve.into())
+        );
+        assert_eq!(
+            config.check_reserve(u64::MAX, u64::MAX),
+            Err(TokenError::RebaseTooLarge.into())
+        );
+    }
+
+    #[test]
+    fn synced_base_supply() {
+        let mut config = RebaseMintConfig {
+            exempt_supply: 100.into(),
+            ..config(1_000, 1_000)
+        };
+        // without the flag, the base supply is left alone
+        assert_eq!(config.check_base_supply(0), Ok(()));
+        assert_eq!(config.preview_rebase(u64::MAX).map(|_| ()), Ok(()));
+
+        config.sync_base_supply = true.into();
+        assert_eq!(config.backed_supply(1_000), Some(1_100));
+        assert_eq!(config.check_base_supply(1_100), Ok(()));
+        assert_eq!(config.check_consistency(1_100), Ok(()));
+        // rounding dust retired with the last shares
+        assert_eq!(config.check_base_supply(1_101), Ok(()));
+        assert_eq!(
+            config.check_base_supply(1_099),
+            Err(TokenError::RebaseBaseSupplyMismatch.into())
+        );
+        assert_eq!(
+            config.check_consistency(1_099),
+            Err(TokenError::RebaseInconsistent.into())
+        );
+
+        // the base supply must hold the exempt supply on top of the new one
+        assert_eq!(config.preview_rebase(u64::MAX - 100).map(|_| ()), Ok(()));
+        assert_eq!(
+            config.preview_rebase(u64::MAX - 99),
+            Err(TokenError::RebaseTooLarge.into())
+        );
+    }
+
+    #[test]
+    fn cached_ratio() {
+        let mut empty = config(0, 0);
+        empty.refresh_cached_ratio();
+        assert_eq!(u128::from(empty.cached_ratio), 0);
+
+        let mut largest = config(u64::MAX, 1);
+        largest.refresh_cached_ratio();
+        assert_eq!(u128::from(largest.cached_ratio), (u64::MAX as u128) << 64);
+        assert_eq!(largest.shares_to_amount(1), Some(u64::MAX));
+        assert_eq!(largest.shares_to_amount(2), None);
+
+        // the scaled supply doesn't fit once the offset takes it past u64::MAX
+        let mut too_large = RebaseMintConfig {
+            virtual_shares_offset: 1.into(),
+            ..config(u64::MAX, 1)
+        };
+        too_large.refresh_cached_ratio();
+        assert_eq!(u128::from(too_large.cached_ratio), 0);
+        assert_eq!(too_large.shares_to_amount(0), Some(0));
+
+        // a stale cache, as left by minting, still gives the exact amount
+        let mut stale = config(3_000, 1_000);
+        stale.refresh_cached_ratio();
+        stale.issue_shares(333, 1_000).unwrap();
+        assert_eq!(stale.shares_to_amount(1_333), Some(4_000));
+        assert_eq!(stale.shares_to_amount(1), Some(3));
+        // and so does a wildly wrong one
+        stale.cached_ratio = u128::MAX.into();
+        assert_eq!(stale.shares_to_amount(1_333), Some(4_000));
+        stale.cached_ratio = 1.into();
+        assert_eq!(stale.shares_to_amount(1_333), Some(4_000));
+    }
+
+    #[test]
+    fn cached_inverse_ratio() {
+        let mut empty = config(0, 0);
+        empty.refresh_cached_ratio();
+        assert_eq!(u128::from(empty.cached_inverse_ratio), 0);
+
+        // 2.5 tokens per share
+        let mut rebased = config(2_500, 1_000);
+        rebased.refresh_cached_ratio();
+        assert_eq!(u128::from(rebased.cached_inverse_ratio), (2u128 << 64) / 5);
+        assert_eq!(rebased.amount_to_shares(5), Some(2));
+        assert_eq!(rebased.amount_to_shares(4), Some(1));
+
+        // the scaled shares don't fit once the offset takes them past
+        // u64::MAX
+        let mut too_large = RebaseMintConfig {
+            virtual_shares_offset: 1.into(),
+            ..config(1, u64::MAX)
+        };
+        too_large.refresh_cached_ratio();
+        assert_eq!(u128::from(too_large.cached_inverse_ratio), 0);
+        assert_eq!(too_large.amount_to_shares(1), Some(1 << 63));
+
+        // a stale cache, as left by burning, still gives the exact shares
+        let mut stale = RebaseMintConfig {
+            outstanding_shares: 1_000.into(),
+            ..config(3_000, 1_000)
+        };
+        stale.refresh_cached_ratio();
+        stale.retire_shares(333, 1_000).unwrap();
+        assert_eq!(stale.amount_to_shares(2_000), Some(667));
+        assert_eq!(stale.amount_to_shares(2), Some(0));
+        assert_eq!(stale.amount_to_shares(3), Some(1));
+        // and so does a wildly wrong one
+        stale.cached_inverse_ratio = u128::MAX.into();
+        assert_eq!(stale.amount_to_shares(2_000), Some(667));
+        stale.cached_inverse_ratio = 1.into();
+        assert_eq!(stale.amount_to_shares(2_000), Some(667));
+    }
+
+    #[test]
+    fn rebase_fee() {
+        let fee_destination = OptionalNonZeroPubkey::try_from(Some(Pubkey::new_unique())).unwrap();
+        let mut config = config(1_000, 1_000);
+        assert_eq!(
+            config.set_rebase_fee(MAX_REBASE_FEE_BPS + 1, fee_destination),
+            Err(TokenError::RebaseFeeTooHigh.into())
+        );
+        // no fee without a destination
+        config
+            .set_rebase_fee(2_500, OptionalNonZeroPubkey::default())
+            .unwrap();
+        assert_eq!(config.rebase_fee(3_000), 0);
+
+        config.set_rebase_fee(2_500, fee_destination).unwrap();
+        assert_eq!(config.rebase_fee(3_000), 500);
+        // nothing on the way down
+        assert_eq!(config.rebase_fee(500), 0);
+
+        // a quarter of the 2_000 increase goes to 200 new shares, worth 500
+        // at 2.5 tokens per share, leaving 2_500 to the existing 1_000 shares
+        let preview = config.preview_rebase(3_000).unwrap();
+        let fee = config.apply_rebase_with_fee(&preview).unwrap();
+        assert_eq!(
+            fee,
+            RebaseFee {
+                amount: 500,
+                shares: 200
+            }
+        );
+        assert_eq!(u64::from(config.total_supply), 3_000);
+        assert_eq!(u64::from(config.total_shares), 1_200);
+        assert_eq!(u64::from(config.outstanding_shares), 200);
+        assert_eq!(config.shares_to_amount(1_000), Some(2_500));
+        assert_eq!(config.shares_to_amount(200), Some(500));
+    }
+
+    #[test]
+    fn preview_counts_fee_shares() {
+        let fee_destination = OptionalNonZeroPubkey::try_from(Some(Pubkey::new_unique())).unwrap();
+        let mut config = config(1_000, 1_000);
+        config.set_rebase_fee(2_500, fee_destination).unwrap();
+        let preview = config.preview_rebase(3_000).unwrap();
+        let (preview_with_fee, rebased) = config.preview_with_fee(&preview).unwrap();
+        assert_eq!(u64::from(preview_with_fee.new_supply), 3_000);
+        assert_eq!(u64::from(preview_with_fee.total_shares), 1_200);
+        assert_eq!(u64::from(preview_with_fee.rounding_error), 600);
+
+        config.apply_rebase_with_fee(&preview).unwrap();
+        assert_eq!(rebased, config);
+    }
+
+    #[test]
+    fn rebase_fee_rounding() {
+        // rounding favors the holders
+        let fee_destination = OptionalNonZeroPubkey::try_from(Some(Pubkey::new_unique())).unwrap();
+        let mut config = config(1_000, 1_000);
+        config.set_rebase_fee(1_000, fee_destination).unwrap();
+        let preview = config.preview_rebase(2_000).unwrap();
+        let fee = config.apply_rebase_with_fee(&preview).unwrap();
+        assert_eq!(
+            fee,
+            RebaseFee {
+                amount: 100,
+                shares: 52
+            }
+        );
+        assert_eq!(u64::from(config.total_supply), 2_000);
+        assert!(config.shares_to_amount(fee.shares).unwrap() <= fee.amount);
+        assert!(config.shares_to_amount(1_000).unwrap() >= 1_900);
+    }
+
+    #[test]
+    fn cumulative_supply_changes() {
+        let fee_destination = OptionalNonZeroPubkey::try_from(Some(Pubkey::new_unique())).unwrap();
+        let mut rebase_config = RebaseMintConfig {
+            outstanding_shares: 1_000.into(),
+            allow_sunset: true.into(),
+            ..config(1_000, 1_000)
+        };
+        rebase_config
+            .set_rebase_fee(1_000, fee_destination)
+            .unwrap();
+        // the fee counts towards the increase
+        for new_supply in [1_500, 1_200, 1_200, 2_000, 100] {
+            let preview = rebase_config.preview_rebase(new_supply).unwrap();
+            rebase_config.apply_rebase_with_fee(&preview).unwrap();
+        }
+        // mints and burns don't rebase
+        rebase_config.issue_shares(50, 500).unwrap();
+        rebase_config.retire_shares(20, 200).unwrap();
+        assert_eq!(
+            u128::from(rebase_config.cumulative_supply_increase),
+            500 + 800
+        );
+        assert_eq!(
+            u128::from(rebase_config.cumulative_supply_decrease),
+            300 + 1_900
+        );
+
+        let total_supply = u64::from(rebase_config.total_supply) as u128;
+        rebase_config.sunset().unwrap();
+        assert_eq!(
+            u128::from(rebase_config.cumulative_supply_decrease),
+            300 + 1_900 + total_supply
+        );
+
+        // saturates rather than failing the rebase
+        let mut rebase_config = RebaseMintConfig {
+            cumulative_supply_increase: (u128::MAX - 1).into(),
+            ..config(1_000, 1_000)
+        };
+        let preview = rebase_config.preview_rebase(2_000).unwrap();
+        rebase_config.apply_rebase(&preview);
+        assert_eq!(u64::from(rebase_config.total_supply), 2_000);
+        assert_eq!(
+            u128::from(rebase_config.cumulative_supply_increase),
+            u128::MAX
+        );
+        assert_eq!(u128::from(rebase_config.cumulative_supply_decrease), 0);
+    }
+
+    #[test]
+    fn supply_decrease() {
+        let mut config = config(1_000, 1_000);
+        config.preview_rebase(999).unwrap();
+        config.allow_supply_decrease = false.into();
+        assert_eq!(
+            config.preview_rebase(999),
+            Err(TokenError::SupplyDecreaseForbidden.into())
+        );
+        config.preview_rebase(1_000).unwrap();
+        config.preview_rebase(1_001).unwrap();
+    }
+
+    #[test]
+    fn outstanding_shares_floor() {
+        let mut config = config(1_000, 1_000);
+        config.outstanding_shares = 600.into();
+        assert_eq!(config.validate(), Ok(()));
+        assert_eq!(
+            config.preview_rebase(0),
+            Err(TokenError::RebaseBelowOutstandingShares.into())
+        );
+        config.preview_rebase(1).unwrap();
+
+        config.total_shares = 599.into();
+        assert_eq!(
+            config.validate(),
+            Err(TokenError::RebaseBelowOutstandingShares.into())
+        );
+        assert_eq!(
+            config.preview_rebase(2_000),
+            Err(TokenError::RebaseBelowOutstandingShares.into())
+        );
+    }
+
+    #[test]
+    fn share_slippage() {
+        assert_eq!(check_share_slippage(100, 100, 0), Ok(()));
+        assert_eq!(
+            check_share_slippage(99, 100, 0),
+            Err(TokenError::ShareSlippageExceeded.into())
+        );
+        // 1% either way
+        assert_eq!(check_share_slippage(99, 100, 100), Ok(()));
+        assert_eq!(check_share_slippage(101, 100, 100), Ok(()));
+        assert_eq!(
+            check_share_slippage(98, 100, 100),
+            Err(TokenError::ShareSlippageExceeded.into())
+        );
+        assert_eq!(
+            check_share_slippage(1, 0, u16::MAX),
+            Err(TokenError::ShareSlippageExceeded.into())
+        );
+        assert_eq!(check_share_slippage(0, u64::MAX, u16::MAX), Ok(()));
+    }
+
+    #[test]
+    fn direct_authority_change() {
+        let new_authority = Some(Pubkey::new_unique()).try_into().unwrap();
+        let config = RebaseMintConfig::default();
+        assert_eq!(config.check_direct_authority_change(&new_authority), Ok(()));
+        let config = RebaseMintConfig {
+            two_step_authority_transfer: true.into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.check_direct_authority_change(&new_authority),
+            Err(TokenError::RebaseAuthorityRequiresAcceptance.into())
+        );
+        assert_eq!(
+            config.check_direct_authority_change(&OptionalNonZeroPubkey::default()),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn delayed_authority_change() {
+        let authority = Some(Pubkey::new_unique()).try_into().unwrap();
+        let new_authority = Some(Pubkey::new_unique()).try_into().unwrap();
+        let mut config = RebaseMintConfig {
+            supply_authority: authority,
+            authority_change_delay_seconds: 100.into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.finalize_authority_change(1_000),
+            Err(TokenError::NoPendingRebaseAuthority.into())
+        );
+        assert!(config.change_supply_authority(new_authority, 1_000));
+        assert_eq!(config.supply_authority, authority);
+        assert_eq!(
+            config.finalize_authority_change(1_099),
+            Err(TokenError::RebaseAuthorityChangeNotReady.into())
+        );
+        config.finalize_authority_change(1_100).unwrap();
+        assert_eq!(config.supply_authority, new_authority);
+        assert_eq!(
+            config.scheduled_supply_authority,
+            OptionalNonZeroPubkey::default()
+        );
+
+        // revoking is immediate
+        assert!(!config.change_supply_authority(OptionalNonZeroPubkey::default(), 2_000));
+        assert_eq!(config.supply_authority, OptionalNonZeroPubkey::default());
+    }
+
+    #[test]
+    fn rebase_authority() {
+        let supply_authority = Pubkey::new_unique();
+        let rebase_delegate = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let mut config = RebaseMintConfig {
+            supply_authority: Some(supply_authority).try_into().unwrap(),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.rebase_authority_for(&rebase_delegate, None),
+            Ok(supply_authority)
+        );
+        config.rebase_delegate = Some(rebase_delegate).try_into().unwrap();
+        assert_eq!(
+            config.rebase_authority_for(&rebase_delegate, None),
+            Ok(rebase_delegate)
+        );
+        assert_eq!(
+            config.rebase_authority_for(&supply_authority, None),
+            Ok(supply_authority)
+        );
+        assert_eq!(
+            config.rebase_authority_for(&other, None),
+            Ok(supply_authority)
+        );
+        config.supply_authority = OptionalNonZeroPubkey::default();
+        assert_eq!(
+            config.rebase_authority_for(&rebase_delegate, None),
+            Err(TokenError::RebaseSupplyImmutable.into())
+        );
+        assert_eq!(
+            config.rebase_authority_for(&other, Some(other)),
+            Err(TokenError::RebaseSupplyImmutable.into())
+        );
+
+        // fall back to the mint authority, but not once it's revoked
+        config.use_mint_authority_fallback = true.into();
+        assert_eq!(
+            config.rebase_authority_for(&rebase_delegate, Some(other)),
+            Ok(other)
+        );
+        assert_eq!(
+            config.rebase_authority_for(&other, None),
+            Err(TokenError::RebaseSupplyImmutable.into())
+        );
+        config.supply_authority = Some(supply_authority).try_into().unwrap();
+        assert_eq!(
+            config.rebase_authority_for(&other, Some(other)),
+            Ok(supply_authority)
+        );
+    }
+
+    /// Offset of a field from the start of the struct holding it
+    macro_rules! field_offset {
+        ($value:expr, $field:ident) => {
+            std::ptr::addr_of!($value.$field) as usize - std::ptr::addr_of!($value) as usize
+        };
+    }
+
+    #[test]
+    fn config_layout() {
+        let config = RebaseMintConfig::default();
+        assert_eq!(field_offset!(config, supply_authority), 0);
+        assert_eq!(field_offset!(config, total_supply), 32);
+        assert_eq!(field_offset!(config, total_shares), 40);
+        assert_eq!(field_offset!(config, reserve_account), 48);
+        assert_eq!(field_offset!(config, first_rebase_after_timestamp), 80);
+        assert_eq!(field_offset!(config, pending_supply_authority), 88);
+        assert_eq!(field_offset!(config, two_step_authority_transfer), 120);
+        assert_eq!(field_offset!(config, authority_change_delay_seconds), 121);
+        assert_eq!(field_offset!(config, scheduled_supply_authority), 125);
+        assert_eq!(
+            field_offset!(config, authority_change_requested_timestamp),
+            157
+        );
+        assert_eq!(field_offset!(config, rebase_delegate), 165);
+        assert_eq!(field_offset!(config, use_mint_authority_fallback), 197);
+        assert_eq!(field_offset!(config, require_rebase_memo), 198);
+        assert_eq!(field_offset!(config, has_rebased), 199);
+        assert_eq!(field_offset!(config, exempt_supply), 200);
+        assert_eq!(field_offset!(config, outstanding_shares), 208);
+        assert_eq!(field_offset!(config, virtual_shares_offset), 216);
+        assert_eq!(field_offset!(config, allow_supply_decrease), 224);
+        assert_eq!(field_offset!(config, cached_ratio), 225);
+        assert_eq!(field_offset!(config, rebase_fee_bps), 241);
+        assert_eq!(field_offset!(config, fee_destination), 243);
+        assert_eq!(field_offset!(config, max_total_shares), 275);
+        assert_eq!(field_offset!(config, allow_sunset), 283);
+        assert_eq!(field_offset!(config, is_sunset), 284);
+        assert_eq!(field_offset!(config, update_metadata_rate), 285);
+        assert_eq!(field_offset!(config, is_retired), 286);
+        assert_eq!(field_offset!(config, retired_supply), 287);
+        assert_eq!(field_offset!(config, retired_shares), 295);
+        assert_eq!(field_offset!(config, co_signer), 303);
+        assert_eq!(field_offset!(config, rebase_mode), 335);
+        assert_eq!(field_offset!(config, rebase_index), 336);
+        assert_eq!(field_offset!(config, sync_base_supply), 352);
+        assert_eq!(field_offset!(config, cumulative_supply_increase), 353);
+        assert_eq!(field_offset!(config, cumulative_supply_decrease), 369);
+        assert_eq!(field_offset!(config, cached_inverse_ratio), 385);
+
+        let account = RebaseAccount::default();
+        assert_eq!(field_offset!(account, shares), 0);
+        assert_eq!(field_offset!(account, rebase_exempt), 8);
+    }
+
+    #[test]
+    fn state_layout() {
+        let state = RebaseState::default();
+        assert_eq!(field_offset!(state, version), 0);
+        assert_eq!(field_offset!(state, decimals), 1);
+        assert_eq!(field_offset!(state, mint_supply), 2);
+        assert_eq!(field_offset!(state, total_supply), 10);
+        assert_eq!(field_offset!(state, total_shares), 18);
+        assert_eq!(field_offset!(state, outstanding_shares), 26);
+        assert_eq!(field_offset!(state, exempt_supply), 34);
+        assert_eq!(field_offset!(state, virtual_shares_offset), 42);
+        assert_eq!(field_offset!(state, ratio), 50);
+        assert_eq!(field_offset!(state, supply_authority), 66);
+        assert_eq!(field_offset!(state, pending_supply_authority), 98);
+        assert_eq!(field_offset!(state, scheduled_supply_authority), 130);
+        assert_eq!(field_offset!(state, rebase_delegate), 162);
+        assert_eq!(field_offset!(state, reserve_account), 194);
+        assert_eq!(field_offset!(state, first_rebase_after_timestamp), 226);
+        assert_eq!(field_offset!(state, rebase_allowed), 234);
+        assert_eq!(field_offset!(state, has_rebased), 235);
+        assert_eq!(field_offset!(state, allow_supply_decrease), 236);
+        assert_eq!(field_offset!(state, require_rebase_memo), 237);
+        assert_eq!(field_offset!(state, rebase_mode), 238);
+        assert_eq!(field_offset!(state, rebase_index), 239);
+        // appending fields must come with a bump of the version
+        assert_eq!(std::mem::size_of::<RebaseState>(), 255);
+        assert_eq!(REBASE_STATE_VERSION, 2);
+    }
+
+    #[test]
+    fn instruction_data_layout() {
+        let data = instruction::InitializeInstructionData::zeroed();
+        assert_eq!(field_offset!(data, supply_authority), 0);
+        assert_eq!(field_offset!(data, initial_supply), 32);
+        assert_eq!(field_offset!(data, first_rebase_after_timestamp), 40);
+        assert_eq!(field_offset!(data, two_step_authority_transfer), 48);
+        assert_eq!(field_offset!(data, authority_change_delay_seconds), 49);
+        assert_eq!(field_offset!(data, use_mint_authority_fallback), 53);
+        assert_eq!(field_offset!(data, require_rebase_memo), 54);
+        assert_eq!(field_offset!(data, virtual_shares_offset), 55);
+        assert_eq!(field_offset!(data, allow_supply_decrease), 63);
+        assert_eq!(field_offset!(data, rebase_fee_bps), 64);
+        assert_eq!(field_offset!(data, fee_destination), 66);
+        assert_eq!(field_offset!(data, initial_shares), 98);
+        assert_eq!(field_offset!(data, max_total_shares), 106);
+        assert_eq!(field_offset!(data, allow_sunset), 114);
+        assert_eq!(field_offset!(data, update_metadata_rate), 115);
+        assert_eq!(field_offset!(data, co_signer), 116);
+        assert_eq!(field_offset!(data, rebase_mode), 148);
+
+        let data = instruction::RebaseSupplyData::zeroed();
+        assert_eq!(field_offset!(data, new_supply), 0);
+        assert_eq!(field_offset!(data, reason_code), 8);
+        assert_eq!(field_offset!(data, reference), 9);
+        assert_eq!(field_offset!(data, not_after_unix_timestamp), 41);
+    }
+
+    /// Run `f` on a copy of `bytes` that starts at an odd address, as account
+    /// and instruction data can
+    fn with_misaligned<R>(bytes: &[u8], f: impl FnOnce(&[u8]) -> R) -> R {
+        let mut buffer = vec![0; bytes.len() + 1];
+        let start = if buffer.as_ptr() as usize % 2 == 0 {
+            1
+        } else {
+            0
+        };
+        let misaligned = &mut buffer[start..start + bytes.len()];
+        misaligned.copy_from_slice(bytes);
+        f(misaligned)
+    }
+
+    #[test]
+    fn unpack_misaligned() {
+        let config = RebaseMintConfig {
+            total_supply: 2_000.into(),
+            total_shares: 1_000.into(),
+            virtual_shares_offset: 7.into(),
+            cached_ratio: u128::MAX.into(),
+            ..config(0, 0)
+        };
+        with_misaligned(bytemuck::bytes_of(&config), |bytes| {
+            assert_eq!(
+                spl_pod::bytemuck::pod_from_bytes::<RebaseMintConfig>(bytes),
+                Ok(&config)
+            );
+        });
+
+        let account = RebaseAccount {
+            shares: 1_000.into(),
+            rebase_exempt: true.into(),
+        };
+        with_misaligned(bytemuck::bytes_of(&account), |bytes| {
+            assert_eq!(
+                spl_pod::bytemuck::pod_from_bytes::<RebaseAccount>(bytes),
+                Ok(&account)
+            );
+        });
+
+        let supply_authority = Pubkey::new_unique();
+        let initialize = instruction::InitializeRebaseMintBuilder::new(&crate::id())
+            .mint(&Pubkey::new_unique())
+            .supply_authority(&supply_authority)
+            .initial_supply(1_000, 0)
+            .first_rebase_after_timestamp(-1)
+            .two_step_authority_transfer()
+            .authority_change_delay_seconds(u32::MAX)
+            .use_mint_authority_fallback()
+            .require_rebase_memo()
+            .virtual_shares_offset(u64::MAX)
+            .allow_supply_decrease()
+            .build()
+            .unwrap();
+        let rebase = instruction::update_supply(
+            &crate::id(),
+            &Pubkey::new_unique(),
+            None,
+            &supply_authority,
+            &[],
+            u64::MAX,
+        )
+        .unwrap();
+        for ix in [initialize, rebase] {
+            with_misaligned(&ix.data, |data| {
+                assert_eq!(
+                    instruction::decode(data).unwrap(),
+                    instruction::decode(&ix.data).unwrap()
+                );
+            });
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn round_trip_never_creates_shares(
+            config in test_utils::rebase_mint_config(),
+            shares in 0..=u64::MAX,
+        ) {
+            if let Some(amount) = config.shares_to_amount(shares) {
+                let round_trip_shares = config.amount_to_shares(amount).unwrap();
+                assert!(round_trip_shares <= shares);
+            }
+        }
+
+        #[test]
+        fn rounding_up_covers_amount(
+            config in test_utils::rebase_mint_config(),
+            amount in 0..=u64::MAX,
+        ) {
+            if let Some(shares) = config.amount_to_shares_rounding_up(amount) {
+                assert!(shares >= config.amount_to_shares(amount).unwrap());
+                assert!(config.shares_to_amount(shares).map_or(true, |a| a >= amount));
+            }
+        }
+
+        #[test]
+        fn cached_ratio_is_within_one_token(
+            total_supply in 0..=u64::MAX,
+            total_shares in 1..=u64::MAX,
+            shares in 0..=u64::MAX,
+        ) {
+            let mut config = config(total_supply, total_shares);
+            config.refresh_cached_ratio();
+            let exact = shares as u128 * total_supply as u128 / total_shares as u128;
+            // the truncated ratio alone never overestimates, and falls short by
+            // at most one token
+            if let Some(product) = (shares as u128).checked_mul(config.cached_ratio.into()) {
+                let estimate = product >> CACHED_RATIO_FRACTIONAL_BITS;
+                assert!(estimate <= exact);
+                assert!(exact - estimate <= 1);
+            }
+            // which the conversion corrects
+            assert_eq!(config.shares_to_amount(shares), u64::try_from(exact).ok());
+        }
+
+        #[test]
+        fn cached_inverse_ratio_is_within_one_share(
+            total_supply in 1..=u64::MAX,
+            total_shares in 0..=u64::MAX,
+            amount in 0..=u64::MAX,
+        ) {
+            let mut config = config(total_supply, total_shares);
+            config.refresh_cached_ratio();
+            let exact = amount as u128 * total_shares as u128 / total_supply as u128;
+            if let Some(product) = (amount as u128).checked_mul(config.cached_inverse_ratio.into()) {
+                let estimate = product >> CACHED_RATIO_FRACTIONAL_BITS;
+                assert!(estimate <= exact);
+                assert!(exact - estimate <= 1);
+            }
+            let expected = if total_shares == 0 { Some(amount) } else { u64::try_from(exact).ok() };
+            assert_eq!(config.amount_to_shares(amount), expected);
+        }
+
+        #[test]
+        fn rebases_never_drift(
+            total_shares in 1..=1_000_000_000_000u64,
+            held_shares in 0..=1_000_000_000_000u64,
+            new_supplies in prop::collection::vec(1..=1_000_000_000_000_000u64, 100..500),
+        ) {
+            let held_shares = held_shares.min(total_shares);
+            let mut fixture = test_utils::RebaseMintFixture::new(config(total_shares, total_shares));
+            for new_supply in new_supplies {
+                let preview = fixture.config().preview_rebase(new_supply).unwrap();
+                assert!(u64::from(preview.rounding_error) < total_shares);
+                fixture.rebase(new_supply, 0).unwrap();
+                let config = fixture.config();
+                // shares are never rescaled, so there is nothing to accumulate
+                assert_eq!(u64::from(config.total_shares), total_shares);
+                let exact = held_shares as u128 * new_supply as u128 / total_shares as u128;
+                assert_eq!(config.shares_to_amount(held_shares), Some(exact as u64));
+            }
+        }
+    }
+}
@@ -0,0 +1,402 @@
+//! Off-chain helpers to read the balances of many token accounts of rebasing
+//! mints at once
+
+pub use crate::offchain::AccountFetchError;
+use {
+    crate::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{LazyRebaseAccount, RebaseAccount, RebaseMintConfig},
+            BaseStateWithExtensions, StateWithExtensions,
+        },
+        state::{Account, Mint},
+    },
+    solana_program::pubkey::Pubkey,
+    std::{collections::HashMap, future::Future},
+};
+
+/// Most accounts that RPC nodes return from a single `getMultipleAccounts`
+/// request
+pub const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+/// Data of each requested account, in order, or `None` for accounts that
+/// don't exist
+pub type MultipleAccountsDataResult = Result<Vec<Option<Vec<u8>>>, AccountFetchError>;
+
+/// Balance of a token account, adjusted for the rebases of its mint
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RebaseAdjustedBalance {
+    /// Account of a rebasing mint, holding `shares` worth `amount` tokens at
+    /// the current ratio. Exempt accounts hold no shares, only their fixed
+    /// `amount`, and neither do accounts of lazy mints, whose `amount`
+    /// includes the rebases they haven't caught up to yet.
+    Rebasing {
+        /// Shares held by the account
+        shares: u64,
+        /// Tokens that the account holds at the current ratio
+        amount: u64,
+    },
+    /// Account of a mint without the rebasing mint extension, holding its
+    /// `amount` as is
+    NotRebasing {
+        /// Tokens held by the account
+        amount: u64,
+    },
+    /// The account or its mint doesn't exist or can't be read as such
+    Missing,
+}
+
+async fn fetch_in_chunks<F, Fut>(
+    addresses: &[Pubkey],
+    fetch_multiple_accounts_data_fn: &F,
+) -> Result<Vec<Option<Vec<u8>>>, AccountFetchError>
+where
+    F: Fn(Vec<Pubkey>) -> Fut,
+    Fut: Future<Output = MultipleAccountsDataResult>,
+{
+    let mut accounts_data = Vec::with_capacity(addresses.len());
+    for chunk in addresses.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        let chunk_data = fetch_multiple_accounts_data_fn(chunk.to_vec()).await?;
+        if chunk_data.len() != chunk.len() {
+            return Err(format!(
+                "Expected data for {} accounts, got {}",
+                chunk.len(),
+                chunk_data.len()
+            )
+            .into());
+        }
+        accounts_data.extend(chunk_data);
+    }
+    Ok(accounts_data)
+}
+
+/// Offchain helper to get the rebase-adjusted balances of many token accounts,
+/// possibly of different mints, in as few requests as possible.
+///
+/// The accounts are fetched in chunks of `MAX_MULTIPLE_ACCOUNTS`, then each of
+/// their mints once, also in chunks. To be client-agnostic, this takes a
+/// function that returns the data of several accounts at once, in the same
+/// order, as `getMultipleAccounts` does. Can be called in the following way:
+///
+/// ```rust,ignore
+/// let balances = get_rebase_adjusted_balances(&accounts, |addresses| {
+///     rpc_client
+///         .get_multiple_accounts(&addresses)
+///         .map_ok(|accounts| accounts.into_iter().map(|a| a.map(|a| a.data)).collect())
+///         .map_err(Into::into)
+/// })
+/// .await?
+/// ```
+///
+/// Returns the balances in the order of `account_pubkeys`.
+pub async fn get_rebase_adjusted_balances<F, Fut>(
+    account_pubkeys: &[Pubkey],
+    fetch_multiple_accounts_data_fn: F,
+) -> Result<Vec<(Pubkey, RebaseAdjustedBalance)>, AccountFetchError>
+where
+    F: Fn(Vec<Pubkey>) -> Fut,
+    Fut: Future<Output = MultipleAccountsDataResult>,
+{
+    let accounts_data = fetch_in_chunks(account_pubkeys, &fetch_multiple_accounts_data_fn).await?;
+    let accounts = accounts_data
+        .iter()
+        .map(|data| {
+            data.as_ref()
+                .and_then(|data| StateWithExtensions::<Account>::unpack(data).ok())
+        })
+        .collect::<Vec<_>>();
+
+    let mut mint_pubkeys = vec![];
+    for account in accounts.iter().flatten() {
+        if !mint_pubkeys.contains(&account.base.mint) {
+            mint_pubkeys.push(account.base.mint);
+        }
+    }
+    let mints_data = fetch_in_chunks(&mint_pubkeys, &fetch_multiple_accounts_data_fn).await?;
+    let mint_configs = mint_pubkeys
+        .into_iter()
+        .zip(mints_data)
+        .filter_map(|(mint_pubkey, data)| {
+            let mint = StateWithExtensions::<Mint>::unpack(data.as_ref()?).ok()?;
+            Some((
+                mint_pubkey,
+                mint.get_extension::<RebaseMintConfig>().ok().copied(),
+            ))
+        })
+        .collect::<HashMap<_, _>>();
+
+    account_pubkeys
+        .iter()
+        .zip(accounts)
+        .map(|(pubkey, account)| -> Result<_, AccountFetchError> {
+            let Some(account) = account else {
+                return Ok((*pubkey, RebaseAdjustedBalance::Missing));
+            };
+            let balance = match mint_configs.get(&account.base.mint) {
+                None => RebaseAdjustedBalance::Missing,
+                Some(None) => RebaseAdjustedBalance::NotRebasing {
+                    amount: account.base.amount,
+                },
+                Some(Some(config)) if config.is_lazy() => {
+                    match account.get_extension::<LazyRebaseAccount>() {
+                        Err(_) => RebaseAdjustedBalance::Missing,
+                        Ok(lazy_account) => RebaseAdjustedBalance::Rebasing {
+                            shares: 0,
+                            amount: config
+                                .lazy_amount(
+                                    account.base.amount,
+                                    u128::from(lazy_account.last_seen_rebase_index),
+                                )
+                                .ok_or(TokenError::Overflow)?,
+                        },
+                    }
+                }
+                Some(Some(config)) => match account.get_extension::<RebaseAccount>() {
+                    Err(_) => RebaseAdjustedBalance::Missing,
+                    Ok(rebase_account) if rebase_account.is_exempt() => {
+                        RebaseAdjustedBalance::Rebasing {
+                            shares: 0,
+                            amount: account.base.amount,
+                        }
+                    }
+                    Ok(rebase_account) => {
+                        let shares = u64::from(rebase_account.shares);
+                        RebaseAdjustedBalance::Rebasing {
+                            shares,
+                            amount: config
+                                .shares_to_amount(shares)
+                                .ok_or(TokenError::Overflow)?,
+                        }
+                    }
+                },
+            };
+            Ok((*pubkey, balance))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            extension::{ExtensionType, StateWithExtensionsMut},
+            state::AccountState,
+        },
+        solana_program::{program_option::COption, program_pack::Pack},
+        solana_program_test::tokio,
+        std::sync::Mutex,
+    };
+
+    fn mint_data(config: Option<RebaseMintConfig>) -> Vec<u8> {
+        let extension_types = config
+            .map(|_| vec![ExtensionType::RebaseMintConfig])
+            .unwrap_or_default();
+        let mut data =
+            vec![0; ExtensionType::try_calculate_account_len::<Mint>(&extension_types).unwrap()];
+        let mut mint = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+        if let Some(config) = config {
+            *mint.init_extension::<RebaseMintConfig>(true).unwrap() = config;
+        }
+        mint.base.mint_authority = COption::Some(Pubkey::new_unique());
+        mint.base.is_initialized = true;
+        mint.pack_base();
+        mint.init_account_type().unwrap();
+        data
+    }
+
+    fn account_data(mint: Pubkey, amount: u64, rebase_account: Option<RebaseAccount>) -> Vec<u8> {
+        let extension_types = rebase_account
+            .map(|_| vec![ExtensionType::RebaseAccount])
+            .unwrap_or_default();
+        let mut data =
+            vec![0; ExtensionType::try_calculate_account_len::<Account>(&extension_types).unwrap()];
+        let mut account =
+            StateWithExtensionsMut::<Account>::unpack_uninitialized(&mut data).unwrap();
+        if let Some(rebase_account) = rebase_account {
+            *account.init_extension::<RebaseAccount>(true).unwrap() = rebase_account;
+        }
+        account.base.mint = mint;
+        account.base.owner = Pubkey::new_unique();
+        account.base.amount = amount;
+        account.base.state = AccountState::Initialized;
+        account.pack_base();
+        account.init_account_type().unwrap();
+        data
+    }
+
+    fn rebase_account(shares: u64, exempt: bool) -> Option<RebaseAccount> {
+        Some(RebaseAccount {
+            shares: shares.into(),
+            rebase_exempt: exempt.into(),
+        })
+    }
+
+    struct MockRpc {
+        accounts: HashMap<Pubkey, Vec<u8>>,
+        requests: Mutex<Vec<usize>>,
+    }
+
+    impl MockRpc {
+        async fn get_multiple_accounts(
+            &self,
+            addresses: Vec<Pubkey>,
+        ) -> MultipleAccountsDataResult {
+            assert!(addresses.len() <= MAX_MULTIPLE_ACCOUNTS);
+            self.requests.lock().unwrap().push(addresses.len());
+            Ok(addresses
+                .iter()
+                .map(|address| self.accounts.get(address).cloned())
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn mixed_mints_and_missing_accounts() {
+        let doubled_mint = Pubkey::new_unique();
+        let halved_mint = Pubkey::new_unique();
+        let plain_mint = Pubkey::new_unique();
+        let missing_mint = Pubkey::new_unique();
+        let doubled_config = RebaseMintConfig {
+            total_supply: 2_000.into(),
+            total_shares: 1_000.into(),
+            ..Default::default()
+        };
+        let halved_config = RebaseMintConfig {
+            total_supply: 500.into(),
+            total_shares: 1_000.into(),
+            ..Default::default()
+        };
+
+        let doubled_holder = Pubkey::new_unique();
+        let doubled_exempt = Pubkey::new_unique();
+        let halved_holder = Pubkey::new_unique();
+        let plain_holder = Pubkey::new_unique();
+        let orphan = Pubkey::new_unique();
+        let not_an_account = Pubkey::new_unique();
+        let nonexistent = Pubkey::new_unique();
+        let mut accounts = HashMap::new();
+        accounts.insert(doubled_mint, mint_data(Some(doubled_config)));
+        accounts.insert(halved_mint, mint_data(Some(halved_config)));
+        accounts.insert(plain_mint, mint_data(None));
+        accounts.insert(
+            doubled_holder,
+            account_data(doubled_mint, 0, rebase_account(300, false)),
+        );
+        accounts.insert(
+            doubled_exempt,
+            account_data(doubled_mint, 77, rebase_account(0, true)),
+        );
+        accounts.insert(
+            halved_holder,
+            account_data(halved_mint, 0, rebase_account(301, false)),
+        );
+        accounts.insert(plain_holder, account_data(plain_mint, 42, None));
+        accounts.insert(orphan, account_data(missing_mint, 5, None));
+        accounts.insert(not_an_account, vec![1; Account::LEN - 1]);
+        let rpc = MockRpc {
+            accounts,
+            requests: Mutex::new(vec![]),
+        };
+
+        let balances = get_rebase_adjusted_balances(
+            &[
+                doubled_holder,
+                halved_holder,
+                nonexistent,
+                plain_holder,
+                doubled_exempt,
+                orphan,
+                not_an_account,
+            ],
+            |addresses| rpc.get_multiple_accounts(addresses),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            balances,
+            vec![
+                (
+                    doubled_holder,
+                    RebaseAdjustedBalance::Rebasing {
+                        shares: 300,
+                        amount: 600,
+                    }
+                ),
+                (
+                    halved_holder,
+                    RebaseAdjustedBalance::Rebasing {
+                        shares: 301,
+                        amount: 150,
+                    }
+                ),
+                (nonexistent, RebaseAdjustedBalance::Missing),
+                (
+                    plain_holder,
+                    RebaseAdjustedBalance::NotRebasing { amount: 42 }
+                ),
+                (
+                    doubled_exempt,
+                    RebaseAdjustedBalance::Rebasing {
+                        shares: 0,
+                        amount: 77,
+                    }
+                ),
+                (orphan, RebaseAdjustedBalance::Missing),
+                (not_an_account, RebaseAdjustedBalance::Missing),
+            ]
+        );
+        // one request for the accounts, one for their four distinct mints
+        assert_eq!(*rpc.requests.lock().unwrap(), vec![7, 4]);
+    }
+
+    #[tokio::test]
+    async fn chunks_large_requests() {
+        let mint = Pubkey::new_unique();
+        let config = RebaseMintConfig {
+            total_supply: 3_000.into(),
+            total_shares: 1_000.into(),
+            ..Default::default()
+        };
+        let mut accounts = HashMap::new();
+        accounts.insert(mint, mint_data(Some(config)));
+        let holders = (0..MAX_MULTIPLE_ACCOUNTS as u64 * 2 + 1)
+            .map(|shares| {
+                let holder = Pubkey::new_unique();
+                accounts.insert(holder, account_data(mint, 0, rebase_account(shares, false)));
+                holder
+            })
+            .collect::<Vec<_>>();
+        let rpc = MockRpc {
+            accounts,
+            requests: Mutex::new(vec![]),
+        };
+
+        let balances = get_rebase_adjusted_balances(&holders, |addresses| {
+            rpc.get_multiple_accounts(addresses)
+        })
+        .await
+        .unwrap();
+        for (shares, (holder, balance)) in balances.into_iter().enumerate() {
+            assert_eq!(holder, holders[shares]);
+            assert_eq!(
+                balance,
+                RebaseAdjustedBalance::Rebasing {
+                    shares: shares as u64,
+                    amount: shares as u64 * 3,
+                }
+            );
+        }
+        assert_eq!(
+            *rpc.requests.lock().unwrap(),
+            vec![MAX_MULTIPLE_ACCOUNTS, MAX_MULTIPLE_ACCOUNTS, 1, 1]
+        );
+    }
+
+    #[tokio::test]
+    async fn fail_short_response() {
+        let result =
+            get_rebase_adjusted_balances(&[Pubkey::new_unique()], |_| async { Ok(vec![]) }).await;
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,41 @@
+use {
+    crate::extension::rebase_mint::instruction,
+    solana_program::{
+        account_info::AccountInfo, entrypoint::ProgramResult, program::invoke_signed,
+        pubkey::Pubkey,
+    },
+};
+
+/// Helper to CPI into token-2022 on-chain to rebase the supply of a rebasing
+/// mint, typically with a program-derived address as the supply authority.
+///
+/// If the authority is a multisig, `signer_infos` are its signing members,
+/// any of which may be a program-derived address signing through `seeds`.
+pub fn invoke_rebase_supply<'a>(
+    token_program_id: &Pubkey,
+    mint_info: AccountInfo<'a>,
+    reserve_account_info: Option<AccountInfo<'a>>,
+    authority_info: AccountInfo<'a>,
+    signer_infos: &[AccountInfo<'a>],
+    new_supply: u64,
+    seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let signer_pubkeys = signer_infos.iter().map(|ai| ai.key).collect::<Vec<_>>();
+    let cpi_instruction = instruction::update_supply(
+        token_program_id,
+        mint_info.key,
+        reserve_account_info.as_ref().map(|ai| ai.key),
+        authority_info.key,
+        &signer_pubkeys,
+        new_supply,
+    )?;
+
+    let mut cpi_account_infos = vec![mint_info];
+    if let Some(reserve_account_info) = reserve_account_info {
+        cpi_account_infos.push(reserve_account_info);
+    }
+    cpi_account_infos.push(authority_info);
+    cpi_account_infos.extend_from_slice(signer_infos);
+
+    invoke_signed(&cpi_instruction, &cpi_account_infos, seeds)
+}
@@ -0,0 +1,246 @@
+//! Zero-copy reads of the rebasing mint configuration, for programs that only
+//! need the ratio of a mint passed in by account info
+
+use {
+    crate::{
+        extension::{
+            check_account_type, check_min_len_and_not_multisig, get_extension_bytes,
+            rebase_mint::RebaseMintConfig, type_and_tlv_indices, AccountType,
+            BASE_ACCOUNT_AND_TYPE_LENGTH,
+        },
+        state::Mint,
+    },
+    solana_program::{program_error::ProgramError, program_pack::Pack},
+    spl_pod::bytemuck::pod_from_bytes,
+};
+
+/// Index of the `is_initialized` flag in the base mint, see state.rs
+const MINT_INITIALIZED_INDEX: usize = 45;
+/// Indices of the `COption` tags of the mint and freeze authorities
+const AUTHORITY_TAG_INDICES: [usize; 2] = [0, 46];
+
+/// Current ratio of a rebasing mint, as `supply` tokens for `shares` shares.
+///
+/// Both include the virtual offset used in conversions. Shares and tokens are
+/// reported 1:1 until shares have been issued.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RebaseRatio {
+    /// Tokens, including the virtual offset
+    pub supply: u128,
+    /// Shares, including the virtual offset
+    pub shares: u128,
+}
+
+/// Get the rebasing mint configuration from the data of a mint account,
+/// without unpacking the base mint or copying the extension.
+///
+/// Fails on the same data that `StateWithExtensions::<Mint>::unpack` rejects,
+/// or if the mint doesn't have the rebasing mint extension.
+pub fn get_rebase_config(account_data: &[u8]) -> Result<&RebaseMintConfig, ProgramError> {
+    check_min_len_and_not_multisig(account_data, BASE_ACCOUNT_AND_TYPE_LENGTH)?;
+    match account_data[MINT_INITIALIZED_INDEX] {
+        1 => {}
+        0 => return Err(ProgramError::UninitializedAccount),
+        _ => return Err(ProgramError::InvalidAccountData),
+    }
+    // `Mint::unpack` rejects any other tag, even if the key isn't read
+    for index in AUTHORITY_TAG_INDICES {
+        match account_data[index..index.saturating_add(4)] {
+            [0, 0, 0, 0] | [1, 0, 0, 0] => {}
+            _ => return Err(ProgramError::InvalidAccountData),
+        }
+    }
+    let rest = &account_data[Mint::LEN..];
+    let (account_type_index, tlv_start_index) =
+        type_and_tlv_indices::<Mint>(rest)?.ok_or(ProgramError::InvalidAccountData)?;
+    let account_type = AccountType::try_from(rest[account_type_index])
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+    check_account_type::<Mint>(account_type)?;
+    pod_from_bytes::<RebaseMintConfig>(get_extension_bytes::<Mint, RebaseMintConfig>(
+        &rest[tlv_start_index..],
+    )?)
+}
+
+/// Get the current ratio of a rebasing mint from the data of its account
+pub fn get_current_ratio(account_data: &[u8]) -> Result<RebaseRatio, ProgramError> {
+    let config = get_rebase_config(account_data)?;
+    let shares = config.virtual_total_shares();
+    if shares == 0 {
+        Ok(RebaseRatio {
+            supply: 1,
+            shares: 1,
+        })
+    } else {
+        Ok(RebaseRatio {
+            supply: config.virtual_total_supply(),
+            shares,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            extension::{
+                mint_close_authority::MintCloseAuthority, BaseStateWithExtensions, ExtensionType,
+                StateWithExtensions, StateWithExtensionsMut,
+            },
+            state::Account,
+        },
+        solana_program::{program_option::COption, pubkey::Pubkey},
+    };
+
+    // the rebasing config comes after another extension, to walk past it
+    fn mint_data(config: Option<RebaseMintConfig>) -> Vec<u8> {
+        let mut extension_types = vec![ExtensionType::MintCloseAuthority];
+        if config.is_some() {
+            extension_types.push(ExtensionType::RebaseMintConfig);
+        }
+        let mut data =
+            vec![0; ExtensionType::try_calculate_account_len::<Mint>(&extension_types).unwrap()];
+        let mut mint = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+        mint.init_extension::<MintCloseAuthority>(true).unwrap();
+        if let Some(config) = config {
+            *mint.init_extension::<RebaseMintConfig>(true).unwrap() = config;
+        }
+        mint.base.mint_authority = COption::Some(Pubkey::new_unique());
+        mint.base.is_initialized = true;
+        mint.pack_base();
+        mint.init_account_type().unwrap();
+        data
+    }
+
+    fn rebasing_mint_data(total_supply: u64, total_shares: u64, offset: u64) -> Vec<u8> {
+        mint_data(Some(RebaseMintConfig {
+            total_supply: total_supply.into(),
+            total_shares: total_shares.into(),
+            virtual_shares_offset: offset.into(),
+            ..Default::default()
+        }))
+    }
+
+    #[test]
+    fn matches_unpacked_mint() {
+        for (total_supply, total_shares, offset) in [(0, 0, 0), (1_500, 1_000, 0), (7, 3, 1)] {
+            let data = rebasing_mint_data(total_supply, total_shares, offset);
+            let state = StateWithExtensions::<Mint>::unpack(&data).unwrap();
+            let expected = state.get_extension::<RebaseMintConfig>().unwrap();
+            let config = get_rebase_config(&data).unwrap();
+            assert_eq!(config, expected);
+            // no copy was made
+            assert!(std::ptr::eq(config, expected));
+
+            let ratio = get_current_ratio(&data).unwrap();
+            for shares in [0, 1, 1_000, 123_456] {
+                let amount = (shares as u128 * ratio.supply / ratio.shares) as u64;
+                assert_eq!(Some(amount), expected.shares_to_amount(shares));
+            }
+        }
+    }
+
+    #[test]
+    fn misaligned_data() {
+        let data = rebasing_mint_data(7_000, 3_000, 1);
+        let expected = *get_rebase_config(&data).unwrap();
+        // one of the two starts puts the extension at an odd address
+        let mut buffer = vec![0; data.len() + 1];
+        for start in [0, 1] {
+            let misaligned = &mut buffer[start..start + data.len()];
+            misaligned.copy_from_slice(&data);
+            assert_eq!(get_rebase_config(misaligned), Ok(&expected));
+            let state = StateWithExtensions::<Mint>::unpack(misaligned).unwrap();
+            assert_eq!(state.get_extension::<RebaseMintConfig>(), Ok(&expected));
+        }
+    }
+
+    #[test]
+    fn ratio_is_one_to_one_without_shares() {
+        let data = rebasing_mint_data(0, 0, 0);
+        assert_eq!(
+            get_current_ratio(&data).unwrap(),
+            RebaseRatio {
+                supply: 1,
+                shares: 1
+            }
+        );
+        let data = rebasing_mint_data(1_500, 1_000, 1);
+        assert_eq!(
+            get_current_ratio(&data).unwrap(),
+            RebaseRatio {
+                supply: 1_501,
+                shares: 1_001
+            }
+        );
+    }
+
+    #[test]
+    fn fail_on_truncated_data() {
+        let data = rebasing_mint_data(1_500, 1_000, 0);
+        for len in [
+            0,
+            1,
+            Mint::LEN,
+            Account::LEN,
+            Account::LEN + 1,
+            data.len() - 1,
+        ] {
+            assert!(get_rebase_config(&data[..len]).is_err(), "{}", len);
+            assert!(get_current_ratio(&data[..len]).is_err(), "{}", len);
+        }
+    }
+
+    #[test]
+    fn fail_on_other_data() {
+        // mint without the extension
+        let data = mint_data(None);
+        let state = StateWithExtensions::<Mint>::unpack(&data).unwrap();
+        assert_eq!(
+            get_rebase_config(&data).unwrap_err(),
+            state.get_extension::<RebaseMintConfig>().unwrap_err()
+        );
+        // base mint without any extension
+        let data = vec![0; Mint::LEN];
+        assert_eq!(
+            get_rebase_config(&data).unwrap_err(),
+            ProgramError::InvalidAccountData
+        );
+
+        // uninitialized mint
+        let mut data = rebasing_mint_data(1_500, 1_000, 0);
+        data[MINT_INITIALIZED_INDEX] = 0;
+        assert_eq!(
+            get_rebase_config(&data).unwrap_err(),
+            ProgramError::UninitializedAccount
+        );
+
+        // token account of the same length
+        let mut data = rebasing_mint_data(1_500, 1_000, 0);
+        data[Account::LEN] = AccountType::Account.into();
+        assert_eq!(
+            get_rebase_config(&data).unwrap_err(),
+            ProgramError::InvalidAccountData
+        );
+
+        // nonzero padding between the mint and the account type
+        let mut data = rebasing_mint_data(1_500, 1_000, 0);
+        data[Mint::LEN] = 1;
+        assert_eq!(
+            get_rebase_config(&data).unwrap_err(),
+            ProgramError::InvalidAccountData
+        );
+
+        // invalid authority tags
+        for index in AUTHORITY_TAG_INDICES {
+            for tag in [2, 0x0100] {
+                let mut data = rebasing_mint_data(1_500, 1_000, 0);
+                data[index..index + 4].copy_from_slice(&u32::to_le_bytes(tag));
+                assert_eq!(
+                    get_rebase_config(&data).unwrap_err(),
+                    StateWithExtensions::<Mint>::unpack(&data).unwrap_err()
+                );
+            }
+        }
+    }
+}
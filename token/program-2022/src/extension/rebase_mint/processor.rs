@@ -0,0 +1,1745 @@
+use {
+    crate::{
+        check_program_account, check_spl_token_program_account,
+        error::TokenError,
+        extension::{
+            alloc_and_serialize_variable_len_extension,
+            memo_transfer::check_previous_sibling_instruction_is_memo,
+            rebase_mint::{
+                activation::check_active,
+                catch_up_lazy_account, credit_shares,
+                event::{
+                    log_event, RebaseClaimed, RebaseDelegateChanged, RebaseFeeChanged,
+                    RebaseMintInitialized, RebaseMintRetired, RebaseMintSunset, RebaseTagged,
+                    ReserveAccountChanged, RoundingDustSwept, SupplyAuthorityChangeCancelled,
+                    SupplyAuthorityChangeScheduled, SupplyAuthorityChanged,
+                    SupplyAuthorityProposalCancelled, SupplyAuthorityProposed,
+                },
+                instruction::{
+                    self, decode_rebase_supply_data, decode_rebase_supply_ui_data,
+                    AmountToSharesData, ApproveSharesData, BurnSharesData,
+                    InitializeInstructionData, MintToSharesData, ProposeSupplyAuthorityData,
+                    RebaseHistoryData, RebaseMintInstruction, RebaseSupplyData, RebaseSupplyUiTags,
+                    SetAccountExemptData, SetRebaseDelegateData, SetRebaseFeeData,
+                    SetReserveAccountData, SetSupplyAuthorityData, SharesToAmountData,
+                    TransferCheckedWithShareSlippageData, TransferSharesCheckedData,
+                    TransferSharesData, UnwrapSharesData, WrapSharesData,
+                },
+                is_exempt, set_account_exempt, try_ui_supply_into_supply,
+                wrapper::{
+                    get_escrow_address_and_bump_seed, get_wrapper_authority_address_and_bump_seed,
+                    get_wrapper_mint_address_and_bump_seed, ESCROW_SEED, WRAPPER_AUTHORITY_SEED,
+                    WRAPPER_MINT_SEED,
+                },
+                LazyRebaseAccount, RebaseAccount, RebaseConsistency, RebaseHistory,
+                RebaseHistoryEntry, RebaseMintConfig, RebaseMode, RebasePreview, RebaseState,
+                SupplyRebased, REBASE_INDEX_SCALE, REBASE_RATE_METADATA_KEY, REBASE_STATE_VERSION,
+            },
+            BaseStateWithExtensions, ExtensionType, StateWithExtensions, StateWithExtensionsMut,
+        },
+        instruction::{decode_instruction_data, decode_instruction_type},
+        processor::Processor,
+        state::{Account, Mint},
+    },
+    solana_program::{
+        account_info::{next_account_info, AccountInfo},
+        clock::Clock,
+        entrypoint::ProgramResult,
+        log::sol_log_data,
+        msg,
+        program::{invoke, invoke_signed, set_return_data},
+        program_error::ProgramError,
+        program_option::COption,
+        program_pack::Pack,
+        pubkey::Pubkey,
+        system_instruction,
+        sysvar::{rent::Rent, Sysvar},
+    },
+    spl_pod::{
+        optional_keys::OptionalNonZeroPubkey,
+        primitives::{PodBool, PodI64, PodU16, PodU32},
+    },
+    spl_token_metadata_interface::state::{Field, TokenMetadata},
+    std::slice::Iter,
+};
+
+#[allow(clippy::too_many_arguments)]
+fn process_initialize(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    supply_authority: &OptionalNonZeroPubkey,
+    initial_supply: u64,
+    first_rebase_after_timestamp: &PodI64,
+    two_step_authority_transfer: &PodBool,
+    authority_change_delay_seconds: &PodU32,
+    use_mint_authority_fallback: &PodBool,
+    require_rebase_memo: &PodBool,
+    virtual_shares_offset: u64,
+    allow_supply_decrease: &PodBool,
+    rebase_fee_bps: &PodU16,
+    fee_destination: &OptionalNonZeroPubkey,
+    initial_shares: u64,
+    max_total_shares: u64,
+    allow_sunset: &PodBool,
+    update_metadata_rate: &PodBool,
+    co_signer: &OptionalNonZeroPubkey,
+    rebase_mode: u8,
+    sync_base_supply: &PodBool,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let mint_account_info = next_account_info(account_info_iter)?;
+    // the runtime would reject the write anyway, but with a confusing error
+    check_program_account(mint_account_info.owner)?;
+
+    let rebase_mode =
+        RebaseMode::try_from(rebase_mode).map_err(|_| ProgramError::InvalidInstructionData)?;
+    if rebase_mode == RebaseMode::Lazy
+        && (initial_supply != 0
+            || initial_shares != 0
+            || virtual_shares_offset != 0
+            || u16::from(*rebase_fee_bps) != 0
+            || Option::<Pubkey>::from(*fee_destination).is_some()
+            || max_total_shares != 0
+            || bool::from(*allow_sunset)
+            || bool::from(*update_metadata_rate))
+    {
+        msg!("Lazy rebasing mints have no shares to configure");
+        return Err(TokenError::RebaseModeMismatch.into());
+    }
+
+    let initial_shares = if initial_shares == 0 {
+        initial_supply
+    } else {
+        initial_shares
+    };
+    if initial_supply == 0 && initial_shares != 0 {
+        msg!(
+            "Cannot divide an initial supply of zero into {} shares",
+            initial_shares
+        );
+        return Err(TokenError::RebaseSupplyZero.into());
+    }
+    // the base supply starts at zero, and would never account for tokens
+    // that were not minted
+    if bool::from(*sync_base_supply) && initial_supply != 0 {
+        msg!(
+            "Cannot sync the base supply to an initial supply of {}",
+            initial_supply
+        );
+        return Err(TokenError::RebaseBaseSupplyMismatch.into());
+    }
+
+    let mut mint_data = mint_account_info.data.borrow_mut();
+    let mut mint = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut mint_data)?;
+
+    let extension = mint.init_extension::<RebaseMintConfig>(true)?;
+    extension.supply_authority = *supply_authority;
+    extension.total_supply = initial_supply.into();
+    extension.total_shares = initial_shares.into();
+    extension.reserve_account = OptionalNonZeroPubkey::default();
+    extension.first_rebase_after_timestamp = *first_rebase_after_timestamp;
+    extension.pending_supply_authority = OptionalNonZeroPubkey::default();
+    extension.two_step_authority_transfer = *two_step_authority_transfer;
+    extension.authority_change_delay_seconds = *authority_change_delay_seconds;
+    extension.cancel_authority_change();
+    extension.rebase_delegate = OptionalNonZeroPubkey::default();
+    extension.use_mint_authority_fallback = *use_mint_authority_fallback;
+    extension.require_rebase_memo = *require_rebase_memo;
+    extension.has_rebased = false.into();
+    extension.exempt_supply = 0.into();
+    extension.outstanding_shares = 0.into();
+    extension.virtual_shares_offset = virtual_shares_offset.into();
+    extension.allow_supply_decrease = *allow_supply_decrease;
+    extension.set_rebase_fee((*rebase_fee_bps).into(), *fee_destination)?;
+    extension.max_total_shares = max_total_shares.into();
+    extension.check_total_shares(initial_shares)?;
+    extension.allow_sunset = *allow_sunset;
+    extension.is_sunset = false.into();
+    extension.update_metadata_rate = *update_metadata_rate;
+    extension.co_signer = *co_signer;
+    extension.rebase_mode = rebase_mode.into();
+    extension.rebase_index = if rebase_mode == RebaseMode::Lazy {
+        REBASE_INDEX_SCALE
+    } else {
+        0
+    }
+    .into();
+    extension.sync_base_supply = *sync_base_supply;
+    extension.refresh_cached_ratio();
+    log_event(
+        mint_account_info.key,
+        &RebaseMintInitialized {
+            supply_authority: *supply_authority,
+            initial_supply: initial_supply.into(),
+            first_rebase_after_timestamp: *first_rebase_after_timestamp,
+            authority_change_delay_seconds: *authority_change_delay_seconds,
+            virtual_shares_offset: virtual_shares_offset.into(),
+        },
+    )
+}
+
+/// Reads the amount held by the reserve account, if the mint has one
+/// configured, from the next account in the iterator
+fn next_reserve_amount(
+    extension: &RebaseMintConfig,
+    account_info_iter: &mut Iter<'_, AccountInfo<'_>>,
+) -> Result<Option<u64>, ProgramError> {
+    if let Some(reserve_account) = Option::<Pubkey>::from(extension.reserve_account) {
+        let reserve_account_info = next_account_info(account_info_iter)?;
+        if *reserve_account_info.key != reserve_account {
+            return Err(TokenError::RebaseReserveMismatch.into());
+        }
+        check_spl_token_program_account(reserve_account_info.owner)?;
+        let reserve_account_data = reserve_account_info.try_borrow_data()?;
+        let reserve = StateWithExtensions::<Account>::unpack(&reserve_account_data)?;
+        Ok(Some(reserve.base.amount))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Reads the fee destination account, if the mint has one configured, from
+/// the next account in the iterator
+fn next_fee_destination<'a, 'b>(
+    extension: &RebaseMintConfig,
+    account_info_iter: &mut Iter<'a, AccountInfo<'b>>,
+) -> Result<Option<&'a AccountInfo<'b>>, ProgramError> {
+    if let Some(fee_destination) = Option::<Pubkey>::from(extension.fee_destination) {
+        let fee_destination_info = next_account_info(account_info_iter)?;
+        if *fee_destination_info.key != fee_destination {
+            return Err(TokenError::RebaseFeeDestinationMismatch.into());
+        }
+        Ok(Some(fee_destination_info))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Reads the payer and system program used to grow the mint's
+/// token-metadata, if the mint writes its rate there, from the next accounts
+/// in the iterator
+fn next_metadata_payer<'a, 'b>(
+    extension: &RebaseMintConfig,
+    account_info_iter: &mut Iter<'a, AccountInfo<'b>>,
+) -> Result<Option<(&'a AccountInfo<'b>, &'a AccountInfo<'b>)>, ProgramError> {
+    if bool::from(extension.update_metadata_rate) {
+        let payer_info = next_account_info(account_info_iter)?;
+        let system_program_info = next_account_info(account_info_iter)?;
+        Ok(Some((payer_info, system_program_info)))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Checks that the next account in the iterator is the mint's co-signer and
+/// signed the instruction, if the mint has one configured
+fn check_co_signer(
+    extension: &RebaseMintConfig,
+    account_info_iter: &mut Iter<'_, AccountInfo<'_>>,
+) -> ProgramResult {
+    if let Some(co_signer) = Option::<Pubkey>::from(extension.co_signer) {
+        let co_signer_info = next_account_info(account_info_iter)?;
+        if *co_signer_info.key != co_signer {
+            return Err(TokenError::RebaseCoSignerMismatch.into());
+        }
+        if !co_signer_info.is_signer {
+            msg!("The co-signer must sign alongside the authority");
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+    }
+    Ok(())
+}
+
+/// Writes the scaled exchange rate into the mint's own token-metadata, if it
+/// has any, topping up the mint's rent from the payer if the metadata grows
+fn update_rebase_rate_metadata<'a>(
+    mint_info: &AccountInfo<'a>,
+    payer_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    tokens_per_share_scaled: u128,
+) -> ProgramResult {
+    let mut token_metadata = {
+        let mint_data = mint_info.try_borrow_data()?;
+        let mint = StateWithExtensions::<Mint>::unpack(&mint_data)?;
+        if mint.get_extension_bytes::<TokenMetadata>().is_err() {
+            return Ok(());
+        }
+        mint.get_variable_len_extension::<TokenMetadata>()?
+    };
+    token_metadata.update(
+        Field::Key(REBASE_RATE_METADATA_KEY.to_string()),
+        tokens_per_share_scaled.to_string(),
+    );
+    alloc_and_serialize_variable_len_extension::<Mint, _>(mint_info, &token_metadata, true)?;
+
+    let lamports_diff = Rent::get()?
+        .minimum_balance(mint_info.data_len())
+        .saturating_sub(mint_info.lamports());
+    if lamports_diff > 0 {
+        invoke(
+            &system_instruction::transfer(payer_info.key, mint_info.key, lamports_diff),
+            &[
+                payer_info.clone(),
+                mint_info.clone(),
+                system_program_info.clone(),
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+/// Credits shares that no token account held before, such as those issued
+/// as rebase fee or swept as rounding dust, to a rebasing account of the mint
+fn credit_unheld_shares(
+    extension: &RebaseMintConfig,
+    mint_key: &Pubkey,
+    destination_info: &AccountInfo,
+    shares: u64,
+) -> ProgramResult {
+    check_program_account(destination_info.owner)?;
+    let mut destination_data = destination_info.try_borrow_mut_data()?;
+    let mut destination = StateWithExtensionsMut::<Account>::unpack(&mut destination_data)?;
+    if destination.base.mint != *mint_key {
+        return Err(TokenError::MintMismatch.into());
+    }
+    if destination.base.is_frozen() {
+        return Err(TokenError::AccountFrozen.into());
+    }
+    if is_exempt(&destination) {
+        msg!("Destination is exempt from rebasing");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    credit_shares(extension, &mut destination, shares)?;
+    destination.pack_base();
+    Ok(())
+}
+
+/// Checks that a rebase with a deadline, if it has one, executes by then
+fn check_rebase_deadline(not_after_unix_timestamp: i64) -> ProgramResult {
+    if not_after_unix_timestamp == 0 {
+        return Ok(());
+    }
+    let clock = Clock::get()?;
+    if clock.unix_timestamp > not_after_unix_timestamp {
+        msg!(
+            "Rebase expired at {}, now {}",
+            not_after_unix_timestamp,
+            clock.unix_timestamp
+        );
+        return Err(TokenError::RebaseExpired.into());
+    }
+    Ok(())
+}
+
+/// Checks that the supply can be rebased to `new_supply` and computes the
+/// outcome
+fn check_rebase(
+    extension: &RebaseMintConfig,
+    new_supply: u64,
+    reserve_amount: Option<u64>,
+) -> Result<RebasePreview, ProgramError> {
+    let clock = Clock::get()?;
+    if !extension.is_rebase_allowed_at(clock.unix_timestamp) {
+        msg!(
+            "Rebasing is not allowed until {}",
+            i64::from(extension.first_rebase_after_timestamp)
+        );
+        return Err(TokenError::RebaseNotYetAllowed.into());
+    }
+    let preview = extension.preview_rebase(new_supply).map_err(|e| {
+        msg!(
+            "Cannot rebase the supply from {} to {}",
+            u64::from(extension.total_supply),
+            new_supply
+        );
+        e
+    })?;
+    if let Some(reserve_amount) = reserve_amount {
+        extension
+            .check_reserve(new_supply, reserve_amount)
+            .map_err(|e| {
+                msg!(
+                    "New supply {} and exempt supply {} exceed reserve of {}",
+                    new_supply,
+                    u64::from(extension.exempt_supply),
+                    reserve_amount
+                );
+                e
+            })?;
+    }
+    Ok(preview)
+}
+
+fn process_rebase_supply(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_supply: u64,
+    reason_code: u8,
+    reference: [u8; 32],
+    not_after_unix_timestamp: i64,
+) -> ProgramResult {
+    check_rebase_deadline(not_after_unix_timestamp)?;
+    let account_info_iter = &mut accounts.iter();
+    let mint_account_info = next_account_info(account_info_iter)?;
+    check_program_account(mint_account_info.owner)?;
+
+    // the mint isn't borrowed while the other accounts are read, so that any
+    // of them aliasing the mint fails cleanly instead of panicking
+    let (config, mint_authority) = {
+        let mint_data = mint_account_info.try_borrow_data()?;
+        let mint = StateWithExtensions::<Mint>::unpack(&mint_data)?;
+        (
+            *mint.get_extension::<RebaseMintConfig>()?,
+            Option::<Pubkey>::from(mint.base.mint_authority),
+        )
+    };
+    config.check_not_sunset()?;
+    config.check_not_retired()?;
+    let reserve_amount = next_reserve_amount(&config, account_info_iter)?;
+    let fee_destination_info = next_fee_destination(&config, account_info_iter)?;
+    let metadata_payer_infos = next_metadata_payer(&config, account_info_iter)?;
+    check_co_signer(&config, account_info_iter)?;
+
+    let owner_info = next_account_info(account_info_iter)?;
+    let owner_info_data_len = owner_info.try_data_len()?;
+    let authority = config.rebase_authority_for(owner_info.key, mint_authority)?;
+
+    Processor::validate_owner(
+        program_id,
+        &authority,
+        owner_info,
+        owner_info_data_len,
+        account_info_iter.as_slice(),
+    )?;
+
+    if bool::from(config.require_rebase_memo) {
+        check_previous_sibling_instruction_is_memo().map_err(|_| TokenError::RebaseMemoRequired)?;
+    }
+
+    let mut mint_data = mint_account_info.try_borrow_mut_data()?;
+    let mut mint = StateWithExtensionsMut::<Mint>::unpack(&mut mint_data)?;
+    let mint_supply = mint.base.supply;
+    let extension = mint.get_extension_mut::<RebaseMintConfig>()?;
+    let preview = check_rebase(extension, new_supply, reserve_amount)?;
+    extension.check_base_supply(mint_supply)?;
+    // the fee is issued out of the new supply, so the total supply lands on
+    // the previewed one either way
+    let synced_base_supply = if bool::from(extension.sync_base_supply) {
+        Some(
+            extension
+                .backed_supply(preview.new_supply.into())
+                .ok_or(TokenError::RebaseTooLarge)?,
+        )
+    } else {
+        None
+    };
+    let previous_supply = extension.total_supply;
+    let fee = extension.apply_rebase_with_fee(&preview)?;
+    let event = SupplyRebased {
+        previous_supply,
+        new_supply: preview.new_supply,
+        total_shares: extension.total_shares,
+        exempt_supply: extension.exempt_supply,
+        fee_shares: fee.shares.into(),
+    };
+    if let Some(fee_destination_info) = fee_destination_info {
+        credit_unheld_shares(
+            extension,
+            mint_account_info.key,
+            fee_destination_info,
+            fee.shares,
+        )?;
+    }
+    let tokens_per_share_scaled = extension.tokens_per_share_scaled();
+    let cumulative_supply_increase = extension.cumulative_supply_increase;
+    let cumulative_supply_decrease = extension.cumulative_supply_decrease;
+    let entry = RebaseHistoryEntry {
+        unix_timestamp: Clock::get()?.unix_timestamp.into(),
+        old_supply: event.previous_supply,
+        new_supply: extension.total_supply,
+        new_shares: extension.total_shares,
+        reason_code,
+        reference,
+    };
+    if let Ok(history) = mint.get_extension_mut::<RebaseHistory>() {
+        history.record(entry);
+    }
+    if let Some(synced_base_supply) = synced_base_supply {
+        mint.base.supply = synced_base_supply;
+        mint.pack_base();
+    }
+    sol_log_data(&[bytemuck::bytes_of(&event)]);
+    log_event(
+        mint_account_info.key,
+        &RebaseTagged {
+            previous_supply: entry.old_supply,
+            new_supply: entry.new_supply,
+            reason_code,
+            reference,
+            cumulative_supply_increase,
+            cumulative_supply_decrease,
+        },
+    )?;
+
+    if let Some((payer_info, system_program_info)) = metadata_payer_infos {
+        // the metadata may need to realloc the mint
+        drop(mint_data);
+        update_rebase_rate_metadata(
+            mint_account_info,
+            payer_info,
+            system_program_info,
+            tokens_per_share_scaled,
+        )?;
+    }
+    Ok(())
+}
+
+/// Converts a new supply in UI units with the mint's decimals, then rebases
+/// to it like `RebaseSupply`
+fn process_rebase_supply_ui(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ui_supply: &str,
+    tags: RebaseSupplyUiTags,
+) -> ProgramResult {
+    let mint_account_info = accounts.first().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    check_program_account(mint_account_info.owner)?;
+    let decimals = {
+        let mint_data = mint_account_info.try_borrow_data()?;
+        StateWithExtensions::<Mint>::unpack(&mint_data)?
+            .base
+            .decimals
+    };
+    let new_supply = try_ui_supply_into_supply(ui_supply, decimals).map_err(|e| {
+        msg!(
+            "UI supply {:?} is not a decimal amount within {} decimals",
+            ui_supply,
+            decimals
+        );
+        e
+    })?;
+    msg!("Rebasing to {} ({} in base units)", ui_supply, new_supply);
+    process_rebase_supply(
+        program_id,
+        accounts,
+        new_supply,
+        tags.reason_code,
+        tags.reference,
+        tags.not_after_unix_timestamp.into(),
+    )
+}
+
+fn process_initialize_history(accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let mint_account_info = next_account_info(account_info_iter)?;
+    check_program_account(mint_account_info.owner)?;
+
+    let mut mint_data = mint_account_info.data.borrow_mut();
+    let mut mint = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut mint_data)?;
+    mint.init_extension::<RebaseHistory>(true)?;
+    Ok(())
+}
+
+fn process_get_rebase_history(accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let mint_info = next_account_info(account_info_iter)?;
+    check_program_account(mint_info.owner)?;
+
+    let mint_data = mint_info.data.borrow();
+    let mint = StateWithExtensions::<Mint>::unpack(&mint_data)
+        .map_err(|_| Into::<ProgramError>::into(TokenError::InvalidMint))?;
+    let history = mint.get_extension::<RebaseHistory>()?;
+
+    set_return_data(&RebaseHistoryData::from(history).pack());
+    Ok(())
+}
+
+fn process_sync_supply_from_mint(accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let mint_account_info = next_account_info(account_info_iter)?;
+    check_program_account(mint_account_info.owner)?;
+
+    let mut mint_data = mint_account_info.data.borrow_mut();
+    let mut mint = StateWithExtensionsMut::<Mint>::unpack(&mut mint_data)?;
+    let mint_supply = mint.base.supply;
+    let extension = mint.get_extension_mut::<RebaseMintConfig>()?;
+    extension.check_rebase_mode(RebaseMode::Shares)?;
+
+    let event = extension.sync_supply(mint_supply)?;
+    sol_log_data(&[bytemuck::bytes_of(&event)]);
+    Ok(())
+}
+
+fn process_preview_rebase_supply(accounts: &[AccountInfo], new_supply: u64) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let mint_account_info = next_account_info(account_info_iter)?;
+    check_program_account(mint_account_info.owner)?;
+
+    let mint_data = mint_account_info.data.borrow();
+    let mint = StateWithExtensions::<Mint>::unpack(&mint_data)?;
+    let extension = mint.get_extension::<RebaseMintConfig>()?;
+    let reserve_amount = next_reserve_amount(extension, account_info_iter)?;
+
+    let preview = check_rebase(extension, new_supply, reserve_amount)?;
+    extension.check_base_supply(mint.base.supply)?;
+    // the rebase issues its fee as new shares
+    let (preview, _) = extension.preview_with_fee(&preview)?;
+    set_return_data(bytemuck::bytes_of(&preview));
+    Ok(())
+}
+
+fn process_assert_rebase_consistency(accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let mint_account_info = next_account_info(account_info_iter)?;
+    check_program_account(mint_account_info.owner)?;
+
+    let mint_data = mint_account_info.data.borrow();
+    let mint = StateWithExtensions::<Mint>::unpack(&mint_data)?;
+    let extension = mint.get_extension::<RebaseMintConfig>()?;
+    let consistency = RebaseConsistency {
+        mint_supply: mint.base.supply.into(),
+        total_supply: extension.total_supply,
+        total_shares: extension.total_shares,
+        outstanding_shares: extension.outstanding_shares,
+        exempt_supply: extension.exempt_supply,
+    };
+
+    extension.check_consistency(mint.base.supply)?;
+
+    set_return_data(bytemuck::bytes_of(&consistency));
+    Ok(())
+}
+
+fn process_set_reserve_account(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_reserve_account: &OptionalNonZeroPubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let mint_account_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let owner_info_data_len = owner_info.data_len();
+
+    let mut mint_data = mint_account_info.data.borrow_mut();
+    let mut mint = StateWithExtensionsMut::<Mint>::unpack(&mut mint_data)?;
+    let extension = mint.get_extension_mut::<RebaseMintConfig>()?;
+    let supply_authority =
+        Option::<Pubkey>::from(extension.supply_authority).ok_or(TokenError::NoAuthorityExists)?;
+
+    Processor::validate_owner(
+        program_id,
+        &supply_authority,
+        owner_info,
+        owner_info_data_len,
+        account_info_iter.as_slice(),
+    )?;
+
+    let event = ReserveAccountChanged {
+        previous_reserve_account: extension.reserve_account,
+        new_reserve_account: *new_reserve_account,
+    };
+    extension.reserve_account = *new_reserve_account;
+    log_event(mint_account_info.key, &event)
+}
+
+fn process_set_rebase_fee(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    rebase_fee_bps: u16,
+    fee_destination: &OptionalNonZeroPubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let mint_account_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let owner_info_data_len = owner_info.data_len();
+
+    let mut mint_data = mint_account_info.data.borrow_mut();
+    let mut mint = StateWithExtensionsMut::<Mint>::unpack(&mut mint_data)?;
+    let extension = mint.get_extension_mut::<RebaseMintConfig>()?;
+    let supply_authority =
+        Option::<Pubkey>::from(extension.supply_authority).ok_or(TokenError::NoAuthorityExists)?;
+
+    Processor::validate_owner(
+        program_id,
+        &supply_authority,
+        owner_info,
+        owner_info_data_len,
+        account_info_iter.as_slice(),
+    )?;
+
+    extension.check_rebase_mode(RebaseMode::Shares)?;
+    let previous_rebase_fee_bps = extension.rebase_fee_bps;
+    let previous_fee_destination = extension.fee_destination;
+    extension.set_rebase_fee(rebase_fee_bps, *fee_destination)?;
+    let event = RebaseFeeChanged {
+        previous_rebase_fee_bps,
+        previous_fee_destination,
+        new_rebase_fee_bps: extension.rebase_fee_bps,
+        new_fee_destination: extension.fee_destination,
+    };
+    log_event(mint_account_info.key, &event)
+}
+
+fn process_sunset_rebase(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let mint_account_info = next_account_info(account_info_iter)?;
+
+    let mut mint_data = mint_account_info.data.borrow_mut();
+    let mut mint = StateWithExtensionsMut::<Mint>::unpack(&mut mint_data)?;
+    let extension = mint.get_extension_mut::<RebaseMintConfig>()?;
+    extension.check_not_sunset()?;
+    extension.check_not_retired()?;
+    check_co_signer(extension, account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let owner_info_data_len = owner_info.data_len();
+    let supply_authority =
+        Option::<Pubkey>::from(extension.supply_authority).ok_or(TokenError::NoAuthorityExists)?;
+
+    Processor::validate_owner(
+        program_id,
+        &supply_authority,
+        owner_info,
+        owner_info_data_len,
+        account_info_iter.as_slice(),
+    )?;
+
+    let mut event = RebaseMintSunset {
+        previous_supply: extension.total_supply,
+        previous_shares: extension.total_shares,
+        previous_authority: extension.supply_authority,
+        ..RebaseMintSunset::default()
+    };
+    extension.sunset().map_err(|e| {
+        if !bool::from(extension.allow_sunset) {
+            msg!("The mint was not initialized with allow_sunset");
+        } else {
+            msg!(
+                "Cannot sunset while exempt accounts hold {} tokens",
+                u64::from(extension.exempt_supply)
+            );
+        }
+        e
+    })?;
+    event.cumulative_supply_increase = extension.cumulative_supply_increase;
+    event.cumulative_supply_decrease = extension.cumulative_supply_decrease;
+    // the tokens backing the written off shares are gone too, so that the
+    // mint can be closed
+    mint.base.supply = 0;
+    mint.pack_base();
+    if let Ok(history) = mint.get_extension_mut::<RebaseHistory>() {
+        history.record(RebaseHistoryEntry {
+            unix_timestamp: Clock::get()?.unix_timestamp.into(),
+            old_supply: event.previous_supply,
+            ..RebaseHistoryEntry::default()
+        });
+    }
+    log_event(mint_account_info.key, &event)
+}
+
+fn process_retire_rebase(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let mint_account_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let owner_info_data_len = owner_info.data_len();
+
+    let mut mint_data = mint_account_info.data.borrow_mut();
+    let mut mint = StateWithExtensionsMut::<Mint>::unpack(&mut mint_data)?;
+    let extension = mint.get_extension_mut::<RebaseMintConfig>()?;
+    extension.check_not_sunset()?;
+    extension.check_not_retired()?;
+    let supply_authority =
+        Option::<Pubkey>::from(extension.supply_authority).ok_or(TokenError::NoAuthorityExists)?;
+
+    Processor::validate_owner(
+        program_id,
+        &supply_authority,
+        owner_info,
+        owner_info_data_len,
+        account_info_iter.as_slice(),
+    )?;
+
+    let event = RebaseMintRetired {
+        retired_supply: extension.total_supply,
+        retired_shares: extension.total_shares,
+        previous_authority: extension.supply_authority,
+    };
+    extension.retire()?;
+    log_event(mint_account_info.key, &event)
+}
+
+fn process_sweep_rounding_dust(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let mint_account_info = next_account_info(account_info_iter)?;
+    let destination_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let owner_info_data_len = owner_info.data_len();
+
+    let mut mint_data = mint_account_info.data.borrow_mut();
+    let mut mint = StateWithExtensionsMut::<Mint>::unpack(&mut mint_data)?;
+    let extension = mint.get_extension_mut::<RebaseMintConfig>()?;
+    let supply_authority =
+        Option::<Pubkey>::from(extension.supply_authority).ok_or(TokenError::NoAuthorityExists)?;
+
+    Processor::validate_owner(
+        program_id,
+        &supply_authority,
+        owner_info,
+        owner_info_data_len,
+        account_info_iter.as_slice(),
+    )?;
+
+    extension.check_rebase_mode(RebaseMode::Shares)?;
+    let shares = extension.sweep_rounding_dust()?;
+    credit_unheld_shares(extension, mint_account_info.key, destination_info, shares)?;
+    let amount = extension
+        .shares_to_amount(shares)
+        .ok_or(TokenError::Overflow)?;
+    log_event(
+        mint_account_info.key,
+        &RoundingDustSwept {
+            destination: *destination_info.key,
+            shares: shares.into(),
+            amount: amount.into(),
+        },
+    )
+}
+
+fn process_claim_rebase(accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let token_account_info = next_account_info(account_info_iter)?;
+    let mint_account_info = next_account_info(account_info_iter)?;
+    check_program_account(token_account_info.owner)?;
+    check_program_account(mint_account_info.owner)?;
+
+    let mut token_account_data = token_account_info.data.borrow_mut();
+    let mut token_account = StateWithExtensionsMut::<Account>::unpack(&mut token_account_data)?;
+    if token_account.base.mint != *mint_account_info.key {
+        return Err(TokenError::MintMismatch.into());
+    }
+    let mint_data = mint_account_info.data.borrow();
+    let mint = StateWithExtensions::<Mint>::unpack(&mint_data)?;
+    let extension = mint.get_extension::<RebaseMintConfig>()?;
+    extension.check_rebase_mode(RebaseMode::Lazy)?;
+
+    let previous_amount = catch_up_lazy_account(extension, &mut token_account)?;
+    token_account.pack_base();
+    log_event(
+        mint_account_info.key,
+        &RebaseClaimed {
+            account: *token_account_info.key,
+            previous_amount: previous_amount.into(),
+            amount: token_account.base.amount.into(),
+            rebase_index: extension.rebase_index,
+        },
+    )
+}
+
+fn process_set_supply_authority(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_authority: &OptionalNonZeroPubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let mint_account_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let owner_info_data_len = owner_info.data_len();
+
+    let mut mint_data = mint_account_info.data.borrow_mut();
+    let mut mint = StateWithExtensionsMut::<Mint>::unpack(&mut mint_data)?;
+    let extension = mint.get_extension_mut::<RebaseMintConfig>()?;
+    let supply_authority =
+        Option::<Pubkey>::from(extension.supply_authority).ok_or(TokenError::NoAuthorityExists)?;
+
+    Processor::validate_owner(
+        program_id,
+        &supply_authority,
+        owner_info,
+        owner_info_data_len,
+        account_info_iter.as_slice(),
+    )?;
+
+    extension.check_direct_authority_change(new_authority)?;
+    change_supply_authority(mint_account_info.key, extension, *new_authority)
+}
+
+fn process_propose_supply_authority(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    pending_authority: &OptionalNonZeroPubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let mint_account_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let owner_info_data_len = owner_info.data_len();
+
+    let mut mint_data = mint_account_info.data.borrow_mut();
+    let mut mint = StateWithExtensionsMut::<Mint>::unpack(&mut mint_data)?;
+    let extension = mint.get_extension_mut::<RebaseMintConfig>()?;
+    let supply_authority =
+        Option::<Pubkey>::from(extension.supply_authority).ok_or(TokenError::NoAuthorityExists)?;
+
+    Processor::validate_owner(
+        program_id,
+        &supply_authority,
+        owner_info,
+        owner_info_data_len,
+        account_info_iter.as_slice(),
+    )?;
+
+    if Option::<Pubkey>::from(extension.pending_supply_authority).is_some() {
+        return Err(TokenError::RebaseAuthorityProposalPending.into());
+    }
+    if Option::<Pubkey>::from(*pending_authority).is_none() {
+        return Err(ProgramError::InvalidArgument);
+    }
+    extension.pending_supply_authority = *pending_authority;
+    log_event(
+        mint_account_info.key,
+        &SupplyAuthorityProposed {
+            pending_authority: *pending_authority,
+        },
+    )
+}
+
+fn process_accept_supply_authority(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let mint_account_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let owner_info_data_len = owner_info.data_len();
+
+    let mut mint_data = mint_account_info.data.borrow_mut();
+    let mut mint = StateWithExtensionsMut::<Mint>::unpack(&mut mint_data)?;
+    let extension = mint.get_extension_mut::<RebaseMintConfig>()?;
+    let pending_authority = Option::<Pubkey>::from(extension.pending_supply_authority)
+        .ok_or(TokenError::NoPendingRebaseAuthority)?;
+
+    Processor::validate_owner(
+        program_id,
+        &pending_authority,
+        owner_info,
+        owner_info_data_len,
+        account_info_iter.as_slice(),
+    )?;
+
+    let new_authority = extension.pending_supply_authority;
+    change_supply_authority(mint_account_info.key, extension, new_authority)
+}
+
+fn process_cancel_pending_authority(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let mint_account_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let owner_info_data_len = owner_info.data_len();
+
+    let mut mint_data = mint_account_info.data.borrow_mut();
+    let mut mint = StateWithExtensionsMut::<Mint>::unpack(&mut mint_data)?;
+    let extension = mint.get_extension_mut::<RebaseMintConfig>()?;
+    let supply_authority =
+        Option::<Pubkey>::from(extension.supply_authority).ok_or(TokenError::NoAuthorityExists)?;
+
+    Processor::validate_owner(
+        program_id,
+        &supply_authority,
+        owner_info,
+        owner_info_data_len,
+        account_info_iter.as_slice(),
+    )?;
+
+    if Option::<Pubkey>::from(extension.pending_supply_authority).is_none() {
+        return Err(TokenError::NoPendingRebaseAuthority.into());
+    }
+    let event = SupplyAuthorityProposalCancelled {
+        pending_authority: extension.pending_supply_authority,
+    };
+    extension.pending_supply_authority = OptionalNonZeroPubkey::default();
+    log_event(mint_account_info.key, &event)
+}
+
+fn process_set_rebase_delegate(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    rebase_delegate: &OptionalNonZeroPubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let mint_account_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let owner_info_data_len = owner_info.data_len();
+
+    let mut mint_data = mint_account_info.data.borrow_mut();
+    let mut mint = StateWithExtensionsMut::<Mint>::unpack(&mut mint_data)?;
+    let extension = mint.get_extension_mut::<RebaseMintConfig>()?;
+    let supply_authority =
+        Option::<Pubkey>::from(extension.supply_authority).ok_or(TokenError::NoAuthorityExists)?;
+
+    Processor::validate_owner(
+        program_id,
+        &supply_authority,
+        owner_info,
+        owner_info_data_len,
+        account_info_iter.as_slice(),
+    )?;
+
+    let event = RebaseDelegateChanged {
+        previous_delegate: extension.rebase_delegate,
+        new_delegate: *rebase_delegate,
+    };
+    extension.rebase_delegate = *rebase_delegate;
+    log_event(mint_account_info.key, &event)
+}
+
+fn process_set_account_exempt(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    exempt: bool,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let token_account_info = next_account_info(account_info_iter)?;
+    let mint_account_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let owner_info_data_len = owner_info.data_len();
+    check_program_account(token_account_info.owner)?;
+
+    let mut token_account_data = token_account_info.data.borrow_mut();
+    let mut token_account = StateWithExtensionsMut::<Account>::unpack(&mut token_account_data)?;
+    if token_account.base.mint != *mint_account_info.key {
+        return Err(TokenError::MintMismatch.into());
+    }
+    let mut mint_data = mint_account_info.data.borrow_mut();
+    let mut mint = StateWithExtensionsMut::<Mint>::unpack(&mut mint_data)?;
+    let extension = mint.get_extension_mut::<RebaseMintConfig>()?;
+    let supply_authority =
+        Option::<Pubkey>::from(extension.supply_authority).ok_or(TokenError::NoAuthorityExists)?;
+
+    Processor::validate_owner(
+        program_id,
+        &supply_authority,
+        owner_info,
+        owner_info_data_len,
+        account_info_iter.as_slice(),
+    )?;
+
+    extension.check_rebase_mode(RebaseMode::Shares)?;
+    set_account_exempt(extension, &mut token_account, exempt)?;
+    token_account.pack_base();
+    Ok(())
+}
+
+/// Logs the supply authority change scheduled on the mint
+fn log_scheduled_authority_change(extension: &RebaseMintConfig) {
+    if let Some(scheduled_authority) = Option::<Pubkey>::from(extension.scheduled_supply_authority)
+    {
+        msg!(
+            "Supply authority change to {} requested at {}, can be finalized after {} seconds",
+            scheduled_authority,
+            i64::from(extension.authority_change_requested_timestamp),
+            u32::from(extension.authority_change_delay_seconds),
+        );
+    }
+}
+
+/// Change the supply authority of `mint` to `new_authority`, or schedule the
+/// change if the mint delays them, and log the matching event
+pub(crate) fn change_supply_authority(
+    mint: &Pubkey,
+    extension: &mut RebaseMintConfig,
+    new_authority: OptionalNonZeroPubkey,
+) -> ProgramResult {
+    let previous_authority = extension.supply_authority;
+    let clock = Clock::get()?;
+    if extension.change_supply_authority(new_authority, clock.unix_timestamp) {
+        log_scheduled_authority_change(extension);
+        let ready_timestamp = clock
+            .unix_timestamp
+            .saturating_add(u32::from(extension.authority_change_delay_seconds) as i64);
+        log_event(
+            mint,
+            &SupplyAuthorityChangeScheduled {
+                scheduled_authority: new_authority,
+                ready_timestamp: ready_timestamp.into(),
+            },
+        )
+    } else {
+        log_event(
+            mint,
+            &SupplyAuthorityChanged {
+                previous_authority,
+                new_authority,
+            },
+        )
+    }
+}
+
+fn process_finalize_authority_change(accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let mint_account_info = next_account_info(account_info_iter)?;
+
+    let mut mint_data = mint_account_info.data.borrow_mut();
+    let mut mint = StateWithExtensionsMut::<Mint>::unpack(&mut mint_data)?;
+    let extension = mint.get_extension_mut::<RebaseMintConfig>()?;
+
+    let previous_authority = extension.supply_authority;
+    let clock = Clock::get()?;
+    extension.finalize_authority_change(clock.unix_timestamp)?;
+    msg!(
+        "Supply authority change to {:?} finalized",
+        Option::<Pubkey>::from(extension.supply_authority)
+    );
+    log_event(
+        mint_account_info.key,
+        &SupplyAuthorityChanged {
+            previous_authority,
+            new_authority: extension.supply_authority,
+        },
+    )
+}
+
+fn process_cancel_authority_change(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let mint_account_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let owner_info_data_len = owner_info.data_len();
+
+    let mut mint_data = mint_account_info.data.borrow_mut();
+    let mut mint = StateWithExtensionsMut::<Mint>::unpack(&mut mint_data)?;
+    let extension = mint.get_extension_mut::<RebaseMintConfig>()?;
+    let supply_authority =
+        Option::<Pubkey>::from(extension.supply_authority).ok_or(TokenError::NoAuthorityExists)?;
+
+    Processor::validate_owner(
+        program_id,
+        &supply_authority,
+        owner_info,
+        owner_info_data_len,
+        account_info_iter.as_slice(),
+    )?;
+
+    if i64::from(extension.authority_change_requested_timestamp) == 0 {
+        return Err(TokenError::NoPendingRebaseAuthority.into());
+    }
+    msg!(
+        "Supply authority change to {:?} cancelled",
+        Option::<Pubkey>::from(extension.scheduled_supply_authority)
+    );
+    let event = SupplyAuthorityChangeCancelled {
+        scheduled_authority: extension.scheduled_supply_authority,
+    };
+    extension.cancel_authority_change();
+    log_event(mint_account_info.key, &event)
+}
+
+fn process_get_rebased_balance(accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let account_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    check_program_account(account_info.owner)?;
+    check_program_account(mint_info.owner)?;
+
+    let account_data = account_info.data.borrow();
+    let account = StateWithExtensions::<Account>::unpack(&account_data)?;
+    let mint_data = mint_info.data.borrow();
+    let mint = StateWithExtensions::<Mint>::unpack(&mint_data)
+        .map_err(|_| Into::<ProgramError>::into(TokenError::InvalidMint))?;
+    if account.base.mint != *mint_info.key {
+        return Err(TokenError::MintMismatch.into());
+    }
+    let extension = mint.get_extension::<RebaseMintConfig>()?;
+
+    let amount = if extension.is_lazy() {
+        let lazy_account = account.get_extension::<LazyRebaseAccount>()?;
+        extension
+            .lazy_amount(
+                account.base.amount,
+                u128::from(lazy_account.last_seen_rebase_index),
+            )
+            .ok_or(TokenError::Overflow)?
+    } else {
+        let rebase_account = account.get_extension::<RebaseAccount>()?;
+        if rebase_account.is_exempt() {
+            account.base.amount
+        } else {
+            extension
+                .shares_to_amount(u64::from(rebase_account.shares))
+                .ok_or(TokenError::Overflow)?
+        }
+    };
+    let ui_amount = crate::amount_to_ui_amount_string_trimmed(amount, mint.base.decimals);
+    let mut return_data = amount.to_le_bytes().to_vec();
+    return_data.extend_from_slice(ui_amount.as_bytes());
+    set_return_data(&return_data);
+    Ok(())
+}
+
+fn process_convert(
+    accounts: &[AccountInfo],
+    convert: impl Fn(&RebaseMintConfig) -> Option<u64>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let mint_info = next_account_info(account_info_iter)?;
+    check_program_account(mint_info.owner)?;
+
+    let mint_data = mint_info.data.borrow();
+    let mint = StateWithExtensions::<Mint>::unpack(&mint_data)
+        .map_err(|_| Into::<ProgramError>::into(TokenError::InvalidMint))?;
+    let extension = mint.get_extension::<RebaseMintConfig>()?;
+    extension.check_rebase_mode(RebaseMode::Shares)?;
+
+    let converted = convert(extension).ok_or(TokenError::Overflow)?;
+    set_return_data(&converted.to_le_bytes());
+    Ok(())
+}
+
+fn process_get_rebase_state(accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let mint_info = next_account_info(account_info_iter)?;
+    check_program_account(mint_info.owner)?;
+
+    let mint_data = mint_info.data.borrow();
+    let mint = StateWithExtensions::<Mint>::unpack(&mint_data)
+        .map_err(|_| Into::<ProgramError>::into(TokenError::InvalidMint))?;
+    let extension = mint.get_extension::<RebaseMintConfig>()?;
+
+    // the cached ratio goes stale with mints and burns, so it's recomputed
+    let mut current = *extension;
+    current.refresh_cached_ratio();
+    let mint_authority = Option::<Pubkey>::from(mint.base.mint_authority);
+    let rebase_allowed = extension
+        .rebase_authority_for(mint_info.key, mint_authority)
+        .is_ok()
+        && extension.is_rebase_allowed_at(Clock::get()?.unix_timestamp);
+    let state = RebaseState {
+        version: REBASE_STATE_VERSION,
+        decimals: mint.base.decimals,
+        mint_supply: mint.base.supply.into(),
+        total_supply: extension.total_supply,
+        total_shares: extension.total_shares,
+        outstanding_shares: extension.outstanding_shares,
+        exempt_supply: extension.exempt_supply,
+        virtual_shares_offset: extension.virtual_shares_offset,
+        ratio: current.cached_ratio,
+        supply_authority: extension.supply_authority,
+        pending_supply_authority: extension.pending_supply_authority,
+        scheduled_supply_authority: extension.scheduled_supply_authority,
+        rebase_delegate: extension.rebase_delegate,
+        reserve_account: extension.reserve_account,
+        first_rebase_after_timestamp: extension.first_rebase_after_timestamp,
+        rebase_allowed: rebase_allowed.into(),
+        has_rebased: extension.has_rebased,
+        allow_supply_decrease: extension.allow_supply_decrease,
+        require_rebase_memo: extension.require_rebase_memo,
+        rebase_mode: extension.rebase_mode,
+        rebase_index: extension.rebase_index,
+    };
+    set_return_data(bytemuck::bytes_of(&state));
+    Ok(())
+}
+
+/// Get the decimals of the mint at `accounts[1]`, for instructions that don't
+/// check them
+fn mint_decimals(accounts: &[AccountInfo]) -> Result<u8, ProgramError> {
+    let mint_info = accounts.get(1).ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let mint_data = mint_info.try_borrow_data()?;
+    Ok(StateWithExtensions::<Mint>::unpack(&mint_data)?
+        .base
+        .decimals)
+}
+
+fn process_transfer_shares(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    shares: u64,
+    expected_decimals: Option<u8>,
+) -> ProgramResult {
+    // without a decimals check, take the mint's own decimals
+    let expected_decimals = match expected_decimals {
+        Some(expected_decimals) => expected_decimals,
+        None => mint_decimals(accounts)?,
+    };
+    Processor::process_transfer_shares(program_id, accounts, shares, expected_decimals)
+}
+
+/// Creates a program-derived account owned by the token program, funding it
+/// from the payer, in the same way as the native mint
+fn create_program_derived_account<'a>(
+    program_id: &Pubkey,
+    payer_info: &AccountInfo<'a>,
+    new_account_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    space: usize,
+    seeds: &[&[u8]],
+) -> ProgramResult {
+    let rent = Rent::get()?;
+    let lamports_diff = rent
+        .minimum_balance(space)
+        .saturating_sub(new_account_info.lamports());
+    invoke(
+        &system_instruction::transfer(payer_info.key, new_account_info.key, lamports_diff),
+        &[
+            payer_info.clone(),
+            new_account_info.clone(),
+            system_program_info.clone(),
+        ],
+    )?;
+    invoke_signed(
+        &system_instruction::allocate(new_account_info.key, space as u64),
+        &[new_account_info.clone(), system_program_info.clone()],
+        &[seeds],
+    )?;
+    invoke_signed(
+        &system_instruction::assign(new_account_info.key, program_id),
+        &[new_account_info.clone(), system_program_info.clone()],
+        &[seeds],
+    )
+}
+
+fn process_initialize_wrapper(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let payer_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let wrapper_mint_info = next_account_info(account_info_iter)?;
+    let escrow_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    check_program_account(mint_info.owner)?;
+
+    let (decimals, escrow_extensions) = {
+        let mint_data = mint_info.data.borrow();
+        let mint = StateWithExtensions::<Mint>::unpack(&mint_data)?;
+        mint.get_extension::<RebaseMintConfig>()?
+            .check_rebase_mode(RebaseMode::Shares)?;
+        (
+            mint.base.decimals,
+            ExtensionType::get_required_init_account_extensions(&mint.get_extension_types()?),
+        )
+    };
+
+    let (wrapper_mint, wrapper_mint_bump_seed) =
+        get_wrapper_mint_address_and_bump_seed(mint_info.key, program_id);
+    if wrapper_mint != *wrapper_mint_info.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    let (escrow, escrow_bump_seed) = get_escrow_address_and_bump_seed(mint_info.key, program_id);
+    if escrow != *escrow_info.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    let (wrapper_authority, _) =
+        get_wrapper_authority_address_and_bump_seed(mint_info.key, program_id);
+
+    create_program_derived_account(
+        program_id,
+        payer_info,
+        wrapper_mint_info,
+        system_program_info,
+        Mint::get_packed_len(),
+        &[
+            WRAPPER_MINT_SEED,
+            mint_info.key.as_ref(),
+            &[wrapper_mint_bump_seed],
+        ],
+    )?;
+    Mint::pack(
+        Mint {
+            mint_authority: COption::Some(wrapper_authority),
+            decimals,
+            is_initialized: true,
+            ..Mint::default()
+        },
+        &mut wrapper_mint_info.data.borrow_mut(),
+    )?;
+
+    create_program_derived_account(
+        program_id,
+        payer_info,
+        escrow_info,
+        system_program_info,
+        ExtensionType::try_calculate_account_len::<Account>(&escrow_extensions)?,
+        &[ESCROW_SEED, mint_info.key.as_ref(), &[escrow_bump_seed]],
+    )?;
+    Processor::process_initialize_account3(
+        &[escrow_info.clone(), mint_info.clone()],
+        wrapper_authority,
+    )
+}
+
+/// Checks the wrapper accounts of the rebasing mint, returning the wrapper
+/// authority's bump seed
+fn check_wrapper_accounts(
+    program_id: &Pubkey,
+    mint: &Pubkey,
+    escrow_info: &AccountInfo,
+    wrapper_mint_info: &AccountInfo,
+    wrapper_authority_info: &AccountInfo,
+) -> Result<u8, ProgramError> {
+    let (escrow, _) = get_escrow_address_and_bump_seed(mint, program_id);
+    let (wrapper_mint, _) = get_wrapper_mint_address_and_bump_seed(mint, program_id);
+    let (wrapper_authority, wrapper_authority_bump_seed) =
+        get_wrapper_authority_address_and_bump_seed(mint, program_id);
+    if escrow != *escrow_info.key
+        || wrapper_mint != *wrapper_mint_info.key
+        || wrapper_authority != *wrapper_authority_info.key
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    Ok(wrapper_authority_bump_seed)
+}
+
+fn process_wrap_shares(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    shares: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let source_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let escrow_info = next_account_info(account_info_iter)?;
+    let wrapper_mint_info = next_account_info(account_info_iter)?;
+    let destination_info = next_account_info(account_info_iter)?;
+    let wrapper_authority_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let bump_seed = check_wrapper_accounts(
+        program_id,
+        mint_info.key,
+        escrow_info,
+        wrapper_mint_info,
+        wrapper_authority_info,
+    )?;
+
+    let mut transfer_accounts = vec![
+        source_info.clone(),
+        mint_info.clone(),
+        escrow_info.clone(),
+        owner_info.clone(),
+    ];
+    transfer_accounts.extend_from_slice(account_info_iter.as_slice());
+    let expected_decimals = mint_decimals(&transfer_accounts)?;
+    Processor::process_transfer_shares(program_id, &transfer_accounts, shares, expected_decimals)?;
+
+    invoke_signed(
+        &crate::instruction::mint_to(
+            program_id,
+            wrapper_mint_info.key,
+            destination_info.key,
+            wrapper_authority_info.key,
+            &[],
+            shares,
+        )?,
+        &[
+            wrapper_mint_info.clone(),
+            destination_info.clone(),
+            wrapper_authority_info.clone(),
+            token_program_info.clone(),
+        ],
+        &[&[WRAPPER_AUTHORITY_SEED, mint_info.key.as_ref(), &[bump_seed]]],
+    )
+}
+
+fn process_unwrap_shares(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    shares: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let source_info = next_account_info(account_info_iter)?;
+    let wrapper_mint_info = next_account_info(account_info_iter)?;
+    let escrow_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let destination_info = next_account_info(account_info_iter)?;
+    let wrapper_authority_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let bump_seed = check_wrapper_accounts(
+        program_id,
+        mint_info.key,
+        escrow_info,
+        wrapper_mint_info,
+        wrapper_authority_info,
+    )?;
+
+    let mut burn_accounts = vec![
+        source_info.clone(),
+        wrapper_mint_info.clone(),
+        owner_info.clone(),
+    ];
+    burn_accounts.extend_from_slice(account_info_iter.as_slice());
+    Processor::process_burn(program_id, &burn_accounts, shares, None)?;
+
+    let decimals = mint_decimals(&[escrow_info.clone(), mint_info.clone()])?;
+    let mut transfer_instruction = instruction::transfer_shares_checked(
+        program_id,
+        escrow_info.key,
+        mint_info.key,
+        destination_info.key,
+        wrapper_authority_info.key,
+        &[],
+        shares,
+        decimals,
+    )?;
+    // an exempt destination changes the totals on the mint
+    transfer_instruction.accounts[1].is_writable = mint_info.is_writable;
+    invoke_signed(
+        &transfer_instruction,
+        &[
+            escrow_info.clone(),
+            mint_info.clone(),
+            destination_info.clone(),
+            wrapper_authority_info.clone(),
+            token_program_info.clone(),
+        ],
+        &[&[WRAPPER_AUTHORITY_SEED, mint_info.key.as_ref(), &[bump_seed]]],
+    )
+}
+
+pub(crate) fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    input: &[u8],
+) -> ProgramResult {
+    check_program_account(program_id)?;
+    check_active()?;
+    match decode_instruction_type(input)? {
+        RebaseMintInstruction::Initialize => {
+            msg!("RebaseMintInstruction::Initialize");
+            let InitializeInstructionData {
+                supply_authority,
+                initial_supply,
+                first_rebase_after_timestamp,
+                two_step_authority_transfer,
+                authority_change_delay_seconds,
+                use_mint_authority_fallback,
+                require_rebase_memo,
+                virtual_shares_offset,
+                allow_supply_decrease,
+                rebase_fee_bps,
+                fee_destination,
+                initial_shares,
+                max_total_shares,
+                allow_sunset,
+                update_metadata_rate,
+                co_signer,
+                rebase_mode,
+                sync_base_supply,
+            } = decode_instruction_data(input)?;
+            process_initialize(
+                program_id,
+                accounts,
+                supply_authority,
+                u64::from(*initial_supply),
+                first_rebase_after_timestamp,
+                two_step_authority_transfer,
+                authority_change_delay_seconds,
+                use_mint_authority_fallback,
+                require_rebase_memo,
+                u64::from(*virtual_shares_offset),
+                allow_supply_decrease,
+                rebase_fee_bps,
+                fee_destination,
+                u64::from(*initial_shares),
+                u64::from(*max_total_shares),
+                allow_sunset,
+                update_metadata_rate,
+                co_signer,
+                *rebase_mode,
+                sync_base_supply,
+            )
+        }
+        RebaseMintInstruction::RebaseSupply => {
+            msg!("RebaseMintInstruction::RebaseSupply");
+            let RebaseSupplyData {
+                new_supply,
+                reason_code,
+                reference,
+                not_after_unix_timestamp,
+            } = decode_rebase_supply_data(input)?;
+            process_rebase_supply(
+                program_id,
+                accounts,
+                u64::from(new_supply),
+                reason_code,
+                reference,
+                i64::from(not_after_unix_timestamp),
+            )
+        }
+        RebaseMintInstruction::SetReserveAccount => {
+            msg!("RebaseMintInstruction::SetReserveAccount");
+            let SetReserveAccountData { reserve_account } = decode_instruction_data(input)?;
+            process_set_reserve_account(program_id, accounts, reserve_account)
+        }
+        RebaseMintInstruction::PreviewRebaseSupply => {
+            msg!("RebaseMintInstruction::PreviewRebaseSupply");
+            let RebaseSupplyData { new_supply, .. } = decode_rebase_supply_data(input)?;
+            process_preview_rebase_supply(accounts, u64::from(new_supply))
+        }
+        RebaseMintInstruction::SetSupplyAuthority => {
+            msg!("RebaseMintInstruction::SetSupplyAuthority");
+            let SetSupplyAuthorityData { new_authority } = decode_instruction_data(input)?;
+            process_set_supply_authority(program_id, accounts, new_authority)
+        }
+        RebaseMintInstruction::ProposeSupplyAuthority => {
+            msg!("RebaseMintInstruction::ProposeSupplyAuthority");
+            let ProposeSupplyAuthorityData { pending_authority } = decode_instruction_data(input)?;
+            process_propose_supply_authority(program_id, accounts, pending_authority)
+        }
+        RebaseMintInstruction::AcceptSupplyAuthority => {
+            msg!("RebaseMintInstruction::AcceptSupplyAuthority");
+            process_accept_supply_authority(program_id, accounts)
+        }
+        RebaseMintInstruction::CancelPendingAuthority => {
+            msg!("RebaseMintInstruction::CancelPendingAuthority");
+            process_cancel_pending_authority(program_id, accounts)
+        }
+        RebaseMintInstruction::FinalizeAuthorityChange => {
+            msg!("RebaseMintInstruction::FinalizeAuthorityChange");
+            process_finalize_authority_change(accounts)
+        }
+        RebaseMintInstruction::CancelAuthorityChange => {
+            msg!("RebaseMintInstruction::CancelAuthorityChange");
+            process_cancel_authority_change(program_id, accounts)
+        }
+        RebaseMintInstruction::SetRebaseDelegate => {
+            msg!("RebaseMintInstruction::SetRebaseDelegate");
+            let SetRebaseDelegateData { rebase_delegate } = decode_instruction_data(input)?;
+            process_set_rebase_delegate(program_id, accounts, rebase_delegate)
+        }
+        RebaseMintInstruction::TransferShares => {
+            msg!("RebaseMintInstruction::TransferShares");
+            let TransferSharesData { shares } = decode_instruction_data(input)?;
+            process_transfer_shares(program_id, accounts, u64::from(*shares), None)
+        }
+        RebaseMintInstruction::TransferSharesChecked => {
+            msg!("RebaseMintInstruction::TransferSharesChecked");
+            let TransferSharesCheckedData { shares, decimals } = decode_instruction_data(input)?;
+            process_transfer_shares(program_id, accounts, u64::from(*shares), Some(*decimals))
+        }
+        RebaseMintInstruction::MintToShares => {
+            msg!("RebaseMintInstruction::MintToShares");
+            let MintToSharesData { shares } = decode_instruction_data(input)?;
+            Processor::process_mint_to_shares(program_id, accounts, u64::from(*shares))
+        }
+        RebaseMintInstruction::BurnShares => {
+            msg!("RebaseMintInstruction::BurnShares");
+            let BurnSharesData { shares } = decode_instruction_data(input)?;
+            Processor::process_burn_shares(program_id, accounts, u64::from(*shares))
+        }
+        RebaseMintInstruction::SyncSupplyFromMint => {
+            msg!("RebaseMintInstruction::SyncSupplyFromMint");
+            process_sync_supply_from_mint(accounts)
+        }
+        RebaseMintInstruction::GetRebasedBalance => {
+            msg!("RebaseMintInstruction::GetRebasedBalance");
+            process_get_rebased_balance(accounts)
+        }
+        RebaseMintInstruction::ConvertAmountToShares => {
+            msg!("RebaseMintInstruction::ConvertAmountToShares");
+            let AmountToSharesData { amount } = decode_instruction_data(input)?;
+            process_convert(accounts, |extension| {
+                extension.amount_to_shares(u64::from(*amount))
+            })
+        }
+        RebaseMintInstruction::ConvertSharesToAmount => {
+            msg!("RebaseMintInstruction::ConvertSharesToAmount");
+            let SharesToAmountData { shares } = decode_instruction_data(input)?;
+            process_convert(accounts, |extension| {
+                extension.shares_to_amount(u64::from(*shares))
+            })
+        }
+        RebaseMintInstruction::ApproveShares => {
+            msg!("RebaseMintInstruction::ApproveShares");
+            let ApproveSharesData { shares } = decode_instruction_data(input)?;
+            let expected_decimals = mint_decimals(accounts)?;
+            Processor::process_approve_shares(
+                program_id,
+                accounts,
+                u64::from(*shares),
+                expected_decimals,
+            )
+        }
+        RebaseMintInstruction::SetAccountExempt => {
+            msg!("RebaseMintInstruction::SetAccountExempt");
+            let SetAccountExemptData { exempt } = decode_instruction_data(input)?;
+            process_set_account_exempt(program_id, accounts, bool::from(*exempt))
+        }
+        RebaseMintInstruction::InitializeWrapper => {
+            msg!("RebaseMintInstruction::InitializeWrapper");
+            process_initialize_wrapper(program_id, accounts)
+        }
+        RebaseMintInstruction::WrapShares => {
+            msg!("RebaseMintInstruction::WrapShares");
+            let WrapSharesData { shares } = decode_instruction_data(input)?;
+            process_wrap_shares(program_id, accounts, u64::from(*shares))
+        }
+        RebaseMintInstruction::UnwrapShares => {
+            msg!("RebaseMintInstruction::UnwrapShares");
+            let UnwrapSharesData { shares } = decode_instruction_data(input)?;
+            process_unwrap_shares(program_id, accounts, u64::from(*shares))
+        }
+        RebaseMintInstruction::AssertRebaseConsistency => {
+            msg!("RebaseMintInstruction::AssertRebaseConsistency");
+            process_assert_rebase_consistency(accounts)
+        }
+        RebaseMintInstruction::TransferCheckedWithShareSlippage => {
+            msg!("RebaseMintInstruction::TransferCheckedWithShareSlippage");
+            let TransferCheckedWithShareSlippageData {
+                amount,
+                decimals,
+                expected_shares,
+                max_share_slippage_bps,
+            } = decode_instruction_data(input)?;
+            Processor::process_transfer_with_share_slippage(
+                program_id,
+                accounts,
+                u64::from(*amount),
+                *decimals,
+                u64::from(*expected_shares),
+                u16::from(*max_share_slippage_bps),
+            )
+        }
+        RebaseMintInstruction::GetRebaseState => {
+            msg!("RebaseMintInstruction::GetRebaseState");
+            process_get_rebase_state(accounts)
+        }
+        RebaseMintInstruction::InitializeHistory => {
+            msg!("RebaseMintInstruction::InitializeHistory");
+            process_initialize_history(accounts)
+        }
+        RebaseMintInstruction::GetRebaseHistory => {
+            msg!("RebaseMintInstruction::GetRebaseHistory");
+            process_get_rebase_history(accounts)
+        }
+        RebaseMintInstruction::SetRebaseFee => {
+            msg!("RebaseMintInstruction::SetRebaseFee");
+            let SetRebaseFeeData {
+                rebase_fee_bps,
+                fee_destination,
+            } = decode_instruction_data(input)?;
+            process_set_rebase_fee(
+                program_id,
+                accounts,
+                u16::from(*rebase_fee_bps),
+                fee_destination,
+            )
+        }
+        RebaseMintInstruction::SunsetRebase => {
+            msg!("RebaseMintInstruction::SunsetRebase");
+            process_sunset_rebase(program_id, accounts)
+        }
+        RebaseMintInstruction::RetireRebase => {
+            msg!("RebaseMintInstruction::RetireRebase");
+            process_retire_rebase(program_id, accounts)
+        }
+        RebaseMintInstruction::SweepRoundingDust => {
+            msg!("RebaseMintInstruction::SweepRoundingDust");
+            process_sweep_rounding_dust(program_id, accounts)
+        }
+        RebaseMintInstruction::ClaimRebase => {
+            msg!("RebaseMintInstruction::ClaimRebase");
+            process_claim_rebase(accounts)
+        }
+        RebaseMintInstruction::RebaseSupplyUi => {
+            msg!("RebaseMintInstruction::RebaseSupplyUi");
+            let (ui_supply, tags) = decode_rebase_supply_ui_data(input)?;
+            process_rebase_supply_ui(program_id, accounts, ui_supply, tags)
+        }
+    }
+}
@@ -0,0 +1,485 @@
+//! Proptest strategies and mint account fixtures for testing programs that
+//! integrate with rebasing mints.
+//!
+//! The strategies only generate configs that the program could have stored,
+//! and the fixtures produce account data that unpacks like a real mint, so
+//! that downstream tests exercise the states their programs will meet.
+
+use {
+    crate::{
+        error::TokenError,
+        extension::{
+            rebase_mint::{
+                RebaseHistory, RebaseHistoryEntry, RebaseMintConfig, RebaseMode,
+                DEFAULT_MAX_TOTAL_SHARES, REBASE_INDEX_SCALE,
+            },
+            Extension, ExtensionType, StateWithExtensionsMut,
+        },
+        state::Mint,
+    },
+    bytemuck::Pod,
+    proptest::{collection::SizeRange, prelude::*},
+    solana_program::{
+        entrypoint::ProgramResult, program_error::ProgramError, program_option::COption,
+        pubkey::Pubkey,
+    },
+    spl_pod::optional_keys::OptionalNonZeroPubkey,
+    std::{fmt, sync::Arc},
+};
+
+/// Largest virtual offset generated for configs, well above what mints use in
+/// practice
+const MAX_VIRTUAL_SHARES_OFFSET: u64 = 1_000_000_000;
+
+/// Largest rebase index generated for lazy configs, a millionfold growth
+const MAX_LAZY_REBASE_INDEX: u128 = REBASE_INDEX_SCALE * 1_000_000;
+
+/// Bounds of the factor, in thousandths, by which `rebased_fixture` scales the
+/// supply on each rebase
+const MIN_REBASE_FACTOR: u64 = 500;
+const MAX_REBASE_FACTOR: u64 = 2_000;
+
+/// Seconds between the rebases applied by `rebased_fixture`
+const SECONDS_BETWEEN_REBASES: i64 = 24 * 60 * 60;
+
+/// Random key, or none about half the time
+fn optional_key() -> impl Strategy<Value = OptionalNonZeroPubkey> {
+    prop::option::of(any::<[u8; 32]>()).prop_map(|key| {
+        OptionalNonZeroPubkey::try_from(key.map(Pubkey::new_from_array)).unwrap_or_default()
+    })
+}
+
+/// Configs in `RebaseMode::Shares` that satisfy the invariants kept by the
+/// program: outstanding shares never exceed the total, which never exceeds
+/// the default cap, the supply and the shares are both zero or both nonzero,
+/// and the supply and exempt supply add up without overflow.
+///
+/// The mint is neither sunset nor retired, may or may not have rebased, and
+/// has a fresh cached ratio.
+pub fn rebase_mint_config() -> impl Strategy<Value = RebaseMintConfig> {
+    (0..=DEFAULT_MAX_TOTAL_SHARES)
+        .prop_flat_map(|total_shares| {
+            let total_supply = if total_shares == 0 {
+                0..=0
+            } else {
+                1..=u64::MAX
+            };
+            (Just(total_shares), total_supply, 0..=total_shares)
+        })
+        .prop_flat_map(|(total_shares, total_supply, outstanding_shares)| {
+            (
+                Just((total_shares, total_supply, outstanding_shares)),
+                0..=u64::MAX - total_supply,
+                0..=MAX_VIRTUAL_SHARES_OFFSET,
+                optional_key(),
+                any::<bool>(),
+                any::<bool>(),
+            )
+        })
+        .prop_map(
+            |(
+                (total_shares, total_supply, outstanding_shares),
+                exempt_supply,
+                virtual_shares_offset,
+                supply_authority,
+                has_rebased,
+                allow_supply_decrease,
+            )| {
+                let mut config = RebaseMintConfig {
+                    supply_authority,
+                    total_supply: total_supply.into(),
+                    total_shares: total_shares.into(),
+                    has_rebased: has_rebased.into(),
+                    exempt_supply: exempt_supply.into(),
+                    outstanding_shares: outstanding_shares.into(),
+                    virtual_shares_offset: virtual_shares_offset.into(),
+                    allow_supply_decrease: allow_supply_decrease.into(),
+                    rebase_mode: RebaseMode::Shares.into(),
+                    ..Default::default()
+                };
+                config.refresh_cached_ratio();
+                config
+            },
+        )
+}
+
+/// Configs in `RebaseMode::Lazy` that satisfy the invariants kept by the
+/// program: no shares, no exempt supply, and a nonzero rebase index
+pub fn lazy_rebase_mint_config() -> impl Strategy<Value = RebaseMintConfig> {
+    (
+        any::<u64>(),
+        1..=MAX_LAZY_REBASE_INDEX,
+        optional_key(),
+        any::<bool>(),
+    )
+        .prop_map(
+            |(total_supply, rebase_index, supply_authority, allow_supply_decrease)| {
+                RebaseMintConfig {
+                    supply_authority,
+                    total_supply: total_supply.into(),
+                    has_rebased: (rebase_index != REBASE_INDEX_SCALE).into(),
+                    allow_supply_decrease: allow_supply_decrease.into(),
+                    rebase_mode: RebaseMode::Lazy.into(),
+                    rebase_index: rebase_index.into(),
+                    ..Default::default()
+                }
+            },
+        )
+}
+
+/// Writes an extension into the mint data of a fixture
+type MintInit = Arc<dyn Fn(&mut StateWithExtensionsMut<'_, Mint>) -> ProgramResult>;
+
+/// Another mint extension to write into a fixture
+#[derive(Clone)]
+struct FixtureExtension {
+    extension_type: ExtensionType,
+    init: MintInit,
+}
+
+/// Builder of the account data of an initialized mint with a
+/// `RebaseMintConfig`, ready to be unpacked with `StateWithExtensions`.
+///
+/// The base supply defaults to the supply that the config accounts for, the
+/// total supply plus the exempt supply, as it would be before any rebase.
+#[derive(Clone)]
+pub struct RebaseMintFixture {
+    config: RebaseMintConfig,
+    supply: u64,
+    decimals: u8,
+    mint_authority: Option<Pubkey>,
+    freeze_authority: Option<Pubkey>,
+    history: Option<RebaseHistory>,
+    extensions: Vec<FixtureExtension>,
+}
+
+impl fmt::Debug for RebaseMintFixture {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RebaseMintFixture")
+            .field("config", &self.config)
+            .field("supply", &self.supply)
+            .field("decimals", &self.decimals)
+            .field("mint_authority", &self.mint_authority)
+            .field("freeze_authority", &self.freeze_authority)
+            .field(
+                "history",
+                &self.history.as_ref().map(|history| history.count),
+            )
+            .field(
+                "extensions",
+                &self
+                    .extensions
+                    .iter()
+                    .map(|extension| extension.extension_type)
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl RebaseMintFixture {
+    /// Start building a mint with `config`, 9 decimals, and no authorities
+    pub fn new(config: RebaseMintConfig) -> Self {
+        let supply = u64::from(config.total_supply).saturating_add(config.exempt_supply.into());
+        Self {
+            config,
+            supply,
+            decimals: 9,
+            mint_authority: None,
+            freeze_authority: None,
+            history: None,
+            extensions: vec![],
+        }
+    }
+
+    /// Set the base mint's supply, which may differ from the config's once
+    /// the mint has rebased
+    pub fn supply(mut self, supply: u64) -> Self {
+        self.supply = supply;
+        self
+    }
+
+    /// Set the decimals of the mint
+    pub fn decimals(mut self, decimals: u8) -> Self {
+        self.decimals = decimals;
+        self
+    }
+
+    /// Set the mint authority
+    pub fn mint_authority(mut self, mint_authority: &Pubkey) -> Self {
+        self.mint_authority = Some(*mint_authority);
+        self
+    }
+
+    /// Set the freeze authority
+    pub fn freeze_authority(mut self, freeze_authority: &Pubkey) -> Self {
+        self.freeze_authority = Some(*freeze_authority);
+        self
+    }
+
+    /// Add an empty `RebaseHistory`, which records the rebases applied to the
+    /// fixture
+    pub fn with_history(mut self) -> Self {
+        self.history = Some(RebaseHistory::default());
+        self
+    }
+
+    /// Add another mint extension holding `value`. Extensions are written in
+    /// the order they are added, ahead of the `RebaseMintConfig` and the
+    /// `RebaseHistory`.
+    pub fn extension<V: Extension + Pod + Default>(mut self, value: V) -> Self {
+        self.extensions.push(FixtureExtension {
+            extension_type: V::TYPE,
+            init: Arc::new(move |mint| {
+                *mint.init_extension::<V>(true)? = value;
+                Ok(())
+            }),
+        });
+        self
+    }
+
+    /// The config as of the last rebase applied to the fixture
+    pub fn config(&self) -> &RebaseMintConfig {
+        &self.config
+    }
+
+    /// The base mint's supply
+    pub fn mint_supply(&self) -> u64 {
+        self.supply
+    }
+
+    /// The history, if the fixture has one
+    pub fn history(&self) -> Option<&RebaseHistory> {
+        self.history.as_ref()
+    }
+
+    /// Rebase the supply to `new_supply` at `unix_timestamp`, as
+    /// `RebaseMintInstruction::RebaseSupply` would without a reserve account.
+    ///
+    /// The rebase is recorded in the history, if the fixture has one, and
+    /// written into the base supply if the config syncs it. The rebase fee,
+    /// if any, is issued as shares that no account in the fixture holds.
+    pub fn rebase(&mut self, new_supply: u64, unix_timestamp: i64) -> ProgramResult {
+        if !self.config.is_rebase_allowed_at(unix_timestamp) {
+            return Err(TokenError::RebaseNotYetAllowed.into());
+        }
+        let preview = self.config.preview_rebase(new_supply)?;
+        self.config.check_base_supply(self.supply)?;
+        let synced_base_supply = if bool::from(self.config.sync_base_supply) {
+            Some(
+                self.config
+                    .backed_supply(preview.new_supply.into())
+                    .ok_or(TokenError::RebaseTooLarge)?,
+            )
+        } else {
+            None
+        };
+        let old_supply = self.config.total_supply;
+        self.config.apply_rebase_with_fee(&preview)?;
+        if let Some(history) = &mut self.history {
+            history.record(RebaseHistoryEntry {
+                unix_timestamp: unix_timestamp.into(),
+                old_supply,
+                new_supply: self.config.total_supply,
+                new_shares: self.config.total_shares,
+                ..Default::default()
+            });
+        }
+        if let Some(synced_base_supply) = synced_base_supply {
+            self.supply = synced_base_supply;
+        }
+        Ok(())
+    }
+
+    /// Timestamp of the next rebase applied by `rebased_fixture`: a day after
+    /// the latest recorded rebase, and no earlier than the first allowed one
+    fn next_rebase_timestamp(&self) -> i64 {
+        let after_latest = self
+            .history
+            .as_ref()
+            .and_then(RebaseHistory::latest)
+            .map_or(0, |entry| {
+                i64::from(entry.unix_timestamp).saturating_add(SECONDS_BETWEEN_REBASES)
+            });
+        after_latest.max(self.config.first_rebase_after_timestamp.into())
+    }
+
+    /// Build the account data
+    pub fn build(&self) -> Result<Vec<u8>, ProgramError> {
+        let mut extension_types = self
+            .extensions
+            .iter()
+            .map(|extension| extension.extension_type)
+            .collect::<Vec<_>>();
+        extension_types.push(ExtensionType::RebaseMintConfig);
+        if self.history.is_some() {
+            extension_types.push(ExtensionType::RebaseHistory);
+        }
+        let mut data = vec![0; ExtensionType::try_calculate_account_len::<Mint>(&extension_types)?];
+        let mut mint = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data)?;
+        for extension in &self.extensions {
+            (extension.init)(&mut mint)?;
+        }
+        *mint.init_extension::<RebaseMintConfig>(true)? = self.config;
+        if let Some(history) = self.history {
+            *mint.init_extension::<RebaseHistory>(true)? = history;
+        }
+        mint.base = Mint {
+            mint_authority: self.mint_authority.map_or(COption::None, COption::Some),
+            supply: self.supply,
+            decimals: self.decimals,
+            is_initialized: true,
+            freeze_authority: self.freeze_authority.map_or(COption::None, COption::Some),
+        };
+        mint.pack_base();
+        mint.init_account_type()?;
+        Ok(data)
+    }
+}
+
+/// `fixture` after `count` random rebases, a day apart, each scaling the
+/// supply by a factor between one half and two.
+///
+/// Rebases never lower the supply of a config that forbids it, and rebases
+/// that the config rejects, such as those of a lazy mint with no supply, are
+/// skipped, so the fixture may end up with fewer rebases than drawn.
+pub fn rebased_fixture(
+    fixture: RebaseMintFixture,
+    count: impl Into<SizeRange>,
+) -> impl Strategy<Value = RebaseMintFixture> {
+    prop::collection::vec(MIN_REBASE_FACTOR..=MAX_REBASE_FACTOR, count).prop_map(move |factors| {
+        let mut fixture = fixture.clone();
+        for factor in factors {
+            let total_supply = u64::from(fixture.config.total_supply);
+            let scaled_supply = total_supply as u128 * factor as u128 / 1_000;
+            let mut new_supply = u64::try_from(scaled_supply).unwrap_or(u64::MAX);
+            if !bool::from(fixture.config.allow_supply_decrease) {
+                new_supply = new_supply.max(total_supply);
+            }
+            let unix_timestamp = fixture.next_rebase_timestamp();
+            let _ = fixture.rebase(new_supply, unix_timestamp);
+        }
+        fixture
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::extension::{
+            mint_close_authority::MintCloseAuthority, rebase_mint::pod::get_rebase_config,
+            BaseStateWithExtensions, StateWithExtensions,
+        },
+    };
+
+    proptest! {
+        #[test]
+        fn configs_are_consistent(config in rebase_mint_config()) {
+            assert_eq!(config.validate(), Ok(()));
+            let fixture = RebaseMintFixture::new(config);
+            assert_eq!(config.check_consistency(fixture.mint_supply()), Ok(()));
+        }
+
+        #[test]
+        fn lazy_configs_are_consistent(config in lazy_rebase_mint_config()) {
+            assert_eq!(config.validate(), Ok(()));
+            assert_eq!(config.check_consistency(config.total_supply.into()), Ok(()));
+        }
+
+        #[test]
+        fn fixtures_unpack(
+            config in rebase_mint_config(),
+            close_authority in any::<[u8; 32]>(),
+        ) {
+            let close_authority = Pubkey::new_from_array(close_authority);
+            let mint_authority = Pubkey::new_unique();
+            let data = RebaseMintFixture::new(config)
+                .mint_authority(&mint_authority)
+                .decimals(6)
+                .extension(MintCloseAuthority {
+                    close_authority: Some(close_authority).try_into().unwrap_or_default(),
+                })
+                .with_history()
+                .build()
+                .unwrap();
+
+            let mint = StateWithExtensions::<Mint>::unpack(&data).unwrap();
+            assert_eq!(mint.base.mint_authority, COption::Some(mint_authority));
+            assert_eq!(mint.base.decimals, 6);
+            assert!(mint.base.is_initialized);
+            assert_eq!(
+                mint.get_extension_types().unwrap(),
+                vec![
+                    ExtensionType::MintCloseAuthority,
+                    ExtensionType::RebaseMintConfig,
+                    ExtensionType::RebaseHistory,
+                ]
+            );
+            assert_eq!(mint.get_extension::<RebaseMintConfig>().unwrap(), &config);
+            assert_eq!(
+                mint.get_extension::<RebaseHistory>().unwrap(),
+                &RebaseHistory::default()
+            );
+            assert_eq!(get_rebase_config(&data).unwrap(), &config);
+        }
+
+        #[test]
+        fn rebased_fixtures_stay_consistent(
+            fixture in rebase_mint_config()
+                .prop_flat_map(|config| {
+                    rebased_fixture(RebaseMintFixture::new(config).with_history(), 0..20)
+                }),
+        ) {
+            let config = fixture.config();
+            assert_eq!(config.validate(), Ok(()));
+            assert_eq!(config.check_consistency(fixture.mint_supply()), Ok(()));
+
+            let data = fixture.build().unwrap();
+            let mint = StateWithExtensions::<Mint>::unpack(&data).unwrap();
+            assert_eq!(mint.get_extension::<RebaseMintConfig>().unwrap(), config);
+            let history = mint.get_extension::<RebaseHistory>().unwrap();
+            assert_eq!(history, fixture.history().unwrap());
+            // the rebases are recorded in order
+            let timestamps = history
+                .entries()
+                .map(|entry| i64::from(entry.unix_timestamp))
+                .collect::<Vec<_>>();
+            assert!(timestamps.windows(2).all(|pair| pair[0] < pair[1]));
+        }
+    }
+
+    #[test]
+    fn synced_fixture_follows_rebases() {
+        let config = RebaseMintConfig {
+            total_supply: 1_000.into(),
+            total_shares: 1_000.into(),
+            outstanding_shares: 1_000.into(),
+            exempt_supply: 100.into(),
+            allow_supply_decrease: true.into(),
+            sync_base_supply: true.into(),
+            ..Default::default()
+        };
+        let mut fixture = RebaseMintFixture::new(config);
+        assert_eq!(fixture.mint_supply(), 1_100);
+
+        fixture.rebase(1_500, 0).unwrap();
+        assert_eq!(u64::from(fixture.config().total_supply), 1_500);
+        assert_eq!(fixture.mint_supply(), 1_600);
+
+        let unsynced = RebaseMintFixture::new(config).supply(1_099);
+        assert_eq!(
+            unsynced.clone().rebase(1_500, 0),
+            Err(TokenError::RebaseBaseSupplyMismatch.into())
+        );
+
+        let config = RebaseMintConfig {
+            first_rebase_after_timestamp: 100.into(),
+            ..config
+        };
+        assert_eq!(
+            RebaseMintFixture::new(config).rebase(1_500, 99),
+            Err(TokenError::RebaseNotYetAllowed.into())
+        );
+    }
+}
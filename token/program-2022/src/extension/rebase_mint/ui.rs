@@ -0,0 +1,174 @@
+//! Display types for rendering rebasing mint state as JSON.
+//!
+//! Pubkeys are encoded as base58 strings and `u64` amounts as decimal strings,
+//! so that JavaScript parsers don't lose precision on large supplies.
+
+use {
+    crate::extension::rebase_mint::{RebaseAccount, RebaseMintConfig, RebaseMode},
+    serde::{Deserialize, Serialize},
+    serde_with::{As, DisplayFromStr},
+    solana_program::pubkey::Pubkey,
+};
+
+/// Display form of `RebaseMintConfig`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UiRebaseMintConfig {
+    /// Authority that can rebase the supply
+    #[serde(with = "As::<Option<DisplayFromStr>>")]
+    pub supply_authority: Option<Pubkey>,
+    /// Total supply of tokens, as of the last rebase, mint, or burn
+    #[serde(with = "As::<DisplayFromStr>")]
+    pub total_supply: u64,
+    /// Total number of shares that the supply is divided into
+    #[serde(with = "As::<DisplayFromStr>")]
+    pub total_shares: u64,
+    /// Optional token account holding the reserve backing the supply
+    #[serde(with = "As::<Option<DisplayFromStr>>")]
+    pub reserve_account: Option<Pubkey>,
+    /// Unix timestamp before which the supply cannot be rebased
+    pub first_rebase_after_timestamp: i64,
+    /// Proposed supply authority, waiting to accept the role
+    #[serde(with = "As::<Option<DisplayFromStr>>")]
+    pub pending_supply_authority: Option<Pubkey>,
+    /// If true, the supply authority can only be handed over by proposal
+    pub two_step_authority_transfer: bool,
+    /// Number of seconds that a change of supply authority must wait
+    pub authority_change_delay_seconds: u32,
+    /// Supply authority waiting for the change delay to elapse
+    #[serde(with = "As::<Option<DisplayFromStr>>")]
+    pub scheduled_supply_authority: Option<Pubkey>,
+    /// Unix timestamp at which the scheduled change was requested
+    pub authority_change_requested_timestamp: i64,
+    /// Key that can rebase the supply on behalf of the supply authority
+    #[serde(with = "As::<Option<DisplayFromStr>>")]
+    pub rebase_delegate: Option<Pubkey>,
+    /// If true, the mint authority can rebase while there is no supply
+    /// authority
+    pub use_mint_authority_fallback: bool,
+    /// If true, every rebase must be preceded by a memo
+    pub require_rebase_memo: bool,
+    /// True once the supply has been rebased
+    pub has_rebased: bool,
+    /// Total amount of tokens held by accounts exempt from rebasing
+    #[serde(with = "As::<DisplayFromStr>")]
+    pub exempt_supply: u64,
+    /// Shares held by token accounts
+    #[serde(with = "As::<DisplayFromStr>")]
+    pub outstanding_shares: u64,
+    /// Virtual shares and tokens added to the totals in every conversion
+    #[serde(with = "As::<DisplayFromStr>")]
+    pub virtual_shares_offset: u64,
+    /// If false, the supply can never be rebased lower than it is
+    pub allow_supply_decrease: bool,
+    /// Share of every increase of the supply taken as fee, in basis points
+    pub rebase_fee_bps: u16,
+    /// Token account credited with the rebase fee
+    #[serde(with = "As::<Option<DisplayFromStr>>")]
+    pub fee_destination: Option<Pubkey>,
+    /// Most shares that may exist at once, with the default applied
+    #[serde(with = "As::<DisplayFromStr>")]
+    pub max_total_shares: u64,
+    /// If true, the supply authority can sunset the mint
+    pub allow_sunset: bool,
+    /// True once the mint has been sunset, leaving every share worthless
+    pub is_sunset: bool,
+    /// If true, rebases write the exchange rate into the token-metadata
+    pub update_metadata_rate: bool,
+    /// True once the mint has been retired, freezing its ratio for good
+    pub is_retired: bool,
+    /// Supply that the ratio was frozen at on retirement
+    #[serde(with = "As::<DisplayFromStr>")]
+    pub retired_supply: u64,
+    /// Shares that the ratio was frozen at on retirement
+    #[serde(with = "As::<DisplayFromStr>")]
+    pub retired_shares: u64,
+    /// Key that must co-sign every change of the supply
+    #[serde(with = "As::<Option<DisplayFromStr>>")]
+    pub co_signer: Option<Pubkey>,
+    /// How the balances of token accounts follow rebases
+    pub rebase_mode: RebaseMode,
+    /// Index that lazy accounts catch up to, scaled by `REBASE_INDEX_SCALE`,
+    /// or zero in `RebaseMode::Shares`
+    #[serde(with = "As::<DisplayFromStr>")]
+    pub rebase_index: u128,
+    /// Whether rebases write the new supply into the base mint's supply
+    pub sync_base_supply: bool,
+    /// Sum of every increase of the total supply by a rebase
+    #[serde(with = "As::<DisplayFromStr>")]
+    pub cumulative_supply_increase: u128,
+    /// Sum of every decrease of the total supply by a rebase or sunset
+    #[serde(with = "As::<DisplayFromStr>")]
+    pub cumulative_supply_decrease: u128,
+    /// Tokens that one share is currently worth, or none before any share
+    /// exists. Only for display, since conversions round on integers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_ratio: Option<f64>,
+}
+
+impl From<&RebaseMintConfig> for UiRebaseMintConfig {
+    fn from(config: &RebaseMintConfig) -> Self {
+        let total_shares = config.virtual_total_shares();
+        let current_ratio =
+            (total_shares != 0).then(|| config.virtual_total_supply() as f64 / total_shares as f64);
+        Self {
+            supply_authority: config.supply_authority.into(),
+            total_supply: config.total_supply.into(),
+            total_shares: config.total_shares.into(),
+            reserve_account: config.reserve_account.into(),
+            first_rebase_after_timestamp: config.first_rebase_after_timestamp.into(),
+            pending_supply_authority: config.pending_supply_authority.into(),
+            two_step_authority_transfer: config.two_step_authority_transfer.into(),
+            authority_change_delay_seconds: config.authority_change_delay_seconds.into(),
+            scheduled_supply_authority: config.scheduled_supply_authority.into(),
+            authority_change_requested_timestamp: config
+                .authority_change_requested_timestamp
+                .into(),
+            rebase_delegate: config.rebase_delegate.into(),
+            use_mint_authority_fallback: config.use_mint_authority_fallback.into(),
+            require_rebase_memo: config.require_rebase_memo.into(),
+            has_rebased: config.has_rebased.into(),
+            exempt_supply: config.exempt_supply.into(),
+            outstanding_shares: config.outstanding_shares.into(),
+            virtual_shares_offset: config.virtual_shares_offset.into(),
+            allow_supply_decrease: config.allow_supply_decrease.into(),
+            rebase_fee_bps: config.rebase_fee_bps.into(),
+            fee_destination: config.fee_destination.into(),
+            max_total_shares: config.max_total_shares(),
+            allow_sunset: config.allow_sunset.into(),
+            is_sunset: config.is_sunset.into(),
+            update_metadata_rate: config.update_metadata_rate.into(),
+            is_retired: config.is_retired.into(),
+            retired_supply: config.retired_supply.into(),
+            retired_shares: config.retired_shares.into(),
+            co_signer: config.co_signer.into(),
+            // the program never stores any other mode
+            rebase_mode: config.rebase_mode().unwrap_or(RebaseMode::Shares),
+            rebase_index: config.rebase_index.into(),
+            sync_base_supply: config.sync_base_supply.into(),
+            cumulative_supply_increase: config.cumulative_supply_increase.into(),
+            cumulative_supply_decrease: config.cumulative_supply_decrease.into(),
+            current_ratio,
+        }
+    }
+}
+
+/// Display form of `RebaseAccount`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UiRebaseAccount {
+    /// Shares of the mint's total supply held by the account
+    #[serde(with = "As::<DisplayFromStr>")]
+    pub shares: u64,
+    /// If true, the account holds a fixed amount and is unaffected by rebases
+    pub rebase_exempt: bool,
+}
+
+impl From<&RebaseAccount> for UiRebaseAccount {
+    fn from(account: &RebaseAccount) -> Self {
+        Self {
+            shares: account.shares.into(),
+            rebase_exempt: account.rebase_exempt.into(),
+        }
+    }
+}
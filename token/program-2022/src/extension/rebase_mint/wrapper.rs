@@ -0,0 +1,46 @@
+use solana_program::pubkey::Pubkey;
+
+/// Seed for the wrapper mint's program-derived address
+pub const WRAPPER_MINT_SEED: &[u8] = b"rebase-wrapper-mint";
+
+/// Seed for the escrow token account's program-derived address
+pub const ESCROW_SEED: &[u8] = b"rebase-wrapper-escrow";
+
+/// Seed for the program-derived address that owns the escrow and mints the
+/// wrapper
+pub const WRAPPER_AUTHORITY_SEED: &[u8] = b"rebase-wrapper-authority";
+
+/// Get the address and bump seed of the wrapper mint for a rebasing mint
+pub fn get_wrapper_mint_address_and_bump_seed(mint: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[WRAPPER_MINT_SEED, mint.as_ref()], program_id)
+}
+
+/// Get the address of the wrapper mint for a rebasing mint
+pub fn get_wrapper_mint_address(mint: &Pubkey, program_id: &Pubkey) -> Pubkey {
+    get_wrapper_mint_address_and_bump_seed(mint, program_id).0
+}
+
+/// Get the address and bump seed of the escrow token account holding the
+/// wrapped shares of a rebasing mint
+pub fn get_escrow_address_and_bump_seed(mint: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ESCROW_SEED, mint.as_ref()], program_id)
+}
+
+/// Get the address of the escrow token account holding the wrapped shares of
+/// a rebasing mint
+pub fn get_escrow_address(mint: &Pubkey, program_id: &Pubkey) -> Pubkey {
+    get_escrow_address_and_bump_seed(mint, program_id).0
+}
+
+/// Get the address and bump seed of the wrapper authority for a rebasing mint
+pub fn get_wrapper_authority_address_and_bump_seed(
+    mint: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[WRAPPER_AUTHORITY_SEED, mint.as_ref()], program_id)
+}
+
+/// Get the address of the wrapper authority for a rebasing mint
+pub fn get_wrapper_authority_address(mint: &Pubkey, program_id: &Pubkey) -> Pubkey {
+    get_wrapper_authority_address_and_bump_seed(mint, program_id).0
+}
@@ -102,7 +102,8 @@ pub enum TransferFeeInstruction {
     ///
     ///   * Single owner/delegate
     ///   0. `[]` The token mint. Must include the `TransferFeeConfig`
-    ///      extension.
+    ///      extension. Must be writable if the mint is rebasing and the fee
+    ///      receiver is exempt from rebasing.
     ///   1. `[writable]` The fee receiver account. Must include the
     ///      `TransferFeeAmount` extension and be associated with the provided
     ///      mint.
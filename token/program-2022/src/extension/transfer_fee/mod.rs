@@ -126,7 +126,7 @@ pub struct TransferFeeConfig {
     /// Withdraw from mint instructions must be signed by this key
     pub withdraw_withheld_authority: OptionalNonZeroPubkey,
     /// Withheld transfer fee tokens that have been moved to the mint for
-    /// withdrawal
+    /// withdrawal, in shares for a rebasing mint
     pub withheld_amount: PodU64,
     /// Older transfer fee, used if the current epoch < new_transfer_fee.epoch
     pub older_transfer_fee: TransferFee,
@@ -162,7 +162,8 @@ impl Extension for TransferFeeConfig {
 #[cfg_attr(feature = "serde-traits", serde(rename_all = "camelCase"))]
 #[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
 pub struct TransferFeeAmount {
-    /// Amount withheld during transfers, to be harvested to the mint
+    /// Amount withheld during transfers, to be harvested to the mint, in
+    /// shares for a rebasing mint
     pub withheld_amount: PodU64,
 }
 impl TransferFeeAmount {
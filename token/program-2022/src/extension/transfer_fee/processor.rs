@@ -3,11 +3,12 @@ use {
         check_program_account,
         error::TokenError,
         extension::{
+            rebase_mint::{self, credit_withheld, RebaseMintConfig},
             transfer_fee::{
                 instruction::TransferFeeInstruction, TransferFee, TransferFeeAmount,
                 TransferFeeConfig, MAX_FEE_BASIS_POINTS,
             },
-            BaseStateWithExtensions, StateWithExtensions, StateWithExtensionsMut,
+            BaseStateWithExtensions, StateWithExtensionsMut,
         },
         processor::Processor,
         state::{Account, Mint},
@@ -17,6 +18,7 @@ use {
         clock::Clock,
         entrypoint::ProgramResult,
         msg,
+        program_error::ProgramError,
         program_option::COption,
         pubkey::Pubkey,
         sysvar::Sysvar,
@@ -148,11 +150,19 @@ fn process_withdraw_withheld_tokens_from_mint(
     }
     let withheld_amount = u64::from(extension.withheld_amount);
     extension.withheld_amount = 0.into();
-    destination_account.base.amount = destination_account
-        .base
-        .amount
-        .checked_add(withheld_amount)
-        .ok_or(TokenError::Overflow)?;
+    if let Ok(rebase_mint_config) = mint.get_extension_mut::<RebaseMintConfig>() {
+        credit_withheld(
+            rebase_mint_config,
+            &mut destination_account,
+            withheld_amount,
+        )?;
+    } else {
+        destination_account.base.amount = destination_account
+            .base
+            .amount
+            .checked_add(withheld_amount)
+            .ok_or(TokenError::Overflow)?;
+    }
     destination_account.pack_base();
 
     Ok(())
@@ -221,8 +231,8 @@ fn process_withdraw_withheld_tokens_from_accounts(
     // unnecessary check, but helps for clarity
     check_program_account(mint_account_info.owner)?;
 
-    let mint_data = mint_account_info.data.borrow();
-    let mint = StateWithExtensions::<Mint>::unpack(&mint_data)?;
+    let mut mint_data = mint_account_info.data.borrow_mut();
+    let mut mint = StateWithExtensionsMut::<Mint>::unpack(&mut mint_data)?;
     let extension = mint.get_extension::<TransferFeeConfig>()?;
 
     let withdraw_withheld_authority = Option::<Pubkey>::from(extension.withdraw_withheld_authority)
@@ -244,6 +254,7 @@ fn process_withdraw_withheld_tokens_from_accounts(
     if destination_account.base.is_frozen() {
         return Err(TokenError::AccountFrozen.into());
     }
+    let mut withheld_amount = 0u64;
     for account_info in &account_infos[num_signers..] {
         // self-harvest, can't double-borrow the underlying data
         if account_info.key == destination_account_info.key {
@@ -252,17 +263,13 @@ fn process_withdraw_withheld_tokens_from_accounts(
                 .map_err(|_| TokenError::InvalidState)?;
             let account_withheld_amount = u64::from(token_account_extension.withheld_amount);
             token_account_extension.withheld_amount = 0.into();
-            destination_account.base.amount = destination_account
-                .base
-                .amount
+            withheld_amount = withheld_amount
                 .checked_add(account_withheld_amount)
                 .ok_or(TokenError::Overflow)?;
         } else {
             match harvest_from_account(mint_account_info.key, account_info) {
                 Ok(amount) => {
-                    destination_account.base.amount = destination_account
-                        .base
-                        .amount
+                    withheld_amount = withheld_amount
                         .checked_add(amount)
                         .ok_or(TokenError::Overflow)?;
                 }
@@ -272,6 +279,24 @@ fn process_withdraw_withheld_tokens_from_accounts(
             }
         }
     }
+    if let Ok(rebase_mint_config) = mint.get_extension_mut::<RebaseMintConfig>() {
+        // paying out to an exempt account changes the totals on the mint
+        if rebase_mint::is_exempt(&destination_account) && !mint_account_info.is_writable {
+            msg!("Mint must be writable to withdraw into a rebase-exempt account");
+            return Err(ProgramError::InvalidArgument);
+        }
+        credit_withheld(
+            rebase_mint_config,
+            &mut destination_account,
+            withheld_amount,
+        )?;
+    } else {
+        destination_account.base.amount = destination_account
+            .base
+            .amount
+            .checked_add(withheld_amount)
+            .ok_or(TokenError::Overflow)?;
+    }
     destination_account.pack_base();
 
     Ok(())
@@ -152,6 +152,9 @@ pub enum TokenInstruction<'a> {
     /// Approves a delegate.  A delegate is given the authority over tokens on
     /// behalf of the source account's owner.
     ///
+    /// Fails for accounts of a rebasing mint, which must use `ApproveChecked`
+    /// so the amount can be converted to shares.
+    ///
     /// Accounts expected by this instruction:
     ///
     ///   * Single owner
@@ -344,6 +347,13 @@ pub enum TokenInstruction<'a> {
     /// decimals value is checked by the caller.  This may be useful when
     /// creating transactions offline or within a hardware wallet.
     ///
+    /// For a rebasing mint, the allowance is stored as the number of shares
+    /// the amount is worth at approval time, rounded down, and delegated
+    /// transfers and burns spend it in shares. The delegate's allowance
+    /// therefore scales with every rebase, like the balance it draws from.
+    /// Use `RebaseMintInstruction::ApproveShares` to approve an exact number
+    /// of shares.
+    ///
     /// Accounts expected by this instruction:
     ///
     ///   * Single owner
@@ -487,6 +497,10 @@ pub enum TokenInstruction<'a> {
     /// Return data can be fetched using `sol_get_return_data` and deserializing
     /// the return data as a little-endian `u64`.
     ///
+    /// The size always includes the account extensions required by the mint's
+    /// extensions, such as `RebaseAccount` for a rebasing mint. Passing a mint
+    /// extension type fails with `TokenError::ExtensionTypeMismatch`.
+    ///
     /// Accounts expected by this instruction:
     ///
     ///   0. `[]` The mint to calculate for
@@ -509,7 +523,9 @@ pub enum TokenInstruction<'a> {
     /// Convert an Amount of tokens to a UiAmount `string`, using the given
     /// mint.
     ///
-    /// Fails on an invalid mint.
+    /// For a rebasing mint, the amount is a number of shares, converted at
+    /// the current ratio. Fails on an invalid mint, or a mint that is both
+    /// rebasing and interest-bearing.
     ///
     /// Return data can be fetched using `sol_get_return_data` and deserialized
     /// with `String::from_utf8`.
@@ -524,6 +540,9 @@ pub enum TokenInstruction<'a> {
     /// Convert a UiAmount of tokens to a little-endian `u64` raw Amount, using
     /// the given mint.
     ///
+    /// For a rebasing mint, the raw Amount is the number of shares worth the
+    /// UiAmount at the current ratio.
+    ///
     /// Return data can be fetched using `sol_get_return_data` and deserializing
     /// the return data as a little-endian `u64`.
     ///
@@ -575,6 +594,10 @@ pub enum TokenInstruction<'a> {
     /// ExtensionTypes, and if not, use reallocation to increase the data
     /// size.
     ///
+    /// The rebasing extensions are only set during initialization, so
+    /// requesting `RebaseMintConfig`, or `RebaseAccount` on an account that
+    /// lacks it, fails with `TokenError::RebaseExtensionNotReallocatable`.
+    ///
     /// Accounts expected by this instruction:
     ///
     ///   * Single owner
@@ -700,6 +723,13 @@ pub enum TokenInstruction<'a> {
     /// for further details about the extended instructions that share this
     /// instruction prefix
     GroupMemberPointerExtension,
+    /// The common instruction prefix for rebasing mint extension
+    /// instructions.
+    ///
+    /// See `extension::rebase_mint::instruction::RebaseMintInstruction` for
+    /// further details about the extended instructions that share this
+    /// instruction prefix
+    RebaseMintExtension,
 }
 impl<'a> TokenInstruction<'a> {
     /// Unpacks a byte buffer into a
@@ -842,6 +872,7 @@ impl<'a> TokenInstruction<'a> {
             39 => Self::MetadataPointerExtension,
             40 => Self::GroupPointerExtension,
             41 => Self::GroupMemberPointerExtension,
+            42 => Self::RebaseMintExtension,
             _ => return Err(TokenError::InvalidInstruction.into()),
         })
     }
@@ -1014,6 +1045,9 @@ impl<'a> TokenInstruction<'a> {
             &Self::GroupMemberPointerExtension => {
                 buf.push(41);
             }
+            &Self::RebaseMintExtension => {
+                buf.push(42);
+            }
         };
         buf
     }
@@ -1111,6 +1145,8 @@ pub enum AuthorityType {
     GroupPointer,
     /// Authority to set the group member address
     GroupMemberPointer,
+    /// Authority to rebase the supply
+    RebaseSupply,
 }
 
 impl AuthorityType {
@@ -1131,6 +1167,7 @@ impl AuthorityType {
             AuthorityType::MetadataPointer => 12,
             AuthorityType::GroupPointer => 13,
             AuthorityType::GroupMemberPointer => 14,
+            AuthorityType::RebaseSupply => 15,
         }
     }
 
@@ -1151,6 +1188,7 @@ impl AuthorityType {
             12 => Ok(AuthorityType::MetadataPointer),
             13 => Ok(AuthorityType::GroupPointer),
             14 => Ok(AuthorityType::GroupMemberPointer),
+            15 => Ok(AuthorityType::RebaseSupply),
             _ => Err(TokenError::InvalidInstruction.into()),
         }
     }
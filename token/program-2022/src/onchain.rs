@@ -1,14 +1,18 @@
-//! On-chain program invoke helper to perform on-chain `transfer_checked` with
-//! correct accounts
+//! On-chain program invoke helpers to perform on-chain `transfer_checked`
+//! and share transfers with correct accounts
 
 use {
     crate::{
-        extension::{transfer_hook, StateWithExtensions},
+        error::TokenError,
+        extension::{
+            rebase_mint::{self, RebaseMintConfig},
+            transfer_hook, BaseStateWithExtensions, StateWithExtensions,
+        },
         instruction,
         state::Mint,
     },
     solana_program::{
-        account_info::AccountInfo, entrypoint::ProgramResult, instruction::AccountMeta,
+        account_info::AccountInfo, entrypoint::ProgramResult, instruction::AccountMeta, msg,
         program::invoke_signed, pubkey::Pubkey,
     },
     spl_transfer_hook_interface::onchain::add_extra_accounts_for_execute_cpi,
@@ -78,3 +82,95 @@ pub fn invoke_transfer_checked<'a>(
 
     invoke_signed(&cpi_instruction, &cpi_account_infos, seeds)
 }
+
+/// Helper to CPI into token-2022 on-chain to transfer the shares of a rebasing
+/// mint that are currently worth `amount`, failing if that's fewer than
+/// `min_shares_out`, or if a nonzero `amount` is worth less than one share.
+///
+/// The shares are converted once and moved exactly with `TransferSharesChecked`,
+/// so the transfer can't round differently from the check. Additional account
+/// infos are handled as in `invoke_transfer_checked`.
+#[allow(clippy::too_many_arguments)]
+pub fn invoke_transfer_checked_rebased<'a>(
+    token_program_id: &Pubkey,
+    source_info: AccountInfo<'a>,
+    mint_info: AccountInfo<'a>,
+    destination_info: AccountInfo<'a>,
+    authority_info: AccountInfo<'a>,
+    additional_accounts: &[AccountInfo<'a>],
+    amount: u64,
+    decimals: u8,
+    min_shares_out: u64,
+    seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    // scope the borrowing to avoid a double-borrow during CPI
+    let (shares, transferred_amount, maybe_transfer_hook_program_id) = {
+        let mint_data = mint_info.try_borrow_data()?;
+        let mint = StateWithExtensions::<Mint>::unpack(&mint_data)?;
+        let rebase_mint_config = mint.get_extension::<RebaseMintConfig>()?;
+        let shares = rebase_mint_config.amount_to_nonzero_shares(amount)?;
+        if shares < min_shares_out {
+            msg!(
+                "Transfer of {} would move {} shares, less than the minimum of {}",
+                amount,
+                shares,
+                min_shares_out
+            );
+            return Err(TokenError::ShareSlippageExceeded.into());
+        }
+        let transferred_amount = rebase_mint_config
+            .shares_to_amount(shares)
+            .ok_or(TokenError::Overflow)?;
+        (
+            shares,
+            transferred_amount,
+            transfer_hook::get_program_id(&mint),
+        )
+    };
+
+    let mut cpi_instruction = rebase_mint::instruction::transfer_shares_checked(
+        token_program_id,
+        source_info.key,
+        mint_info.key,
+        destination_info.key,
+        authority_info.key,
+        &[], // add them later, to avoid unnecessary clones
+        shares,
+        decimals,
+    )?;
+
+    let mut cpi_account_infos = vec![
+        source_info.clone(),
+        mint_info.clone(),
+        destination_info.clone(),
+        authority_info.clone(),
+    ];
+
+    // if it's a signer, it might be a multisig signer, throw it in!
+    additional_accounts
+        .iter()
+        .filter(|ai| ai.is_signer)
+        .for_each(|ai| {
+            cpi_account_infos.push(ai.clone());
+            cpi_instruction
+                .accounts
+                .push(AccountMeta::new_readonly(*ai.key, ai.is_signer));
+        });
+
+    // the hook sees the value of the shares actually moved
+    if let Some(program_id) = maybe_transfer_hook_program_id {
+        add_extra_accounts_for_execute_cpi(
+            &mut cpi_instruction,
+            &mut cpi_account_infos,
+            &program_id,
+            source_info,
+            mint_info,
+            destination_info,
+            authority_info,
+            transferred_amount,
+            additional_accounts,
+        )?;
+    }
+
+    invoke_signed(&cpi_instruction, &cpi_account_infos, seeds)
+}
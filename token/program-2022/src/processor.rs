@@ -20,7 +20,13 @@ use {
             mint_close_authority::MintCloseAuthority,
             non_transferable::{NonTransferable, NonTransferableAccount},
             permanent_delegate::{get_permanent_delegate, PermanentDelegate},
-            reallocate, token_group, token_metadata,
+            reallocate,
+            rebase_mint::{
+                self, catch_up_lazy_account, check_share_slippage, credit_exempt, credit_shares,
+                debit_exempt, debit_shares, transfer_exempt, LazyRebaseAccount, RebaseAccount,
+                RebaseMintConfig,
+            },
+            token_group, token_metadata,
             transfer_fee::{self, TransferFeeAmount, TransferFeeConfig},
             transfer_hook::{self, TransferHook, TransferHookAccount},
             AccountType, BaseStateWithExtensions, ExtensionType, StateWithExtensions,
@@ -43,11 +49,21 @@ use {
         system_instruction, system_program,
         sysvar::{rent::Rent, Sysvar},
     },
+    spl_pod::optional_keys::OptionalNonZeroPubkey,
     spl_token_group_interface::instruction::TokenGroupInstruction,
     spl_token_metadata_interface::instruction::TokenMetadataInstruction,
     std::convert::{TryFrom, TryInto},
 };
 
+/// Quantity moved by a transfer, mint, or burn
+#[derive(Clone, Copy)]
+enum Quantity {
+    /// Amount of tokens
+    Amount(u64),
+    /// Shares of a rebasing mint
+    Shares(u64),
+}
+
 /// Program state handler.
 pub struct Processor {}
 impl Processor {
@@ -279,6 +295,64 @@ impl Processor {
         amount: u64,
         expected_decimals: Option<u8>,
         expected_fee: Option<u64>,
+    ) -> ProgramResult {
+        Self::_process_transfer(
+            program_id,
+            accounts,
+            Quantity::Amount(amount),
+            expected_decimals,
+            expected_fee,
+            None,
+        )
+    }
+
+    /// Processes a transfer of an amount of a rebasing mint, failing if the
+    /// number of shares it moves is outside `max_share_slippage_bps` basis
+    /// points of `expected_shares`. The accounts are the same as for
+    /// `TransferChecked`.
+    pub fn process_transfer_with_share_slippage(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+        expected_decimals: u8,
+        expected_shares: u64,
+        max_share_slippage_bps: u16,
+    ) -> ProgramResult {
+        Self::_process_transfer(
+            program_id,
+            accounts,
+            Quantity::Amount(amount),
+            Some(expected_decimals),
+            None,
+            Some((expected_shares, max_share_slippage_bps)),
+        )
+    }
+
+    /// Processes a transfer of an exact number of shares of a rebasing mint.
+    /// The accounts are the same as for `TransferChecked`.
+    pub fn process_transfer_shares(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        shares: u64,
+        expected_decimals: u8,
+    ) -> ProgramResult {
+        Self::_process_transfer(
+            program_id,
+            accounts,
+            Quantity::Shares(shares),
+            Some(expected_decimals),
+            None,
+            None,
+        )
+    }
+
+    fn _process_transfer(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        quantity: Quantity,
+        expected_decimals: Option<u8>,
+        expected_fee: Option<u64>,
+        share_slippage: Option<(u64, u16)>,
     ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
 
@@ -300,8 +374,16 @@ impl Processor {
         if source_account.base.is_frozen() {
             return Err(TokenError::AccountFrozen.into());
         }
-        if source_account.base.amount < amount {
-            return Err(TokenError::InsufficientFunds.into());
+        // the amount of a rebasing account is stale, so its shares, or its
+        // amount caught up to the rebase index, are checked once the mint is
+        // known
+        let source_rebase_account = source_account.get_extension::<RebaseAccount>().copied();
+        let is_rebase_account = source_rebase_account.is_ok()
+            || source_account.get_extension::<LazyRebaseAccount>().is_ok();
+        if let Quantity::Amount(amount) = quantity {
+            if !is_rebase_account && source_account.base.amount < amount {
+                return Err(TokenError::InsufficientFunds.into());
+            }
         }
         if source_account
             .get_extension::<NonTransferableAccount>()
@@ -309,57 +391,141 @@ impl Processor {
         {
             return Err(TokenError::NonTransferable.into());
         }
-        let (fee, maybe_permanent_delegate, maybe_transfer_hook_program_id) =
-            if let Some((mint_info, expected_decimals)) = expected_mint_info {
-                if !cmp_pubkeys(&source_account.base.mint, mint_info.key) {
-                    return Err(TokenError::MintMismatch.into());
-                }
+        let (
+            amount,
+            fee,
+            maybe_permanent_delegate,
+            maybe_transfer_hook_program_id,
+            maybe_rebase_shares,
+            maybe_lazy_rebase_config,
+        ) = if let Some((mint_info, expected_decimals)) = expected_mint_info {
+            if !cmp_pubkeys(&source_account.base.mint, mint_info.key) {
+                return Err(TokenError::MintMismatch.into());
+            }
 
-                let mint_data = mint_info.try_borrow_data()?;
-                let mint = StateWithExtensions::<Mint>::unpack(&mint_data)?;
+            let mint_data = mint_info.try_borrow_data()?;
+            let mint = StateWithExtensions::<Mint>::unpack(&mint_data)?;
 
-                if expected_decimals != mint.base.decimals {
-                    return Err(TokenError::MintDecimalsMismatch.into());
-                }
+            if expected_decimals != mint.base.decimals {
+                return Err(TokenError::MintDecimalsMismatch.into());
+            }
 
-                let fee = if let Ok(transfer_fee_config) = mint.get_extension::<TransferFeeConfig>()
-                {
-                    transfer_fee_config
-                        .calculate_epoch_fee(Clock::get()?.epoch, amount)
-                        .ok_or(TokenError::Overflow)?
-                } else {
-                    0
+            let (amount, maybe_rebase_shares, maybe_lazy_rebase_config) =
+                match (mint.get_extension::<RebaseMintConfig>(), quantity) {
+                    // a lazy account moves its amount once caught up, and has
+                    // no shares to move
+                    (Ok(rebase_mint_config), Quantity::Amount(amount))
+                        if rebase_mint_config.is_lazy() =>
+                    {
+                        if share_slippage.is_some() {
+                            return Err(TokenError::RebaseModeMismatch.into());
+                        }
+                        catch_up_lazy_account(rebase_mint_config, &mut source_account)?;
+                        if source_account.base.amount < amount {
+                            return Err(TokenError::InsufficientFunds.into());
+                        }
+                        (amount, None, Some(*rebase_mint_config))
+                    }
+                    (Ok(rebase_mint_config), Quantity::Shares(_))
+                        if rebase_mint_config.is_lazy() =>
+                    {
+                        return Err(TokenError::RebaseModeMismatch.into());
+                    }
+                    (Ok(rebase_mint_config), quantity) => {
+                        let (amount, shares) = match quantity {
+                            Quantity::Amount(amount) => {
+                                (amount, rebase_mint_config.amount_to_nonzero_shares(amount)?)
+                            }
+                            Quantity::Shares(shares) => (
+                                rebase_mint_config
+                                    .shares_to_amount(shares)
+                                    .ok_or(TokenError::Overflow)?,
+                                shares,
+                            ),
+                        };
+                        if let Some((expected_shares, max_share_slippage_bps)) = share_slippage {
+                            check_share_slippage(shares, expected_shares, max_share_slippage_bps)
+                                .map_err(|e| {
+                                msg!(
+                                    "Transfer moves {} shares, expected {} within {} bps",
+                                    shares,
+                                    expected_shares,
+                                    max_share_slippage_bps
+                                );
+                                e
+                            })?;
+                        }
+                        let source_rebase_account = source_rebase_account.clone()?;
+                        let insufficient_funds = if source_rebase_account.is_exempt() {
+                            source_account.base.amount < amount
+                        } else {
+                            u64::from(source_rebase_account.shares) < shares
+                        };
+                        if insufficient_funds {
+                            return Err(TokenError::InsufficientFunds.into());
+                        }
+                        (amount, Some((*rebase_mint_config, shares)), None)
+                    }
+                    (Err(err), Quantity::Amount(_)) if share_slippage.is_some() => return Err(err),
+                    (Err(_), Quantity::Amount(amount)) => (amount, None, None),
+                    (Err(err), Quantity::Shares(_)) => return Err(err),
                 };
 
-                let maybe_permanent_delegate = get_permanent_delegate(&mint);
-                let maybe_transfer_hook_program_id = transfer_hook::get_program_id(&mint);
-
-                (
-                    fee,
-                    maybe_permanent_delegate,
-                    maybe_transfer_hook_program_id,
-                )
+            let fee = if let Ok(transfer_fee_config) = mint.get_extension::<TransferFeeConfig>() {
+                transfer_fee_config
+                    .calculate_epoch_fee(Clock::get()?.epoch, amount)
+                    .ok_or(TokenError::Overflow)?
             } else {
-                // Transfer hook extension exists on the account, but no mint
-                // was provided to figure out required accounts, abort
-                if source_account
-                    .get_extension::<TransferHookAccount>()
-                    .is_ok()
-                {
-                    return Err(TokenError::MintRequiredForTransfer.into());
-                }
+                0
+            };
 
-                // Transfer fee amount extension exists on the account, but no mint
-                // was provided to calculate the fee, abort
-                if source_account
-                    .get_extension_mut::<TransferFeeAmount>()
-                    .is_ok()
-                {
+            let maybe_permanent_delegate = get_permanent_delegate(&mint);
+            let maybe_transfer_hook_program_id = transfer_hook::get_program_id(&mint);
+
+            (
+                amount,
+                fee,
+                maybe_permanent_delegate,
+                maybe_transfer_hook_program_id,
+                maybe_rebase_shares,
+                maybe_lazy_rebase_config,
+            )
+        } else {
+            // Shares can only be converted with the mint
+            let amount = match quantity {
+                Quantity::Amount(amount) => amount,
+                Quantity::Shares(_) => {
                     return Err(TokenError::MintRequiredForTransfer.into());
-                } else {
-                    (0, None, None)
                 }
             };
+
+            // Transfer hook extension exists on the account, but no mint
+            // was provided to figure out required accounts, abort
+            if source_account
+                .get_extension::<TransferHookAccount>()
+                .is_ok()
+            {
+                return Err(TokenError::MintRequiredForTransfer.into());
+            }
+
+            // Rebase account extension exists on the account, but no mint
+            // was provided to convert the amount to shares, or to catch it
+            // up, abort
+            if is_rebase_account {
+                return Err(TokenError::MintRequiredForTransfer.into());
+            }
+
+            // Transfer fee amount extension exists on the account, but no mint
+            // was provided to calculate the fee, abort
+            if source_account
+                .get_extension_mut::<TransferFeeAmount>()
+                .is_ok()
+            {
+                return Err(TokenError::MintRequiredForTransfer.into());
+            } else {
+                (amount, 0, None, None, None, None)
+            }
+        };
         if let Some(expected_fee) = expected_fee {
             if expected_fee != fee {
                 msg!("Calculated fee {}, received {}", fee, expected_fee);
@@ -386,14 +552,16 @@ impl Processor {
                     authority_info_data_len,
                     account_info_iter.as_slice(),
                 )?;
-                if source_account.base.delegated_amount < amount {
+                // a rebasing account's allowance is in shares
+                let delegated_quantity = maybe_rebase_shares.map_or(amount, |(_, shares)| shares);
+                if source_account.base.delegated_amount < delegated_quantity {
                     return Err(TokenError::InsufficientFunds.into());
                 }
                 if !self_transfer {
                     source_account.base.delegated_amount = source_account
                         .base
                         .delegated_amount
-                        .checked_sub(amount)
+                        .checked_sub(delegated_quantity)
                         .ok_or(TokenError::Overflow)?;
                     if source_account.base.delegated_amount == 0 {
                         source_account.base.delegate = COption::None;
@@ -426,6 +594,10 @@ impl Processor {
         // This check MUST occur just before the amounts are manipulated
         // to ensure self-transfers are fully validated
         if self_transfer {
+            // keep the catch-up, which already moved the rebase index
+            if maybe_lazy_rebase_config.is_some() {
+                source_account.pack_base();
+            }
             return Ok(());
         }
 
@@ -440,6 +612,9 @@ impl Processor {
         if !cmp_pubkeys(&source_account.base.mint, &destination_account.base.mint) {
             return Err(TokenError::MintMismatch.into());
         }
+        if let Some(rebase_mint_config) = maybe_lazy_rebase_config {
+            catch_up_lazy_account(&rebase_mint_config, &mut destination_account)?;
+        }
 
         if memo_required(&destination_account) {
             check_previous_sibling_instruction_is_memo()?;
@@ -451,21 +626,62 @@ impl Processor {
             confidential_transfer_state.non_confidential_transfer_allowed()?
         }
 
-        source_account.base.amount = source_account
-            .base
-            .amount
-            .checked_sub(amount)
-            .ok_or(TokenError::Overflow)?;
-        let credited_amount = amount.checked_sub(fee).ok_or(TokenError::Overflow)?;
-        destination_account.base.amount = destination_account
-            .base
-            .amount
-            .checked_add(credited_amount)
-            .ok_or(TokenError::Overflow)?;
-        if fee > 0 {
+        let withheld = if let Some((rebase_mint_config, shares)) = maybe_rebase_shares {
+            // the fee applies to the amount, but is withheld in shares, so that
+            // it keeps its value across rebases
+            let fee_shares = rebase_mint_config
+                .amount_to_shares_rounding_up(fee)
+                .ok_or(TokenError::Overflow)?
+                .min(shares);
+            let source_is_exempt = source_rebase_account
+                .as_ref()
+                .map_or(false, RebaseAccount::is_exempt);
+            if source_is_exempt || rebase_mint::is_exempt(&destination_account) {
+                // tokens cross between the shares and the exempt supply, so the
+                // totals on the mint change
+                let (mint_info, _) =
+                    expected_mint_info.ok_or(TokenError::MintRequiredForTransfer)?;
+                if !mint_info.is_writable {
+                    msg!("Mint must be writable to transfer with a rebase-exempt account");
+                    return Err(ProgramError::InvalidArgument);
+                }
+                let mut mint_data = mint_info.try_borrow_mut_data()?;
+                let mut mint = StateWithExtensionsMut::<Mint>::unpack(&mut mint_data)?;
+                transfer_exempt(
+                    mint.get_extension_mut::<RebaseMintConfig>()?,
+                    &mut source_account,
+                    &mut destination_account,
+                    shares,
+                    fee_shares,
+                    amount,
+                )?;
+            } else {
+                debit_shares(&rebase_mint_config, &mut source_account, shares)?;
+                credit_shares(
+                    &rebase_mint_config,
+                    &mut destination_account,
+                    shares.checked_sub(fee_shares).ok_or(TokenError::Overflow)?,
+                )?;
+            }
+            fee_shares
+        } else {
+            source_account.base.amount = source_account
+                .base
+                .amount
+                .checked_sub(amount)
+                .ok_or(TokenError::Overflow)?;
+            let credited_amount = amount.checked_sub(fee).ok_or(TokenError::Overflow)?;
+            destination_account.base.amount = destination_account
+                .base
+                .amount
+                .checked_add(credited_amount)
+                .ok_or(TokenError::Overflow)?;
+            fee
+        };
+        if withheld > 0 {
             if let Ok(extension) = destination_account.get_extension_mut::<TransferFeeAmount>() {
                 let new_withheld_amount = u64::from(extension.withheld_amount)
-                    .checked_add(fee)
+                    .checked_add(withheld)
                     .ok_or(TokenError::Overflow)?;
                 extension.withheld_amount = new_withheld_amount.into();
             } else {
@@ -500,6 +716,8 @@ impl Processor {
                 // must drop these to avoid the double-borrow during CPI
                 drop(source_account_data);
                 drop(destination_account_data);
+                // on a rebasing mint, the hook sees the amount in tokens at the
+                // current ratio, even when a number of shares was transferred
                 spl_transfer_hook_interface::onchain::invoke_execute(
                     &program_id,
                     source_account_info.clone(),
@@ -527,6 +745,36 @@ impl Processor {
         accounts: &[AccountInfo],
         amount: u64,
         expected_decimals: Option<u8>,
+    ) -> ProgramResult {
+        Self::_process_approve(
+            program_id,
+            accounts,
+            Quantity::Amount(amount),
+            expected_decimals,
+        )
+    }
+
+    /// Processes an approval of an exact number of shares of a rebasing mint.
+    /// The accounts are the same as for `ApproveChecked`.
+    pub fn process_approve_shares(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        shares: u64,
+        expected_decimals: u8,
+    ) -> ProgramResult {
+        Self::_process_approve(
+            program_id,
+            accounts,
+            Quantity::Shares(shares),
+            Some(expected_decimals),
+        )
+    }
+
+    fn _process_approve(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        quantity: Quantity,
+        expected_decimals: Option<u8>,
     ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
 
@@ -549,7 +797,9 @@ impl Processor {
             return Err(TokenError::AccountFrozen.into());
         }
 
-        if let Some((mint_info, expected_decimals)) = expected_mint_info {
+        // allowances on rebasing accounts are kept in shares, so that they
+        // scale with the balance they draw from
+        let delegated_amount = if let Some((mint_info, expected_decimals)) = expected_mint_info {
             if !cmp_pubkeys(&source_account.base.mint, mint_info.key) {
                 return Err(TokenError::MintMismatch.into());
             }
@@ -559,7 +809,38 @@ impl Processor {
             if expected_decimals != mint.base.decimals {
                 return Err(TokenError::MintDecimalsMismatch.into());
             }
-        }
+
+            match (mint.get_extension::<RebaseMintConfig>(), quantity) {
+                // a lazy account has no shares, so its allowance is in tokens
+                (Ok(rebase_mint_config), Quantity::Amount(amount))
+                    if rebase_mint_config.is_lazy() =>
+                {
+                    amount
+                }
+                (Ok(rebase_mint_config), Quantity::Shares(_)) if rebase_mint_config.is_lazy() => {
+                    return Err(TokenError::RebaseModeMismatch.into())
+                }
+                // round down, so the delegate is never allowed more than the
+                // amount was worth
+                (Ok(rebase_mint_config), Quantity::Amount(amount)) => rebase_mint_config
+                    .amount_to_shares(amount)
+                    .ok_or(TokenError::Overflow)?,
+                (Ok(_), Quantity::Shares(shares)) => shares,
+                (Err(_), Quantity::Amount(amount)) => amount,
+                (Err(err), Quantity::Shares(_)) => return Err(err),
+            }
+        } else {
+            // Rebase account extension exists on the account, but no mint
+            // was provided to convert the amount to shares, abort
+            match quantity {
+                Quantity::Amount(amount)
+                    if source_account.get_extension::<RebaseAccount>().is_err() =>
+                {
+                    amount
+                }
+                _ => return Err(TokenError::MintRequiredForTransfer.into()),
+            }
+        };
 
         Self::validate_owner(
             program_id,
@@ -576,7 +857,7 @@ impl Processor {
         }
 
         source_account.base.delegate = COption::Some(*delegate_info.key);
-        source_account.base.delegated_amount = amount;
+        source_account.base.delegated_amount = delegated_amount;
         source_account.pack_base();
 
         Ok(())
@@ -880,6 +1161,26 @@ impl Processor {
                     )?;
                     extension.authority = new_authority.try_into()?;
                 }
+                AuthorityType::RebaseSupply => {
+                    let extension = mint.get_extension_mut::<RebaseMintConfig>()?;
+                    let maybe_supply_authority: Option<Pubkey> = extension.supply_authority.into();
+                    let supply_authority =
+                        maybe_supply_authority.ok_or(TokenError::AuthorityTypeNotSupported)?;
+                    Self::validate_owner(
+                        program_id,
+                        &supply_authority,
+                        authority_info,
+                        authority_info_data_len,
+                        account_info_iter.as_slice(),
+                    )?;
+                    let new_authority: OptionalNonZeroPubkey = new_authority.try_into()?;
+                    extension.check_direct_authority_change(&new_authority)?;
+                    rebase_mint::processor::change_supply_authority(
+                        account_info.key,
+                        extension,
+                        new_authority,
+                    )?;
+                }
                 _ => {
                     return Err(TokenError::AuthorityTypeNotSupported.into());
                 }
@@ -897,6 +1198,30 @@ impl Processor {
         accounts: &[AccountInfo],
         amount: u64,
         expected_decimals: Option<u8>,
+    ) -> ProgramResult {
+        Self::_process_mint_to(
+            program_id,
+            accounts,
+            Quantity::Amount(amount),
+            expected_decimals,
+        )
+    }
+
+    /// Processes a mint of an exact number of shares of a rebasing mint.
+    /// The accounts are the same as for `MintTo`.
+    pub fn process_mint_to_shares(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        shares: u64,
+    ) -> ProgramResult {
+        Self::_process_mint_to(program_id, accounts, Quantity::Shares(shares), None)
+    }
+
+    fn _process_mint_to(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        quantity: Quantity,
+        expected_decimals: Option<u8>,
     ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
         let mint_info = next_account_info(account_info_iter)?;
@@ -954,11 +1279,55 @@ impl Processor {
         check_program_account(mint_info.owner)?;
         check_program_account(destination_account_info.owner)?;
 
-        destination_account.base.amount = destination_account
-            .base
-            .amount
-            .checked_add(amount)
-            .ok_or(TokenError::Overflow)?;
+        let amount = match (mint.get_extension_mut::<RebaseMintConfig>(), quantity) {
+            (Ok(rebase_mint_config), Quantity::Amount(amount)) if rebase_mint_config.is_lazy() => {
+                catch_up_lazy_account(rebase_mint_config, &mut destination_account)?;
+                rebase_mint_config.add_lazy_supply(amount)?;
+                destination_account.base.amount = destination_account
+                    .base
+                    .amount
+                    .checked_add(amount)
+                    .ok_or(TokenError::Overflow)?;
+                amount
+            }
+            (Ok(rebase_mint_config), Quantity::Shares(_)) if rebase_mint_config.is_lazy() => {
+                return Err(TokenError::RebaseModeMismatch.into())
+            }
+            (Ok(rebase_mint_config), quantity) => {
+                // round in favor of existing holders, so new shares never
+                // dilute them
+                let (amount, shares) = match quantity {
+                    Quantity::Amount(amount) => (
+                        amount,
+                        rebase_mint_config
+                            .amount_to_shares(amount)
+                            .ok_or(TokenError::Overflow)?,
+                    ),
+                    Quantity::Shares(shares) => (
+                        rebase_mint_config
+                            .shares_to_amount_rounding_up(shares)
+                            .ok_or(TokenError::Overflow)?,
+                        shares,
+                    ),
+                };
+                if rebase_mint::is_exempt(&destination_account) {
+                    credit_exempt(rebase_mint_config, &mut destination_account, amount)?;
+                } else {
+                    rebase_mint_config.issue_shares(shares, amount)?;
+                    credit_shares(rebase_mint_config, &mut destination_account, shares)?;
+                }
+                amount
+            }
+            (Err(_), Quantity::Amount(amount)) => {
+                destination_account.base.amount = destination_account
+                    .base
+                    .amount
+                    .checked_add(amount)
+                    .ok_or(TokenError::Overflow)?;
+                amount
+            }
+            (Err(err), Quantity::Shares(_)) => return Err(err),
+        };
 
         mint.base.supply = mint
             .base
@@ -978,6 +1347,30 @@ impl Processor {
         accounts: &[AccountInfo],
         amount: u64,
         expected_decimals: Option<u8>,
+    ) -> ProgramResult {
+        Self::_process_burn(
+            program_id,
+            accounts,
+            Quantity::Amount(amount),
+            expected_decimals,
+        )
+    }
+
+    /// Processes a burn of an exact number of shares of a rebasing mint.
+    /// The accounts are the same as for `Burn`.
+    pub fn process_burn_shares(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        shares: u64,
+    ) -> ProgramResult {
+        Self::_process_burn(program_id, accounts, Quantity::Shares(shares), None)
+    }
+
+    fn _process_burn(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        quantity: Quantity,
+        expected_decimals: Option<u8>,
     ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
 
@@ -992,13 +1385,52 @@ impl Processor {
         let mut mint_data = mint_info.data.borrow_mut();
         let mut mint = StateWithExtensionsMut::<Mint>::unpack(&mut mint_data)?;
 
+        let (amount, maybe_rebase_shares, is_lazy) =
+            match (mint.get_extension::<RebaseMintConfig>(), quantity) {
+                // a lazy account burns from its amount once caught up
+                (Ok(rebase_mint_config), Quantity::Amount(amount))
+                    if rebase_mint_config.is_lazy() =>
+                {
+                    catch_up_lazy_account(rebase_mint_config, &mut source_account)?;
+                    (amount, None, true)
+                }
+                (Ok(rebase_mint_config), Quantity::Shares(_)) if rebase_mint_config.is_lazy() => {
+                    return Err(TokenError::RebaseModeMismatch.into())
+                }
+                // round up, so the burner can't keep shares worth part of the amount
+                (Ok(rebase_mint_config), Quantity::Amount(amount)) => (
+                    amount,
+                    Some(
+                        rebase_mint_config
+                            .amount_to_shares_rounding_up(amount)
+                            .ok_or(TokenError::Overflow)?,
+                    ),
+                    false,
+                ),
+                // round down, so burning shares never releases more than they back
+                (Ok(rebase_mint_config), Quantity::Shares(shares)) => (
+                    rebase_mint_config
+                        .shares_to_amount(shares)
+                        .ok_or(TokenError::Overflow)?,
+                    Some(shares),
+                    false,
+                ),
+                (Err(_), Quantity::Amount(amount)) => (amount, None, false),
+                (Err(err), Quantity::Shares(_)) => return Err(err),
+            };
+
         if source_account.base.is_frozen() {
             return Err(TokenError::AccountFrozen.into());
         }
         if source_account.base.is_native() {
             return Err(TokenError::NativeNotSupported.into());
         }
-        if source_account.base.amount < amount {
+        // the amount of a rebasing account is stale, so its shares are checked
+        // when they're burned
+        let is_rebasing_account = source_account
+            .get_extension::<RebaseAccount>()
+            .map_or(false, |rebase_account| !rebase_account.is_exempt());
+        if !is_rebasing_account && source_account.base.amount < amount {
             return Err(TokenError::InsufficientFunds.into());
         }
         if mint_info.key != &source_account.base.mint {
@@ -1035,13 +1467,15 @@ impl Processor {
                         account_info_iter.as_slice(),
                     )?;
 
-                    if source_account.base.delegated_amount < amount {
+                    // a rebasing account's allowance is in shares
+                    let delegated_quantity = maybe_rebase_shares.unwrap_or(amount);
+                    if source_account.base.delegated_amount < delegated_quantity {
                         return Err(TokenError::InsufficientFunds.into());
                     }
                     source_account.base.delegated_amount = source_account
                         .base
                         .delegated_amount
-                        .checked_sub(amount)
+                        .checked_sub(delegated_quantity)
                         .ok_or(TokenError::Overflow)?;
                     if source_account.base.delegated_amount == 0 {
                         source_account.base.delegate = COption::None;
@@ -1071,16 +1505,40 @@ impl Processor {
         check_program_account(source_account_info.owner)?;
         check_program_account(mint_info.owner)?;
 
-        source_account.base.amount = source_account
-            .base
-            .amount
-            .checked_sub(amount)
-            .ok_or(TokenError::Overflow)?;
-        mint.base.supply = mint
-            .base
-            .supply
-            .checked_sub(amount)
-            .ok_or(TokenError::Overflow)?;
+        if let Some(shares) = maybe_rebase_shares {
+            let rebase_mint_config = mint.get_extension_mut::<RebaseMintConfig>()?;
+            if rebase_mint::is_exempt(&source_account) {
+                debit_exempt(rebase_mint_config, &mut source_account, amount)?;
+            } else {
+                debit_shares(rebase_mint_config, &mut source_account, shares)?;
+                rebase_mint_config.retire_shares(shares, amount)?;
+            }
+        } else {
+            source_account.base.amount = source_account
+                .base
+                .amount
+                .checked_sub(amount)
+                .ok_or(TokenError::Overflow)?;
+            if is_lazy {
+                mint.get_extension_mut::<RebaseMintConfig>()?
+                    .remove_lazy_supply(amount)?;
+            }
+        }
+        mint.base.supply = match mint.get_extension::<RebaseMintConfig>() {
+            // an unsynced base supply counts tokens at their value when they
+            // were minted, so holders can burn more than it after a rebase up.
+            // It still accounts for the exempt supply, which never rebases.
+            Ok(rebase_mint_config) if !bool::from(rebase_mint_config.sync_base_supply) => mint
+                .base
+                .supply
+                .saturating_sub(amount)
+                .max(rebase_mint_config.exempt_supply.into()),
+            _ => mint
+                .base
+                .supply
+                .checked_sub(amount)
+                .ok_or(TokenError::Overflow)?,
+        };
 
         source_account.pack_base();
         mint.pack_base();
@@ -1150,6 +1608,10 @@ impl Processor {
             if let Ok(transfer_fee_state) = source_account.get_extension::<TransferFeeAmount>() {
                 transfer_fee_state.closable()?
             }
+
+            if let Ok(rebase_account) = source_account.get_extension::<RebaseAccount>() {
+                rebase_account.closable()?
+            }
         } else if let Ok(mint) = StateWithExtensions::<Mint>::unpack(&source_account_data) {
             let extension = mint.get_extension::<MintCloseAuthority>()?;
             let maybe_authority: Option<Pubkey> = extension.close_authority.into();
@@ -1165,6 +1627,9 @@ impl Processor {
             if mint.base.supply != 0 {
                 return Err(TokenError::MintHasSupply.into());
             }
+            if let Ok(rebase_mint_config) = mint.get_extension::<RebaseMintConfig>() {
+                rebase_mint_config.closable()?
+            }
         } else {
             return Err(ProgramError::UninitializedAccount);
         }
@@ -1324,13 +1789,23 @@ impl Processor {
         let mint_data = mint_info.data.borrow();
         let mint = StateWithExtensions::<Mint>::unpack(&mint_data)
             .map_err(|_| Into::<ProgramError>::into(TokenError::InvalidMint))?;
-        let ui_amount = if let Ok(extension) = mint.get_extension::<InterestBearingConfig>() {
-            let unix_timestamp = Clock::get()?.unix_timestamp;
-            extension
-                .amount_to_ui_amount(amount, mint.base.decimals, unix_timestamp)
-                .ok_or(ProgramError::InvalidArgument)?
-        } else {
-            crate::amount_to_ui_amount_string_trimmed(amount, mint.base.decimals)
+        let ui_amount = match (
+            mint.get_extension::<RebaseMintConfig>(),
+            mint.get_extension::<InterestBearingConfig>(),
+        ) {
+            (Ok(_), Ok(_)) => return Err(TokenError::InvalidExtensionCombination.into()),
+            (Ok(extension), Err(_)) => extension
+                .shares_to_ui_amount(amount, mint.base.decimals)
+                .ok_or(ProgramError::InvalidArgument)?,
+            (Err(_), Ok(extension)) => {
+                let unix_timestamp = Clock::get()?.unix_timestamp;
+                extension
+                    .amount_to_ui_amount(amount, mint.base.decimals, unix_timestamp)
+                    .ok_or(ProgramError::InvalidArgument)?
+            }
+            (Err(_), Err(_)) => {
+                crate::amount_to_ui_amount_string_trimmed(amount, mint.base.decimals)
+            }
         };
 
         set_return_data(&ui_amount.into_bytes());
@@ -1346,11 +1821,25 @@ impl Processor {
         let mint_data = mint_info.data.borrow();
         let mint = StateWithExtensions::<Mint>::unpack(&mint_data)
             .map_err(|_| Into::<ProgramError>::into(TokenError::InvalidMint))?;
-        let amount = if let Ok(extension) = mint.get_extension::<InterestBearingConfig>() {
-            let unix_timestamp = Clock::get()?.unix_timestamp;
-            extension.try_ui_amount_into_amount(ui_amount, mint.base.decimals, unix_timestamp)?
-        } else {
-            crate::try_ui_amount_into_amount(ui_amount.to_string(), mint.base.decimals)?
+        let amount = match (
+            mint.get_extension::<RebaseMintConfig>(),
+            mint.get_extension::<InterestBearingConfig>(),
+        ) {
+            (Ok(_), Ok(_)) => return Err(TokenError::InvalidExtensionCombination.into()),
+            (Ok(extension), Err(_)) => {
+                extension.try_ui_amount_into_shares(ui_amount, mint.base.decimals)?
+            }
+            (Err(_), Ok(extension)) => {
+                let unix_timestamp = Clock::get()?.unix_timestamp;
+                extension.try_ui_amount_into_amount(
+                    ui_amount,
+                    mint.base.decimals,
+                    unix_timestamp,
+                )?
+            }
+            (Err(_), Err(_)) => {
+                crate::try_ui_amount_into_amount(ui_amount.to_string(), mint.base.decimals)?
+            }
         };
 
         set_return_data(&amount.to_le_bytes());
@@ -1503,7 +1992,22 @@ impl Processor {
     }
 
     /// Processes an [Instruction](enum.Instruction.html).
+    ///
+    /// With the `audit-assertions` feature, the invariants of the rebasing
+    /// mints among `accounts` are asserted after every successful
+    /// instruction.
     pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+        Self::process_instruction(program_id, accounts, input)?;
+        #[cfg(feature = "audit-assertions")]
+        crate::extension::rebase_mint::audit::assert_invariants(program_id, accounts);
+        Ok(())
+    }
+
+    fn process_instruction(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        input: &[u8],
+    ) -> ProgramResult {
         if let Ok(instruction) = TokenInstruction::unpack(input) {
             match instruction {
                 TokenInstruction::InitializeMint {
@@ -1709,6 +2213,9 @@ impl Processor {
                         &input[1..],
                     )
                 }
+                TokenInstruction::RebaseMintExtension => {
+                    rebase_mint::processor::process_instruction(program_id, accounts, &input[1..])
+                }
             }
         } else if let Ok(instruction) = TokenMetadataInstruction::unpack(input) {
             token_metadata::processor::process_instruction(program_id, accounts, instruction)
@@ -1720,6 +2227,9 @@ impl Processor {
     }
 
     /// Validates owner(s) are present. Used for Mints and Accounts only.
+    ///
+    /// A single owner only needs to be a signer, so a program-derived address
+    /// signed for through `invoke_signed` is accepted like any other key.
     pub fn validate_owner(
         program_id: &Pubkey,
         expected_owner: &Pubkey,
@@ -1772,10 +2282,7 @@ impl Processor {
         state: &StateWithExtensions<Mint>,
     ) -> Result<Vec<ExtensionType>, ProgramError> {
         check_program_account(token_program_id)?;
-        let mint_extensions: Vec<ExtensionType> = state.get_extension_types()?;
-        Ok(ExtensionType::get_required_init_account_extensions(
-            &mint_extensions,
-        ))
+        rebase_mint::get_required_init_account_extensions(state)
     }
 }
 
@@ -4646,6 +5153,371 @@ mod tests {
         );
     }
 
+    #[test]
+    #[cfg(feature = "rebase-mint")]
+    fn test_set_authority_rebase_supply() {
+        let program_id = crate::id();
+        let owner_key = Pubkey::new_unique();
+        let mut owner_account = SolanaAccount::default();
+        let owner2_key = Pubkey::new_unique();
+        let mut owner2_account = SolanaAccount::default();
+        let mut rent_sysvar = rent_sysvar();
+
+        let mint_key = Pubkey::new_unique();
+        let mint_len =
+            ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::RebaseMintConfig])
+                .unwrap();
+        let mut mint_account = SolanaAccount::new(
+            Rent::default().minimum_balance(mint_len),
+            mint_len,
+            &program_id,
+        );
+        do_process_instruction(
+            rebase_mint::instruction::InitializeRebaseMintBuilder::new(&program_id)
+                .mint(&mint_key)
+                .supply_authority(&owner_key)
+                .initial_supply(1_000, 0)
+                .allow_supply_decrease()
+                .build()
+                .unwrap(),
+            vec![&mut mint_account],
+        )
+        .unwrap();
+        do_process_instruction(
+            initialize_mint(&program_id, &mint_key, &owner_key, None, 2).unwrap(),
+            vec![&mut mint_account, &mut rent_sysvar],
+        )
+        .unwrap();
+
+        // wrong signer
+        assert_eq!(
+            Err(TokenError::OwnerMismatch.into()),
+            do_process_instruction(
+                set_authority(
+                    &program_id,
+                    &mint_key,
+                    Some(&owner_key),
+                    AuthorityType::RebaseSupply,
+                    &owner2_key,
+                    &[],
+                )
+                .unwrap(),
+                vec![&mut mint_account, &mut owner2_account],
+            )
+        );
+
+        // set
+        do_process_instruction(
+            set_authority(
+                &program_id,
+                &mint_key,
+                Some(&owner2_key),
+                AuthorityType::RebaseSupply,
+                &owner_key,
+                &[],
+            )
+            .unwrap(),
+            vec![&mut mint_account, &mut owner_account],
+        )
+        .unwrap();
+        let mint = StateWithExtensions::<Mint>::unpack(&mint_account.data).unwrap();
+        let extension = mint.get_extension::<RebaseMintConfig>().unwrap();
+        assert_eq!(
+            extension.supply_authority,
+            Some(owner2_key).try_into().unwrap()
+        );
+
+        // clear
+        do_process_instruction(
+            set_authority(
+                &program_id,
+                &mint_key,
+                None,
+                AuthorityType::RebaseSupply,
+                &owner2_key,
+                &[],
+            )
+            .unwrap(),
+            vec![&mut mint_account, &mut owner2_account],
+        )
+        .unwrap();
+        let mint = StateWithExtensions::<Mint>::unpack(&mint_account.data).unwrap();
+        let extension = mint.get_extension::<RebaseMintConfig>().unwrap();
+        assert_eq!(extension.supply_authority, None.try_into().unwrap());
+
+        // no authority left to change
+        assert_eq!(
+            Err(TokenError::AuthorityTypeNotSupported.into()),
+            do_process_instruction(
+                set_authority(
+                    &program_id,
+                    &mint_key,
+                    Some(&owner_key),
+                    AuthorityType::RebaseSupply,
+                    &owner2_key,
+                    &[],
+                )
+                .unwrap(),
+                vec![&mut mint_account, &mut owner2_account],
+            )
+        );
+
+        // mint without the extension
+        let mint2_key = Pubkey::new_unique();
+        let mut mint2_account =
+            SolanaAccount::new(mint_minimum_balance(), Mint::get_packed_len(), &program_id);
+        do_process_instruction(
+            initialize_mint(&program_id, &mint2_key, &owner_key, None, 2).unwrap(),
+            vec![&mut mint2_account, &mut rent_sysvar],
+        )
+        .unwrap();
+        assert_eq!(
+            Err(ProgramError::InvalidAccountData),
+            do_process_instruction(
+                set_authority(
+                    &program_id,
+                    &mint2_key,
+                    Some(&owner2_key),
+                    AuthorityType::RebaseSupply,
+                    &owner_key,
+                    &[],
+                )
+                .unwrap(),
+                vec![&mut mint2_account, &mut owner_account],
+            )
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "rebase-mint")]
+    fn test_rebase_supply_immutable() {
+        let program_id = crate::id();
+        let owner_key = Pubkey::new_unique();
+        let mut owner_account = SolanaAccount::default();
+        let mut rent_sysvar = rent_sysvar();
+
+        let mint_key = Pubkey::new_unique();
+        let mint_len =
+            ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::RebaseMintConfig])
+                .unwrap();
+        let mut mint_account = SolanaAccount::new(
+            Rent::default().minimum_balance(mint_len),
+            mint_len,
+            &program_id,
+        );
+        do_process_instruction(
+            rebase_mint::instruction::InitializeRebaseMintBuilder::new(&program_id)
+                .mint(&mint_key)
+                .initial_supply(1_000, 0)
+                .allow_supply_decrease()
+                .build()
+                .unwrap(),
+            vec![&mut mint_account],
+        )
+        .unwrap();
+        do_process_instruction(
+            initialize_mint(&program_id, &mint_key, &owner_key, None, 2).unwrap(),
+            vec![&mut mint_account, &mut rent_sysvar],
+        )
+        .unwrap();
+
+        // even the mint authority can't rebase
+        assert_eq!(
+            Err(TokenError::RebaseSupplyImmutable.into()),
+            do_process_instruction(
+                rebase_mint::instruction::update_supply(
+                    &program_id,
+                    &mint_key,
+                    None,
+                    &owner_key,
+                    &[],
+                    2_000,
+                )
+                .unwrap(),
+                vec![&mut mint_account, &mut owner_account],
+            )
+        );
+        let mint = StateWithExtensions::<Mint>::unpack(&mint_account.data).unwrap();
+        let extension = mint.get_extension::<RebaseMintConfig>().unwrap();
+        assert_eq!(u64::from(extension.total_supply), 1_000);
+        assert_eq!(u64::from(extension.total_shares), 1_000);
+    }
+
+    #[test]
+    #[cfg(feature = "rebase-mint")]
+    fn test_rebase_supply_long_run() {
+        let program_id = crate::id();
+        let owner_key = Pubkey::new_unique();
+        let mut owner_account = SolanaAccount::default();
+        let mut rent_sysvar = rent_sysvar();
+
+        let mint_key = Pubkey::new_unique();
+        let mint_len =
+            ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::RebaseMintConfig])
+                .unwrap();
+        let mut mint_account = SolanaAccount::new(
+            Rent::default().minimum_balance(mint_len),
+            mint_len,
+            &program_id,
+        );
+        let account_len =
+            ExtensionType::try_calculate_account_len::<Account>(&[ExtensionType::RebaseAccount])
+                .unwrap();
+        let account_key = Pubkey::new_unique();
+        let mut account_account = SolanaAccount::new(
+            Rent::default().minimum_balance(account_len),
+            account_len,
+            &program_id,
+        );
+        let account2_key = Pubkey::new_unique();
+        let mut account2_account = SolanaAccount::new(
+            Rent::default().minimum_balance(account_len),
+            account_len,
+            &program_id,
+        );
+
+        do_process_instruction(
+            rebase_mint::instruction::InitializeRebaseMintBuilder::new(&program_id)
+                .mint(&mint_key)
+                .supply_authority(&owner_key)
+                .allow_supply_decrease()
+                .build()
+                .unwrap(),
+            vec![&mut mint_account],
+        )
+        .unwrap();
+        do_process_instruction(
+            initialize_mint(&program_id, &mint_key, &owner_key, None, 2).unwrap(),
+            vec![&mut mint_account, &mut rent_sysvar],
+        )
+        .unwrap();
+        for (key, account, amount) in [
+            (&account_key, &mut account_account, 600_000),
+            (&account2_key, &mut account2_account, 400_000),
+        ] {
+            do_process_instruction(
+                initialize_account(&program_id, key, &mint_key, &owner_key).unwrap(),
+                vec![
+                    &mut *account,
+                    &mut mint_account,
+                    &mut owner_account,
+                    &mut rent_sysvar,
+                ],
+            )
+            .unwrap();
+            do_process_instruction(
+                mint_to(&program_id, &mint_key, key, &owner_key, &[], amount).unwrap(),
+                vec![&mut mint_account, account, &mut owner_account],
+            )
+            .unwrap();
+        }
+
+        // ten years of daily rebases, each day up then partly back down,
+        // netting 10 tokens a day
+        for step in 0..10_000u64 {
+            let day = step / 2 + 1;
+            let new_supply = if step % 2 == 0 {
+                1_000_000 + day * 10 + 7_000
+            } else {
+                1_000_000 + day * 10
+            };
+            do_process_instruction(
+                rebase_mint::instruction::update_supply(
+                    &program_id,
+                    &mint_key,
+                    None,
+                    &owner_key,
+                    &[],
+                    new_supply,
+                )
+                .unwrap(),
+                vec![&mut mint_account, &mut owner_account],
+            )
+            .unwrap();
+
+            let mint = StateWithExtensions::<Mint>::unpack(&mint_account.data).unwrap();
+            let extension = mint.get_extension::<RebaseMintConfig>().unwrap();
+            extension.validate().unwrap();
+            assert_eq!(u64::from(extension.total_supply), new_supply);
+            assert_eq!(u64::from(extension.total_shares), 1_000_000);
+            assert_eq!(u64::from(extension.outstanding_shares), 1_000_000);
+            assert_eq!(
+                extension.shares_to_amount(600_000),
+                Some(600_000 * new_supply / 1_000_000)
+            );
+        }
+
+        // 50,000 tokens were added over 5,000 days, 60% of which belong to the
+        // reference holder
+        let mint = StateWithExtensions::<Mint>::unpack(&mint_account.data).unwrap();
+        let extension = mint.get_extension::<RebaseMintConfig>().unwrap();
+        let account = StateWithExtensions::<Account>::unpack(&account_account.data).unwrap();
+        let shares = u64::from(account.get_extension::<RebaseAccount>().unwrap().shares);
+        assert_eq!(shares, 600_000);
+        assert_eq!(extension.shares_to_amount(shares), Some(630_000));
+    }
+
+    #[test]
+    #[serial]
+    #[cfg(not(feature = "rebase-mint"))]
+    fn test_rebase_mint_not_active() {
+        let program_id = crate::id();
+        let owner_key = Pubkey::new_unique();
+        let mut owner_account = SolanaAccount::default();
+
+        // a mint created while the instructions were active, at 1.5 tokens
+        // per share
+        let mint_key = Pubkey::new_unique();
+        let mut config = RebaseMintConfig {
+            supply_authority: Some(owner_key).try_into().unwrap(),
+            total_supply: 1_500.into(),
+            total_shares: 1_000.into(),
+            outstanding_shares: 1_000.into(),
+            has_rebased: true.into(),
+            ..Default::default()
+        };
+        config.refresh_cached_ratio();
+        let data = rebase_mint::test_utils::RebaseMintFixture::new(config)
+            .supply(1_000)
+            .decimals(2)
+            .mint_authority(&owner_key)
+            .build()
+            .unwrap();
+        let mut mint_account = SolanaAccount::new(
+            Rent::default().minimum_balance(data.len()),
+            data.len(),
+            &program_id,
+        );
+        mint_account.data = data;
+
+        assert_eq!(
+            Err(TokenError::RebaseMintNotActive.into()),
+            do_process_instruction(
+                rebase_mint::instruction::update_supply(
+                    &program_id,
+                    &mint_key,
+                    None,
+                    &owner_key,
+                    &[],
+                    2_000,
+                )
+                .unwrap(),
+                vec![&mut mint_account, &mut owner_account],
+            )
+        );
+        let mint = StateWithExtensions::<Mint>::unpack(&mint_account.data).unwrap();
+        let extension = mint.get_extension::<RebaseMintConfig>().unwrap();
+        assert_eq!(u64::from(extension.total_supply), 1_500);
+
+        // the base instructions still convert through the shares
+        set_expected_data("9".as_bytes().to_vec());
+        do_process_instruction(
+            amount_to_ui_amount(&program_id, &mint_key, 600).unwrap(),
+            vec![&mut mint_account],
+        )
+        .unwrap();
+    }
+
     #[test]
     fn test_mint_to_dups() {
         let program_id = crate::id();
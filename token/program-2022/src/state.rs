@@ -107,7 +107,8 @@ pub struct Account {
     /// used by the Processor to ensure that wrapped SOL accounts do not
     /// drop below this threshold.
     pub is_native: COption<u64>,
-    /// The amount delegated
+    /// The amount delegated, or the number of shares delegated for an account
+    /// of a rebasing mint
     pub delegated_amount: u64,
     /// Optional authority to close the account.
     pub close_authority: COption<Pubkey>,
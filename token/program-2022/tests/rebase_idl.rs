@@ -0,0 +1,747 @@
+//! Keeps `idl/rebase_mint.json` in sync with the rebasing mint instructions.
+//!
+//! The account flags come from the instruction builders themselves, and the
+//! argument layouts are checked against the length of the data they encode,
+//! so the IDL can't drift from the program without failing this test.
+//!
+//! Run with `REGENERATE_IDL=1` to rewrite the IDL after changing an
+//! instruction.
+
+use {
+    serde_json::{json, Value},
+    solana_program::{instruction::Instruction, pubkey::Pubkey},
+    spl_token_2022::{
+        extension::rebase_mint::instruction::{self, RebaseMintInstruction, RebaseSupplyBuilder},
+        id,
+        instruction::TokenInstruction,
+    },
+    std::{convert::TryFrom, env, fs},
+};
+
+const IDL_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/idl/rebase_mint.json");
+
+/// Placeholder keys to build every instruction with
+struct Keys {
+    mint: Pubkey,
+    account: Pubkey,
+    destination: Pubkey,
+    delegate: Pubkey,
+    reserve_account: Pubkey,
+    fee_destination: Pubkey,
+    payer: Pubkey,
+    co_signer: Pubkey,
+    authority: Pubkey,
+}
+
+type Build = fn(&Keys, &[&Pubkey]) -> Instruction;
+
+/// How an instruction is described, apart from what its builder says
+struct Spec {
+    name: &'static str,
+    build: Build,
+    /// Names of the accounts, in order, as built without any multisig signers
+    accounts: &'static [&'static str],
+    /// Accounts only passed if configured on the mint
+    optional: &'static [&'static str],
+    /// Names and types of the fields of the instruction data, in order
+    args: &'static [(&'static str, &'static str)],
+}
+
+const TRANSFER_ACCOUNTS: &[&str] = &["source", "mint", "destination", "authority"];
+const SUPPLY_AUTHORITY_ACCOUNTS: &[&str] = &["mint", "supplyAuthority"];
+
+fn specs() -> Vec<Spec> {
+    vec![
+        Spec {
+            name: "Initialize",
+            build: |keys, _| {
+                instruction::InitializeRebaseMintBuilder::new(&id())
+                    .mint(&keys.mint)
+                    .build()
+                    .unwrap()
+            },
+            accounts: &["mint"],
+            optional: &[],
+            args: &[
+                ("supplyAuthority", "OptionalNonZeroPubkey"),
+                ("initialSupply", "u64"),
+                ("firstRebaseAfterTimestamp", "i64"),
+                ("twoStepAuthorityTransfer", "bool"),
+                ("authorityChangeDelaySeconds", "u32"),
+                ("useMintAuthorityFallback", "bool"),
+                ("requireRebaseMemo", "bool"),
+                ("virtualSharesOffset", "u64"),
+                ("allowSupplyDecrease", "bool"),
+                ("rebaseFeeBps", "u16"),
+                ("feeDestination", "OptionalNonZeroPubkey"),
+                ("initialShares", "u64"),
+                ("maxTotalShares", "u64"),
+                ("allowSunset", "bool"),
+                ("updateMetadataRate", "bool"),
+                ("coSigner", "OptionalNonZeroPubkey"),
+                ("rebaseMode", "RebaseMode"),
+                ("syncBaseSupply", "bool"),
+            ],
+        },
+        Spec {
+            name: "RebaseSupply",
+            build: |keys, signers| {
+                RebaseSupplyBuilder::new(&id(), 1)
+                    .mint(&keys.mint)
+                    .reserve_account(&keys.reserve_account)
+                    .fee_destination(&keys.fee_destination)
+                    .metadata_payer(&keys.payer)
+                    .co_signer(&keys.co_signer)
+                    .authority(&keys.authority)
+                    .multisig_signers(signers)
+                    .build()
+                    .unwrap()
+            },
+            accounts: &[
+                "mint",
+                "reserveAccount",
+                "feeDestination",
+                "payer",
+                "systemProgram",
+                "coSigner",
+                "authority",
+            ],
+            optional: &[
+                "reserveAccount",
+                "feeDestination",
+                "payer",
+                "systemProgram",
+                "coSigner",
+            ],
+            args: &[
+                ("newSupply", "u64"),
+                ("reasonCode", "u8"),
+                ("reference", "[u8; 32]"),
+                ("notAfterUnixTimestamp", "i64"),
+            ],
+        },
+        Spec {
+            name: "SetReserveAccount",
+            build: |keys, signers| {
+                instruction::set_reserve_account(&id(), &keys.mint, &keys.authority, signers, None)
+                    .unwrap()
+            },
+            accounts: SUPPLY_AUTHORITY_ACCOUNTS,
+            optional: &[],
+            args: &[("reserveAccount", "OptionalNonZeroPubkey")],
+        },
+        Spec {
+            name: "PreviewRebaseSupply",
+            build: |keys, _| {
+                instruction::preview_rebase_supply(
+                    &id(),
+                    &keys.mint,
+                    Some(&keys.reserve_account),
+                    1,
+                )
+                .unwrap()
+            },
+            accounts: &["mint", "reserveAccount"],
+            optional: &["reserveAccount"],
+            args: &[
+                ("newSupply", "u64"),
+                ("reasonCode", "u8"),
+                ("reference", "[u8; 32]"),
+                ("notAfterUnixTimestamp", "i64"),
+            ],
+        },
+        Spec {
+            name: "SetSupplyAuthority",
+            build: |keys, signers| {
+                instruction::set_supply_authority(&id(), &keys.mint, &keys.authority, signers, None)
+                    .unwrap()
+            },
+            accounts: SUPPLY_AUTHORITY_ACCOUNTS,
+            optional: &[],
+            args: &[("newAuthority", "OptionalNonZeroPubkey")],
+        },
+        Spec {
+            name: "ProposeSupplyAuthority",
+            build: |keys, signers| {
+                instruction::propose_supply_authority(
+                    &id(),
+                    &keys.mint,
+                    &keys.authority,
+                    signers,
+                    &keys.delegate,
+                )
+                .unwrap()
+            },
+            accounts: SUPPLY_AUTHORITY_ACCOUNTS,
+            optional: &[],
+            args: &[("pendingAuthority", "OptionalNonZeroPubkey")],
+        },
+        Spec {
+            name: "AcceptSupplyAuthority",
+            build: |keys, signers| {
+                instruction::accept_supply_authority(&id(), &keys.mint, &keys.authority, signers)
+                    .unwrap()
+            },
+            accounts: &["mint", "pendingAuthority"],
+            optional: &[],
+            args: &[],
+        },
+        Spec {
+            name: "CancelPendingAuthority",
+            build: |keys, signers| {
+                instruction::cancel_pending_authority(&id(), &keys.mint, &keys.authority, signers)
+                    .unwrap()
+            },
+            accounts: SUPPLY_AUTHORITY_ACCOUNTS,
+            optional: &[],
+            args: &[],
+        },
+        Spec {
+            name: "FinalizeAuthorityChange",
+            build: |keys, _| instruction::finalize_authority_change(&id(), &keys.mint).unwrap(),
+            accounts: &["mint"],
+            optional: &[],
+            args: &[],
+        },
+        Spec {
+            name: "CancelAuthorityChange",
+            build: |keys, signers| {
+                instruction::cancel_authority_change(&id(), &keys.mint, &keys.authority, signers)
+                    .unwrap()
+            },
+            accounts: SUPPLY_AUTHORITY_ACCOUNTS,
+            optional: &[],
+            args: &[],
+        },
+        Spec {
+            name: "SetRebaseDelegate",
+            build: |keys, signers| {
+                instruction::set_rebase_delegate(&id(), &keys.mint, &keys.authority, signers, None)
+                    .unwrap()
+            },
+            accounts: SUPPLY_AUTHORITY_ACCOUNTS,
+            optional: &[],
+            args: &[("rebaseDelegate", "OptionalNonZeroPubkey")],
+        },
+        Spec {
+            name: "TransferShares",
+            build: |keys, signers| {
+                instruction::transfer_shares(
+                    &id(),
+                    &keys.account,
+                    &keys.mint,
+                    &keys.destination,
+                    &keys.authority,
+                    signers,
+                    1,
+                )
+                .unwrap()
+            },
+            accounts: TRANSFER_ACCOUNTS,
+            optional: &[],
+            args: &[("shares", "u64")],
+        },
+        Spec {
+            name: "TransferSharesChecked",
+            build: |keys, signers| {
+                instruction::transfer_shares_checked(
+                    &id(),
+                    &keys.account,
+                    &keys.mint,
+                    &keys.destination,
+                    &keys.authority,
+                    signers,
+                    1,
+                    0,
+                )
+                .unwrap()
+            },
+            accounts: TRANSFER_ACCOUNTS,
+            optional: &[],
+            args: &[("shares", "u64"), ("decimals", "u8")],
+        },
+        Spec {
+            name: "MintToShares",
+            build: |keys, signers| {
+                instruction::mint_to_shares(
+                    &id(),
+                    &keys.mint,
+                    &keys.account,
+                    &keys.authority,
+                    signers,
+                    1,
+                )
+                .unwrap()
+            },
+            accounts: &["mint", "account", "mintAuthority"],
+            optional: &[],
+            args: &[("shares", "u64")],
+        },
+        Spec {
+            name: "BurnShares",
+            build: |keys, signers| {
+                instruction::burn_shares(
+                    &id(),
+                    &keys.account,
+                    &keys.mint,
+                    &keys.authority,
+                    signers,
+                    1,
+                )
+                .unwrap()
+            },
+            accounts: &["account", "mint", "authority"],
+            optional: &[],
+            args: &[("shares", "u64")],
+        },
+        Spec {
+            name: "SyncSupplyFromMint",
+            build: |keys, _| instruction::sync_supply_from_mint(&id(), &keys.mint).unwrap(),
+            accounts: &["mint"],
+            optional: &[],
+            args: &[],
+        },
+        Spec {
+            name: "GetRebasedBalance",
+            build: |keys, _| {
+                instruction::get_rebased_balance(&id(), &keys.account, &keys.mint).unwrap()
+            },
+            accounts: &["account", "mint"],
+            optional: &[],
+            args: &[],
+        },
+        Spec {
+            name: "ConvertAmountToShares",
+            build: |keys, _| instruction::convert_amount_to_shares(&id(), &keys.mint, 1).unwrap(),
+            accounts: &["mint"],
+            optional: &[],
+            args: &[("amount", "u64")],
+        },
+        Spec {
+            name: "ConvertSharesToAmount",
+            build: |keys, _| instruction::convert_shares_to_amount(&id(), &keys.mint, 1).unwrap(),
+            accounts: &["mint"],
+            optional: &[],
+            args: &[("shares", "u64")],
+        },
+        Spec {
+            name: "ApproveShares",
+            build: |keys, signers| {
+                instruction::approve_shares(
+                    &id(),
+                    &keys.account,
+                    &keys.mint,
+                    &keys.delegate,
+                    &keys.authority,
+                    signers,
+                    1,
+                )
+                .unwrap()
+            },
+            accounts: &["source", "mint", "delegate", "owner"],
+            optional: &[],
+            args: &[("shares", "u64")],
+        },
+        Spec {
+            name: "SetAccountExempt",
+            build: |keys, signers| {
+                instruction::set_account_exempt(
+                    &id(),
+                    &keys.account,
+                    &keys.mint,
+                    &keys.authority,
+                    signers,
+                    true,
+                )
+                .unwrap()
+            },
+            accounts: &["account", "mint", "supplyAuthority"],
+            optional: &[],
+            args: &[("exempt", "bool")],
+        },
+        Spec {
+            name: "InitializeWrapper",
+            build: |keys, _| {
+                instruction::initialize_wrapper(&id(), &keys.payer, &keys.mint).unwrap()
+            },
+            accounts: &["payer", "mint", "wrapperMint", "escrow", "systemProgram"],
+            optional: &[],
+            args: &[],
+        },
+        Spec {
+            name: "WrapShares",
+            build: |keys, signers| {
+                instruction::wrap_shares(
+                    &id(),
+                    &keys.account,
+                    &keys.mint,
+                    &keys.destination,
+                    &keys.authority,
+                    signers,
+                    1,
+                )
+                .unwrap()
+            },
+            accounts: &[
+                "source",
+                "mint",
+                "escrow",
+                "wrapperMint",
+                "destination",
+                "wrapperAuthority",
+                "tokenProgram",
+                "authority",
+            ],
+            optional: &[],
+            args: &[("shares", "u64")],
+        },
+        Spec {
+            name: "UnwrapShares",
+            build: |keys, signers| {
+                instruction::unwrap_shares(
+                    &id(),
+                    &keys.account,
+                    &keys.mint,
+                    &keys.destination,
+                    &keys.authority,
+                    signers,
+                    1,
+                )
+                .unwrap()
+            },
+            accounts: &[
+                "source",
+                "wrapperMint",
+                "escrow",
+                "mint",
+                "destination",
+                "wrapperAuthority",
+                "tokenProgram",
+                "authority",
+            ],
+            optional: &[],
+            args: &[("shares", "u64")],
+        },
+        Spec {
+            name: "AssertRebaseConsistency",
+            build: |keys, _| instruction::assert_rebase_consistency(&id(), &keys.mint).unwrap(),
+            accounts: &["mint"],
+            optional: &[],
+            args: &[],
+        },
+        Spec {
+            name: "TransferCheckedWithShareSlippage",
+            build: |keys, signers| {
+                instruction::transfer_checked_with_share_slippage(
+                    &id(),
+                    &keys.account,
+                    &keys.mint,
+                    &keys.destination,
+                    &keys.authority,
+                    signers,
+                    1,
+                    0,
+                    1,
+                    0,
+                )
+                .unwrap()
+            },
+            accounts: TRANSFER_ACCOUNTS,
+            optional: &[],
+            args: &[
+                ("amount", "u64"),
+                ("decimals", "u8"),
+                ("expectedShares", "u64"),
+                ("maxShareSlippageBps", "u16"),
+            ],
+        },
+        Spec {
+            name: "GetRebaseState",
+            build: |keys, _| instruction::get_rebase_state(&id(), &keys.mint).unwrap(),
+            accounts: &["mint"],
+            optional: &[],
+            args: &[],
+        },
+        Spec {
+            name: "InitializeHistory",
+            build: |keys, _| instruction::initialize_history(&id(), &keys.mint).unwrap(),
+            accounts: &["mint"],
+            optional: &[],
+            args: &[],
+        },
+        Spec {
+            name: "GetRebaseHistory",
+            build: |keys, _| instruction::get_rebase_history(&id(), &keys.mint).unwrap(),
+            accounts: &["mint"],
+            optional: &[],
+            args: &[],
+        },
+        Spec {
+            name: "SetRebaseFee",
+            build: |keys, signers| {
+                instruction::set_rebase_fee(&id(), &keys.mint, &keys.authority, signers, 0, None)
+                    .unwrap()
+            },
+            accounts: SUPPLY_AUTHORITY_ACCOUNTS,
+            optional: &[],
+            args: &[
+                ("rebaseFeeBps", "u16"),
+                ("feeDestination", "OptionalNonZeroPubkey"),
+            ],
+        },
+        Spec {
+            name: "SunsetRebase",
+            build: |keys, signers| {
+                instruction::sunset_rebase_with_co_signer(
+                    &id(),
+                    &keys.mint,
+                    Some(&keys.co_signer),
+                    &keys.authority,
+                    signers,
+                )
+                .unwrap()
+            },
+            accounts: &["mint", "coSigner", "supplyAuthority"],
+            optional: &["coSigner"],
+            args: &[],
+        },
+        Spec {
+            name: "RetireRebase",
+            build: |keys, signers| {
+                instruction::retire_rebase(&id(), &keys.mint, &keys.authority, signers).unwrap()
+            },
+            accounts: SUPPLY_AUTHORITY_ACCOUNTS,
+            optional: &[],
+            args: &[],
+        },
+        Spec {
+            name: "SweepRoundingDust",
+            build: |keys, signers| {
+                instruction::sweep_rounding_dust(
+                    &id(),
+                    &keys.mint,
+                    &keys.destination,
+                    &keys.authority,
+                    signers,
+                )
+                .unwrap()
+            },
+            accounts: &["mint", "destination", "supplyAuthority"],
+            optional: &[],
+            args: &[],
+        },
+        Spec {
+            name: "ClaimRebase",
+            build: |keys, _| instruction::claim_rebase(&id(), &keys.account, &keys.mint).unwrap(),
+            accounts: &["account", "mint"],
+            optional: &[],
+            args: &[],
+        },
+        Spec {
+            name: "RebaseSupplyUi",
+            build: |keys, signers| {
+                RebaseSupplyBuilder::new_ui(&id(), "1")
+                    .mint(&keys.mint)
+                    .reserve_account(&keys.reserve_account)
+                    .fee_destination(&keys.fee_destination)
+                    .metadata_payer(&keys.payer)
+                    .co_signer(&keys.co_signer)
+                    .authority(&keys.authority)
+                    .multisig_signers(signers)
+                    .build()
+                    .unwrap()
+            },
+            accounts: &[
+                "mint",
+                "reserveAccount",
+                "feeDestination",
+                "payer",
+                "systemProgram",
+                "coSigner",
+                "authority",
+            ],
+            optional: &[
+                "reserveAccount",
+                "feeDestination",
+                "payer",
+                "systemProgram",
+                "coSigner",
+            ],
+            args: &[
+                ("uiSupply", "string"),
+                ("reasonCode", "u8"),
+                ("reference", "[u8; 32]"),
+                ("notAfterUnixTimestamp", "i64"),
+            ],
+        },
+    ]
+}
+
+/// Encoded size and IDL type of an argument type, only counting the length
+/// prefix of a string
+fn arg_type(name: &str) -> (usize, Value) {
+    match name {
+        "u8" | "bool" => (1, json!(name)),
+        "string" => (4, json!(name)),
+        "u16" => (2, json!(name)),
+        "u32" => (4, json!(name)),
+        "u64" | "i64" => (8, json!(name)),
+        "[u8; 32]" => (32, json!({ "array": ["u8", 32] })),
+        "OptionalNonZeroPubkey" => (32, json!({ "defined": name })),
+        "RebaseMode" => (1, json!({ "defined": name })),
+        _ => panic!("unknown argument type {}", name),
+    }
+}
+
+fn instruction_idl(spec: &Spec, discriminant: u8, keys: &Keys) -> Value {
+    let mut prefix = TokenInstruction::RebaseMintExtension.pack();
+    prefix.push(discriminant);
+    let instruction = (spec.build)(keys, &[]);
+    assert!(instruction.data.starts_with(&prefix), "{}", spec.name);
+    assert_eq!(
+        instruction.accounts.len(),
+        spec.accounts.len(),
+        "{}",
+        spec.name
+    );
+
+    // an authority that turns into a non-signer once signers are passed can
+    // be a multisig
+    let signer = Pubkey::new_unique();
+    let with_signers = (spec.build)(keys, &[&signer]);
+    let multisig_authority = (with_signers.accounts.len() == instruction.accounts.len() + 1)
+        .then_some(instruction.accounts.len() - 1);
+    if let Some(index) = multisig_authority {
+        assert!(!with_signers.accounts[index].is_signer, "{}", spec.name);
+        assert_eq!(with_signers.accounts[index + 1].pubkey, signer);
+    } else {
+        assert_eq!(with_signers.accounts, instruction.accounts, "{}", spec.name);
+    }
+
+    let accounts = spec
+        .accounts
+        .iter()
+        .zip(instruction.accounts.iter())
+        .enumerate()
+        .map(|(index, (name, meta))| {
+            let mut account = json!({
+                "name": name,
+                "isMut": meta.is_writable,
+                "isSigner": meta.is_signer,
+            });
+            if spec.optional.contains(name) {
+                account["isOptional"] = json!(true);
+                account["docs"] = json!(["Only passed if configured on the mint"]);
+            }
+            if multisig_authority == Some(index) {
+                account["docs"] = json!([
+                    "Signs unless it's a multisig, followed by the signers of the multisig"
+                ]);
+            }
+            account
+        })
+        .collect::<Vec<_>>();
+
+    let mut data_len = prefix.len();
+    let args = spec
+        .args
+        .iter()
+        .map(|(name, ty)| {
+            let (size, idl_type) = arg_type(ty);
+            data_len += size;
+            if *ty == "string" {
+                let len = instruction.data[data_len - size..data_len]
+                    .try_into()
+                    .unwrap();
+                data_len += u32::from_le_bytes(len) as usize;
+            }
+            json!({ "name": name, "type": idl_type })
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(instruction.data.len(), data_len, "{}", spec.name);
+
+    json!({
+        "name": spec.name,
+        "accounts": accounts,
+        "args": args,
+        "discriminant": {
+            "type": { "array": ["u8", prefix.len()] },
+            "value": prefix,
+        },
+    })
+}
+
+fn generate_idl() -> Value {
+    let keys = Keys {
+        mint: Pubkey::new_unique(),
+        account: Pubkey::new_unique(),
+        destination: Pubkey::new_unique(),
+        delegate: Pubkey::new_unique(),
+        reserve_account: Pubkey::new_unique(),
+        fee_destination: Pubkey::new_unique(),
+        payer: Pubkey::new_unique(),
+        co_signer: Pubkey::new_unique(),
+        authority: Pubkey::new_unique(),
+    };
+    let specs = specs();
+
+    // every variant is covered, in order
+    let variants = (0..=u8::MAX)
+        .map_while(|discriminant| RebaseMintInstruction::try_from(discriminant).ok())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        specs.iter().map(|spec| spec.name).collect::<Vec<_>>(),
+        variants
+            .iter()
+            .map(|variant| format!("{:?}", variant))
+            .collect::<Vec<_>>()
+    );
+
+    let instructions = specs
+        .iter()
+        .zip(variants)
+        .map(|(spec, variant)| instruction_idl(spec, variant.into(), &keys))
+        .collect::<Vec<_>>();
+    json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "name": "spl_token_2022_rebase_mint",
+        "instructions": instructions,
+        "types": [
+            {
+                "name": "OptionalNonZeroPubkey",
+                "docs": ["A public key, or all zeroes for none"],
+                "type": {
+                    "kind": "struct",
+                    "fields": [{ "name": "pubkey", "type": "publicKey" }],
+                },
+            },
+            {
+                "name": "RebaseMode",
+                "type": {
+                    "kind": "enum",
+                    "variants": [{ "name": "Shares" }, { "name": "Lazy" }],
+                },
+            },
+        ],
+        "metadata": {
+            "address": id().to_string(),
+        },
+    })
+}
+
+#[test]
+fn rebase_mint_idl_is_up_to_date() {
+    let idl = generate_idl();
+    if env::var_os("REGENERATE_IDL").is_some() {
+        let mut json = serde_json::to_string_pretty(&idl).unwrap();
+        json.push('\n');
+        fs::write(IDL_PATH, json).unwrap();
+        return;
+    }
+    let checked_in: Value = serde_json::from_str(&fs::read_to_string(IDL_PATH).unwrap()).unwrap();
+    assert_eq!(
+        checked_in, idl,
+        "{} is stale, regenerate it with REGENERATE_IDL=1",
+        IDL_PATH
+    );
+}
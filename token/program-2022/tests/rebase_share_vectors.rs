@@ -0,0 +1,178 @@
+//! Replays the share vectors of `fixtures/rebase_share_vectors.json`, whose
+//! format is documented in `fixtures/README.md`, through the rebasing mint's
+//! library functions
+
+use {
+    serde_json::Value,
+    solana_program::{
+        decode_error::DecodeError, entrypoint::ProgramResult, program_error::ProgramError,
+    },
+    spl_token_2022::{error::TokenError, extension::rebase_mint::RebaseMintConfig},
+};
+
+const VECTORS: &str = include_str!("fixtures/rebase_share_vectors.json");
+
+fn quantity(value: &Value) -> u64 {
+    value.as_str().unwrap().parse().unwrap()
+}
+
+fn error_name(error: ProgramError) -> String {
+    match error {
+        ProgramError::Custom(code) => {
+            let error: TokenError = TokenError::decode_custom_error_to_enum(code).unwrap();
+            format!("{:?}", error)
+        }
+        error => panic!("not a token error: {:?}", error),
+    }
+}
+
+#[derive(Clone)]
+struct Replay {
+    config: RebaseMintConfig,
+    mint_supply: u64,
+    shares: Vec<u64>,
+}
+
+impl Replay {
+    fn new(scenario: &Value) -> Self {
+        let initial_supply = quantity(&scenario["initialSupply"]);
+        let mut config = RebaseMintConfig {
+            total_supply: initial_supply.into(),
+            total_shares: initial_supply.into(),
+            virtual_shares_offset: quantity(&scenario["virtualSharesOffset"]).into(),
+            max_total_shares: quantity(&scenario["maxTotalShares"]).into(),
+            allow_supply_decrease: true.into(),
+            ..RebaseMintConfig::default()
+        };
+        config.refresh_cached_ratio();
+        Self {
+            config,
+            mint_supply: 0,
+            shares: vec![0; scenario["accounts"].as_u64().unwrap() as usize],
+        }
+    }
+
+    /// Same conversions and checks, in the same order, as `MintTo`
+    fn mint(&mut self, account: usize, amount: u64) -> ProgramResult {
+        let shares = self
+            .config
+            .amount_to_shares(amount)
+            .ok_or(TokenError::Overflow)?;
+        self.config.issue_shares(shares, amount)?;
+        self.shares[account] = self.shares[account]
+            .checked_add(shares)
+            .ok_or(TokenError::Overflow)?;
+        self.config
+            .shares_to_amount(self.shares[account])
+            .ok_or(TokenError::Overflow)?;
+        self.mint_supply = self
+            .mint_supply
+            .checked_add(amount)
+            .ok_or(TokenError::Overflow)?;
+        Ok(())
+    }
+
+    /// Same conversions and checks, in the same order, as `Burn`
+    fn burn(&mut self, account: usize, amount: u64) -> ProgramResult {
+        let shares = self
+            .config
+            .amount_to_shares_rounding_up(amount)
+            .ok_or(TokenError::Overflow)?;
+        self.shares[account] = self.shares[account]
+            .checked_sub(shares)
+            .ok_or(TokenError::InsufficientFunds)?;
+        self.config
+            .shares_to_amount(self.shares[account])
+            .ok_or(TokenError::Overflow)?;
+        self.config.retire_shares(shares, amount)?;
+        // the base supply isn't synced, so holders can burn more than it
+        self.mint_supply = self.mint_supply.saturating_sub(amount);
+        Ok(())
+    }
+
+    fn rebase(&mut self, new_supply: u64) -> ProgramResult {
+        let preview = self.config.preview_rebase(new_supply)?;
+        self.config.apply_rebase(&preview);
+        Ok(())
+    }
+
+    /// Apply a step, leaving the state as it was if it fails
+    fn apply(&mut self, step: &Value) -> ProgramResult {
+        let mut next = self.clone();
+        match step["op"].as_str().unwrap() {
+            "mint" => next.mint(
+                step["account"].as_u64().unwrap() as usize,
+                quantity(&step["amount"]),
+            ),
+            "burn" => next.burn(
+                step["account"].as_u64().unwrap() as usize,
+                quantity(&step["amount"]),
+            ),
+            "rebase" => next.rebase(quantity(&step["newSupply"])),
+            op => panic!("unknown op {}", op),
+        }?;
+        *self = next;
+        Ok(())
+    }
+
+    fn check(&self, expect: &Value, context: &str) {
+        let quantities = |value: &Value| {
+            value
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(quantity)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            u64::from(self.config.total_supply),
+            quantity(&expect["totalSupply"]),
+            "{}",
+            context
+        );
+        assert_eq!(
+            u64::from(self.config.total_shares),
+            quantity(&expect["totalShares"]),
+            "{}",
+            context
+        );
+        assert_eq!(
+            self.mint_supply,
+            quantity(&expect["mintSupply"]),
+            "{}",
+            context
+        );
+        assert_eq!(self.shares, quantities(&expect["shares"]), "{}", context);
+        let balances = self
+            .shares
+            .iter()
+            .map(|shares| self.config.shares_to_amount(*shares).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(balances, quantities(&expect["balances"]), "{}", context);
+    }
+}
+
+#[test]
+fn replay_share_vectors() {
+    let vectors: Value = serde_json::from_str(VECTORS).unwrap();
+    assert_eq!(vectors["version"], 2);
+    for scenario in vectors["scenarios"].as_array().unwrap() {
+        let name = scenario["name"].as_str().unwrap();
+        let mut replay = Replay::new(scenario);
+        for (index, step) in scenario["steps"].as_array().unwrap().iter().enumerate() {
+            let context = format!("{}, step {}", name, index);
+            let result = replay.apply(step);
+            if let Some(expect) = step.get("expect") {
+                result.unwrap_or_else(|e| panic!("{}: {:?}", context, e));
+                replay.check(expect, &context);
+            } else {
+                assert_eq!(
+                    error_name(result.unwrap_err()),
+                    step["expectError"].as_str().unwrap(),
+                    "{}",
+                    context
+                );
+            }
+        }
+    }
+}
@@ -166,3 +166,601 @@ fn serde_instruction_basis_points() {
 
     serde_json::from_str::<InitializeInstructionData>(&serialized_expected).unwrap();
 }
+
+#[test]
+fn serde_ui_rebase_mint_config() {
+    use spl_token_2022::extension::rebase_mint::{ui::UiRebaseMintConfig, RebaseMintConfig};
+
+    let supply_authority: Option<Pubkey> =
+        Some(Pubkey::from_str("4uQeVj5tqViQh7yWWGStvkEG1Zmhx6uasJtWCJziofM").unwrap());
+    let config = RebaseMintConfig {
+        supply_authority: supply_authority.try_into().unwrap(),
+        total_supply: u64::MAX.into(),
+        total_shares: 12_297_829_382_473_034_410.into(),
+        first_rebase_after_timestamp: 1_700_000_000.into(),
+        has_rebased: true.into(),
+        outstanding_shares: 12_297_829_382_473_034_410.into(),
+        cumulative_supply_increase: u128::MAX.into(),
+        ..Default::default()
+    };
+    let ui_config = UiRebaseMintConfig::from(&config);
+    assert_eq!(ui_config.current_ratio, Some(1.5));
+
+    // downstream parsers code against this fixture, so only extend it
+    let serialized = serde_json::to_string(&ui_config).unwrap();
+    let serialized_expected = "{\"supplyAuthority\":\"4uQeVj5tqViQh7yWWGStvkEG1Zmhx6uasJtWCJziofM\",\"totalSupply\":\"18446744073709551615\",\"totalShares\":\"12297829382473034410\",\"reserveAccount\":null,\"firstRebaseAfterTimestamp\":1700000000,\"pendingSupplyAuthority\":null,\"twoStepAuthorityTransfer\":false,\"authorityChangeDelaySeconds\":0,\"scheduledSupplyAuthority\":null,\"authorityChangeRequestedTimestamp\":0,\"rebaseDelegate\":null,\"useMintAuthorityFallback\":false,\"requireRebaseMemo\":false,\"hasRebased\":true,\"exemptSupply\":\"0\",\"outstandingShares\":\"12297829382473034410\",\"virtualSharesOffset\":\"0\",\"allowSupplyDecrease\":false,\"rebaseFeeBps\":0,\"feeDestination\":null,\"maxTotalShares\":\"9223372036854775808\",\"allowSunset\":false,\"isSunset\":false,\"updateMetadataRate\":false,\"isRetired\":false,\"retiredSupply\":\"0\",\"retiredShares\":\"0\",\"coSigner\":null,\"rebaseMode\":\"shares\",\"rebaseIndex\":\"0\",\"syncBaseSupply\":false,\"cumulativeSupplyIncrease\":\"340282366920938463463374607431768211455\",\"cumulativeSupplyDecrease\":\"0\",\"currentRatio\":1.5}";
+    assert_eq!(&serialized, serialized_expected);
+
+    let deserialized = serde_json::from_str::<UiRebaseMintConfig>(serialized_expected).unwrap();
+    assert_eq!(ui_config, deserialized);
+}
+
+#[test]
+fn serde_ui_rebase_mint_config_without_shares() {
+    use spl_token_2022::extension::rebase_mint::{ui::UiRebaseMintConfig, RebaseMintConfig};
+
+    let ui_config = UiRebaseMintConfig::from(&RebaseMintConfig::default());
+    assert_eq!(ui_config.current_ratio, None);
+
+    let serialized = serde_json::to_string(&ui_config).unwrap();
+    assert!(!serialized.contains("currentRatio"));
+    let deserialized = serde_json::from_str::<UiRebaseMintConfig>(&serialized).unwrap();
+    assert_eq!(ui_config, deserialized);
+}
+
+#[test]
+fn serde_ui_rebase_account() {
+    use spl_token_2022::extension::rebase_mint::{ui::UiRebaseAccount, RebaseAccount};
+
+    let account = RebaseAccount {
+        shares: u64::MAX.into(),
+        rebase_exempt: false.into(),
+    };
+    let ui_account = UiRebaseAccount::from(&account);
+
+    let serialized = serde_json::to_string(&ui_account).unwrap();
+    let serialized_expected = "{\"shares\":\"18446744073709551615\",\"rebaseExempt\":false}";
+    assert_eq!(&serialized, serialized_expected);
+
+    let deserialized = serde_json::from_str::<UiRebaseAccount>(serialized_expected).unwrap();
+    assert_eq!(ui_account, deserialized);
+}
+
+/// Check that `value` serializes to the `expected` fixture and back.
+/// Downstream parsers code against these fixtures, so only extend them.
+fn assert_serde_fixture<T>(value: &T, expected: &str)
+where
+    T: serde::Serialize + serde::de::DeserializeOwned + PartialEq + std::fmt::Debug,
+{
+    let serialized = serde_json::to_string(value).unwrap();
+    assert_eq!(&serialized, expected);
+    let deserialized = serde_json::from_str::<T>(expected).unwrap();
+    assert_eq!(value, &deserialized);
+}
+
+#[test]
+fn serde_rebase_mint_config() {
+    use spl_token_2022::extension::rebase_mint::RebaseMintConfig;
+
+    let supply_authority: Option<Pubkey> =
+        Some(Pubkey::from_str("4uQeVj5tqViQh7yWWGStvkEG1Zmhx6uasJtWCJziofM").unwrap());
+    let rebase_delegate: Option<Pubkey> =
+        Some(Pubkey::from_str("8opHzTAnfzRpPEx21XtnrVTX28YQuCpAjcn1PczScKh").unwrap());
+    let config = RebaseMintConfig {
+        supply_authority: supply_authority.try_into().unwrap(),
+        total_supply: u64::MAX.into(),
+        total_shares: 1_000.into(),
+        first_rebase_after_timestamp: (-1).into(),
+        two_step_authority_transfer: true.into(),
+        authority_change_delay_seconds: 86_400.into(),
+        rebase_delegate: rebase_delegate.try_into().unwrap(),
+        exempt_supply: 7.into(),
+        virtual_shares_offset: 1.into(),
+        cumulative_supply_decrease: 12_345.into(),
+        ..Default::default()
+    };
+    assert_serde_fixture(
+        &config,
+        "{\"supplyAuthority\":\"4uQeVj5tqViQh7yWWGStvkEG1Zmhx6uasJtWCJziofM\",\"totalSupply\":18446744073709551615,\"totalShares\":1000,\"reserveAccount\":null,\"firstRebaseAfterTimestamp\":-1,\"pendingSupplyAuthority\":null,\"twoStepAuthorityTransfer\":true,\"authorityChangeDelaySeconds\":86400,\"scheduledSupplyAuthority\":null,\"authorityChangeRequestedTimestamp\":0,\"rebaseDelegate\":\"8opHzTAnfzRpPEx21XtnrVTX28YQuCpAjcn1PczScKh\",\"useMintAuthorityFallback\":false,\"requireRebaseMemo\":false,\"hasRebased\":false,\"exemptSupply\":7,\"outstandingShares\":0,\"virtualSharesOffset\":1,\"allowSupplyDecrease\":false,\"cachedRatio\":0,\"rebaseFeeBps\":0,\"feeDestination\":null,\"maxTotalShares\":0,\"allowSunset\":false,\"isSunset\":false,\"updateMetadataRate\":false,\"isRetired\":false,\"retiredSupply\":0,\"retiredShares\":0,\"coSigner\":null,\"rebaseMode\":0,\"rebaseIndex\":0,\"syncBaseSupply\":false,\"cumulativeSupplyIncrease\":0,\"cumulativeSupplyDecrease\":12345,\"cachedInverseRatio\":0}",
+    );
+}
+
+#[test]
+fn serde_rebase_account() {
+    use spl_token_2022::extension::rebase_mint::RebaseAccount;
+
+    let account = RebaseAccount {
+        shares: 1_500.into(),
+        rebase_exempt: true.into(),
+    };
+    assert_serde_fixture(&account, "{\"shares\":1500,\"rebaseExempt\":true}");
+}
+
+#[test]
+fn serde_rebase_results_and_events() {
+    use spl_token_2022::extension::rebase_mint::{
+        RebaseConsistency, RebasePreview, SupplyRebased, SupplySynced,
+    };
+
+    assert_serde_fixture(
+        &RebasePreview {
+            new_supply: 1_500.into(),
+            total_shares: 1_000.into(),
+            rounding_error: 1.into(),
+        },
+        "{\"newSupply\":1500,\"totalShares\":1000,\"roundingError\":1}",
+    );
+    assert_serde_fixture(
+        &RebaseConsistency {
+            mint_supply: 1_507.into(),
+            total_supply: 1_500.into(),
+            total_shares: 1_000.into(),
+            outstanding_shares: 1_000.into(),
+            exempt_supply: 7.into(),
+        },
+        "{\"mintSupply\":1507,\"totalSupply\":1500,\"totalShares\":1000,\"outstandingShares\":1000,\"exemptSupply\":7}",
+    );
+    assert_serde_fixture(
+        &SupplySynced {
+            previous_supply: 1_000.into(),
+            previous_shares: 1_000.into(),
+            total_supply: 900.into(),
+            total_shares: 900.into(),
+        },
+        "{\"previousSupply\":1000,\"previousShares\":1000,\"totalSupply\":900,\"totalShares\":900}",
+    );
+    assert_serde_fixture(
+        &SupplyRebased {
+            previous_supply: 1_000.into(),
+            new_supply: 1_500.into(),
+            total_shares: 1_100.into(),
+            exempt_supply: 7.into(),
+            fee_shares: 100.into(),
+        },
+        "{\"previousSupply\":1000,\"newSupply\":1500,\"totalShares\":1100,\"exemptSupply\":7,\"feeShares\":100}",
+    );
+}
+
+#[test]
+fn serde_rebase_typed_events() {
+    use spl_token_2022::extension::rebase_mint::event::{
+        RebaseEventHeader, RebaseEventType, RebaseTagged, SupplyAuthorityChanged,
+    };
+
+    assert_serde_fixture(
+        &RebaseEventType::SupplyAuthorityChanged,
+        "\"supplyAuthorityChanged\"",
+    );
+    assert_serde_fixture(
+        &RebaseEventHeader {
+            mint: Pubkey::from_str("4uQeVj5tqViQh7yWWGStvkEG1Zmhx6uasJtWCJziofM").unwrap(),
+            unix_timestamp: 1_700_000_000.into(),
+        },
+        "{\"mint\":\"4uQeVj5tqViQh7yWWGStvkEG1Zmhx6uasJtWCJziofM\",\"unixTimestamp\":1700000000}",
+    );
+    let previous_authority: OptionalNonZeroPubkey =
+        Some(Pubkey::from_str("8opHzTAnfzRpPEx21XtnrVTX28YQuCpAjcn1PczScKh").unwrap())
+            .try_into()
+            .unwrap();
+    assert_serde_fixture(
+        &SupplyAuthorityChanged {
+            previous_authority,
+            new_authority: None.try_into().unwrap(),
+        },
+        "{\"previousAuthority\":\"8opHzTAnfzRpPEx21XtnrVTX28YQuCpAjcn1PczScKh\",\"newAuthority\":null}",
+    );
+    assert_serde_fixture(
+        &RebaseTagged {
+            previous_supply: 1_000.into(),
+            new_supply: 1_500.into(),
+            reason_code: 3,
+            reference: [9; 32],
+            cumulative_supply_increase: 500.into(),
+            cumulative_supply_decrease: 200.into(),
+        },
+        "{\"previousSupply\":1000,\"newSupply\":1500,\"reasonCode\":3,\"reference\":[9,9,9,9,9,9,9,9,9,9,9,9,9,9,9,9,9,9,9,9,9,9,9,9,9,9,9,9,9,9,9,9],\"cumulativeSupplyIncrease\":500,\"cumulativeSupplyDecrease\":200}",
+    );
+}
+
+#[test]
+fn serde_rebase_instruction_type() {
+    use spl_token_2022::extension::rebase_mint::instruction::RebaseMintInstruction;
+
+    assert_serde_fixture(&RebaseMintInstruction::RebaseSupply, "\"rebaseSupply\"");
+    assert_serde_fixture(
+        &RebaseMintInstruction::TransferCheckedWithShareSlippage,
+        "\"transferCheckedWithShareSlippage\"",
+    );
+}
+
+#[test]
+fn serde_rebase_initialize_instruction_data() {
+    use spl_token_2022::extension::rebase_mint::instruction::InitializeInstructionData;
+
+    let supply_authority: Option<Pubkey> =
+        Some(Pubkey::from_str("4uQeVj5tqViQh7yWWGStvkEG1Zmhx6uasJtWCJziofM").unwrap());
+    let inst = InitializeInstructionData {
+        supply_authority: supply_authority.try_into().unwrap(),
+        initial_supply: 1_000.into(),
+        first_rebase_after_timestamp: 1_700_000_000.into(),
+        two_step_authority_transfer: false.into(),
+        authority_change_delay_seconds: 0.into(),
+        use_mint_authority_fallback: true.into(),
+        require_rebase_memo: false.into(),
+        virtual_shares_offset: 0.into(),
+        allow_supply_decrease: true.into(),
+        rebase_fee_bps: 250.into(),
+        fee_destination: supply_authority.try_into().unwrap(),
+        initial_shares: 3_000.into(),
+        max_total_shares: 1_000_000.into(),
+        allow_sunset: true.into(),
+        update_metadata_rate: true.into(),
+        co_signer: supply_authority.try_into().unwrap(),
+        rebase_mode: 0,
+        sync_base_supply: false.into(),
+    };
+    assert_serde_fixture(
+        &inst,
+        "{\"supplyAuthority\":\"4uQeVj5tqViQh7yWWGStvkEG1Zmhx6uasJtWCJziofM\",\"initialSupply\":1000,\"firstRebaseAfterTimestamp\":1700000000,\"twoStepAuthorityTransfer\":false,\"authorityChangeDelaySeconds\":0,\"useMintAuthorityFallback\":true,\"requireRebaseMemo\":false,\"virtualSharesOffset\":0,\"allowSupplyDecrease\":true,\"rebaseFeeBps\":250,\"feeDestination\":\"4uQeVj5tqViQh7yWWGStvkEG1Zmhx6uasJtWCJziofM\",\"initialShares\":3000,\"maxTotalShares\":1000000,\"allowSunset\":true,\"updateMetadataRate\":true,\"coSigner\":\"4uQeVj5tqViQh7yWWGStvkEG1Zmhx6uasJtWCJziofM\",\"rebaseMode\":0,\"syncBaseSupply\":false}",
+    );
+
+    let inst = InitializeInstructionData {
+        supply_authority: OptionalNonZeroPubkey::default(),
+        ..inst
+    };
+    let serialized = serde_json::to_string(&inst).unwrap();
+    assert!(serialized.starts_with("{\"supplyAuthority\":null,"));
+}
+
+#[test]
+fn serde_rebase_authority_instruction_data() {
+    use spl_token_2022::extension::rebase_mint::instruction::{
+        ProposeSupplyAuthorityData, SetRebaseDelegateData, SetRebaseFeeData, SetReserveAccountData,
+        SetSupplyAuthorityData,
+    };
+
+    let authority: OptionalNonZeroPubkey =
+        Some(Pubkey::from_str("4uQeVj5tqViQh7yWWGStvkEG1Zmhx6uasJtWCJziofM").unwrap())
+            .try_into()
+            .unwrap();
+    assert_serde_fixture(
+        &ProposeSupplyAuthorityData {
+            pending_authority: authority,
+        },
+        "{\"pendingAuthority\":\"4uQeVj5tqViQh7yWWGStvkEG1Zmhx6uasJtWCJziofM\"}",
+    );
+    assert_serde_fixture(
+        &SetReserveAccountData {
+            reserve_account: OptionalNonZeroPubkey::default(),
+        },
+        "{\"reserveAccount\":null}",
+    );
+    assert_serde_fixture(
+        &SetSupplyAuthorityData {
+            new_authority: authority,
+        },
+        "{\"newAuthority\":\"4uQeVj5tqViQh7yWWGStvkEG1Zmhx6uasJtWCJziofM\"}",
+    );
+    assert_serde_fixture(
+        &SetRebaseDelegateData {
+            rebase_delegate: OptionalNonZeroPubkey::default(),
+        },
+        "{\"rebaseDelegate\":null}",
+    );
+    assert_serde_fixture(
+        &SetRebaseFeeData {
+            rebase_fee_bps: 10_000.into(),
+            fee_destination: authority,
+        },
+        "{\"rebaseFeeBps\":10000,\"feeDestination\":\"4uQeVj5tqViQh7yWWGStvkEG1Zmhx6uasJtWCJziofM\"}",
+    );
+}
+
+#[test]
+fn serde_rebase_amount_instruction_data() {
+    use spl_token_2022::extension::rebase_mint::instruction::{
+        AmountToSharesData, ApproveSharesData, BurnSharesData, MintToSharesData, RebaseSupplyData,
+        SetAccountExemptData, SharesToAmountData, TransferCheckedWithShareSlippageData,
+        TransferSharesCheckedData, TransferSharesData, UnwrapSharesData, WrapSharesData,
+    };
+
+    assert_serde_fixture(
+        &RebaseSupplyData {
+            new_supply: u64::MAX.into(),
+            reason_code: 3,
+            reference: [9; 32],
+            not_after_unix_timestamp: 1_700_000_000.into(),
+        },
+        "{\"newSupply\":18446744073709551615,\"reasonCode\":3,\"reference\":[9,9,9,9,9,9,9,9,9,9,9,9,9,9,9,9,9,9,9,9,9,9,9,9,9,9,9,9,9,9,9,9],\"notAfterUnixTimestamp\":1700000000}",
+    );
+    assert_serde_fixture(&TransferSharesData { shares: 42.into() }, "{\"shares\":42}");
+    assert_serde_fixture(
+        &TransferSharesCheckedData {
+            shares: 42.into(),
+            decimals: 6,
+        },
+        "{\"shares\":42,\"decimals\":6}",
+    );
+    assert_serde_fixture(
+        &TransferCheckedWithShareSlippageData {
+            amount: 63.into(),
+            decimals: 6,
+            expected_shares: 42.into(),
+            max_share_slippage_bps: 50.into(),
+        },
+        "{\"amount\":63,\"decimals\":6,\"expectedShares\":42,\"maxShareSlippageBps\":50}",
+    );
+    assert_serde_fixture(&MintToSharesData { shares: 42.into() }, "{\"shares\":42}");
+    assert_serde_fixture(&BurnSharesData { shares: 42.into() }, "{\"shares\":42}");
+    assert_serde_fixture(&AmountToSharesData { amount: 63.into() }, "{\"amount\":63}");
+    assert_serde_fixture(&SharesToAmountData { shares: 42.into() }, "{\"shares\":42}");
+    assert_serde_fixture(&ApproveSharesData { shares: 42.into() }, "{\"shares\":42}");
+    assert_serde_fixture(
+        &SetAccountExemptData {
+            exempt: true.into(),
+        },
+        "{\"exempt\":true}",
+    );
+    assert_serde_fixture(&WrapSharesData { shares: 42.into() }, "{\"shares\":42}");
+    assert_serde_fixture(&UnwrapSharesData { shares: 42.into() }, "{\"shares\":42}");
+}
+
+#[test]
+fn serde_rebase_ignores_unknown_fields() {
+    use spl_token_2022::extension::rebase_mint::instruction::RebaseSupplyData;
+
+    // like every other serde type of the crate, and without the reason and
+    // deadline fields, which were added later
+    let deserialized =
+        serde_json::from_str::<RebaseSupplyData>("{\"newSupply\":1500,\"memo\":\"hello\"}")
+            .unwrap();
+    assert_eq!(
+        deserialized,
+        RebaseSupplyData {
+            new_supply: 1_500.into(),
+            reason_code: 0,
+            reference: [0; 32],
+            not_after_unix_timestamp: 0.into(),
+        }
+    );
+}
+
+#[test]
+fn serde_decoded_rebase_instruction() {
+    use spl_token_2022::extension::rebase_mint::{
+        instruction::DecodedRebaseInstruction, RebaseMode,
+    };
+
+    let authority = Some(Pubkey::from_str("4uQeVj5tqViQh7yWWGStvkEG1Zmhx6uasJtWCJziofM").unwrap());
+    let fixtures = [
+        (
+            DecodedRebaseInstruction::Initialize {
+                supply_authority: authority,
+                initial_supply: 1_000,
+                first_rebase_after_timestamp: 1_700_000_000,
+                two_step_authority_transfer: true,
+                authority_change_delay_seconds: 86_400,
+                use_mint_authority_fallback: false,
+                require_rebase_memo: false,
+                virtual_shares_offset: 1,
+                allow_supply_decrease: true,
+                rebase_fee_bps: 250,
+                fee_destination: authority,
+                initial_shares: 3_000,
+                max_total_shares: 1_000_000,
+                allow_sunset: true,
+                update_metadata_rate: true,
+                co_signer: authority,
+                rebase_mode: RebaseMode::Shares,
+                sync_base_supply: false,
+            },
+            "{\"initialize\":{\"supplyAuthority\":\"4uQeVj5tqViQh7yWWGStvkEG1Zmhx6uasJtWCJziofM\",\"initialSupply\":1000,\"firstRebaseAfterTimestamp\":1700000000,\"twoStepAuthorityTransfer\":true,\"authorityChangeDelaySeconds\":86400,\"useMintAuthorityFallback\":false,\"requireRebaseMemo\":false,\"virtualSharesOffset\":1,\"allowSupplyDecrease\":true,\"rebaseFeeBps\":250,\"feeDestination\":\"4uQeVj5tqViQh7yWWGStvkEG1Zmhx6uasJtWCJziofM\",\"initialShares\":3000,\"maxTotalShares\":1000000,\"allowSunset\":true,\"updateMetadataRate\":true,\"coSigner\":\"4uQeVj5tqViQh7yWWGStvkEG1Zmhx6uasJtWCJziofM\",\"rebaseMode\":\"shares\",\"syncBaseSupply\":false}}",
+        ),
+        (
+            DecodedRebaseInstruction::Initialize {
+                supply_authority: None,
+                initial_supply: 0,
+                first_rebase_after_timestamp: 0,
+                two_step_authority_transfer: false,
+                authority_change_delay_seconds: 0,
+                use_mint_authority_fallback: true,
+                require_rebase_memo: true,
+                virtual_shares_offset: 0,
+                allow_supply_decrease: false,
+                rebase_fee_bps: 0,
+                fee_destination: None,
+                initial_shares: 0,
+                max_total_shares: 0,
+                allow_sunset: false,
+                update_metadata_rate: false,
+                co_signer: None,
+                rebase_mode: RebaseMode::Lazy,
+                sync_base_supply: true,
+            },
+            "{\"initialize\":{\"supplyAuthority\":null,\"initialSupply\":0,\"firstRebaseAfterTimestamp\":0,\"twoStepAuthorityTransfer\":false,\"authorityChangeDelaySeconds\":0,\"useMintAuthorityFallback\":true,\"requireRebaseMemo\":true,\"virtualSharesOffset\":0,\"allowSupplyDecrease\":false,\"rebaseFeeBps\":0,\"feeDestination\":null,\"initialShares\":0,\"maxTotalShares\":0,\"allowSunset\":false,\"updateMetadataRate\":false,\"coSigner\":null,\"rebaseMode\":\"lazy\",\"syncBaseSupply\":true}}",
+        ),
+        (
+            DecodedRebaseInstruction::RebaseSupply {
+                new_supply: u64::MAX,
+                reason_code: 0,
+                reference: [0; 32],
+                not_after_unix_timestamp: 0,
+            },
+            "{\"rebaseSupply\":{\"newSupply\":18446744073709551615,\"reasonCode\":0,\"reference\":[0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0],\"notAfterUnixTimestamp\":0}}",
+        ),
+        (
+            DecodedRebaseInstruction::SetReserveAccount {
+                reserve_account: authority,
+            },
+            "{\"setReserveAccount\":{\"reserveAccount\":\"4uQeVj5tqViQh7yWWGStvkEG1Zmhx6uasJtWCJziofM\"}}",
+        ),
+        (
+            DecodedRebaseInstruction::SetReserveAccount {
+                reserve_account: None,
+            },
+            "{\"setReserveAccount\":{\"reserveAccount\":null}}",
+        ),
+        (
+            DecodedRebaseInstruction::PreviewRebaseSupply { new_supply: 1_500 },
+            "{\"previewRebaseSupply\":{\"newSupply\":1500}}",
+        ),
+        (
+            DecodedRebaseInstruction::SetSupplyAuthority {
+                new_authority: authority,
+            },
+            "{\"setSupplyAuthority\":{\"newAuthority\":\"4uQeVj5tqViQh7yWWGStvkEG1Zmhx6uasJtWCJziofM\"}}",
+        ),
+        (
+            DecodedRebaseInstruction::SetSupplyAuthority {
+                new_authority: None,
+            },
+            "{\"setSupplyAuthority\":{\"newAuthority\":null}}",
+        ),
+        (
+            DecodedRebaseInstruction::ProposeSupplyAuthority {
+                pending_authority: authority,
+            },
+            "{\"proposeSupplyAuthority\":{\"pendingAuthority\":\"4uQeVj5tqViQh7yWWGStvkEG1Zmhx6uasJtWCJziofM\"}}",
+        ),
+        (
+            DecodedRebaseInstruction::ProposeSupplyAuthority {
+                pending_authority: None,
+            },
+            "{\"proposeSupplyAuthority\":{\"pendingAuthority\":null}}",
+        ),
+        (
+            DecodedRebaseInstruction::AcceptSupplyAuthority,
+            "\"acceptSupplyAuthority\"",
+        ),
+        (
+            DecodedRebaseInstruction::CancelPendingAuthority,
+            "\"cancelPendingAuthority\"",
+        ),
+        (
+            DecodedRebaseInstruction::FinalizeAuthorityChange,
+            "\"finalizeAuthorityChange\"",
+        ),
+        (
+            DecodedRebaseInstruction::CancelAuthorityChange,
+            "\"cancelAuthorityChange\"",
+        ),
+        (
+            DecodedRebaseInstruction::SetRebaseDelegate {
+                rebase_delegate: authority,
+            },
+            "{\"setRebaseDelegate\":{\"rebaseDelegate\":\"4uQeVj5tqViQh7yWWGStvkEG1Zmhx6uasJtWCJziofM\"}}",
+        ),
+        (
+            DecodedRebaseInstruction::SetRebaseDelegate {
+                rebase_delegate: None,
+            },
+            "{\"setRebaseDelegate\":{\"rebaseDelegate\":null}}",
+        ),
+        (
+            DecodedRebaseInstruction::TransferShares { shares: 42 },
+            "{\"transferShares\":{\"shares\":42}}",
+        ),
+        (
+            DecodedRebaseInstruction::TransferSharesChecked {
+                shares: 42,
+                decimals: 6,
+            },
+            "{\"transferSharesChecked\":{\"shares\":42,\"decimals\":6}}",
+        ),
+        (
+            DecodedRebaseInstruction::MintToShares { shares: 42 },
+            "{\"mintToShares\":{\"shares\":42}}",
+        ),
+        (
+            DecodedRebaseInstruction::BurnShares { shares: 42 },
+            "{\"burnShares\":{\"shares\":42}}",
+        ),
+        (
+            DecodedRebaseInstruction::SyncSupplyFromMint,
+            "\"syncSupplyFromMint\"",
+        ),
+        (
+            DecodedRebaseInstruction::GetRebasedBalance,
+            "\"getRebasedBalance\"",
+        ),
+        (
+            DecodedRebaseInstruction::ConvertAmountToShares { amount: 63 },
+            "{\"convertAmountToShares\":{\"amount\":63}}",
+        ),
+        (
+            DecodedRebaseInstruction::ConvertSharesToAmount { shares: 42 },
+            "{\"convertSharesToAmount\":{\"shares\":42}}",
+        ),
+        (
+            DecodedRebaseInstruction::ApproveShares { shares: 42 },
+            "{\"approveShares\":{\"shares\":42}}",
+        ),
+        (
+            DecodedRebaseInstruction::SetAccountExempt { exempt: true },
+            "{\"setAccountExempt\":{\"exempt\":true}}",
+        ),
+        (
+            DecodedRebaseInstruction::InitializeWrapper,
+            "\"initializeWrapper\"",
+        ),
+        (
+            DecodedRebaseInstruction::WrapShares { shares: 42 },
+            "{\"wrapShares\":{\"shares\":42}}",
+        ),
+        (
+            DecodedRebaseInstruction::UnwrapShares { shares: 42 },
+            "{\"unwrapShares\":{\"shares\":42}}",
+        ),
+        (
+            DecodedRebaseInstruction::AssertRebaseConsistency,
+            "\"assertRebaseConsistency\"",
+        ),
+        (
+            DecodedRebaseInstruction::TransferCheckedWithShareSlippage {
+                amount: 63,
+                decimals: 6,
+                expected_shares: 42,
+                max_share_slippage_bps: 50,
+            },
+            "{\"transferCheckedWithShareSlippage\":{\"amount\":63,\"decimals\":6,\"expectedShares\":42,\"maxShareSlippageBps\":50}}",
+        ),
+        (
+            DecodedRebaseInstruction::GetRebaseState,
+            "\"getRebaseState\"",
+        ),
+        (
+            DecodedRebaseInstruction::InitializeHistory,
+            "\"initializeHistory\"",
+        ),
+        (
+            DecodedRebaseInstruction::GetRebaseHistory,
+            "\"getRebaseHistory\"",
+        ),
+        (
+            DecodedRebaseInstruction::SetRebaseFee {
+                rebase_fee_bps: 250,
+                fee_destination: authority,
+            },
+            "{\"setRebaseFee\":{\"rebaseFeeBps\":250,\"feeDestination\":\"4uQeVj5tqViQh7yWWGStvkEG1Zmhx6uasJtWCJziofM\"}}",
+        ),
+        (DecodedRebaseInstruction::SunsetRebase, "\"sunsetRebase\""),
+        (DecodedRebaseInstruction::RetireRebase, "\"retireRebase\""),
+        (
+            DecodedRebaseInstruction::SweepRoundingDust,
+            "\"sweepRoundingDust\"",
+        ),
+        (DecodedRebaseInstruction::ClaimRebase, "\"claimRebase\""),
+        (
+            DecodedRebaseInstruction::RebaseSupplyUi {
+                ui_supply: "1250000.5".to_string(),
+                reason_code: 0,
+                reference: [0; 32],
+                not_after_unix_timestamp: 0,
+            },
+            "{\"rebaseSupplyUi\":{\"uiSupply\":\"1250000.5\",\"reasonCode\":0,\"reference\":[0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0],\"notAfterUnixTimestamp\":0}}",
+        ),
+    ];
+    for (instruction, expected) in fixtures {
+        assert_serde_fixture(&instruction, expected);
+    }
+}
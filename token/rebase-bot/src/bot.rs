@@ -0,0 +1,565 @@
+//! A single poll of the feed, and the rebase it leads to
+
+use {
+    crate::feed::{supply_from_json, Feed},
+    solana_client::{
+        nonblocking::rpc_client::RpcClient, nonce_utils, rpc_config::RpcTransactionConfig,
+    },
+    solana_sdk::{
+        account::from_account,
+        clock::Clock,
+        hash::Hash,
+        pubkey::Pubkey,
+        signature::{Signature, Signer},
+        sysvar,
+    },
+    solana_transaction_status::{option_serializer::OptionSerializer, UiTransactionEncoding},
+    spl_token_2022::{
+        extension::{
+            rebase_mint::{event::RebaseEvent, instruction::RebaseSupplyBuilder, RebaseMintConfig},
+            BaseStateWithExtensions,
+        },
+        try_ui_amount_into_amount,
+    },
+    spl_token_client::{
+        client::{ProgramClient, ProgramRpcClientSendTransaction, RpcClientResponse},
+        token::{
+            rebase_error_from_transaction_error, rebase_events_from_logs, RebaseSupplyAccounts,
+            Token,
+        },
+    },
+    std::{error::Error, sync::Arc},
+};
+
+/// Durable nonce used to submit the rebases, so that they don't expire
+/// while waiting for a blockhash
+pub struct NonceConfig {
+    /// Nonce account
+    pub account: Pubkey,
+    /// Authority of the nonce account
+    pub authority: Arc<dyn Signer>,
+}
+
+impl NonceConfig {
+    /// Fetch the blockhash currently stored in the nonce account
+    async fn blockhash(&self, rpc_client: &RpcClient) -> Result<Hash, Box<dyn Error>> {
+        let account = nonce_utils::nonblocking::get_account_with_commitment(
+            rpc_client,
+            &self.account,
+            rpc_client.commitment(),
+        )
+        .await?;
+        Ok(nonce_utils::nonblocking::data_from_account(&account)?.blockhash())
+    }
+}
+
+/// Everything a poll needs to read the feed and rebase the mint
+pub struct Bot {
+    pub rpc_client: Arc<RpcClient>,
+    pub program_client: Arc<dyn ProgramClient<ProgramRpcClientSendTransaction>>,
+    pub http_client: reqwest::Client,
+    pub payer: Arc<dyn Signer>,
+    pub supply_authority: Arc<dyn Signer>,
+    /// Co-signer of the mint, which must sign every rebase if it has one
+    pub co_signer: Option<Arc<dyn Signer>>,
+    pub mint: Pubkey,
+    pub feed: Feed,
+    /// Key or JSON pointer of the target supply in the feed, in UI units
+    pub feed_field: String,
+    /// Largest change of the supply in a single rebase, in basis points of
+    /// the current supply
+    pub max_change_bps: u16,
+    /// Seconds after the supply is read, by the cluster clock, past which a
+    /// rebase computed from it fails instead of landing
+    pub valid_for_seconds: u32,
+    /// Simulate the rebases instead of sending them
+    pub dry_run: bool,
+    pub nonce: Option<NonceConfig>,
+}
+
+/// What a poll did
+#[derive(Debug, PartialEq)]
+pub enum PollOutcome {
+    /// The supply already matches the feed
+    Unchanged { supply: u64 },
+    /// The rebase was simulated, but not sent
+    Simulated {
+        previous_supply: u64,
+        new_supply: u64,
+        events: Vec<RebaseEvent>,
+    },
+    /// The rebase landed
+    Rebased {
+        signature: Signature,
+        previous_supply: u64,
+        new_supply: u64,
+        events: Vec<RebaseEvent>,
+    },
+}
+
+/// Check a rebase from `current_supply` to `target_supply` against the
+/// largest change allowed, returning the new supply, or `None` if there is
+/// nothing to do.
+///
+/// A mint without supply can't be rebased by any number of basis points, so
+/// it has to be seeded by hand.
+pub fn plan_rebase(
+    current_supply: u64,
+    target_supply: u64,
+    max_change_bps: u16,
+) -> Result<Option<u64>, String> {
+    if target_supply == current_supply {
+        return Ok(None);
+    }
+    let change = u128::from(current_supply.abs_diff(target_supply));
+    if change * 10_000 > u128::from(current_supply) * u128::from(max_change_bps) {
+        return Err(format!(
+            "Rebase from {} to {} exceeds the maximum change of {}bps",
+            current_supply, target_supply, max_change_bps
+        ));
+    }
+    Ok(Some(target_supply))
+}
+
+impl Bot {
+    fn token(&self) -> Token<ProgramRpcClientSendTransaction> {
+        Token::new(
+            self.program_client.clone(),
+            &spl_token_2022::id(),
+            &self.mint,
+            None,
+            self.payer.clone(),
+        )
+    }
+
+    /// Decode the events the program logged for the mint in the logs of a
+    /// transaction
+    fn events_from_logs(&self, logs: &[String]) -> Vec<RebaseEvent> {
+        rebase_events_from_logs(logs, &spl_token_2022::id(), &self.mint)
+    }
+
+    /// Deadline of a rebase computed from the supply read now
+    async fn deadline(&self) -> Result<i64, Box<dyn Error>> {
+        let clock_account = self.rpc_client.get_account(&sysvar::clock::id()).await?;
+        let clock = from_account::<Clock, _>(&clock_account).ok_or("Invalid clock sysvar")?;
+        Ok(clock
+            .unix_timestamp
+            .saturating_add(i64::from(self.valid_for_seconds)))
+    }
+
+    /// Signers of a rebase
+    fn signers(&self) -> Vec<&dyn Signer> {
+        let mut signers = vec![self.supply_authority.as_ref()];
+        signers.extend(self.co_signer.as_deref());
+        signers
+    }
+
+    /// Read the feed and rebase the mint to the supply it reports
+    pub async fn poll(&self) -> Result<PollOutcome, Box<dyn Error>> {
+        let document = self.feed.read(&self.http_client).await?;
+        let ui_supply = supply_from_json(&document, &self.feed_field)?;
+
+        let token = self.token();
+        let not_after_unix_timestamp = self.deadline().await?;
+        let mint = token.get_mint_info().await?;
+        let config = *mint.get_extension::<RebaseMintConfig>()?;
+        let target_supply = try_ui_amount_into_amount(ui_supply.clone(), mint.base.decimals)
+            .map_err(|_| format!("Invalid supply in the feed: {}", ui_supply))?;
+        let previous_supply = u64::from(config.total_supply);
+        let Some(new_supply) = plan_rebase(previous_supply, target_supply, self.max_change_bps)?
+        else {
+            return Ok(PollOutcome::Unchanged {
+                supply: previous_supply,
+            });
+        };
+
+        if self.dry_run {
+            return self
+                .simulate(
+                    &token,
+                    &config,
+                    previous_supply,
+                    new_supply,
+                    not_after_unix_timestamp,
+                )
+                .await;
+        }
+
+        let token = match &self.nonce {
+            Some(nonce) => {
+                let blockhash = nonce.blockhash(&self.rpc_client).await?;
+                token.with_nonce(&nonce.account, nonce.authority.clone(), &blockhash)
+            }
+            None => token,
+        };
+
+        // the change was only bounded against the supply read above, so the
+        // rebase must land soon after, or not at all. With a durable nonce,
+        // nothing else stops it from landing long after.
+        let response = token
+            .safe_rebase_supply_with_deadline(
+                &self.supply_authority.pubkey(),
+                new_supply,
+                not_after_unix_timestamp,
+                &self.signers(),
+            )
+            .await?;
+        let RpcClientResponse::Signature(signature) = response else {
+            return Err("Rebase wasn't sent".into());
+        };
+        let events = self.fetch_events(&signature).await?;
+        Ok(PollOutcome::Rebased {
+            signature,
+            previous_supply,
+            new_supply,
+            events,
+        })
+    }
+
+    async fn simulate(
+        &self,
+        token: &Token<ProgramRpcClientSendTransaction>,
+        config: &RebaseMintConfig,
+        previous_supply: u64,
+        new_supply: u64,
+        not_after_unix_timestamp: i64,
+    ) -> Result<PollOutcome, Box<dyn Error>> {
+        let instruction = RebaseSupplyAccounts::from_config(config, &self.payer.pubkey())
+            .apply(
+                RebaseSupplyBuilder::new(&spl_token_2022::id(), new_supply)
+                    .not_after_unix_timestamp(not_after_unix_timestamp),
+            )
+            .mint(&self.mint)
+            .authority(&self.supply_authority.pubkey())
+            .build()?;
+        let response = token.simulate_ixs(&[instruction], &self.signers()).await?;
+        let RpcClientResponse::Simulation(result) = response else {
+            return Err("Rebase wasn't simulated".into());
+        };
+        if let Some(err) = result.err {
+            return Err(match rebase_error_from_transaction_error(&err) {
+                Some(err) => format!("Simulated rebase failed: {}", err),
+                None => format!("Simulated rebase failed: {}", err),
+            }
+            .into());
+        }
+        Ok(PollOutcome::Simulated {
+            previous_supply,
+            new_supply,
+            events: self.events_from_logs(&result.logs.unwrap_or_default()),
+        })
+    }
+
+    async fn fetch_events(
+        &self,
+        signature: &Signature,
+    ) -> Result<Vec<RebaseEvent>, Box<dyn Error>> {
+        let transaction = self
+            .rpc_client
+            .get_transaction_with_config(
+                signature,
+                RpcTransactionConfig {
+                    encoding: Some(UiTransactionEncoding::Base64),
+                    commitment: Some(self.rpc_client.commitment()),
+                    max_supported_transaction_version: Some(0),
+                },
+            )
+            .await?;
+        let logs = match transaction.transaction.meta.map(|meta| meta.log_messages) {
+            Some(OptionSerializer::Some(logs)) => logs,
+            _ => vec![],
+        };
+        Ok(self.events_from_logs(&logs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{
+            bpf_loader_upgradeable, nonce, signer::keypair::Keypair, system_instruction,
+            transaction::Transaction,
+        },
+        solana_test_validator::{TestValidator, TestValidatorGenesis, UpgradeableProgramInfo},
+        spl_token_2022::{error::TokenError, extension::rebase_mint::event::RebaseTagged},
+        spl_token_client::{
+            client::ProgramRpcClient,
+            token::{ExtensionInitializationParams, TokenError as TokenClientError},
+        },
+        std::path::PathBuf,
+        tempfile::NamedTempFile,
+    };
+
+    const DECIMALS: u8 = 2;
+
+    #[test]
+    fn bounded_change() {
+        assert_eq!(plan_rebase(10_000, 10_000, 0), Ok(None));
+        assert_eq!(plan_rebase(10_000, 10_100, 100), Ok(Some(10_100)));
+        assert_eq!(plan_rebase(10_000, 9_900, 100), Ok(Some(9_900)));
+        assert!(plan_rebase(10_000, 10_101, 100).is_err());
+        assert!(plan_rebase(10_000, 9_899, 100).is_err());
+        assert!(plan_rebase(0, 1, u16::MAX).is_err());
+        assert_eq!(plan_rebase(u64::MAX, 0, 10_000), Ok(Some(0)));
+    }
+
+    #[test]
+    fn decode_events() {
+        let program_id = spl_token_2022::id();
+        let logs = [
+            format!("Program {} invoke [1]", program_id),
+            "Program log: RebaseMintInstruction::RebaseSupply".to_string(),
+            "Program data: not base64".to_string(),
+            "Program data: AQ==".to_string(),
+            format!("Program {} success", program_id),
+        ];
+        assert_eq!(
+            rebase_events_from_logs(&logs, &program_id, &Pubkey::new_unique()),
+            vec![]
+        );
+    }
+
+    async fn new_validator_for_test() -> (TestValidator, Keypair) {
+        solana_logger::setup();
+        let mut test_validator_genesis = TestValidatorGenesis::default();
+        test_validator_genesis.add_upgradeable_programs_with_path(&[UpgradeableProgramInfo {
+            program_id: spl_token_2022::id(),
+            loader: bpf_loader_upgradeable::id(),
+            program_path: PathBuf::from("../../target/deploy/spl_token_2022.so"),
+            upgrade_authority: Pubkey::new_unique(),
+        }]);
+        test_validator_genesis.start_async().await
+    }
+
+    struct TestContext {
+        _test_validator: TestValidator,
+        bot: Bot,
+        token: Token<ProgramRpcClientSendTransaction>,
+        feed: NamedTempFile,
+    }
+
+    impl TestContext {
+        fn set_feed(&self, ui_supply: &str) {
+            std::fs::write(
+                self.feed.path(),
+                format!(r#"{{"supply": "{}"}}"#, ui_supply),
+            )
+            .unwrap();
+        }
+
+        async fn supply(&self) -> u64 {
+            u64::from(
+                self.token
+                    .get_rebase_mint_config()
+                    .await
+                    .unwrap()
+                    .total_supply,
+            )
+        }
+    }
+
+    /// Rebasing mint with 1,000 tokens in circulation, and a bot watching a
+    /// file feed
+    async fn setup() -> TestContext {
+        let (test_validator, payer) = new_validator_for_test().await;
+        let payer: Arc<dyn Signer> = Arc::new(payer);
+        let rpc_client = Arc::new(test_validator.get_async_rpc_client());
+        let program_client: Arc<dyn ProgramClient<ProgramRpcClientSendTransaction>> = Arc::new(
+            ProgramRpcClient::new(rpc_client.clone(), ProgramRpcClientSendTransaction),
+        );
+
+        let mint = Keypair::new();
+        let mint_authority = Keypair::new();
+        let supply_authority: Arc<dyn Signer> = Arc::new(Keypair::new());
+        let token = Token::new(
+            program_client.clone(),
+            &spl_token_2022::id(),
+            &mint.pubkey(),
+            Some(DECIMALS),
+            payer.clone(),
+        );
+        token
+            .create_mint(
+                &mint_authority.pubkey(),
+                None,
+                vec![ExtensionInitializationParams::RebaseMintConfig {
+                    supply_authority: Some(supply_authority.pubkey()),
+                    initial_supply: 0,
+                }],
+                &[&mint],
+            )
+            .await
+            .unwrap();
+        let account = Keypair::new();
+        token
+            .create_auxiliary_token_account(&account, &payer.pubkey())
+            .await
+            .unwrap();
+        token
+            .mint_to(
+                &account.pubkey(),
+                &mint_authority.pubkey(),
+                100_000,
+                &[&mint_authority],
+            )
+            .await
+            .unwrap();
+
+        let feed = NamedTempFile::new().unwrap();
+        let bot = Bot {
+            rpc_client,
+            program_client,
+            http_client: reqwest::Client::new(),
+            payer,
+            supply_authority,
+            co_signer: None,
+            mint: mint.pubkey(),
+            feed: Feed::File(feed.path().to_path_buf()),
+            feed_field: "supply".to_string(),
+            max_change_bps: 100,
+            valid_for_seconds: 60,
+            dry_run: false,
+            nonce: None,
+        };
+        let context = TestContext {
+            _test_validator: test_validator,
+            bot,
+            token,
+            feed,
+        };
+        context.set_feed("1000");
+        context
+    }
+
+    fn rebase_tagged(previous_supply: u64, new_supply: u64) -> RebaseEvent {
+        RebaseEvent::RebaseTagged(RebaseTagged {
+            previous_supply: previous_supply.into(),
+            new_supply: new_supply.into(),
+            cumulative_supply_increase: u128::from(new_supply.saturating_sub(previous_supply))
+                .into(),
+            cumulative_supply_decrease: u128::from(previous_supply.saturating_sub(new_supply))
+                .into(),
+            ..RebaseTagged::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn rebase_from_file_feed() {
+        let context = setup().await;
+        assert_eq!(
+            context.bot.poll().await.unwrap(),
+            PollOutcome::Unchanged { supply: 100_000 }
+        );
+
+        context.set_feed("1005.5");
+        let PollOutcome::Rebased {
+            previous_supply,
+            new_supply,
+            events,
+            ..
+        } = context.bot.poll().await.unwrap()
+        else {
+            panic!("no rebase");
+        };
+        assert_eq!((previous_supply, new_supply), (100_000, 100_550));
+        assert_eq!(events, vec![rebase_tagged(100_000, 100_550)]);
+        assert_eq!(context.supply().await, 100_550);
+
+        // the next poll has nothing left to do
+        assert_eq!(
+            context.bot.poll().await.unwrap(),
+            PollOutcome::Unchanged { supply: 100_550 }
+        );
+    }
+
+    #[tokio::test]
+    async fn reject_rebases_out_of_bounds() {
+        let context = setup().await;
+
+        // more than 1% at once
+        context.set_feed("1010.01");
+        let err = context.bot.poll().await.unwrap_err();
+        assert!(err.to_string().contains("maximum change"), "{}", err);
+
+        // within bounds, but the mint doesn't allow decreases, which the
+        // preflight catches before sending
+        context.set_feed("999");
+        let err = context.bot.poll().await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<TokenClientError>(),
+            Some(&TokenClientError::Program(
+                TokenError::SupplyDecreaseForbidden.into()
+            ))
+        );
+
+        // more decimals than the mint
+        context.set_feed("1000.001");
+        assert!(context.bot.poll().await.is_err());
+        assert_eq!(context.supply().await, 100_000);
+    }
+
+    #[tokio::test]
+    async fn dry_run() {
+        let mut context = setup().await;
+        context.bot.dry_run = true;
+        context.set_feed("1001");
+        assert_eq!(
+            context.bot.poll().await.unwrap(),
+            PollOutcome::Simulated {
+                previous_supply: 100_000,
+                new_supply: 100_100,
+                events: vec![rebase_tagged(100_000, 100_100)],
+            }
+        );
+        assert_eq!(context.supply().await, 100_000);
+    }
+
+    #[tokio::test]
+    async fn durable_nonce() {
+        let mut context = setup().await;
+        let rpc_client = context.bot.rpc_client.clone();
+        let payer = context.bot.payer.clone();
+        let nonce_account = Keypair::new();
+        let rent = rpc_client
+            .get_minimum_balance_for_rent_exemption(nonce::State::size())
+            .await
+            .unwrap();
+        let blockhash = rpc_client.get_latest_blockhash().await.unwrap();
+        let transaction = Transaction::new_signed_with_payer(
+            &system_instruction::create_nonce_account(
+                &payer.pubkey(),
+                &nonce_account.pubkey(),
+                &payer.pubkey(),
+                rent,
+            ),
+            Some(&payer.pubkey()),
+            &[payer.as_ref(), &nonce_account as &dyn Signer],
+            blockhash,
+        );
+        rpc_client
+            .send_and_confirm_transaction(&transaction)
+            .await
+            .unwrap();
+
+        let nonce = NonceConfig {
+            account: nonce_account.pubkey(),
+            authority: payer,
+        };
+        let nonce_blockhash = nonce.blockhash(&rpc_client).await.unwrap();
+        context.bot.nonce = Some(nonce);
+        context.set_feed("1002");
+        assert!(matches!(
+            context.bot.poll().await.unwrap(),
+            PollOutcome::Rebased {
+                new_supply: 100_200,
+                ..
+            }
+        ));
+        assert_eq!(context.supply().await, 100_200);
+
+        // the rebase advanced the nonce
+        let nonce = context.bot.nonce.as_ref().unwrap();
+        assert_ne!(nonce.blockhash(&rpc_client).await.unwrap(), nonce_blockhash);
+    }
+}
@@ -0,0 +1,117 @@
+//! Sources of the target supply
+
+use {
+    serde_json::Value,
+    std::{error::Error, path::PathBuf},
+};
+
+/// Where the bot reads the target supply from
+#[derive(Clone, Debug, PartialEq)]
+pub enum Feed {
+    /// JSON document served over HTTP(S)
+    Http(String),
+    /// JSON document in a local file, re-read on every poll
+    File(PathBuf),
+}
+
+impl Feed {
+    /// Parse a feed source: an `http://` or `https://` URL, or a path,
+    /// optionally prefixed with `file://`
+    pub fn parse(source: &str) -> Self {
+        if source.starts_with("http://") || source.starts_with("https://") {
+            Self::Http(source.to_string())
+        } else {
+            Self::File(PathBuf::from(
+                source.strip_prefix("file://").unwrap_or(source),
+            ))
+        }
+    }
+
+    /// Fetch the current document
+    pub async fn read(&self, http_client: &reqwest::Client) -> Result<String, Box<dyn Error>> {
+        match self {
+            Self::Http(url) => Ok(http_client
+                .get(url)
+                .send()
+                .await?
+                .error_for_status()?
+                .text()
+                .await?),
+            Self::File(path) => tokio::fs::read_to_string(path)
+                .await
+                .map_err(|err| format!("Unable to read {}: {}", path.display(), err).into()),
+        }
+    }
+}
+
+/// Extract the target supply, in UI units, from a feed document.
+///
+/// `field` is either a top-level key or a JSON pointer such as
+/// `/data/supply`. The value must be a string, like `"1250000.5"`, or an
+/// integer: fractional JSON numbers are rejected, since they may have been
+/// rounded on the way.
+pub fn supply_from_json(document: &str, field: &str) -> Result<String, Box<dyn Error>> {
+    let document: Value = serde_json::from_str(document)?;
+    let value = if field.starts_with('/') {
+        document.pointer(field)
+    } else {
+        document.get(field)
+    }
+    .ok_or_else(|| format!("Feed has no `{}` field", field))?;
+    match value {
+        Value::String(ui_amount) => Ok(ui_amount.clone()),
+        Value::Number(number) if number.is_u64() => Ok(number.to_string()),
+        _ => Err(format!(
+            "Feed field `{}` must be a string or a nonnegative integer, found {}",
+            field, value
+        )
+        .into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_feed() {
+        assert_eq!(
+            Feed::parse("https://example.com/supply"),
+            Feed::Http("https://example.com/supply".to_string())
+        );
+        assert_eq!(
+            Feed::parse("file:///tmp/supply.json"),
+            Feed::File(PathBuf::from("/tmp/supply.json"))
+        );
+        assert_eq!(
+            Feed::parse("supply.json"),
+            Feed::File(PathBuf::from("supply.json"))
+        );
+    }
+
+    #[test]
+    fn read_supply() {
+        assert_eq!(
+            supply_from_json(r#"{"supply": "1250000.5"}"#, "supply").unwrap(),
+            "1250000.5"
+        );
+        assert_eq!(
+            supply_from_json(r#"{"supply": 42}"#, "supply").unwrap(),
+            "42"
+        );
+        assert_eq!(
+            supply_from_json(r#"{"data": {"nav": "7.25"}}"#, "/data/nav").unwrap(),
+            "7.25"
+        );
+        for (document, field) in [
+            (r#"{"supply": 1.5}"#, "supply"),
+            (r#"{"supply": -1}"#, "supply"),
+            (r#"{"supply": null}"#, "supply"),
+            (r#"{"total": "1"}"#, "supply"),
+            (r#"{"data": {}}"#, "/data/nav"),
+            ("not json", "supply"),
+        ] {
+            assert!(supply_from_json(document, field).is_err(), "{}", document);
+        }
+    }
+}
@@ -0,0 +1,284 @@
+mod bot;
+mod feed;
+
+use {
+    crate::{
+        bot::{Bot, NonceConfig, PollOutcome},
+        feed::Feed,
+    },
+    clap::{crate_description, crate_name, crate_version, Arg, Command},
+    solana_clap_v3_utils::{
+        input_parsers::{parse_url_or_moniker, pubkey_of},
+        input_validators::{is_valid_pubkey, is_valid_signer, normalize_to_url_if_moniker},
+        keypair::{signer_from_path, DefaultSigner},
+    },
+    solana_client::nonblocking::rpc_client::RpcClient,
+    solana_remote_wallet::remote_wallet::RemoteWalletManager,
+    solana_sdk::{commitment_config::CommitmentConfig, signature::Signer},
+    spl_token_client::client::{ProgramRpcClient, ProgramRpcClientSendTransaction},
+    std::{error::Error, process::exit, rc::Rc, sync::Arc, time::Duration},
+};
+
+fn print_outcome(outcome: &PollOutcome) {
+    match outcome {
+        PollOutcome::Unchanged { supply } => {
+            println!("Supply is already {}, nothing to do", supply);
+        }
+        PollOutcome::Simulated {
+            previous_supply,
+            new_supply,
+            events,
+        } => {
+            println!(
+                "Dry run: rebase from {} to {} would succeed",
+                previous_supply, new_supply
+            );
+            for event in events {
+                println!("  {:?}", event);
+            }
+        }
+        PollOutcome::Rebased {
+            signature,
+            previous_supply,
+            new_supply,
+            events,
+        } => {
+            println!(
+                "Rebased from {} to {}: {}",
+                previous_supply, new_supply, signature
+            );
+            for event in events {
+                println!("  {:?}", event);
+            }
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let matches = Command::new(crate_name!())
+        .about(crate_description!())
+        .version(crate_version!())
+        .arg({
+            let arg = Arg::new("config_file")
+                .short('C')
+                .long("config")
+                .value_name("PATH")
+                .takes_value(true)
+                .help("Configuration file to use");
+            if let Some(ref config_file) = *solana_cli_config::CONFIG_FILE {
+                arg.default_value(config_file)
+            } else {
+                arg
+            }
+        })
+        .arg(
+            Arg::new("payer")
+                .long("payer")
+                .value_name("KEYPAIR")
+                .validator(|s| is_valid_signer(s))
+                .takes_value(true)
+                .help("Filepath or URL to a keypair [default: client keypair]"),
+        )
+        .arg(
+            Arg::new("supply_authority")
+                .long("supply-authority")
+                .value_name("KEYPAIR")
+                .validator(|s| is_valid_signer(s))
+                .takes_value(true)
+                .help("Filepath or URL to the supply authority keypair [default: fee payer]"),
+        )
+        .arg(
+            Arg::new("co_signer")
+                .long("co-signer")
+                .value_name("KEYPAIR")
+                .validator(|s| is_valid_signer(s))
+                .takes_value(true)
+                .help("Filepath or URL to the co-signer keypair, if the mint has a co-signer"),
+        )
+        .arg(
+            Arg::new("json_rpc_url")
+                .short('u')
+                .long("url")
+                .value_name("URL")
+                .takes_value(true)
+                .value_parser(parse_url_or_moniker)
+                .help("JSON RPC URL for the cluster [default: value from configuration file]"),
+        )
+        .arg(
+            Arg::new("mint")
+                .validator(|s| is_valid_pubkey(s))
+                .value_name("ADDRESS")
+                .required(true)
+                .takes_value(true)
+                .index(1)
+                .help("Rebasing mint to keep in line with the feed"),
+        )
+        .arg(
+            Arg::new("feed")
+                .long("feed")
+                .value_name("URL_OR_PATH")
+                .required(true)
+                .takes_value(true)
+                .help("HTTP(S) URL or path of a JSON document holding the target supply"),
+        )
+        .arg(
+            Arg::new("feed_field")
+                .long("feed-field")
+                .value_name("FIELD")
+                .takes_value(true)
+                .default_value("supply")
+                .help("Key or JSON pointer of the target supply in the feed, in UI units"),
+        )
+        .arg(
+            Arg::new("interval")
+                .long("interval")
+                .value_name("SECONDS")
+                .value_parser(clap::value_parser!(u64))
+                .takes_value(true)
+                .default_value("60")
+                .help("Seconds between polls of the feed"),
+        )
+        .arg(
+            Arg::new("max_change_bps")
+                .long("max-change-bps")
+                .value_name("BPS")
+                .value_parser(clap::value_parser!(u16))
+                .takes_value(true)
+                .default_value("100")
+                .help("Largest change of the supply in a single rebase, in basis points"),
+        )
+        .arg(
+            Arg::new("valid_for")
+                .long("valid-for")
+                .value_name("SECONDS")
+                .value_parser(clap::value_parser!(u32))
+                .takes_value(true)
+                .default_value("60")
+                .help(
+                    "Seconds after the supply is read past which the rebase fails instead of \
+                     landing",
+                ),
+        )
+        .arg(
+            Arg::new("dry_run")
+                .long("dry-run")
+                .takes_value(false)
+                .help("Simulate the rebases instead of sending them"),
+        )
+        .arg(
+            Arg::new("nonce")
+                .long("nonce")
+                .value_name("ADDRESS")
+                .validator(|s| is_valid_pubkey(s))
+                .takes_value(true)
+                .help("Submit the rebases with this durable nonce account"),
+        )
+        .arg(
+            Arg::new("nonce_authority")
+                .long("nonce-authority")
+                .value_name("KEYPAIR")
+                .validator(|s| is_valid_signer(s))
+                .takes_value(true)
+                .requires("nonce")
+                .help("Filepath or URL to the nonce authority keypair [default: fee payer]"),
+        )
+        .arg(
+            Arg::new("once")
+                .long("once")
+                .takes_value(false)
+                .help("Poll the feed a single time, and exit with an error if it fails"),
+        )
+        .get_matches();
+
+    let mut wallet_manager: Option<Rc<RemoteWalletManager>> = None;
+    let cli_config = if let Some(config_file) = matches.value_of("config_file") {
+        solana_cli_config::Config::load(config_file).unwrap_or_default()
+    } else {
+        solana_cli_config::Config::default()
+    };
+    let payer: Arc<dyn Signer> = Arc::from(
+        DefaultSigner::new(
+            "payer",
+            matches
+                .value_of("payer")
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| cli_config.keypair_path.clone()),
+        )
+        .signer_from_path(&matches, &mut wallet_manager)
+        .unwrap_or_else(|err| {
+            eprintln!("error: {}", err);
+            exit(1);
+        }),
+    );
+    let mut signer_or_payer = |name: &str| -> Arc<dyn Signer> {
+        matches
+            .value_of(name)
+            .map_or(Ok(payer.clone()), |path| {
+                signer_from_path(&matches, path, name, &mut wallet_manager).map(Arc::from)
+            })
+            .unwrap_or_else(|err| {
+                eprintln!("error: {}: {}", name, err);
+                exit(1);
+            })
+    };
+    let supply_authority = signer_or_payer("supply_authority");
+    let co_signer = matches
+        .is_present("co_signer")
+        .then(|| signer_or_payer("co_signer"));
+    let nonce = pubkey_of(&matches, "nonce").map(|account| NonceConfig {
+        account,
+        authority: signer_or_payer("nonce_authority"),
+    });
+    solana_logger::setup_with_default("solana=info");
+
+    let json_rpc_url = normalize_to_url_if_moniker(
+        matches
+            .get_one::<String>("json_rpc_url")
+            .unwrap_or(&cli_config.json_rpc_url),
+    );
+    let rpc_client = Arc::new(RpcClient::new_with_commitment(
+        json_rpc_url,
+        CommitmentConfig::confirmed(),
+    ));
+    let bot = Bot {
+        rpc_client: rpc_client.clone(),
+        program_client: Arc::new(ProgramRpcClient::new(
+            rpc_client,
+            ProgramRpcClientSendTransaction,
+        )),
+        http_client: reqwest::Client::new(),
+        payer,
+        supply_authority,
+        co_signer,
+        mint: pubkey_of(&matches, "mint").unwrap(),
+        feed: Feed::parse(matches.value_of("feed").unwrap()),
+        feed_field: matches.value_of("feed_field").unwrap().to_string(),
+        max_change_bps: *matches.get_one::<u16>("max_change_bps").unwrap(),
+        valid_for_seconds: *matches.get_one::<u32>("valid_for").unwrap(),
+        dry_run: matches.is_present("dry_run"),
+        nonce,
+    };
+    let interval = Duration::from_secs(*matches.get_one::<u64>("interval").unwrap());
+
+    if matches.is_present("once") {
+        let outcome = bot.poll().await.unwrap_or_else(|err| {
+            eprintln!("error: {}", err);
+            exit(1);
+        });
+        print_outcome(&outcome);
+        return Ok(());
+    }
+
+    // keep polling through failures, which may only be a feed or RPC node
+    // being briefly unavailable
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        match bot.poll().await {
+            Ok(outcome) => print_outcome(&outcome),
+            Err(err) => eprintln!("error: {}", err),
+        }
+    }
+}
@@ -0,0 +1,11 @@
+//! Error types
+
+use spl_program_error::*;
+
+/// Errors that may be returned by the example program.
+#[spl_program_error(hash_error_code_start = 2_612_633_826)]
+pub enum ExampleError {
+    /// Transfer amount exceeds the max transfer of the mint
+    #[error("Transfer amount exceeds the max transfer of the mint")]
+    AmountExceedsMaxTransfer,
+}
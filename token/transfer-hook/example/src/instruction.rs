@@ -0,0 +1,119 @@
+//! Instructions of the example program, next to the ones of the
+//! `spl-transfer-hook-interface`
+
+use {
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+        system_program,
+    },
+    spl_discriminator::{ArrayDiscriminator, SplDiscriminate},
+    std::convert::TryInto,
+};
+
+/// Instructions supported by the example program, on top of the ones of the
+/// transfer hook interface.
+#[repr(C)]
+#[derive(Clone, Debug, PartialEq)]
+pub enum ExampleInstruction {
+    /// Sets the most tokens that a single transfer of the mint may move,
+    /// creating the max transfer account if needed.
+    ///
+    /// The limit only applies to transfers once the max transfer account is
+    /// in the mint's extra account metas. For a rebasing mint, it's in tokens
+    /// at the current ratio, so it holds across rebases.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[w]` Max transfer account, as given by `get_max_transfer_address`
+    ///   1. `[]` Mint
+    ///   2. `[s]` Mint authority
+    ///   3. `[]` System program
+    SetMaxTransfer {
+        /// Most tokens that a single transfer may move
+        max_amount: u64,
+    },
+}
+/// TLV instruction type only used to define the discriminator
+#[derive(SplDiscriminate)]
+#[discriminator_hash_input("spl-transfer-hook-example:set-max-transfer")]
+pub struct SetMaxTransferInstruction;
+
+impl ExampleInstruction {
+    /// Unpacks a byte buffer into an
+    /// [ExampleInstruction](enum.ExampleInstruction.html).
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        if input.len() < ArrayDiscriminator::LENGTH {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let (discriminator, rest) = input.split_at(ArrayDiscriminator::LENGTH);
+        Ok(match discriminator {
+            SetMaxTransferInstruction::SPL_DISCRIMINATOR_SLICE => {
+                let max_amount = rest
+                    .get(..8)
+                    .and_then(|slice| slice.try_into().ok())
+                    .map(u64::from_le_bytes)
+                    .ok_or(ProgramError::InvalidInstructionData)?;
+                Self::SetMaxTransfer { max_amount }
+            }
+            _ => return Err(ProgramError::InvalidInstructionData),
+        })
+    }
+
+    /// Packs an [ExampleInstruction](enum.ExampleInstruction.html) into a
+    /// byte buffer.
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = vec![];
+        match self {
+            Self::SetMaxTransfer { max_amount } => {
+                buf.extend_from_slice(SetMaxTransferInstruction::SPL_DISCRIMINATOR_SLICE);
+                buf.extend_from_slice(&max_amount.to_le_bytes());
+            }
+        };
+        buf
+    }
+}
+
+/// Creates a `SetMaxTransfer` instruction.
+pub fn set_max_transfer(
+    program_id: &Pubkey,
+    max_transfer_pubkey: &Pubkey,
+    mint_pubkey: &Pubkey,
+    authority_pubkey: &Pubkey,
+    max_amount: u64,
+) -> Instruction {
+    let data = ExampleInstruction::SetMaxTransfer { max_amount }.pack();
+
+    let accounts = vec![
+        AccountMeta::new(*max_transfer_pubkey, false),
+        AccountMeta::new_readonly(*mint_pubkey, false),
+        AccountMeta::new_readonly(*authority_pubkey, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn pack_unpack_set_max_transfer() {
+        let check = ExampleInstruction::SetMaxTransfer {
+            max_amount: 111_111,
+        };
+        let packed = check.pack();
+        let mut expect = vec![];
+        expect.extend_from_slice(SetMaxTransferInstruction::SPL_DISCRIMINATOR_SLICE);
+        expect.extend_from_slice(&111_111u64.to_le_bytes());
+        assert_eq!(packed, expect);
+        let unpacked = ExampleInstruction::unpack(&expect).unwrap();
+        assert_eq!(unpacked, check);
+    }
+}
@@ -7,6 +7,8 @@
 #![deny(missing_docs)]
 #![cfg_attr(not(test), forbid(unsafe_code))]
 
+pub mod error;
+pub mod instruction;
 pub mod processor;
 pub mod state;
 
@@ -1,6 +1,14 @@
 //! Program state processor
 
 use {
+    crate::{
+        error::ExampleError,
+        instruction::{ExampleInstruction, SetMaxTransferInstruction},
+        state::{
+            get_max_transfer_address, get_max_transfer_address_and_bump_seed, MAX_TRANSFER_LEN,
+            MAX_TRANSFER_SEED,
+        },
+    },
     solana_program::{
         account_info::{next_account_info, AccountInfo},
         entrypoint::ProgramResult,
@@ -10,10 +18,12 @@ use {
         pubkey::Pubkey,
         system_instruction,
     },
+    spl_discriminator::SplDiscriminate,
     spl_tlv_account_resolution::{account::ExtraAccountMeta, state::ExtraAccountMetaList},
     spl_token_2022::{
         extension::{
-            transfer_hook::TransferHookAccount, BaseStateWithExtensions, StateWithExtensions,
+            rebase_mint::RebaseMintConfig, transfer_hook::TransferHookAccount,
+            BaseStateWithExtensions, StateWithExtensions,
         },
         state::{Account, Mint},
     },
@@ -36,6 +46,37 @@ fn check_token_account_is_transferring(account_info: &AccountInfo) -> Result<(),
     }
 }
 
+fn check_max_transfer(
+    program_id: &Pubkey,
+    max_transfer_info: &AccountInfo,
+    mint_info: &AccountInfo,
+    amount: u64,
+) -> ProgramResult {
+    // The max transfer may be in the extra account metas before being set
+    if max_transfer_info.owner != program_id {
+        return Ok(());
+    }
+    let max_amount = max_transfer_info
+        .try_borrow_data()?
+        .get(..MAX_TRANSFER_LEN)
+        .and_then(|slice| slice.try_into().ok())
+        .map(u64::from_le_bytes)
+        .ok_or(ProgramError::InvalidAccountData)?;
+    if amount > max_amount {
+        // The amount is in tokens at the current ratio, so a hook that needs
+        // shares instead can convert it with the rebasing mint's config
+        let mint_data = mint_info.try_borrow_data()?;
+        let mint = StateWithExtensions::<Mint>::unpack(&mint_data)?;
+        if let Ok(config) = mint.get_extension::<RebaseMintConfig>() {
+            if let Some(shares) = config.amount_to_shares(amount) {
+                msg!("Transfer of {} tokens, {} shares", amount, shares);
+            }
+        }
+        return Err(ExampleError::AmountExceedsMaxTransfer.into());
+    }
+    Ok(())
+}
+
 /// Processes an [Execute](enum.TransferHookInstruction.html) instruction.
 pub fn process_execute(
     program_id: &Pubkey,
@@ -70,6 +111,18 @@ pub fn process_execute(
         &data,
     )?;
 
+    // The amount is what the user asked to transfer, in tokens: for a
+    // rebasing mint, it's already converted at the current ratio, so the max
+    // transfer holds across rebases
+    let max_transfer_address = get_max_transfer_address(mint_info.key, program_id);
+    if let Some(max_transfer_info) = accounts
+        .iter()
+        .skip(5)
+        .find(|account_info| *account_info.key == max_transfer_address)
+    {
+        check_max_transfer(program_id, max_transfer_info, mint_info, amount)?;
+    }
+
     Ok(())
 }
 
@@ -196,8 +249,75 @@ pub fn process_update_extra_account_meta_list(
     Ok(())
 }
 
+/// Processes a [SetMaxTransfer](enum.ExampleInstruction.html) instruction.
+pub fn process_set_max_transfer(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    max_amount: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let max_transfer_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+    let _system_program_info = next_account_info(account_info_iter)?;
+
+    // check that the mint authority is valid without fully deserializing
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<Mint>::unpack(&mint_data)?;
+    let mint_authority = mint
+        .base
+        .mint_authority
+        .ok_or(TransferHookError::MintHasNoMintAuthority)?;
+
+    // Check signers
+    if !authority_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if *authority_info.key != mint_authority {
+        return Err(TransferHookError::IncorrectMintAuthority.into());
+    }
+
+    // Check max transfer account
+    let (expected_max_transfer_address, bump_seed) =
+        get_max_transfer_address_and_bump_seed(mint_info.key, program_id);
+    if expected_max_transfer_address != *max_transfer_info.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    // Create the account on first use
+    if max_transfer_info.owner != program_id {
+        let bump_seed = [bump_seed];
+        let signer_seeds = [MAX_TRANSFER_SEED, mint_info.key.as_ref(), &bump_seed];
+        invoke_signed(
+            &system_instruction::allocate(max_transfer_info.key, MAX_TRANSFER_LEN as u64),
+            &[max_transfer_info.clone()],
+            &[&signer_seeds],
+        )?;
+        invoke_signed(
+            &system_instruction::assign(max_transfer_info.key, program_id),
+            &[max_transfer_info.clone()],
+            &[&signer_seeds],
+        )?;
+    }
+
+    // Write the data
+    let mut data = max_transfer_info.try_borrow_mut_data()?;
+    data.get_mut(..MAX_TRANSFER_LEN)
+        .ok_or(ProgramError::InvalidAccountData)?
+        .copy_from_slice(&max_amount.to_le_bytes());
+
+    Ok(())
+}
+
 /// Processes an [Instruction](enum.Instruction.html).
 pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+    if input.starts_with(SetMaxTransferInstruction::SPL_DISCRIMINATOR_SLICE) {
+        let ExampleInstruction::SetMaxTransfer { max_amount } = ExampleInstruction::unpack(input)?;
+        msg!("Instruction: SetMaxTransfer");
+        return process_set_max_transfer(program_id, accounts, max_amount);
+    }
+
     let instruction = TransferHookInstruction::unpack(input)?;
 
     match instruction {
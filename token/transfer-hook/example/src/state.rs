@@ -1,7 +1,7 @@
 //! State helpers for working with the example program
 
 use {
-    solana_program::program_error::ProgramError,
+    solana_program::{program_error::ProgramError, pubkey::Pubkey},
     spl_tlv_account_resolution::{account::ExtraAccountMeta, state::ExtraAccountMetaList},
     spl_transfer_hook_interface::instruction::ExecuteInstruction,
 };
@@ -13,3 +13,21 @@ pub fn example_data(account_metas: &[ExtraAccountMeta]) -> Result<Vec<u8>, Progr
     ExtraAccountMetaList::init::<ExecuteInstruction>(&mut data, account_metas)?;
     Ok(data)
 }
+
+/// Seed of the max transfer account, followed by the mint
+pub const MAX_TRANSFER_SEED: &[u8] = b"max-transfer";
+
+/// Size of the max transfer account, which only holds the max amount as a
+/// little-endian `u64`
+pub const MAX_TRANSFER_LEN: usize = 8;
+
+/// Get the address of the account holding the max transfer of the mint
+pub fn get_max_transfer_address(mint: &Pubkey, program_id: &Pubkey) -> Pubkey {
+    get_max_transfer_address_and_bump_seed(mint, program_id).0
+}
+
+/// Get the address of the account holding the max transfer of the mint, and
+/// its bump seed
+pub fn get_max_transfer_address_and_bump_seed(mint: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[MAX_TRANSFER_SEED, mint.as_ref()], program_id)
+}